@@ -9,12 +9,13 @@ use crate::thread::{
     ThreadEvent, ThreadFeedback, ThreadSummary,
 };
 use crate::thread_store::{RulesLoadingError, TextThreadStore, ThreadStore};
+use crate::tool_permissions::ToolPermissionDecision;
 use crate::tool_use::{PendingToolUseStatus, ToolUse};
 use crate::ui::{
     AddedContext, AgentNotification, AgentNotificationEvent, AnimatedLabel, ContextPill,
 };
 use anyhow::Context as _;
-use assistant_settings::{AssistantSettings, NotifyWhenAgentWaiting};
+use assistant_settings::{AssistantSettings, NotifyWhenAgentWaiting, ToolPermission};
 use assistant_tool::ToolUseStatus;
 use collections::{HashMap, HashSet};
 use editor::actions::{MoveUp, Paste};
@@ -1078,7 +1079,7 @@ impl ActiveThread {
                     );
                 }
             }
-            ThreadEvent::CheckpointChanged => cx.notify(),
+            ThreadEvent::CheckpointChanged | ThreadEvent::ContextCompacted => cx.notify(),
             ThreadEvent::ReceivedTextChunk => {}
             ThreadEvent::InvalidToolInput {
                 tool_use_id,
@@ -1768,6 +1769,9 @@ impl ActiveThread {
 
         let is_first_message = ix == 0;
         let is_last_message = ix == self.messages.len() - 1;
+        let is_last_compacted_message = thread
+            .compaction()
+            .is_some_and(|compaction| compaction.message_id == message_id);
 
         let loading_dots = (is_generating_stale && is_last_message)
             .then(|| AnimatedLabel::new("").size(LabelSize::Small));
@@ -1796,6 +1800,14 @@ impl ActiveThread {
                 }
             });
 
+        let fork_thread = IconButton::new(("fork-thread", ix), IconName::GitBranchSmall)
+            .icon_size(IconSize::XSmall)
+            .icon_color(Color::Ignored)
+            .tooltip(Tooltip::text("Fork Thread from Here"))
+            .on_click(cx.listener(move |this, _, window, cx| {
+                this.handle_fork_thread(message_id, window, cx);
+            }));
+
         // For all items that should be aligned with the LLM's response.
         const RESPONSE_PADDING_X: Pixels = px(19.);
 
@@ -1862,7 +1874,8 @@ impl ActiveThread {
                                     );
                                 })),
                         )
-                        .child(open_as_markdown),
+                        .child(open_as_markdown)
+                        .child(fork_thread),
                 )
                 .into_any_element(),
             None if AssistantSettings::get_global(cx).enable_feedback =>
@@ -1906,11 +1919,12 @@ impl ActiveThread {
                                     );
                                 })),
                         )
-                        .child(open_as_markdown),
+                        .child(open_as_markdown)
+                        .child(fork_thread),
                 )
                 .into_any_element(),
             None => feedback_container
-                .child(h_flex().child(open_as_markdown))
+                .child(h_flex().child(open_as_markdown).child(fork_thread))
                 .into_any_element(),
         };
 
@@ -2252,6 +2266,26 @@ impl ActiveThread {
                     },
                 )
             })
+            .when(is_last_compacted_message, |parent| {
+                parent.child(
+                    h_flex()
+                        .id(("compaction-divider", ix))
+                        .pt_2p5()
+                        .px_2p5()
+                        .w_full()
+                        .gap_1()
+                        .child(ui::Divider::horizontal())
+                        .child(
+                            Label::new("Earlier Messages Summarized")
+                                .size(LabelSize::XSmall)
+                                .color(Color::Muted),
+                        )
+                        .child(ui::Divider::horizontal())
+                        .tooltip(Tooltip::text(
+                            "The messages above were summarized to fit in the model's context window. They stay here for you to read.",
+                        )),
+                )
+            })
             .when(after_editing_message, |parent| {
                 // Backdrop to dim out the whole thread below the editing user message
                 parent.relative().child(backdrop)
@@ -3266,6 +3300,14 @@ impl ActiveThread {
         {
             self.thread.update(cx, |thread, cx| {
                 if let Some(configured) = thread.get_or_init_configured_model(cx) {
+                    thread.log_tool_permission_decision(
+                        c.tool_use_id.clone(),
+                        c.tool.name().into(),
+                        ToolPermissionDecision {
+                            permission: ToolPermission::Allow,
+                            reason: "allowed by the user".into(),
+                        },
+                    );
                     thread.run_tool(
                         c.tool_use_id.clone(),
                         c.ui_text.clone(),
@@ -3281,6 +3323,27 @@ impl ActiveThread {
         }
     }
 
+    fn handle_fork_thread(
+        &mut self,
+        message_id: MessageId,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let fork = self.thread_store.update(cx, |thread_store, cx| {
+            thread_store.fork_thread(&self.thread, message_id, cx)
+        });
+        let workspace = self.workspace.clone();
+        cx.spawn_in(window, async move |_, cx| {
+            let thread = fork.await?;
+            workspace.update_in(cx, |workspace, window, cx| {
+                if let Some(panel) = workspace.panel::<AgentPanel>(cx) {
+                    panel.update(cx, |panel, cx| panel.open_thread(thread, window, cx));
+                }
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
     fn handle_deny_tool(
         &mut self,
         tool_use_id: LanguageModelToolUseId,
@@ -3291,6 +3354,14 @@ impl ActiveThread {
     ) {
         let window_handle = window.window_handle();
         self.thread.update(cx, |thread, cx| {
+            thread.log_tool_permission_decision(
+                tool_use_id.clone(),
+                tool_name.clone(),
+                ToolPermissionDecision {
+                    permission: ToolPermission::Deny,
+                    reason: "denied by the user".into(),
+                },
+            );
             thread.deny_tool_use(tool_use_id, tool_name, Some(window_handle), cx);
         });
     }
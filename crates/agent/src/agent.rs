@@ -11,6 +11,7 @@ mod context_server_tool;
 mod context_store;
 mod context_strip;
 mod debug;
+mod headless;
 mod history_store;
 mod inline_assistant;
 mod inline_prompt_editor;
@@ -22,9 +23,12 @@ mod terminal_inline_assistant;
 mod thread;
 mod thread_history;
 mod thread_store;
+mod token_accounting;
 mod tool_compatibility;
+mod tool_permissions;
 mod tool_use;
 mod ui;
+mod usage_report;
 
 use std::sync::Arc;
 
@@ -46,6 +50,9 @@ pub use crate::active_thread::ActiveThread;
 use crate::agent_configuration::{AddContextServerModal, ManageProfilesModal};
 pub use crate::agent_panel::{AgentPanel, ConcreteAssistantPanelDelegate};
 pub use crate::context::{ContextLoadResult, LoadedContext};
+pub use crate::headless::{
+    HeadlessEvent, HeadlessOutcome, HeadlessRun, HeadlessRunResult, run_headless,
+};
 pub use crate::inline_assistant::InlineAssistant;
 use crate::slash_command_settings::SlashCommandSettings;
 pub use crate::thread::{Message, MessageSegment, Thread, ThreadEvent};
@@ -78,6 +85,10 @@ actions!(
         RemoveFocusedContext,
         AcceptSuggestedContext,
         OpenActiveThreadAsMarkdown,
+        ExportThreadAsMarkdown,
+        ExportThreadAsJson,
+        ImportThread,
+        OpenUsageReport,
         OpenAgentDiff,
         Keep,
         Reject,
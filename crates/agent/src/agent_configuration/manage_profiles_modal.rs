@@ -291,7 +291,15 @@ impl ManageProfilesModal {
                         .map(|profile| profile.enable_all_context_servers)
                         .unwrap_or_default(),
                     context_servers: base_profile
-                        .map(|profile| profile.context_servers)
+                        .as_ref()
+                        .map(|profile| profile.context_servers.clone())
+                        .unwrap_or_default(),
+                    permissions: base_profile
+                        .as_ref()
+                        .map(|profile| profile.permissions.clone())
+                        .unwrap_or_default(),
+                    terminal_sandbox: base_profile
+                        .map(|profile| profile.terminal_sandbox)
                         .unwrap_or_default(),
                 };
 
@@ -298,6 +298,8 @@ impl PickerDelegate for ToolPickerDelegate {
                                             )
                                         })
                                         .collect(),
+                                    permissions: default_profile.permissions,
+                                    terminal_sandbox: default_profile.terminal_sandbox,
                                 });
 
                         if let Some(server_id) = server_id {
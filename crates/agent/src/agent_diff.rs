@@ -1371,7 +1371,8 @@ impl AgentDiff {
             | ThreadEvent::ToolFinished { .. }
             | ThreadEvent::CheckpointChanged
             | ThreadEvent::ToolConfirmationNeeded
-            | ThreadEvent::CancelEditing => {}
+            | ThreadEvent::CancelEditing
+            | ThreadEvent::ContextCompacted => {}
         }
     }
 
@@ -1756,7 +1757,7 @@ mod tests {
             Project::init_settings(cx);
             AssistantSettings::register(cx);
             prompt_store::init(cx);
-            thread_store::init(cx);
+            thread_store::init_test(cx);
             workspace::init_settings(cx);
             ThemeSettings::register(cx);
             EditorSettings::register(cx);
@@ -1912,7 +1913,7 @@ mod tests {
             Project::init_settings(cx);
             AssistantSettings::register(cx);
             prompt_store::init(cx);
-            thread_store::init(cx);
+            thread_store::init_test(cx);
             workspace::init_settings(cx);
             ThemeSettings::register(cx);
             EditorSettings::register(cx);
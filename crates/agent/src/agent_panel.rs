@@ -1,5 +1,5 @@
 use std::ops::Range;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::time::Duration;
 
@@ -23,8 +23,8 @@ use fs::Fs;
 use gpui::{
     Action, Animation, AnimationExt as _, AnyElement, App, AsyncWindowContext, ClipboardItem,
     Corner, DismissEvent, Entity, EventEmitter, ExternalPaths, FocusHandle, Focusable, FontWeight,
-    KeyContext, Pixels, Subscription, Task, UpdateGlobal, WeakEntity, linear_color_stop,
-    linear_gradient, prelude::*, pulsating_between,
+    KeyContext, PathPromptOptions, Pixels, Subscription, Task, UpdateGlobal, WeakEntity,
+    linear_color_stop, linear_gradient, prelude::*, pulsating_between,
 };
 use language::LanguageRegistry;
 use language_model::{
@@ -36,6 +36,7 @@ use prompt_store::{PromptBuilder, PromptStore, UserPromptId};
 use proto::Plan;
 use rules_library::{RulesLibrary, open_rules_library};
 use search::{BufferSearchBar, buffer_search};
+use semantic_index::{ProjectIndex, SemanticDb};
 use settings::{Settings, update_settings_file};
 use theme::ThemeSettings;
 use time::UtcOffset;
@@ -46,6 +47,7 @@ use ui::{
 };
 use util::{ResultExt as _, maybe};
 use workspace::dock::{DockPosition, Panel, PanelEvent};
+use workspace::notifications::DetachAndPromptErr as _;
 use workspace::{
     CollaboratorId, DraggedSelection, DraggedTab, ToggleZoom, ToolbarItemView, Workspace,
 };
@@ -60,13 +62,15 @@ use crate::agent_diff::AgentDiff;
 use crate::history_store::{HistoryStore, RecentEntry};
 use crate::message_editor::{MessageEditor, MessageEditorEvent};
 use crate::thread::{Thread, ThreadError, ThreadId, ThreadSummary, TokenUsageRatio};
-use crate::thread_history::{HistoryEntryElement, ThreadHistory};
+use crate::thread_history::{HistoryEntryElement, ThreadHistory, thread_summaries};
 use crate::thread_store::ThreadStore;
 use crate::ui::AgentOnboardingModal;
+use crate::usage_report::UsageReportView;
 use crate::{
     AddContextServer, AgentDiffPane, ContextStore, DeleteRecentlyOpenThread, ExpandMessageEditor,
-    Follow, InlineAssistant, NewTextThread, NewThread, OpenActiveThreadAsMarkdown, OpenAgentDiff,
-    OpenHistory, ResetTrialUpsell, TextThreadStore, ThreadEvent, ToggleContextPicker,
+    ExportThreadAsJson, ExportThreadAsMarkdown, Follow, ImportThread, InlineAssistant,
+    NewTextThread, NewThread, OpenActiveThreadAsMarkdown, OpenAgentDiff, OpenHistory,
+    OpenUsageReport, ResetTrialUpsell, TextThreadStore, ThreadEvent, ToggleContextPicker,
     ToggleNavigationMenu, ToggleOptionsMenu,
 };
 
@@ -99,6 +103,12 @@ pub fn init(cx: &mut App) {
                         panel.update(cx, |panel, cx| panel.open_configuration(window, cx));
                     }
                 })
+                .register_action(|workspace, _: &OpenUsageReport, window, cx| {
+                    if let Some(panel) = workspace.panel::<AgentPanel>(cx) {
+                        workspace.focus_panel::<AgentPanel>(window, cx);
+                        panel.update(cx, |panel, cx| panel.open_usage_report(window, cx));
+                    }
+                })
                 .register_action(|workspace, _: &NewTextThread, window, cx| {
                     if let Some(panel) = workspace.panel::<AgentPanel>(cx) {
                         workspace.focus_panel::<AgentPanel>(window, cx);
@@ -120,6 +130,26 @@ pub fn init(cx: &mut App) {
                         AgentDiffPane::deploy_in_workspace(thread, workspace, window, cx);
                     }
                 })
+                .register_action(|workspace, action: &ExportThreadAsMarkdown, window, cx| {
+                    if let Some(panel) = workspace.panel::<AgentPanel>(cx) {
+                        panel.update(cx, |panel, cx| {
+                            panel.export_thread_as_markdown(action, window, cx)
+                        });
+                    }
+                })
+                .register_action(|workspace, action: &ExportThreadAsJson, window, cx| {
+                    if let Some(panel) = workspace.panel::<AgentPanel>(cx) {
+                        panel.update(cx, |panel, cx| {
+                            panel.export_thread_as_json(action, window, cx)
+                        });
+                    }
+                })
+                .register_action(|workspace, action: &ImportThread, window, cx| {
+                    if let Some(panel) = workspace.panel::<AgentPanel>(cx) {
+                        workspace.focus_panel::<AgentPanel>(window, cx);
+                        panel.update(cx, |panel, cx| panel.import_thread(action, window, cx));
+                    }
+                })
                 .register_action(|workspace, _: &Follow, window, cx| {
                     workspace.follow(CollaboratorId::Agent, window, cx);
                 })
@@ -178,6 +208,7 @@ enum ActiveView {
     },
     History,
     Configuration,
+    UsageReport,
 }
 
 enum WhichFontSize {
@@ -191,7 +222,7 @@ impl ActiveView {
         match self {
             ActiveView::Thread { .. } | ActiveView::History => WhichFontSize::AgentFont,
             ActiveView::PromptEditor { .. } => WhichFontSize::BufferFont,
-            ActiveView::Configuration => WhichFontSize::None,
+            ActiveView::Configuration | ActiveView::UsageReport => WhichFontSize::None,
         }
     }
 
@@ -349,11 +380,14 @@ pub struct AgentPanel {
     message_editor: Entity<MessageEditor>,
     _active_thread_subscriptions: Vec<Subscription>,
     _default_model_subscription: Subscription,
+    semantic_index: Option<(Entity<ProjectIndex>, Subscription)>,
+    _semantic_db_subscription: Subscription,
     context_store: Entity<TextThreadStore>,
     prompt_store: Option<Entity<PromptStore>>,
     inline_assist_context_store: Entity<crate::context_store::ContextStore>,
     configuration: Option<Entity<AgentConfiguration>>,
     configuration_subscription: Option<Subscription>,
+    usage_report: Option<Entity<UsageReportView>>,
     local_timezone: UtcOffset,
     active_view: ActiveView,
     previous_view: Option<ActiveView>,
@@ -664,6 +698,10 @@ impl AgentPanel {
             },
         );
 
+        let _semantic_db_subscription =
+            cx.observe_global::<SemanticDb>(|this, cx| this.refresh_semantic_index(cx));
+        cx.defer_in(window, |this, _window, cx| this.refresh_semantic_index(cx));
+
         let trial_markdown = cx.new(|cx| {
             Markdown::new(
                 include_str!("trial_markdown.md").into(),
@@ -689,10 +727,13 @@ impl AgentPanel {
                 message_editor_subscription,
             ],
             _default_model_subscription,
+            semantic_index: None,
+            _semantic_db_subscription,
             context_store,
             prompt_store,
             configuration: None,
             configuration_subscription: None,
+            usage_report: None,
             local_timezone: UtcOffset::from_whole_seconds(
                 chrono::Local::now().offset().local_minus_utc(),
             )
@@ -728,6 +769,28 @@ impl AgentPanel {
         }
     }
 
+    fn refresh_semantic_index(&mut self, cx: &mut Context<Self>) {
+        if !cx.has_global::<SemanticDb>() {
+            if self.semantic_index.take().is_some() {
+                cx.notify();
+            }
+            return;
+        }
+        if self.semantic_index.is_some() {
+            return;
+        }
+
+        let project = self.project.clone();
+        let project_index = SemanticDb::update_global(cx, |semantic_db, cx| {
+            semantic_db.index_for_project(project, cx)
+        });
+        let subscription = cx.subscribe(&project_index, |_, _, _: &semantic_index::Status, cx| {
+            cx.notify()
+        });
+        self.semantic_index = Some((project_index, subscription));
+        cx.notify();
+    }
+
     pub(crate) fn local_timezone(&self) -> UtcOffset {
         self.local_timezone
     }
@@ -1058,7 +1121,7 @@ impl AgentPanel {
 
     pub fn go_back(&mut self, _: &workspace::GoBack, window: &mut Window, cx: &mut Context<Self>) {
         match self.active_view {
-            ActiveView::Configuration | ActiveView::History => {
+            ActiveView::Configuration | ActiveView::History | ActiveView::UsageReport => {
                 self.active_view =
                     ActiveView::thread(self.thread.read(cx).thread().clone(), window, cx);
                 self.message_editor.focus_handle(cx).focus(window);
@@ -1194,6 +1257,17 @@ impl AgentPanel {
         }
     }
 
+    fn open_usage_report(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let thread = self.thread.read(cx).thread().clone();
+        let thread_store = self.thread_store.clone();
+        thread_store
+            .update(cx, |thread_store, cx| thread_store.reload(cx))
+            .detach_and_log_err(cx);
+
+        self.usage_report = Some(cx.new(|cx| UsageReportView::new(thread, thread_store, cx)));
+        self.set_active_view(ActiveView::UsageReport, window, cx);
+    }
+
     pub(crate) fn open_active_thread_as_markdown(
         &mut self,
         _: &OpenActiveThreadAsMarkdown,
@@ -1217,6 +1291,85 @@ impl AgentPanel {
             .detach_and_log_err(cx);
     }
 
+    fn export_thread_as_markdown(
+        &mut self,
+        _: &ExportThreadAsMarkdown,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(thread) = self.active_thread() else {
+            return;
+        };
+        let markdown = thread.read(cx).to_markdown(cx);
+        self.export_thread(Task::ready(markdown), window, cx);
+    }
+
+    fn export_thread_as_json(
+        &mut self,
+        _: &ExportThreadAsJson,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(thread) = self.active_thread() else {
+            return;
+        };
+        let serialized = thread.update(cx, |thread, cx| thread.serialize(cx));
+        let json = cx.spawn(async move |_, _| serialized.await?.to_json());
+        self.export_thread(json, window, cx);
+    }
+
+    fn export_thread(
+        &mut self,
+        contents: Task<Result<String>>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let path = cx.prompt_for_new_path(&self.thread_export_directory(cx));
+        let fs = self.fs.clone();
+        cx.spawn_in(window, async move |_, _| {
+            let Some(path) = path.await?? else {
+                return Ok(());
+            };
+            fs.atomic_write(path, contents.await?).await
+        })
+        .detach_and_prompt_err("Failed to export thread", window, cx, |_, _, _| None);
+    }
+
+    fn import_thread(&mut self, _: &ImportThread, window: &mut Window, cx: &mut Context<Self>) {
+        let paths = cx.prompt_for_paths(PathPromptOptions {
+            files: true,
+            directories: false,
+            multiple: false,
+        });
+        let fs = self.fs.clone();
+        let thread_store = self.thread_store.clone();
+        cx.spawn_in(window, async move |this, cx| {
+            let Some(path) = paths.await??.and_then(|paths| paths.into_iter().next()) else {
+                return Ok(());
+            };
+            let json = fs.load(&path).await?;
+            let thread_id = thread_store
+                .update(cx, |thread_store, cx| {
+                    thread_store.import_thread(json.as_bytes(), cx)
+                })?
+                .await?;
+            this.update_in(cx, |this, window, cx| {
+                this.open_thread_by_id(&thread_id, window, cx)
+            })?
+            .await
+        })
+        .detach_and_prompt_err("Failed to import thread", window, cx, |_, _, _| None);
+    }
+
+    /// Threads are exported next to the project by default.
+    fn thread_export_directory(&self, cx: &App) -> PathBuf {
+        self.project
+            .read(cx)
+            .visible_worktrees(cx)
+            .find_map(|worktree| Some(worktree.read(cx).as_local()?.abs_path().to_path_buf()))
+            .unwrap_or_else(|| paths::home_dir().clone())
+    }
+
     fn handle_agent_configuration_event(
         &mut self,
         _entity: &Entity<AgentConfiguration>,
@@ -1345,6 +1498,13 @@ impl Focusable for AgentPanel {
                     cx.focus_handle()
                 }
             }
+            ActiveView::UsageReport => {
+                if let Some(usage_report) = self.usage_report.as_ref() {
+                    usage_report.focus_handle(cx)
+                } else {
+                    cx.focus_handle()
+                }
+            }
         }
     }
 }
@@ -1543,6 +1703,7 @@ impl AgentPanel {
             }
             ActiveView::History => Label::new("History").truncate().into_any_element(),
             ActiveView::Configuration => Label::new("Settings").truncate().into_any_element(),
+            ActiveView::UsageReport => Label::new("Usage").truncate().into_any_element(),
         };
 
         h_flex()
@@ -1688,6 +1849,14 @@ impl AgentPanel {
                         })
                         .separator();
 
+                    menu = menu
+                        .when(!is_empty, |menu| {
+                            menu.action("Export as Markdown…", Box::new(ExportThreadAsMarkdown))
+                                .action("Export as JSON…", Box::new(ExportThreadAsJson))
+                        })
+                        .action("Import Thread…", Box::new(ImportThread))
+                        .separator();
+
                     menu = menu
                         .header("MCP Servers")
                         .action(
@@ -1740,6 +1909,7 @@ impl AgentPanel {
 
                     menu = menu
                         .action("Rules…", Box::new(OpenRulesLibrary::default()))
+                        .action("Usage", Box::new(OpenUsageReport))
                         .action("Settings", Box::new(OpenConfiguration))
                         .action(zoom_in_label, Box::new(ToggleZoom));
                     menu
@@ -1762,7 +1932,9 @@ impl AgentPanel {
                     .pl_1()
                     .gap_1()
                     .child(match &self.active_view {
-                        ActiveView::History | ActiveView::Configuration => go_back_button,
+                        ActiveView::History
+                        | ActiveView::Configuration
+                        | ActiveView::UsageReport => go_back_button,
                         _ => recent_entries_menu,
                     })
                     .child(self.render_title_view(window, cx)),
@@ -1771,6 +1943,10 @@ impl AgentPanel {
                 h_flex()
                     .h_full()
                     .gap_2()
+                    .when(
+                        matches!(self.active_view, ActiveView::Thread { .. }),
+                        |parent| parent.children(self.render_semantic_index_status(cx)),
+                    )
                     .when(show_token_count, |parent| {
                         parent.children(self.render_token_count(&thread, cx))
                     })
@@ -1806,6 +1982,56 @@ impl AgentPanel {
             )
     }
 
+    fn render_semantic_index_status(&self, cx: &App) -> Option<AnyElement> {
+        let (project_index, _) = self.semantic_index.as_ref()?;
+        let (tooltip, remaining_count) = match project_index.read(cx).status() {
+            semantic_index::Status::Idle => ("Codebase Indexed for Semantic Search", None),
+            semantic_index::Status::Loading => ("Loading Codebase Index…", None),
+            semantic_index::Status::Scanning { remaining_count } => {
+                ("Indexing Codebase…", Some(remaining_count))
+            }
+        };
+
+        Some(
+            h_flex()
+                .id("semantic-index-status")
+                .flex_shrink_0()
+                .gap_0p5()
+                .child(
+                    Icon::new(IconName::SearchCode)
+                        .size(IconSize::Small)
+                        .color(Color::Muted),
+                )
+                .children(remaining_count.map(|remaining_count| {
+                    Label::new(remaining_count.to_string())
+                        .size(LabelSize::Small)
+                        .color(Color::Muted)
+                        .with_animation(
+                            "semantic-index-remaining",
+                            Animation::new(Duration::from_secs(2))
+                                .repeat()
+                                .with_easing(pulsating_between(0.6, 1.)),
+                            |label, delta| label.alpha(delta),
+                        )
+                }))
+                .tooltip(move |window, cx| {
+                    Tooltip::with_meta(
+                        tooltip,
+                        None,
+                        match remaining_count {
+                            Some(remaining_count) => {
+                                format!("{remaining_count} files left to index")
+                            }
+                            None => "The agent can search this project by meaning.".to_string(),
+                        },
+                        window,
+                        cx,
+                    )
+                })
+                .into_any_element(),
+        )
+    }
+
     fn render_token_count(&self, thread: &Thread, cx: &App) -> Option<AnyElement> {
         let is_generating = thread.is_generating();
         let message_editor = self.message_editor.read(cx);
@@ -2314,12 +2540,14 @@ impl AgentPanel {
                     .child(
                         v_flex()
                             .gap_1()
-                            .children(
+                            .children({
+                                let thread_summaries = thread_summaries(self.thread_store.read(cx));
                                 recent_history.into_iter().enumerate().map(|(index, entry)| {
                                     // TODO: Add keyboard navigation.
                                     let is_hovered = self.hovered_recent_history_item == Some(index);
                                     HistoryEntryElement::new(entry.clone(), cx.entity().downgrade())
                                         .hovered(is_hovered)
+                                        .thread_summaries(&thread_summaries)
                                         .on_hover(cx.listener(move |this, is_hovered, _window, cx| {
                                             if *is_hovered {
                                                 this.hovered_recent_history_item = Some(index);
@@ -2329,8 +2557,9 @@ impl AgentPanel {
                                             cx.notify();
                                         }))
                                         .into_any_element()
-                                }),
-                            )
+                                })
+                                .collect::<Vec<_>>()
+                            })
                     )
                     .map(|parent| {
                         match configuration_error_ref {
@@ -2746,7 +2975,7 @@ impl AgentPanel {
                     );
                 });
             }
-            ActiveView::History | ActiveView::Configuration => {}
+            ActiveView::History | ActiveView::Configuration | ActiveView::UsageReport => {}
         }
     }
 
@@ -2794,7 +3023,13 @@ impl Render for AgentPanel {
             .on_action(cx.listener(|this, _: &OpenConfiguration, window, cx| {
                 this.open_configuration(window, cx);
             }))
+            .on_action(cx.listener(|this, _: &OpenUsageReport, window, cx| {
+                this.open_usage_report(window, cx);
+            }))
             .on_action(cx.listener(Self::open_active_thread_as_markdown))
+            .on_action(cx.listener(Self::export_thread_as_markdown))
+            .on_action(cx.listener(Self::export_thread_as_json))
+            .on_action(cx.listener(Self::import_thread))
             .on_action(cx.listener(Self::deploy_rules_library))
             .on_action(cx.listener(Self::open_agent_diff))
             .on_action(cx.listener(Self::go_back))
@@ -2826,6 +3061,7 @@ impl Render for AgentPanel {
                     cx,
                 )),
                 ActiveView::Configuration => parent.children(self.configuration.clone()),
+                ActiveView::UsageReport => parent.children(self.usage_report.clone()),
             });
 
         match self.active_view.which_font_size_used() {
@@ -2,6 +2,7 @@ mod completion_provider;
 mod fetch_context_picker;
 mod file_context_picker;
 mod rules_context_picker;
+mod semantic_context_picker;
 mod symbol_context_picker;
 mod thread_context_picker;
 
@@ -26,6 +27,8 @@ use paths::contexts_dir;
 use project::{Entry, ProjectPath};
 use prompt_store::{PromptStore, UserPromptId};
 use rules_context_picker::{RulesContextEntry, RulesContextPicker};
+use semantic_context_picker::SemanticContextPicker;
+use semantic_index::SemanticDb;
 use symbol_context_picker::SymbolContextPicker;
 use thread_context_picker::{
     ThreadContextEntry, ThreadContextPicker, render_thread_context_entry, unordered_thread_entries,
@@ -78,6 +81,7 @@ enum ContextPickerMode {
     Fetch,
     Thread,
     Rules,
+    Semantic,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -115,6 +119,7 @@ impl TryFrom<&str> for ContextPickerMode {
             "fetch" => Ok(Self::Fetch),
             "thread" => Ok(Self::Thread),
             "rule" => Ok(Self::Rules),
+            "search" => Ok(Self::Semantic),
             _ => Err(format!("Invalid context picker mode: {}", value)),
         }
     }
@@ -128,6 +133,7 @@ impl ContextPickerMode {
             Self::Fetch => "fetch",
             Self::Thread => "thread",
             Self::Rules => "rule",
+            Self::Semantic => "search",
         }
     }
 
@@ -138,6 +144,7 @@ impl ContextPickerMode {
             Self::Fetch => "Fetch",
             Self::Thread => "Threads",
             Self::Rules => "Rules",
+            Self::Semantic => "Codebase Search",
         }
     }
 
@@ -148,6 +155,7 @@ impl ContextPickerMode {
             Self::Fetch => IconName::Globe,
             Self::Thread => IconName::MessageBubbles,
             Self::Rules => RULES_ICON,
+            Self::Semantic => IconName::SearchCode,
         }
     }
 }
@@ -160,6 +168,7 @@ enum ContextPickerState {
     Fetch(Entity<FetchContextPicker>),
     Thread(Entity<ThreadContextPicker>),
     Rules(Entity<RulesContextPicker>),
+    Semantic(Entity<SemanticContextPicker>),
 }
 
 pub(super) struct ContextPicker {
@@ -345,6 +354,17 @@ impl ContextPicker {
                         )
                     }));
                 }
+                ContextPickerMode::Semantic => {
+                    self.mode = ContextPickerState::Semantic(cx.new(|cx| {
+                        SemanticContextPicker::new(
+                            context_picker.clone(),
+                            self.workspace.clone(),
+                            self.context_store.clone(),
+                            window,
+                            cx,
+                        )
+                    }));
+                }
                 ContextPickerMode::Thread => {
                     if let Some((thread_store, text_thread_store)) = self
                         .thread_store
@@ -545,6 +565,7 @@ impl ContextPicker {
             ContextPickerState::Fetch(entity) => entity.update(cx, |_, cx| cx.notify()),
             ContextPickerState::Thread(entity) => entity.update(cx, |_, cx| cx.notify()),
             ContextPickerState::Rules(entity) => entity.update(cx, |_, cx| cx.notify()),
+            ContextPickerState::Semantic(entity) => entity.update(cx, |_, cx| cx.notify()),
         }
     }
 }
@@ -560,6 +581,7 @@ impl Focusable for ContextPicker {
             ContextPickerState::Fetch(fetch_picker) => fetch_picker.focus_handle(cx),
             ContextPickerState::Thread(thread_picker) => thread_picker.focus_handle(cx),
             ContextPickerState::Rules(user_rules_picker) => user_rules_picker.focus_handle(cx),
+            ContextPickerState::Semantic(semantic_picker) => semantic_picker.focus_handle(cx),
         }
     }
 }
@@ -578,6 +600,9 @@ impl Render for ContextPicker {
                 ContextPickerState::Rules(user_rules_picker) => {
                     parent.child(user_rules_picker.clone())
                 }
+                ContextPickerState::Semantic(semantic_picker) => {
+                    parent.child(semantic_picker.clone())
+                }
             })
     }
 }
@@ -600,6 +625,10 @@ fn available_context_picker_entries(
         ContextPickerEntry::Mode(ContextPickerMode::Symbol),
     ];
 
+    if cx.has_global::<SemanticDb>() {
+        entries.push(ContextPickerEntry::Mode(ContextPickerMode::Semantic));
+    }
+
     let has_selection = workspace
         .read(cx)
         .active_item(cx)
@@ -30,6 +30,7 @@ use crate::thread_store::{TextThreadStore, ThreadStore};
 use super::fetch_context_picker::fetch_url_content;
 use super::file_context_picker::{FileMatch, search_files};
 use super::rules_context_picker::{RulesContextEntry, search_rules};
+use super::semantic_context_picker::{SemanticMatch, add_semantic_match, search_semantic};
 use super::symbol_context_picker::SymbolMatch;
 use super::symbol_context_picker::search_symbols;
 use super::thread_context_picker::{ThreadContextEntry, ThreadMatch, search_threads};
@@ -44,6 +45,7 @@ pub(crate) enum Match {
     Thread(ThreadMatch),
     Fetch(SharedString),
     Rules(RulesContextEntry),
+    Semantic(SemanticMatch),
     Entry(EntryMatch),
 }
 
@@ -61,6 +63,7 @@ impl Match {
             Match::Symbol(_) => 1.,
             Match::Fetch(_) => 1.,
             Match::Rules(_) => 1.,
+            Match::Semantic(_) => 1.,
         }
     }
 }
@@ -101,6 +104,17 @@ fn search(
             })
         }
 
+        Some(ContextPickerMode::Semantic) => {
+            let search_semantic_task = search_semantic(query.clone(), &workspace, cx);
+            cx.background_spawn(async move {
+                search_semantic_task
+                    .await
+                    .into_iter()
+                    .map(Match::Semantic)
+                    .collect()
+            })
+        }
+
         Some(ContextPickerMode::Thread) => {
             if let Some((thread_store, context_store)) = thread_store
                 .as_ref()
@@ -716,6 +730,59 @@ impl ContextPickerCompletionProvider {
             )),
         })
     }
+
+    fn completion_for_semantic_match(
+        semantic_match: SemanticMatch,
+        excerpt_id: ExcerptId,
+        source_range: Range<Anchor>,
+        editor: Entity<Editor>,
+        context_store: Entity<ContextStore>,
+        workspace: Entity<Workspace>,
+        cx: &mut App,
+    ) -> Completion {
+        let file_name = semantic_match.file_name();
+        let start_row = *semantic_match.row_range.start();
+        let end_row = *semantic_match.row_range.end();
+
+        let comment_id = cx.theme().syntax().highlight_id("comment").map(HighlightId);
+        let mut label = CodeLabel::plain(file_name.clone(), None);
+        label.push_str(" ", None);
+        label.push_str(&format!("L{}-{}", start_row + 1, end_row + 1), comment_id);
+
+        let new_text = MentionLink::for_selection(
+            &file_name,
+            &semantic_match.full_path.to_string_lossy(),
+            start_row as usize..end_row as usize,
+        );
+        let new_text_len = new_text.len();
+        Completion {
+            replace_range: source_range.clone(),
+            new_text,
+            label,
+            documentation: None,
+            source: project::CompletionSource::Custom,
+            icon_path: Some(IconName::SearchCode.path().into()),
+            insert_text_mode: None,
+            confirm: Some(confirm_completion_callback(
+                IconName::Context.path().into(),
+                format!("{} ({}-{})", file_name, start_row + 1, end_row + 1).into(),
+                excerpt_id,
+                source_range.start,
+                new_text_len,
+                editor.clone(),
+                context_store.clone(),
+                move |_, cx| {
+                    let result = add_semantic_match(
+                        &semantic_match,
+                        workspace.clone(),
+                        context_store.downgrade(),
+                        cx,
+                    );
+                    cx.spawn(async move |_| result.await.log_err())
+                },
+            )),
+        }
+    }
 }
 
 fn build_code_label_for_full_path(file_name: &str, directory: Option<&str>, cx: &App) -> CodeLabel {
@@ -887,6 +954,18 @@ impl CompletionProvider for ContextPickerCompletionProvider {
                             http_client.clone(),
                         )),
 
+                        Match::Semantic(semantic_match) => {
+                            Some(Self::completion_for_semantic_match(
+                                semantic_match,
+                                excerpt_id,
+                                source_range.clone(),
+                                editor.clone(),
+                                context_store.clone(),
+                                workspace.clone(),
+                                cx,
+                            ))
+                        }
+
                         Match::Entry(EntryMatch { entry, .. }) => Self::completion_for_entry(
                             entry,
                             excerpt_id,
@@ -0,0 +1,446 @@
+use std::ops::RangeInclusive;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{Context as _, Result};
+use gpui::{
+    App, AppContext, BorrowAppContext, DismissEvent, Entity, FocusHandle, Focusable, Stateful,
+    Task, WeakEntity,
+};
+use language::Point;
+use picker::{Picker, PickerDelegate};
+use project::ProjectPath;
+use semantic_index::SemanticDb;
+use ui::{ListItem, prelude::*};
+use util::ResultExt as _;
+use workspace::Workspace;
+
+use crate::context::AgentContextHandle;
+use crate::context_picker::ContextPicker;
+use crate::context_store::ContextStore;
+
+/// Searching embeds the query, so wait for the user to stop typing first.
+const SEARCH_DEBOUNCE: Duration = Duration::from_millis(300);
+const MAX_MATCHES: usize = 20;
+
+pub struct SemanticContextPicker {
+    picker: Entity<Picker<SemanticContextPickerDelegate>>,
+}
+
+impl SemanticContextPicker {
+    pub fn new(
+        context_picker: WeakEntity<ContextPicker>,
+        workspace: WeakEntity<Workspace>,
+        context_store: WeakEntity<ContextStore>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let delegate = SemanticContextPickerDelegate::new(context_picker, workspace, context_store);
+        let picker = cx.new(|cx| Picker::uniform_list(delegate, window, cx));
+
+        Self { picker }
+    }
+}
+
+impl Focusable for SemanticContextPicker {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl Render for SemanticContextPicker {
+    fn render(&mut self, _window: &mut Window, _cx: &mut Context<Self>) -> impl IntoElement {
+        self.picker.clone()
+    }
+}
+
+pub struct SemanticContextPickerDelegate {
+    context_picker: WeakEntity<ContextPicker>,
+    workspace: WeakEntity<Workspace>,
+    context_store: WeakEntity<ContextStore>,
+    matches: Vec<SemanticEntry>,
+    selected_index: usize,
+}
+
+impl SemanticContextPickerDelegate {
+    pub fn new(
+        context_picker: WeakEntity<ContextPicker>,
+        workspace: WeakEntity<Workspace>,
+        context_store: WeakEntity<ContextStore>,
+    ) -> Self {
+        Self {
+            context_picker,
+            workspace,
+            context_store,
+            matches: Vec::new(),
+            selected_index: 0,
+        }
+    }
+}
+
+impl PickerDelegate for SemanticContextPickerDelegate {
+    type ListItem = ListItem;
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(
+        &mut self,
+        ix: usize,
+        _window: &mut Window,
+        _cx: &mut Context<Picker<Self>>,
+    ) {
+        self.selected_index = ix;
+    }
+
+    fn placeholder_text(&self, _window: &mut Window, _cx: &mut App) -> Arc<str> {
+        "Describe the code you're looking for…".into()
+    }
+
+    fn update_matches(
+        &mut self,
+        query: String,
+        window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> Task<()> {
+        let Some(workspace) = self.workspace.upgrade() else {
+            return Task::ready(());
+        };
+
+        let search_task = search_semantic(query, &workspace, cx);
+        cx.spawn_in(window, async move |this, cx| {
+            let matches = search_task.await;
+            this.update(cx, |this, _cx| {
+                this.delegate.matches = matches
+                    .into_iter()
+                    .map(|mat| SemanticEntry {
+                        mat,
+                        is_included: false,
+                    })
+                    .collect();
+            })
+            .log_err();
+        })
+    }
+
+    fn confirm(&mut self, _secondary: bool, _window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let Some(entry) = self.matches.get(self.selected_index) else {
+            return;
+        };
+        if entry.is_included {
+            return;
+        }
+        let Some(workspace) = self.workspace.upgrade() else {
+            return;
+        };
+
+        let add_task = add_semantic_match(&entry.mat, workspace, self.context_store.clone(), cx);
+
+        let selected_index = self.selected_index;
+        cx.spawn(async move |this, cx| {
+            add_task.await?;
+            this.update(cx, |this, _| {
+                if let Some(entry) = this.delegate.matches.get_mut(selected_index) {
+                    entry.is_included = true;
+                }
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn dismissed(&mut self, _: &mut Window, cx: &mut Context<Picker<Self>>) {
+        self.context_picker
+            .update(cx, |_, cx| {
+                cx.emit(DismissEvent);
+            })
+            .ok();
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _window: &mut Window,
+        _: &mut Context<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let entry = &self.matches[ix];
+
+        Some(ListItem::new(ix).inset(true).toggle_state(selected).child(
+            render_semantic_context_entry(ElementId::named_usize("semantic-ctx-picker", ix), entry),
+        ))
+    }
+}
+
+struct SemanticEntry {
+    mat: SemanticMatch,
+    is_included: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct SemanticMatch {
+    pub project_path: ProjectPath,
+    pub full_path: PathBuf,
+    pub row_range: RangeInclusive<u32>,
+}
+
+impl SemanticMatch {
+    pub fn file_name(&self) -> String {
+        self.full_path
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .to_string()
+    }
+}
+
+pub(crate) fn search_semantic(
+    query: String,
+    workspace: &Entity<Workspace>,
+    cx: &mut App,
+) -> Task<Vec<SemanticMatch>> {
+    if query.trim().is_empty() || !cx.has_global::<SemanticDb>() {
+        return Task::ready(Vec::new());
+    }
+
+    let project = workspace.read(cx).project().clone();
+    let project_index = cx.update_global::<SemanticDb, _>(|semantic_db, cx| {
+        semantic_db.index_for_project(project.clone(), cx)
+    });
+    let fs = project_index.read(cx).fs();
+    cx.spawn(async move |cx| {
+        cx.background_executor().timer(SEARCH_DEBOUNCE).await;
+
+        let matches = async {
+            let search_task = project_index
+                .read_with(cx, |project_index, cx| {
+                    project_index.search(vec![query], MAX_MATCHES, cx)
+                })
+                .ok()?;
+            let results = search_task.await.log_err()?;
+            let results = SemanticDb::load_results(results, &fs, cx).await.log_err()?;
+
+            project
+                .read_with(cx, |project, cx| {
+                    results
+                        .into_iter()
+                        .filter_map(|result| {
+                            let root_name = result.full_path.iter().next()?;
+                            let worktree = project
+                                .visible_worktrees(cx)
+                                .find(|worktree| worktree.read(cx).root_name() == root_name)?;
+                            Some(SemanticMatch {
+                                project_path: ProjectPath {
+                                    worktree_id: worktree.read(cx).id(),
+                                    path: result.path,
+                                },
+                                full_path: result.full_path,
+                                row_range: result.row_range,
+                            })
+                        })
+                        .collect()
+                })
+                .ok()
+        };
+        matches.await.unwrap_or_default()
+    })
+}
+
+pub(crate) fn add_semantic_match(
+    mat: &SemanticMatch,
+    workspace: Entity<Workspace>,
+    context_store: WeakEntity<ContextStore>,
+    cx: &mut App,
+) -> Task<Result<AgentContextHandle>> {
+    let project = workspace.read(cx).project().clone();
+    let open_buffer_task = project.update(cx, |project, cx| {
+        project.open_buffer(mat.project_path.clone(), cx)
+    });
+    let row_range = mat.row_range.clone();
+    cx.spawn(async move |cx| {
+        let buffer = open_buffer_task.await?;
+        let range = buffer.read_with(cx, |buffer, _| {
+            let end_row = (*row_range.end()).min(buffer.max_point().row);
+            buffer.anchor_before(Point::new(*row_range.start(), 0))
+                ..buffer.anchor_after(Point::new(end_row, buffer.line_len(end_row)))
+        })?;
+        context_store
+            .update(cx, |context_store, cx| {
+                context_store.add_selection(buffer, range, cx)
+            })
+            .context("context store not available")
+    })
+}
+
+fn render_semantic_context_entry(id: ElementId, entry: &SemanticEntry) -> Stateful<Div> {
+    let mat = &entry.mat;
+    let location = format!("L{}-{}", mat.row_range.start() + 1, mat.row_range.end() + 1);
+
+    h_flex()
+        .id(id)
+        .gap_1p5()
+        .w_full()
+        .child(
+            Icon::new(IconName::SearchCode)
+                .size(IconSize::Small)
+                .color(Color::Muted),
+        )
+        .child(
+            h_flex().gap_1().child(Label::new(mat.file_name())).child(
+                Label::new(location)
+                    .size(LabelSize::Small)
+                    .color(Color::Muted),
+            ),
+        )
+        .when(entry.is_included, |el| {
+            el.child(
+                h_flex()
+                    .w_full()
+                    .justify_end()
+                    .gap_0p5()
+                    .child(
+                        Icon::new(IconName::Check)
+                            .size(IconSize::Small)
+                            .color(Color::Success),
+                    )
+                    .child(Label::new("Added").size(LabelSize::Small)),
+            )
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use feature_flags::FeatureFlagAppExt;
+    use futures::{FutureExt, future::BoxFuture};
+    use gpui::TestAppContext;
+    use project::{FakeFs, Project};
+    use semantic_index::{Embedding, EmbeddingProvider, Status, TextToEmbed};
+    use serde_json::json;
+    use settings::SettingsStore;
+    use std::future;
+    use util::{path, separator};
+
+    /// Embeds texts that mention the gutter close to each other, and far from the rest.
+    struct FakeEmbeddingProvider;
+
+    impl EmbeddingProvider for FakeEmbeddingProvider {
+        fn embed<'a>(
+            &'a self,
+            texts: &'a [TextToEmbed<'a>],
+        ) -> BoxFuture<'a, Result<Vec<Embedding>>> {
+            let embeddings = texts
+                .iter()
+                .map(|to_embed| {
+                    let x = if to_embed.text.contains("gutter") {
+                        1.
+                    } else {
+                        -1.
+                    };
+                    Ok(Embedding::new(vec![x, 0.1]))
+                })
+                .collect();
+            future::ready(embeddings).boxed()
+        }
+
+        fn batch_size(&self) -> usize {
+            16
+        }
+    }
+
+    #[gpui::test]
+    async fn test_semantic_context_picker(cx: &mut TestAppContext) {
+        init_test(cx);
+        cx.executor().allow_parking();
+
+        let db_dir = tempfile::tempdir().unwrap();
+        let semantic_db = SemanticDb::new(
+            db_dir.path().into(),
+            Arc::new(FakeEmbeddingProvider),
+            &mut cx.to_async(),
+        )
+        .await
+        .unwrap();
+        cx.update(|cx| cx.set_global(semantic_db));
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            path!("/project"),
+            json!({
+                "src": {
+                    "gutter.rs": "fn gutter_width(em_width: f32) -> f32 {\n    em_width * 4.\n}\n",
+                    "buffer.rs": "struct Buffer {\n    text: String,\n}\n",
+                }
+            }),
+        )
+        .await;
+        let project = Project::test(fs, [path!("/project").as_ref()], cx).await;
+        let window = cx.add_window(|window, cx| Workspace::test_new(project.clone(), window, cx));
+        let workspace = window.root(cx).unwrap();
+
+        // Opening the workspace starts indexing its project.
+        let project_index = cx
+            .update(|cx| {
+                cx.update_global::<SemanticDb, _>(|semantic_db, cx| {
+                    semantic_db.project_index(project.clone(), cx)
+                })
+            })
+            .expect("project should be indexed");
+        cx.run_until_parked();
+        while cx.read(|cx| project_index.read(cx).status()) != Status::Idle {
+            cx.run_until_parked();
+        }
+
+        let matches = cx.update(|cx| search_semantic("  ".into(), &workspace, cx));
+        assert!(matches.await.is_empty());
+
+        let matches = cx.update(|cx| search_semantic("width of the gutter".into(), &workspace, cx));
+        cx.executor().advance_clock(SEARCH_DEBOUNCE);
+        let matches = matches.await;
+        // The file about the gutter is the closest match.
+        assert_eq!(
+            matches[0].full_path.to_string_lossy(),
+            separator!("project/src/gutter.rs")
+        );
+        assert_eq!(matches[0].row_range, 0..=2);
+        assert_eq!(matches[0].file_name(), "gutter.rs");
+
+        let context_store = cx.new(|_| ContextStore::new(project.downgrade(), None));
+        let handle = cx
+            .update(|cx| add_semantic_match(&matches[0], workspace, context_store.downgrade(), cx))
+            .await
+            .unwrap();
+        let AgentContextHandle::Selection(selection) = handle else {
+            panic!("expected a selection, got {handle:?}");
+        };
+        cx.read(|cx| {
+            let buffer = selection.buffer.read(cx);
+            assert_eq!(
+                buffer
+                    .text_for_range(selection.range.clone())
+                    .collect::<String>(),
+                "fn gutter_width(em_width: f32) -> f32 {\n    em_width * 4.\n}"
+            );
+        });
+        assert_eq!(cx.read(|cx| context_store.read(cx).context().count()), 1);
+    }
+
+    fn init_test(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let store = SettingsStore::test(cx);
+            cx.set_global(store);
+            theme::init(theme::LoadThemes::JustBase, cx);
+            client::init_settings(cx);
+            language::init(cx);
+            Project::init_settings(cx);
+            workspace::init_settings(cx);
+            // Indexing is staff-flagged.
+            cx.update_flags(true, vec![]);
+        });
+    }
+}
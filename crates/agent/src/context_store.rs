@@ -354,14 +354,15 @@ impl ContextStore {
         buffer: Entity<Buffer>,
         range: Range<Anchor>,
         cx: &mut Context<ContextStore>,
-    ) {
+    ) -> AgentContextHandle {
         let context_id = self.next_context_id.post_inc();
         let context = AgentContextHandle::Selection(SelectionContextHandle {
             buffer,
             range,
             context_id,
         });
-        self.insert_context(context, cx);
+        self.insert_context(context.clone(), cx);
+        context
     }
 
     pub fn add_suggested_context(
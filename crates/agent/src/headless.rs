@@ -0,0 +1,555 @@
+use std::sync::Arc;
+
+use anyhow::Result;
+use assistant_settings::ToolPermission;
+use futures::{StreamExt as _, channel::mpsc};
+use gpui::{App, AsyncApp, Entity};
+use language_model::{ConfiguredModel, StopReason, TokenUsage};
+use serde::Serialize;
+
+use crate::context::ContextLoadResult;
+use crate::thread::{Thread, ThreadEvent};
+use crate::tool_permissions::ToolPermissionDecision;
+use crate::tool_use::PendingToolUseStatus;
+
+/// Progress of a thread that runs without a user interface, reported as one
+/// line of JSON per event.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum HeadlessEvent {
+    Started {
+        thread_id: String,
+        model: String,
+    },
+    Text {
+        text: String,
+    },
+    Thinking {
+        text: String,
+    },
+    ToolUse {
+        id: String,
+        name: String,
+        input: serde_json::Value,
+    },
+    ToolPermission {
+        id: String,
+        name: String,
+        permission: ToolPermission,
+        reason: String,
+    },
+    ToolResult {
+        id: String,
+        name: String,
+        is_error: bool,
+        output: String,
+    },
+    Error {
+        message: String,
+    },
+    Finished {
+        outcome: HeadlessOutcome,
+        token_usage: TokenUsage,
+        /// The changes the agent made to the project's repositories, as a patch.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        patch: Option<String>,
+    },
+}
+
+/// How a headless run ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HeadlessOutcome {
+    /// The model ended its turn.
+    Completed,
+    /// The model still wanted to use tools after the last allowed turn.
+    MaxTurns,
+    /// The model's response was cut off by its output token limit.
+    MaxTokens,
+    Failed,
+}
+
+impl HeadlessOutcome {
+    /// The exit status of a process that ran the thread: 0 when the model completed its
+    /// work and 1 otherwise. Runs that couldn't start exit with 2.
+    pub fn exit_code(self) -> i32 {
+        match self {
+            HeadlessOutcome::Completed => 0,
+            HeadlessOutcome::MaxTurns | HeadlessOutcome::MaxTokens | HeadlessOutcome::Failed => 1,
+        }
+    }
+}
+
+pub struct HeadlessRun {
+    pub prompt: String,
+    pub model: ConfiguredModel,
+    /// The maximum number of requests to send to the model.
+    pub max_turns: u32,
+    /// Whether to run tool calls that would otherwise wait for the user's
+    /// confirmation. When `false`, those calls are denied.
+    pub approve_confirmations: bool,
+}
+
+pub struct HeadlessRunResult {
+    pub outcome: HeadlessOutcome,
+    pub patch: Option<String>,
+}
+
+enum HeadlessUpdate {
+    Event(HeadlessEvent),
+    Done(HeadlessOutcome),
+}
+
+/// Sends `run.prompt` to a thread and runs it until the model stops, reporting
+/// its progress to `on_event`.
+///
+/// Tool calls are subject to the active profile's permissions, like in the
+/// agent panel. The changes made to the project are captured by checkpointing
+/// its git repositories before and after the run.
+pub async fn run_headless(
+    thread: Entity<Thread>,
+    run: HeadlessRun,
+    mut on_event: impl FnMut(HeadlessEvent),
+    cx: &mut AsyncApp,
+) -> Result<HeadlessRunResult> {
+    let git_store = thread.read_with(cx, |thread, cx| {
+        thread.project().read(cx).git_store().clone()
+    })?;
+    let base_checkpoint = git_store
+        .update(cx, |git_store, cx| git_store.checkpoint(cx))?
+        .await;
+
+    let (tx, mut rx) = mpsc::unbounded();
+    let model = run.model.model.clone();
+    let approve_confirmations = run.approve_confirmations;
+    let _subscription = cx.subscribe(&thread, move |thread, event: &ThreadEvent, cx| {
+        for update in handle_thread_event(&thread, event, &model, approve_confirmations, cx) {
+            tx.unbounded_send(update).ok();
+        }
+    })?;
+
+    let thread_id = thread.update(cx, |thread, cx| {
+        thread.set_configured_model(Some(run.model.clone()), cx);
+        thread.insert_user_message(
+            run.prompt,
+            ContextLoadResult::default(),
+            None,
+            Vec::new(),
+            cx,
+        );
+        thread.set_remaining_turns(run.max_turns);
+        thread.send_to_model(run.model.model.clone(), None, cx);
+        thread.id().to_string()
+    })?;
+    on_event(HeadlessEvent::Started {
+        thread_id,
+        model: format!("{}/{}", run.model.provider.id().0, run.model.model.id().0),
+    });
+
+    let mut outcome = HeadlessOutcome::Failed;
+    while let Some(update) = rx.next().await {
+        match update {
+            HeadlessUpdate::Event(event) => on_event(event),
+            HeadlessUpdate::Done(done) => {
+                outcome = done;
+                break;
+            }
+        }
+    }
+
+    let patch = match base_checkpoint {
+        Ok(base_checkpoint) => {
+            let checkpoint = git_store
+                .update(cx, |git_store, cx| git_store.checkpoint(cx))?
+                .await?;
+            let patch = git_store
+                .update(cx, |git_store, cx| {
+                    git_store.diff_checkpoints(base_checkpoint, checkpoint, cx)
+                })?
+                .await?;
+            Some(patch).filter(|patch| !patch.is_empty())
+        }
+        Err(error) => {
+            on_event(HeadlessEvent::Error {
+                message: format!("failed to capture the project's changes: {error}"),
+            });
+            None
+        }
+    };
+
+    let token_usage = thread.read_with(cx, |thread, _| thread.cumulative_token_usage())?;
+    on_event(HeadlessEvent::Finished {
+        outcome,
+        token_usage,
+        patch: patch.clone(),
+    });
+
+    Ok(HeadlessRunResult { outcome, patch })
+}
+
+fn handle_thread_event(
+    thread: &Entity<Thread>,
+    event: &ThreadEvent,
+    model: &Arc<dyn language_model::LanguageModel>,
+    approve_confirmations: bool,
+    cx: &mut App,
+) -> Vec<HeadlessUpdate> {
+    let mut updates = Vec::new();
+    match event {
+        ThreadEvent::StreamedAssistantText(_, text) => {
+            updates.push(HeadlessUpdate::Event(HeadlessEvent::Text {
+                text: text.clone(),
+            }));
+        }
+        ThreadEvent::StreamedAssistantThinking(_, text) => {
+            updates.push(HeadlessUpdate::Event(HeadlessEvent::Thinking {
+                text: text.clone(),
+            }));
+        }
+        ThreadEvent::UsePendingTools { tool_uses } => {
+            let mut confirmations = Vec::new();
+            let thread_ref = thread.read(cx);
+            for tool_use in tool_uses {
+                updates.push(HeadlessUpdate::Event(HeadlessEvent::ToolUse {
+                    id: tool_use.id.to_string(),
+                    name: tool_use.name.to_string(),
+                    input: tool_use.input.clone(),
+                }));
+                if let Some(entry) = thread_ref
+                    .tool_permission_log()
+                    .iter()
+                    .rev()
+                    .find(|entry| entry.tool_use_id == tool_use.id)
+                {
+                    updates.push(HeadlessUpdate::Event(HeadlessEvent::ToolPermission {
+                        id: tool_use.id.to_string(),
+                        name: tool_use.name.to_string(),
+                        permission: entry.decision.permission,
+                        reason: entry.decision.reason.to_string(),
+                    }));
+                }
+                if let Some(PendingToolUseStatus::NeedsConfirmation(confirmation)) = thread_ref
+                    .pending_tool(&tool_use.id)
+                    .map(|tool_use| &tool_use.status)
+                {
+                    confirmations.push((tool_use.name.clone(), confirmation.clone()));
+                }
+            }
+
+            // Nobody is around to confirm tool calls, so they're settled here.
+            thread.update(cx, |thread, cx| {
+                for (name, confirmation) in confirmations {
+                    let (permission, reason) = if approve_confirmations {
+                        thread.run_tool(
+                            confirmation.tool_use_id.clone(),
+                            confirmation.ui_text.clone(),
+                            confirmation.input.clone(),
+                            confirmation.request.clone(),
+                            confirmation.tool.clone(),
+                            model.clone(),
+                            None,
+                            cx,
+                        );
+                        (ToolPermission::Allow, "confirmations are approved")
+                    } else {
+                        thread.deny_tool_use_with_reason(
+                            confirmation.tool_use_id.clone(),
+                            name.clone(),
+                            "it requires confirmation, which isn't available in a headless run"
+                                .into(),
+                            None,
+                            cx,
+                        );
+                        (ToolPermission::Deny, "confirmation isn't available")
+                    };
+                    thread.log_tool_permission_decision(
+                        confirmation.tool_use_id.clone(),
+                        name.clone(),
+                        ToolPermissionDecision {
+                            permission,
+                            reason: reason.into(),
+                        },
+                    );
+                    updates.push(HeadlessUpdate::Event(HeadlessEvent::ToolPermission {
+                        id: confirmation.tool_use_id.to_string(),
+                        name: name.to_string(),
+                        permission,
+                        reason: reason.to_string(),
+                    }));
+                }
+            });
+        }
+        ThreadEvent::ToolFinished { tool_use_id, .. } => {
+            let thread = thread.read(cx);
+            if let Some(result) = thread.tool_result(tool_use_id) {
+                updates.push(HeadlessUpdate::Event(HeadlessEvent::ToolResult {
+                    id: tool_use_id.to_string(),
+                    name: result.tool_name.to_string(),
+                    is_error: result.is_error,
+                    output: result.content.to_str().unwrap_or("<image>").to_string(),
+                }));
+            }
+            if thread.remaining_turns() == 0 && !thread.is_generating() {
+                updates.push(HeadlessUpdate::Done(HeadlessOutcome::MaxTurns));
+            }
+        }
+        ThreadEvent::Stopped(Ok(StopReason::EndTurn)) => {
+            updates.push(HeadlessUpdate::Done(HeadlessOutcome::Completed));
+        }
+        ThreadEvent::Stopped(Ok(StopReason::MaxTokens)) => {
+            updates.push(HeadlessUpdate::Done(HeadlessOutcome::MaxTokens));
+        }
+        ThreadEvent::Stopped(Ok(StopReason::ToolUse)) => {
+            let thread = thread.read(cx);
+            if thread.remaining_turns() == 0 && !thread.is_generating() {
+                updates.push(HeadlessUpdate::Done(HeadlessOutcome::MaxTurns));
+            }
+        }
+        ThreadEvent::Stopped(Err(error)) => {
+            updates.push(HeadlessUpdate::Event(HeadlessEvent::Error {
+                message: error.to_string(),
+            }));
+            updates.push(HeadlessUpdate::Done(HeadlessOutcome::Failed));
+        }
+        ThreadEvent::ShowError(error) => {
+            updates.push(HeadlessUpdate::Event(HeadlessEvent::Error {
+                message: error.to_string(),
+            }));
+            updates.push(HeadlessUpdate::Done(HeadlessOutcome::Failed));
+        }
+        ThreadEvent::CompletionCanceled => {
+            updates.push(HeadlessUpdate::Done(HeadlessOutcome::Failed));
+        }
+        ThreadEvent::StreamedCompletion
+        | ThreadEvent::ReceivedTextChunk
+        | ThreadEvent::NewRequest
+        | ThreadEvent::StreamedToolUse { .. }
+        | ThreadEvent::MissingToolUse { .. }
+        | ThreadEvent::InvalidToolInput { .. }
+        | ThreadEvent::MessageAdded(_)
+        | ThreadEvent::MessageEdited(_)
+        | ThreadEvent::MessageDeleted(_)
+        | ThreadEvent::SummaryGenerated
+        | ThreadEvent::SummaryChanged
+        | ThreadEvent::CheckpointChanged
+        | ThreadEvent::ToolConfirmationNeeded
+        | ThreadEvent::CancelEditing
+        | ThreadEvent::ContextCompacted => {}
+    }
+    updates
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, rc::Rc};
+
+    use assistant_settings::AssistantSettings;
+    use assistant_tool::{
+        ActionLog, Tool, ToolRegistry, ToolResult, ToolResultOutput, ToolWorkingSet,
+    };
+    use gpui::{AnyWindowHandle, AppContext as _, Task, TestAppContext};
+    use language_model::{
+        LanguageModel, LanguageModelCompletionEvent, LanguageModelRequest, LanguageModelToolUse,
+        fake_provider::FakeLanguageModelProvider,
+    };
+    use project::{FakeFs, Project};
+    use prompt_store::PromptBuilder;
+    use serde_json::json;
+    use settings::{Settings as _, SettingsStore};
+    use ui::IconName;
+    use util::path;
+
+    use super::*;
+    use crate::{ThreadStore, thread_store};
+
+    struct EchoTool {
+        needs_confirmation: bool,
+    }
+
+    impl Tool for EchoTool {
+        fn name(&self) -> String {
+            if self.needs_confirmation {
+                "confirmed_echo".into()
+            } else {
+                "echo".into()
+            }
+        }
+
+        fn description(&self) -> String {
+            "Echoes its input".into()
+        }
+
+        fn icon(&self) -> IconName {
+            IconName::Terminal
+        }
+
+        fn needs_confirmation(&self, _: &serde_json::Value, _: &App) -> bool {
+            self.needs_confirmation
+        }
+
+        fn ui_text(&self, _: &serde_json::Value) -> String {
+            "Echo".into()
+        }
+
+        fn run(
+            self: Arc<Self>,
+            input: serde_json::Value,
+            _request: Arc<LanguageModelRequest>,
+            _project: Entity<project::Project>,
+            _action_log: Entity<ActionLog>,
+            _model: Arc<dyn LanguageModel>,
+            _window: Option<AnyWindowHandle>,
+            _cx: &mut App,
+        ) -> ToolResult {
+            Task::ready(Ok(ToolResultOutput::from(input["text"].to_string()))).into()
+        }
+    }
+
+    fn tool_use(id: &str, name: &str, text: &str) -> LanguageModelCompletionEvent {
+        let input = json!({ "text": text });
+        LanguageModelCompletionEvent::ToolUse(LanguageModelToolUse {
+            id: id.into(),
+            name: name.into(),
+            raw_input: input.to_string(),
+            input,
+            is_input_complete: true,
+        })
+    }
+
+    #[gpui::test]
+    async fn test_run_headless(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+            language::init(cx);
+            Project::init_settings(cx);
+            AssistantSettings::register(cx);
+            prompt_store::init(cx);
+            thread_store::init_test(cx);
+            language_model::init_settings(cx);
+            let registry = ToolRegistry::default_global(cx);
+            registry.register_tool(EchoTool {
+                needs_confirmation: false,
+            });
+            registry.register_tool(EchoTool {
+                needs_confirmation: true,
+            });
+        });
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(path!("/test"), json!({"code.rs": "fn main() {}"}))
+            .await;
+        let project = Project::test(fs, [path!("/test").as_ref()], cx).await;
+        let thread_store = cx
+            .update(|cx| {
+                ThreadStore::load(
+                    project.clone(),
+                    cx.new(|_| ToolWorkingSet::default()),
+                    None,
+                    Arc::new(PromptBuilder::new(None).unwrap()),
+                    cx,
+                )
+            })
+            .await
+            .unwrap();
+        let thread = thread_store.update(cx, |store, cx| store.create_thread(cx));
+
+        let provider = Arc::new(FakeLanguageModelProvider);
+        let model = Arc::new(provider.test_model());
+        let events = Rc::new(RefCell::new(Vec::new()));
+        let run = cx.spawn({
+            let model = model.clone();
+            let events = events.clone();
+            async move |mut cx| {
+                run_headless(
+                    thread,
+                    HeadlessRun {
+                        prompt: "Echo twice".into(),
+                        model: ConfiguredModel { provider, model },
+                        max_turns: 5,
+                        approve_confirmations: false,
+                    },
+                    move |event| events.borrow_mut().push(event),
+                    &mut cx,
+                )
+                .await
+            }
+        });
+
+        cx.run_until_parked();
+        model.send_last_completion_stream_event(tool_use("tool_1", "echo", "hello"));
+        model.send_last_completion_stream_event(tool_use("tool_2", "confirmed_echo", "world"));
+        model.send_last_completion_stream_event(LanguageModelCompletionEvent::Stop(
+            StopReason::ToolUse,
+        ));
+        model.end_last_completion_stream();
+        cx.run_until_parked();
+
+        // Once both tools finished, the results are sent back to the model.
+        let request = model.pending_completions().pop().unwrap();
+        let tool_results = request
+            .messages
+            .iter()
+            .flat_map(|message| &message.content)
+            .filter(|content| matches!(content, language_model::MessageContent::ToolResult(_)))
+            .count();
+        assert_eq!(tool_results, 2);
+        model.stream_last_completion_response("Echoed.".into());
+        model.send_last_completion_stream_event(LanguageModelCompletionEvent::Stop(
+            StopReason::EndTurn,
+        ));
+        model.end_last_completion_stream();
+        cx.run_until_parked();
+
+        let result = run.await.unwrap();
+        assert_eq!(result.outcome, HeadlessOutcome::Completed);
+        assert_eq!(result.outcome.exit_code(), 0);
+
+        let events = events.borrow();
+        assert!(matches!(
+            events.first(),
+            Some(HeadlessEvent::Started { .. })
+        ));
+        assert!(matches!(
+            events.last(),
+            Some(HeadlessEvent::Finished {
+                outcome: HeadlessOutcome::Completed,
+                ..
+            })
+        ));
+        let lines = events
+            .iter()
+            .filter_map(|event| match event {
+                HeadlessEvent::ToolPermission { id, permission, .. } => {
+                    Some(format!("{id} {permission:?}"))
+                }
+                HeadlessEvent::ToolResult { id, is_error, .. } => {
+                    Some(format!("{id} error: {is_error}"))
+                }
+                HeadlessEvent::Text { text } => Some(text.clone()),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        for expected in [
+            "tool_1 Allow",
+            "tool_1 error: false",
+            "tool_2 Ask",
+            "tool_2 Deny",
+            "tool_2 error: true",
+        ] {
+            assert!(
+                lines.iter().any(|line| line == expected),
+                "missing {expected:?} in {lines:?}"
+            );
+        }
+        // Every event is printed as a line of JSON.
+        for event in events.iter() {
+            let line = serde_json::to_string(event).unwrap();
+            assert!(!line.contains('\n'));
+            assert!(serde_json::from_str::<serde_json::Value>(&line).unwrap()["type"].is_string());
+        }
+
+        assert_eq!(HeadlessOutcome::MaxTurns.exit_code(), 1);
+        assert_eq!(HeadlessOutcome::Failed.exit_code(), 1);
+    }
+}
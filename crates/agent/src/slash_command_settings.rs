@@ -41,6 +41,7 @@ impl Settings for SlashCommandSettings {
             [sources.default]
                 .into_iter()
                 .chain(sources.user)
+                .chain(sources.profile)
                 .chain(sources.server),
         )
     }
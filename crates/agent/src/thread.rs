@@ -5,7 +5,7 @@ use std::sync::Arc;
 use std::time::Instant;
 
 use anyhow::{Result, anyhow};
-use assistant_settings::{AssistantSettings, CompletionMode};
+use assistant_settings::{AssistantSettings, CompletionMode, ToolPermission};
 use assistant_tool::{ActionLog, AnyToolCard, Tool, ToolWorkingSet};
 use chrono::{DateTime, Utc};
 use collections::HashMap;
@@ -36,7 +36,7 @@ use serde::{Deserialize, Serialize};
 use settings::Settings;
 use thiserror::Error;
 use ui::Window;
-use util::{ResultExt as _, post_inc};
+use util::{ResultExt as _, post_inc, truncate_and_trailoff};
 use uuid::Uuid;
 use zed_llm_client::CompletionRequestStatus;
 
@@ -46,6 +46,8 @@ use crate::thread_store::{
     SerializedCrease, SerializedLanguageModel, SerializedMessage, SerializedMessageSegment,
     SerializedThread, SerializedToolResult, SerializedToolUse, SharedProjectContext,
 };
+use crate::token_accounting::{BudgetExceeded, SharedProjectUsage, UsageReport};
+use crate::tool_permissions::{ToolPermissionDecision, ToolPermissionLogEntry, tool_permission};
 use crate::tool_use::{PendingToolUse, ToolUse, ToolUseMetadata, ToolUseState};
 
 #[derive(
@@ -220,6 +222,46 @@ pub struct ThreadCheckpoint {
     git_checkpoint: GitStoreCheckpoint,
 }
 
+/// The thread and message that a thread was forked from.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ThreadParent {
+    pub thread_id: ThreadId,
+    pub message_id: MessageId,
+}
+
+/// The state of a thread up to a message, which is copied into the threads
+/// forked from that message.
+pub struct ThreadForkPoint {
+    parent: ThreadParent,
+    messages: Vec<Message>,
+    checkpoints: Vec<ThreadCheckpoint>,
+}
+
+/// A summary of a thread's older messages, which is sent to the model in their
+/// place once the thread gets close to the model's context window.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ThreadCompaction {
+    /// The last message covered by the summary.
+    pub message_id: MessageId,
+    pub summary: SharedString,
+}
+
+/// The number of most recent messages that are always sent to the model as-is
+/// when a thread is compacted.
+const COMPACTION_KEPT_MESSAGES: usize = 4;
+
+/// The number of characters of each tool result and attached context that is
+/// given to the model summarizing a thread.
+const COMPACTION_MAX_ITEM_CHARS: usize = 4000;
+
+/// The share of the summarizing model's context window that the messages it summarizes
+/// may take up in one request, leaving room for the earlier summary and the new one.
+const COMPACTION_TRANSCRIPT_SHARE: usize = 2;
+
+/// A conservative estimate of the number of characters per token, used to fit the
+/// messages being summarized into the summarizing model's context window.
+const COMPACTION_CHARS_PER_TOKEN: usize = 3;
+
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum ThreadFeedback {
     Positive,
@@ -323,6 +365,7 @@ pub enum QueueState {
 /// A thread of conversation with the LLM.
 pub struct Thread {
     id: ThreadId,
+    parent: Option<ThreadParent>,
     updated_at: DateTime<Utc>,
     summary: ThreadSummary,
     pending_summary: Task<Option<()>>,
@@ -350,6 +393,7 @@ pub struct Thread {
     exceeded_window_error: Option<ExceededWindowError>,
     last_usage: Option<RequestUsage>,
     tool_use_limit_reached: bool,
+    tool_permission_log: Vec<ToolPermissionLogEntry>,
     feedback: Option<ThreadFeedback>,
     message_feedback: HashMap<MessageId, ThreadFeedback>,
     last_auto_capture_at: Option<Instant>,
@@ -359,6 +403,13 @@ pub struct Thread {
     >,
     remaining_turns: u32,
     configured_model: Option<ConfiguredModel>,
+    compaction: Option<ThreadCompaction>,
+    token_usage_by_model: UsageReport,
+    /// Tokens used by the thread this one was forked or imported from. They
+    /// count towards this thread's limits, but not towards the project's,
+    /// which already counts them or never spent them.
+    inherited_token_usage: UsageReport,
+    project_usage: SharedProjectUsage,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -409,6 +460,7 @@ impl Thread {
 
         Self {
             id: ThreadId::new(),
+            parent: None,
             updated_at: Utc::now(),
             summary: ThreadSummary::Pending,
             pending_summary: Task::ready(None),
@@ -441,6 +493,7 @@ impl Thread {
             exceeded_window_error: None,
             last_usage: None,
             tool_use_limit_reached: false,
+            tool_permission_log: Vec::new(),
             feedback: None,
             message_feedback: HashMap::default(),
             last_auto_capture_at: None,
@@ -448,6 +501,10 @@ impl Thread {
             request_callback: None,
             remaining_turns: u32::MAX,
             configured_model,
+            compaction: None,
+            token_usage_by_model: UsageReport::default(),
+            inherited_token_usage: UsageReport::default(),
+            project_usage: SharedProjectUsage::default(),
         }
     }
 
@@ -478,6 +535,7 @@ impl Thread {
         let (detailed_summary_tx, detailed_summary_rx) =
             postage::watch::channel_with(serialized.detailed_summary_state);
 
+        let token_usage_by_model = serialized.token_usage_by_model();
         let configured_model = LanguageModelRegistry::global(cx).update(cx, |registry, cx| {
             serialized
                 .model
@@ -497,6 +555,7 @@ impl Thread {
 
         Self {
             id,
+            parent: serialized.parent,
             updated_at: serialized.updated_at,
             summary: ThreadSummary::Ready(serialized.summary),
             pending_summary: Task::ready(None),
@@ -561,6 +620,7 @@ impl Thread {
             exceeded_window_error: None,
             last_usage: None,
             tool_use_limit_reached: false,
+            tool_permission_log: serialized.tool_permission_log,
             feedback: None,
             message_feedback: HashMap::default(),
             last_auto_capture_at: None,
@@ -568,6 +628,10 @@ impl Thread {
             request_callback: None,
             remaining_turns: u32::MAX,
             configured_model,
+            compaction: serialized.compaction,
+            token_usage_by_model,
+            inherited_token_usage: serialized.inherited_token_usage,
+            project_usage: SharedProjectUsage::default(),
         }
     }
 
@@ -583,6 +647,11 @@ impl Thread {
         &self.id
     }
 
+    /// Returns the thread and message that this thread was forked from, if any.
+    pub fn parent(&self) -> Option<&ThreadParent> {
+        self.parent.as_ref()
+    }
+
     pub fn is_empty(&self) -> bool {
         self.messages.is_empty()
     }
@@ -806,12 +875,90 @@ impl Thread {
         else {
             return;
         };
+        self.discard_compaction_covering(message_ix);
         for deleted_message in self.messages.drain(message_ix..) {
             self.checkpoints_by_message.remove(&deleted_message.id);
         }
         cx.notify();
     }
 
+    /// Captures this thread up to and including `message_id`, so that a new
+    /// thread can continue from there with [`Thread::forked`].
+    pub fn fork_point(&self, message_id: MessageId) -> Option<ThreadForkPoint> {
+        let message_ix = self
+            .messages
+            .iter()
+            .position(|message| message.id == message_id)?;
+        let messages = self.messages[..=message_ix].to_vec();
+        let checkpoints = messages
+            .iter()
+            .filter_map(|message| self.checkpoints_by_message.get(&message.id).cloned())
+            .collect();
+        Some(ThreadForkPoint {
+            parent: ThreadParent {
+                thread_id: self.id.clone(),
+                message_id,
+            },
+            messages,
+            checkpoints,
+        })
+    }
+
+    /// Creates a thread that continues the conversation of `serialized` from
+    /// `fork_point`, leaving the original thread untouched.
+    pub fn forked(
+        id: ThreadId,
+        mut serialized: SerializedThread,
+        fork_point: ThreadForkPoint,
+        project: Entity<Project>,
+        tools: Entity<ToolWorkingSet>,
+        prompt_builder: Arc<PromptBuilder>,
+        project_context: SharedProjectContext,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        serialized.messages.retain(|message| {
+            fork_point
+                .messages
+                .iter()
+                .any(|forked_message| forked_message.id == message.id)
+        });
+        serialized
+            .request_token_usage
+            .truncate(fork_point.messages.len());
+        // The fork continues the parent's conversation, so it starts with the
+        // parent's usage towards its limits, but hasn't spent anything itself.
+        let usage = serialized.token_usage_by_model();
+        serialized.inherited_token_usage.extend(&usage);
+        serialized.cumulative_token_usage = TokenUsage::default();
+        serialized.token_usage_by_model = UsageReport::default();
+
+        let mut this = Self::deserialize(
+            id,
+            serialized,
+            project,
+            tools,
+            prompt_builder,
+            project_context,
+            None,
+            cx,
+        );
+        this.parent = Some(fork_point.parent);
+        this.updated_at = Utc::now();
+        // Unlike their serialized form, these messages keep the context that was attached to them.
+        this.messages = fork_point.messages;
+        this.compaction = this.compaction.take().filter(|compaction| {
+            this.messages
+                .iter()
+                .any(|message| message.id == compaction.message_id)
+        });
+        this.checkpoints_by_message = fork_point
+            .checkpoints
+            .into_iter()
+            .map(|checkpoint| (checkpoint.message_id, checkpoint))
+            .collect();
+        this
+    }
+
     pub fn context_for_message(&self, id: MessageId) -> impl Iterator<Item = &AgentContext> {
         self.messages
             .iter()
@@ -854,6 +1001,11 @@ impl Thread {
         self.tool_use_limit_reached
     }
 
+    /// Returns the permission decisions made for this thread's tool uses, in order.
+    pub fn tool_permission_log(&self) -> &[ToolPermissionLogEntry] {
+        &self.tool_permission_log
+    }
+
     /// Returns whether all of the tool uses have finished running.
     pub fn all_tools_finished(&self) -> bool {
         // If the only pending tool uses left are the ones with errors, then
@@ -998,9 +1150,11 @@ impl Thread {
         loaded_context: Option<LoadedContext>,
         cx: &mut Context<Self>,
     ) -> bool {
-        let Some(message) = self.messages.iter_mut().find(|message| message.id == id) else {
+        let Some(index) = self.messages.iter().position(|message| message.id == id) else {
             return false;
         };
+        self.discard_compaction_covering(index);
+        let message = &mut self.messages[index];
         message.role = new_role;
         message.segments = new_segments;
         if let Some(context) = loaded_context {
@@ -1015,6 +1169,7 @@ impl Thread {
         let Some(index) = self.messages.iter().position(|message| message.id == id) else {
             return false;
         };
+        self.discard_compaction_covering(index);
         self.messages.remove(index);
         self.touch_updated_at();
         cx.emit(ThreadEvent::MessageDeleted(id));
@@ -1057,6 +1212,7 @@ impl Thread {
             let initial_project_snapshot = initial_project_snapshot.await;
             this.read_with(cx, |this, cx| SerializedThread {
                 version: SerializedThread::VERSION.to_string(),
+                parent: this.parent.clone(),
                 summary: this.summary().or_default(),
                 updated_at: this.updated_at(),
                 messages: this
@@ -1129,6 +1285,10 @@ impl Thread {
                         model: model.model.id().0.to_string(),
                     }),
                 completion_mode: Some(this.completion_mode),
+                compaction: this.compaction.clone(),
+                token_usage_by_model: this.token_usage_by_model.clone(),
+                inherited_token_usage: this.inherited_token_usage.clone(),
+                tool_permission_log: this.tool_permission_log.clone(),
             })
         })
     }
@@ -1153,11 +1313,194 @@ impl Thread {
 
         self.remaining_turns -= 1;
 
+        if let Some(exceeded) = self.exceeded_budget(cx) {
+            cx.emit(ThreadEvent::ShowError(ThreadError::Message {
+                header: "Usage Limit Reached".into(),
+                message: exceeded.to_string().into(),
+            }));
+            return;
+        }
+
+        if let Some(compacted_ix) = self.compaction_boundary(cx) {
+            if let Some(summarizer) = self.compaction_model(cx) {
+                self.compact_and_send(compacted_ix, summarizer, model, window, cx);
+                return;
+            }
+        }
+
         let request = self.to_completion_request(model.clone(), cx);
 
         self.stream_completion(request, model, window, cx);
     }
 
+    /// Returns the summary that replaces this thread's older messages in
+    /// requests, if the thread has been compacted.
+    pub fn compaction(&self) -> Option<&ThreadCompaction> {
+        self.compaction.as_ref()
+    }
+
+    /// Returns the index of the last message to compact, if the thread has
+    /// grown past the configured share of the model's context window.
+    fn compaction_boundary(&self, cx: &App) -> Option<usize> {
+        let settings = &AssistantSettings::get_global(cx).context_compaction;
+        if !settings.enabled {
+            return None;
+        }
+
+        let usage = self.total_token_usage()?;
+        if usage.max == 0
+            || (usage.total as f32) < usage.max as f32 * settings.threshold.clamp(0., 1.)
+        {
+            return None;
+        }
+
+        let start = self.first_uncompacted_message_ix();
+
+        // The summary is sent as a user message, so the messages sent after it
+        // must start with one from the assistant.
+        let mut end = self.messages.len().saturating_sub(COMPACTION_KEPT_MESSAGES);
+        while end > start && self.messages[end].role != Role::Assistant {
+            end -= 1;
+        }
+
+        (end > start).then_some(end - 1)
+    }
+
+    fn compaction_model(&self, cx: &mut Context<Self>) -> Option<ConfiguredModel> {
+        let selection = AssistantSettings::get_global(cx)
+            .context_compaction
+            .model
+            .clone();
+        let model = LanguageModelRegistry::global(cx).update(cx, |registry, cx| {
+            selection
+                .and_then(|selection| {
+                    registry.select_model(
+                        &SelectedModel {
+                            provider: selection.provider.0.into(),
+                            model: selection.model.into(),
+                        },
+                        cx,
+                    )
+                })
+                .or_else(|| registry.thread_summary_model())
+        })?;
+        model.provider.is_authenticated(cx).then_some(model)
+    }
+
+    /// Summarizes the messages up to and including `compacted_ix`, then sends
+    /// the thread to `model` with the summary in their place.
+    ///
+    /// If the summary can't be generated, the thread is sent as it is.
+    fn compact_and_send(
+        &mut self,
+        compacted_ix: usize,
+        summarizer: ConfiguredModel,
+        model: Arc<dyn LanguageModel>,
+        window: Option<AnyWindowHandle>,
+        cx: &mut Context<Self>,
+    ) {
+        let message_id = self.messages[compacted_ix].id;
+        let max_transcript_chars = summarizer.model.max_token_count() / COMPACTION_TRANSCRIPT_SHARE
+            * COMPACTION_CHARS_PER_TOKEN;
+        let transcripts =
+            compaction_transcripts(self.compaction_sections(compacted_ix), max_transcript_chars);
+        let earlier_summary = self
+            .compaction
+            .as_ref()
+            .map(|compaction| compaction.summary.to_string());
+        let temperature = AssistantSettings::temperature_for_model(&summarizer.model, cx);
+        let pending_completion_id = post_inc(&mut self.completion_count);
+
+        let task = cx.spawn(async move |thread, cx| {
+            let mut token_usage = TokenUsage::default();
+            // Transcripts that don't fit in one request are summarized in turn, each along
+            // with the summary of the ones before it.
+            let summary = async {
+                let mut summary = earlier_summary;
+                for transcript in transcripts {
+                    let request = compaction_request(summary.as_deref(), &transcript, temperature);
+                    let mut messages = summarizer
+                        .model
+                        .stream_completion_text(request, &cx)
+                        .await?;
+                    let mut chunk_summary = String::new();
+                    let chunk_result = async {
+                        while let Some(chunk) = messages.stream.next().await {
+                            chunk_summary.push_str(&chunk?);
+                        }
+                        anyhow::Ok(())
+                    }
+                    .await;
+                    token_usage = token_usage + *messages.last_token_usage.lock();
+                    chunk_result?;
+                    if chunk_summary.trim().is_empty() {
+                        return Err(anyhow!("the summary was empty"));
+                    }
+                    summary = Some(chunk_summary);
+                }
+                summary.ok_or_else(|| anyhow!("there was nothing to summarize"))
+            }
+            .await;
+
+            thread
+                .update(cx, |thread, cx| {
+                    thread
+                        .pending_completions
+                        .retain(|completion| completion.id != pending_completion_id);
+                    thread.record_token_usage(summarizer.model.as_ref(), token_usage);
+
+                    match summary {
+                        Ok(summary) => {
+                            thread.compaction = Some(ThreadCompaction {
+                                message_id,
+                                summary: summary.trim().to_string().into(),
+                            });
+                            cx.emit(ThreadEvent::ContextCompacted);
+                        }
+                        Err(error) => log::error!("Failed to compact thread: {error:#}"),
+                    }
+
+                    let request = thread.to_completion_request(model.clone(), cx);
+                    thread.stream_completion(request, model, window, cx);
+                })
+                .ok();
+        });
+
+        self.pending_completions.push(PendingCompletion {
+            id: pending_completion_id,
+            queue_state: QueueState::Sending,
+            _task: task,
+        });
+        cx.notify();
+    }
+
+    /// Returns the index of the first message that isn't covered by the
+    /// thread's compaction.
+    fn first_uncompacted_message_ix(&self) -> usize {
+        self.compaction
+            .as_ref()
+            .and_then(|compaction| {
+                self.messages
+                    .iter()
+                    .position(|message| message.id == compaction.message_id)
+            })
+            .map_or(0, |ix| ix + 1)
+    }
+
+    /// Drops the thread's compaction if it covers the message at `message_ix`,
+    /// since the summary no longer matches the messages it stands in for.
+    fn discard_compaction_covering(&mut self, message_ix: usize) {
+        let Some(compaction) = &self.compaction else {
+            return;
+        };
+        if self.messages[message_ix..]
+            .iter()
+            .any(|message| message.id == compaction.message_id)
+        {
+            self.compaction = None;
+        }
+    }
+
     pub fn used_tools_since_last_user_message(&self) -> bool {
         for message in self.messages.iter().rev() {
             if self.tool_use.message_has_tool_results(message.id) {
@@ -1226,8 +1569,24 @@ impl Thread {
             }));
         }
 
+        let first_uncompacted_message_ix = self.first_uncompacted_message_ix();
+        if let Some(compaction) = self
+            .compaction
+            .as_ref()
+            .filter(|_| first_uncompacted_message_ix > 0)
+        {
+            request.messages.push(LanguageModelRequestMessage {
+                role: Role::User,
+                content: vec![MessageContent::Text(format!(
+                    "The earlier part of this conversation was summarized to fit in your context window:\n\n{}",
+                    compaction.summary
+                ))],
+                cache: false,
+            });
+        }
+
         let mut message_ix_to_cache = None;
-        for message in &self.messages {
+        for message in &self.messages[first_uncompacted_message_ix..] {
             let mut request_message = LanguageModelRequestMessage {
                 role: message.role,
                 content: Vec::new(),
@@ -1378,6 +1737,62 @@ impl Thread {
         request
     }
 
+    /// Returns the messages that are not yet compacted, up to and including
+    /// `compacted_ix`, as sections of the transcript given to the summarizing model.
+    fn compaction_sections(&self, compacted_ix: usize) -> Vec<String> {
+        let start = self.first_uncompacted_message_ix();
+        let mut sections = Vec::new();
+        for message in &self.messages[start..=compacted_ix] {
+            let mut section = String::new();
+            let role = match message.role {
+                Role::User => "User",
+                Role::Assistant => "Assistant",
+                Role::System => "System",
+            };
+            writeln!(&mut section, "## {role}\n").ok();
+
+            if !message.loaded_context.text.is_empty() {
+                writeln!(
+                    &mut section,
+                    "{}\n",
+                    truncate_and_trailoff(&message.loaded_context.text, COMPACTION_MAX_ITEM_CHARS)
+                )
+                .ok();
+            }
+
+            for segment in &message.segments {
+                if let MessageSegment::Text(text) = segment {
+                    writeln!(&mut section, "{text}\n").ok();
+                }
+            }
+
+            for (tool_use, tool_result) in self.tool_use.tool_results(message.id) {
+                writeln!(
+                    &mut section,
+                    "Called `{}` with {}",
+                    tool_use.name, tool_use.input
+                )
+                .ok();
+                if let Some(tool_result) = tool_result {
+                    let output = tool_result.content.to_str().unwrap_or("<image>");
+                    writeln!(
+                        &mut section,
+                        "{}:\n{}\n",
+                        if tool_result.is_error {
+                            "Error"
+                        } else {
+                            "Result"
+                        },
+                        truncate_and_trailoff(output, COMPACTION_MAX_ITEM_CHARS)
+                    )
+                    .ok();
+                }
+            }
+            sections.push(section);
+        }
+        sections
+    }
+
     fn attached_tracked_files_state(
         &self,
         messages: &mut Vec<LanguageModelRequestMessage>,
@@ -1503,9 +1918,14 @@ impl Thread {
                             }
                             LanguageModelCompletionEvent::UsageUpdate(token_usage) => {
                                 thread.update_token_usage_at_last_message(token_usage);
-                                thread.cumulative_token_usage = thread.cumulative_token_usage
-                                    + token_usage
-                                    - current_token_usage;
+                                let new_token_usage = token_usage - current_token_usage;
+                                thread.cumulative_token_usage =
+                                    thread.cumulative_token_usage + new_token_usage;
+                                thread.token_usage_by_model.add(
+                                    &model.provider_id().0,
+                                    &model.id().0,
+                                    new_token_usage,
+                                );
                                 current_token_usage = token_usage;
                             }
                             LanguageModelCompletionEvent::Text(chunk) => {
@@ -1781,6 +2201,7 @@ impl Thread {
         self.summary = ThreadSummary::Generating;
 
         self.pending_summary = cx.spawn(async move |this, cx| {
+            let mut token_usage = TokenUsage::default();
             let result = async {
                 let mut messages = model.model.stream_completion(request, &cx).await?;
 
@@ -1802,6 +2223,10 @@ impl Thread {
                             })?;
                             continue;
                         }
+                        LanguageModelCompletionEvent::UsageUpdate(usage) => {
+                            token_usage = usage;
+                            continue;
+                        }
                         _ => continue,
                     };
 
@@ -1819,6 +2244,7 @@ impl Thread {
             .await;
 
             this.update(cx, |this, cx| {
+                this.record_token_usage(model.model.as_ref(), token_usage);
                 match result {
                     Ok(new_summary) => {
                         if new_summary.is_empty() {
@@ -1906,9 +2332,11 @@ impl Thread {
                     new_detailed_summary.push_str(&chunk);
                 }
             }
+            let token_usage = *messages.last_token_usage.lock();
 
             thread
                 .update(cx, |thread, _cx| {
+                    thread.record_token_usage(model.as_ref(), token_usage);
                     *thread.detailed_summary_tx.borrow_mut() = DetailedSummaryState::Generated {
                         text: new_detailed_summary.into(),
                         message_id: last_message_id,
@@ -1980,28 +2408,44 @@ impl Thread {
 
         for tool_use in pending_tool_uses.iter() {
             if let Some(tool) = self.tools.read(cx).tool(&tool_use.name, cx) {
-                if tool.needs_confirmation(&tool_use.input, cx)
-                    && !AssistantSettings::get_global(cx).always_allow_tool_actions
-                {
-                    self.tool_use.confirm_tool_use(
-                        tool_use.id.clone(),
-                        tool_use.ui_text.clone(),
-                        tool_use.input.clone(),
-                        request.clone(),
-                        tool,
-                    );
-                    cx.emit(ThreadEvent::ToolConfirmationNeeded);
-                } else {
-                    self.run_tool(
-                        tool_use.id.clone(),
-                        tool_use.ui_text.clone(),
-                        tool_use.input.clone(),
-                        request.clone(),
-                        tool,
-                        model.clone(),
-                        window,
-                        cx,
-                    );
+                let decision = tool_permission(&tool, &tool_use.input, cx);
+                self.log_tool_permission_decision(
+                    tool_use.id.clone(),
+                    tool_use.name.clone(),
+                    decision.clone(),
+                );
+                match decision.permission {
+                    ToolPermission::Allow => {
+                        self.run_tool(
+                            tool_use.id.clone(),
+                            tool_use.ui_text.clone(),
+                            tool_use.input.clone(),
+                            request.clone(),
+                            tool,
+                            model.clone(),
+                            window,
+                            cx,
+                        );
+                    }
+                    ToolPermission::Ask => {
+                        self.tool_use.confirm_tool_use(
+                            tool_use.id.clone(),
+                            tool_use.ui_text.clone(),
+                            tool_use.input.clone(),
+                            request.clone(),
+                            tool,
+                        );
+                        cx.emit(ThreadEvent::ToolConfirmationNeeded);
+                    }
+                    ToolPermission::Deny => {
+                        self.deny_tool_use_with_reason(
+                            tool_use.id.clone(),
+                            tool_use.name.clone(),
+                            decision.reason,
+                            window,
+                            cx,
+                        );
+                    }
                 }
             } else {
                 self.handle_hallucinated_tool_use(
@@ -2016,6 +2460,51 @@ impl Thread {
         pending_tool_uses
     }
 
+    /// Records a decision on whether a tool use may run in the thread's permission log.
+    pub fn log_tool_permission_decision(
+        &mut self,
+        tool_use_id: LanguageModelToolUseId,
+        tool_name: Arc<str>,
+        decision: ToolPermissionDecision,
+    ) {
+        log::info!(
+            "tool permission for {} ({}) in thread {}: {:?}, {}",
+            tool_name,
+            tool_use_id,
+            self.id,
+            decision.permission,
+            decision.reason
+        );
+        self.tool_permission_log.push(ToolPermissionLogEntry {
+            tool_use_id,
+            tool_name,
+            decision,
+            decided_at: Utc::now(),
+        });
+    }
+
+    /// Denies a tool use without canceling the turn, so that the model is told
+    /// why the tool didn't run and can carry on.
+    pub fn deny_tool_use_with_reason(
+        &mut self,
+        tool_use_id: LanguageModelToolUseId,
+        tool_name: Arc<str>,
+        reason: SharedString,
+        window: Option<AnyWindowHandle>,
+        cx: &mut Context<Thread>,
+    ) {
+        let pending_tool_use = self.tool_use.insert_tool_output(
+            tool_use_id.clone(),
+            tool_name,
+            Err(anyhow!(
+                "Permission to run this tool was denied: {reason}. \
+                Don't retry this call; find another way or ask the user."
+            )),
+            self.configured_model.as_ref(),
+        );
+        self.tool_finished(tool_use_id, pending_tool_use, false, window, cx);
+    }
+
     pub fn handle_hallucinated_tool_use(
         &mut self,
         tool_use_id: LanguageModelToolUseId,
@@ -2482,10 +2971,11 @@ impl Thread {
                 }
             }
 
+            // Tool calls are collapsed, so that the conversation stays readable.
             for tool_use in self.tool_uses_for_message(message.id, cx) {
                 writeln!(
                     markdown,
-                    "**Use Tool: {} ({})**",
+                    "<details>\n<summary>Tool Call: {} ({})</summary>\n",
                     tool_use.name, tool_use.id
                 )?;
                 writeln!(markdown, "```json")?;
@@ -2495,31 +2985,33 @@ impl Thread {
                     serde_json::to_string_pretty(&tool_use.input)?
                 )?;
                 writeln!(markdown, "```")?;
-            }
 
-            for tool_result in self.tool_results_for_message(message.id) {
-                write!(markdown, "\n**Tool Results: {}", tool_result.tool_use_id)?;
-                if tool_result.is_error {
-                    write!(markdown, " (Error)")?;
-                }
+                if let Some(tool_result) = self.tool_result(&tool_use.id) {
+                    write!(markdown, "\n**Tool Results")?;
+                    if tool_result.is_error {
+                        write!(markdown, " (Error)")?;
+                    }
 
-                writeln!(markdown, "**\n")?;
-                match &tool_result.content {
-                    LanguageModelToolResultContent::Text(str) => {
-                        writeln!(markdown, "{}", str)?;
+                    writeln!(markdown, "**\n")?;
+                    match &tool_result.content {
+                        LanguageModelToolResultContent::Text(str) => {
+                            writeln!(markdown, "{}", str)?;
+                        }
+                        LanguageModelToolResultContent::Image(image) => {
+                            writeln!(markdown, "![Image](data:base64,{})", image.source)?;
+                        }
                     }
-                    LanguageModelToolResultContent::Image(image) => {
-                        writeln!(markdown, "![Image](data:base64,{})", image.source)?;
+
+                    if let Some(output) = tool_result.output.as_ref() {
+                        writeln!(
+                            markdown,
+                            "\n\nDebug Output:\n\n```json\n{}\n```\n",
+                            serde_json::to_string_pretty(output)?
+                        )?;
                     }
                 }
 
-                if let Some(output) = tool_result.output.as_ref() {
-                    writeln!(
-                        markdown,
-                        "\n\nDebug Output:\n\n```json\n{}\n```\n",
-                        serde_json::to_string_pretty(output)?
-                    )?;
-                }
+                writeln!(markdown, "\n</details>\n")?;
             }
         }
 
@@ -2609,6 +3101,48 @@ impl Thread {
         self.cumulative_token_usage
     }
 
+    /// Returns the tokens this thread has used, per model.
+    pub fn token_usage_by_model(&self) -> &UsageReport {
+        &self.token_usage_by_model
+    }
+
+    /// Records tokens used on the thread's behalf outside of its completions,
+    /// such as for its summaries, so they count towards its spending limits.
+    fn record_token_usage(&mut self, model: &dyn LanguageModel, token_usage: TokenUsage) {
+        self.token_usage_by_model
+            .add(&model.provider_id().0, &model.id().0, token_usage);
+    }
+
+    pub fn set_project_usage(&mut self, project_usage: SharedProjectUsage) {
+        self.project_usage = project_usage;
+    }
+
+    /// Returns the tokens that count towards this thread's limits: its own,
+    /// and those of the thread it was forked or imported from.
+    pub fn usage_towards_limits(&self) -> UsageReport {
+        let mut usage = self.inherited_token_usage.clone();
+        usage.extend(&self.token_usage_by_model);
+        usage
+    }
+
+    /// Returns the tokens used by all threads in this thread's project,
+    /// including this one.
+    pub fn project_usage(&self) -> UsageReport {
+        let mut usage = self.project_usage.excluding(&self.id);
+        usage.extend(&self.token_usage_by_model);
+        usage
+    }
+
+    /// Returns the spending limit this thread has reached, if any, in which
+    /// case it won't send more requests.
+    pub fn exceeded_budget(&self, cx: &App) -> Option<BudgetExceeded> {
+        BudgetExceeded::check(
+            &self.usage_towards_limits(),
+            &self.project_usage(),
+            &AssistantSettings::get_global(cx).usage,
+        )
+    }
+
     pub fn token_usage_up_to_message(&self, message_id: MessageId) -> TotalTokenUsage {
         let Some(model) = self.configured_model.as_ref() else {
             return TotalTokenUsage::default();
@@ -2752,6 +3286,8 @@ pub enum ThreadEvent {
     ToolConfirmationNeeded,
     CancelEditing,
     CompletionCanceled,
+    /// The thread's older messages were summarized to fit in the model's context window.
+    ContextCompacted,
 }
 
 impl EventEmitter<ThreadEvent> for Thread {}
@@ -2762,11 +3298,75 @@ struct PendingCompletion {
     _task: Task<()>,
 }
 
+/// Builds a request that asks a model to summarize `transcript`, along with the summary of
+/// the conversation before it.
+fn compaction_request(
+    earlier_summary: Option<&str>,
+    transcript: &str,
+    temperature: Option<f32>,
+) -> LanguageModelRequest {
+    let mut conversation = String::new();
+    if let Some(earlier_summary) = earlier_summary {
+        writeln!(
+            &mut conversation,
+            "## Summary of the earlier conversation\n\n{earlier_summary}\n"
+        )
+        .ok();
+    }
+    conversation.push_str(transcript);
+
+    let instructions = "The conversation above is the beginning of a long session between a user and an AI agent \
+        working in their project, and it no longer fits in the agent's context window. \
+        Summarize it so that the agent can continue the work from your summary alone. Preserve:\n\
+        - The user's requests, and any constraints or preferences they stated\n\
+        - The decisions that were made, and why\n\
+        - The paths of the files that were read, created or changed, and what changed in them\n\
+        - The results of tool calls that are still relevant, such as errors, failing tests and the locations of relevant code\n\
+        - Work that is in progress or left to do\n\
+        Leave out anything that no longer matters. Format the summary in Markdown, without any preamble.";
+
+    LanguageModelRequest {
+        thread_id: None,
+        prompt_id: None,
+        mode: None,
+        messages: vec![LanguageModelRequestMessage {
+            role: Role::User,
+            content: vec![MessageContent::Text(format!(
+                "<conversation>\n{conversation}</conversation>\n\n{instructions}"
+            ))],
+            cache: false,
+        }],
+        tools: Vec::new(),
+        tool_choice: None,
+        stop: Vec::new(),
+        temperature,
+    }
+}
+
+/// Joins the `sections` of a transcript into as few transcripts of at most `max_chars` as
+/// possible, truncating sections that don't fit in one on their own.
+fn compaction_transcripts(sections: Vec<String>, max_chars: usize) -> Vec<String> {
+    let max_chars = max_chars.max(COMPACTION_MAX_ITEM_CHARS);
+    let mut transcripts = Vec::new();
+    let mut transcript = String::new();
+    for section in sections {
+        let section = truncate_and_trailoff(&section, max_chars);
+        if !transcript.is_empty() && transcript.len() + section.len() > max_chars {
+            transcripts.push(std::mem::take(&mut transcript));
+        }
+        transcript.push_str(&section);
+    }
+    if !transcript.is_empty() {
+        transcripts.push(transcript);
+    }
+    transcripts
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::{ThreadStore, context::load_context, context_store::ContextStore, thread_store};
-    use assistant_settings::{AssistantSettings, LanguageModelParameters};
+    use assistant_settings::{AssistantSettings, LanguageModelParameters, UsageSettings};
     use assistant_tool::ToolRegistry;
     use editor::EditorSettings;
     use gpui::TestAppContext;
@@ -3090,6 +3690,567 @@ fn main() {{
         );
     }
 
+    #[gpui::test]
+    async fn test_fork_thread(cx: &mut TestAppContext) {
+        init_test_settings(cx);
+
+        let project = create_test_project(cx, json!({"code.rs": "fn main() {}"})).await;
+        let (_, thread_store, thread, _context_store, _model) =
+            setup_test_environment(cx, project.clone()).await;
+
+        let fork_message_id = thread.update(cx, |thread, cx| {
+            thread.insert_user_message(
+                "Rename main",
+                ContextLoadResult::default(),
+                None,
+                Vec::new(),
+                cx,
+            );
+            thread.insert_assistant_message(
+                vec![MessageSegment::Text("Renamed main to start".into())],
+                cx,
+            )
+        });
+        let later_message_id = thread.update(cx, |thread, cx| {
+            thread.insert_user_message(
+                "Now rename it back",
+                ContextLoadResult::default(),
+                None,
+                Vec::new(),
+                cx,
+            )
+        });
+        let git_checkpoint = project
+            .update(cx, |project, cx| {
+                project
+                    .git_store()
+                    .update(cx, |git_store, cx| git_store.checkpoint(cx))
+            })
+            .await
+            .unwrap();
+        thread.update(cx, |thread, cx| {
+            for message_id in [fork_message_id, later_message_id] {
+                thread.insert_checkpoint(
+                    ThreadCheckpoint {
+                        message_id,
+                        git_checkpoint: git_checkpoint.clone(),
+                    },
+                    cx,
+                );
+            }
+        });
+
+        let forked = thread_store
+            .update(cx, |thread_store, cx| {
+                thread_store.fork_thread(&thread, fork_message_id, cx)
+            })
+            .await
+            .unwrap();
+
+        let parent_id = thread.read_with(cx, |thread, _| thread.id().clone());
+        let expected_parent = ThreadParent {
+            thread_id: parent_id,
+            message_id: fork_message_id,
+        };
+        let forked_id = forked.read_with(cx, |forked, _| {
+            assert_eq!(forked.parent(), Some(&expected_parent));
+            assert_eq!(
+                forked
+                    .messages()
+                    .map(|message| message.to_string())
+                    .collect::<Vec<_>>(),
+                vec!["Rename main", "Renamed main to start"]
+            );
+            // Only the checkpoints of the copied messages are copied.
+            assert!(forked.checkpoint_for_message(fork_message_id).is_some());
+            assert!(forked.checkpoint_for_message(later_message_id).is_none());
+            forked.id().clone()
+        });
+
+        // The fork is saved to the history, along with the thread it was forked from.
+        thread_store.read_with(cx, |thread_store, _| {
+            let saved_fork = thread_store
+                .unordered_threads()
+                .find(|thread| thread.id == forked_id)
+                .expect("the fork should be saved");
+            assert_eq!(saved_fork.parent.as_ref(), Some(&expected_parent));
+        });
+
+        // The parent keeps its later messages, and new messages in the fork
+        // follow the copied ones.
+        assert_eq!(
+            thread.read_with(cx, |thread, _| thread.messages().count()),
+            3
+        );
+        let new_message_id = forked.update(cx, |forked, cx| {
+            forked.insert_user_message(
+                "Rename it to run instead",
+                ContextLoadResult::default(),
+                None,
+                Vec::new(),
+                cx,
+            )
+        });
+        assert!(new_message_id > fork_message_id);
+        assert_eq!(
+            forked.read_with(cx, |forked, _| forked.messages().count()),
+            3
+        );
+    }
+
+    #[gpui::test]
+    async fn test_context_compaction(cx: &mut TestAppContext) {
+        init_test_settings(cx);
+
+        let project = create_test_project(cx, json!({"code.rs": "fn main() {}"})).await;
+        let (_, _thread_store, thread, _context_store, model) =
+            setup_test_environment(cx, project.clone()).await;
+        let fake_model = model.as_fake();
+
+        thread.update(cx, |thread, cx| {
+            thread.set_configured_model(
+                Some(ConfiguredModel {
+                    provider: Arc::new(FakeLanguageModelProvider),
+                    model: model.clone(),
+                }),
+                cx,
+            );
+            for ix in 0..3 {
+                thread.insert_user_message(
+                    format!("Request {ix}"),
+                    ContextLoadResult::default(),
+                    None,
+                    Vec::new(),
+                    cx,
+                );
+                thread.insert_assistant_message(
+                    vec![MessageSegment::Text(format!("Response {ix}"))],
+                    cx,
+                );
+            }
+            thread.insert_user_message(
+                "Request 3",
+                ContextLoadResult::default(),
+                None,
+                Vec::new(),
+                cx,
+            );
+        });
+
+        // Below the threshold, the thread is sent as it is.
+        thread.update(cx, |thread, cx| {
+            thread.update_token_usage_at_last_message(TokenUsage {
+                input_tokens: 100_000,
+                ..Default::default()
+            });
+            assert_eq!(thread.compaction_boundary(cx), None);
+        });
+
+        thread.update(cx, |thread, cx| {
+            thread.update_token_usage_at_last_message(TokenUsage {
+                input_tokens: model.max_token_count() as u32 - 1000,
+                ..Default::default()
+            });
+            thread.send_to_model(model.clone(), None, cx);
+        });
+        cx.run_until_parked();
+
+        // The four most recent messages are kept, starting with "Response 1".
+        let compaction_request = fake_model.pending_completions().pop().unwrap();
+        let compaction_prompt = compaction_request.messages[0].string_contents();
+        assert!(compaction_prompt.contains("Request 0"));
+        assert!(compaction_prompt.contains("Request 1"));
+        assert!(!compaction_prompt.contains("Response 1"));
+        assert!(thread.read_with(cx, |thread, _| thread.is_generating()));
+
+        fake_model.stream_last_completion_response("The user made requests 0 and 1.".into());
+        let compaction_usage = TokenUsage {
+            input_tokens: 5000,
+            output_tokens: 100,
+            ..Default::default()
+        };
+        fake_model.send_last_completion_stream_event(LanguageModelCompletionEvent::UsageUpdate(
+            compaction_usage,
+        ));
+        fake_model.end_last_completion_stream();
+        cx.run_until_parked();
+
+        let compacted_message_id = thread.read_with(cx, |thread, _| thread.messages[2].id);
+        thread.read_with(cx, |thread, _| {
+            // The summary's tokens count towards the thread's usage.
+            assert_eq!(thread.token_usage_by_model().total(), compaction_usage);
+            assert_eq!(
+                thread.compaction(),
+                Some(&ThreadCompaction {
+                    message_id: compacted_message_id,
+                    summary: "The user made requests 0 and 1.".into(),
+                })
+            );
+            // The full history stays in the thread.
+            assert_eq!(thread.messages().count(), 7);
+        });
+
+        let request = fake_model.pending_completions().pop().unwrap();
+        let messages = request
+            .messages
+            .iter()
+            .filter(|message| message.role != Role::System)
+            .map(|message| message.string_contents())
+            .collect::<Vec<_>>();
+        assert_eq!(messages.len(), 5);
+        assert!(messages[0].contains("The user made requests 0 and 1."));
+        assert_eq!(
+            messages[1..],
+            ["Response 1", "Request 2", "Response 2", "Request 3"]
+        );
+
+        // Editing a summarized message discards the summary.
+        thread.update(cx, |thread, cx| {
+            thread.edit_message(
+                compacted_message_id,
+                Role::User,
+                vec![MessageSegment::Text("Request 1, edited".into())],
+                None,
+                cx,
+            );
+            assert_eq!(thread.compaction(), None);
+        });
+    }
+
+    #[gpui::test]
+    async fn test_usage_limit(cx: &mut TestAppContext) {
+        init_test_settings(cx);
+
+        let project = create_test_project(cx, json!({"code.rs": "fn main() {}"})).await;
+        let (_, _thread_store, thread, _context_store, model) =
+            setup_test_environment(cx, project.clone()).await;
+        let fake_model = model.as_fake();
+
+        cx.update(|cx| {
+            AssistantSettings::override_global(
+                AssistantSettings {
+                    usage: UsageSettings {
+                        max_tokens_per_thread: Some(1000),
+                        ..Default::default()
+                    },
+                    ..AssistantSettings::get_global(cx).clone()
+                },
+                cx,
+            );
+        });
+
+        let provider_id = model.provider_id().0.to_string();
+        let model_id = model.id().0.to_string();
+        thread.update(cx, |thread, cx| {
+            thread.token_usage_by_model.add(
+                &provider_id,
+                &model_id,
+                TokenUsage {
+                    input_tokens: 999,
+                    ..Default::default()
+                },
+            );
+            thread.insert_user_message("Hello", ContextLoadResult::default(), None, Vec::new(), cx);
+            thread.send_to_model(model.clone(), None, cx);
+        });
+        cx.run_until_parked();
+        assert_eq!(fake_model.pending_completions().len(), 1);
+        fake_model.end_last_completion_stream();
+        cx.run_until_parked();
+
+        // Once the limit is reached, the thread stops sending requests.
+        thread.update(cx, |thread, cx| {
+            thread.token_usage_by_model.add(
+                &provider_id,
+                &model_id,
+                TokenUsage {
+                    output_tokens: 1,
+                    ..Default::default()
+                },
+            );
+            assert_eq!(
+                thread.exceeded_budget(cx),
+                Some(BudgetExceeded::ThreadTokens { limit: 1000 })
+            );
+            thread.insert_user_message("Again", ContextLoadResult::default(), None, Vec::new(), cx);
+            thread.send_to_model(model.clone(), None, cx);
+        });
+        cx.run_until_parked();
+        assert!(fake_model.pending_completions().is_empty());
+        assert!(!thread.read_with(cx, |thread, _| thread.is_generating()));
+    }
+
+    #[gpui::test]
+    async fn test_forks_count_parent_usage_towards_thread_limits(cx: &mut TestAppContext) {
+        init_test_settings(cx);
+
+        let project = create_test_project(cx, json!({"code.rs": "fn main() {}"})).await;
+        let (_, thread_store, thread, _context_store, model) =
+            setup_test_environment(cx, project.clone()).await;
+
+        cx.update(|cx| {
+            AssistantSettings::override_global(
+                AssistantSettings {
+                    usage: UsageSettings {
+                        max_tokens_per_thread: Some(1000),
+                        ..Default::default()
+                    },
+                    ..AssistantSettings::get_global(cx).clone()
+                },
+                cx,
+            );
+        });
+
+        let provider_id = model.provider_id().0.to_string();
+        let model_id = model.id().0.to_string();
+        let message_id = thread.update(cx, |thread, cx| {
+            thread.token_usage_by_model.add(
+                &provider_id,
+                &model_id,
+                TokenUsage {
+                    input_tokens: 1000,
+                    ..Default::default()
+                },
+            );
+            thread.insert_user_message("Hello", ContextLoadResult::default(), None, Vec::new(), cx)
+        });
+        thread_store
+            .update(cx, |thread_store, cx| thread_store.save_thread(&thread, cx))
+            .await
+            .unwrap();
+
+        let forked = thread_store
+            .update(cx, |thread_store, cx| {
+                thread_store.fork_thread(&thread, message_id, cx)
+            })
+            .await
+            .unwrap();
+
+        // Forking doesn't reset the thread's limits, but the parent's tokens
+        // are only counted once towards the project's.
+        forked.read_with(cx, |forked, cx| {
+            assert!(forked.token_usage_by_model().is_empty());
+            assert_eq!(forked.usage_towards_limits().total().input_tokens, 1000);
+            assert_eq!(
+                forked.exceeded_budget(cx),
+                Some(BudgetExceeded::ThreadTokens { limit: 1000 })
+            );
+            assert_eq!(forked.project_usage().total().input_tokens, 1000);
+        });
+
+        // Forks of forks inherit the usage of every thread before them.
+        let forked_again = thread_store
+            .update(cx, |thread_store, cx| {
+                thread_store.fork_thread(&forked, message_id, cx)
+            })
+            .await
+            .unwrap();
+        forked_again.read_with(cx, |forked_again, _| {
+            assert_eq!(
+                forked_again.usage_towards_limits().total().input_tokens,
+                1000
+            );
+        });
+    }
+
+    #[gpui::test]
+    async fn test_export_thread(cx: &mut TestAppContext) {
+        init_test_settings(cx);
+
+        let project = create_test_project(cx, json!({"code.rs": "fn main() {}"})).await;
+        let (_, thread_store, _thread, _context_store, _model) =
+            setup_test_environment(cx, project.clone()).await;
+
+        let exported = json!({
+            "version": SerializedThread::VERSION,
+            "summary": "Checking the tests",
+            "updated_at": "2025-05-01T12:00:00Z",
+            "messages": [
+                {
+                    "id": 0,
+                    "role": "user",
+                    "segments": [{"type": "text", "text": "Do the tests pass?"}]
+                },
+                {
+                    "id": 1,
+                    "role": "assistant",
+                    "segments": [{"type": "text", "text": "Let me run them."}],
+                    "tool_uses": [
+                        {"id": "tool_1", "name": "terminal", "input": {"command": "cargo test"}}
+                    ],
+                    "tool_results": [
+                        {"tool_use_id": "tool_1", "is_error": false, "content": "test result: ok", "output": null}
+                    ]
+                }
+            ]
+        });
+        let serialized = SerializedThread::from_json(exported.to_string().as_bytes()).unwrap();
+        let thread = thread_store.update(cx, |thread_store, cx| {
+            thread_store.create_thread_from_serialized(serialized, cx)
+        });
+
+        let markdown = thread.read_with(cx, |thread, cx| thread.to_markdown(cx).unwrap());
+        assert_eq!(
+            markdown,
+            indoc::indoc! {r#"
+                # Checking the tests
+
+                ## User
+
+                Do the tests pass?
+
+                ## Agent
+
+                Let me run them.
+
+                <details>
+                <summary>Tool Call: terminal (tool_1)</summary>
+
+                ```json
+                {
+                  "command": "cargo test"
+                }
+                ```
+
+                **Tool Results**
+
+                test result: ok
+
+                </details>
+
+            "#}
+        );
+
+        // Exported JSON can be read back in.
+        let json = thread
+            .update(cx, |thread, cx| thread.serialize(cx))
+            .await
+            .unwrap()
+            .to_json()
+            .unwrap();
+        let reimported = SerializedThread::from_json(json.as_bytes()).unwrap();
+        assert_eq!(reimported.version, SerializedThread::VERSION);
+        assert_eq!(reimported.summary, "Checking the tests");
+        assert_eq!(reimported.messages.len(), 2);
+        assert_eq!(reimported.messages[1].tool_results.len(), 1);
+
+        // Imported threads count the tokens they used before they were exported
+        // towards their own limits, but not towards the project's.
+        let mut exported = exported;
+        exported["cumulative_token_usage"] = json!({"input_tokens": 1000});
+        exported["token_usage_by_model"] = json!([{
+            "provider": "anthropic",
+            "model": "claude-3-7-sonnet-latest",
+            "usage": {"input_tokens": 1000}
+        }]);
+        let imported_id = thread_store
+            .update(cx, |thread_store, cx| {
+                thread_store.import_thread(exported.to_string().as_bytes(), cx)
+            })
+            .await
+            .unwrap();
+        thread_store.read_with(cx, |thread_store, _| {
+            let imported = thread_store
+                .unordered_threads()
+                .find(|thread| thread.id == imported_id)
+                .expect("the imported thread should be saved");
+            assert_eq!(imported.summary, "Checking the tests");
+            assert!(imported.token_usage_by_model.is_empty());
+            assert!(thread_store.total_usage(None).is_empty());
+        });
+        let database = cx
+            .update(thread_store::ThreadsDatabase::global_future)
+            .await
+            .unwrap();
+        let imported = database
+            .try_find_thread(imported_id)
+            .await
+            .unwrap()
+            .expect("the imported thread should be saved");
+        assert_eq!(imported.inherited_token_usage.total().input_tokens, 1000);
+    }
+
+    #[gpui::test]
+    async fn test_tool_permission_log_is_saved(cx: &mut TestAppContext) {
+        init_test_settings(cx);
+
+        let project = create_test_project(cx, json!({"code.rs": "fn main() {}"})).await;
+        let (_, thread_store, thread, _context_store, _model) =
+            setup_test_environment(cx, project.clone()).await;
+
+        thread.update(cx, |thread, _| {
+            thread.log_tool_permission_decision(
+                LanguageModelToolUseId::from("tool_1"),
+                "terminal".into(),
+                ToolPermissionDecision {
+                    permission: ToolPermission::Ask,
+                    reason: "tool requires confirmation".into(),
+                },
+            );
+            thread.log_tool_permission_decision(
+                LanguageModelToolUseId::from("tool_1"),
+                "terminal".into(),
+                ToolPermissionDecision {
+                    permission: ToolPermission::Deny,
+                    reason: "denied by the user".into(),
+                },
+            );
+        });
+
+        let json = thread
+            .update(cx, |thread, cx| thread.serialize(cx))
+            .await
+            .unwrap()
+            .to_json()
+            .unwrap();
+        let serialized = SerializedThread::from_json(json.as_bytes()).unwrap();
+        let reloaded = thread_store.update(cx, |thread_store, cx| {
+            cx.new(|cx| {
+                Thread::deserialize(
+                    ThreadId::new(),
+                    serialized,
+                    project.clone(),
+                    thread_store.tools(),
+                    Arc::new(PromptBuilder::new(None).unwrap()),
+                    SharedProjectContext::default(),
+                    None,
+                    cx,
+                )
+            })
+        });
+        reloaded.read_with(cx, |reloaded, _| {
+            let decisions = reloaded
+                .tool_permission_log()
+                .iter()
+                .map(|entry| {
+                    (
+                        entry.tool_use_id.to_string(),
+                        entry.tool_name.to_string(),
+                        entry.decision.permission,
+                        entry.decision.reason.to_string(),
+                    )
+                })
+                .collect::<Vec<_>>();
+            assert_eq!(
+                decisions,
+                [
+                    (
+                        "tool_1".to_string(),
+                        "terminal".to_string(),
+                        ToolPermission::Ask,
+                        "tool requires confirmation".to_string()
+                    ),
+                    (
+                        "tool_1".to_string(),
+                        "terminal".to_string(),
+                        ToolPermission::Deny,
+                        "denied by the user".to_string()
+                    ),
+                ]
+            );
+        });
+    }
+
     #[gpui::test]
     async fn test_stale_buffer_notification(cx: &mut TestAppContext) {
         init_test_settings(cx);
@@ -3322,13 +4483,22 @@ fn main() {{
         cx.run_until_parked();
         fake_model.stream_last_completion_response("Brief".into());
         fake_model.stream_last_completion_response(" Introduction".into());
+        let summary_usage = TokenUsage {
+            input_tokens: 200,
+            output_tokens: 3,
+            ..Default::default()
+        };
+        fake_model.send_last_completion_stream_event(LanguageModelCompletionEvent::UsageUpdate(
+            summary_usage,
+        ));
         fake_model.end_last_completion_stream();
         cx.run_until_parked();
 
-        // Summary should be set
+        // Summary should be set, and its tokens counted
         thread.read_with(cx, |thread, _| {
             assert!(matches!(thread.summary(), ThreadSummary::Ready(_)));
             assert_eq!(thread.summary().or_default(), "Brief Introduction");
+            assert_eq!(thread.token_usage_by_model().total(), summary_usage);
         });
 
         // Now we should be able to set a summary
@@ -3454,6 +4624,35 @@ fn main() {{
         });
     }
 
+    #[test]
+    fn test_compaction_transcripts_fit_the_summarizer() {
+        let sections = vec![
+            "a".repeat(3000),
+            "b".repeat(3000),
+            "c".repeat(1000),
+            "d".repeat(10_000),
+        ];
+        let transcripts = compaction_transcripts(sections, 5000);
+        assert_eq!(transcripts.len(), 3);
+        assert!(
+            transcripts
+                .iter()
+                .all(|transcript| transcript.len() <= 5000 + '…'.len_utf8())
+        );
+        assert_eq!(transcripts[0], "a".repeat(3000));
+        assert_eq!(transcripts[1], "b".repeat(3000) + &"c".repeat(1000));
+        // Sections that are too long on their own are truncated.
+        assert!(transcripts[2].starts_with(&"d".repeat(5000)));
+        assert!(transcripts[2].ends_with('…'));
+
+        // Earlier summaries are carried into the next request.
+        let request = compaction_request(Some("Earlier work"), &transcripts[1], None);
+        let prompt = request.messages[0].string_contents();
+        assert!(prompt.contains("Earlier work"));
+        assert!(prompt.contains("bbb"));
+        assert!(!prompt.contains("aaa"));
+    }
+
     fn simulate_successful_response(fake_model: &FakeLanguageModel, cx: &mut TestAppContext) {
         cx.run_until_parked();
         fake_model.stream_last_completion_response("Assistant response".into());
@@ -3469,7 +4668,7 @@ fn main() {{
             Project::init_settings(cx);
             AssistantSettings::register(cx);
             prompt_store::init(cx);
-            thread_store::init(cx);
+            thread_store::init_test(cx);
             workspace::init_settings(cx);
             language_model::init_settings(cx);
             ThemeSettings::register(cx);
@@ -3,6 +3,7 @@ use std::ops::Range;
 use std::sync::Arc;
 
 use chrono::{Datelike as _, Local, NaiveDate, TimeDelta};
+use collections::HashMap;
 use editor::{Editor, EditorEvent};
 use fuzzy::{StringMatch, StringMatchCandidate};
 use gpui::{
@@ -17,6 +18,8 @@ use ui::{
 use util::ResultExt;
 
 use crate::history_store::{HistoryEntry, HistoryStore};
+use crate::thread::ThreadId;
+use crate::thread_store::ThreadStore;
 use crate::{AgentPanel, RemoveSelectedThread};
 
 pub struct ThreadHistory {
@@ -454,6 +457,11 @@ impl ThreadHistory {
         cx: &mut Context<Self>,
     ) -> Vec<AnyElement> {
         let range_start = range.start;
+        let thread_summaries = self
+            .agent_panel
+            .upgrade()
+            .map(|agent_panel| thread_summaries(agent_panel.read(cx).thread_store().read(cx)))
+            .unwrap_or_default();
 
         match &self.search_state {
             SearchState::Empty => self
@@ -461,9 +469,15 @@ impl ThreadHistory {
                 .get(range)
                 .iter()
                 .flat_map(|items| {
-                    items
-                        .iter()
-                        .map(|item| self.render_list_item(item.entry_index(), item, vec![], cx))
+                    items.iter().map(|item| {
+                        self.render_list_item(
+                            item.entry_index(),
+                            item,
+                            vec![],
+                            &thread_summaries,
+                            cx,
+                        )
+                    })
                 })
                 .collect(),
             SearchState::Searched { matches, .. } => matches[range]
@@ -477,6 +491,7 @@ impl ThreadHistory {
                             format: EntryTimeFormat::DateAndTime,
                         },
                         m.positions.clone(),
+                        &thread_summaries,
                         cx,
                     )
                 })
@@ -492,6 +507,7 @@ impl ThreadHistory {
         list_entry_ix: Option<usize>,
         item: &ListItemType,
         highlight_positions: Vec<usize>,
+        thread_summaries: &HashMap<ThreadId, SharedString>,
         cx: &Context<Self>,
     ) -> AnyElement {
         match item {
@@ -502,6 +518,7 @@ impl ThreadHistory {
                     .child(
                         HistoryEntryElement::new(entry.clone(), self.agent_panel.clone())
                             .highlight_positions(highlight_positions)
+                            .thread_summaries(thread_summaries)
                             .timestamp_format(*format)
                             .selected(list_entry_ix == Some(self.selected_index))
                             .hovered(list_entry_ix == self.hovered_index)
@@ -611,6 +628,15 @@ impl Render for ThreadHistory {
     }
 }
 
+/// The summaries of the threads in `thread_store`, by ID, so that the entries of forks
+/// can show the thread they were forked from.
+pub fn thread_summaries(thread_store: &ThreadStore) -> HashMap<ThreadId, SharedString> {
+    thread_store
+        .unordered_threads()
+        .map(|thread| (thread.id.clone(), thread.summary.clone()))
+        .collect()
+}
+
 #[derive(IntoElement)]
 pub struct HistoryEntryElement {
     entry: HistoryEntry,
@@ -619,6 +645,8 @@ pub struct HistoryEntryElement {
     hovered: bool,
     highlight_positions: Vec<usize>,
     timestamp_format: EntryTimeFormat,
+    /// For forks, the summary of the thread they were forked from, unless it was deleted.
+    forked_from: Option<Option<SharedString>>,
     on_hover: Box<dyn Fn(&bool, &mut Window, &mut App) + 'static>,
 }
 
@@ -631,6 +659,7 @@ impl HistoryEntryElement {
             hovered: false,
             highlight_positions: vec![],
             timestamp_format: EntryTimeFormat::DateAndTime,
+            forked_from: None,
             on_hover: Box::new(|_, _, _| {}),
         }
     }
@@ -659,6 +688,18 @@ impl HistoryEntryElement {
         self.timestamp_format = format;
         self
     }
+
+    /// Looks up the thread that this entry was forked from in the summaries returned
+    /// by [`thread_summaries`].
+    pub fn thread_summaries(mut self, thread_summaries: &HashMap<ThreadId, SharedString>) -> Self {
+        if let HistoryEntry::Thread(thread) = &self.entry {
+            self.forked_from = thread
+                .parent
+                .as_ref()
+                .map(|parent| thread_summaries.get(&parent.thread_id).cloned());
+        }
+        self
+    }
 }
 
 impl RenderOnce for HistoryEntryElement {
@@ -680,6 +721,8 @@ impl RenderOnce for HistoryEntryElement {
             self.timestamp_format
                 .format_timestamp(&self.agent_panel, timestamp, cx);
 
+        let forked_from = self.forked_from;
+
         ListItem::new(SharedString::from(id))
             .rounded()
             .toggle_state(self.selected)
@@ -690,9 +733,21 @@ impl RenderOnce for HistoryEntryElement {
                     .gap_2()
                     .justify_between()
                     .child(
-                        HighlightedLabel::new(summary, self.highlight_positions)
-                            .size(LabelSize::Small)
-                            .truncate(),
+                        h_flex()
+                            .gap_1()
+                            .min_w_0()
+                            .when(forked_from.is_some(), |this| {
+                                this.child(
+                                    Icon::new(IconName::GitBranchSmall)
+                                        .size(IconSize::XSmall)
+                                        .color(Color::Muted),
+                                )
+                            })
+                            .child(
+                                HighlightedLabel::new(summary, self.highlight_positions)
+                                    .size(LabelSize::Small)
+                                    .truncate(),
+                            ),
                     )
                     .child(
                         Label::new(thread_timestamp)
@@ -700,6 +755,13 @@ impl RenderOnce for HistoryEntryElement {
                             .size(LabelSize::XSmall),
                     ),
             )
+            .when_some(forked_from, |this, parent_summary| {
+                let tooltip = match parent_summary {
+                    Some(parent_summary) => format!("Forked from \"{parent_summary}\""),
+                    None => "Forked from a deleted thread".to_string(),
+                };
+                this.tooltip(Tooltip::text(tooltip))
+            })
             .on_hover(self.on_hover)
             .end_slot::<IconButton>(if self.hovered || self.selected {
                 Some(
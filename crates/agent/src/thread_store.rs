@@ -8,7 +8,7 @@ use anyhow::{Context as _, Result, anyhow};
 use assistant_settings::{AgentProfile, AgentProfileId, AssistantSettings, CompletionMode};
 use assistant_tool::{ToolId, ToolSource, ToolWorkingSet};
 use chrono::{DateTime, Utc};
-use collections::HashMap;
+use collections::{HashMap, HashSet};
 use context_server::ContextServerId;
 use futures::channel::{mpsc, oneshot};
 use futures::future::{self, BoxFuture, Shared};
@@ -33,8 +33,11 @@ use util::ResultExt as _;
 
 use crate::context_server_tool::ContextServerTool;
 use crate::thread::{
-    DetailedSummaryState, ExceededWindowError, MessageId, ProjectSnapshot, Thread, ThreadId,
+    DetailedSummaryState, ExceededWindowError, MessageId, ProjectSnapshot, Thread,
+    ThreadCompaction, ThreadId, ThreadParent,
 };
+use crate::token_accounting::{SharedProjectUsage, UsageReport};
+use crate::tool_permissions::ToolPermissionLogEntry;
 
 const RULES_FILE_NAMES: [&'static str; 6] = [
     ".rules",
@@ -46,7 +49,19 @@ const RULES_FILE_NAMES: [&'static str; 6] = [
 ];
 
 pub fn init(cx: &mut App) {
-    ThreadsDatabase::init(cx);
+    ThreadsDatabase::init(paths::data_dir().join("threads/threads-db.1.mdb"), cx);
+}
+
+/// Like [`init`], but keeps the threads in a temporary directory that's
+/// removed with the app, so that tests don't touch the user's threads.
+#[cfg(test)]
+pub(crate) fn init_test(cx: &mut App) {
+    struct TestThreadsDirectory(tempfile::TempDir);
+    impl Global for TestThreadsDirectory {}
+
+    let directory = tempfile::tempdir().unwrap();
+    ThreadsDatabase::init(directory.path().join("threads-db.1.mdb"), cx);
+    cx.set_global(TestThreadsDirectory(directory));
 }
 
 /// A system prompt shared by all threads created by this ThreadStore
@@ -69,6 +84,7 @@ pub struct ThreadStore {
     context_server_tool_ids: HashMap<ContextServerId, Vec<ToolId>>,
     threads: Vec<SerializedThreadMetadata>,
     project_context: SharedProjectContext,
+    project_usage: SharedProjectUsage,
     reload_system_prompt_tx: mpsc::Sender<()>,
     _reload_system_prompt_task: Task<()>,
     _subscriptions: Vec<Subscription>,
@@ -160,6 +176,7 @@ impl ThreadStore {
             context_server_tool_ids: HashMap::default(),
             threads: Vec::new(),
             project_context: SharedProjectContext::default(),
+            project_usage: SharedProjectUsage::default(),
             reload_system_prompt_tx,
             _reload_system_prompt_task: reload_system_prompt_task,
             _subscriptions: subscriptions,
@@ -174,11 +191,12 @@ impl ThreadStore {
         &mut self,
         _project: Entity<Project>,
         event: &project::Event,
-        _cx: &mut Context<Self>,
+        cx: &mut Context<Self>,
     ) {
         match event {
             project::Event::WorktreeAdded(_) | project::Event::WorktreeRemoved(_) => {
                 self.enqueue_system_prompt_reload();
+                self.update_project_usage(cx);
             }
             project::Event::WorktreeUpdatedEntries(_, items) => {
                 if items.iter().any(|(path, _, _)| {
@@ -374,15 +392,51 @@ impl ThreadStore {
         threads
     }
 
+    /// Returns the tokens used by all saved threads, other than `except`.
+    pub fn total_usage(&self, except: Option<&ThreadId>) -> UsageReport {
+        let mut usage = UsageReport::default();
+        for thread in &self.threads {
+            if Some(&thread.id) != except {
+                usage.extend(&thread.token_usage_by_model);
+            }
+        }
+        usage
+    }
+
+    /// Recomputes the usage of the saved threads that were started in one of
+    /// the project's current worktrees.
+    fn update_project_usage(&self, cx: &App) {
+        let worktree_paths = self
+            .project
+            .read(cx)
+            .visible_worktrees(cx)
+            .map(|worktree| worktree.read(cx).abs_path().to_string_lossy().to_string())
+            .collect::<HashSet<_>>();
+        self.project_usage.set(
+            self.threads
+                .iter()
+                .filter(|thread| {
+                    thread
+                        .worktree_paths
+                        .iter()
+                        .any(|path| worktree_paths.contains(path))
+                })
+                .map(|thread| (thread.id.clone(), thread.token_usage_by_model.clone()))
+                .collect(),
+        );
+    }
+
     pub fn create_thread(&mut self, cx: &mut Context<Self>) -> Entity<Thread> {
         cx.new(|cx| {
-            Thread::new(
+            let mut thread = Thread::new(
                 self.project.clone(),
                 self.tools.clone(),
                 self.prompt_builder.clone(),
                 self.project_context.clone(),
                 cx,
-            )
+            );
+            thread.set_project_usage(self.project_usage.clone());
+            thread
         })
     }
 
@@ -392,7 +446,7 @@ impl ThreadStore {
         cx: &mut Context<Self>,
     ) -> Entity<Thread> {
         cx.new(|cx| {
-            Thread::deserialize(
+            let mut thread = Thread::deserialize(
                 ThreadId::new(),
                 serialized,
                 self.project.clone(),
@@ -401,7 +455,76 @@ impl ThreadStore {
                 self.project_context.clone(),
                 None,
                 cx,
-            )
+            );
+            thread.set_project_usage(self.project_usage.clone());
+            thread
+        })
+    }
+
+    /// Creates a thread that copies `thread` up to and including `message_id`,
+    /// along with its context and checkpoints, and saves it to the history.
+    pub fn fork_thread(
+        &mut self,
+        thread: &Entity<Thread>,
+        message_id: MessageId,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Entity<Thread>>> {
+        if thread.read(cx).is_generating() {
+            return Task::ready(Err(anyhow!("can't fork a thread while it's generating")));
+        }
+        let serialized = thread.update(cx, |thread, cx| thread.serialize(cx));
+        let thread = thread.clone();
+        cx.spawn(async move |this, cx| {
+            let serialized = serialized.await?;
+            let forked_thread = this.update(cx, |this, cx| {
+                let fork_point = thread
+                    .read(cx)
+                    .fork_point(message_id)
+                    .ok_or_else(|| anyhow!("no message with ID {message_id:?} to fork from"))?;
+                anyhow::Ok(cx.new(|cx| {
+                    let mut thread = Thread::forked(
+                        ThreadId::new(),
+                        serialized,
+                        fork_point,
+                        this.project.clone(),
+                        this.tools.clone(),
+                        this.prompt_builder.clone(),
+                        this.project_context.clone(),
+                        cx,
+                    );
+                    thread.set_project_usage(this.project_usage.clone());
+                    thread
+                }))
+            })??;
+            this.update(cx, |this, cx| this.save_thread(&forked_thread, cx))?
+                .await?;
+            Ok(forked_thread)
+        })
+    }
+
+    /// Adds a thread exported with [`SerializedThread::to_json`] to the
+    /// history, under a new ID.
+    pub fn import_thread(&mut self, json: &[u8], cx: &mut Context<Self>) -> Task<Result<ThreadId>> {
+        let mut serialized = match SerializedThread::from_json(json) {
+            Ok(serialized) => serialized,
+            Err(error) => return Task::ready(Err(error.context("invalid thread"))),
+        };
+        // The thread it was forked from isn't in this history.
+        serialized.parent = None;
+        // Its tokens were spent wherever it was exported from, so they count
+        // towards the thread's limits, but not towards this project's.
+        let usage = serialized.token_usage_by_model();
+        serialized.inherited_token_usage.extend(&usage);
+        serialized.cumulative_token_usage = TokenUsage::default();
+        serialized.token_usage_by_model = UsageReport::default();
+
+        let id = ThreadId::new();
+        let database_future = ThreadsDatabase::global_future(cx);
+        cx.spawn(async move |this, cx| {
+            let database = database_future.await.map_err(|err| anyhow!(err))?;
+            database.save_thread(id.clone(), serialized).await?;
+            this.update(cx, |this, cx| this.reload(cx))?.await?;
+            Ok(id)
         })
     }
 
@@ -423,7 +546,7 @@ impl ThreadStore {
 
             let thread = this.update_in(cx, |this, window, cx| {
                 cx.new(|cx| {
-                    Thread::deserialize(
+                    let mut thread = Thread::deserialize(
                         id.clone(),
                         thread,
                         this.project.clone(),
@@ -432,7 +555,9 @@ impl ThreadStore {
                         this.project_context.clone(),
                         Some(window),
                         cx,
-                    )
+                    );
+                    thread.set_project_usage(this.project_usage.clone());
+                    thread
                 })
             })?;
 
@@ -463,6 +588,7 @@ impl ThreadStore {
 
             this.update(cx, |this, cx| {
                 this.threads.retain(|thread| thread.id != id);
+                this.update_project_usage(cx);
                 cx.notify();
             })
         })
@@ -479,6 +605,7 @@ impl ThreadStore {
 
             this.update(cx, |this, cx| {
                 this.threads = threads;
+                this.update_project_usage(cx);
                 cx.notify();
             })
         })
@@ -654,11 +781,20 @@ pub struct SerializedThreadMetadata {
     pub id: ThreadId,
     pub summary: SharedString,
     pub updated_at: DateTime<Utc>,
+    #[serde(default)]
+    pub parent: Option<ThreadParent>,
+    #[serde(default)]
+    pub token_usage_by_model: UsageReport,
+    /// The worktrees that were open when the thread was started.
+    #[serde(default)]
+    pub worktree_paths: Vec<String>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct SerializedThread {
     pub version: String,
+    #[serde(default)]
+    pub parent: Option<ThreadParent>,
     pub summary: SharedString,
     pub updated_at: DateTime<Utc>,
     pub messages: Vec<SerializedMessage>,
@@ -676,6 +812,16 @@ pub struct SerializedThread {
     pub model: Option<SerializedLanguageModel>,
     #[serde(default)]
     pub completion_mode: Option<CompletionMode>,
+    #[serde(default)]
+    pub compaction: Option<ThreadCompaction>,
+    #[serde(default)]
+    pub token_usage_by_model: UsageReport,
+    /// The usage of the thread this one was forked or imported from, which
+    /// counts towards this thread's limits but not its project's.
+    #[serde(default)]
+    pub inherited_token_usage: UsageReport,
+    #[serde(default)]
+    pub tool_permission_log: Vec<ToolPermissionLogEntry>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -687,6 +833,26 @@ pub struct SerializedLanguageModel {
 impl SerializedThread {
     pub const VERSION: &'static str = "0.2.0";
 
+    /// Returns the tokens used by the thread, per model. Threads saved before
+    /// usage was tracked per model attribute all of it to their last model.
+    pub fn token_usage_by_model(&self) -> UsageReport {
+        if !self.token_usage_by_model.is_empty() {
+            return self.token_usage_by_model.clone();
+        }
+
+        let mut usage = UsageReport::default();
+        if let Some(model) = &self.model {
+            usage.add(&model.provider, &model.model, self.cumulative_token_usage);
+        }
+        usage
+    }
+
+    /// Serializes the thread for sharing, in the same versioned format that
+    /// [`SerializedThread::from_json`] reads.
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
     pub fn from_json(json: &[u8]) -> Result<Self> {
         let saved_thread_json = serde_json::from_slice::<serde_json::Value>(json)?;
         match saved_thread_json.get("version") {
@@ -811,6 +977,7 @@ impl LegacySerializedThread {
     pub fn upgrade(self) -> SerializedThread {
         SerializedThread {
             version: SerializedThread::VERSION.to_string(),
+            parent: None,
             summary: self.summary,
             updated_at: self.updated_at,
             messages: self.messages.into_iter().map(|msg| msg.upgrade()).collect(),
@@ -821,6 +988,10 @@ impl LegacySerializedThread {
             exceeded_window_error: None,
             model: None,
             completion_mode: None,
+            compaction: None,
+            token_usage_by_model: UsageReport::default(),
+            inherited_token_usage: UsageReport::default(),
+            tool_permission_log: Vec::new(),
         }
     }
 }
@@ -889,18 +1060,17 @@ impl<'a> heed::BytesDecode<'a> for SerializedThread {
 }
 
 impl ThreadsDatabase {
-    fn global_future(
+    pub(crate) fn global_future(
         cx: &mut App,
     ) -> Shared<BoxFuture<'static, Result<Arc<ThreadsDatabase>, Arc<anyhow::Error>>>> {
         GlobalThreadsDatabase::global(cx).0.clone()
     }
 
-    fn init(cx: &mut App) {
+    fn init(database_path: PathBuf, cx: &mut App) {
         let executor = cx.background_executor().clone();
         let database_future = executor
             .spawn({
                 let executor = executor.clone();
-                let database_path = paths::data_dir().join("threads/threads-db.1.mdb");
                 async move { ThreadsDatabase::new(database_path, executor) }
             })
             .then(|result| future::ready(result.map(Arc::new).map_err(Arc::new)))
@@ -943,8 +1113,16 @@ impl ThreadsDatabase {
             while let Some((key, value)) = iter.next().transpose()? {
                 threads.push(SerializedThreadMetadata {
                     id: key,
+                    token_usage_by_model: value.token_usage_by_model(),
+                    worktree_paths: value
+                        .initial_project_snapshot
+                        .iter()
+                        .flat_map(|snapshot| &snapshot.worktree_snapshots)
+                        .map(|worktree| worktree.worktree_path.clone())
+                        .collect(),
                     summary: value.summary,
                     updated_at: value.updated_at,
+                    parent: value.parent,
                 });
             }
 
@@ -0,0 +1,334 @@
+//! Token usage of agent threads, broken down by model, and the spending limits
+//! configured in `agent.usage`.
+
+use std::cell::RefCell;
+use std::fmt;
+use std::rc::Rc;
+
+use assistant_settings::UsageSettings;
+use collections::HashMap;
+use language_model::TokenUsage;
+use serde::{Deserialize, Serialize};
+
+use crate::thread::ThreadId;
+
+/// The tokens used with a single model, identified the same way as in
+/// [`crate::thread_store::SerializedLanguageModel`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ModelTokenUsage {
+    pub provider: String,
+    pub model: String,
+    pub usage: TokenUsage,
+}
+
+impl ModelTokenUsage {
+    /// Returns the estimated cost of these tokens in US dollars, or `None` if
+    /// no price is configured for the model.
+    pub fn estimated_cost(&self, settings: &UsageSettings) -> Option<f64> {
+        settings
+            .pricing_for(&self.provider, &self.model)
+            .map(|pricing| pricing.cost(&self.usage))
+    }
+}
+
+/// The tokens used by one or more threads, per model.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct UsageReport {
+    models: Vec<ModelTokenUsage>,
+}
+
+impl UsageReport {
+    pub fn is_empty(&self) -> bool {
+        self.models.is_empty()
+    }
+
+    pub fn models(&self) -> &[ModelTokenUsage] {
+        &self.models
+    }
+
+    pub fn add(&mut self, provider: &str, model: &str, usage: TokenUsage) {
+        if usage == TokenUsage::default() {
+            return;
+        }
+
+        if let Some(entry) = self
+            .models
+            .iter_mut()
+            .find(|entry| entry.provider == provider && entry.model == model)
+        {
+            entry.usage = saturating_add(entry.usage, usage);
+        } else {
+            self.models.push(ModelTokenUsage {
+                provider: provider.to_string(),
+                model: model.to_string(),
+                usage,
+            });
+        }
+    }
+
+    pub fn extend(&mut self, other: &UsageReport) {
+        for entry in &other.models {
+            self.add(&entry.provider, &entry.model, entry.usage);
+        }
+    }
+
+    /// Returns the tokens used across all models.
+    pub fn total(&self) -> TokenUsage {
+        self.models
+            .iter()
+            .fold(TokenUsage::default(), |total, entry| {
+                saturating_add(total, entry.usage)
+            })
+    }
+
+    /// Returns the number of tokens used across all models, including cached ones.
+    pub fn total_tokens(&self) -> u64 {
+        self.models
+            .iter()
+            .map(|entry| {
+                entry.usage.input_tokens as u64
+                    + entry.usage.output_tokens as u64
+                    + entry.usage.cache_creation_input_tokens as u64
+                    + entry.usage.cache_read_input_tokens as u64
+            })
+            .sum()
+    }
+
+    /// Returns the estimated cost in US dollars of the tokens used with
+    /// models that have a configured price.
+    pub fn estimated_cost(&self, settings: &UsageSettings) -> f64 {
+        self.models
+            .iter()
+            .filter_map(|entry| entry.estimated_cost(settings))
+            .sum()
+    }
+
+    /// Returns whether some of the tokens were used with models that have no
+    /// configured price, and so aren't part of [`Self::estimated_cost`].
+    pub fn has_unpriced_models(&self, settings: &UsageSettings) -> bool {
+        self.models
+            .iter()
+            .any(|entry| entry.estimated_cost(settings).is_none())
+    }
+}
+
+fn saturating_add(left: TokenUsage, right: TokenUsage) -> TokenUsage {
+    TokenUsage {
+        input_tokens: left.input_tokens.saturating_add(right.input_tokens),
+        output_tokens: left.output_tokens.saturating_add(right.output_tokens),
+        cache_creation_input_tokens: left
+            .cache_creation_input_tokens
+            .saturating_add(right.cache_creation_input_tokens),
+        cache_read_input_tokens: left
+            .cache_read_input_tokens
+            .saturating_add(right.cache_read_input_tokens),
+    }
+}
+
+/// The usage of the saved threads of a project, shared by all threads created
+/// by a [`crate::ThreadStore`] so they can enforce the project's spending limit.
+#[derive(Clone, Default)]
+pub struct SharedProjectUsage(Rc<RefCell<HashMap<ThreadId, UsageReport>>>);
+
+impl SharedProjectUsage {
+    pub(crate) fn set(&self, usage_by_thread: HashMap<ThreadId, UsageReport>) {
+        *self.0.borrow_mut() = usage_by_thread;
+    }
+
+    /// Returns the usage of the project's saved threads other than `thread_id`,
+    /// whose saved usage may be outdated.
+    pub fn excluding(&self, thread_id: &ThreadId) -> UsageReport {
+        let mut report = UsageReport::default();
+        for (id, usage) in self.0.borrow().iter() {
+            if id != thread_id {
+                report.extend(usage);
+            }
+        }
+        report
+    }
+}
+
+/// A spending limit from `agent.usage` that has been reached.
+#[derive(Debug, Clone, PartialEq)]
+pub enum BudgetExceeded {
+    ThreadTokens { limit: u64 },
+    ThreadCost { limit: f64 },
+    ProjectCost { limit: f64 },
+}
+
+impl BudgetExceeded {
+    /// Returns the first limit reached by a thread's usage, or by the usage of
+    /// all threads in its project (which includes the thread's own).
+    pub fn check(
+        thread_usage: &UsageReport,
+        project_usage: &UsageReport,
+        settings: &UsageSettings,
+    ) -> Option<Self> {
+        if let Some(limit) = settings.max_tokens_per_thread {
+            if thread_usage.total_tokens() >= limit {
+                return Some(Self::ThreadTokens { limit });
+            }
+        }
+        if let Some(limit) = settings.max_cost_per_thread {
+            if thread_usage.estimated_cost(settings) >= limit {
+                return Some(Self::ThreadCost { limit });
+            }
+        }
+        if let Some(limit) = settings.max_cost_per_project {
+            if project_usage.estimated_cost(settings) >= limit {
+                return Some(Self::ProjectCost { limit });
+            }
+        }
+        None
+    }
+}
+
+impl fmt::Display for BudgetExceeded {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ThreadTokens { limit } => write!(
+                f,
+                "This thread has used its limit of {limit} tokens. \
+                 Raise `agent.usage.max_tokens_per_thread` to continue."
+            ),
+            Self::ThreadCost { limit } => write!(
+                f,
+                "This thread has reached its estimated cost limit of {}. \
+                 Raise `agent.usage.max_cost_per_thread` to continue.",
+                format_cost(*limit)
+            ),
+            Self::ProjectCost { limit } => write!(
+                f,
+                "The threads of this project have reached their estimated cost limit of {}. \
+                 Raise `agent.usage.max_cost_per_project` to continue.",
+                format_cost(*limit)
+            ),
+        }
+    }
+}
+
+pub fn format_cost(cost: f64) -> String {
+    if cost > 0.0 && cost < 0.01 {
+        "<$0.01".to_string()
+    } else {
+        format!("${cost:.2}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assistant_settings::ModelPricing;
+
+    fn usage(input_tokens: u32, output_tokens: u32) -> TokenUsage {
+        TokenUsage {
+            input_tokens,
+            output_tokens,
+            ..Default::default()
+        }
+    }
+
+    fn settings() -> UsageSettings {
+        UsageSettings {
+            pricing: vec![
+                ModelPricing {
+                    provider: Some("anthropic".into()),
+                    model: None,
+                    input: 3.0,
+                    output: 15.0,
+                    cache_creation_input: None,
+                    cache_read_input: Some(0.3),
+                },
+                ModelPricing {
+                    provider: Some("anthropic".into()),
+                    model: Some("claude-3-5-haiku-latest".into()),
+                    input: 0.8,
+                    output: 4.0,
+                    cache_creation_input: None,
+                    cache_read_input: None,
+                },
+            ],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_usage_report() {
+        let mut report = UsageReport::default();
+        report.add("anthropic", "claude-3-7-sonnet-latest", usage(1000, 100));
+        report.add("openai", "gpt-4o", usage(10, 0));
+        report.add("anthropic", "claude-3-7-sonnet-latest", usage(500, 50));
+        report.add("openai", "gpt-4o", TokenUsage::default());
+
+        assert_eq!(report.models().len(), 2);
+        assert_eq!(report.models()[0].usage, usage(1500, 150));
+        assert_eq!(report.total(), usage(1510, 150));
+        assert_eq!(report.total_tokens(), 1660);
+
+        let mut combined = UsageReport::default();
+        combined.add("openai", "gpt-4o", usage(5, 5));
+        combined.extend(&report);
+        assert_eq!(combined.total(), usage(1515, 155));
+    }
+
+    #[test]
+    fn test_estimated_cost() {
+        let settings = settings();
+        let mut report = UsageReport::default();
+        report.add(
+            "anthropic",
+            "claude-3-7-sonnet-latest",
+            TokenUsage {
+                input_tokens: 1_000_000,
+                output_tokens: 100_000,
+                cache_creation_input_tokens: 1_000_000,
+                cache_read_input_tokens: 1_000_000,
+            },
+        );
+        // The last matching entry wins.
+        report.add("anthropic", "claude-3-5-haiku-latest", usage(1_000_000, 0));
+        assert!(!report.has_unpriced_models(&settings));
+        assert!((report.estimated_cost(&settings) - (3.0 + 1.5 + 3.0 + 0.3 + 0.8)).abs() < 1e-9);
+
+        report.add("openai", "gpt-4o", usage(1_000_000, 0));
+        assert!(report.has_unpriced_models(&settings));
+        assert!((report.estimated_cost(&settings) - 8.6).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_budget_check() {
+        let mut settings = settings();
+        let mut thread = UsageReport::default();
+        thread.add("anthropic", "claude-3-7-sonnet-latest", usage(100_000, 0));
+        let mut project = thread.clone();
+        project.add("anthropic", "claude-3-7-sonnet-latest", usage(900_000, 0));
+
+        assert_eq!(BudgetExceeded::check(&thread, &project, &settings), None);
+
+        settings.max_cost_per_project = Some(3.0);
+        assert_eq!(
+            BudgetExceeded::check(&thread, &project, &settings),
+            Some(BudgetExceeded::ProjectCost { limit: 3.0 })
+        );
+
+        settings.max_cost_per_thread = Some(0.25);
+        assert_eq!(
+            BudgetExceeded::check(&thread, &project, &settings),
+            Some(BudgetExceeded::ThreadCost { limit: 0.25 })
+        );
+
+        settings.max_tokens_per_thread = Some(100_000);
+        assert_eq!(
+            BudgetExceeded::check(&thread, &project, &settings),
+            Some(BudgetExceeded::ThreadTokens { limit: 100_000 })
+        );
+    }
+
+    #[test]
+    fn test_format_cost() {
+        assert_eq!(format_cost(0.0), "$0.00");
+        assert_eq!(format_cost(0.004), "<$0.01");
+        assert_eq!(format_cost(12.346), "$12.35");
+    }
+}
@@ -0,0 +1,84 @@
+use std::sync::Arc;
+
+use assistant_settings::{AssistantSettings, ToolPermission, ToolPermissions};
+use assistant_tool::{Tool, ToolPermissionSubject, ToolSource};
+use chrono::{DateTime, Utc};
+use gpui::{App, SharedString};
+use language_model::LanguageModelToolUseId;
+use serde::{Deserialize, Serialize};
+use settings::Settings as _;
+
+/// The decision on whether a tool call may run.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ToolPermissionDecision {
+    pub permission: ToolPermission,
+    /// Why the permission was chosen, such as the rule that matched the call.
+    pub reason: SharedString,
+}
+
+/// A [`ToolPermissionDecision`] made for a tool use in a thread, either by the permission
+/// rules or by the user when asked for confirmation. Entries are saved with the thread.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ToolPermissionLogEntry {
+    pub tool_use_id: LanguageModelToolUseId,
+    pub tool_name: Arc<str>,
+    pub decision: ToolPermissionDecision,
+    pub decided_at: DateTime<Utc>,
+}
+
+/// Decides whether a call to `tool` with `input` may run, based on the permission
+/// rules of the active profile and the tool's own confirmation behavior.
+pub fn tool_permission(
+    tool: &Arc<dyn Tool>,
+    input: &serde_json::Value,
+    cx: &App,
+) -> ToolPermissionDecision {
+    let settings = AssistantSettings::get_global(cx);
+    let rule_match = settings
+        .active_profile()
+        .and_then(|profile| evaluate_rules(&profile.permissions, tool, input));
+    if let Some(rule_match) = rule_match {
+        return ToolPermissionDecision {
+            permission: rule_match.permission,
+            reason: format!("matched rule {}", rule_match.rule).into(),
+        };
+    }
+
+    if !tool.needs_confirmation(input, cx) {
+        ToolPermissionDecision {
+            permission: ToolPermission::Allow,
+            reason: "tool doesn't require confirmation".into(),
+        }
+    } else if settings.always_allow_tool_actions {
+        ToolPermissionDecision {
+            permission: ToolPermission::Allow,
+            reason: "`always_allow_tool_actions` is enabled".into(),
+        }
+    } else {
+        ToolPermissionDecision {
+            permission: ToolPermission::Ask,
+            reason: "tool requires confirmation".into(),
+        }
+    }
+}
+
+fn evaluate_rules(
+    permissions: &ToolPermissions,
+    tool: &Arc<dyn Tool>,
+    input: &serde_json::Value,
+) -> Option<assistant_settings::ToolPermissionMatch> {
+    if let ToolSource::ContextServer { id } = tool.source() {
+        return permissions.evaluate_context_server_tool(&id, &tool.name());
+    }
+
+    match tool.permission_subject(input) {
+        ToolPermissionSubject::None => None,
+        ToolPermissionSubject::TerminalCommand(command) => {
+            permissions.evaluate_terminal_command(&command)
+        }
+        ToolPermissionSubject::Paths(paths) => {
+            permissions.evaluate_paths(paths.iter().map(String::as_str))
+        }
+        ToolPermissionSubject::FetchHost(host) => permissions.evaluate_fetch_host(&host),
+    }
+}
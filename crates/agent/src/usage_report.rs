@@ -0,0 +1,209 @@
+use assistant_context_editor::humanize_token_count;
+use assistant_settings::{AssistantSettings, UsageSettings};
+use gpui::{App, Entity, FocusHandle, Focusable, ScrollHandle, Subscription};
+use settings::{Settings as _, SettingsStore};
+use ui::prelude::*;
+
+use crate::thread::Thread;
+use crate::thread_store::ThreadStore;
+use crate::token_accounting::{ModelTokenUsage, UsageReport, format_cost};
+
+/// Shows the tokens used, and their estimated cost, by the active thread, by
+/// the threads of the project and by all threads.
+pub struct UsageReportView {
+    thread: Entity<Thread>,
+    thread_store: Entity<ThreadStore>,
+    focus_handle: FocusHandle,
+    scroll_handle: ScrollHandle,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl UsageReportView {
+    pub fn new(
+        thread: Entity<Thread>,
+        thread_store: Entity<ThreadStore>,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let subscriptions = vec![
+            cx.observe(&thread, |_, _, cx| cx.notify()),
+            cx.observe(&thread_store, |_, _, cx| cx.notify()),
+            cx.observe_global::<SettingsStore>(|_, cx| cx.notify()),
+        ];
+
+        Self {
+            thread,
+            thread_store,
+            focus_handle: cx.focus_handle(),
+            scroll_handle: ScrollHandle::new(),
+            _subscriptions: subscriptions,
+        }
+    }
+
+    fn render_section(
+        &self,
+        title: &'static str,
+        description: &'static str,
+        report: &UsageReport,
+        limits: Vec<String>,
+        settings: &UsageSettings,
+        cx: &App,
+    ) -> impl IntoElement {
+        v_flex()
+            .p(DynamicSpacing::Base16.rems(cx))
+            .pr(DynamicSpacing::Base20.rems(cx))
+            .gap_2()
+            .border_b_1()
+            .border_color(cx.theme().colors().border)
+            .child(
+                v_flex()
+                    .gap_0p5()
+                    .child(Headline::new(title))
+                    .child(Label::new(description).color(Color::Muted)),
+            )
+            .map(|parent| {
+                if report.is_empty() {
+                    parent.child(Label::new("No tokens used yet.").color(Color::Muted))
+                } else {
+                    parent
+                        .children(
+                            report
+                                .models()
+                                .iter()
+                                .map(|entry| Self::render_model_usage(entry, settings)),
+                        )
+                        .child(
+                            h_flex()
+                                .justify_between()
+                                .pt_1()
+                                .border_t_1()
+                                .border_color(cx.theme().colors().border_variant)
+                                .child(Label::new(format!(
+                                    "Total: {} tokens",
+                                    humanize_token_count(report.total_tokens() as usize)
+                                )))
+                                .child(Label::new(total_cost_label(report, settings))),
+                        )
+                }
+            })
+            .children(
+                limits
+                    .into_iter()
+                    .map(|limit| Label::new(limit).size(LabelSize::Small).color(Color::Muted)),
+            )
+    }
+
+    fn render_model_usage(entry: &ModelTokenUsage, settings: &UsageSettings) -> impl IntoElement {
+        let usage = &entry.usage;
+        let cached_tokens =
+            usage.cache_creation_input_tokens as usize + usage.cache_read_input_tokens as usize;
+
+        h_flex()
+            .gap_4()
+            .justify_between()
+            .child(
+                v_flex()
+                    .gap_0p5()
+                    .child(Label::new(entry.model.clone()))
+                    .child(
+                        Label::new(format!(
+                            "{} · {} in · {} out · {} cached",
+                            entry.provider,
+                            humanize_token_count(usage.input_tokens as usize),
+                            humanize_token_count(usage.output_tokens as usize),
+                            humanize_token_count(cached_tokens),
+                        ))
+                        .size(LabelSize::Small)
+                        .color(Color::Muted),
+                    ),
+            )
+            .child(match entry.estimated_cost(settings) {
+                Some(cost) => Label::new(format_cost(cost)),
+                None => Label::new("No price").color(Color::Muted),
+            })
+    }
+}
+
+fn total_cost_label(report: &UsageReport, settings: &UsageSettings) -> String {
+    let cost = format_cost(report.estimated_cost(settings));
+    if report.has_unpriced_models(settings) {
+        format!("{cost}+")
+    } else {
+        cost
+    }
+}
+
+impl Focusable for UsageReportView {
+    fn focus_handle(&self, _cx: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for UsageReportView {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let settings = &AssistantSettings::get_global(cx).usage;
+        let thread = self.thread.read(cx);
+        let thread_usage = thread.usage_towards_limits();
+        let project_usage = thread.project_usage();
+        let mut total_usage = self.thread_store.read(cx).total_usage(Some(thread.id()));
+        total_usage.extend(thread.token_usage_by_model());
+
+        let mut thread_limits = Vec::new();
+        if let Some(limit) = settings.max_tokens_per_thread {
+            thread_limits.push(format!(
+                "Limit: {} tokens",
+                humanize_token_count(limit as usize)
+            ));
+        }
+        if let Some(limit) = settings.max_cost_per_thread {
+            thread_limits.push(format!("Limit: {}", format_cost(limit)));
+        }
+        let project_limits = settings
+            .max_cost_per_project
+            .map(|limit| format!("Limit: {}", format_cost(limit)))
+            .into_iter()
+            .collect();
+
+        v_flex()
+            .id("agent-usage-report")
+            .key_context("AgentUsageReport")
+            .track_focus(&self.focus_handle)
+            .track_scroll(&self.scroll_handle)
+            .size_full()
+            .overflow_y_scroll()
+            .bg(cx.theme().colors().panel_background)
+            .child(self.render_section(
+                "This Thread",
+                "Tokens used by the active thread, and by the thread it was forked or imported from.",
+                &thread_usage,
+                thread_limits,
+                settings,
+                cx,
+            ))
+            .child(self.render_section(
+                "This Project",
+                "Tokens used by the threads started in this project's worktrees.",
+                &project_usage,
+                project_limits,
+                settings,
+                cx,
+            ))
+            .child(self.render_section(
+                "All Threads",
+                "Tokens used by every thread in your history.",
+                &total_usage,
+                Vec::new(),
+                settings,
+                cx,
+            ))
+            .when(settings.pricing.is_empty(), |parent| {
+                parent.child(
+                    div().p(DynamicSpacing::Base16.rems(cx)).child(
+                        Label::new(
+                            "Configure `agent.usage.pricing` in your settings to estimate what threads cost.",
+                        )
+                        .color(Color::Muted),
+                    ),
+                )
+            })
+    }
+}
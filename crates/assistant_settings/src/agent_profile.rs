@@ -5,6 +5,8 @@ use gpui::SharedString;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
+use crate::ToolPermissions;
+
 pub mod builtin_profiles {
     use super::AgentProfileId;
 
@@ -69,9 +71,33 @@ pub struct AgentProfile {
     pub tools: IndexMap<Arc<str>, bool>,
     pub enable_all_context_servers: bool,
     pub context_servers: IndexMap<Arc<str>, ContextServerPreset>,
+    /// The rules deciding which tool calls may run without confirmation.
+    pub permissions: ToolPermissions,
+    /// The restrictions applied to commands run by the `terminal` tool.
+    pub terminal_sandbox: TerminalSandbox,
 }
 
 #[derive(Debug, Clone, Default)]
 pub struct ContextServerPreset {
     pub tools: IndexMap<Arc<str>, bool>,
 }
+
+/// Restrictions applied to the commands the agent runs with the `terminal` tool.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct TerminalSandbox {
+    /// Whether to run terminal commands in a sandbox that can only write to the
+    /// project's worktrees. Only supported in local projects on Linux, and
+    /// requires bubblewrap (`bwrap`) to be installed.
+    ///
+    /// Default: false
+    #[serde(default)]
+    pub enabled: bool,
+    /// Whether sandboxed commands can access the network.
+    ///
+    /// Default: false
+    #[serde(default)]
+    pub allow_network: bool,
+    /// Additional paths that sandboxed commands can write to, such as `~/.cargo`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub writable_paths: Vec<String>,
+}
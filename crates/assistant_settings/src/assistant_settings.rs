@@ -1,4 +1,5 @@
 mod agent_profile;
+mod tool_permissions;
 
 use std::sync::Arc;
 
@@ -8,7 +9,7 @@ use anyhow::{Result, bail};
 use collections::IndexMap;
 use deepseek::Model as DeepseekModel;
 use gpui::{App, Pixels, SharedString};
-use language_model::{CloudModel, LanguageModel};
+use language_model::{CloudModel, LanguageModel, TokenUsage};
 use lmstudio::Model as LmStudioModel;
 use ollama::Model as OllamaModel;
 use schemars::{JsonSchema, schema::Schema};
@@ -16,6 +17,7 @@ use serde::{Deserialize, Serialize};
 use settings::{Settings, SettingsSources};
 
 pub use crate::agent_profile::*;
+pub use crate::tool_permissions::*;
 
 pub fn init(cx: &mut App) {
     AssistantSettings::register(cx);
@@ -95,6 +97,35 @@ pub struct AssistantSettings {
     pub model_parameters: Vec<LanguageModelParameters>,
     pub preferred_completion_mode: CompletionMode,
     pub enable_feedback: bool,
+    pub context_compaction: ContextCompaction,
+    pub usage: UsageSettings,
+}
+
+/// How the agent summarizes the older turns of a thread that approaches the
+/// model's context window.
+#[derive(Default, Clone, Debug, PartialEq)]
+pub struct ContextCompaction {
+    pub enabled: bool,
+    pub threshold: f32,
+    pub model: Option<LanguageModelSelection>,
+}
+
+/// What agent threads are estimated to cost, and how much they may spend.
+#[derive(Default, Clone, Debug, PartialEq)]
+pub struct UsageSettings {
+    pub pricing: Vec<ModelPricing>,
+    pub max_tokens_per_thread: Option<u64>,
+    pub max_cost_per_thread: Option<f64>,
+    pub max_cost_per_project: Option<f64>,
+}
+
+impl UsageSettings {
+    /// Returns the last pricing entry that matches the given model, if any.
+    pub fn pricing_for(&self, provider: &str, model: &str) -> Option<&ModelPricing> {
+        self.pricing
+            .iter()
+            .rfind(|pricing| pricing.matches(provider, model))
+    }
 }
 
 impl AssistantSettings {
@@ -107,6 +138,11 @@ impl AssistantSettings {
             .and_then(|m| m.temperature)
     }
 
+    /// Returns the profile currently selected in the Agent Panel.
+    pub fn active_profile(&self) -> Option<&AgentProfile> {
+        self.profiles.get(&self.default_profile)
+    }
+
     pub fn set_inline_assistant_model(&mut self, provider: String, model: String) {
         self.inline_assistant_model = Some(LanguageModelSelection {
             provider: provider.into(),
@@ -152,6 +188,52 @@ impl LanguageModelParameters {
     }
 }
 
+/// The price of a model's tokens, in US dollars per million tokens.
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq)]
+pub struct ModelPricing {
+    pub provider: Option<LanguageModelProviderSetting>,
+    pub model: Option<SharedString>,
+    /// The price of input tokens.
+    pub input: f64,
+    /// The price of output tokens.
+    pub output: f64,
+    /// The price of input tokens written to the prompt cache. Defaults to the
+    /// price of input tokens.
+    pub cache_creation_input: Option<f64>,
+    /// The price of input tokens read from the prompt cache. Defaults to the
+    /// price of input tokens.
+    pub cache_read_input: Option<f64>,
+}
+
+impl ModelPricing {
+    pub fn matches(&self, provider: &str, model: &str) -> bool {
+        if let Some(setting_provider) = &self.provider {
+            if setting_provider.0 != provider {
+                return false;
+            }
+        }
+        if let Some(setting_model) = &self.model {
+            if setting_model != model {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Returns the estimated cost of `usage`, in US dollars.
+    pub fn cost(&self, usage: &TokenUsage) -> f64 {
+        const TOKENS_PER_UNIT: f64 = 1_000_000.0;
+
+        let cache_creation_input = self.cache_creation_input.unwrap_or(self.input);
+        let cache_read_input = self.cache_read_input.unwrap_or(self.input);
+        (usage.input_tokens as f64 * self.input
+            + usage.output_tokens as f64 * self.output
+            + usage.cache_creation_input_tokens as f64 * cache_creation_input
+            + usage.cache_read_input_tokens as f64 * cache_read_input)
+            / TOKENS_PER_UNIT
+    }
+}
+
 /// Assistant panel settings
 #[derive(Clone, Serialize, Deserialize, Debug, Default)]
 pub struct AssistantSettingsContent {
@@ -263,6 +345,8 @@ impl AssistantSettingsContent {
                     model_parameters: Vec::new(),
                     preferred_completion_mode: None,
                     enable_feedback: None,
+                    context_compaction: None,
+                    usage: None,
                 },
                 VersionedAssistantSettingsContent::V2(ref settings) => settings.clone(),
             },
@@ -294,6 +378,8 @@ impl AssistantSettingsContent {
                 model_parameters: Vec::new(),
                 preferred_completion_mode: None,
                 enable_feedback: None,
+                context_compaction: None,
+                usage: None,
             },
             None => AssistantSettingsContentV2::default(),
         }
@@ -537,6 +623,8 @@ impl AssistantSettingsContent {
                             )
                         })
                         .collect(),
+                    permissions: profile.permissions,
+                    terminal_sandbox: profile.terminal_sandbox,
                 },
             );
 
@@ -577,6 +665,8 @@ impl Default for VersionedAssistantSettingsContent {
             model_parameters: Vec::new(),
             preferred_completion_mode: None,
             enable_feedback: None,
+            context_compaction: None,
+            usage: None,
         })
     }
 }
@@ -655,6 +745,54 @@ pub struct AssistantSettingsContentV2 {
     ///
     /// Default: true
     enable_feedback: Option<bool>,
+    /// When to summarize the older turns of long threads, so that they keep
+    /// fitting in the model's context window.
+    context_compaction: Option<ContextCompactionContent>,
+    /// How to estimate the cost of agent threads, and when to pause threads
+    /// that spend too much.
+    usage: Option<UsageSettingsContent>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct ContextCompactionContent {
+    /// Whether to summarize the older turns of a thread when it approaches the
+    /// model's context window. The full thread stays visible in the agent panel.
+    ///
+    /// Default: true
+    pub enabled: Option<bool>,
+    /// The fraction of the model's context window a thread can use before its
+    /// older turns are summarized.
+    ///
+    /// Default: 0.8
+    pub threshold: Option<f32>,
+    /// Model to use for summarizing older turns. Defaults to thread_summary_model
+    /// when not specified.
+    pub model: Option<LanguageModelSelection>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct UsageSettingsContent {
+    /// The prices used to estimate what threads cost. When estimating the cost
+    /// of a model's tokens, the last entry that matches the model's provider
+    /// and name is used. Both provider and model are optional, and tokens of
+    /// models without a matching entry are counted but not priced.
+    ///
+    /// Default: []
+    #[serde(default)]
+    pub pricing: Vec<ModelPricing>,
+    /// The number of tokens a thread can use before it is paused.
+    ///
+    /// Default: null
+    pub max_tokens_per_thread: Option<u64>,
+    /// The estimated cost, in US dollars, a thread can reach before it is paused.
+    ///
+    /// Default: null
+    pub max_cost_per_thread: Option<f64>,
+    /// The estimated cost, in US dollars, the threads of a project can reach
+    /// before they are paused.
+    ///
+    /// Default: null
+    pub max_cost_per_project: Option<f64>,
 }
 
 #[derive(Clone, Copy, Debug, Serialize, Deserialize, JsonSchema, PartialEq, Default)]
@@ -737,6 +875,19 @@ pub struct AgentProfileContent {
     pub enable_all_context_servers: Option<bool>,
     #[serde(default)]
     pub context_servers: IndexMap<Arc<str>, ContextServerPresetContent>,
+    /// Rules deciding which tool calls run without confirmation, need
+    /// confirmation, or are never run.
+    ///
+    /// These take precedence over `always_allow_tool_actions`.
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub permissions: ToolPermissions,
+    /// Restrictions applied to the commands run by the `terminal` tool.
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub terminal_sandbox: TerminalSandbox,
+}
+
+fn is_default<T: Default + PartialEq>(value: &T) -> bool {
+    *value == T::default()
 }
 
 #[derive(Debug, PartialEq, Clone, Default, Serialize, Deserialize, JsonSchema)]
@@ -862,6 +1013,28 @@ impl Settings for AssistantSettings {
                 value.preferred_completion_mode,
             );
             merge(&mut settings.enable_feedback, value.enable_feedback);
+            if let Some(compaction) = value.context_compaction {
+                merge(&mut settings.context_compaction.enabled, compaction.enabled);
+                merge(
+                    &mut settings.context_compaction.threshold,
+                    compaction.threshold,
+                );
+                settings.context_compaction.model = compaction
+                    .model
+                    .or(settings.context_compaction.model.take());
+            }
+            if let Some(usage) = value.usage {
+                settings.usage.pricing.extend(usage.pricing);
+                settings.usage.max_tokens_per_thread = usage
+                    .max_tokens_per_thread
+                    .or(settings.usage.max_tokens_per_thread);
+                settings.usage.max_cost_per_thread = usage
+                    .max_cost_per_thread
+                    .or(settings.usage.max_cost_per_thread);
+                settings.usage.max_cost_per_project = usage
+                    .max_cost_per_project
+                    .or(settings.usage.max_cost_per_project);
+            }
 
             settings
                 .model_parameters
@@ -891,6 +1064,8 @@ impl Settings for AssistantSettings {
                                         )
                                     })
                                     .collect(),
+                                permissions: profile.permissions,
+                                terminal_sandbox: profile.terminal_sandbox,
                             },
                         )
                     }));
@@ -999,6 +1174,8 @@ mod tests {
                                 stream_edits: None,
                                 single_file_review: None,
                                 enable_feedback: None,
+                                context_compaction: None,
+                                usage: None,
                                 model_parameters: Vec::new(),
                                 preferred_completion_mode: None,
                             },
@@ -0,0 +1,790 @@
+use std::sync::Arc;
+
+use collections::IndexMap;
+use globset::GlobBuilder;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Whether a tool call may run.
+///
+/// Variants are ordered from least to most restrictive.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, JsonSchema,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum ToolPermission {
+    /// Run the tool without asking for confirmation.
+    Allow,
+    /// Ask for confirmation before running the tool.
+    Ask,
+    /// Never run the tool.
+    Deny,
+}
+
+/// Patterns deciding whether a tool call is allowed, denied, or needs confirmation.
+///
+/// When a call matches patterns in more than one list, `deny` takes precedence
+/// over `ask`, which takes precedence over `allow`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct ToolPermissionRules {
+    /// Patterns for calls that run without asking for confirmation.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub allow: Vec<String>,
+    /// Patterns for calls that are never run.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub deny: Vec<String>,
+    /// Patterns for calls that always ask for confirmation.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub ask: Vec<String>,
+}
+
+impl ToolPermissionRules {
+    pub fn is_empty(&self) -> bool {
+        self.allow.is_empty() && self.deny.is_empty() && self.ask.is_empty()
+    }
+
+    fn evaluate(
+        &self,
+        kind: &str,
+        mut is_match: impl FnMut(&str) -> bool,
+    ) -> Option<ToolPermissionMatch> {
+        [
+            (ToolPermission::Deny, "deny", &self.deny),
+            (ToolPermission::Ask, "ask", &self.ask),
+            (ToolPermission::Allow, "allow", &self.allow),
+        ]
+        .into_iter()
+        .find_map(|(permission, list, patterns)| {
+            let pattern = patterns.iter().find(|pattern| is_match(pattern))?;
+            Some(ToolPermissionMatch {
+                permission,
+                rule: format!("{kind}.{list}: {pattern:?}"),
+            })
+        })
+    }
+}
+
+/// A permission rule that matched a tool call.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ToolPermissionMatch {
+    pub permission: ToolPermission,
+    /// A description of the rule, such as `terminal.deny: "rm *"`.
+    pub rule: String,
+}
+
+/// Declarative permission rules for the tools used by an agent profile.
+///
+/// Tool calls that no rule applies to fall back to the tool's own confirmation behavior.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct ToolPermissions {
+    /// Rules for commands run by the `terminal` tool.
+    ///
+    /// Command lines are split on `;`, `&`, `|`, parentheses, and newlines, and every command
+    /// must be allowed for the command line to be allowed. Commands within substitutions and
+    /// in scripts run by `eval` or `sh -c` are matched too, but command lines using those,
+    /// here-documents, or redirections to files are never allowed by rules, as their effects
+    /// aren't described by their commands. `*` matches any sequence of characters.
+    #[serde(default, skip_serializing_if = "ToolPermissionRules::is_empty")]
+    pub terminal: ToolPermissionRules,
+    /// Rules for the paths modified by the `edit_file`, `delete_path`, and `move_path` tools.
+    ///
+    /// Patterns are globs matched against the path within the project, including the name
+    /// of its worktree (e.g. `zed/src/**`).
+    #[serde(default, skip_serializing_if = "ToolPermissionRules::is_empty")]
+    pub paths: ToolPermissionRules,
+    /// Rules for the hosts contacted by the `fetch` tool.
+    ///
+    /// `*` matches any sequence of characters, so `*.example.com` matches every subdomain
+    /// of `example.com`.
+    #[serde(default, skip_serializing_if = "ToolPermissionRules::is_empty")]
+    pub fetch: ToolPermissionRules,
+    /// Permissions for the tools of context servers, keyed by server ID and then tool name.
+    ///
+    /// The `*` tool name applies to every tool of a server without its own entry.
+    #[serde(default, skip_serializing_if = "IndexMap::is_empty")]
+    pub context_servers: IndexMap<Arc<str>, IndexMap<Arc<str>, ToolPermission>>,
+}
+
+impl ToolPermissions {
+    pub fn evaluate_terminal_command(&self, command: &str) -> Option<ToolPermissionMatch> {
+        let command_line = CommandLine::parse(command);
+        let rule_match = evaluate_all(
+            command_line.commands.iter().map(String::as_str),
+            |command| {
+                self.terminal.evaluate("terminal", |pattern| {
+                    matches_wildcard(pattern.trim(), command)
+                })
+            },
+        )?;
+        // Constructs that can run or write anything can be denied but never allowed.
+        if command_line.is_unrestricted && rule_match.permission == ToolPermission::Allow {
+            None
+        } else {
+            Some(rule_match)
+        }
+    }
+
+    pub fn evaluate_paths<'a>(
+        &self,
+        paths: impl IntoIterator<Item = &'a str>,
+    ) -> Option<ToolPermissionMatch> {
+        let mut escapes_root = false;
+        let rule_match = evaluate_all(paths, |path| {
+            let normalized = normalize_path(path);
+            escapes_root |= normalized.is_none();
+            let path = normalized.as_deref().unwrap_or(path);
+            self.paths.evaluate("paths", |pattern| {
+                match GlobBuilder::new(pattern).literal_separator(true).build() {
+                    Ok(glob) => glob.compile_matcher().is_match(path),
+                    Err(error) => {
+                        log::warn!("invalid path pattern {pattern:?} in tool permissions: {error}");
+                        false
+                    }
+                }
+            })
+        })?;
+        // Paths outside of the directory they're relative to can be denied but never allowed.
+        if escapes_root && rule_match.permission == ToolPermission::Allow {
+            None
+        } else {
+            Some(rule_match)
+        }
+    }
+
+    pub fn evaluate_fetch_host(&self, host: &str) -> Option<ToolPermissionMatch> {
+        let host = host.to_lowercase();
+        self.fetch.evaluate("fetch", |pattern| {
+            matches_wildcard(&pattern.to_lowercase(), &host)
+        })
+    }
+
+    pub fn evaluate_context_server_tool(
+        &self,
+        server_id: &str,
+        tool_name: &str,
+    ) -> Option<ToolPermissionMatch> {
+        let tools = self.context_servers.get(server_id)?;
+        let (name, permission) = tools
+            .get_key_value(tool_name)
+            .or_else(|| tools.get_key_value("*"))?;
+        Some(ToolPermissionMatch {
+            permission: *permission,
+            rule: format!("context_servers.{server_id}.{name}"),
+        })
+    }
+}
+
+/// Combines the rules matching each of several items acted on by a single tool call.
+///
+/// The most restrictive match wins, but the call is only allowed if every item is.
+fn evaluate_all<'a>(
+    items: impl IntoIterator<Item = &'a str>,
+    mut evaluate: impl FnMut(&str) -> Option<ToolPermissionMatch>,
+) -> Option<ToolPermissionMatch> {
+    let mut result: Option<ToolPermissionMatch> = None;
+    let mut all_matched = true;
+    for item in items {
+        match evaluate(item) {
+            Some(rule_match) => {
+                if result
+                    .as_ref()
+                    .is_none_or(|result| rule_match.permission > result.permission)
+                {
+                    result = Some(rule_match);
+                }
+            }
+            None => all_matched = false,
+        }
+    }
+
+    match result {
+        Some(rule_match) if rule_match.permission == ToolPermission::Allow && !all_matched => None,
+        result => result,
+    }
+}
+
+/// Resolves the `.` and `..` components of a path without touching the file system, so that
+/// `zed/src/../secrets/key` is matched as `zed/secrets/key`.
+///
+/// Returns `None` when the path leaves the directory it's relative to.
+fn normalize_path(path: &str) -> Option<String> {
+    let (root, relative) = match path.strip_prefix('/') {
+        Some(relative) => ("/", relative),
+        None => ("", path),
+    };
+    let mut components = Vec::new();
+    for component in relative.split('/') {
+        match component {
+            "" | "." => {}
+            ".." => {
+                components.pop()?;
+            }
+            component => components.push(component),
+        }
+    }
+    Some(format!("{root}{}", components.join("/")))
+}
+
+/// The commands of a shell command line that permission rules are matched against.
+#[derive(Debug, Default, PartialEq)]
+struct CommandLine {
+    /// The simple commands of the command line, including those nested in substitutions
+    /// and in scripts passed to `eval` or `sh -c`.
+    commands: Vec<String>,
+    /// Whether the command line uses constructs whose effects aren't described by its
+    /// commands, such as substitutions, here-documents, redirections to files, and
+    /// scripts run by `eval` or a shell.
+    is_unrestricted: bool,
+}
+
+/// Shells whose `-c` argument is a script to run.
+const SHELLS: &[&str] = &["sh", "bash", "zsh", "dash", "ksh", "fish"];
+
+/// Builtins that run their arguments, or a file, as shell code.
+const EVALUATING_BUILTINS: &[&str] = &["eval", "exec", "source", "."];
+
+/// Keywords of compound commands, which may precede a command.
+const COMPOUND_KEYWORDS: &[&str] = &[
+    "{", "}", "!", "if", "then", "elif", "else", "fi", "do", "done", "while", "until",
+];
+
+/// How deeply nested scripts are parsed before the rest is treated as unrestricted.
+const MAX_NESTING: usize = 8;
+
+impl CommandLine {
+    fn parse(command_line: &str) -> Self {
+        let mut this = Self::default();
+        this.parse_nested(command_line, 0);
+        this
+    }
+
+    fn parse_nested(&mut self, command_line: &str, depth: usize) {
+        if depth > MAX_NESTING {
+            self.is_unrestricted = true;
+            return;
+        }
+
+        let chars = command_line.chars().collect::<Vec<_>>();
+        let mut command = String::new();
+        let mut in_single_quotes = false;
+        let mut in_double_quotes = false;
+        let mut ix = 0;
+        while ix < chars.len() {
+            let c = chars[ix];
+            let next = chars.get(ix + 1).copied();
+            if in_single_quotes {
+                in_single_quotes = c != '\'';
+                command.push(c);
+                ix += 1;
+                continue;
+            }
+
+            match c {
+                '\\' => {
+                    command.push(c);
+                    command.extend(next);
+                    ix += 2;
+                    continue;
+                }
+                '\'' if !in_double_quotes => in_single_quotes = true,
+                '"' => in_double_quotes = !in_double_quotes,
+                '$' if next == Some('(') => {
+                    let end = closing_paren(&chars, ix + 1);
+                    self.parse_substitution(&chars[ix + 2..end], depth);
+                    command.extend(&chars[ix..(end + 1).min(chars.len())]);
+                    ix = end + 1;
+                    continue;
+                }
+                '`' => {
+                    let end = (ix + 1..chars.len())
+                        .find(|&end| chars[end] == '`' && chars[end - 1] != '\\')
+                        .unwrap_or(chars.len());
+                    self.parse_substitution(&chars[ix + 1..end], depth);
+                    command.extend(&chars[ix..(end + 1).min(chars.len())]);
+                    ix = end + 1;
+                    continue;
+                }
+                _ if in_double_quotes => {}
+                '<' | '>' if next == Some('(') => {
+                    // Process substitution.
+                    let end = closing_paren(&chars, ix + 1);
+                    self.parse_substitution(&chars[ix + 2..end], depth);
+                    command.extend(&chars[ix..(end + 1).min(chars.len())]);
+                    ix = end + 1;
+                    continue;
+                }
+                '<' if next == Some('<') => {
+                    // Here-documents and here-strings feed text that isn't matched by any
+                    // rule to the command.
+                    self.is_unrestricted = true;
+                }
+                '>' | '&' if c == '>' || next == Some('>') => {
+                    let start = ix;
+                    ix = self.parse_output_redirection(&chars, ix);
+                    command.extend(&chars[start..ix]);
+                    continue;
+                }
+                ';' | '&' | '|' | '\n' | '(' | ')' => {
+                    self.push_command(&command, depth);
+                    command.clear();
+                    ix += 1;
+                    continue;
+                }
+                _ => {}
+            }
+            command.push(c);
+            ix += 1;
+        }
+        if in_single_quotes || in_double_quotes {
+            self.is_unrestricted = true;
+        }
+        self.push_command(&command, depth);
+    }
+
+    fn parse_substitution(&mut self, script: &[char], depth: usize) {
+        self.is_unrestricted = true;
+        self.parse_nested(&script.iter().collect::<String>(), depth + 1);
+    }
+
+    /// Parses a redirection of output starting at `ix`, such as `> file`, `2>> file`,
+    /// `&> file`, or `2>&1`, and returns the index following it. Redirections to files
+    /// make the command line unrestricted, as path rules don't apply to them.
+    fn parse_output_redirection(&mut self, chars: &[char], mut ix: usize) -> usize {
+        if chars[ix] == '&' {
+            ix += 1;
+        }
+        ix += 1;
+        let duplicates_descriptor = match chars.get(ix) {
+            Some('>') | Some('|') => {
+                ix += 1;
+                false
+            }
+            Some('&') => {
+                ix += 1;
+                true
+            }
+            _ => false,
+        };
+        while chars.get(ix).is_some_and(|c| *c == ' ' || *c == '\t') {
+            ix += 1;
+        }
+        let target_start = ix;
+        while chars
+            .get(ix)
+            .is_some_and(|c| !c.is_whitespace() && !matches!(c, ';' | '&' | '|' | '(' | ')'))
+        {
+            ix += 1;
+        }
+        let target = chars[target_start..ix].iter().collect::<String>();
+        let is_descriptor =
+            duplicates_descriptor && (target == "-" || target.chars().all(|c| c.is_ascii_digit()));
+        if !is_descriptor && target != "/dev/null" {
+            self.is_unrestricted = true;
+        }
+        ix
+    }
+
+    fn push_command(&mut self, command: &str, depth: usize) {
+        // Rules are matched against the commands within compound commands.
+        let mut command = command.trim();
+        loop {
+            let (word, rest) = command
+                .split_once(char::is_whitespace)
+                .unwrap_or((command, ""));
+            if word.is_empty() || !COMPOUND_KEYWORDS.contains(&word) {
+                break;
+            }
+            command = rest.trim_start();
+        }
+        if command.is_empty() {
+            return;
+        }
+
+        let words = shell_words(command);
+        let program_ix = words
+            .iter()
+            .position(|word| !is_variable_assignment(word))
+            .unwrap_or(words.len());
+        if let Some(program) = words.get(program_ix) {
+            if EVALUATING_BUILTINS.contains(&program.as_str()) {
+                self.is_unrestricted = true;
+                if program == "eval" {
+                    self.parse_nested(&words[program_ix + 1..].join(" "), depth + 1);
+                }
+            }
+        }
+        // Shells may also be run through other commands, as in `xargs sh -c`.
+        for (ix, word) in words.iter().enumerate() {
+            let name = word.rsplit('/').next().unwrap_or(word);
+            if SHELLS.contains(&name) {
+                if let Some(script_ix) = words[ix + 1..]
+                    .iter()
+                    .position(|word| word.starts_with('-') && word.contains('c'))
+                {
+                    self.is_unrestricted = true;
+                    if let Some(script) = words.get(ix + 1 + script_ix + 1) {
+                        self.parse_nested(script, depth + 1);
+                    }
+                }
+            }
+        }
+
+        self.commands.push(command.to_string());
+    }
+}
+
+/// Returns the index of the parenthesis closing the one at `open`, or the length of
+/// `chars` if it isn't closed.
+fn closing_paren(chars: &[char], open: usize) -> usize {
+    let mut depth = 0;
+    let mut in_single_quotes = false;
+    let mut in_double_quotes = false;
+    let mut ix = open;
+    while ix < chars.len() {
+        match chars[ix] {
+            '\\' if !in_single_quotes => ix += 1,
+            '\'' if !in_double_quotes => in_single_quotes = !in_single_quotes,
+            '"' if !in_single_quotes => in_double_quotes = !in_double_quotes,
+            '(' if !in_single_quotes && !in_double_quotes => depth += 1,
+            ')' if !in_single_quotes && !in_double_quotes => {
+                depth -= 1;
+                if depth == 0 {
+                    return ix;
+                }
+            }
+            _ => {}
+        }
+        ix += 1;
+    }
+    chars.len()
+}
+
+/// Splits a simple command into words, removing quotes and escapes.
+fn shell_words(command: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut word: Option<String> = None;
+    let mut quote = None;
+    let mut chars = command.chars();
+    while let Some(c) = chars.next() {
+        match (quote, c) {
+            (None, c) if c.is_whitespace() => words.extend(word.take()),
+            (None, '\'' | '"') => {
+                quote = Some(c);
+                word.get_or_insert_default();
+            }
+            (Some(q), c) if c == q => quote = None,
+            (None | Some('"'), '\\') => word.get_or_insert_default().extend(chars.next()),
+            (_, c) => word.get_or_insert_default().push(c),
+        }
+    }
+    words.extend(word);
+    words
+}
+
+fn is_variable_assignment(word: &str) -> bool {
+    word.split_once('=').is_some_and(|(name, _)| {
+        !name.is_empty()
+            && !name.starts_with(|c: char| c.is_ascii_digit())
+            && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+    })
+}
+
+/// Matches `text` against `pattern`, where `*` matches any sequence of characters.
+fn matches_wildcard(pattern: &str, text: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let prefix = parts.next().unwrap_or_default();
+    let Some(mut rest) = text.strip_prefix(prefix) else {
+        return false;
+    };
+    let parts = parts.collect::<Vec<_>>();
+    let Some((suffix, middle)) = parts.split_last() else {
+        return rest.is_empty();
+    };
+    for part in middle {
+        match rest.find(part) {
+            Some(ix) => rest = &rest[ix + part.len()..],
+            None => return false,
+        }
+    }
+    rest.len() >= suffix.len() && rest.ends_with(suffix)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rules(allow: &[&str], deny: &[&str], ask: &[&str]) -> ToolPermissionRules {
+        let to_vec = |patterns: &[&str]| patterns.iter().map(|s| s.to_string()).collect();
+        ToolPermissionRules {
+            allow: to_vec(allow),
+            deny: to_vec(deny),
+            ask: to_vec(ask),
+        }
+    }
+
+    fn permission(rule_match: Option<ToolPermissionMatch>) -> Option<ToolPermission> {
+        rule_match.map(|rule_match| rule_match.permission)
+    }
+
+    #[test]
+    fn test_matches_wildcard() {
+        assert!(matches_wildcard("cargo *", "cargo test -p editor"));
+        assert!(matches_wildcard("git status", "git status"));
+        assert!(!matches_wildcard("git status", "git status --short"));
+        assert!(matches_wildcard("*.github.com", "api.github.com"));
+        assert!(!matches_wildcard("*.github.com", "github.com"));
+        assert!(matches_wildcard("rm *-rf*", "rm -v -rf target"));
+        assert!(!matches_wildcard("a*b*c", "abca"));
+        assert!(matches_wildcard("*", ""));
+    }
+
+    #[test]
+    fn test_terminal_command_permissions() {
+        let permissions = ToolPermissions {
+            terminal: rules(&["cargo *", "git status", "ls*"], &["rm *"], &["git push*"]),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            permissions.evaluate_terminal_command("cargo test -p editor"),
+            Some(ToolPermissionMatch {
+                permission: ToolPermission::Allow,
+                rule: r#"terminal.allow: "cargo *""#.into(),
+            })
+        );
+        assert_eq!(
+            permission(permissions.evaluate_terminal_command("git status && ls -la")),
+            Some(ToolPermission::Allow)
+        );
+        assert_eq!(
+            permission(permissions.evaluate_terminal_command("cargo build; rm -rf /")),
+            Some(ToolPermission::Deny)
+        );
+        assert_eq!(
+            permission(permissions.evaluate_terminal_command("git status | git push origin")),
+            Some(ToolPermission::Ask)
+        );
+        // Commands without a rule aren't allowed by the rules of their neighbors.
+        assert_eq!(
+            permission(permissions.evaluate_terminal_command("cargo build && curl example.com")),
+            None
+        );
+        assert_eq!(
+            permission(permissions.evaluate_terminal_command("cargo run $(curl example.com)")),
+            None
+        );
+        assert_eq!(
+            permission(permissions.evaluate_terminal_command("make")),
+            None
+        );
+        // Separators within quotes don't split commands.
+        assert_eq!(
+            permission(permissions.evaluate_terminal_command("cargo run -- 'a; rm -rf /'")),
+            Some(ToolPermission::Allow)
+        );
+        // Commands within grouping are matched too.
+        assert_eq!(
+            permission(permissions.evaluate_terminal_command("(rm -rf /)")),
+            Some(ToolPermission::Deny)
+        );
+        assert_eq!(
+            permission(permissions.evaluate_terminal_command("{ rm -rf /; }")),
+            Some(ToolPermission::Deny)
+        );
+        assert_eq!(
+            permission(permissions.evaluate_terminal_command("if true; then rm -rf /; fi")),
+            Some(ToolPermission::Deny)
+        );
+    }
+
+    #[test]
+    fn test_terminal_command_permission_bypasses() {
+        let permissions = ToolPermissions {
+            terminal: rules(&["cargo *", "ls*"], &["rm *"], &[]),
+            ..Default::default()
+        };
+        let permission = |command: &str| permission(permissions.evaluate_terminal_command(command));
+
+        // Redirections to files write outside of the path rules.
+        assert_eq!(permission("cargo build > ~/.bashrc"), None);
+        assert_eq!(permission("cargo build >>~/.bashrc"), None);
+        assert_eq!(permission("cargo build 2> errors.txt"), None);
+        assert_eq!(permission("cargo build &> log.txt"), None);
+        assert_eq!(permission("cargo build >| out.txt"), None);
+        assert_eq!(
+            permission("cargo build 2>&1 > /dev/null"),
+            Some(ToolPermission::Allow)
+        );
+        assert_eq!(permission("cargo build 2>&1"), Some(ToolPermission::Allow));
+        assert_eq!(
+            permission("cargo build \"> out.txt\""),
+            Some(ToolPermission::Allow)
+        );
+
+        // Process and command substitutions run commands of their own.
+        assert_eq!(permission("cargo run -- <(curl example.com)"), None);
+        assert_eq!(permission("cargo run -- >(sh)"), None);
+        assert_eq!(permission("cargo run -- <(ls)"), None);
+        assert_eq!(permission("cargo run -- `ls`"), None);
+        assert_eq!(permission("cargo run -- \"$(ls)\""), None);
+        assert_eq!(
+            permission("cargo run -- $(rm -rf /)"),
+            Some(ToolPermission::Deny)
+        );
+        assert_eq!(
+            permission("cargo run -- \"$(echo; rm -rf /)\""),
+            Some(ToolPermission::Deny)
+        );
+        assert_eq!(
+            permission("cargo run -- <(rm -rf /)"),
+            Some(ToolPermission::Deny)
+        );
+        assert_eq!(
+            permission("cargo run -- '$(rm -rf /)'"),
+            Some(ToolPermission::Allow)
+        );
+
+        // Scripts run by `eval` and shells are matched, but never allowed.
+        assert_eq!(permission("eval cargo build"), None);
+        assert_eq!(permission("eval 'rm -rf /'"), Some(ToolPermission::Deny));
+        assert_eq!(permission("sh -c 'cargo build'"), None);
+        assert_eq!(
+            permission("bash -c \"rm -rf /\""),
+            Some(ToolPermission::Deny)
+        );
+        assert_eq!(
+            permission("/bin/sh -c 'rm -rf /'"),
+            Some(ToolPermission::Deny)
+        );
+        assert_eq!(permission("ls | xargs sh -c 'cargo build'"), None);
+        assert_eq!(permission("source ./script.sh"), None);
+        assert_eq!(permission("cargo build; . ./script.sh"), None);
+
+        // Here-documents and here-strings feed unmatched text to commands.
+        assert_eq!(permission("cargo run <<EOF\nanything\nEOF"), None);
+        assert_eq!(permission("cargo run <<< anything"), None);
+        assert_eq!(
+            permission("cargo run <<EOF\nrm -rf /\nEOF"),
+            Some(ToolPermission::Deny)
+        );
+
+        // Unterminated quotes can't be parsed reliably.
+        assert_eq!(permission("cargo run -- 'unterminated"), None);
+    }
+
+    #[test]
+    fn test_path_permissions() {
+        let permissions = ToolPermissions {
+            paths: rules(&["zed/src/**"], &["**/.env", "zed/secrets/**"], &[]),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            permission(permissions.evaluate_paths(["zed/src/main.rs"])),
+            Some(ToolPermission::Allow)
+        );
+        assert_eq!(
+            permission(permissions.evaluate_paths(["zed/src/nested/.env"])),
+            Some(ToolPermission::Deny)
+        );
+        assert_eq!(
+            permission(permissions.evaluate_paths(["zed/src/a.rs", "zed/secrets/key"])),
+            Some(ToolPermission::Deny)
+        );
+        assert_eq!(
+            permission(permissions.evaluate_paths(["zed/src/a.rs", "zed/docs/a.md"])),
+            None
+        );
+        assert_eq!(permission(permissions.evaluate_paths([])), None);
+
+        // Paths are matched once `.` and `..` are resolved.
+        assert_eq!(
+            permission(permissions.evaluate_paths(["./zed/src/./main.rs"])),
+            Some(ToolPermission::Allow)
+        );
+        assert_eq!(
+            permission(permissions.evaluate_paths(["zed/src/../secrets/key"])),
+            Some(ToolPermission::Deny)
+        );
+        assert_eq!(
+            permission(permissions.evaluate_paths(["zed/src/../docs/a.md"])),
+            None
+        );
+        assert_eq!(
+            permission(permissions.evaluate_paths(["zed/src/nested/../../src/a.rs"])),
+            Some(ToolPermission::Allow)
+        );
+
+        // Paths that leave the project are never allowed.
+        let permissions = ToolPermissions {
+            paths: rules(&["**"], &["**/.env"], &[]),
+            ..Default::default()
+        };
+        assert_eq!(
+            permission(permissions.evaluate_paths(["zed/../../etc/passwd"])),
+            None
+        );
+        assert_eq!(
+            permission(permissions.evaluate_paths(["../project/.env"])),
+            Some(ToolPermission::Deny)
+        );
+    }
+
+    #[test]
+    fn test_normalize_path() {
+        assert_eq!(normalize_path("a/./b//c/").as_deref(), Some("a/b/c"));
+        assert_eq!(normalize_path("a/b/../c").as_deref(), Some("a/c"));
+        assert_eq!(normalize_path("/a/../b").as_deref(), Some("/b"));
+        assert_eq!(normalize_path("a/.."), Some(String::new()));
+        assert_eq!(normalize_path("a/../.."), None);
+        assert_eq!(normalize_path("/.."), None);
+    }
+
+    #[test]
+    fn test_fetch_and_context_server_permissions() {
+        let permissions = ToolPermissions {
+            fetch: rules(&["docs.rs", "*.github.com"], &["*.internal"], &[]),
+            context_servers: IndexMap::from_iter([(
+                "postgres".into(),
+                IndexMap::from_iter([
+                    ("query".into(), ToolPermission::Allow),
+                    ("*".into(), ToolPermission::Deny),
+                ]),
+            )]),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            permission(permissions.evaluate_fetch_host("Docs.rs")),
+            Some(ToolPermission::Allow)
+        );
+        assert_eq!(
+            permission(permissions.evaluate_fetch_host("api.github.com")),
+            Some(ToolPermission::Allow)
+        );
+        assert_eq!(
+            permission(permissions.evaluate_fetch_host("db.internal")),
+            Some(ToolPermission::Deny)
+        );
+        assert_eq!(
+            permission(permissions.evaluate_fetch_host("example.com")),
+            None
+        );
+
+        assert_eq!(
+            permissions.evaluate_context_server_tool("postgres", "query"),
+            Some(ToolPermissionMatch {
+                permission: ToolPermission::Allow,
+                rule: "context_servers.postgres.query".into(),
+            })
+        );
+        assert_eq!(
+            permission(permissions.evaluate_context_server_tool("postgres", "drop_table")),
+            Some(ToolPermission::Deny)
+        );
+        assert_eq!(
+            permission(permissions.evaluate_context_server_tool("github", "search")),
+            None
+        );
+    }
+}
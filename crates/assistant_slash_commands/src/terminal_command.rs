@@ -3,8 +3,8 @@ use std::sync::atomic::AtomicBool;
 
 use anyhow::Result;
 use assistant_slash_command::{
-    ArgumentCompletion, SlashCommand, SlashCommandOutput, SlashCommandOutputSection,
-    SlashCommandResult,
+    AfterCompletion, ArgumentCompletion, SlashCommand, SlashCommandOutput,
+    SlashCommandOutputSection, SlashCommandResult,
 };
 use gpui::{App, Entity, Task, WeakEntity};
 use language::{BufferSnapshot, CodeLabel, LspAdapterDelegate};
@@ -17,6 +17,7 @@ use super::create_label_for_command;
 pub struct TerminalSlashCommand;
 
 const LINE_COUNT_ARG: &str = "--line-count";
+const LAST_COMMAND_ARG: &str = "--last-command";
 
 const DEFAULT_CONTEXT_LINES: usize = 50;
 
@@ -26,7 +27,7 @@ impl SlashCommand for TerminalSlashCommand {
     }
 
     fn label(&self, cx: &App) -> CodeLabel {
-        create_label_for_command("terminal", &[LINE_COUNT_ARG], cx)
+        create_label_for_command("terminal", &[LINE_COUNT_ARG, LAST_COMMAND_ARG], cx)
     }
 
     fn description(&self) -> String {
@@ -57,7 +58,12 @@ impl SlashCommand for TerminalSlashCommand {
         _window: &mut Window,
         _cx: &mut App,
     ) -> Task<Result<Vec<ArgumentCompletion>>> {
-        Task::ready(Ok(Vec::new()))
+        Task::ready(Ok(vec![ArgumentCompletion {
+            label: CodeLabel::plain(LAST_COMMAND_ARG.to_string(), None),
+            new_text: LAST_COMMAND_ARG.to_string(),
+            after_completion: AfterCompletion::Run,
+            replace_previous_arguments: false,
+        }]))
     }
 
     fn run(
@@ -78,20 +84,32 @@ impl SlashCommand for TerminalSlashCommand {
             return Task::ready(Err(anyhow::anyhow!("no active terminal")));
         };
 
-        let line_count = arguments
-            .get(0)
-            .and_then(|s| s.parse::<usize>().ok())
-            .unwrap_or(DEFAULT_CONTEXT_LINES);
+        let terminal = active_terminal.read(cx).entity().read(cx);
 
-        let lines = active_terminal
-            .read(cx)
-            .entity()
-            .read(cx)
-            .last_n_non_empty_lines(line_count);
+        // Only the last command's output is wanted, which the terminal knows when the
+        // shell reports its commands. Otherwise fall back to the last lines of output.
+        let last_command_output = arguments
+            .iter()
+            .any(|argument| argument == LAST_COMMAND_ARG)
+            .then(|| terminal.last_command_output())
+            .flatten();
 
         let mut text = String::new();
-        text.push_str("Terminal output:\n");
-        text.push_str(&lines.join("\n"));
+        if let Some(last_command_output) = last_command_output {
+            text.push_str(&format!("Output of `{}`:\n", last_command_output.command));
+            text.push_str(&last_command_output.output);
+            if let Some(exit_code) = last_command_output.exit_code {
+                text.push_str(&format!("\nExit code: {exit_code}"));
+            }
+        } else {
+            let line_count = arguments
+                .iter()
+                .find_map(|argument| argument.parse::<usize>().ok())
+                .unwrap_or(DEFAULT_CONTEXT_LINES);
+            let lines = terminal.last_n_non_empty_lines(line_count);
+            text.push_str("Terminal output:\n");
+            text.push_str(&lines.join("\n"));
+        }
         let range = 0..text.len();
 
         Task::ready(Ok(SlashCommandOutput {
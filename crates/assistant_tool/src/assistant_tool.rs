@@ -198,6 +198,19 @@ pub enum ToolSource {
     ContextServer { id: SharedString },
 }
 
+/// What a tool call acts on, as far as the permission rules of agent profiles are concerned.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ToolPermissionSubject {
+    /// The tool call isn't covered by any permission rules.
+    None,
+    /// A command line run in a terminal.
+    TerminalCommand(String),
+    /// Project paths that the tool call modifies.
+    Paths(Vec<String>),
+    /// The host of a URL that the tool call fetches.
+    FetchHost(String),
+}
+
 /// A tool that can be used by a language model.
 pub trait Tool: 'static + Send + Sync {
     /// Returns the name of the tool.
@@ -218,6 +231,12 @@ pub trait Tool: 'static + Send + Sync {
     /// before having permission to run.
     fn needs_confirmation(&self, input: &serde_json::Value, cx: &App) -> bool;
 
+    /// Returns what the tool call with the given input acts on, so that it
+    /// can be checked against the permission rules of the active profile.
+    fn permission_subject(&self, _input: &serde_json::Value) -> ToolPermissionSubject {
+        ToolPermissionSubject::None
+    }
+
     /// Returns the JSON schema that describes the tool's input.
     fn input_schema(&self, _: LanguageModelToolSchemaFormat) -> Result<serde_json::Value> {
         Ok(serde_json::Value::Object(serde_json::Map::default()))
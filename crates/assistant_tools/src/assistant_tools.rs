@@ -13,6 +13,7 @@ mod now_tool;
 mod open_tool;
 mod read_file_tool;
 mod schema;
+mod semantic_search_tool;
 mod templates;
 mod terminal_tool;
 mod thinking_tool;
@@ -27,6 +28,8 @@ use gpui::{App, Entity};
 use http_client::HttpClientWithUrl;
 use language_model::LanguageModelRegistry;
 use move_path_tool::MovePathTool;
+use semantic_index::SemanticDb;
+use semantic_search_tool::SemanticSearchTool;
 use web_search_tool::WebSearchTool;
 
 pub(crate) use templates::*;
@@ -79,6 +82,18 @@ pub fn init(http_client: Arc<HttpClientWithUrl>, cx: &mut App) {
         },
     )
     .detach();
+
+    register_semantic_search_tool(cx);
+    cx.observe_global::<SemanticDb>(register_semantic_search_tool)
+        .detach();
+}
+
+fn register_semantic_search_tool(cx: &mut App) {
+    if cx.has_global::<SemanticDb>() {
+        ToolRegistry::global(cx).register_tool(SemanticSearchTool);
+    } else {
+        ToolRegistry::global(cx).unregister_tool(SemanticSearchTool);
+    }
 }
 
 fn register_web_search_tool(registry: &Entity<LanguageModelRegistry>, cx: &mut App) {
@@ -1,6 +1,6 @@
 use crate::schema::json_schema_for;
 use anyhow::{Result, anyhow};
-use assistant_tool::{ActionLog, Tool, ToolResult};
+use assistant_tool::{ActionLog, Tool, ToolPermissionSubject, ToolResult};
 use futures::{SinkExt, StreamExt, channel::mpsc};
 use gpui::{AnyWindowHandle, App, AppContext, Entity, Task};
 use language_model::{LanguageModel, LanguageModelRequest, LanguageModelToolSchemaFormat};
@@ -37,6 +37,13 @@ impl Tool for DeletePathTool {
         false
     }
 
+    fn permission_subject(&self, input: &serde_json::Value) -> ToolPermissionSubject {
+        match serde_json::from_value::<DeletePathToolInput>(input.clone()) {
+            Ok(input) => ToolPermissionSubject::Paths(vec![input.path]),
+            Err(_) => ToolPermissionSubject::None,
+        }
+    }
+
     fn description(&self) -> String {
         include_str!("./delete_path_tool/description.md").into()
     }
@@ -5,8 +5,8 @@ use crate::{
 };
 use anyhow::{Result, anyhow};
 use assistant_tool::{
-    ActionLog, AnyToolCard, Tool, ToolCard, ToolResult, ToolResultContent, ToolResultOutput,
-    ToolUseStatus,
+    ActionLog, AnyToolCard, Tool, ToolCard, ToolPermissionSubject, ToolResult, ToolResultContent,
+    ToolResultOutput, ToolUseStatus,
 };
 use buffer_diff::{BufferDiff, BufferDiffSnapshot};
 use editor::{Editor, EditorMode, MultiBuffer, PathKey};
@@ -121,6 +121,13 @@ impl Tool for EditFileTool {
         false
     }
 
+    fn permission_subject(&self, input: &serde_json::Value) -> ToolPermissionSubject {
+        match serde_json::from_value::<EditFileToolInput>(input.clone()) {
+            Ok(input) => ToolPermissionSubject::Paths(vec![input.path.to_string_lossy().into()]),
+            Err(_) => ToolPermissionSubject::None,
+        }
+    }
+
     fn description(&self) -> String {
         include_str!("edit_file_tool/description.md").to_string()
     }
@@ -4,11 +4,11 @@ use std::sync::Arc;
 
 use crate::schema::json_schema_for;
 use anyhow::{Context as _, Result, anyhow, bail};
-use assistant_tool::{ActionLog, Tool, ToolResult};
+use assistant_tool::{ActionLog, Tool, ToolPermissionSubject, ToolResult};
 use futures::AsyncReadExt as _;
 use gpui::{AnyWindowHandle, App, AppContext as _, Entity, Task};
 use html_to_markdown::{TagHandler, convert_html_to_markdown, markdown};
-use http_client::{AsyncBody, HttpClientWithUrl};
+use http_client::{AsyncBody, HttpClientWithUrl, Url};
 use language_model::{LanguageModel, LanguageModelRequest, LanguageModelToolSchemaFormat};
 use project::Project;
 use schemars::JsonSchema;
@@ -29,6 +29,15 @@ pub struct FetchToolInput {
     url: String,
 }
 
+/// Adds the `https` scheme to URLs without one.
+fn normalize_url(url: &str) -> String {
+    if url.starts_with("https://") || url.starts_with("http://") {
+        url.to_string()
+    } else {
+        format!("https://{url}")
+    }
+}
+
 pub struct FetchTool {
     http_client: Arc<HttpClientWithUrl>,
 }
@@ -39,11 +48,7 @@ impl FetchTool {
     }
 
     async fn build_message(http_client: Arc<HttpClientWithUrl>, url: &str) -> Result<String> {
-        let mut url = url.to_owned();
-        if !url.starts_with("https://") && !url.starts_with("http://") {
-            url = format!("https://{url}");
-        }
-
+        let url = normalize_url(url);
         let mut response = http_client.get(&url, AsyncBody::default(), true).await?;
 
         let mut body = Vec::new();
@@ -120,6 +125,18 @@ impl Tool for FetchTool {
         true
     }
 
+    fn permission_subject(&self, input: &serde_json::Value) -> ToolPermissionSubject {
+        serde_json::from_value::<FetchToolInput>(input.clone())
+            .ok()
+            .and_then(|input| Url::parse(&normalize_url(&input.url)).ok())
+            .and_then(|url| {
+                Some(ToolPermissionSubject::FetchHost(
+                    url.host_str()?.to_string(),
+                ))
+            })
+            .unwrap_or(ToolPermissionSubject::None)
+    }
+
     fn description(&self) -> String {
         include_str!("./fetch_tool/description.md").to_string()
     }
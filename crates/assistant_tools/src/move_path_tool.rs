@@ -1,6 +1,6 @@
 use crate::schema::json_schema_for;
 use anyhow::{Result, anyhow};
-use assistant_tool::{ActionLog, Tool, ToolResult};
+use assistant_tool::{ActionLog, Tool, ToolPermissionSubject, ToolResult};
 use gpui::{AnyWindowHandle, App, AppContext, Entity, Task};
 use language_model::{LanguageModel, LanguageModelRequest, LanguageModelToolSchemaFormat};
 use project::Project;
@@ -46,6 +46,15 @@ impl Tool for MovePathTool {
         false
     }
 
+    fn permission_subject(&self, input: &serde_json::Value) -> ToolPermissionSubject {
+        match serde_json::from_value::<MovePathToolInput>(input.clone()) {
+            Ok(input) => {
+                ToolPermissionSubject::Paths(vec![input.source_path, input.destination_path])
+            }
+            Err(_) => ToolPermissionSubject::None,
+        }
+    }
+
     fn description(&self) -> String {
         include_str!("./move_path_tool/description.md").into()
     }
@@ -0,0 +1,322 @@
+use crate::schema::json_schema_for;
+use anyhow::{Result, anyhow};
+use assistant_tool::{ActionLog, Tool, ToolResult};
+use gpui::{AnyWindowHandle, App, BorrowAppContext, Entity, Task};
+use language_model::{LanguageModel, LanguageModelRequest, LanguageModelToolSchemaFormat};
+use project::Project;
+use schemars::JsonSchema;
+use semantic_index::{LoadedSearchResult, SemanticDb, Status};
+use serde::{Deserialize, Serialize};
+use std::{fmt::Write, sync::Arc};
+use ui::IconName;
+use util::markdown::MarkdownInlineCode;
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct SemanticSearchToolInput {
+    /// Natural-language descriptions of the code to find.
+    ///
+    /// <example>
+    /// To find where the editor decides how wide the gutter is, you could pass
+    /// ["computing the width of the gutter", "gutter dimensions layout"].
+    /// </example>
+    pub queries: Vec<String>,
+
+    /// The maximum number of excerpts to return. Defaults to 10.
+    pub limit: Option<usize>,
+}
+
+const DEFAULT_LIMIT: usize = 10;
+const MAX_LIMIT: usize = 50;
+
+pub struct SemanticSearchTool;
+
+impl Tool for SemanticSearchTool {
+    fn name(&self) -> String {
+        "semantic_search".into()
+    }
+
+    fn needs_confirmation(&self, _: &serde_json::Value, _: &App) -> bool {
+        false
+    }
+
+    fn description(&self) -> String {
+        include_str!("./semantic_search_tool/description.md").into()
+    }
+
+    fn icon(&self) -> IconName {
+        IconName::SearchCode
+    }
+
+    fn input_schema(&self, format: LanguageModelToolSchemaFormat) -> Result<serde_json::Value> {
+        json_schema_for::<SemanticSearchToolInput>(format)
+    }
+
+    fn ui_text(&self, input: &serde_json::Value) -> String {
+        match serde_json::from_value::<SemanticSearchToolInput>(input.clone()) {
+            Ok(input) => match input.queries.first() {
+                Some(query) => format!("Search codebase for {}", MarkdownInlineCode(query)),
+                None => "Search codebase".to_string(),
+            },
+            Err(_) => "Search codebase".to_string(),
+        }
+    }
+
+    fn run(
+        self: Arc<Self>,
+        input: serde_json::Value,
+        _request: Arc<LanguageModelRequest>,
+        project: Entity<Project>,
+        _action_log: Entity<ActionLog>,
+        _model: Arc<dyn LanguageModel>,
+        _window: Option<AnyWindowHandle>,
+        cx: &mut App,
+    ) -> ToolResult {
+        let input = match serde_json::from_value::<SemanticSearchToolInput>(input) {
+            Ok(input) => input,
+            Err(error) => {
+                return Task::ready(Err(anyhow!("Failed to parse input: {}", error))).into();
+            }
+        };
+        if input.queries.is_empty() {
+            return Task::ready(Err(anyhow!("At least one query is required"))).into();
+        }
+        if !cx.has_global::<SemanticDb>() {
+            return Task::ready(Err(anyhow!("The semantic index is not available"))).into();
+        }
+
+        let limit = input.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+        let project_index = cx.update_global::<SemanticDb, _>(|semantic_db, cx| {
+            semantic_db.index_for_project(project, cx)
+        });
+        let project_index = project_index.read(cx);
+        let still_indexing = project_index.status() != Status::Idle;
+        let fs = project_index.fs();
+        let search = project_index.search(input.queries, limit, cx);
+
+        cx.spawn(async move |cx| {
+            let results = search.await?;
+            let results = SemanticDb::load_results(results, &fs, cx).await?;
+
+            let mut output = String::new();
+            if still_indexing {
+                output.push_str(
+                    "The project is still being indexed, so these results may be incomplete.\n",
+                );
+            }
+            if results.is_empty() {
+                output.push_str("No results found");
+            } else {
+                write_results(&results, &mut output)?;
+            }
+            Ok(output.into())
+        })
+        .into()
+    }
+}
+
+fn write_results(results: &[LoadedSearchResult], output: &mut String) -> std::fmt::Result {
+    let mut last_path = None;
+    for result in results {
+        if last_path != Some(&result.full_path) {
+            writeln!(output, "\n## Matches in {}", result.full_path.display())?;
+            last_path = Some(&result.full_path);
+        }
+
+        let start_row = result.row_range.start() + 1;
+        let end_row = result.row_range.end() + 1;
+        if start_row == end_row {
+            writeln!(output, "\n### L{start_row}")?;
+        } else {
+            writeln!(output, "\n### L{start_row}-{end_row}")?;
+        }
+        output.push_str("```\n");
+        output.push_str(&result.excerpt_content);
+        if !result.excerpt_content.ends_with('\n') {
+            output.push('\n');
+        }
+        output.push_str("```\n");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use feature_flags::FeatureFlagAppExt;
+    use futures::{FutureExt, future::BoxFuture};
+    use gpui::{AppContext, TestAppContext};
+    use language_model::fake_provider::FakeLanguageModel;
+    use project::FakeFs;
+    use semantic_index::{Embedding, EmbeddingProvider, TextToEmbed};
+    use serde_json::json;
+    use settings::SettingsStore;
+    use std::{
+        future,
+        path::{Path, PathBuf},
+    };
+    use util::{path, separator};
+
+    /// Embeds texts that mention the gutter close to each other, and far from the rest.
+    struct FakeEmbeddingProvider;
+
+    impl EmbeddingProvider for FakeEmbeddingProvider {
+        fn embed<'a>(
+            &'a self,
+            texts: &'a [TextToEmbed<'a>],
+        ) -> BoxFuture<'a, Result<Vec<Embedding>>> {
+            let embeddings = texts
+                .iter()
+                .map(|to_embed| {
+                    let x = if to_embed.text.contains("gutter") {
+                        1.
+                    } else {
+                        -1.
+                    };
+                    Ok(Embedding::new(vec![x, 0.1]))
+                })
+                .collect();
+            future::ready(embeddings).boxed()
+        }
+
+        fn batch_size(&self) -> usize {
+            16
+        }
+    }
+
+    #[gpui::test]
+    async fn test_semantic_search_tool(cx: &mut TestAppContext) {
+        init_test(cx);
+        cx.executor().allow_parking();
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            path!("/project"),
+            json!({
+                "src": {
+                    "gutter.rs": "fn gutter_width(em_width: f32) -> f32 {\n    em_width * 4.\n}\n",
+                    "buffer.rs": "struct Buffer {\n    text: String,\n}\n",
+                }
+            }),
+        )
+        .await;
+        let project = Project::test(fs, [path!("/project").as_ref()], cx).await;
+
+        let input = json!({ "queries": ["width of the gutter"] });
+        let error = run_semantic_search_tool(input.clone(), project.clone(), cx)
+            .await
+            .unwrap_err();
+        assert_eq!(error.to_string(), "The semantic index is not available");
+
+        let db_dir = tempfile::tempdir().unwrap();
+        let semantic_db = SemanticDb::new(
+            db_dir.path().into(),
+            Arc::new(FakeEmbeddingProvider),
+            &mut cx.to_async(),
+        )
+        .await
+        .unwrap();
+        let project_index = cx.update(|cx| {
+            cx.set_global(semantic_db);
+            cx.update_global::<SemanticDb, _>(|semantic_db, cx| {
+                semantic_db.index_for_project(project.clone(), cx)
+            })
+        });
+        cx.run_until_parked();
+        while cx.read(|cx| project_index.read(cx).status()) != Status::Idle {
+            cx.run_until_parked();
+        }
+
+        let output = run_semantic_search_tool(
+            json!({ "queries": ["width of the gutter"], "limit": 1 }),
+            project.clone(),
+            cx,
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            output,
+            format!(
+                "\n## Matches in {}\n\n### L1-3\n```\n\
+                 fn gutter_width(em_width: f32) -> f32 {{\n    em_width * 4.\n}}\n```\n",
+                separator!("project/src/gutter.rs")
+            )
+        );
+
+        let output = run_semantic_search_tool(json!({ "queries": [] }), project, cx).await;
+        assert_eq!(
+            output.unwrap_err().to_string(),
+            "At least one query is required"
+        );
+    }
+
+    async fn run_semantic_search_tool(
+        input: serde_json::Value,
+        project: Entity<Project>,
+        cx: &mut TestAppContext,
+    ) -> Result<String> {
+        let action_log = cx.new(|_| ActionLog::new(project.clone()));
+        let model = Arc::new(FakeLanguageModel::default());
+        let task = cx.update(|cx| {
+            Arc::new(SemanticSearchTool).run(
+                input,
+                Arc::default(),
+                project,
+                action_log,
+                model,
+                None,
+                cx,
+            )
+        });
+        let output = task.output.await?;
+        Ok(output.content.as_str().unwrap().to_string())
+    }
+
+    fn init_test(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+            language::init(cx);
+            Project::init_settings(cx);
+            // Indexing is staff-flagged.
+            cx.update_flags(true, vec![]);
+        });
+    }
+
+    #[test]
+    fn test_write_results() {
+        let results = vec![
+            LoadedSearchResult {
+                path: Path::new("src/anchor.rs").into(),
+                full_path: PathBuf::from("project/src/anchor.rs"),
+                excerpt_content: "fn to_point(&self) -> Point {\n    todo!()\n}\n".into(),
+                row_range: 9..=11,
+                query_index: 0,
+            },
+            LoadedSearchResult {
+                path: Path::new("src/anchor.rs").into(),
+                full_path: PathBuf::from("project/src/anchor.rs"),
+                excerpt_content: "const MIN: Anchor = Anchor;".into(),
+                row_range: 19..=19,
+                query_index: 0,
+            },
+            LoadedSearchResult {
+                path: Path::new("src/point.rs").into(),
+                full_path: PathBuf::from("project/src/point.rs"),
+                excerpt_content: "struct Point;\n".into(),
+                row_range: 0..=0,
+                query_index: 1,
+            },
+        ];
+
+        let mut output = String::new();
+        write_results(&results, &mut output).unwrap();
+        assert_eq!(
+            output,
+            "\n## Matches in project/src/anchor.rs\n\
+             \n### L10-12\n```\nfn to_point(&self) -> Point {\n    todo!()\n}\n```\n\
+             \n### L20\n```\nconst MIN: Anchor = Anchor;\n```\n\
+             \n## Matches in project/src/point.rs\n\
+             \n### L1\n```\nstruct Point;\n```\n"
+        );
+    }
+}
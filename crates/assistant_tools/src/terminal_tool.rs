@@ -1,6 +1,8 @@
+mod sandbox;
+
 use crate::schema::json_schema_for;
 use anyhow::{Context as _, Result, anyhow};
-use assistant_tool::{ActionLog, Tool, ToolCard, ToolResult, ToolUseStatus};
+use assistant_tool::{ActionLog, Tool, ToolCard, ToolPermissionSubject, ToolResult, ToolUseStatus};
 use futures::{FutureExt as _, future::Shared};
 use gpui::{
     AnyWindowHandle, App, AppContext, Empty, Entity, EntityId, Task, TextStyleRefinement,
@@ -11,6 +13,7 @@ use language_model::{LanguageModel, LanguageModelRequest, LanguageModelToolSchem
 use markdown::{Markdown, MarkdownElement, MarkdownStyle};
 use portable_pty::{CommandBuilder, PtySize, native_pty_system};
 use project::{Project, terminals::TerminalKind};
+use sandbox::Sandbox;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use settings::Settings;
@@ -77,6 +80,13 @@ impl Tool for TerminalTool {
         true
     }
 
+    fn permission_subject(&self, input: &serde_json::Value) -> ToolPermissionSubject {
+        match serde_json::from_value::<TerminalToolInput>(input.clone()) {
+            Ok(input) => ToolPermissionSubject::TerminalCommand(input.command),
+            Err(_) => ToolPermissionSubject::None,
+        }
+    }
+
     fn description(&self) -> String {
         include_str!("./terminal_tool/description.md").to_string()
     }
@@ -129,6 +139,10 @@ impl Tool for TerminalTool {
             Ok(dir) => dir,
             Err(err) => return Task::ready(Err(err)).into(),
         };
+        let sandbox = match Sandbox::for_project(&project, cx) {
+            Ok(sandbox) => sandbox,
+            Err(err) => return Task::ready(Err(err)).into(),
+        };
         let program = self.determine_shell.clone();
         let command = if cfg!(windows) {
             format!("$null | & {{{}}}", input.command.replace("\"", "'"))
@@ -165,7 +179,7 @@ impl Tool for TerminalTool {
             let task = cx.background_spawn(async move {
                 let env = env.await;
                 let pty_system = native_pty_system();
-                let program = program.await;
+                let (program, args) = sandbox_command(sandbox.as_ref(), program.await, args);
                 let mut cmd = CommandBuilder::new(program);
                 cmd.args(args);
                 for (k, v) in env {
@@ -194,7 +208,7 @@ impl Tool for TerminalTool {
                 let content = content.trim_start().trim_start_matches("^D");
                 let exit_status = child.wait()?;
                 let (processed_content, _) =
-                    process_content(content, &input.command, Some(exit_status));
+                    process_content(content, &input.command, Some(exit_status), sandbox.as_ref());
                 Ok(processed_content.into())
             });
             return ToolResult {
@@ -205,8 +219,9 @@ impl Tool for TerminalTool {
 
         let terminal = cx.spawn({
             let project = project.downgrade();
+            let sandbox = sandbox.clone();
             async move |cx| {
-                let program = program.await;
+                let (program, args) = sandbox_command(sandbox.as_ref(), program.await, args);
                 let env = env.await;
                 let terminal = project
                     .update(cx, |project, cx| {
@@ -276,7 +291,14 @@ impl Tool for TerminalTool {
                     .update(cx, |terminal, cx| terminal.wait_for_completed_task(cx))?
                     .await;
                 let (content, content_line_count) = terminal.update(cx, |terminal, _| {
-                    (terminal.get_content(), terminal.total_lines())
+                    // When the shell reports the command that was run, only attach its own
+                    // output rather than everything the shell printed. Blocks reported for
+                    // other commands, such as those the command ran itself, are skipped.
+                    let content = terminal
+                        .last_output_of_command(&input.command)
+                        .map(|command_output| command_output.output)
+                        .unwrap_or_else(|| terminal.get_content());
+                    (content, terminal.total_lines())
                 })?;
 
                 let previous_len = content.len();
@@ -284,6 +306,7 @@ impl Tool for TerminalTool {
                     &content,
                     &input.command,
                     exit_status.map(portable_pty::ExitStatus::from),
+                    sandbox.as_ref(),
                 );
 
                 let _ = card.update(cx, |card, _| {
@@ -307,11 +330,29 @@ impl Tool for TerminalTool {
     }
 }
 
+fn sandbox_command(
+    sandbox: Option<&Sandbox>,
+    program: String,
+    args: Vec<String>,
+) -> (String, Vec<String>) {
+    match sandbox {
+        Some(sandbox) => sandbox.wrap_command(program, args),
+        None => (program, args),
+    }
+}
+
 fn process_content(
     content: &str,
     command: &str,
     exit_status: Option<portable_pty::ExitStatus>,
+    sandbox: Option<&Sandbox>,
 ) -> (String, bool) {
+    let sandbox_explanation = exit_status
+        .as_ref()
+        .is_none_or(|exit_status| !exit_status.success())
+        .then(|| sandbox.map(Sandbox::explain_failure))
+        .flatten();
+
     let should_truncate = content.len() > COMMAND_OUTPUT_LIMIT;
 
     let content = if should_truncate {
@@ -365,6 +406,10 @@ fn process_content(
             )
         }
     };
+    let content = match sandbox_explanation {
+        Some(explanation) => format!("{content}\n\n{explanation}"),
+        None => content,
+    };
     (content, is_empty)
 }
 
@@ -674,6 +719,8 @@ mod tests {
     use theme::ThemeSettings;
     use util::{ResultExt as _, test::TempTree};
 
+    use assistant_settings::AssistantSettings;
+
     use super::*;
 
     fn init_test(executor: &BackgroundExecutor, cx: &mut TestAppContext) {
@@ -690,6 +737,7 @@ mod tests {
             ThemeSettings::register(cx);
             TerminalSettings::register(cx);
             EditorSettings::register(cx);
+            AssistantSettings::register(cx);
         });
     }
 
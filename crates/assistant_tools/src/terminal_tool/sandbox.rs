@@ -0,0 +1,380 @@
+mod seccomp;
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use anyhow::{Context as _, Result, anyhow, bail};
+use assistant_settings::{AssistantSettings, TerminalSandbox};
+use gpui::{App, Entity};
+use project::Project;
+use settings::Settings as _;
+use tempfile::TempDir;
+
+const BWRAP: &str = "bwrap";
+
+/// Opens the seccomp filter and the file that records whether the command started, then
+/// runs bubblewrap with the filter. Its arguments are the two paths, followed by those of
+/// bubblewrap.
+const LAUNCHER_SCRIPT: &str = r#"exec 3<"$1" 5>"$2" && shift 2 && exec bwrap --seccomp 3 "$@""#;
+
+/// Runs inside the sandbox, once it's set up, and records that before running the command
+/// without access to the record.
+const STARTER_SCRIPT: &str = r#"printf started >&5 && exec 5>&- && exec "$0" "$@""#;
+
+/// Runs terminal commands under bubblewrap, so that they can only write to the
+/// project's worktrees and, optionally, can't access the network.
+///
+/// bubblewrap relies on unprivileged user namespaces, so it doesn't need root. Commands
+/// get their own namespaces, don't see the sockets under `/run`, such as the session's
+/// D-Bus, and can't change the hooks or configuration of the worktrees' git
+/// repositories, which git would run outside of the sandbox.
+#[derive(Debug, Clone)]
+pub struct Sandbox {
+    writable_paths: Vec<PathBuf>,
+    allow_network: bool,
+    /// Holds the seccomp filter and the record of whether the command started.
+    state_dir: Arc<TempDir>,
+}
+
+impl Sandbox {
+    /// Returns the sandbox that the active agent profile configures for
+    /// commands run in `project`, if any.
+    pub fn for_project(project: &Entity<Project>, cx: &App) -> Result<Option<Self>> {
+        let Some(profile) = AssistantSettings::get_global(cx).active_profile() else {
+            return Ok(None);
+        };
+        if !profile.terminal_sandbox.enabled {
+            return Ok(None);
+        }
+
+        let project = project.read(cx);
+        if !project.is_local() {
+            bail!("The terminal sandbox isn't supported in remote projects.");
+        }
+        Self::check_supported()?;
+
+        Self::new(
+            &profile.terminal_sandbox,
+            project
+                .worktrees(cx)
+                .map(|worktree| worktree.read(cx).abs_path().to_path_buf()),
+        )
+        .map(Some)
+    }
+
+    pub fn new(
+        settings: &TerminalSandbox,
+        worktree_paths: impl IntoIterator<Item = PathBuf>,
+    ) -> Result<Self> {
+        let writable_paths = worktree_paths
+            .into_iter()
+            .chain(
+                settings
+                    .writable_paths
+                    .iter()
+                    .map(|path| match path.strip_prefix("~/") {
+                        Some(path) => util::paths::home_dir().join(path),
+                        None => PathBuf::from(path),
+                    }),
+            )
+            .collect();
+
+        let state_dir = tempfile::tempdir().context("creating the terminal sandbox's state")?;
+        let filter = seccomp::filter()
+            .context("The terminal sandbox isn't supported on this architecture.")?;
+        fs::write(state_dir.path().join("seccomp.bpf"), filter)
+            .context("writing the terminal sandbox's seccomp filter")?;
+
+        Ok(Self {
+            writable_paths,
+            allow_network: settings.allow_network,
+            state_dir: Arc::new(state_dir),
+        })
+    }
+
+    /// Returns an error if commands can't be sandboxed on this system.
+    pub fn check_supported() -> Result<()> {
+        if !cfg!(target_os = "linux") {
+            bail!("The terminal sandbox is only supported on Linux.");
+        }
+        if !seccomp::is_supported() {
+            bail!("The terminal sandbox isn't supported on this architecture.");
+        }
+        which::which(BWRAP).map(|_| ()).map_err(|_| {
+            anyhow!("The terminal sandbox requires bubblewrap (`bwrap`) to be installed.")
+        })
+    }
+
+    fn seccomp_filter_path(&self) -> PathBuf {
+        self.state_dir.path().join("seccomp.bpf")
+    }
+
+    fn started_path(&self) -> PathBuf {
+        self.state_dir.path().join("started")
+    }
+
+    /// Returns the program and arguments that run `program` with `args` inside the sandbox.
+    pub fn wrap_command(&self, program: String, args: Vec<String>) -> (String, Vec<String>) {
+        let mut sandbox_args = vec!["--unshare-all".to_string()];
+        if self.allow_network {
+            sandbox_args.push("--share-net".into());
+        }
+        sandbox_args.extend(
+            [
+                "--die-with-parent",
+                // Prevents the command from injecting input into the terminal with `TIOCSTI`.
+                "--new-session",
+                "--unsetenv",
+                "DBUS_SESSION_BUS_ADDRESS",
+                // Everything is read-only, apart from a private `/tmp`...
+                "--ro-bind",
+                "/",
+                "/",
+                "--dev",
+                "/dev",
+                "--proc",
+                "/proc",
+                "--tmpfs",
+                "/tmp",
+                // ...and the sockets of the user's session and system services are hidden.
+                "--tmpfs",
+                "/run",
+            ]
+            .map(String::from),
+        );
+        // `/var/run` is usually a link to `/run`.
+        if fs::symlink_metadata("/var/run").is_ok_and(|metadata| metadata.is_dir()) {
+            sandbox_args.extend(["--tmpfs".into(), "/var/run".into()]);
+        }
+        // The writable paths are mounted over the rest.
+        for path in &self.writable_paths {
+            let path_arg = path.to_string_lossy().to_string();
+            sandbox_args.extend(["--bind-try".into(), path_arg.clone(), path_arg]);
+            protect_git_dir(path, &mut sandbox_args);
+        }
+        sandbox_args.extend([
+            "--".into(),
+            "sh".into(),
+            "-c".into(),
+            STARTER_SCRIPT.into(),
+            program,
+        ]);
+        sandbox_args.extend(args);
+
+        let mut launcher_args = vec![
+            "-c".to_string(),
+            LAUNCHER_SCRIPT.into(),
+            "sandbox".into(),
+            self.seccomp_filter_path().to_string_lossy().to_string(),
+            self.started_path().to_string_lossy().to_string(),
+        ];
+        launcher_args.extend(sandbox_args);
+        ("sh".into(), launcher_args)
+    }
+
+    /// Explains how the sandbox may have caused a sandboxed command to fail, so that the
+    /// model can work around it.
+    pub fn explain_failure(&self) -> String {
+        let started = fs::read(self.started_path()).is_ok_and(|record| !record.is_empty());
+        if !started {
+            return "The sandbox for terminal commands could not be set up, so the command didn't run. \
+                Ask the user to check that bubblewrap can create unprivileged user namespaces."
+                .to_string();
+        }
+
+        let writable_paths = self
+            .writable_paths
+            .iter()
+            .map(|path| format!("`{}`", path.display()))
+            .chain(["`/tmp`".to_string()])
+            .collect::<Vec<_>>()
+            .join(", ");
+        let mut explanation = format!(
+            "The command ran in a sandbox, which may have caused it to fail. \
+            The sandbox only allows writing to {writable_paths}, and changes to `/tmp` \
+            are discarded when the command exits. Git hooks and configuration can't be changed."
+        );
+        if !self.allow_network {
+            explanation.push_str(" The network can't be accessed.");
+        }
+        explanation.push_str(
+            " System calls that manage mounts, kernel modules, keyrings or user namespaces \
+            fail with `EPERM`.",
+        );
+        explanation
+    }
+}
+
+/// Keeps the hooks and configuration of the git repository in `worktree_path` read-only,
+/// since git runs the commands they contain outside of the sandbox.
+fn protect_git_dir(worktree_path: &Path, sandbox_args: &mut Vec<String>) {
+    let git_path = worktree_path.join(".git");
+    let Ok(metadata) = fs::metadata(&git_path) else {
+        return;
+    };
+    let path_arg = |path: &Path| path.to_string_lossy().to_string();
+    // A `.git` file points to the repository, which is either outside of the writable paths
+    // or protected as a worktree of its own.
+    if !metadata.is_dir() {
+        let git_path = path_arg(&git_path);
+        sandbox_args.extend(["--ro-bind".into(), git_path.clone(), git_path]);
+        return;
+    }
+
+    // Mounting the repository over itself prevents it from being replaced by renaming it.
+    let git_path_arg = path_arg(&git_path);
+    sandbox_args.extend(["--bind".into(), git_path_arg.clone(), git_path_arg]);
+    for read_only in ["config", "modules"] {
+        let path = path_arg(&git_path.join(read_only));
+        sandbox_args.extend(["--ro-bind-try".into(), path.clone(), path]);
+    }
+    let hooks_path = git_path.join("hooks");
+    let hooks_path_arg = path_arg(&hooks_path);
+    if hooks_path.is_dir() {
+        sandbox_args.extend(["--ro-bind".into(), hooks_path_arg.clone(), hooks_path_arg]);
+    } else {
+        // Otherwise the command could create the hooks.
+        sandbox_args.extend([
+            "--tmpfs".into(),
+            hooks_path_arg.clone(),
+            "--remount-ro".into(),
+            hooks_path_arg,
+        ]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::process::Command;
+
+    use super::*;
+
+    fn sandbox(writable_paths: &[&str], allow_network: bool) -> Sandbox {
+        Sandbox::new(
+            &TerminalSandbox {
+                enabled: true,
+                allow_network,
+                writable_paths: writable_paths.iter().map(|path| path.to_string()).collect(),
+            },
+            [PathBuf::from("/projects/zed")],
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_wrap_command() {
+        let sandbox = sandbox(&["/opt/cache"], false);
+        let (program, args) =
+            sandbox.wrap_command("bash".into(), vec!["-c".into(), "cargo test".into()]);
+        assert_eq!(program, "sh");
+        assert_eq!(args[1], LAUNCHER_SCRIPT);
+        assert_eq!(PathBuf::from(&args[3]), sandbox.seccomp_filter_path());
+        assert!(sandbox.seccomp_filter_path().exists());
+        assert!(args.iter().any(|arg| arg == "--unshare-all"));
+        assert!(!args.iter().any(|arg| arg == "--share-net"));
+        let args = args.join(" ");
+        assert!(args.contains("--ro-bind / / "));
+        assert!(args.contains("--tmpfs /run "));
+        assert!(args.contains("--bind-try /projects/zed /projects/zed"));
+        assert!(args.contains("--bind-try /opt/cache /opt/cache"));
+        assert!(args.ends_with(&format!("-- sh -c {STARTER_SCRIPT} bash -c cargo test")));
+
+        let (_, args) = self::sandbox(&[], true).wrap_command("sh".into(), Vec::new());
+        assert!(args.iter().any(|arg| arg == "--share-net"));
+    }
+
+    #[test]
+    fn test_wrap_command_protects_git_dir() {
+        let project = tempfile::tempdir().unwrap();
+        fs::create_dir_all(project.path().join(".git/hooks")).unwrap();
+        let sandbox =
+            Sandbox::new(&TerminalSandbox::default(), [project.path().to_path_buf()]).unwrap();
+        let (_, args) = sandbox.wrap_command("sh".into(), Vec::new());
+        let args = args.join(" ");
+        let git_path = project.path().join(".git");
+        let git_path = git_path.display();
+        assert!(args.contains(&format!("--bind {git_path} {git_path}")));
+        assert!(args.contains(&format!("--ro-bind {git_path}/hooks {git_path}/hooks")));
+        assert!(args.contains(&format!(
+            "--ro-bind-try {git_path}/config {git_path}/config"
+        )));
+
+        fs::remove_dir(project.path().join(".git/hooks")).unwrap();
+        let (_, args) = sandbox.wrap_command("sh".into(), Vec::new());
+        assert!(args.join(" ").contains(&format!(
+            "--tmpfs {git_path}/hooks --remount-ro {git_path}/hooks"
+        )));
+    }
+
+    #[test]
+    fn test_explain_failure() {
+        let sandbox = sandbox(&[], false);
+        assert!(sandbox.explain_failure().contains("could not be set up"));
+
+        fs::write(sandbox.started_path(), "started").unwrap();
+        let explanation = sandbox.explain_failure();
+        assert!(explanation.contains("`/projects/zed`"));
+        assert!(explanation.contains("network"));
+        assert!(
+            !self::sandbox(&[], true)
+                .explain_failure()
+                .contains("network")
+        );
+    }
+
+    #[test]
+    fn test_sandboxed_writes() {
+        // Only run where bubblewrap can create namespaces, which doesn't require root.
+        if Sandbox::check_supported().is_err()
+            || !Command::new(BWRAP)
+                .args(["--unshare-all", "--ro-bind", "/", "/", "true"])
+                .status()
+                .is_ok_and(|status| status.success())
+        {
+            return;
+        }
+
+        let project = tempfile::tempdir().unwrap();
+        fs::create_dir_all(project.path().join(".git/hooks")).unwrap();
+        fs::write(project.path().join(".git/config"), "[core]\n").unwrap();
+        fs::write(project.path().join(".git/HEAD"), "ref: refs/heads/main\n").unwrap();
+        let outside = tempfile::tempdir_in(util::paths::home_dir()).unwrap();
+        let sandbox =
+            Sandbox::new(&TerminalSandbox::default(), [project.path().to_path_buf()]).unwrap();
+        let run = |command: String| {
+            let (program, args) = sandbox.wrap_command("sh".into(), vec!["-c".into(), command]);
+            Command::new(program).args(args).output().unwrap()
+        };
+        let project_dir = project.path().display();
+
+        let output = run(format!("touch {project_dir}/inside"));
+        assert!(output.status.success());
+        assert!(project.path().join("inside").exists());
+        assert!(!sandbox.explain_failure().contains("could not be set up"));
+
+        let output = run(format!("touch {}/outside", outside.path().display()));
+        assert!(!output.status.success());
+        assert!(!outside.path().join("outside").exists());
+
+        // Git runs hooks and reads its configuration outside of the sandbox.
+        let output = run(format!("touch {project_dir}/.git/hooks/pre-commit"));
+        assert!(!output.status.success());
+        assert!(!project.path().join(".git/hooks/pre-commit").exists());
+        let output = run(format!(
+            "echo '  fsmonitor = evil' >> {project_dir}/.git/config"
+        ));
+        assert!(!output.status.success());
+        let output = run(format!("mv {project_dir}/.git {project_dir}/.git-moved"));
+        assert!(!output.status.success());
+        // The rest of the repository can be changed, so that git commands work.
+        let output = run(format!("touch {project_dir}/.git/HEAD"));
+        assert!(output.status.success());
+
+        // The session's sockets are hidden.
+        let output = run("test -z \"$(ls -A /run)\"".into());
+        assert!(output.status.success());
+    }
+}
@@ -0,0 +1,231 @@
+//! A seccomp filter for sandboxed commands, in the classic BPF format that bubblewrap's
+//! `--seccomp` option loads.
+//!
+//! The filter denies the system calls that could reach beyond the sandbox's namespaces:
+//! the kernel keyring that is shared with the user's session, mounting and module loading,
+//! creating nested user namespaces, and injecting input into the terminal.
+
+/// The errno returned by denied system calls.
+const EPERM: u32 = 1;
+/// The errno returned by `clone3`, whose flags can't be inspected, so that the C library
+/// falls back to `clone`.
+const ENOSYS: u32 = 38;
+
+const CLONE_NEWUSER: u32 = 0x1000_0000;
+const TIOCSTI: u32 = 0x5412;
+const TIOCLINUX: u32 = 0x541c;
+
+const BPF_LD_W_ABS: u16 = 0x20;
+const BPF_JMP_JEQ_K: u16 = 0x15;
+const BPF_JMP_JGE_K: u16 = 0x35;
+const BPF_JMP_JSET_K: u16 = 0x45;
+const BPF_RET_K: u16 = 0x06;
+
+const SECCOMP_RET_KILL_PROCESS: u32 = 0x8000_0000;
+const SECCOMP_RET_ERRNO: u32 = 0x0005_0000;
+const SECCOMP_RET_ALLOW: u32 = 0x7fff_0000;
+
+/// Offsets into `struct seccomp_data`. Arguments are 64 bits wide, and the filter only
+/// reads their low half, which comes first on the little-endian architectures we support.
+const SYSCALL_NR_OFFSET: u32 = 0;
+const ARCH_OFFSET: u32 = 4;
+const fn arg_offset(index: u32) -> u32 {
+    16 + 8 * index
+}
+
+struct Syscalls {
+    audit_arch: u32,
+    /// System calls at or above this number use another ABI of the architecture, such as
+    /// x32, which the filter doesn't cover.
+    foreign_abi_start: Option<u32>,
+    denied: &'static [u32],
+    clone: u32,
+    clone3: u32,
+    unshare: u32,
+    ioctl: u32,
+}
+
+#[cfg(target_arch = "x86_64")]
+const SYSCALLS: Option<Syscalls> = Some(Syscalls {
+    audit_arch: 0xc000_003e,
+    foreign_abi_start: Some(0x4000_0000),
+    denied: &[
+        103, // syslog
+        155, // pivot_root
+        161, // chroot
+        165, // mount
+        166, // umount2
+        175, // init_module
+        176, // delete_module
+        246, // kexec_load
+        248, // add_key
+        249, // request_key
+        250, // keyctl
+        298, // perf_event_open
+        304, // open_by_handle_at
+        313, // finit_module
+        321, // bpf
+        323, // userfaultfd
+        425, // io_uring_setup
+        426, // io_uring_enter
+        427, // io_uring_register
+    ],
+    clone: 56,
+    clone3: 435,
+    unshare: 272,
+    ioctl: 16,
+});
+
+#[cfg(target_arch = "aarch64")]
+const SYSCALLS: Option<Syscalls> = Some(Syscalls {
+    audit_arch: 0xc000_00b7,
+    foreign_abi_start: None,
+    denied: &[
+        39,  // umount2
+        40,  // mount
+        41,  // pivot_root
+        51,  // chroot
+        104, // kexec_load
+        105, // init_module
+        106, // delete_module
+        116, // syslog
+        217, // add_key
+        218, // request_key
+        219, // keyctl
+        241, // perf_event_open
+        265, // open_by_handle_at
+        273, // finit_module
+        280, // bpf
+        282, // userfaultfd
+        425, // io_uring_setup
+        426, // io_uring_enter
+        427, // io_uring_register
+    ],
+    clone: 220,
+    clone3: 435,
+    unshare: 97,
+    ioctl: 29,
+});
+
+#[cfg(not(any(target_arch = "x86_64", target_arch = "aarch64")))]
+const SYSCALLS: Option<Syscalls> = None;
+
+pub(super) fn is_supported() -> bool {
+    SYSCALLS.is_some()
+}
+
+/// Returns the compiled filter for the current architecture, if it's supported.
+pub(super) fn filter() -> Option<Vec<u8>> {
+    let syscalls = SYSCALLS?;
+    let mut program = Program::default();
+
+    // Kill processes that use another architecture's system call numbers.
+    program.load(ARCH_OFFSET);
+    program.jump(BPF_JMP_JEQ_K, syscalls.audit_arch, 1, 0);
+    program.ret(SECCOMP_RET_KILL_PROCESS);
+    program.load(SYSCALL_NR_OFFSET);
+    if let Some(foreign_abi_start) = syscalls.foreign_abi_start {
+        program.jump(BPF_JMP_JGE_K, foreign_abi_start, 0, 1);
+        program.ret(SECCOMP_RET_KILL_PROCESS);
+    }
+
+    for &syscall in syscalls.denied {
+        program.jump(BPF_JMP_JEQ_K, syscall, 0, 1);
+        program.ret(SECCOMP_RET_ERRNO | EPERM);
+    }
+    program.jump(BPF_JMP_JEQ_K, syscalls.clone3, 0, 1);
+    program.ret(SECCOMP_RET_ERRNO | ENOSYS);
+
+    // The checks of arguments replace the system call number, so they either deny the
+    // call or allow it.
+    for syscall in [syscalls.clone, syscalls.unshare] {
+        program.jump(BPF_JMP_JEQ_K, syscall, 0, 4);
+        program.load(arg_offset(0));
+        program.jump(BPF_JMP_JSET_K, CLONE_NEWUSER, 1, 0);
+        program.ret(SECCOMP_RET_ALLOW);
+        program.ret(SECCOMP_RET_ERRNO | EPERM);
+    }
+    program.jump(BPF_JMP_JEQ_K, syscalls.ioctl, 0, 5);
+    program.load(arg_offset(1));
+    program.jump(BPF_JMP_JEQ_K, TIOCSTI, 2, 0);
+    program.jump(BPF_JMP_JEQ_K, TIOCLINUX, 1, 0);
+    program.ret(SECCOMP_RET_ALLOW);
+    program.ret(SECCOMP_RET_ERRNO | EPERM);
+
+    program.ret(SECCOMP_RET_ALLOW);
+    Some(program.bytes)
+}
+
+/// A sequence of `struct sock_filter` instructions.
+#[derive(Default)]
+struct Program {
+    bytes: Vec<u8>,
+}
+
+impl Program {
+    fn push(&mut self, code: u16, jump_if_true: u8, jump_if_false: u8, k: u32) {
+        self.bytes.extend(code.to_ne_bytes());
+        self.bytes.push(jump_if_true);
+        self.bytes.push(jump_if_false);
+        self.bytes.extend(k.to_ne_bytes());
+    }
+
+    fn load(&mut self, offset: u32) {
+        self.push(BPF_LD_W_ABS, 0, 0, offset);
+    }
+
+    /// Skips `jump_if_true` or `jump_if_false` instructions, depending on the comparison
+    /// of the loaded value with `k`.
+    fn jump(&mut self, code: u16, k: u32, jump_if_true: u8, jump_if_false: u8) {
+        self.push(code, jump_if_true, jump_if_false, k);
+    }
+
+    fn ret(&mut self, action: u32) {
+        self.push(BPF_RET_K, 0, 0, action);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn instructions(filter: &[u8]) -> Vec<(u16, u8, u8, u32)> {
+        filter
+            .chunks_exact(8)
+            .map(|instruction| {
+                (
+                    u16::from_ne_bytes([instruction[0], instruction[1]]),
+                    instruction[2],
+                    instruction[3],
+                    u32::from_ne_bytes(instruction[4..8].try_into().unwrap()),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_filter() {
+        let Some(filter) = filter() else {
+            return;
+        };
+        assert_eq!(filter.len() % 8, 0);
+        let instructions = instructions(&filter);
+        assert_eq!(
+            instructions.last(),
+            Some(&(BPF_RET_K, 0, 0, SECCOMP_RET_ALLOW))
+        );
+        // Every jump lands on an instruction of the program.
+        for (ix, &(code, jump_if_true, jump_if_false, _)) in instructions.iter().enumerate() {
+            if code & 0x07 == 0x05 {
+                let target = ix + 1 + jump_if_true.max(jump_if_false) as usize;
+                assert!(target < instructions.len());
+            }
+        }
+        // Denied calls fail with `EPERM` rather than killing the command.
+        let denials = instructions
+            .iter()
+            .filter(|instruction| **instruction == (BPF_RET_K, 0, 0, SECCOMP_RET_ERRNO | EPERM))
+            .count();
+        assert_eq!(denials, SYSCALLS.unwrap().denied.len() + 3);
+    }
+}
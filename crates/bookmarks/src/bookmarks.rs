@@ -0,0 +1,423 @@
+use editor::{Editor, scroll::Autoscroll};
+use fuzzy::{StringMatch, StringMatchCandidate, match_strings};
+use gpui::{
+    App, Context, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable, ParentElement,
+    Render, Styled, Subscription, Task, WeakEntity, Window, actions,
+};
+use picker::{Picker, PickerDelegate};
+use project::{
+    Project,
+    bookmark_store::{BookmarkEntry, BookmarkStore},
+};
+use std::sync::Arc;
+use ui::{HighlightedLabel, ListItem, ListItemSpacing, prelude::*};
+use util::ResultExt;
+use workspace::{ModalView, Workspace};
+
+actions!(bookmarks, [Toggle, RenameBookmark]);
+
+pub fn init(cx: &mut App) {
+    cx.observe_new(Bookmarks::register).detach();
+    cx.observe_new(BookmarkNameModal::register).detach();
+}
+
+/// Lists the bookmarks in every file of the project, and jumps to the selected one.
+pub struct Bookmarks {
+    picker: Entity<Picker<BookmarksDelegate>>,
+}
+
+impl Bookmarks {
+    fn register(
+        workspace: &mut Workspace,
+        _window: Option<&mut Window>,
+        _: &mut Context<Workspace>,
+    ) {
+        workspace.register_action(|workspace, _: &Toggle, window, cx| {
+            let project = workspace.project().clone();
+            let handle = cx.entity().downgrade();
+            workspace.toggle_modal(window, cx, move |window, cx| {
+                Bookmarks::new(handle, project, window, cx)
+            });
+        });
+    }
+
+    fn new(
+        workspace: WeakEntity<Workspace>,
+        project: Entity<Project>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let bookmark_store = project.read(cx).bookmark_store();
+        let delegate =
+            BookmarksDelegate::new(cx.entity().downgrade(), workspace, &bookmark_store, cx);
+        let picker = cx.new(|cx| Picker::uniform_list(delegate, window, cx));
+        Self { picker }
+    }
+}
+
+impl Render for Bookmarks {
+    fn render(&mut self, _window: &mut Window, _cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex().w(rems(34.)).child(self.picker.clone())
+    }
+}
+
+impl Focusable for Bookmarks {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl EventEmitter<DismissEvent> for Bookmarks {}
+impl ModalView for Bookmarks {}
+
+struct BookmarkCandidate {
+    entry: BookmarkEntry,
+    /// The bookmark's name, or the text of its line if it's unnamed.
+    label: String,
+    location: String,
+}
+
+pub struct BookmarksDelegate {
+    bookmarks: WeakEntity<Bookmarks>,
+    workspace: WeakEntity<Workspace>,
+    candidates: Vec<BookmarkCandidate>,
+    matches: Vec<StringMatch>,
+    selected_index: usize,
+}
+
+impl BookmarksDelegate {
+    fn new(
+        bookmarks: WeakEntity<Bookmarks>,
+        workspace: WeakEntity<Workspace>,
+        bookmark_store: &Entity<BookmarkStore>,
+        cx: &App,
+    ) -> Self {
+        let candidates = bookmark_store
+            .read(cx)
+            .all_bookmarks(cx)
+            .into_iter()
+            .map(|entry| {
+                let buffer = entry.buffer.read(cx);
+                let label = match &entry.bookmark.name {
+                    Some(name) => name.to_string(),
+                    None => {
+                        let snapshot = buffer.snapshot();
+                        let start = text::Point::new(entry.row, 0);
+                        let end = text::Point::new(entry.row, snapshot.line_len(entry.row));
+                        snapshot
+                            .text_for_range(start..end)
+                            .collect::<String>()
+                            .trim()
+                            .to_string()
+                    }
+                };
+                let path = buffer
+                    .file()
+                    .map(|file| file.full_path(cx))
+                    .unwrap_or_else(|| entry.path.to_path_buf());
+                let location = format!("{}:{}", path.display(), entry.row + 1);
+                BookmarkCandidate {
+                    entry,
+                    label,
+                    location,
+                }
+            })
+            .collect();
+
+        Self {
+            bookmarks,
+            workspace,
+            candidates,
+            matches: Vec::new(),
+            selected_index: 0,
+        }
+    }
+}
+
+impl PickerDelegate for BookmarksDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self, _window: &mut Window, _cx: &mut App) -> Arc<str> {
+        "Go to bookmark…".into()
+    }
+
+    fn no_matches_text(&self, _window: &mut Window, _cx: &mut App) -> Option<SharedString> {
+        Some(if self.candidates.is_empty() {
+            "No bookmarks".into()
+        } else {
+            "No matching bookmarks".into()
+        })
+    }
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(
+        &mut self,
+        ix: usize,
+        _window: &mut Window,
+        _: &mut Context<Picker<Self>>,
+    ) {
+        self.selected_index = ix;
+    }
+
+    fn update_matches(
+        &mut self,
+        query: String,
+        window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> Task<()> {
+        let background = cx.background_executor().clone();
+        let candidates = self
+            .candidates
+            .iter()
+            .enumerate()
+            .map(|(id, candidate)| {
+                StringMatchCandidate::new(
+                    id,
+                    &format!("{} {}", candidate.label, candidate.location),
+                )
+            })
+            .collect::<Vec<_>>();
+        cx.spawn_in(window, async move |this, cx| {
+            let matches = if query.is_empty() {
+                candidates
+                    .into_iter()
+                    .map(|candidate| StringMatch {
+                        candidate_id: candidate.id,
+                        string: candidate.string,
+                        positions: Vec::new(),
+                        score: 0.0,
+                    })
+                    .collect()
+            } else {
+                let mut matches = match_strings(
+                    &candidates,
+                    &query,
+                    false,
+                    100,
+                    &Default::default(),
+                    background,
+                )
+                .await;
+                // Keep bookmarks in path order, as they're listed without a query.
+                matches.sort_by_key(|mat| mat.candidate_id);
+                matches
+            };
+
+            this.update(cx, |this, cx| {
+                let delegate = &mut this.delegate;
+                delegate.matches = matches;
+                delegate.selected_index = delegate
+                    .selected_index
+                    .min(delegate.matches.len().saturating_sub(1));
+                cx.notify();
+            })
+            .log_err();
+        })
+    }
+
+    fn confirm(&mut self, secondary: bool, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let Some(candidate) = self
+            .matches
+            .get(self.selected_index)
+            .and_then(|mat| self.candidates.get(mat.candidate_id))
+        else {
+            return;
+        };
+        let buffer = candidate.entry.buffer.clone();
+        let position = candidate.entry.position;
+        self.workspace
+            .update(cx, |workspace, cx| {
+                let pane = if secondary {
+                    workspace.adjacent_pane(window, cx)
+                } else {
+                    workspace.active_pane().clone()
+                };
+                let point = position.summary::<text::Point>(&buffer.read(cx).snapshot());
+                let editor =
+                    workspace.open_project_item::<Editor>(pane, buffer, true, true, window, cx);
+                editor.update(cx, |editor, cx| {
+                    editor.change_selections(Some(Autoscroll::center()), window, cx, |s| {
+                        s.select_ranges([point..point])
+                    });
+                });
+            })
+            .log_err();
+        self.dismissed(window, cx);
+    }
+
+    fn dismissed(&mut self, _: &mut Window, cx: &mut Context<Picker<Self>>) {
+        self.bookmarks
+            .update(cx, |_, cx| cx.emit(DismissEvent))
+            .log_err();
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _: &mut Window,
+        _: &mut Context<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let mat = &self.matches[ix];
+        let candidate = &self.candidates[mat.candidate_id];
+        // The match was against the label and the location, separated by a space.
+        let location_start = candidate.label.len() + 1;
+        let label_positions = mat
+            .positions
+            .iter()
+            .copied()
+            .filter(|position| *position < candidate.label.len())
+            .collect();
+        let location_positions = mat
+            .positions
+            .iter()
+            .filter_map(|position| position.checked_sub(location_start))
+            .collect();
+
+        Some(
+            ListItem::new(ix)
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .toggle_state(selected)
+                .start_slot(
+                    Icon::new(IconName::Bookmark)
+                        .size(IconSize::Small)
+                        .color(Color::Accent),
+                )
+                .child(
+                    v_flex()
+                        .child(HighlightedLabel::new(
+                            candidate.label.clone(),
+                            label_positions,
+                        ))
+                        .child(
+                            HighlightedLabel::new(candidate.location.clone(), location_positions)
+                                .size(LabelSize::Small)
+                                .color(Color::Muted),
+                        ),
+                ),
+        )
+    }
+}
+
+/// Prompts for the name of the bookmark on the line of the editor's cursor.
+pub struct BookmarkNameModal {
+    name_editor: Entity<Editor>,
+    active_editor: Entity<Editor>,
+    _subscription: Subscription,
+}
+
+impl ModalView for BookmarkNameModal {}
+
+impl Focusable for BookmarkNameModal {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.name_editor.focus_handle(cx)
+    }
+}
+
+impl EventEmitter<DismissEvent> for BookmarkNameModal {}
+
+impl BookmarkNameModal {
+    fn register(editor: &mut Editor, _window: Option<&mut Window>, cx: &mut Context<Editor>) {
+        if !editor.mode().is_full() {
+            return;
+        }
+        let handle = cx.entity().downgrade();
+        editor
+            .register_action(move |_: &RenameBookmark, window, cx| {
+                let Some(editor) = handle.upgrade() else {
+                    return;
+                };
+                let Some(workspace) = editor.read(cx).workspace() else {
+                    return;
+                };
+                workspace.update(cx, |workspace, cx| {
+                    workspace.toggle_modal(window, cx, move |window, cx| {
+                        BookmarkNameModal::new(editor, window, cx)
+                    });
+                })
+            })
+            .detach();
+    }
+
+    fn new(active_editor: Entity<Editor>, window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let name = active_editor
+            .read(cx)
+            .bookmark_at_cursor(cx)
+            .and_then(|bookmark| bookmark.name);
+        let name_editor = cx.new(|cx| {
+            let mut editor = Editor::single_line(window, cx);
+            editor.set_placeholder_text("Bookmark name", cx);
+            if let Some(name) = name {
+                editor.set_text(name.as_ref(), window, cx);
+                editor.select_all(&editor::actions::SelectAll, window, cx);
+            }
+            editor
+        });
+        let _subscription = cx.subscribe_in(&name_editor, window, Self::on_name_editor_event);
+        Self {
+            name_editor,
+            active_editor,
+            _subscription,
+        }
+    }
+
+    fn on_name_editor_event(
+        &mut self,
+        _: &Entity<Editor>,
+        event: &editor::EditorEvent,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if let editor::EditorEvent::Blurred = event {
+            cx.emit(DismissEvent);
+        }
+    }
+
+    fn cancel(&mut self, _: &menu::Cancel, _: &mut Window, cx: &mut Context<Self>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn confirm(&mut self, _: &menu::Confirm, window: &mut Window, cx: &mut Context<Self>) {
+        let name = self.name_editor.read(cx).text(cx);
+        let name = name.trim();
+        let name = (!name.is_empty()).then(|| Arc::from(name));
+        self.active_editor.update(cx, |editor, cx| {
+            editor.set_bookmark_name_at_cursor(name, cx);
+            editor.focus_handle(cx).focus(window);
+        });
+        cx.emit(DismissEvent);
+    }
+}
+
+impl Render for BookmarkNameModal {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .w(rems(24.))
+            .elevation_2(cx)
+            .key_context("BookmarkName")
+            .on_action(cx.listener(Self::cancel))
+            .on_action(cx.listener(Self::confirm))
+            .child(
+                div()
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border_variant)
+                    .px_2()
+                    .py_1()
+                    .child(self.name_editor.clone()),
+            )
+            .child(
+                h_flex().px_2().py_1().child(
+                    Label::new("Name the bookmark on this line, or leave empty to unname it")
+                        .color(Color::Muted),
+                ),
+            )
+    }
+}
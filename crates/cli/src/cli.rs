@@ -1,6 +1,8 @@
 use collections::HashMap;
 pub use ipc_channel::ipc;
 use serde::{Deserialize, Serialize};
+use std::ffi::OsString;
+use std::path::PathBuf;
 
 #[derive(Serialize, Deserialize)]
 pub struct IpcHandshake {
@@ -31,3 +33,165 @@ pub enum CliResponse {
 /// When Zed started not as an *.app but as a binary (e.g. local development),
 /// there's a possibility to tell it to behave "regularly".
 pub const FORCE_CLI_MODE_ENV_VAR_NAME: &str = "ZED_FORCE_CLI_MODE";
+
+/// The options for running Zed's agent without opening a window. The Zed
+/// binary runs the agent, and the CLI forwards these options to it.
+///
+/// They're flags rather than a subcommand, so that `zed agent` still opens a
+/// path named `agent`.
+#[derive(clap::Args, Debug, Default, PartialEq)]
+pub struct AgentRunArgs {
+    /// Runs the agent on a project without opening a window, sending it this
+    /// prompt and printing its progress to stdout as JSON lines.
+    #[arg(
+        long = "agent-run",
+        value_name = "PROMPT",
+        group = "agent_prompt",
+        allow_hyphen_values = true
+    )]
+    pub prompt: Option<String>,
+
+    /// Like `--agent-run`, but reads the prompt from a file, or from stdin
+    /// when set to `-`.
+    #[arg(long = "agent-run-file", value_name = "PATH", group = "agent_prompt")]
+    pub prompt_file: Option<PathBuf>,
+
+    /// The agent profile to run with, such as `write` or `ask`. Defaults to
+    /// the profile selected in your settings.
+    #[arg(
+        long = "agent-profile",
+        value_name = "PROFILE",
+        requires = "agent_prompt"
+    )]
+    pub profile: Option<String>,
+
+    /// The model the agent uses, as `provider/model`. Defaults to the model
+    /// selected in your settings.
+    #[arg(
+        long = "agent-model",
+        value_name = "PROVIDER/MODEL",
+        requires = "agent_prompt"
+    )]
+    pub model: Option<String>,
+
+    /// The project directory the agent works in. Defaults to the current
+    /// directory.
+    #[arg(long = "agent-project", value_name = "DIR", requires = "agent_prompt")]
+    pub project: Option<PathBuf>,
+
+    /// Writes the changes made by the agent to this file, as a patch.
+    #[arg(
+        long = "agent-patch-file",
+        value_name = "PATH",
+        requires = "agent_prompt"
+    )]
+    pub patch_file: Option<PathBuf>,
+
+    /// The maximum number of requests the agent sends to the model. Defaults
+    /// to 50.
+    #[arg(long = "agent-max-turns", value_name = "N", requires = "agent_prompt")]
+    pub max_turns: Option<u32>,
+
+    /// Runs the agent's tool calls that would ask for confirmation in the
+    /// agent panel. By default, those calls are denied.
+    #[arg(long = "agent-approve-confirmations", requires = "agent_prompt")]
+    pub approve_confirmations: bool,
+}
+
+impl AgentRunArgs {
+    /// Whether the agent should run instead of Zed opening a window.
+    pub fn is_requested(&self) -> bool {
+        self.prompt.is_some() || self.prompt_file.is_some()
+    }
+
+    /// The command-line arguments that parse back into these options.
+    pub fn to_args(&self) -> Vec<OsString> {
+        let mut args = Vec::new();
+        let mut push = |flag: &str, value: OsString| {
+            args.push(OsString::from(flag));
+            args.push(value);
+        };
+        if let Some(prompt) = &self.prompt {
+            push("--agent-run", prompt.into());
+        }
+        if let Some(path) = &self.prompt_file {
+            push("--agent-run-file", path.into());
+        }
+        if let Some(profile) = &self.profile {
+            push("--agent-profile", profile.into());
+        }
+        if let Some(model) = &self.model {
+            push("--agent-model", model.into());
+        }
+        if let Some(project) = &self.project {
+            push("--agent-project", project.into());
+        }
+        if let Some(path) = &self.patch_file {
+            push("--agent-patch-file", path.into());
+        }
+        if let Some(max_turns) = self.max_turns {
+            push("--agent-max-turns", max_turns.to_string().into());
+        }
+        if self.approve_confirmations {
+            args.push("--agent-approve-confirmations".into());
+        }
+        args
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+
+    #[derive(Parser, Debug)]
+    struct Args {
+        paths: Vec<String>,
+        #[command(flatten)]
+        agent: AgentRunArgs,
+    }
+
+    #[test]
+    fn test_agent_is_still_a_path() {
+        let args = Args::try_parse_from(["zed", "agent"]).unwrap();
+        assert_eq!(args.paths, ["agent"]);
+        assert!(!args.agent.is_requested());
+    }
+
+    #[test]
+    fn test_agent_run_args_round_trip() {
+        let args = Args::try_parse_from([
+            "zed",
+            "--agent-run",
+            "--verbose is ignored, fix it",
+            "--agent-profile",
+            "write",
+            "--agent-model",
+            "anthropic/claude-3-7-sonnet-latest",
+            "--agent-max-turns",
+            "5",
+            "--agent-approve-confirmations",
+        ])
+        .unwrap();
+        assert!(args.agent.is_requested());
+        assert_eq!(
+            args.agent.prompt.as_deref(),
+            Some("--verbose is ignored, fix it")
+        );
+        assert!(args.paths.is_empty());
+
+        let forwarded = Args::try_parse_from(
+            std::iter::once(OsString::from("zed")).chain(args.agent.to_args()),
+        )
+        .unwrap();
+        assert_eq!(forwarded.agent, args.agent);
+    }
+
+    #[test]
+    fn test_agent_options_require_a_prompt() {
+        assert!(Args::try_parse_from(["zed", "--agent-model", "a/b"]).is_err());
+        assert!(
+            Args::try_parse_from(["zed", "--agent-run", "a", "--agent-run-file", "-"]).is_err()
+        );
+    }
+}
@@ -5,7 +5,7 @@
 
 use anyhow::{Context as _, Result};
 use clap::Parser;
-use cli::{CliRequest, CliResponse, IpcHandshake, ipc::IpcOneShotServer};
+use cli::{AgentRunArgs, CliRequest, CliResponse, IpcHandshake, ipc::IpcOneShotServer};
 use collections::HashMap;
 use parking_lot::Mutex;
 use std::{
@@ -96,6 +96,8 @@ struct Args {
     ))]
     #[arg(long)]
     uninstall: bool,
+    #[command(flatten)]
+    agent: AgentRunArgs,
 }
 
 fn parse_path_with_position(argument_str: &str) -> anyhow::Result<String> {
@@ -172,6 +174,18 @@ fn main() -> Result<()> {
         return Err(anyhow::anyhow!(msg.join("\n")));
     }
 
+    if args.agent.is_requested() {
+        let mut command = std::process::Command::new(app.path());
+        if let Some(dir) = &user_data_dir {
+            command.arg("--user-data-dir").arg(dir);
+        }
+        let status = command
+            .args(args.agent.to_args())
+            .status()
+            .context("running Zed's agent")?;
+        std::process::exit(status.code().unwrap_or(1));
+    }
+
     #[cfg(all(
         any(target_os = "linux", target_os = "macos"),
         not(feature = "no-bundled-uninstall")
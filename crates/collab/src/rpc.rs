@@ -405,7 +405,13 @@ impl Server {
             .add_request_handler(forward_mutating_project_request::<proto::GitChangeBranch>)
             .add_request_handler(forward_mutating_project_request::<proto::CheckForPushedCommits>)
             .add_message_handler(broadcast_project_message_from_host::<proto::AdvertiseContexts>)
-            .add_message_handler(update_context);
+            .add_message_handler(update_context)
+            .add_message_handler(
+                broadcast_project_message_from_host::<proto::UpdateSharedTerminals>,
+            )
+            .add_message_handler(broadcast_project_message_from_host::<proto::UpdateTerminalScreen>)
+            .add_request_handler(forward_read_only_project_request::<proto::OpenSharedTerminal>)
+            .add_request_handler(forward_mutating_project_request::<proto::TerminalInput>);
 
         Arc::new(server)
     }
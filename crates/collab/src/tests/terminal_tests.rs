@@ -0,0 +1,199 @@
+use crate::tests::TestServer;
+use call::ActiveCall;
+use futures::{StreamExt as _, channel::mpsc};
+use gpui::{BackgroundExecutor, Entity, TestAppContext};
+use project::terminals::SharedTerminal;
+use rpc::proto;
+use std::time::Duration;
+use terminal::{ScreenSnapshot, Terminal, TerminalBuilder};
+
+#[gpui::test]
+async fn test_sharing_terminals(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+    cx_c: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+    let client_c = server.create_client(cx_c, "user_c").await;
+    cx_a.update(terminal::init);
+    cx_b.update(terminal::init);
+    cx_c.update(terminal::init);
+
+    let channel_id = server
+        .make_public_channel("the-channel", &client_a, cx_a)
+        .await;
+
+    // Client A shares a project in the channel, which B and C join as guests.
+    let project_a = client_a.build_test_project(cx_a).await;
+    cx_a.update(|cx| workspace::join_channel(channel_id, client_a.app_state.clone(), None, cx))
+        .await
+        .unwrap();
+    let active_call_a = cx_a.read(ActiveCall::global);
+    let project_id = active_call_a
+        .update(cx_a, |call, cx| call.share_project(project_a.clone(), cx))
+        .await
+        .unwrap();
+    executor.run_until_parked();
+    cx_b.update(|cx| workspace::join_channel(channel_id, client_b.app_state.clone(), None, cx))
+        .await
+        .unwrap();
+    cx_c.update(|cx| workspace::join_channel(channel_id, client_c.app_state.clone(), None, cx))
+        .await
+        .unwrap();
+    executor.run_until_parked();
+
+    // B is promoted, so it can edit the project, and C remains a guest.
+    active_call_a
+        .update(cx_a, |call, cx| {
+            call.room().unwrap().update(cx, |room, cx| {
+                room.set_participant_role(
+                    client_b.user_id().unwrap(),
+                    proto::ChannelRole::Member,
+                    cx,
+                )
+            })
+        })
+        .await
+        .unwrap();
+    executor.run_until_parked();
+    let project_b = cx_b
+        .read(ActiveCall::global)
+        .read_with(cx_b, |call, _| call.location().unwrap().upgrade().unwrap());
+    let project_c = cx_c
+        .read(ActiveCall::global)
+        .read_with(cx_c, |call, _| call.location().unwrap().upgrade().unwrap());
+    assert!(project_b.read_with(cx_b, |project, cx| !project.is_read_only(cx)));
+    assert!(project_c.read_with(cx_c, |project, cx| project.is_read_only(cx)));
+
+    // A shares a terminal, whose input is sent to the test instead of a process.
+    let (input_tx, mut input_rx) = mpsc::unbounded();
+    let terminal_a = cx_a.new(|cx| TerminalBuilder::new_fake("zsh".into(), input_tx).subscribe(cx));
+    set_screen(&terminal_a, "$ ls\r\nREADME.md\r\n$ ", cx_a);
+    project_a
+        .update(cx_a, |project, cx| {
+            project.share_terminal(&terminal_a, false, cx)
+        })
+        .unwrap();
+    executor.run_until_parked();
+
+    let terminal_id = terminal_a.entity_id().as_u64();
+    let shared_terminal = SharedTerminal {
+        id: terminal_id,
+        title: "zsh".into(),
+        read_only: false,
+    };
+    assert_eq!(
+        project_b.read_with(cx_b, |project, _| project.shared_terminals().to_vec()),
+        [shared_terminal.clone()]
+    );
+    assert_eq!(
+        project_c.read_with(cx_c, |project, _| project.shared_terminals().to_vec()),
+        [shared_terminal]
+    );
+
+    // B opens the terminal and sees A's screen, along with the lines that change later.
+    let terminal_b = project_b
+        .update(cx_b, |project, cx| {
+            project.open_shared_terminal(terminal_id, cx)
+        })
+        .await
+        .unwrap();
+    assert!(terminal_b.read_with(cx_b, |terminal, _| !terminal.is_read_only()));
+    assert_same_screen(&terminal_a, cx_a, &terminal_b, cx_b);
+
+    set_screen(&terminal_a, "\x1b[3;3Hcargo test\r\nrunning 1 test", cx_a);
+    executor.advance_clock(Duration::from_secs(1));
+    executor.run_until_parked();
+    assert_same_screen(&terminal_a, cx_a, &terminal_b, cx_b);
+
+    // B's input is sent to A's terminal.
+    terminal_b.update(cx_b, |terminal, _| terminal.input(b"q".to_vec()));
+    executor.run_until_parked();
+    assert_eq!(input_rx.next().await.unwrap(), b"q");
+
+    // C can only watch the terminal: its input is dropped, and the server rejects it too.
+    let terminal_c = project_c
+        .update(cx_c, |project, cx| {
+            project.open_shared_terminal(terminal_id, cx)
+        })
+        .await
+        .unwrap();
+    terminal_c.read_with(cx_c, |terminal, _| {
+        assert!(terminal.is_read_only());
+        assert_eq!(terminal.title(false), "zsh (read-only)");
+    });
+    assert_same_screen(&terminal_a, cx_a, &terminal_c, cx_c);
+    terminal_c.update(cx_c, |terminal, _| terminal.input(b"rm -rf ~\r".to_vec()));
+    executor.run_until_parked();
+    assert!(input_rx.try_next().is_err());
+    assert!(
+        client_c
+            .client()
+            .request(proto::TerminalInput {
+                project_id,
+                terminal_id,
+                data: b"rm -rf ~\r".to_vec(),
+            })
+            .await
+            .is_err()
+    );
+    executor.run_until_parked();
+    assert!(input_rx.try_next().is_err());
+
+    // Once A shares the terminal read-only, B's input is rejected as well.
+    project_a
+        .update(cx_a, |project, cx| {
+            project.share_terminal(&terminal_a, true, cx)
+        })
+        .unwrap();
+    executor.run_until_parked();
+    assert!(project_b.read_with(cx_b, |project, _| project.shared_terminals()[0].read_only));
+    terminal_b.update(cx_b, |terminal, _| terminal.input(b"q".to_vec()));
+    executor.run_until_parked();
+    assert!(input_rx.try_next().is_err());
+
+    // When A stops sharing the terminal, it is no longer listed for B and C.
+    project_a.update(cx_a, |project, cx| {
+        project.stop_sharing_terminal(&terminal_a, cx)
+    });
+    executor.run_until_parked();
+    assert!(project_b.read_with(cx_b, |project, _| project.shared_terminals().is_empty()));
+    assert!(project_c.read_with(cx_c, |project, _| project.shared_terminals().is_empty()));
+    assert!(
+        project_b
+            .update(cx_b, |project, cx| {
+                project.open_shared_terminal(terminal_id, cx)
+            })
+            .await
+            .is_err()
+    );
+}
+
+/// Draws `output` on the screen of a fake terminal, as if its process had printed it.
+fn set_screen(terminal: &Entity<Terminal>, output: &str, cx: &mut TestAppContext) {
+    terminal.update(cx, |terminal, cx| {
+        terminal.apply_screen_snapshot(
+            &ScreenSnapshot {
+                columns: 20,
+                lines: 4,
+                content: output.into(),
+            },
+            cx,
+        )
+    });
+}
+
+fn assert_same_screen(
+    host_terminal: &Entity<Terminal>,
+    host_cx: &TestAppContext,
+    guest_terminal: &Entity<Terminal>,
+    guest_cx: &TestAppContext,
+) {
+    assert_eq!(
+        guest_terminal.read_with(guest_cx, |terminal, _| terminal.encode_screen()),
+        host_terminal.read_with(host_cx, |terminal, _| terminal.encode_screen()),
+    );
+}
@@ -992,8 +992,8 @@ impl RunningState {
 
             terminal.read_with(cx, |terminal, _| {
                 terminal
-                    .pty_info
-                    .pid()
+                    .pty_info()
+                    .and_then(|info| info.pid())
                     .map(|pid| pid.as_u32())
                     .ok_or_else(|| anyhow!("Terminal was spawned but PID was not available"))
             })?
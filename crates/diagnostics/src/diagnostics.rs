@@ -47,10 +47,9 @@ use workspace::{
     searchable::SearchableItemHandle,
 };
 
-actions!(
-    diagnostics,
-    [Deploy, ToggleWarnings, ToggleDiagnosticsRefresh]
-);
+pub use workspace::DeployDiagnostics as Deploy;
+
+actions!(diagnostics, [ToggleWarnings, ToggleDiagnosticsRefresh]);
 
 #[derive(Default)]
 pub(crate) struct IncludeWarnings(bool);
@@ -251,6 +251,7 @@ actions!(
         Cancel,
         CancelFlycheck,
         CancelLanguageServerWork,
+        ClearBookmarks,
         ClearFlycheck,
         ConfirmRename,
         ConfirmCompletionInsert,
@@ -309,8 +310,10 @@ actions!(
         GoToPreviousHunk,
         GoToImplementation,
         GoToImplementationSplit,
+        GoToNextBookmark,
         GoToNextChange,
         GoToParentModule,
+        GoToPreviousBookmark,
         GoToPreviousChange,
         GoToPreviousDiagnostic,
         GoToTypeDefinition,
@@ -415,6 +418,7 @@ actions!(
         SwitchSourceHeader,
         Tab,
         Backtab,
+        ToggleBookmark,
         ToggleBreakpoint,
         ToggleCase,
         DisableBreakpoint,
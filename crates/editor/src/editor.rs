@@ -18,7 +18,6 @@ mod clangd_ext;
 mod code_context_menus;
 pub mod display_map;
 mod editor_settings;
-mod editor_settings_controls;
 mod element;
 mod git;
 mod highlight_matching_bracket;
@@ -37,6 +36,7 @@ mod proposed_changes_editor;
 mod rust_analyzer_ext;
 pub mod scroll;
 mod selections_collection;
+mod sticky_scroll;
 pub mod tasks;
 
 #[cfg(test)]
@@ -66,7 +66,6 @@ pub use editor_settings::{
     ShowScrollbar,
 };
 use editor_settings::{GoToDefinitionFallback, Minimap as MinimapSettings};
-pub use editor_settings_controls::*;
 use element::{AcceptEditPredictionBinding, LineWithInvisibles, PositionMap, layout_line};
 pub use element::{
     CursorLayout, EditorElement, HighlightedRange, HighlightedRangeLine, PointForPosition,
@@ -123,6 +122,7 @@ use mouse_context_menu::MouseContextMenu;
 use persistence::DB;
 use project::{
     ProjectPath,
+    bookmark_store::{Bookmark, BookmarkStore},
     debugger::{
         breakpoint_store::{
             BreakpointEditAction, BreakpointState, BreakpointStore, BreakpointStoreEvent,
@@ -189,6 +189,7 @@ use std::{
     rc::Rc,
     time::{Duration, Instant},
 };
+use sticky_scroll::StickyScrollCache;
 pub use sum_tree::Bias;
 use sum_tree::TreeMap;
 use text::{BufferId, FromAnchor, OffsetUtf16, Rope};
@@ -1023,6 +1024,7 @@ pub struct Editor {
     tasks: BTreeMap<(BufferId, BufferRow), RunnableTasks>,
     tasks_update_task: Option<Task<()>>,
     breakpoint_store: Option<Entity<BreakpointStore>>,
+    bookmark_store: Option<Entity<BookmarkStore>>,
     gutter_breakpoint_indicator: (Option<PhantomBreakpointIndicator>, Option<Task<()>>),
     in_project_search: bool,
     previous_search_ranges: Option<Arc<[Range<Anchor>]>>,
@@ -1044,6 +1046,7 @@ pub struct Editor {
     hide_mouse_mode: HideMouseMode,
     pub change_list: ChangeList,
     inline_value_cache: InlineValueCache,
+    sticky_scroll_cache: StickyScrollCache,
 }
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
@@ -1711,6 +1714,10 @@ impl Editor {
             (EditorMode::Full { .. }, Some(project)) => Some(project.read(cx).breakpoint_store()),
             _ => None,
         };
+        let bookmark_store = match (&mode, project.as_ref()) {
+            (EditorMode::Full { .. }, Some(project)) => Some(project.read(cx).bookmark_store()),
+            _ => None,
+        };
 
         let mut code_action_providers = Vec::new();
         let mut load_uncommitted_diff = None;
@@ -1825,6 +1832,7 @@ impl Editor {
             },
             inline_diagnostics_enabled: mode.is_full(),
             inline_value_cache: InlineValueCache::new(inlay_hint_settings.show_value_hints),
+            sticky_scroll_cache: StickyScrollCache::default(),
             inlay_hint_cache: InlayHintCache::new(inlay_hint_settings),
 
             gutter_hovered: false,
@@ -1860,6 +1868,7 @@ impl Editor {
             tasks: BTreeMap::default(),
 
             breakpoint_store,
+            bookmark_store,
             gutter_breakpoint_indicator: (None, None),
             _subscriptions: vec![
                 cx.observe(&buffer, Self::on_buffer_changed),
@@ -1910,6 +1919,10 @@ impl Editor {
                     cx.notify();
                 }));
         }
+        if let Some(bookmarks) = this.bookmark_store.as_ref() {
+            this._subscriptions
+                .push(cx.observe(bookmarks, |_, _, cx| cx.notify()));
+        }
         this.tasks_update_task = Some(this.refresh_runnables(window, cx));
         this._subscriptions.extend(project_subscriptions);
 
@@ -2066,6 +2079,15 @@ impl Editor {
             key_context.add("renaming");
         }
 
+        let newest_selection = self.selections.newest_anchor();
+        if newest_selection
+            .start
+            .cmp(&newest_selection.end, &self.buffer.read(cx).read(cx))
+            .is_ne()
+        {
+            key_context.add("selection");
+        }
+
         match self.context_menu.borrow().as_ref() {
             Some(CodeContextMenu::Completions(_)) => {
                 key_context.add("menu");
@@ -9819,6 +9841,250 @@ impl Editor {
         self.breakpoint_store.clone()
     }
 
+    fn active_bookmarks(
+        &self,
+        range: Range<DisplayRow>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> HashMap<DisplayRow, (Anchor, Bookmark)> {
+        let mut bookmark_display_points = HashMap::default();
+        let Some(bookmark_store) = self.bookmark_store.clone() else {
+            return bookmark_display_points;
+        };
+
+        let snapshot = self.snapshot(window, cx);
+        let multi_buffer_snapshot = &snapshot.display_snapshot.buffer_snapshot;
+        let range = snapshot.display_point_to_point(DisplayPoint::new(range.start, 0), Bias::Left)
+            ..snapshot.display_point_to_point(DisplayPoint::new(range.end, 0), Bias::Right);
+
+        for (buffer_snapshot, range, excerpt_id) in
+            multi_buffer_snapshot.range_to_buffer_ranges(range)
+        {
+            let Some(buffer) = self.buffer.read(cx).buffer(buffer_snapshot.remote_id()) else {
+                continue;
+            };
+            let bookmarks = bookmark_store.read(cx).bookmarks(
+                &buffer,
+                Some(
+                    buffer_snapshot.anchor_before(range.start)
+                        ..buffer_snapshot.anchor_after(range.end),
+                ),
+                buffer_snapshot,
+                cx,
+            );
+            for (anchor, bookmark) in bookmarks {
+                let multi_buffer_anchor =
+                    Anchor::in_buffer(excerpt_id, buffer_snapshot.remote_id(), *anchor);
+                let position = multi_buffer_anchor
+                    .to_point(&multi_buffer_snapshot)
+                    .to_display_point(&snapshot);
+                bookmark_display_points
+                    .insert(position.row(), (multi_buffer_anchor, bookmark.clone()));
+            }
+        }
+
+        bookmark_display_points
+    }
+
+    fn render_bookmark(
+        &self,
+        position: Anchor,
+        row: DisplayRow,
+        bookmark: &Bookmark,
+        cx: &mut Context<Self>,
+    ) -> IconButton {
+        let tooltip = match &bookmark.name {
+            Some(name) => SharedString::from(format!("Bookmark: {name}")),
+            None => SharedString::from("Bookmark"),
+        };
+        IconButton::new(
+            ("bookmark_indicator", row.0 as usize),
+            ui::IconName::Bookmark,
+        )
+        .icon_size(IconSize::XSmall)
+        .size(ui::ButtonSize::None)
+        .icon_color(Color::Accent)
+        .style(ButtonStyle::Transparent)
+        .on_click(cx.listener(move |editor, _: &ClickEvent, window, cx| {
+            window.focus(&editor.focus_handle(cx));
+            editor.toggle_bookmark_at_anchor(position, cx);
+        }))
+        .tooltip(Tooltip::for_action_title_in(
+            tooltip,
+            &crate::actions::ToggleBookmark,
+            &self.focus_handle,
+        ))
+    }
+
+    fn toggle_bookmark_at_anchor(&mut self, position: Anchor, cx: &mut Context<Self>) {
+        let Some(bookmark_store) = self.bookmark_store.clone() else {
+            return;
+        };
+        let Some(buffer) = position
+            .buffer_id
+            .and_then(|buffer_id| self.buffer.read(cx).buffer(buffer_id))
+        else {
+            return;
+        };
+        bookmark_store.update(cx, |bookmark_store, cx| {
+            bookmark_store.toggle_bookmark(buffer, position.text_anchor, cx);
+        });
+    }
+
+    /// Returns the rows of every bookmark in the editor's excerpts, in order.
+    fn bookmark_rows(&self, cx: &App) -> Vec<MultiBufferRow> {
+        let Some(bookmark_store) = self.bookmark_store.as_ref() else {
+            return Vec::new();
+        };
+        let multi_buffer = self.buffer.read(cx);
+        let multi_buffer_snapshot = multi_buffer.snapshot(cx);
+
+        let mut rows = Vec::new();
+        for (excerpt_id, buffer_snapshot, range) in multi_buffer_snapshot.excerpts() {
+            let Some(buffer) = multi_buffer.buffer(buffer_snapshot.remote_id()) else {
+                continue;
+            };
+            let bookmarks = bookmark_store.read(cx).bookmarks(
+                &buffer,
+                Some(range.context.clone()),
+                buffer_snapshot,
+                cx,
+            );
+            rows.extend(bookmarks.filter_map(|(anchor, _)| {
+                let anchor = multi_buffer_snapshot.anchor_in_excerpt(excerpt_id, *anchor)?;
+                Some(MultiBufferRow(anchor.to_point(&multi_buffer_snapshot).row))
+            }));
+        }
+        rows.sort_unstable();
+        rows.dedup();
+        rows
+    }
+
+    /// Returns the buffer and text anchor of each line with a cursor, one per line.
+    fn cursor_lines(&self, cx: &mut App) -> Vec<(Entity<Buffer>, text::Anchor)> {
+        let selections = self.selections.all::<Point>(cx);
+        let multi_buffer = self.buffer.read(cx);
+        let multi_buffer_snapshot = multi_buffer.snapshot(cx);
+        let mut rows = HashSet::default();
+        selections
+            .into_iter()
+            .filter(|selection| rows.insert(selection.head().row))
+            .filter_map(|selection| {
+                let line_start = Point::new(selection.head().row, 0);
+                let (buffer_snapshot, offset) =
+                    multi_buffer_snapshot.point_to_buffer_offset(line_start)?;
+                let buffer = multi_buffer.buffer(buffer_snapshot.remote_id())?;
+                Some((buffer, buffer_snapshot.anchor_after(offset)))
+            })
+            .collect()
+    }
+
+    pub fn toggle_bookmark(
+        &mut self,
+        _: &crate::actions::ToggleBookmark,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(bookmark_store) = self.bookmark_store.clone() else {
+            return;
+        };
+        for (buffer, anchor) in self.cursor_lines(cx) {
+            bookmark_store.update(cx, |bookmark_store, cx| {
+                bookmark_store.toggle_bookmark(buffer, anchor, cx);
+            });
+        }
+    }
+
+    /// Returns the bookmark on the line of the newest cursor, if there is one.
+    pub fn bookmark_at_cursor(&self, cx: &App) -> Option<Bookmark> {
+        let bookmark_store = self.bookmark_store.as_ref()?;
+        let head = self.selections.newest_anchor().head();
+        let (buffer, anchor) = self.buffer.read(cx).text_anchor_for_position(head, cx)?;
+        let row = buffer
+            .read(cx)
+            .snapshot()
+            .summary_for_anchor::<Point>(&anchor)
+            .row;
+        bookmark_store
+            .read(cx)
+            .bookmark_at_row(&buffer, row, cx)
+            .map(|(_, bookmark)| bookmark)
+    }
+
+    /// Names the bookmark on the line of the newest cursor, adding one if there isn't one.
+    pub fn set_bookmark_name_at_cursor(&mut self, name: Option<Arc<str>>, cx: &mut Context<Self>) {
+        let Some(bookmark_store) = self.bookmark_store.clone() else {
+            return;
+        };
+        let head = self.selections.newest_anchor().head();
+        let Some((buffer, anchor)) = self.buffer.read(cx).text_anchor_for_position(head, cx) else {
+            return;
+        };
+        bookmark_store.update(cx, |bookmark_store, cx| {
+            bookmark_store.set_bookmark_name(buffer, anchor, name, cx);
+        });
+    }
+
+    pub fn go_to_next_bookmark(
+        &mut self,
+        _: &crate::actions::GoToNextBookmark,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.go_to_bookmark(Direction::Next, window, cx);
+    }
+
+    pub fn go_to_previous_bookmark(
+        &mut self,
+        _: &crate::actions::GoToPreviousBookmark,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.go_to_bookmark(Direction::Prev, window, cx);
+    }
+
+    fn go_to_bookmark(
+        &mut self,
+        direction: Direction,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let rows = self.bookmark_rows(cx);
+        let cursor_row = self.selections.newest::<Point>(cx).head().row;
+        let row = match direction {
+            Direction::Next => rows
+                .iter()
+                .find(|row| row.0 > cursor_row)
+                .or_else(|| rows.first()),
+            Direction::Prev => rows
+                .iter()
+                .rev()
+                .find(|row| row.0 < cursor_row)
+                .or_else(|| rows.last()),
+        };
+        let Some(row) = row else {
+            return;
+        };
+
+        self.hide_mouse_cursor(&HideMouseCursorOrigin::MovementAction);
+        let destination = Point::new(row.0, 0);
+        self.unfold_ranges(&[destination..destination], false, false, cx);
+        self.change_selections(Some(Autoscroll::center()), window, cx, |s| {
+            s.select_ranges([destination..destination]);
+        });
+    }
+
+    pub fn clear_bookmarks(
+        &mut self,
+        _: &crate::actions::ClearBookmarks,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if let Some(bookmark_store) = self.bookmark_store.as_ref() {
+            bookmark_store.update(cx, |bookmark_store, cx| bookmark_store.clear_bookmarks(cx));
+        }
+    }
+
     pub fn prepare_restore_change(
         &self,
         revert_changes: &mut HashMap<BufferId, Vec<(Range<text::Anchor>, Rope)>>,
@@ -20522,6 +20788,7 @@ impl EditorSnapshot {
 
         let show_runnables = self.show_runnables.unwrap_or(gutter_settings.runnables);
         let show_breakpoints = self.show_breakpoints.unwrap_or(gutter_settings.breakpoints);
+        let show_bookmarks = gutter_settings.bookmarks;
 
         let git_blame_entries_width =
             self.git_blame_gutter_max_author_length
@@ -20545,7 +20812,7 @@ impl EditorSnapshot {
         let mut left_padding = git_blame_entries_width.unwrap_or(Pixels::ZERO);
         left_padding += if !is_singleton {
             em_width * 4.0
-        } else if show_runnables || show_breakpoints {
+        } else if show_runnables || show_breakpoints || show_bookmarks {
             em_width * 3.0
         } else if show_git_gutter && show_line_numbers {
             em_width * 2.0
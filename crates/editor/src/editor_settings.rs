@@ -19,6 +19,7 @@ pub struct EditorSettings {
     pub scrollbar: Scrollbar,
     pub minimap: Minimap,
     pub gutter: Gutter,
+    pub sticky_scroll: StickyScroll,
     pub scroll_beyond_last_line: ScrollBeyondLastLine,
     pub vertical_scroll_margin: f32,
     pub autoscroll_on_clicks: bool,
@@ -147,9 +148,16 @@ pub struct Gutter {
     pub line_numbers: bool,
     pub runnables: bool,
     pub breakpoints: bool,
+    pub bookmarks: bool,
     pub folds: bool,
 }
 
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct StickyScroll {
+    pub enabled: bool,
+    pub max_depth: usize,
+}
+
 /// When to show the scrollbar in the editor.
 ///
 /// Default: auto
@@ -381,6 +389,8 @@ pub struct EditorSettingsContent {
     pub minimap: Option<MinimapContent>,
     /// Gutter related settings
     pub gutter: Option<GutterContent>,
+    /// Sticky scroll related settings
+    pub sticky_scroll: Option<StickyScrollContent>,
     /// Whether the editor will scroll beyond the last line.
     ///
     /// Default: one_page
@@ -591,12 +601,30 @@ pub struct GutterContent {
     ///
     /// Default: true
     pub breakpoints: Option<bool>,
+    /// Whether to show bookmarks in the gutter.
+    ///
+    /// Default: true
+    pub bookmarks: Option<bool>,
     /// Whether to show fold buttons in the gutter.
     ///
     /// Default: true
     pub folds: Option<bool>,
 }
 
+/// Sticky scroll related settings
+#[derive(Copy, Clone, Debug, Default, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct StickyScrollContent {
+    /// Whether to pin the first lines of the functions, classes and modules
+    /// enclosing the visible code to the top of the editor.
+    ///
+    /// Default: false
+    pub enabled: Option<bool>,
+    /// The maximum number of enclosing items to pin.
+    ///
+    /// Default: 5
+    pub max_depth: Option<usize>,
+}
+
 impl EditorSettings {
     pub fn jupyter_enabled(cx: &App) -> bool {
         EditorSettings::get_global(cx).jupyter.enabled
@@ -683,6 +711,22 @@ impl Settings for EditorSettings {
                 current.gutter = Some(gutter)
             }
         }
+        let mut sticky_scroll = StickyScrollContent::default();
+        vscode.bool_setting("editor.stickyScroll.enabled", &mut sticky_scroll.enabled);
+        vscode.usize_setting(
+            "editor.stickyScroll.maxLineCount",
+            &mut sticky_scroll.max_depth,
+        );
+        if sticky_scroll != StickyScrollContent::default() {
+            let sticky_scroll_settings = current.sticky_scroll.get_or_insert_default();
+            if sticky_scroll.enabled.is_some() {
+                sticky_scroll_settings.enabled = sticky_scroll.enabled;
+            }
+            if sticky_scroll.max_depth.is_some() {
+                sticky_scroll_settings.max_depth = sticky_scroll.max_depth;
+            }
+        }
+
         if let Some(b) = vscode.read_bool("editor.scrollBeyondLastLine") {
             current.scroll_beyond_last_line = Some(if b {
                 ScrollBeyondLastLine::OnePage
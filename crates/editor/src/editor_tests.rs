@@ -16349,6 +16349,98 @@ async fn test_stage_and_unstage_added_file_hunk(
     cx.assert_index_text(None);
 }
 
+#[gpui::test]
+async fn test_sticky_scroll_lines(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+    let mut cx = EditorTestContext::new(cx).await;
+
+    let language = Arc::new(
+        Language::new(
+            LanguageConfig::default(),
+            Some(tree_sitter_rust::LANGUAGE.into()),
+        )
+        .with_outline_query(
+            r#"
+            (mod_item "mod" @context name: (_) @name) @item
+            (impl_item "impl" @context type: (_) @name) @item
+            (function_item "fn" @context name: (_) @name) @item
+            "#,
+        )
+        .unwrap(),
+    );
+    cx.update_buffer(|buffer, cx| buffer.set_language(Some(language), cx));
+    cx.set_state(indoc! {"
+        ˇmod a {
+            impl B {
+                fn c() {
+                    x;
+                    y;
+                }
+                fn d() {
+                    z;
+                }
+            }
+        }
+    "});
+    cx.run_until_parked();
+
+    let mut cache = StickyScrollCache::default();
+    let mut sticky_lines = |scroll_top: f32, max_depth: usize, cx: &mut EditorTestContext| {
+        cx.update_editor(|editor, window, cx| {
+            editor
+                .snapshot(window, cx)
+                .sticky_scroll_lines(scroll_top, max_depth, &mut cache)
+                .into_iter()
+                .map(|line| (line.row.0, line.offset))
+                .collect::<Vec<_>>()
+        })
+    };
+
+    // Nothing is pinned while the outermost item's first line is visible.
+    assert_eq!(sticky_lines(0., 5, &mut cx), vec![]);
+    // Each pinned line hides the next row, so `fn c` is pinned below `impl B`.
+    assert_eq!(
+        sticky_lines(1., 5, &mut cx),
+        vec![(0, 0.), (1, 1.), (2, 2.)]
+    );
+    // `fn c` is pushed up as its end scrolls beneath it.
+    assert_eq!(
+        sticky_lines(3.5, 5, &mut cx),
+        vec![(0, 0.), (1, 1.), (2, 1.5)]
+    );
+    // Once `fn c` has ended, `fn d` is still visible below the pinned lines.
+    assert_eq!(sticky_lines(4., 5, &mut cx), vec![(0, 0.), (1, 1.)]);
+    assert_eq!(
+        sticky_lines(5., 5, &mut cx),
+        vec![(0, 0.), (1, 1.), (6, 2.)]
+    );
+    assert_eq!(sticky_lines(5., 1, &mut cx), vec![(0, 0.)]);
+
+    // The outline is looked up again once the buffer changes, even though
+    // the same rows are in view.
+    assert_eq!(
+        sticky_lines(1., 5, &mut cx),
+        vec![(0, 0.), (1, 1.), (2, 2.)]
+    );
+    cx.update_editor(|editor, _, cx| {
+        editor.edit([(Point::new(0, 0)..Point::new(0, 0), "// a\n")], cx)
+    });
+    cx.run_until_parked();
+    assert_eq!(sticky_lines(1., 5, &mut cx), vec![]);
+    assert_eq!(
+        sticky_lines(2., 5, &mut cx),
+        vec![(1, 0.), (2, 1.), (3, 2.)]
+    );
+
+    let start = cx.update_editor(|editor, window, cx| {
+        editor
+            .snapshot(window, cx)
+            .sticky_scroll_lines(2., 5, &mut cache)[2]
+            .start
+    });
+    assert_eq!(start, Point::new(3, 8));
+}
+
 async fn setup_indent_guides_editor(
     text: &str,
     cx: &mut TestAppContext,
@@ -18642,6 +18734,110 @@ fn add_log_breakpoint_at_cursor(
     );
 }
 
+#[gpui::test]
+async fn test_bookmarks(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+
+    let sample_text = "First line\nSecond line\nThird line\nFourth line".to_string();
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        path!("/a"),
+        json!({
+            "main.rs": sample_text,
+        }),
+    )
+    .await;
+    let project = Project::test(fs, [path!("/a").as_ref()], cx).await;
+    let workspace = cx.add_window(|window, cx| Workspace::test_new(project.clone(), window, cx));
+    let cx = &mut VisualTestContext::from_window(*workspace.deref(), cx);
+    let worktree_id = project.update(cx, |project, cx| {
+        project.worktrees(cx).next().unwrap().read(cx).id()
+    });
+    let buffer = project
+        .update(cx, |project, cx| {
+            project.open_buffer((worktree_id, "main.rs"), cx)
+        })
+        .await
+        .unwrap();
+
+    let (editor, cx) = cx.add_window_view(|window, cx| {
+        Editor::new(
+            EditorMode::full(),
+            MultiBuffer::build_from_buffer(buffer.clone(), cx),
+            Some(project.clone()),
+            window,
+            cx,
+        )
+    });
+    let bookmark_rows = |cx: &mut VisualTestContext| {
+        project.read_with(cx, |project, cx| {
+            project
+                .bookmark_store()
+                .read(cx)
+                .all_bookmarks(cx)
+                .into_iter()
+                .map(|entry| entry.row)
+                .collect::<Vec<_>>()
+        })
+    };
+
+    editor.update_in(cx, |editor, window, cx| {
+        editor.toggle_bookmark(&actions::ToggleBookmark, window, cx);
+        editor.move_down(&MoveDown, window, cx);
+        editor.move_down(&MoveDown, window, cx);
+        editor.toggle_bookmark(&actions::ToggleBookmark, window, cx);
+    });
+    assert_eq!(bookmark_rows(cx), vec![0, 2]);
+
+    // Navigation wraps around the ends of the buffer.
+    editor.update_in(cx, |editor, window, cx| {
+        editor.go_to_next_bookmark(&actions::GoToNextBookmark, window, cx);
+        assert_eq!(
+            editor.selections.newest::<Point>(cx).head(),
+            Point::new(0, 0)
+        );
+        editor.go_to_next_bookmark(&actions::GoToNextBookmark, window, cx);
+        assert_eq!(
+            editor.selections.newest::<Point>(cx).head(),
+            Point::new(2, 0)
+        );
+        editor.go_to_previous_bookmark(&actions::GoToPreviousBookmark, window, cx);
+        assert_eq!(
+            editor.selections.newest::<Point>(cx).head(),
+            Point::new(0, 0)
+        );
+        editor.go_to_previous_bookmark(&actions::GoToPreviousBookmark, window, cx);
+        assert_eq!(
+            editor.selections.newest::<Point>(cx).head(),
+            Point::new(2, 0)
+        );
+    });
+
+    // Bookmarks follow their lines as the buffer is edited.
+    buffer.update(cx, |buffer, cx| {
+        buffer.edit([(0..0, "Zeroth line\n")], None, cx);
+    });
+    assert_eq!(bookmark_rows(cx), vec![1, 3]);
+
+    editor.update_in(cx, |editor, window, cx| {
+        editor.set_bookmark_name_at_cursor(Some("third".into()), cx);
+        assert_eq!(
+            editor
+                .bookmark_at_cursor(cx)
+                .and_then(|bookmark| bookmark.name),
+            Some("third".into())
+        );
+        editor.toggle_bookmark(&actions::ToggleBookmark, window, cx);
+        assert_eq!(editor.bookmark_at_cursor(cx), None);
+    });
+    assert_eq!(bookmark_rows(cx), vec![1]);
+
+    editor.update_in(cx, |editor, window, cx| {
+        editor.clear_bookmarks(&actions::ClearBookmarks, window, cx);
+    });
+    assert_eq!(bookmark_rows(cx), Vec::<u32>::new());
+}
+
 #[gpui::test]
 async fn test_breakpoint_toggling(cx: &mut TestAppContext) {
     init_test(cx, |_| {});
@@ -28,10 +28,10 @@ use crate::{
     inlay_hint_settings,
     items::BufferSearchHighlights,
     mouse_context_menu::{self, MenuPosition},
-    scroll::{ActiveScrollbarState, ScrollbarThumbState, scroll_amount::ScrollAmount},
+    scroll::{ActiveScrollbarState, Autoscroll, ScrollbarThumbState, scroll_amount::ScrollAmount},
 };
 use buffer_diff::{DiffHunkStatus, DiffHunkStatusKind};
-use collections::{BTreeMap, HashMap};
+use collections::{BTreeMap, HashMap, HashSet};
 use feature_flags::{DebuggerFeatureFlag, FeatureFlagAppExt};
 use file_icons::FileIcons;
 use git::{
@@ -40,15 +40,16 @@ use git::{
     status::FileStatus,
 };
 use gpui::{
-    Action, Along, AnyElement, App, AppContext, AvailableSpace, Axis as ScrollbarAxis, BorderStyle,
-    Bounds, ClickEvent, ContentMask, Context, Corner, Corners, CursorStyle, DispatchPhase, Edges,
-    Element, ElementInputHandler, Entity, Focusable as _, FontId, GlobalElementId, Hitbox, Hsla,
-    InteractiveElement, IntoElement, IsZero, Keystroke, Length, ModifiersChangedEvent, MouseButton,
-    MouseDownEvent, MouseMoveEvent, MouseUpEvent, PaintQuad, ParentElement, Pixels, ScrollDelta,
+    AccessibilityAction, AccessibilityProperties, Action, Along, AnyElement, App, AppContext,
+    AvailableSpace, Axis as ScrollbarAxis, BorderStyle, Bounds, ClickEvent, ContentMask, Context,
+    Corner, Corners, CursorStyle, DispatchPhase, Edges, Element, ElementInputHandler, Entity,
+    Focusable as _, FontId, GlobalElementId, HighlightStyle, Hitbox, Hsla, InteractiveElement,
+    IntoElement, IsZero, Keystroke, Length, ModifiersChangedEvent, MouseButton, MouseDownEvent,
+    MouseMoveEvent, MouseUpEvent, PaintQuad, ParentElement, Pixels, Role, ScrollDelta,
     ScrollHandle, ScrollWheelEvent, ShapedLine, SharedString, Size, StatefulInteractiveElement,
-    Style, Styled, TextRun, TextStyleRefinement, WeakEntity, Window, anchored, deferred, div, fill,
-    linear_color_stop, linear_gradient, outline, point, px, quad, relative, size, solid_background,
-    transparent_black,
+    Style, Styled, StyledText, TextRun, TextStyleRefinement, WeakEntity, Window, anchored,
+    deferred, div, fill, linear_color_stop, linear_gradient, outline, point, px, quad, relative,
+    size, solid_background, transparent_black,
 };
 use itertools::Itertools;
 use language::language_settings::{
@@ -62,6 +63,7 @@ use multi_buffer::{
 
 use project::{
     ProjectPath,
+    bookmark_store::Bookmark,
     debugger::breakpoint_store::Breakpoint,
     project_settings::{GitGutterSetting, GitHunkStyleSetting, ProjectSettings},
 };
@@ -448,6 +450,10 @@ impl EditorElement {
         register_action(editor, window, Editor::expand_all_diff_hunks);
         register_action(editor, window, Editor::go_to_previous_change);
         register_action(editor, window, Editor::go_to_next_change);
+        register_action(editor, window, Editor::toggle_bookmark);
+        register_action(editor, window, Editor::go_to_next_bookmark);
+        register_action(editor, window, Editor::go_to_previous_bookmark);
+        register_action(editor, window, Editor::clear_bookmarks);
 
         register_action(editor, window, |editor, action, window, cx| {
             if let Some(task) = editor.format(action, window, cx) {
@@ -2367,6 +2373,63 @@ impl EditorElement {
         })
     }
 
+    fn layout_bookmarks(
+        &self,
+        line_height: Pixels,
+        range: Range<DisplayRow>,
+        scroll_pixel_position: gpui::Point<Pixels>,
+        gutter_dimensions: &GutterDimensions,
+        gutter_hitbox: &Hitbox,
+        display_hunks: &[(DisplayDiffHunk, Option<Hitbox>)],
+        snapshot: &EditorSnapshot,
+        bookmarks: HashMap<DisplayRow, (Anchor, Bookmark)>,
+        occupied_rows: &HashSet<DisplayRow>,
+        row_infos: &[RowInfo],
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Vec<AnyElement> {
+        self.editor.update(cx, |editor, cx| {
+            bookmarks
+                .into_iter()
+                .filter_map(|(display_row, (anchor, bookmark))| {
+                    // Breakpoints and runnables take precedence over bookmarks in the gutter.
+                    if !range.contains(&display_row) || occupied_rows.contains(&display_row) {
+                        return None;
+                    }
+                    if row_infos
+                        .get((display_row - range.start).0 as usize)
+                        .is_some_and(|row_info| {
+                            row_info.expand_info.is_some()
+                                || row_info
+                                    .diff_status
+                                    .is_some_and(|status| status.is_deleted())
+                        })
+                    {
+                        return None;
+                    }
+                    let row =
+                        MultiBufferRow(DisplayPoint::new(display_row, 0).to_point(&snapshot).row);
+                    if snapshot.is_line_folded(row) {
+                        return None;
+                    }
+
+                    let button = editor.render_bookmark(anchor, display_row, &bookmark, cx);
+                    Some(prepaint_gutter_button(
+                        button,
+                        display_row,
+                        line_height,
+                        gutter_dimensions,
+                        scroll_pixel_position,
+                        gutter_hitbox,
+                        display_hunks,
+                        window,
+                        cx,
+                    ))
+                })
+                .collect_vec()
+        })
+    }
+
     #[allow(clippy::too_many_arguments)]
     fn layout_run_indicators(
         &self,
@@ -3583,6 +3646,99 @@ impl EditorElement {
         header
     }
 
+    fn layout_sticky_scroll(
+        &self,
+        snapshot: &EditorSnapshot,
+        scroll_top: f32,
+        scroll_pixel_position: gpui::Point<Pixels>,
+        content_origin: gpui::Point<Pixels>,
+        line_height: Pixels,
+        right_margin: Pixels,
+        hitbox: &Hitbox,
+        gutter_hitbox: &Hitbox,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Vec<AnyElement> {
+        let settings = EditorSettings::get_global(cx).sticky_scroll;
+        if !settings.enabled || !snapshot.mode.is_full() {
+            return Vec::new();
+        }
+
+        let lines = self.editor.update(cx, |editor, _| {
+            snapshot.sticky_scroll_lines(
+                scroll_top,
+                settings.max_depth,
+                &mut editor.sticky_scroll_cache,
+            )
+        });
+        let colors = cx.theme().colors();
+        let background = colors.editor_background;
+        let hover_background = colors.editor_active_line_background;
+        let gutter_background = colors.editor_gutter_background;
+        let border_color = colors.border_variant;
+        let available_width = hitbox.size.width - right_margin;
+        let text_left = content_origin.x - hitbox.origin.x - scroll_pixel_position.x;
+        let last_ix = lines.len().saturating_sub(1);
+
+        lines
+            .into_iter()
+            .enumerate()
+            .map(|(ix, line)| {
+                let (text, highlights) = sticky_scroll_line_text(line.row, snapshot, &self.style);
+                let editor = self.editor.clone();
+                let start = line.start;
+                let mut element = div()
+                    .id(("sticky-scroll-line", ix))
+                    .relative()
+                    .w(available_width)
+                    .h(line_height)
+                    .overflow_hidden()
+                    .cursor_pointer()
+                    .bg(background)
+                    .hover(|style| style.bg(hover_background))
+                    .when(ix == last_ix, |div| {
+                        div.border_b_1().border_color(border_color)
+                    })
+                    .child(div().absolute().left(text_left).child(
+                        StyledText::new(text).with_default_highlights(&self.style.text, highlights),
+                    ))
+                    .child(
+                        div()
+                            .absolute()
+                            .left_0()
+                            .w(gutter_hitbox.size.width)
+                            .h_full()
+                            .bg(gutter_background),
+                    )
+                    .on_mouse_down(MouseButton::Left, move |_, window, cx| {
+                        cx.stop_propagation();
+                        // Keep the line where it is, now that it's no longer pinned.
+                        editor.update(cx, |editor, cx| {
+                            editor.change_selections(
+                                Some(Autoscroll::top_relative(ix)),
+                                window,
+                                cx,
+                                |selections| selections.select_ranges([start..start]),
+                            );
+                        });
+                    })
+                    .into_any_element();
+
+                let origin = hitbox.origin + point(Pixels::ZERO, line.offset * line_height);
+                element.prepaint_as_root(
+                    origin,
+                    size(
+                        AvailableSpace::Definite(available_width),
+                        AvailableSpace::Definite(line_height),
+                    ),
+                    window,
+                    cx,
+                );
+                element
+            })
+            .collect()
+    }
+
     fn layout_cursor_popovers(
         &self,
         line_height: Pixels,
@@ -4975,6 +5131,10 @@ impl EditorElement {
             for test_indicator in layout.test_indicators.iter_mut() {
                 test_indicator.paint(window, cx);
             }
+
+            for bookmark in layout.bookmarks.iter_mut() {
+                bookmark.paint(window, cx);
+            }
         });
     }
 
@@ -5059,6 +5219,55 @@ impl EditorElement {
         })
     }
 
+    fn paint_accessibility_node(
+        &self,
+        bounds: Bounds<Pixels>,
+        layout: &EditorLayout,
+        window: &mut Window,
+        cx: &mut App,
+    ) {
+        if !window.is_accessibility_active() {
+            return;
+        }
+        let role = match layout.mode {
+            EditorMode::SingleLine { .. } => Role::TextInput,
+            EditorMode::AutoHeight { .. } | EditorMode::Full { .. } => Role::MultilineTextInput,
+            EditorMode::Minimap { .. } => return,
+        };
+
+        let snapshot = &layout.position_map.snapshot;
+        let mut properties = AccessibilityProperties::default().action(AccessibilityAction::Focus);
+        properties.focus_handle = Some(self.editor.focus_handle(cx));
+        if let Some(placeholder) = snapshot.placeholder_text.as_ref() {
+            properties = properties.label(placeholder.to_string());
+        }
+        if !snapshot.display_snapshot.masked {
+            // Full editors can hold entire files, so only the lines in view are exposed.
+            let text = if layout.mode.is_full() {
+                let rows = &layout.visible_display_row_range;
+                let start = DisplayPoint::new(rows.start, 0).to_point(&snapshot.display_snapshot);
+                let end = if rows.end > snapshot.max_point().row() {
+                    snapshot.buffer_snapshot.max_point()
+                } else {
+                    DisplayPoint::new(rows.end, 0).to_point(&snapshot.display_snapshot)
+                };
+                snapshot
+                    .buffer_snapshot
+                    .text_for_range(start..end)
+                    .collect::<String>()
+            } else {
+                snapshot.buffer_snapshot.text()
+            };
+            properties = properties.value(text);
+        }
+
+        // The element is drawn within the editor's view, so this ID stays the same across
+        // frames.
+        window.with_global_id("editor".into(), |global_id, window| {
+            window.insert_accessibility_node(Some(global_id), role, properties, bounds);
+        });
+    }
+
     fn paint_text(&mut self, layout: &mut EditorLayout, window: &mut Window, cx: &mut App) {
         window.with_content_mask(
             Some(ContentMask {
@@ -6187,6 +6396,31 @@ impl EditorElement {
     }
 }
 
+/// Returns the text of a display row along with its syntax highlighting.
+fn sticky_scroll_line_text(
+    row: DisplayRow,
+    snapshot: &EditorSnapshot,
+    style: &EditorStyle,
+) -> (String, Vec<(Range<usize>, HighlightStyle)>) {
+    let mut text = String::new();
+    let mut highlights = Vec::new();
+    for chunk in snapshot.highlighted_chunks(row..row.next_row(), true, style) {
+        let (chunk_text, ends_line) = match chunk.text.split_once('\n') {
+            Some((chunk_text, _)) => (chunk_text, true),
+            None => (chunk.text, false),
+        };
+        let start = text.len();
+        text.push_str(chunk_text);
+        if let Some(highlight) = chunk.style {
+            highlights.push((start..text.len(), highlight));
+        }
+        if ends_line {
+            break;
+        }
+    }
+    (text, highlights)
+}
+
 fn header_jump_data(
     snapshot: &EditorSnapshot,
     block_row_start: DisplayRow,
@@ -7696,6 +7930,22 @@ impl Element for EditorElement {
                         scroll_position.y * line_height,
                     );
 
+                    let sticky_scroll_lines =
+                        window.with_element_namespace("sticky_scroll", |window| {
+                            self.layout_sticky_scroll(
+                                &snapshot,
+                                scroll_position.y,
+                                scroll_pixel_position,
+                                content_origin,
+                                line_height,
+                                right_margin,
+                                &hitbox,
+                                &gutter_hitbox,
+                                window,
+                                cx,
+                            )
+                        });
+
                     let indent_guides = self.layout_indent_guides(
                         content_origin,
                         text_hitbox.origin,
@@ -7915,6 +8165,25 @@ impl Element for EditorElement {
                         cx,
                     );
 
+                    let show_breakpoints = snapshot
+                        .show_breakpoints
+                        .unwrap_or(gutter_settings.breakpoints);
+                    let mut occupied_gutter_rows = HashSet::default();
+                    if cx.has_flag::<DebuggerFeatureFlag>() && show_breakpoints {
+                        occupied_gutter_rows.extend(breakpoint_rows.keys().copied());
+                    }
+                    if gutter_settings.runnables {
+                        occupied_gutter_rows.extend(self.editor.read(cx).tasks.values().map(
+                            |tasks| {
+                                tasks
+                                    .offset
+                                    .to_point(&snapshot.buffer_snapshot)
+                                    .to_display_point(&snapshot)
+                                    .row()
+                            },
+                        ));
+                    }
+
                     let test_indicators = if gutter_settings.runnables {
                         self.layout_run_indicators(
                             line_height,
@@ -7933,9 +8202,6 @@ impl Element for EditorElement {
                         Vec::new()
                     };
 
-                    let show_breakpoints = snapshot
-                        .show_breakpoints
-                        .unwrap_or(gutter_settings.breakpoints);
                     let breakpoints = if cx.has_flag::<DebuggerFeatureFlag>() && show_breakpoints {
                         self.layout_breakpoints(
                             line_height,
@@ -7954,6 +8220,28 @@ impl Element for EditorElement {
                         vec![]
                     };
 
+                    let bookmarks = if gutter_settings.bookmarks {
+                        let bookmark_rows = self.editor.update(cx, |editor, cx| {
+                            editor.active_bookmarks(start_row..end_row, window, cx)
+                        });
+                        self.layout_bookmarks(
+                            line_height,
+                            start_row..end_row,
+                            scroll_pixel_position,
+                            &gutter_dimensions,
+                            &gutter_hitbox,
+                            &display_hunks,
+                            &snapshot,
+                            bookmark_rows,
+                            &occupied_gutter_rows,
+                            &row_infos,
+                            window,
+                            cx,
+                        )
+                    } else {
+                        Vec::new()
+                    };
+
                     self.layout_signature_help(
                         &hitbox,
                         &text_hitbox,
@@ -8118,11 +8406,13 @@ impl Element for EditorElement {
                         mouse_context_menu,
                         test_indicators,
                         breakpoints,
+                        bookmarks,
                         crease_toggles,
                         crease_trailers,
                         tab_invisible,
                         space_invisible,
                         sticky_buffer_header,
+                        sticky_scroll_lines,
                         expand_toggles,
                     }
                 })
@@ -8152,6 +8442,7 @@ impl Element for EditorElement {
         );
         self.register_actions(window, cx);
         self.register_key_listeners(window, cx, layout);
+        self.paint_accessibility_node(bounds, layout, window, cx);
 
         let text_style = TextStyleRefinement {
             font_size: Some(self.style.text.font_size),
@@ -8190,6 +8481,15 @@ impl Element for EditorElement {
                         }
                     });
 
+                    window.with_element_namespace("sticky_scroll", |window| {
+                        // Paint the outermost lines last, so that inner lines
+                        // being pushed up slide beneath them.
+                        for mut line in mem::take(&mut layout.sticky_scroll_lines).into_iter().rev()
+                        {
+                            line.paint(window, cx);
+                        }
+                    });
+
                     self.paint_minimap(layout, window, cx);
                     self.paint_scrollbars(layout, window, cx);
                     self.paint_inline_completion_popover(layout, window, cx);
@@ -8291,6 +8591,7 @@ pub struct EditorLayout {
     selections: Vec<(PlayerColor, Vec<SelectionLayout>)>,
     test_indicators: Vec<AnyElement>,
     breakpoints: Vec<AnyElement>,
+    bookmarks: Vec<AnyElement>,
     crease_toggles: Vec<Option<AnyElement>>,
     expand_toggles: Vec<Option<(AnyElement, gpui::Point<Pixels>)>>,
     diff_hunk_controls: Vec<AnyElement>,
@@ -8300,6 +8601,7 @@ pub struct EditorLayout {
     tab_invisible: ShapedLine,
     space_invisible: ShapedLine,
     sticky_buffer_header: Option<AnyElement>,
+    sticky_scroll_lines: Vec<AnyElement>,
 }
 
 impl EditorLayout {
@@ -9292,6 +9594,41 @@ mod tests {
         assert_eq!(relative_rows[&DisplayRow(2)], 3);
     }
 
+    #[gpui::test]
+    fn test_accessibility_node(cx: &mut TestAppContext) {
+        init_test(cx, |_| {});
+
+        let (editor, cx) = cx.add_window_view(|window, cx| {
+            let mut editor = Editor::single_line(window, cx);
+            editor.set_placeholder_text("Search", cx);
+            editor.set_text("needle", window, cx);
+            editor
+        });
+        let tree = cx.accessibility_tree();
+        let input = tree.find_by_name(Role::TextInput, "Search").unwrap();
+        assert_eq!(input.value.as_deref(), Some("needle"));
+        assert_eq!(input.actions.as_slice(), &[AccessibilityAction::Focus]);
+
+        // The text of masked editors, such as password fields, isn't exposed.
+        editor.update(cx, |editor, cx| editor.set_masked(true, cx));
+        let tree = cx.accessibility_tree();
+        let input = tree.find_by_name(Role::TextInput, "Search").unwrap();
+        assert_eq!(input.value, None);
+    }
+
+    #[gpui::test]
+    fn test_accessibility_node_of_full_editor(cx: &mut TestAppContext) {
+        init_test(cx, |_| {});
+
+        let (_, cx) = cx.add_window_view(|window, cx| {
+            let buffer = MultiBuffer::build_simple("fn main() {}\n", cx);
+            Editor::new(EditorMode::full(), buffer, None, window, cx)
+        });
+        let tree = cx.accessibility_tree();
+        let editor = &tree.find_all(Role::MultilineTextInput)[0];
+        assert_eq!(editor.value.as_deref(), Some("fn main() {}\n"));
+    }
+
     #[gpui::test]
     async fn test_vim_visual_selections(cx: &mut TestAppContext) {
         init_test(cx, |_| {});
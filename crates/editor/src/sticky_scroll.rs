@@ -0,0 +1,124 @@
+use std::ops::Range;
+
+use language::{Bias, BufferId, BufferSnapshot, Point, ToPoint as _};
+
+use crate::{DisplayPoint, DisplayRow, EditorSnapshot, RowExt as _, display_map::ToDisplayPoint};
+
+/// A line pinned to the top of the editor by sticky scroll: the first line of
+/// a function, class or module that encloses the code scrolled beneath it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct StickyScrollLine {
+    /// The display row of the item's first line.
+    pub row: DisplayRow,
+    /// Where the item starts, which is where clicking the line jumps to.
+    pub start: Point,
+    /// How many lines below the top of the editor the line is drawn. Lines
+    /// are pushed up as the end of their item scrolls beneath them.
+    pub offset: f32,
+}
+
+/// The outline items of the buffer last shown with sticky scroll, which are
+/// reused until the buffer or its syntax changes, so that scrolling only has
+/// to pick the items around the rows in view.
+#[derive(Default)]
+pub struct StickyScrollCache {
+    buffer_id: Option<BufferId>,
+    version: clock::Global,
+    non_text_state_update_count: usize,
+    items: Vec<Range<Point>>,
+}
+
+impl StickyScrollCache {
+    /// Returns the outline items that intersect `range`, sorted by their start.
+    fn items(
+        &mut self,
+        buffer: &BufferSnapshot,
+        range: Range<Point>,
+    ) -> impl Iterator<Item = &Range<Point>> {
+        let is_valid = self.buffer_id == Some(buffer.remote_id())
+            && self.non_text_state_update_count == buffer.non_text_state_update_count()
+            && &self.version == buffer.version();
+        if !is_valid {
+            self.items = buffer
+                .outline_items_containing(0..buffer.len(), false, None)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|item| item.range.start.to_point(buffer)..item.range.end.to_point(buffer))
+                .collect();
+            self.buffer_id = Some(buffer.remote_id());
+            self.version = buffer.version().clone();
+            self.non_text_state_update_count = buffer.non_text_state_update_count();
+        }
+
+        let end = self.items.partition_point(|item| item.start <= range.end);
+        self.items[..end]
+            .iter()
+            .filter(move |item| item.end >= range.start)
+    }
+}
+
+impl EditorSnapshot {
+    /// Returns the lines to pin to the top of the editor when it's scrolled
+    /// to `scroll_top`, outermost first and at most `max_depth` of them.
+    ///
+    /// Only singleton buffers have sticky lines, as excerpts in a multibuffer
+    /// already pin their buffer header.
+    pub fn sticky_scroll_lines(
+        &self,
+        scroll_top: f32,
+        max_depth: usize,
+        cache: &mut StickyScrollCache,
+    ) -> Vec<StickyScrollLine> {
+        if max_depth == 0 {
+            return Vec::new();
+        }
+        let Some((_, _, buffer)) = self.buffer_snapshot.as_singleton() else {
+            return Vec::new();
+        };
+
+        let top_row = DisplayRow(scroll_top.max(0.).floor() as u32);
+        let bottom_row = DisplayRow((scroll_top.max(0.) + max_depth as f32).ceil() as u32);
+        let top = self
+            .clip_point(DisplayPoint::new(top_row, 0), Bias::Left)
+            .to_point(self);
+        let bottom = self
+            .clip_point(DisplayPoint::new(bottom_row, 0), Bias::Left)
+            .to_point(self);
+
+        // Items are sorted by their start, so enclosing items come before the
+        // items they contain. Each pinned line is paired with its item's end.
+        let mut lines: Vec<(StickyScrollLine, Point)> = Vec::new();
+        for item in cache.items(buffer, top..bottom) {
+            let (start, end) = (item.start, item.end);
+            let depth = lines
+                .iter()
+                .take_while(|(_, enclosing_end)| *enclosing_end >= end)
+                .count();
+            if depth >= max_depth {
+                continue;
+            }
+
+            let start_row = start.to_display_point(self).row();
+            let end_row = end.to_display_point(self).row();
+            if start_row == end_row {
+                continue;
+            }
+            // Pin the item while its first line is hidden beneath the lines
+            // pinned above it, and part of its body is still in view.
+            let slot = depth as f32;
+            if start_row.as_f32() < scroll_top + slot && end_row.as_f32() > scroll_top + slot - 1. {
+                lines.truncate(depth);
+                lines.push((
+                    StickyScrollLine {
+                        row: start_row,
+                        start,
+                        offset: slot.min(end_row.as_f32() - scroll_top),
+                    },
+                    end,
+                ));
+            }
+        }
+
+        lines.into_iter().map(|(line, _)| line).collect()
+    }
+}
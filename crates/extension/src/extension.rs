@@ -11,6 +11,7 @@ use ::lsp::LanguageServerName;
 use anyhow::{Context as _, Result, anyhow, bail};
 use async_trait::async_trait;
 use fs::normalize_path;
+use futures::stream::BoxStream;
 use gpui::{App, Task};
 use language::LanguageName;
 use semantic_version::SemanticVersion;
@@ -142,6 +143,20 @@ pub trait Extension: Send + Sync + 'static {
         config: DebugTaskDefinition,
         user_installed_path: Option<PathBuf>,
     ) -> Result<DebugAdapterBinary>;
+
+    async fn language_model_provider_models(
+        &self,
+        provider_id: Arc<str>,
+    ) -> Result<Vec<LanguageModelInfo>>;
+
+    async fn language_model_provider_authenticate(&self, provider_id: Arc<str>) -> Result<()>;
+
+    async fn stream_language_model_completion(
+        &self,
+        provider_id: Arc<str>,
+        model_id: Arc<str>,
+        request: LanguageModelCompletionRequest,
+    ) -> Result<BoxStream<'static, Result<LanguageModelCompletionEvent>>>;
 }
 
 pub fn parse_wasm_extension_version(
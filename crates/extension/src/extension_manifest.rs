@@ -84,6 +84,8 @@ pub struct ExtensionManifest {
     #[serde(default)]
     pub indexed_docs_providers: BTreeMap<Arc<str>, IndexedDocsProviderEntry>,
     #[serde(default)]
+    pub language_model_providers: BTreeMap<Arc<str>, LanguageModelProviderManifestEntry>,
+    #[serde(default)]
     pub snippets: Option<PathBuf>,
     #[serde(default)]
     pub capabilities: Vec<ExtensionCapability>,
@@ -206,6 +208,12 @@ pub struct SlashCommandManifestEntry {
 #[derive(Clone, PartialEq, Eq, Debug, Deserialize, Serialize)]
 pub struct IndexedDocsProviderEntry {}
 
+#[derive(Clone, PartialEq, Eq, Debug, Deserialize, Serialize)]
+pub struct LanguageModelProviderManifestEntry {
+    /// The name of the provider to display in the UI.
+    pub name: String,
+}
+
 impl ExtensionManifest {
     pub async fn load(fs: Arc<dyn Fs>, extension_dir: &Path) -> Result<Self> {
         let extension_name = extension_dir
@@ -272,6 +280,7 @@ fn manifest_from_old_manifest(
         context_servers: BTreeMap::default(),
         slash_commands: BTreeMap::default(),
         indexed_docs_providers: BTreeMap::default(),
+        language_model_providers: BTreeMap::default(),
         snippets: None,
         capabilities: Vec::new(),
     }
@@ -299,6 +308,7 @@ mod tests {
             context_servers: BTreeMap::default(),
             slash_commands: BTreeMap::default(),
             indexed_docs_providers: BTreeMap::default(),
+            language_model_providers: BTreeMap::default(),
             snippets: None,
             capabilities: vec![],
         }
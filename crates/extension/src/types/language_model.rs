@@ -0,0 +1,160 @@
+/// A language model offered by a language model provider.
+#[derive(Debug, Clone)]
+pub struct LanguageModelInfo {
+    /// The ID of the model, unique within its provider.
+    pub id: String,
+    /// The name of the model to display in the UI.
+    pub name: String,
+    /// The size of the model's context window, in tokens.
+    pub max_token_count: usize,
+    /// The maximum number of tokens the model can produce in a single response.
+    pub max_output_tokens: Option<u32>,
+    /// Whether the model accepts images as input.
+    pub supports_images: bool,
+    /// Whether the model can call tools.
+    pub supports_tools: bool,
+    /// Whether this model should be used by default for its provider.
+    pub is_default: bool,
+    /// Whether this model should be used by default for quick tasks.
+    pub is_default_fast: bool,
+}
+
+/// A request for a completion from a language model.
+#[derive(Debug, Clone)]
+pub struct LanguageModelCompletionRequest {
+    /// The ID of the thread this request was made for, if any.
+    pub thread_id: Option<String>,
+    /// The messages in the conversation.
+    pub messages: Vec<LanguageModelRequestMessage>,
+    /// The tools available to the model.
+    pub tools: Vec<LanguageModelToolDefinition>,
+    /// How the model should use the provided tools.
+    pub tool_choice: Option<LanguageModelToolChoice>,
+    /// The sequences that should stop the completion when produced.
+    pub stop: Vec<String>,
+    /// The sampling temperature.
+    pub temperature: Option<f32>,
+}
+
+/// A message in a [`LanguageModelCompletionRequest`].
+#[derive(Debug, Clone)]
+pub struct LanguageModelRequestMessage {
+    /// The author of the message.
+    pub role: LanguageModelMessageRole,
+    /// The content of the message.
+    pub content: Vec<LanguageModelMessageContent>,
+    /// Whether the provider should cache the conversation up to and including this message.
+    pub cache: bool,
+}
+
+/// The role of the author of a message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LanguageModelMessageRole {
+    User,
+    Assistant,
+    System,
+}
+
+/// A piece of content in a message.
+#[derive(Debug, Clone)]
+pub enum LanguageModelMessageContent {
+    Text(String),
+    Thinking {
+        text: String,
+        signature: Option<String>,
+    },
+    RedactedThinking(Vec<u8>),
+    Image(LanguageModelImage),
+    ToolUse(LanguageModelToolUse),
+    ToolResult(LanguageModelToolResult),
+}
+
+/// An image attached to a message.
+#[derive(Debug, Clone)]
+pub struct LanguageModelImage {
+    /// The base64-encoded PNG data of the image.
+    pub source: String,
+}
+
+/// A request from a language model to call a tool.
+#[derive(Debug, Clone)]
+pub struct LanguageModelToolUse {
+    /// The ID of this tool use, which is referenced by its result.
+    pub id: String,
+    /// The name of the tool to call.
+    pub name: String,
+    /// The input to the tool, as a JSON string.
+    pub input: String,
+}
+
+/// The result of calling a tool.
+#[derive(Debug, Clone)]
+pub struct LanguageModelToolResult {
+    /// The ID of the tool use this is the result of.
+    pub tool_use_id: String,
+    /// The name of the tool that was called.
+    pub tool_name: String,
+    /// Whether the tool call failed.
+    pub is_error: bool,
+    /// The output of the tool.
+    pub content: LanguageModelToolResultContent,
+}
+
+/// The content of a [`LanguageModelToolResult`].
+#[derive(Debug, Clone)]
+pub enum LanguageModelToolResultContent {
+    Text(String),
+    Image(LanguageModelImage),
+}
+
+/// A tool that a language model may call.
+#[derive(Debug, Clone)]
+pub struct LanguageModelToolDefinition {
+    /// The name of the tool.
+    pub name: String,
+    /// The description of the tool.
+    pub description: String,
+    /// The JSON schema of the tool's input.
+    pub input_schema: serde_json::Value,
+}
+
+/// Controls how a language model should use the tools in a request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LanguageModelToolChoice {
+    Auto,
+    Any,
+    None,
+}
+
+/// An event in a streamed completion.
+#[derive(Debug, Clone)]
+pub enum LanguageModelCompletionEvent {
+    StartMessage {
+        message_id: String,
+    },
+    Text(String),
+    Thinking {
+        text: String,
+        signature: Option<String>,
+    },
+    ToolUse(LanguageModelToolUse),
+    Stop(LanguageModelStopReason),
+    UsageUpdate(LanguageModelTokenUsage),
+}
+
+/// The reason a completion stopped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LanguageModelStopReason {
+    EndTurn,
+    MaxTokens,
+    ToolUse,
+}
+
+/// The number of tokens used by a completion.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LanguageModelTokenUsage {
+    pub input_tokens: u32,
+    pub output_tokens: u32,
+    pub cache_creation_input_tokens: u32,
+    pub cache_read_input_tokens: u32,
+}
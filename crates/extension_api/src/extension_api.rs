@@ -5,6 +5,7 @@ pub mod process;
 pub mod settings;
 
 use core::fmt;
+use std::collections::BTreeMap;
 
 use wit::*;
 
@@ -48,6 +49,21 @@ pub mod lsp {
     };
 }
 
+/// Types for providing language models to the Assistant.
+pub mod language_model {
+    pub use crate::wit::zed::extension::language_model::{
+        CompletionEvent, CompletionRequest, Image, LanguageModelInfo, MessageContent, MessageRole,
+        RequestMessage, StopReason, Thinking, TokenUsage, ToolChoice, ToolDefinition, ToolResult,
+        ToolResultContent, ToolUse,
+    };
+
+    /// A stream of events produced while completing a request.
+    ///
+    /// The stream is advanced whenever Zed asks for the next event, so it may block while waiting
+    /// on the provider, e.g., on [`HttpResponseStream::next_chunk`](crate::http_client::HttpResponseStream::next_chunk).
+    pub type CompletionEventStream = Box<dyn Iterator<Item = crate::Result<CompletionEvent>>>;
+}
+
 /// A result returned from a Zed extension.
 pub type Result<T, E = String> = core::result::Result<T, E>;
 
@@ -197,6 +213,34 @@ pub trait Extension: Send + Sync {
     ) -> Result<DebugAdapterBinary, String> {
         Err("`get_dap_binary` not implemented".to_string())
     }
+
+    /// Returns the language models offered by the specified language model provider.
+    fn language_model_provider_models(
+        &mut self,
+        _provider_id: &str,
+    ) -> Result<Vec<language_model::LanguageModelInfo>> {
+        Ok(Vec::new())
+    }
+
+    /// Authenticates with the specified language model provider.
+    ///
+    /// Return an error describing how to authenticate if the provider cannot be used yet.
+    fn language_model_provider_authenticate(&mut self, _provider_id: &str) -> Result<()> {
+        Ok(())
+    }
+
+    /// Starts streaming a completion for the given request from the specified language model.
+    ///
+    /// This is called on a separate instance of the extension, right after
+    /// [`Extension::language_model_provider_authenticate`] for the same provider.
+    fn language_model_stream_completion(
+        &mut self,
+        _provider_id: &str,
+        _model_id: &str,
+        _request: language_model::CompletionRequest,
+    ) -> Result<language_model::CompletionEventStream> {
+        Err("`language_model_stream_completion` not implemented".to_string())
+    }
 }
 
 /// Registers the provided type as a Zed extension.
@@ -229,6 +273,17 @@ fn extension() -> &'static mut dyn Extension {
 
 static mut EXTENSION: Option<Box<dyn Extension>> = None;
 
+fn completion_streams() -> &'static mut BTreeMap<u64, language_model::CompletionEventStream> {
+    #[expect(static_mut_refs)]
+    unsafe {
+        &mut COMPLETION_STREAMS
+    }
+}
+
+static mut COMPLETION_STREAMS: BTreeMap<u64, language_model::CompletionEventStream> =
+    BTreeMap::new();
+static mut NEXT_COMPLETION_STREAM_ID: u64 = 0;
+
 #[cfg(target_arch = "wasm32")]
 #[unsafe(link_section = "zed:api-version")]
 #[doc(hidden)]
@@ -389,6 +444,44 @@ impl wit::Guest for Component {
     ) -> Result<DebugAdapterBinary, String> {
         extension().get_dap_binary(adapter_name, config, user_installed_path)
     }
+
+    fn language_model_provider_models(
+        provider_id: String,
+    ) -> Result<Vec<language_model::LanguageModelInfo>, String> {
+        extension().language_model_provider_models(&provider_id)
+    }
+
+    fn language_model_provider_authenticate(provider_id: String) -> Result<(), String> {
+        extension().language_model_provider_authenticate(&provider_id)
+    }
+
+    fn language_model_stream_completion_start(
+        provider_id: String,
+        model_id: String,
+        request: language_model::CompletionRequest,
+    ) -> Result<u64, String> {
+        let stream =
+            extension().language_model_stream_completion(&provider_id, &model_id, request)?;
+        let stream_id = unsafe {
+            NEXT_COMPLETION_STREAM_ID += 1;
+            NEXT_COMPLETION_STREAM_ID
+        };
+        completion_streams().insert(stream_id, stream);
+        Ok(stream_id)
+    }
+
+    fn language_model_stream_completion_next(
+        stream_id: u64,
+    ) -> Result<Option<language_model::CompletionEvent>, String> {
+        let stream = completion_streams()
+            .get_mut(&stream_id)
+            .ok_or_else(|| format!("no completion stream with ID {stream_id}"))?;
+        stream.next().transpose()
+    }
+
+    fn language_model_stream_completion_close(stream_id: u64) {
+        completion_streams().remove(&stream_id);
+    }
 }
 
 /// The ID of a language server.
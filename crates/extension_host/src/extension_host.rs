@@ -15,8 +15,9 @@ pub use extension::ExtensionManifest;
 use extension::extension_builder::{CompileExtensionOptions, ExtensionBuilder};
 use extension::{
     ExtensionContextServerProxy, ExtensionEvents, ExtensionGrammarProxy, ExtensionHostProxy,
-    ExtensionIndexedDocsProviderProxy, ExtensionLanguageProxy, ExtensionLanguageServerProxy,
-    ExtensionSlashCommandProxy, ExtensionSnippetProxy, ExtensionThemeProxy,
+    ExtensionIndexedDocsProviderProxy, ExtensionLanguageModelProviderProxy, ExtensionLanguageProxy,
+    ExtensionLanguageServerProxy, ExtensionSlashCommandProxy, ExtensionSnippetProxy,
+    ExtensionThemeProxy,
 };
 use fs::{Fs, RemoveOptions};
 use futures::{
@@ -1134,6 +1135,11 @@ impl ExtensionStore {
             for (server_id, _) in extension.manifest.context_servers.iter() {
                 self.proxy.unregister_context_server(server_id.clone(), cx);
             }
+
+            for (provider_id, _) in extension.manifest.language_model_providers.iter() {
+                self.proxy
+                    .unregister_language_model_provider(provider_id.clone(), cx);
+            }
         }
 
         self.wasm_extensions
@@ -1328,6 +1334,15 @@ impl ExtensionStore {
                         this.proxy
                             .register_indexed_docs_provider(extension.clone(), provider_id.clone());
                     }
+
+                    for (provider_id, provider) in &manifest.language_model_providers {
+                        this.proxy.register_language_model_provider(
+                            extension.clone(),
+                            provider_id.clone(),
+                            provider.clone(),
+                            cx,
+                        );
+                    }
                 }
 
                 this.wasm_extensions.extend(wasm_extensions);
@@ -47,6 +47,7 @@ impl Settings for ExtensionSettings {
             [sources.default]
                 .into_iter()
                 .chain(sources.user)
+                .chain(sources.profile)
                 .chain(sources.server),
         )
     }
@@ -5,7 +5,8 @@ use anyhow::{Context as _, Result, anyhow, bail};
 use async_trait::async_trait;
 use extension::{
     CodeLabel, Command, Completion, ContextServerConfiguration, DebugAdapterBinary,
-    DebugTaskDefinition, ExtensionHostProxy, KeyValueStoreDelegate, ProjectDelegate, SlashCommand,
+    DebugTaskDefinition, ExtensionHostProxy, KeyValueStoreDelegate, LanguageModelCompletionEvent,
+    LanguageModelCompletionRequest, LanguageModelInfo, ProjectDelegate, SlashCommand,
     SlashCommandArgumentCompletion, SlashCommandOutput, Symbol, WorktreeDelegate,
 };
 use fs::{Fs, normalize_path};
@@ -17,6 +18,7 @@ use futures::{
         oneshot,
     },
     future::BoxFuture,
+    stream::{self, BoxStream},
 };
 use gpui::{App, AsyncApp, BackgroundExecutor, Task};
 use http_client::HttpClient;
@@ -29,6 +31,7 @@ use std::{
     path::{Path, PathBuf},
     sync::{Arc, OnceLock},
 };
+use util::ResultExt as _;
 use wasmtime::{
     Engine, Store,
     component::{Component, ResourceTable},
@@ -53,8 +56,10 @@ pub struct WasmExtension {
     tx: UnboundedSender<ExtensionCall>,
     pub manifest: Arc<ExtensionManifest>,
     pub work_dir: Arc<Path>,
-    #[allow(unused)]
     pub zed_api_version: SemanticVersion,
+    host: Arc<WasmHost>,
+    component: Component,
+    executor: BackgroundExecutor,
 }
 
 #[async_trait]
@@ -393,6 +398,121 @@ impl extension::Extension for WasmExtension {
         })
         .await
     }
+
+    async fn language_model_provider_models(
+        &self,
+        provider_id: Arc<str>,
+    ) -> Result<Vec<LanguageModelInfo>> {
+        self.call(|extension, store| {
+            async move {
+                extension
+                    .call_language_model_provider_models(store, &provider_id)
+                    .await?
+                    .map_err(|err| anyhow!("{err}"))
+            }
+            .boxed()
+        })
+        .await
+    }
+
+    async fn language_model_provider_authenticate(&self, provider_id: Arc<str>) -> Result<()> {
+        self.call(|extension, store| {
+            async move {
+                extension
+                    .call_language_model_provider_authenticate(store, &provider_id)
+                    .await?
+                    .map_err(|err| anyhow!("{err}"))
+            }
+            .boxed()
+        })
+        .await
+    }
+
+    async fn stream_language_model_completion(
+        &self,
+        provider_id: Arc<str>,
+        model_id: Arc<str>,
+        request: LanguageModelCompletionRequest,
+    ) -> Result<BoxStream<'static, Result<LanguageModelCompletionEvent>>> {
+        // Polling a stream may block for as long as the provider takes to respond, so each
+        // stream is driven by its own instance of the extension rather than by the queue that
+        // serves every other call into it.
+        let tx = self
+            .host
+            .instantiate_extension(
+                &self.component,
+                &self.manifest,
+                self.zed_api_version,
+                &self.executor,
+            )
+            .await?;
+
+        let stream_id = call_extension(&tx, |extension, store| {
+            async move {
+                extension
+                    .call_language_model_provider_authenticate(store, &provider_id)
+                    .await?
+                    .map_err(|err| anyhow!("{err}"))?;
+                extension
+                    .call_language_model_stream_completion_start(
+                        store,
+                        &provider_id,
+                        &model_id,
+                        request,
+                    )
+                    .await?
+                    .map_err(|err| anyhow!("{err}"))
+            }
+            .boxed()
+        })
+        .await?;
+
+        let completion = WasmCompletionStream { tx, stream_id };
+        Ok(stream::unfold(Some(completion), |completion| async move {
+            let completion = completion?;
+            let stream_id = completion.stream_id;
+            let event = call_extension(&completion.tx, move |extension, store| {
+                async move {
+                    extension
+                        .call_language_model_stream_completion_next(store, stream_id)
+                        .await?
+                        .map_err(|err| anyhow!("{err}"))
+                }
+                .boxed()
+            })
+            .await;
+            match event {
+                Ok(Some(event)) => Some((Ok(event), Some(completion))),
+                Ok(None) => None,
+                Err(error) => Some((Err(error), None)),
+            }
+        })
+        .boxed())
+    }
+}
+
+/// A completion being streamed from its own instance of an extension, which is released (along
+/// with the instance) when dropped.
+struct WasmCompletionStream {
+    tx: UnboundedSender<ExtensionCall>,
+    stream_id: u64,
+}
+
+impl Drop for WasmCompletionStream {
+    fn drop(&mut self) {
+        let stream_id = self.stream_id;
+        self.tx
+            .unbounded_send(Box::new(move |extension, store| {
+                async move {
+                    extension
+                        .call_language_model_stream_completion_close(store, stream_id)
+                        .await
+                        .log_err();
+                }
+                .boxed()
+            }))
+            .ok();
+    }
 }
 
 pub struct WasmState {
@@ -463,47 +583,66 @@ impl WasmHost {
             let component = Component::from_binary(&this.engine, &wasm_bytes)
                 .context("failed to compile wasm component")?;
 
-            let mut store = wasmtime::Store::new(
-                &this.engine,
-                WasmState {
-                    ctx: this.build_wasi_ctx(&manifest).await?,
-                    manifest: manifest.clone(),
-                    table: ResourceTable::new(),
-                    host: this.clone(),
-                },
-            );
-
-            let mut extension = Extension::instantiate_async(
-                &mut store,
-                this.release_channel,
-                zed_api_version,
-                &component,
-            )
-            .await?;
-
-            extension
-                .call_init_extension(&mut store)
-                .await
-                .context("failed to initialize wasm extension")?;
-
-            let (tx, mut rx) = mpsc::unbounded::<ExtensionCall>();
-            executor
-                .spawn(async move {
-                    while let Some(call) = rx.next().await {
-                        (call)(&mut extension, &mut store).await;
-                    }
-                })
-                .detach();
+            let tx = this
+                .instantiate_extension(&component, &manifest, zed_api_version, &executor)
+                .await?;
 
             Ok(WasmExtension {
                 manifest: manifest.clone(),
                 work_dir: this.work_dir.join(manifest.id.as_ref()).into(),
                 tx,
                 zed_api_version,
+                host: this,
+                component,
+                executor,
             })
         })
     }
 
+    /// Instantiates and initializes the extension in a new store, returning the queue through
+    /// which calls into that instance are made. The instance is dropped along with the queue.
+    async fn instantiate_extension(
+        self: &Arc<Self>,
+        component: &Component,
+        manifest: &Arc<ExtensionManifest>,
+        zed_api_version: SemanticVersion,
+        executor: &BackgroundExecutor,
+    ) -> Result<UnboundedSender<ExtensionCall>> {
+        let mut store = wasmtime::Store::new(
+            &self.engine,
+            WasmState {
+                ctx: self.build_wasi_ctx(manifest).await?,
+                manifest: manifest.clone(),
+                table: ResourceTable::new(),
+                host: self.clone(),
+            },
+        );
+
+        let mut extension = Extension::instantiate_async(
+            &mut store,
+            self.release_channel,
+            zed_api_version,
+            component,
+        )
+        .await?;
+
+        extension
+            .call_init_extension(&mut store)
+            .await
+            .context("failed to initialize wasm extension")?;
+
+        let (tx, mut rx) = mpsc::unbounded::<ExtensionCall>();
+        executor
+            .spawn(async move {
+                while let Some(call) = rx.next().await {
+                    (call)(&mut extension, &mut store).await;
+                }
+            })
+            .detach();
+
+        Ok(tx)
+    }
+
     async fn build_wasi_ctx(&self, manifest: &Arc<ExtensionManifest>) -> Result<wasi::WasiCtx> {
         let extension_work_dir = self.work_dir.join(manifest.id.as_ref());
         self.fs
@@ -615,21 +754,29 @@ impl WasmExtension {
             + Send
             + for<'a> FnOnce(&'a mut Extension, &'a mut Store<WasmState>) -> BoxFuture<'a, T>,
     {
-        let (return_tx, return_rx) = oneshot::channel();
-        self.tx
-            .clone()
-            .unbounded_send(Box::new(move |extension, store| {
-                async {
-                    let result = f(extension, store).await;
-                    return_tx.send(result).ok();
-                }
-                .boxed()
-            }))
-            .expect("wasm extension channel should not be closed yet");
-        return_rx.await.expect("wasm extension channel")
+        call_extension(&self.tx, f).await
     }
 }
 
+async fn call_extension<T, Fn>(tx: &UnboundedSender<ExtensionCall>, f: Fn) -> T
+where
+    T: 'static + Send,
+    Fn: 'static
+        + Send
+        + for<'a> FnOnce(&'a mut Extension, &'a mut Store<WasmState>) -> BoxFuture<'a, T>,
+{
+    let (return_tx, return_rx) = oneshot::channel();
+    tx.unbounded_send(Box::new(move |extension, store| {
+        async {
+            let result = f(extension, store).await;
+            return_tx.send(result).ok();
+        }
+        .boxed()
+    }))
+    .expect("wasm extension channel should not be closed yet");
+    return_rx.await.expect("wasm extension channel")
+}
+
 impl WasmState {
     fn on_main_thread<T, Fn>(&self, f: Fn) -> impl 'static + Future<Output = T>
     where
@@ -921,6 +921,93 @@ impl Extension {
             _ => Err(anyhow!("`get_dap_binary` not available prior to v0.6.0")),
         }
     }
+
+    pub async fn call_language_model_provider_models(
+        &self,
+        store: &mut Store<WasmState>,
+        provider_id: &str,
+    ) -> Result<Result<Vec<extension::LanguageModelInfo>, String>> {
+        match self {
+            Extension::V0_6_0(ext) => Ok(ext
+                .call_language_model_provider_models(store, provider_id)
+                .await?
+                .map(|models| models.into_iter().map(Into::into).collect())),
+            _ => Err(anyhow!(
+                "`language_model_provider_models` not available prior to v0.6.0"
+            )),
+        }
+    }
+
+    pub async fn call_language_model_provider_authenticate(
+        &self,
+        store: &mut Store<WasmState>,
+        provider_id: &str,
+    ) -> Result<Result<(), String>> {
+        match self {
+            Extension::V0_6_0(ext) => {
+                ext.call_language_model_provider_authenticate(store, provider_id)
+                    .await
+            }
+            _ => Err(anyhow!(
+                "`language_model_provider_authenticate` not available prior to v0.6.0"
+            )),
+        }
+    }
+
+    pub async fn call_language_model_stream_completion_start(
+        &self,
+        store: &mut Store<WasmState>,
+        provider_id: &str,
+        model_id: &str,
+        request: extension::LanguageModelCompletionRequest,
+    ) -> Result<Result<u64, String>> {
+        match self {
+            Extension::V0_6_0(ext) => {
+                ext.call_language_model_stream_completion_start(
+                    store,
+                    provider_id,
+                    model_id,
+                    &request.into(),
+                )
+                .await
+            }
+            _ => Err(anyhow!(
+                "`language_model_stream_completion_start` not available prior to v0.6.0"
+            )),
+        }
+    }
+
+    pub async fn call_language_model_stream_completion_next(
+        &self,
+        store: &mut Store<WasmState>,
+        stream_id: u64,
+    ) -> Result<Result<Option<extension::LanguageModelCompletionEvent>, String>> {
+        match self {
+            Extension::V0_6_0(ext) => Ok(ext
+                .call_language_model_stream_completion_next(store, stream_id)
+                .await?
+                .map(|event| event.map(Into::into))),
+            _ => Err(anyhow!(
+                "`language_model_stream_completion_next` not available prior to v0.6.0"
+            )),
+        }
+    }
+
+    pub async fn call_language_model_stream_completion_close(
+        &self,
+        store: &mut Store<WasmState>,
+        stream_id: u64,
+    ) -> Result<()> {
+        match self {
+            Extension::V0_6_0(ext) => {
+                ext.call_language_model_stream_completion_close(store, stream_id)
+                    .await
+            }
+            _ => Err(anyhow!(
+                "`language_model_stream_completion_close` not available prior to v0.6.0"
+            )),
+        }
+    }
 }
 
 trait ToWasmtimeResult<T> {
@@ -363,6 +363,174 @@ impl TryFrom<ContextServerConfiguration> for extension::ContextServerConfigurati
     }
 }
 
+impl From<language_model::LanguageModelInfo> for extension::LanguageModelInfo {
+    fn from(value: language_model::LanguageModelInfo) -> Self {
+        Self {
+            id: value.id,
+            name: value.name,
+            max_token_count: value.max_token_count as usize,
+            max_output_tokens: value
+                .max_output_tokens
+                .map(|tokens| tokens.min(u32::MAX as u64) as u32),
+            supports_images: value.supports_images,
+            supports_tools: value.supports_tools,
+            is_default: value.is_default,
+            is_default_fast: value.is_default_fast,
+        }
+    }
+}
+
+impl From<extension::LanguageModelCompletionRequest> for language_model::CompletionRequest {
+    fn from(value: extension::LanguageModelCompletionRequest) -> Self {
+        Self {
+            thread_id: value.thread_id,
+            messages: value.messages.into_iter().map(Into::into).collect(),
+            tools: value.tools.into_iter().map(Into::into).collect(),
+            tool_choice: value.tool_choice.map(Into::into),
+            stop: value.stop,
+            temperature: value.temperature,
+        }
+    }
+}
+
+impl From<extension::LanguageModelRequestMessage> for language_model::RequestMessage {
+    fn from(value: extension::LanguageModelRequestMessage) -> Self {
+        Self {
+            role: value.role.into(),
+            content: value.content.into_iter().map(Into::into).collect(),
+            cache: value.cache,
+        }
+    }
+}
+
+impl From<extension::LanguageModelMessageRole> for language_model::MessageRole {
+    fn from(value: extension::LanguageModelMessageRole) -> Self {
+        match value {
+            extension::LanguageModelMessageRole::User => Self::User,
+            extension::LanguageModelMessageRole::Assistant => Self::Assistant,
+            extension::LanguageModelMessageRole::System => Self::System,
+        }
+    }
+}
+
+impl From<extension::LanguageModelMessageContent> for language_model::MessageContent {
+    fn from(value: extension::LanguageModelMessageContent) -> Self {
+        match value {
+            extension::LanguageModelMessageContent::Text(text) => Self::Text(text),
+            extension::LanguageModelMessageContent::Thinking { text, signature } => {
+                Self::Thinking(language_model::Thinking { text, signature })
+            }
+            extension::LanguageModelMessageContent::RedactedThinking(data) => {
+                Self::RedactedThinking(data)
+            }
+            extension::LanguageModelMessageContent::Image(image) => Self::Image(image.into()),
+            extension::LanguageModelMessageContent::ToolUse(tool_use) => {
+                Self::ToolUse(tool_use.into())
+            }
+            extension::LanguageModelMessageContent::ToolResult(tool_result) => {
+                Self::ToolResult(tool_result.into())
+            }
+        }
+    }
+}
+
+impl From<extension::LanguageModelImage> for language_model::Image {
+    fn from(value: extension::LanguageModelImage) -> Self {
+        Self {
+            source: value.source,
+        }
+    }
+}
+
+impl From<extension::LanguageModelToolUse> for language_model::ToolUse {
+    fn from(value: extension::LanguageModelToolUse) -> Self {
+        Self {
+            id: value.id,
+            name: value.name,
+            input: value.input,
+        }
+    }
+}
+
+impl From<language_model::ToolUse> for extension::LanguageModelToolUse {
+    fn from(value: language_model::ToolUse) -> Self {
+        Self {
+            id: value.id,
+            name: value.name,
+            input: value.input,
+        }
+    }
+}
+
+impl From<extension::LanguageModelToolResult> for language_model::ToolResult {
+    fn from(value: extension::LanguageModelToolResult) -> Self {
+        Self {
+            tool_use_id: value.tool_use_id,
+            tool_name: value.tool_name,
+            is_error: value.is_error,
+            content: match value.content {
+                extension::LanguageModelToolResultContent::Text(text) => {
+                    language_model::ToolResultContent::Text(text)
+                }
+                extension::LanguageModelToolResultContent::Image(image) => {
+                    language_model::ToolResultContent::Image(image.into())
+                }
+            },
+        }
+    }
+}
+
+impl From<extension::LanguageModelToolDefinition> for language_model::ToolDefinition {
+    fn from(value: extension::LanguageModelToolDefinition) -> Self {
+        Self {
+            name: value.name,
+            description: value.description,
+            input_schema: value.input_schema.to_string(),
+        }
+    }
+}
+
+impl From<extension::LanguageModelToolChoice> for language_model::ToolChoice {
+    fn from(value: extension::LanguageModelToolChoice) -> Self {
+        match value {
+            extension::LanguageModelToolChoice::Auto => Self::Auto,
+            extension::LanguageModelToolChoice::Any => Self::Any,
+            extension::LanguageModelToolChoice::None => Self::None,
+        }
+    }
+}
+
+impl From<language_model::CompletionEvent> for extension::LanguageModelCompletionEvent {
+    fn from(value: language_model::CompletionEvent) -> Self {
+        match value {
+            language_model::CompletionEvent::StartMessage(message_id) => {
+                Self::StartMessage { message_id }
+            }
+            language_model::CompletionEvent::Text(text) => Self::Text(text),
+            language_model::CompletionEvent::Thinking(thinking) => Self::Thinking {
+                text: thinking.text,
+                signature: thinking.signature,
+            },
+            language_model::CompletionEvent::ToolUse(tool_use) => Self::ToolUse(tool_use.into()),
+            language_model::CompletionEvent::Stop(reason) => Self::Stop(match reason {
+                language_model::StopReason::EndTurn => extension::LanguageModelStopReason::EndTurn,
+                language_model::StopReason::MaxTokens => {
+                    extension::LanguageModelStopReason::MaxTokens
+                }
+                language_model::StopReason::ToolUse => extension::LanguageModelStopReason::ToolUse,
+            }),
+            language_model::CompletionEvent::UsageUpdate(usage) => {
+                Self::UsageUpdate(extension::LanguageModelTokenUsage {
+                    input_tokens: usage.input_tokens,
+                    output_tokens: usage.output_tokens,
+                    cache_creation_input_tokens: usage.cache_creation_input_tokens,
+                    cache_read_input_tokens: usage.cache_read_input_tokens,
+                })
+            }
+        }
+    }
+}
+
 impl HostKeyValueStore for WasmState {
     async fn insert(
         &mut self,
@@ -732,6 +900,9 @@ impl context_server::Host for WasmState {}
 #[async_trait]
 impl dap::Host for WasmState {}
 
+#[async_trait]
+impl language_model::Host for WasmState {}
+
 impl ExtensionImports for WasmState {
     async fn get_settings(
         &mut self,
@@ -0,0 +1,660 @@
+//! Accessibility support for GPUI.
+//!
+//! While painting, elements describe themselves to assistive technologies by adding nodes to
+//! an accessibility tree with [`Window::with_accessibility_node`]. While an assistive
+//! technology observes the window, the tree is rebuilt every frame alongside the
+//! [`crate::Scene`], and is handed to the platform, which exposes it to screen readers.
+//! Otherwise no nodes are added, so that painting doesn't pay for it. Tests can inspect the
+//! tree of a window with [`crate::VisualTestContext::accessibility_tree`].
+//!
+//! Most elements don't need to call into the window directly: [`crate::Div`] and the other
+//! interactive elements add a node when they're given a role with
+//! [`crate::InteractiveElement::role`], and text is added as a [`Role::Label`] automatically.
+
+use crate::{Bounds, FocusHandle, FocusId, GlobalElementId, Pixels, SharedString};
+use collections::FxHashMap;
+use smallvec::SmallVec;
+use std::{
+    fmt,
+    hash::{DefaultHasher, Hash, Hasher},
+    ops::Range,
+    sync::Arc,
+};
+
+/// The kind of user interface element an accessibility node represents.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Role {
+    /// The root of a window's tree.
+    Window,
+    /// A generic container for other nodes.
+    Group,
+    /// A button that performs an action when clicked.
+    Button,
+    /// A check box that can be toggled on and off.
+    CheckBox,
+    /// A switch that can be toggled on and off.
+    Switch,
+    /// One option among a group of mutually exclusive options.
+    RadioButton,
+    /// A link to another location.
+    Link,
+    /// Static text.
+    Label,
+    /// A heading introducing a section of content.
+    Heading,
+    /// A single-line text input.
+    TextInput,
+    /// A multi-line text input, such as a code editor.
+    MultilineTextInput,
+    /// A list of items.
+    List,
+    /// An item of a [`Role::List`].
+    ListItem,
+    /// A hierarchical list of items.
+    Tree,
+    /// An item of a [`Role::Tree`].
+    TreeItem,
+    /// A list of tabs.
+    TabList,
+    /// A tab that selects which content is shown.
+    Tab,
+    /// A menu of actions.
+    Menu,
+    /// An item of a [`Role::Menu`].
+    MenuItem,
+    /// A toolbar of controls.
+    Toolbar,
+    /// A window-like element that requires the user's attention.
+    Dialog,
+    /// A popup describing another element.
+    Tooltip,
+    /// An image.
+    Image,
+    /// A visual separator between groups of content.
+    Separator,
+    /// An element whose contents can be scrolled.
+    ScrollView,
+    /// An indicator of the progress of a task.
+    ProgressIndicator,
+}
+
+/// The checked state of a node that can be toggled, such as a [`Role::CheckBox`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Toggled {
+    /// The node is not checked.
+    False,
+    /// The node is checked.
+    True,
+    /// The node is partially checked, for example because only some of its children are.
+    Mixed,
+}
+
+/// An action that assistive technologies can ask a node to perform.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum AccessibilityAction {
+    /// Activate the node, as if it was clicked with the mouse.
+    Click,
+    /// Move the keyboard focus to the node.
+    Focus,
+}
+
+/// Uniquely identifies a node in a window's accessibility tree.
+///
+/// Nodes added by elements with an [`crate::ElementId`] keep the same identifier across frames.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct AccessibilityNodeId(pub u64);
+
+impl AccessibilityNodeId {
+    /// The identifier of the node representing the window itself.
+    pub const ROOT: Self = Self(0);
+}
+
+/// A request made by assistive technologies, which the platform forwards to the window.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AccessibilityRequest {
+    /// An assistive technology started observing the window and needs its current tree.
+    TreeRequested,
+    /// An assistive technology asked a node to perform an action.
+    Action {
+        /// The node to perform the action on.
+        node: AccessibilityNodeId,
+        /// The action to perform.
+        action: AccessibilityAction,
+    },
+}
+
+/// The properties describing an accessibility node, besides its role and bounds.
+#[derive(Clone, Debug, Default)]
+pub struct AccessibilityProperties {
+    /// The name assistive technologies announce for the node. When unset, the name is
+    /// computed from the node's descendants.
+    pub label: Option<SharedString>,
+    /// The current value of the node, such as the text of an input.
+    pub value: Option<SharedString>,
+    /// A longer description of the node.
+    pub description: Option<SharedString>,
+    /// Whether the node can't currently be interacted with.
+    pub disabled: bool,
+    /// Whether the node is selected, for nodes that can be selected.
+    pub selected: Option<bool>,
+    /// Whether the node is checked, for nodes that can be toggled.
+    pub toggled: Option<Toggled>,
+    /// Whether the node is expanded, for nodes that can be expanded or collapsed.
+    pub expanded: Option<bool>,
+    /// The actions the node supports.
+    pub actions: SmallVec<[AccessibilityAction; 2]>,
+    /// The focus handle of the node, for nodes that can be focused.
+    pub focus_handle: Option<FocusHandle>,
+}
+
+impl AccessibilityProperties {
+    /// Set the name assistive technologies announce for the node.
+    pub fn label(mut self, label: impl Into<SharedString>) -> Self {
+        self.label = Some(label.into());
+        self
+    }
+
+    /// Set the current value of the node.
+    pub fn value(mut self, value: impl Into<SharedString>) -> Self {
+        self.value = Some(value.into());
+        self
+    }
+
+    /// Set a longer description of the node.
+    pub fn description(mut self, description: impl Into<SharedString>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    /// Set whether the node can't currently be interacted with.
+    pub fn disabled(mut self, disabled: bool) -> Self {
+        self.disabled = disabled;
+        self
+    }
+
+    /// Set whether the node is selected.
+    pub fn selected(mut self, selected: bool) -> Self {
+        self.selected = Some(selected);
+        self
+    }
+
+    /// Set whether the node is checked.
+    pub fn toggled(mut self, toggled: Toggled) -> Self {
+        self.toggled = Some(toggled);
+        self
+    }
+
+    /// Set whether the node is expanded.
+    pub fn expanded(mut self, expanded: bool) -> Self {
+        self.expanded = Some(expanded);
+        self
+    }
+
+    /// Add an action the node supports.
+    pub fn action(mut self, action: AccessibilityAction) -> Self {
+        if !self.actions.contains(&action) {
+            self.actions.push(action);
+        }
+        self
+    }
+}
+
+/// A node of a window's accessibility tree.
+#[derive(Clone, Debug)]
+pub struct AccessibilityNode {
+    /// The identifier of the node.
+    pub id: AccessibilityNodeId,
+    /// The kind of element the node represents.
+    pub role: Role,
+    /// The bounds of the node, in window coordinates.
+    pub bounds: Bounds<Pixels>,
+    /// The name of the node, if one was given explicitly.
+    pub label: Option<SharedString>,
+    /// The current value of the node.
+    pub value: Option<SharedString>,
+    /// A longer description of the node.
+    pub description: Option<SharedString>,
+    /// Whether the node can't currently be interacted with.
+    pub disabled: bool,
+    /// Whether the node is selected, for nodes that can be selected.
+    pub selected: Option<bool>,
+    /// Whether the node is checked, for nodes that can be toggled.
+    pub toggled: Option<Toggled>,
+    /// Whether the node is expanded, for nodes that can be expanded or collapsed.
+    pub expanded: Option<bool>,
+    /// Whether the node has the keyboard focus.
+    pub focused: bool,
+    /// The actions the node supports.
+    pub actions: SmallVec<[AccessibilityAction; 2]>,
+    /// The parent of the node, which is `None` for the root.
+    pub parent: Option<AccessibilityNodeId>,
+    /// The children of the node, in paint order.
+    pub children: Vec<AccessibilityNodeId>,
+}
+
+/// The accessibility tree of a window, as of the last frame it drew.
+#[derive(Clone)]
+pub struct AccessibilityTree {
+    nodes: Arc<FxHashMap<AccessibilityNodeId, AccessibilityNode>>,
+    focus: Option<AccessibilityNodeId>,
+}
+
+impl AccessibilityTree {
+    /// Returns the node representing the window.
+    pub fn root(&self) -> &AccessibilityNode {
+        &self.nodes[&AccessibilityNodeId::ROOT]
+    }
+
+    /// Returns the node with the given identifier.
+    pub fn node(&self, id: AccessibilityNodeId) -> Option<&AccessibilityNode> {
+        self.nodes.get(&id)
+    }
+
+    /// Returns every node in the tree, in no particular order.
+    pub fn nodes(&self) -> impl Iterator<Item = &AccessibilityNode> {
+        self.nodes.values()
+    }
+
+    /// Returns the children of the given node.
+    pub fn children(&self, node: &AccessibilityNode) -> impl Iterator<Item = &AccessibilityNode> {
+        node.children.iter().filter_map(|id| self.nodes.get(id))
+    }
+
+    /// Returns the node that has the keyboard focus, if any.
+    pub fn focused(&self) -> Option<&AccessibilityNode> {
+        self.focus.and_then(|id| self.nodes.get(&id))
+    }
+
+    /// Returns the identifier of the node that has the keyboard focus, if any.
+    pub fn focus(&self) -> Option<AccessibilityNodeId> {
+        self.focus
+    }
+
+    /// Returns the first node, in depth-first paint order, that matches the predicate.
+    pub fn find(
+        &self,
+        mut predicate: impl FnMut(&AccessibilityNode) -> bool,
+    ) -> Option<&AccessibilityNode> {
+        self.descendants(self.root()).find(|node| predicate(node))
+    }
+
+    /// Returns the first node with the given role whose name is the given one.
+    pub fn find_by_name(&self, role: Role, name: &str) -> Option<&AccessibilityNode> {
+        self.find(|node| node.role == role && self.name(node) == name)
+    }
+
+    /// Returns all nodes with the given role, in depth-first paint order.
+    pub fn find_all(&self, role: Role) -> Vec<&AccessibilityNode> {
+        self.descendants(self.root())
+            .filter(|node| node.role == role)
+            .collect()
+    }
+
+    /// Returns the given node and all of its descendants, in depth-first paint order.
+    pub fn descendants<'a>(
+        &'a self,
+        node: &'a AccessibilityNode,
+    ) -> impl Iterator<Item = &'a AccessibilityNode> + 'a {
+        let mut stack = vec![node];
+        std::iter::from_fn(move || {
+            let node = stack.pop()?;
+            stack.extend(self.children(node).rev());
+            Some(node)
+        })
+    }
+
+    /// Returns the name assistive technologies announce for the node: its label if it has
+    /// one, and otherwise the labels of its descendants, joined by spaces.
+    pub fn name(&self, node: &AccessibilityNode) -> String {
+        if let Some(label) = &node.label {
+            return label.to_string();
+        }
+
+        let mut name = String::new();
+        for child in self.children(node) {
+            let child_name = self.name(child);
+            if child_name.is_empty() {
+                continue;
+            }
+            if !name.is_empty() {
+                name.push(' ');
+            }
+            name.push_str(&child_name);
+        }
+        name
+    }
+
+    fn fmt_node(
+        &self,
+        node: &AccessibilityNode,
+        depth: usize,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        write!(f, "{:indent$}{:?}", "", node.role, indent = depth * 2)?;
+        if let Some(label) = &node.label {
+            write!(f, " {:?}", label.as_ref())?;
+        }
+        if let Some(value) = &node.value {
+            write!(f, " value={:?}", value.as_ref())?;
+        }
+        if let Some(toggled) = node.toggled {
+            write!(f, " toggled={toggled:?}")?;
+        }
+        if let Some(selected) = node.selected {
+            write!(f, " selected={selected}")?;
+        }
+        if let Some(expanded) = node.expanded {
+            write!(f, " expanded={expanded}")?;
+        }
+        if node.disabled {
+            write!(f, " disabled")?;
+        }
+        if node.focused {
+            write!(f, " focused")?;
+        }
+        writeln!(f)?;
+
+        for child in self.children(node) {
+            self.fmt_node(child, depth + 1, f)?;
+        }
+        Ok(())
+    }
+}
+
+/// Prints the tree with one node per line, indenting children below their parent. This
+/// makes it easy to compare whole trees in tests.
+impl fmt::Display for AccessibilityTree {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.fmt_node(self.root(), 0, f)
+    }
+}
+
+impl fmt::Debug for AccessibilityTree {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+#[derive(Clone)]
+pub(crate) enum AccessibilityEntry {
+    Open {
+        stable_id: Option<u64>,
+        role: Role,
+        bounds: Bounds<Pixels>,
+        properties: AccessibilityProperties,
+    },
+    Close,
+}
+
+/// Collects the nodes elements add while painting a frame. Nodes are stored as a flat list
+/// of open and close entries, so that the entries of a view can be replayed into the next
+/// frame when the view is cached, just like its scene primitives.
+#[derive(Default)]
+pub(crate) struct AccessibilityTreeBuilder {
+    entries: Vec<AccessibilityEntry>,
+}
+
+impl AccessibilityTreeBuilder {
+    pub(crate) fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    pub(crate) fn open(
+        &mut self,
+        id: Option<&GlobalElementId>,
+        role: Role,
+        bounds: Bounds<Pixels>,
+        properties: AccessibilityProperties,
+    ) {
+        let stable_id = id.map(|id| {
+            let mut hasher = DefaultHasher::new();
+            id.hash(&mut hasher);
+            hasher.finish()
+        });
+        self.entries.push(AccessibilityEntry::Open {
+            stable_id,
+            role,
+            bounds,
+            properties,
+        });
+    }
+
+    pub(crate) fn close(&mut self) {
+        self.entries.push(AccessibilityEntry::Close);
+    }
+
+    pub(crate) fn replay(&mut self, range: Range<usize>, prev: &Self) {
+        self.entries.extend(prev.entries[range].iter().cloned());
+    }
+
+    /// Builds the tree of the frame. Returns the tree along with the focus handles of its
+    /// nodes, which the window needs to perform [`AccessibilityAction::Focus`].
+    pub(crate) fn build(
+        &self,
+        window_bounds: Bounds<Pixels>,
+        title: Option<SharedString>,
+        focus: Option<FocusId>,
+    ) -> (
+        AccessibilityTree,
+        FxHashMap<AccessibilityNodeId, FocusHandle>,
+    ) {
+        let mut nodes = FxHashMap::default();
+        let mut focus_handles = FxHashMap::default();
+        let mut focused_node = None;
+
+        nodes.insert(
+            AccessibilityNodeId::ROOT,
+            AccessibilityNode {
+                id: AccessibilityNodeId::ROOT,
+                role: Role::Window,
+                bounds: window_bounds,
+                label: title,
+                value: None,
+                description: None,
+                disabled: false,
+                selected: None,
+                toggled: None,
+                expanded: None,
+                focused: false,
+                actions: SmallVec::new(),
+                parent: None,
+                children: Vec::new(),
+            },
+        );
+
+        let mut stack = vec![AccessibilityNodeId::ROOT];
+        for entry in &self.entries {
+            let (stable_id, role, bounds, properties) = match entry {
+                AccessibilityEntry::Open {
+                    stable_id,
+                    role,
+                    bounds,
+                    properties,
+                } => (stable_id, role, bounds, properties),
+                AccessibilityEntry::Close => {
+                    if stack.len() > 1 {
+                        stack.pop();
+                    }
+                    continue;
+                }
+            };
+
+            let parent_id = *stack.last().unwrap();
+            let parent = nodes.get_mut(&parent_id).unwrap();
+            let child_ix = parent.children.len();
+
+            // Nodes without an element ID are identified by their position in the tree.
+            let mut id = AccessibilityNodeId(stable_id.unwrap_or_else(|| {
+                let mut hasher = DefaultHasher::new();
+                (parent_id, child_ix).hash(&mut hasher);
+                hasher.finish()
+            }));
+            while id == AccessibilityNodeId::ROOT || nodes.contains_key(&id) {
+                let mut hasher = DefaultHasher::new();
+                (id, child_ix).hash(&mut hasher);
+                id = AccessibilityNodeId(hasher.finish());
+            }
+            nodes.get_mut(&parent_id).unwrap().children.push(id);
+
+            let focused = properties
+                .focus_handle
+                .as_ref()
+                .is_some_and(|handle| Some(handle.id) == focus);
+            if focused {
+                focused_node = Some(id);
+            }
+            if let Some(focus_handle) = &properties.focus_handle {
+                focus_handles.insert(id, focus_handle.clone());
+            }
+
+            nodes.insert(
+                id,
+                AccessibilityNode {
+                    id,
+                    role: *role,
+                    bounds: *bounds,
+                    label: properties.label.clone(),
+                    value: properties.value.clone(),
+                    description: properties.description.clone(),
+                    disabled: properties.disabled,
+                    selected: properties.selected,
+                    toggled: properties.toggled,
+                    expanded: properties.expanded,
+                    focused,
+                    actions: properties.actions.clone(),
+                    parent: Some(parent_id),
+                    children: Vec::new(),
+                },
+            );
+            stack.push(id);
+        }
+
+        (
+            AccessibilityTree {
+                nodes: Arc::new(nodes),
+                focus: focused_node,
+            },
+            focus_handles,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        Context, FocusHandle, InteractiveElement as _, IntoElement, ParentElement as _, Render,
+        StatefulInteractiveElement as _, Styled as _, TestAppContext, Window, div, px,
+    };
+
+    struct Form {
+        focus_handle: FocusHandle,
+        saves: usize,
+    }
+
+    impl Render for Form {
+        fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+            div()
+                .size_full()
+                .child(div().role(Role::Heading).child("Settings"))
+                .child(
+                    div()
+                        .id("save")
+                        .role(Role::Button)
+                        .size(px(40.))
+                        .on_click(cx.listener(|this, _, _, _| this.saves += 1))
+                        .child("Save"),
+                )
+                .child(
+                    div()
+                        .id("autosave")
+                        .role(Role::CheckBox)
+                        .accessibility(|a| a.label("Autosave").toggled(Toggled::True))
+                        .track_focus(&self.focus_handle)
+                        .size(px(20.)),
+                )
+                .child(
+                    div()
+                        .role(Role::Button)
+                        .accessibility(|a| a.label("Delete").disabled(true)),
+                )
+        }
+    }
+
+    #[crate::test]
+    fn test_accessibility_tree(cx: &mut TestAppContext) {
+        let (form, cx) = cx.add_window_view(|_, cx| Form {
+            focus_handle: cx.focus_handle(),
+            saves: 0,
+        });
+
+        let tree = cx.accessibility_tree();
+        assert_eq!(
+            tree.to_string(),
+            concat!(
+                "Window\n",
+                "  Heading\n",
+                "    Label \"Settings\"\n",
+                "  Button\n",
+                "    Label \"Save\"\n",
+                "  CheckBox \"Autosave\" toggled=True\n",
+                "  Button \"Delete\" disabled\n",
+            )
+        );
+
+        let save = tree.find_by_name(Role::Button, "Save").unwrap();
+        assert_eq!(save.actions.as_slice(), &[AccessibilityAction::Click]);
+        let checkbox = tree.find_by_name(Role::CheckBox, "Autosave").unwrap();
+        assert_eq!(checkbox.actions.as_slice(), &[AccessibilityAction::Focus]);
+        assert!(tree.focused().is_none());
+
+        // Node IDs of elements with an element ID are stable across frames.
+        let save_id = save.id;
+        let checkbox_id = checkbox.id;
+        form.update(cx, |form, cx| {
+            form.saves = 0;
+            cx.notify();
+        });
+        let tree = cx.accessibility_tree();
+        assert_eq!(tree.find_by_name(Role::Button, "Save").unwrap().id, save_id);
+
+        cx.simulate_accessibility_action(save_id, AccessibilityAction::Click);
+        assert_eq!(form.read_with(cx, |form, _| form.saves), 1);
+
+        cx.simulate_accessibility_action(checkbox_id, AccessibilityAction::Focus);
+        let tree = cx.accessibility_tree();
+        assert_eq!(tree.focused().map(|node| node.id), Some(checkbox_id));
+        assert!(
+            tree.to_string()
+                .contains("CheckBox \"Autosave\" toggled=True focused")
+        );
+
+        // Disabled nodes don't perform actions.
+        let delete_id = tree.find_by_name(Role::Button, "Delete").unwrap().id;
+        cx.simulate_accessibility_action(delete_id, AccessibilityAction::Click);
+        assert_eq!(form.read_with(cx, |form, _| form.saves), 1);
+    }
+
+    #[crate::test]
+    fn test_accessibility_tree_requires_assistive_technology(cx: &mut TestAppContext) {
+        let (_, cx) = cx.add_window_view(|_, cx| Form {
+            focus_handle: cx.focus_handle(),
+            saves: 0,
+        });
+
+        // Without an assistive technology observing the window, no nodes are built.
+        let tree = cx.update(|window, cx| {
+            window.draw(cx);
+            window.accessibility_tree()
+        });
+        assert_eq!(tree.to_string(), "Window\n");
+
+        // Once one starts observing it, views are drawn again to build their nodes.
+        let tree = cx.accessibility_tree();
+        assert!(tree.find_by_name(Role::Button, "Save").is_some());
+    }
+}
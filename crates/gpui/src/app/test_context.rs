@@ -1,14 +1,15 @@
 use crate::{
-    Action, AnyView, AnyWindowHandle, App, AppCell, AppContext, AsyncApp, AvailableSpace,
-    BackgroundExecutor, BorrowAppContext, Bounds, ClipboardItem, DrawPhase, Drawable, Element,
-    Empty, EventEmitter, ForegroundExecutor, Global, InputEvent, Keystroke, Modifiers,
-    ModifiersChangedEvent, MouseButton, MouseDownEvent, MouseMoveEvent, MouseUpEvent, Pixels,
-    Platform, Point, Render, Result, Size, Task, TestDispatcher, TestPlatform,
-    TestScreenCaptureSource, TestWindow, TextSystem, VisualContext, Window, WindowBounds,
-    WindowHandle, WindowOptions,
+    AccessibilityAction, AccessibilityNodeId, AccessibilityTree, Action, AnyView, AnyWindowHandle,
+    App, AppCell, AppContext, AsyncApp, AvailableSpace, BackgroundExecutor, BorrowAppContext,
+    Bounds, ClipboardItem, DrawPhase, Drawable, Element, Empty, EventEmitter, ForegroundExecutor,
+    Global, InputEvent, Keystroke, Modifiers, ModifiersChangedEvent, MouseButton, MouseDownEvent,
+    MouseMoveEvent, MouseUpEvent, Pixels, Platform, Point, Render, Result, Size, Task,
+    TestDispatcher, TestPlatform, TestScreenCaptureSource, TestWindow, TextSystem, VisualContext,
+    Window, WindowBounds, WindowHandle, WindowOptions,
 };
 use anyhow::{anyhow, bail};
 use futures::{Stream, StreamExt, channel::oneshot};
+use image::RgbaImage;
 use std::{cell::RefCell, future::Future, ops::Deref, rc::Rc, sync::Arc, time::Duration};
 
 /// A TestAppContext is provided to tests created with `#[gpui::test]`, it provides
@@ -690,6 +691,40 @@ impl VisualTestContext {
         self.cx.dispatch_action(self.window, action)
     }
 
+    /// Draws the window and rasterizes the resulting frame on the CPU, returning the
+    /// pixels the window would show. See [`crate::assert_screenshot_matches`] to compare
+    /// the result against a reference image.
+    pub fn capture_screenshot(&mut self) -> RgbaImage {
+        self.update(|window, cx| {
+            window.draw(cx);
+            let test_window = window.platform_window.as_test().unwrap();
+            test_window.render_scene(&window.rendered_frame.scene)
+        })
+    }
+
+    /// Draws the window and returns its accessibility tree, as assistive technologies
+    /// would see it. The tree is only built while an assistive technology is active, so
+    /// this simulates one from then on.
+    pub fn accessibility_tree(&mut self) -> AccessibilityTree {
+        self.update(|window, cx| {
+            let test_window = window.platform_window.as_test().unwrap();
+            test_window.0.lock().accessibility_active = true;
+            window.draw(cx);
+            window.accessibility_tree()
+        })
+    }
+
+    /// Simulates an assistive technology asking a node of the window's accessibility tree
+    /// to perform an action. Automatically runs until parked.
+    pub fn simulate_accessibility_action(
+        &mut self,
+        node: AccessibilityNodeId,
+        action: AccessibilityAction,
+    ) {
+        self.update(|window, cx| window.perform_accessibility_action(node, action, cx));
+        self.background_executor.run_until_parked();
+    }
+
     /// Read the title off the window (set by `Window#set_window_title`)
     pub fn window_title(&mut self) -> Option<String> {
         self.cx.test_window(self.window).0.lock().title.clone()
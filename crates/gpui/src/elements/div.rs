@@ -16,12 +16,12 @@
 //! constructed by combining these two systems into an all-in-one element.
 
 use crate::{
-    Action, AnyDrag, AnyElement, AnyTooltip, AnyView, App, Bounds, ClickEvent, DispatchPhase,
-    Element, ElementId, Entity, FocusHandle, Global, GlobalElementId, Hitbox, HitboxId,
-    IntoElement, IsZero, KeyContext, KeyDownEvent, KeyUpEvent, LayoutId, ModifiersChangedEvent,
-    MouseButton, MouseDownEvent, MouseMoveEvent, MouseUpEvent, ParentElement, Pixels, Point,
-    Render, ScrollWheelEvent, SharedString, Size, Style, StyleRefinement, Styled, Task, TooltipId,
-    Visibility, Window, point, px, size,
+    AccessibilityAction, AccessibilityProperties, Action, AnyDrag, AnyElement, AnyTooltip, AnyView,
+    App, Bounds, ClickEvent, DispatchPhase, Element, ElementId, Entity, FocusHandle, Global,
+    GlobalElementId, Hitbox, HitboxId, IntoElement, IsZero, KeyContext, KeyDownEvent, KeyUpEvent,
+    LayoutId, ModifiersChangedEvent, MouseButton, MouseDownEvent, MouseMoveEvent, MouseUpEvent,
+    ParentElement, Pixels, Point, Render, Role, ScrollWheelEvent, SharedString, Size, Style,
+    StyleRefinement, Styled, Task, TooltipId, Visibility, Window, point, px, size,
 };
 use collections::HashMap;
 use refineable::Refineable;
@@ -574,6 +574,25 @@ pub trait InteractiveElement: Sized {
         Stateful { element: self }
     }
 
+    /// Expose this element to assistive technologies with the given role. Without a role,
+    /// an element's children are exposed as if they belonged to its parent.
+    fn role(mut self, role: Role) -> Self {
+        self.interactivity().accessibility_role = Some(role);
+        self
+    }
+
+    /// Describe this element to assistive technologies, such as screen readers. This only
+    /// has an effect on elements that have a [`role`](Self::role).
+    fn accessibility(
+        mut self,
+        f: impl FnOnce(AccessibilityProperties) -> AccessibilityProperties,
+    ) -> Self {
+        let properties = self.interactivity().accessibility.take();
+        self.interactivity().accessibility =
+            Some(Box::new(f(properties.map(|p| *p).unwrap_or_default())));
+        self
+    }
+
     /// Track the focus state of the given focus handle on this element.
     /// If the focus handle is focused by the application, this element will
     /// apply its focused styles.
@@ -1402,6 +1421,8 @@ pub struct Interactivity {
     pub(crate) hover_listener: Option<Box<dyn Fn(&bool, &mut Window, &mut App)>>,
     pub(crate) tooltip_builder: Option<TooltipBuilder>,
     pub(crate) occlude_mouse: bool,
+    pub(crate) accessibility_role: Option<Role>,
+    pub(crate) accessibility: Option<Box<AccessibilityProperties>>,
 
     #[cfg(debug_assertions)]
     pub(crate) location: Option<core::panic::Location<'static>>,
@@ -1670,7 +1691,21 @@ impl Interactivity {
                                     }
 
                                     self.paint_keyboard_listeners(window, cx);
-                                    f(&style, window, cx);
+                                    if let Some(role) = self
+                                        .accessibility_role
+                                        .filter(|_| window.is_accessibility_active())
+                                    {
+                                        let properties = self.accessibility_properties();
+                                        window.with_accessibility_node(
+                                            global_id,
+                                            role,
+                                            properties,
+                                            bounds,
+                                            |window| f(&style, window, cx),
+                                        );
+                                    } else {
+                                        f(&style, window, cx);
+                                    }
 
                                     if hitbox.is_some() {
                                         if let Some(group) = self.group.as_ref() {
@@ -1688,6 +1723,20 @@ impl Interactivity {
         );
     }
 
+    fn accessibility_properties(&self) -> AccessibilityProperties {
+        let mut properties = self.accessibility.as_deref().cloned().unwrap_or_default();
+        if !self.click_listeners.is_empty() {
+            properties = properties.action(AccessibilityAction::Click);
+        }
+        if let Some(focus_handle) = self.tracked_focus_handle.as_ref() {
+            properties = properties.action(AccessibilityAction::Focus);
+            properties
+                .focus_handle
+                .get_or_insert_with(|| focus_handle.clone());
+        }
+        properties
+    }
+
     #[cfg(debug_assertions)]
     fn paint_debug_info(
         &self,
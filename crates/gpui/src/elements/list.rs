@@ -8,9 +8,9 @@
 //! If all of your elements are the same height, see [`UniformList`] for a simpler API
 
 use crate::{
-    AnyElement, App, AvailableSpace, Bounds, ContentMask, DispatchPhase, Edges, Element, EntityId,
-    FocusHandle, GlobalElementId, Hitbox, IntoElement, Pixels, Point, ScrollWheelEvent, Size,
-    Style, StyleRefinement, Styled, Window, point, px, size,
+    AccessibilityProperties, AnyElement, App, AvailableSpace, Bounds, ContentMask, DispatchPhase,
+    Edges, Element, EntityId, FocusHandle, GlobalElementId, Hitbox, IntoElement, Pixels, Point,
+    Role, ScrollWheelEvent, Size, Style, StyleRefinement, Styled, Window, point, px, size,
 };
 use collections::VecDeque;
 use refineable::Refineable as _;
@@ -937,7 +937,7 @@ impl Element for List {
 
     fn paint(
         &mut self,
-        _id: Option<&GlobalElementId>,
+        id: Option<&GlobalElementId>,
         bounds: Bounds<crate::Pixels>,
         _: &mut Self::RequestLayoutState,
         prepaint: &mut Self::PrepaintState,
@@ -945,11 +945,19 @@ impl Element for List {
         cx: &mut App,
     ) {
         let current_view = window.current_view();
-        window.with_content_mask(Some(ContentMask { bounds }), |window| {
-            for item in &mut prepaint.layout.item_layouts {
-                item.element.paint(window, cx);
-            }
-        });
+        window.with_accessibility_node(
+            id,
+            Role::List,
+            AccessibilityProperties::default(),
+            bounds,
+            |window| {
+                window.with_content_mask(Some(ContentMask { bounds }), |window| {
+                    for item in &mut prepaint.layout.item_layouts {
+                        item.element.paint(window, cx);
+                    }
+                });
+            },
+        );
 
         let list_state = self.state.clone();
         let height = bounds.size.height;
@@ -1,8 +1,9 @@
 use crate::{
-    ActiveTooltip, AnyView, App, Bounds, DispatchPhase, Element, ElementId, GlobalElementId,
-    HighlightStyle, Hitbox, IntoElement, LayoutId, MouseDownEvent, MouseMoveEvent, MouseUpEvent,
-    Pixels, Point, SharedString, Size, TextOverflow, TextRun, TextStyle, TooltipId, WhiteSpace,
-    Window, WrappedLine, WrappedLineLayout, register_tooltip_mouse_handlers, set_tooltip_on_window,
+    AccessibilityProperties, ActiveTooltip, AnyView, App, Bounds, DispatchPhase, Element,
+    ElementId, GlobalElementId, HighlightStyle, Hitbox, IntoElement, LayoutId, MouseDownEvent,
+    MouseMoveEvent, MouseUpEvent, Pixels, Point, Role, SharedString, Size, TextOverflow, TextRun,
+    TextStyle, TooltipId, WhiteSpace, Window, WrappedLine, WrappedLineLayout,
+    register_tooltip_mouse_handlers, set_tooltip_on_window,
 };
 use anyhow::anyhow;
 use smallvec::SmallVec;
@@ -54,7 +55,7 @@ impl Element for &'static str {
         window: &mut Window,
         cx: &mut App,
     ) {
-        text_layout.paint(self, window, cx)
+        text_layout.paint(SharedString::new_static(*self), window, cx)
     }
 }
 
@@ -115,7 +116,7 @@ impl Element for SharedString {
         window: &mut Window,
         cx: &mut App,
     ) {
-        text_layout.paint(self.as_ref(), window, cx)
+        text_layout.paint(self.clone(), window, cx)
     }
 }
 
@@ -261,7 +262,7 @@ impl Element for StyledText {
         window: &mut Window,
         cx: &mut App,
     ) {
-        self.layout.paint(&self.text, window, cx)
+        self.layout.paint(self.text.clone(), window, cx)
     }
 }
 
@@ -406,7 +407,7 @@ impl TextLayout {
         element_state.bounds = Some(bounds);
     }
 
-    fn paint(&self, text: &str, window: &mut Window, cx: &mut App) {
+    fn paint(&self, text: SharedString, window: &mut Window, cx: &mut App) {
         let element_state = self.0.borrow();
         let element_state = element_state
             .as_ref()
@@ -441,6 +442,15 @@ impl TextLayout {
             .log_err();
             line_origin.y += line.size(line_height).height;
         }
+
+        if window.is_accessibility_active() && !text.is_empty() {
+            window.insert_accessibility_node(
+                None,
+                Role::Label,
+                AccessibilityProperties::default().label(text),
+                bounds,
+            );
+        }
     }
 
     /// Get the byte index into the input of the pixel position.
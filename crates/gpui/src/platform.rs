@@ -31,12 +31,12 @@ mod windows;
 pub(crate) mod scap_screen_capture;
 
 use crate::{
-    Action, AnyWindowHandle, App, AsyncWindowContext, BackgroundExecutor, Bounds,
-    DEFAULT_WINDOW_SIZE, DevicePixels, DispatchEventResult, Font, FontId, FontMetrics, FontRun,
-    ForegroundExecutor, GlyphId, GpuSpecs, ImageSource, Keymap, LineLayout, Pixels, PlatformInput,
-    Point, RenderGlyphParams, RenderImage, RenderImageParams, RenderSvgParams, ScaledPixels, Scene,
-    ShapedGlyph, ShapedRun, SharedString, Size, SvgRenderer, SvgSize, Task, TaskLabel, Window,
-    hash, point, px, size,
+    AccessibilityRequest, AccessibilityTree, Action, AnyWindowHandle, App, AsyncWindowContext,
+    BackgroundExecutor, Bounds, DEFAULT_WINDOW_SIZE, DevicePixels, DispatchEventResult, Font,
+    FontId, FontMetrics, FontRun, ForegroundExecutor, GlyphId, GpuSpecs, ImageSource, Keymap,
+    LineLayout, Pixels, PlatformInput, Point, RenderGlyphParams, RenderImage, RenderImageParams,
+    RenderSvgParams, ScaledPixels, Scene, ShapedGlyph, ShapedRun, SharedString, Size, SvgRenderer,
+    SvgSize, Task, TaskLabel, Window, hash, point, px, size,
 };
 use anyhow::Result;
 use async_task::Runnable;
@@ -469,6 +469,13 @@ pub(crate) trait PlatformWindow: HasWindowHandle + HasDisplayHandle {
     fn set_client_inset(&self, _inset: Pixels) {}
     fn gpu_specs(&self) -> Option<GpuSpecs>;
 
+    // Accessibility, currently only implemented on Linux
+    fn on_accessibility_request(&self, _callback: Box<dyn FnMut(AccessibilityRequest)>) {}
+    fn is_accessibility_active(&self) -> bool {
+        false
+    }
+    fn update_accessibility_tree(&self, _tree: &AccessibilityTree) {}
+
     fn update_ime_position(&self, _bounds: Bounds<ScaledPixels>);
 
     #[cfg(any(test, feature = "test-support"))]
@@ -567,17 +574,37 @@ impl PlatformTextSystem for NoopTextSystem {
     }
 
     fn advance(&self, _font_id: FontId, glyph_id: GlyphId) -> Result<Size<f32>> {
-        Ok(size(600.0 * glyph_id.0 as f32, 0.0))
+        Ok(size(600.0 * noop_glyph_width(glyph_id) as f32, 0.0))
     }
 
+    /// Glyph IDs are the characters themselves, so that tests can draw them.
     fn glyph_for_char(&self, _font_id: FontId, ch: char) -> Option<GlyphId> {
-        Some(GlyphId(ch.len_utf16() as u32))
+        Some(GlyphId(ch as u32))
+    }
+
+    #[cfg(any(test, feature = "test-support"))]
+    fn glyph_raster_bounds(&self, params: &RenderGlyphParams) -> Result<Bounds<DevicePixels>> {
+        Ok(test::glyph_raster_bounds(params))
     }
 
+    #[cfg(not(any(test, feature = "test-support")))]
     fn glyph_raster_bounds(&self, _params: &RenderGlyphParams) -> Result<Bounds<DevicePixels>> {
         Ok(Default::default())
     }
 
+    #[cfg(any(test, feature = "test-support"))]
+    fn rasterize_glyph(
+        &self,
+        params: &RenderGlyphParams,
+        raster_bounds: Bounds<DevicePixels>,
+    ) -> Result<(Size<DevicePixels>, Vec<u8>)> {
+        Ok((
+            raster_bounds.size,
+            test::rasterize_glyph(params, raster_bounds),
+        ))
+    }
+
+    #[cfg(not(any(test, feature = "test-support")))]
     fn rasterize_glyph(
         &self,
         _params: &RenderGlyphParams,
@@ -598,17 +625,14 @@ impl PlatformTextSystem for NoopTextSystem {
         let mut glyphs = Vec::new();
         for (ix, c) in text.char_indices() {
             if let Some(glyph) = self.glyph_for_char(FontId(0), c) {
+                let width = noop_glyph_width(glyph);
                 glyphs.push(ShapedGlyph {
                     id: glyph,
                     position: point(position, px(0.)),
                     index: ix,
-                    is_emoji: glyph.0 == 2,
+                    is_emoji: width == 2,
                 });
-                if glyph.0 == 2 {
-                    position += em_width * 2.0;
-                } else {
-                    position += em_width;
-                }
+                position += em_width * width as f32;
             } else {
                 position += em_width
             }
@@ -634,6 +658,12 @@ impl PlatformTextSystem for NoopTextSystem {
     }
 }
 
+/// How many columns a glyph of [`NoopTextSystem`] takes up: characters outside of the
+/// basic multilingual plane, which are mostly emoji, take up two.
+fn noop_glyph_width(glyph_id: GlyphId) -> usize {
+    char::from_u32(glyph_id.0).map_or(1, char::len_utf16)
+}
+
 #[derive(PartialEq, Eq, Hash, Clone)]
 pub(crate) enum AtlasKey {
     Glyph(RenderGlyphParams),
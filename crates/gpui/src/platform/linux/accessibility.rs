@@ -0,0 +1,215 @@
+use crate::{
+    AccessibilityAction, AccessibilityNode, AccessibilityNodeId, AccessibilityRequest,
+    AccessibilityTree, Bounds, ForegroundExecutor, Pixels, Role, Task, Toggled,
+};
+use accesskit::{
+    ActionHandler, ActionRequest, ActivationHandler, Affine, DeactivationHandler, Node, NodeId,
+    Rect, Tree, TreeUpdate,
+};
+use futures::{StreamExt as _, channel::mpsc};
+use std::sync::{
+    Arc,
+    atomic::{AtomicBool, Ordering::SeqCst},
+};
+
+/// Exposes the accessibility tree of a window to assistive technologies over AT-SPI.
+///
+/// AccessKit calls its handlers on its own thread, so they forward requests through a
+/// channel that is drained on the main thread.
+pub(crate) struct LinuxAccessibility {
+    adapter: accesskit_unix::Adapter,
+    active: Arc<AtomicBool>,
+    _requests: Task<()>,
+}
+
+impl LinuxAccessibility {
+    pub(crate) fn new(
+        executor: &ForegroundExecutor,
+        mut callback: Box<dyn FnMut(AccessibilityRequest)>,
+    ) -> Self {
+        let (requests_tx, mut requests_rx) = mpsc::unbounded();
+        let active = Arc::new(AtomicBool::new(false));
+        let adapter = accesskit_unix::Adapter::new(
+            Activation {
+                active: active.clone(),
+                requests: requests_tx.clone(),
+            },
+            Actions(requests_tx),
+            Deactivation(active.clone()),
+        );
+        let requests = executor.spawn(async move {
+            while let Some(request) = requests_rx.next().await {
+                callback(request);
+            }
+        });
+
+        Self {
+            adapter,
+            active,
+            _requests: requests,
+        }
+    }
+
+    pub(crate) fn is_active(&self) -> bool {
+        self.active.load(SeqCst)
+    }
+
+    pub(crate) fn update(
+        &mut self,
+        tree: &AccessibilityTree,
+        window_bounds: Bounds<Pixels>,
+        scale_factor: f32,
+        focused: bool,
+    ) {
+        let window_rect = rect(window_bounds, scale_factor);
+        self.adapter
+            .set_root_window_bounds(window_rect, window_rect);
+        self.adapter.update_window_focus_state(focused);
+        self.adapter
+            .update_if_active(|| tree_update(tree, scale_factor));
+    }
+}
+
+struct Activation {
+    active: Arc<AtomicBool>,
+    requests: mpsc::UnboundedSender<AccessibilityRequest>,
+}
+
+impl ActivationHandler for Activation {
+    fn request_initial_tree(&mut self) -> Option<TreeUpdate> {
+        // The tree can only be built on the main thread, so ask the window to send it with
+        // its next frame instead.
+        self.active.store(true, SeqCst);
+        self.requests
+            .unbounded_send(AccessibilityRequest::TreeRequested)
+            .ok();
+        None
+    }
+}
+
+struct Actions(mpsc::UnboundedSender<AccessibilityRequest>);
+
+impl ActionHandler for Actions {
+    fn do_action(&mut self, request: ActionRequest) {
+        let action = match request.action {
+            accesskit::Action::Click => AccessibilityAction::Click,
+            accesskit::Action::Focus => AccessibilityAction::Focus,
+            _ => return,
+        };
+        self.0
+            .unbounded_send(AccessibilityRequest::Action {
+                node: AccessibilityNodeId(request.target.0),
+                action,
+            })
+            .ok();
+    }
+}
+
+struct Deactivation(Arc<AtomicBool>);
+
+impl DeactivationHandler for Deactivation {
+    fn deactivate_accessibility(&mut self) {
+        self.0.store(false, SeqCst);
+    }
+}
+
+fn tree_update(tree: &AccessibilityTree, scale_factor: f32) -> TreeUpdate {
+    let nodes = tree
+        .descendants(tree.root())
+        .map(|node| (NodeId(node.id.0), accesskit_node(node, scale_factor)))
+        .collect();
+    TreeUpdate {
+        nodes,
+        tree: Some(Tree::new(NodeId(AccessibilityNodeId::ROOT.0))),
+        focus: NodeId(tree.focus().unwrap_or(AccessibilityNodeId::ROOT).0),
+    }
+}
+
+fn accesskit_node(node: &AccessibilityNode, scale_factor: f32) -> Node {
+    let mut accesskit_node = Node::new(accesskit_role(node.role));
+    // Nodes are laid out in logical pixels, which the root scales to device pixels.
+    if node.id == AccessibilityNodeId::ROOT {
+        accesskit_node.set_transform(Affine::scale(scale_factor as f64));
+    }
+    accesskit_node.set_bounds(rect(node.bounds, 1.));
+    accesskit_node.set_children(
+        node.children
+            .iter()
+            .map(|child| NodeId(child.0))
+            .collect::<Vec<_>>(),
+    );
+
+    if let Some(label) = &node.label {
+        accesskit_node.set_label(label.to_string());
+    }
+    if let Some(value) = &node.value {
+        accesskit_node.set_value(value.to_string());
+    }
+    if let Some(description) = &node.description {
+        accesskit_node.set_description(description.to_string());
+    }
+    if node.disabled {
+        accesskit_node.set_disabled();
+    }
+    if let Some(selected) = node.selected {
+        accesskit_node.set_selected(selected);
+    }
+    if let Some(toggled) = node.toggled {
+        accesskit_node.set_toggled(match toggled {
+            Toggled::False => accesskit::Toggled::False,
+            Toggled::True => accesskit::Toggled::True,
+            Toggled::Mixed => accesskit::Toggled::Mixed,
+        });
+    }
+    if let Some(expanded) = node.expanded {
+        accesskit_node.set_expanded(expanded);
+    }
+    for action in &node.actions {
+        accesskit_node.add_action(match action {
+            AccessibilityAction::Click => accesskit::Action::Click,
+            AccessibilityAction::Focus => accesskit::Action::Focus,
+        });
+    }
+    accesskit_node
+}
+
+fn accesskit_role(role: Role) -> accesskit::Role {
+    match role {
+        Role::Window => accesskit::Role::Window,
+        Role::Group => accesskit::Role::Group,
+        Role::Button => accesskit::Role::Button,
+        Role::CheckBox => accesskit::Role::CheckBox,
+        Role::Switch => accesskit::Role::Switch,
+        Role::RadioButton => accesskit::Role::RadioButton,
+        Role::Link => accesskit::Role::Link,
+        Role::Label => accesskit::Role::Label,
+        Role::Heading => accesskit::Role::Heading,
+        Role::TextInput => accesskit::Role::TextInput,
+        Role::MultilineTextInput => accesskit::Role::MultilineTextInput,
+        Role::List => accesskit::Role::List,
+        Role::ListItem => accesskit::Role::ListItem,
+        Role::Tree => accesskit::Role::Tree,
+        Role::TreeItem => accesskit::Role::TreeItem,
+        Role::TabList => accesskit::Role::TabList,
+        Role::Tab => accesskit::Role::Tab,
+        Role::Menu => accesskit::Role::Menu,
+        Role::MenuItem => accesskit::Role::MenuItem,
+        Role::Toolbar => accesskit::Role::Toolbar,
+        Role::Dialog => accesskit::Role::Dialog,
+        Role::Tooltip => accesskit::Role::Tooltip,
+        Role::Image => accesskit::Role::Image,
+        Role::Separator => accesskit::Role::Splitter,
+        Role::ScrollView => accesskit::Role::ScrollView,
+        Role::ProgressIndicator => accesskit::Role::ProgressIndicator,
+    }
+}
+
+fn rect(bounds: Bounds<Pixels>, scale_factor: f32) -> Rect {
+    let scale = |pixels: Pixels| (pixels.0 * scale_factor) as f64;
+    Rect {
+        x0: scale(bounds.left()),
+        y0: scale(bounds.top()),
+        x1: scale(bounds.right()),
+        y1: scale(bounds.bottom()),
+    }
+}
@@ -28,11 +28,11 @@ use crate::platform::{
 };
 use crate::scene::Scene;
 use crate::{
-    AnyWindowHandle, Bounds, Decorations, Globals, GpuSpecs, Modifiers, Output, Pixels,
-    PlatformDisplay, PlatformInput, Point, PromptLevel, RequestFrameOptions, ResizeEdge,
-    ScaledPixels, Size, Tiling, WaylandClientStatePtr, WindowAppearance,
-    WindowBackgroundAppearance, WindowBounds, WindowControls, WindowDecorations, WindowParams, px,
-    size,
+    AccessibilityRequest, AccessibilityTree, AnyWindowHandle, Bounds, Decorations, Globals,
+    GpuSpecs, LinuxAccessibility, Modifiers, Output, Pixels, PlatformDisplay, PlatformInput, Point,
+    PromptLevel, RequestFrameOptions, ResizeEdge, ScaledPixels, Size, Tiling,
+    WaylandClientStatePtr, WindowAppearance, WindowBackgroundAppearance, WindowBounds,
+    WindowControls, WindowDecorations, WindowParams, px, size,
 };
 
 #[derive(Default)]
@@ -107,6 +107,7 @@ pub struct WaylandWindowState {
     in_progress_window_controls: Option<WindowControls>,
     window_controls: WindowControls,
     inset: Option<Pixels>,
+    accessibility: Option<LinuxAccessibility>,
 }
 
 #[derive(Clone)]
@@ -181,6 +182,7 @@ impl WaylandWindowState {
             in_progress_window_controls: None,
             window_controls: WindowControls::default(),
             inset: None,
+            accessibility: None,
         })
     }
 
@@ -1050,6 +1052,27 @@ impl PlatformWindow for WaylandWindow {
     fn gpu_specs(&self) -> Option<GpuSpecs> {
         self.borrow().renderer.gpu_specs().into()
     }
+
+    fn on_accessibility_request(&self, callback: Box<dyn FnMut(AccessibilityRequest)>) {
+        let mut state = self.borrow_mut();
+        let accessibility = LinuxAccessibility::new(&state.globals.executor, callback);
+        state.accessibility = Some(accessibility);
+    }
+
+    fn is_accessibility_active(&self) -> bool {
+        self.borrow()
+            .accessibility
+            .as_ref()
+            .is_some_and(|accessibility| accessibility.is_active())
+    }
+
+    fn update_accessibility_tree(&self, tree: &AccessibilityTree) {
+        let mut state = self.borrow_mut();
+        let (bounds, scale, active) = (state.bounds, state.scale, state.active);
+        if let Some(accessibility) = state.accessibility.as_mut() {
+            accessibility.update(tree, bounds, scale, active);
+        }
+    }
 }
 
 fn update_window(mut state: RefMut<WaylandWindowState>) {
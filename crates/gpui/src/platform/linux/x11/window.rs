@@ -2,11 +2,12 @@ use anyhow::{Context as _, anyhow};
 
 use crate::platform::blade::{BladeContext, BladeRenderer, BladeSurfaceConfig};
 use crate::{
-    AnyWindowHandle, Bounds, Decorations, DevicePixels, ForegroundExecutor, GpuSpecs, Modifiers,
-    Pixels, PlatformAtlas, PlatformDisplay, PlatformInput, PlatformInputHandler, PlatformWindow,
-    Point, PromptLevel, RequestFrameOptions, ResizeEdge, ScaledPixels, Scene, Size, Tiling,
-    WindowAppearance, WindowBackgroundAppearance, WindowBounds, WindowDecorations, WindowKind,
-    WindowParams, X11ClientStatePtr, px, size,
+    AccessibilityRequest, AccessibilityTree, AnyWindowHandle, Bounds, Decorations, DevicePixels,
+    ForegroundExecutor, GpuSpecs, LinuxAccessibility, Modifiers, Pixels, PlatformAtlas,
+    PlatformDisplay, PlatformInput, PlatformInputHandler, PlatformWindow, Point, PromptLevel,
+    RequestFrameOptions, ResizeEdge, ScaledPixels, Scene, Size, Tiling, WindowAppearance,
+    WindowBackgroundAppearance, WindowBounds, WindowDecorations, WindowKind, WindowParams,
+    X11ClientStatePtr, px, size,
 };
 
 use blade_graphics as gpu;
@@ -269,6 +270,7 @@ pub struct X11WindowState {
     edge_constraints: Option<EdgeConstraints>,
     pub handle: AnyWindowHandle,
     last_insets: [u32; 4],
+    accessibility: Option<LinuxAccessibility>,
 }
 
 impl X11WindowState {
@@ -629,6 +631,7 @@ impl X11WindowState {
                 client_side_decorations_supported,
                 decorations: WindowDecorations::Server,
                 last_insets: [0, 0, 0, 0],
+                accessibility: None,
                 edge_constraints: None,
                 counter_id: sync_request_counter,
                 last_sync_counter: None,
@@ -1611,4 +1614,27 @@ impl PlatformWindow for X11Window {
     fn gpu_specs(&self) -> Option<GpuSpecs> {
         self.0.state.borrow().renderer.gpu_specs().into()
     }
+
+    fn on_accessibility_request(&self, callback: Box<dyn FnMut(AccessibilityRequest)>) {
+        let mut state = self.0.state.borrow_mut();
+        let accessibility = LinuxAccessibility::new(&state.executor, callback);
+        state.accessibility = Some(accessibility);
+    }
+
+    fn is_accessibility_active(&self) -> bool {
+        self.0
+            .state
+            .borrow()
+            .accessibility
+            .as_ref()
+            .is_some_and(|accessibility| accessibility.is_active())
+    }
+
+    fn update_accessibility_tree(&self, tree: &AccessibilityTree) {
+        let mut state = self.0.state.borrow_mut();
+        let (bounds, scale_factor, active) = (state.bounds, state.scale_factor, state.active);
+        if let Some(accessibility) = state.accessibility.as_mut() {
+            accessibility.update(tree, bounds, scale_factor, active);
+        }
+    }
 }
@@ -1,11 +1,15 @@
 mod dispatcher;
 mod display;
+mod glyph_rasterizer;
 mod platform;
+mod software_renderer;
 mod window;
 
 pub use dispatcher::*;
 pub(crate) use display::*;
+pub(crate) use glyph_rasterizer::*;
 pub(crate) use platform::*;
+pub(crate) use software_renderer::*;
 pub(crate) use window::*;
 
 pub use platform::TestScreenCaptureSource;
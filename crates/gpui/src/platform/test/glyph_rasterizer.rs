@@ -0,0 +1,200 @@
+//! Rasterizes the glyphs laid out by [`crate::NoopTextSystem`], so that screenshots of test
+//! windows show text.
+//!
+//! The test text system uses characters as glyph IDs and lays every character out with
+//! the same advance, so glyphs are drawn with the monospace font Zed bundles, whose advance
+//! matches. Outlines are filled on the CPU, which keeps the pixels the same on every
+//! platform.
+
+use crate::{Bounds, DevicePixels, RenderGlyphParams, SUBPIXEL_VARIANTS, point, size};
+use std::sync::LazyLock;
+use tiny_skia::{FillRule, Paint, PathBuilder, Pixmap, Transform};
+use ttf_parser::{Face, OutlineBuilder};
+
+static FONT: LazyLock<Face<'static>> = LazyLock::new(|| {
+    Face::parse(
+        include_bytes!("../../../../../assets/fonts/plex-mono/ZedPlexMono-Regular.ttf"),
+        0,
+    )
+    .expect("the bundled font should be valid")
+});
+
+/// A glyph of the bundled font, placed in device pixels relative to its origin.
+struct PlacedGlyph {
+    glyph_id: ttf_parser::GlyphId,
+    /// Device pixels per font unit.
+    scale: f32,
+    /// The fraction of a device pixel the glyph is shifted by along each axis.
+    shift: [f32; 2],
+    bounds: Bounds<DevicePixels>,
+}
+
+impl PlacedGlyph {
+    fn new(params: &RenderGlyphParams) -> Option<Self> {
+        if params.is_emoji {
+            return None;
+        }
+        let character = char::from_u32(params.glyph_id.0)?;
+        let glyph_id = FONT.glyph_index(character)?;
+        // Glyphs without an outline, such as spaces, have no bounding box.
+        let bounding_box = FONT.glyph_bounding_box(glyph_id)?;
+
+        let scale = params.font_size.0 * params.scale_factor / FONT.units_per_em() as f32;
+        let shift = [
+            params.subpixel_variant.x as f32 / SUBPIXEL_VARIANTS as f32,
+            params.subpixel_variant.y as f32 / SUBPIXEL_VARIANTS as f32,
+        ];
+        let left = (bounding_box.x_min as f32 * scale + shift[0]).floor() as i32;
+        let right = (bounding_box.x_max as f32 * scale + shift[0]).ceil() as i32;
+        let top = (-bounding_box.y_max as f32 * scale + shift[1]).floor() as i32;
+        let bottom = (-bounding_box.y_min as f32 * scale + shift[1]).ceil() as i32;
+        if right <= left || bottom <= top {
+            return None;
+        }
+
+        Some(Self {
+            glyph_id,
+            scale,
+            shift,
+            bounds: Bounds {
+                origin: point(DevicePixels(left), DevicePixels(top)),
+                size: size(DevicePixels(right - left), DevicePixels(bottom - top)),
+            },
+        })
+    }
+}
+
+pub(crate) fn glyph_raster_bounds(params: &RenderGlyphParams) -> Bounds<DevicePixels> {
+    PlacedGlyph::new(params)
+        .map(|glyph| glyph.bounds)
+        .unwrap_or_default()
+}
+
+/// Returns the coverage of each pixel of the glyph, row by row.
+pub(crate) fn rasterize_glyph(
+    params: &RenderGlyphParams,
+    raster_bounds: Bounds<DevicePixels>,
+) -> Vec<u8> {
+    let width = raster_bounds.size.width.0.max(0) as u32;
+    let height = raster_bounds.size.height.0.max(0) as u32;
+    let empty = || vec![0; (width * height) as usize];
+
+    let Some(glyph) = PlacedGlyph::new(params) else {
+        return empty();
+    };
+    let Some(mut pixmap) = Pixmap::new(width, height) else {
+        return empty();
+    };
+    let mut outline = OutlinePath {
+        builder: PathBuilder::new(),
+        scale: glyph.scale,
+        offset: [
+            glyph.shift[0] - raster_bounds.origin.x.0 as f32,
+            glyph.shift[1] - raster_bounds.origin.y.0 as f32,
+        ],
+    };
+    FONT.outline_glyph(glyph.glyph_id, &mut outline);
+    let Some(path) = outline.builder.finish() else {
+        return empty();
+    };
+
+    pixmap.fill_path(
+        &path,
+        &Paint::default(),
+        FillRule::Winding,
+        Transform::identity(),
+        None,
+    );
+    pixmap.pixels().iter().map(|pixel| pixel.alpha()).collect()
+}
+
+/// Builds a path in the pixels of a glyph's raster from its outline in font units, which
+/// point up rather than down.
+struct OutlinePath {
+    builder: PathBuilder,
+    scale: f32,
+    offset: [f32; 2],
+}
+
+impl OutlinePath {
+    fn point(&self, x: f32, y: f32) -> (f32, f32) {
+        (
+            x * self.scale + self.offset[0],
+            -y * self.scale + self.offset[1],
+        )
+    }
+}
+
+impl OutlineBuilder for OutlinePath {
+    fn move_to(&mut self, x: f32, y: f32) {
+        let (x, y) = self.point(x, y);
+        self.builder.move_to(x, y);
+    }
+
+    fn line_to(&mut self, x: f32, y: f32) {
+        let (x, y) = self.point(x, y);
+        self.builder.line_to(x, y);
+    }
+
+    fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+        let (x1, y1) = self.point(x1, y1);
+        let (x, y) = self.point(x, y);
+        self.builder.quad_to(x1, y1, x, y);
+    }
+
+    fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+        let (x1, y1) = self.point(x1, y1);
+        let (x2, y2) = self.point(x2, y2);
+        let (x, y) = self.point(x, y);
+        self.builder.cubic_to(x1, y1, x2, y2, x, y);
+    }
+
+    fn close(&mut self) {
+        self.builder.close();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{FontId, GlyphId, px};
+
+    fn params(character: char) -> RenderGlyphParams {
+        RenderGlyphParams {
+            font_id: FontId(0),
+            glyph_id: GlyphId(character as u32),
+            font_size: px(16.),
+            subpixel_variant: point(0, 0),
+            scale_factor: 2.,
+            is_emoji: false,
+        }
+    }
+
+    #[test]
+    fn test_glyphs_fit_their_advance() {
+        // The test text system advances by 0.6em, which is 19.2 device pixels here.
+        let bounds = glyph_raster_bounds(&params('H'));
+        assert!(bounds.origin.x.0 >= 0);
+        assert!(bounds.origin.x.0 + bounds.size.width.0 <= 20);
+        // Capitals sit on the baseline and rise above it.
+        assert!(bounds.origin.y.0 < 0);
+        assert_eq!(bounds.origin.y.0 + bounds.size.height.0, 0);
+
+        let coverage = rasterize_glyph(&params('H'), bounds);
+        assert_eq!(
+            coverage.len(),
+            (bounds.size.width.0 * bounds.size.height.0) as usize
+        );
+        assert!(coverage.contains(&255));
+        assert!(coverage.contains(&0));
+    }
+
+    #[test]
+    fn test_glyphs_differ_per_character() {
+        let coverage = |character| {
+            rasterize_glyph(&params(character), glyph_raster_bounds(&params(character)))
+        };
+        assert_ne!(coverage('o'), coverage('c'));
+        assert_eq!(glyph_raster_bounds(&params(' ')), Bounds::default());
+    }
+}
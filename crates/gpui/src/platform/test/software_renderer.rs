@@ -0,0 +1,1297 @@
+//! A renderer that rasterizes a [`Scene`] on the CPU, so that tests can look at the pixels
+//! a window would show without needing a GPU.
+//!
+//! The math mirrors the blade shaders (`shaders.wgsl`): colors are blended in linear space
+//! and converted to sRGB when the image is produced. Anti-aliasing follows the same signed
+//! distance approach, so the output closely matches what a window shows on screen.
+
+use crate::{
+    AtlasTextureKind, AtlasTile, Background, BackgroundTag, BorderStyle, Bounds, ColorSpace,
+    ContentMask, Corners, DevicePixels, Hsla, MonochromeSprite, Path, PolychromeSprite,
+    PrimitiveBatch, Quad, Rgba, ScaledPixels, Scene, Shadow, Size, TestAtlas, Underline,
+};
+use image::RgbaImage;
+use std::{f32::consts::PI, ops::Range};
+
+/// Linear RGBA with straight alpha.
+type Color = [f32; 4];
+
+const GRAYSCALE_FACTORS: [f32; 3] = [0.2126, 0.7152, 0.0722];
+/// Paths are rasterized with this many samples along each axis of a pixel.
+const PATH_SAMPLES_PER_AXIS: usize = 4;
+/// Moves path samples off the pixel grid, so that samples never lie exactly on the edge
+/// between two triangles whose vertices are on whole or half pixels. The offsets differ
+/// per axis so that samples also avoid diagonal edges.
+const PATH_SAMPLE_BIAS: [f32; 2] = [1. / 1024., 1. / 2048.];
+
+/// Renders the scene into an image of the given size, reading sprites from the atlas the
+/// scene was painted with.
+pub(crate) fn render_scene(
+    scene: &Scene,
+    size: Size<DevicePixels>,
+    atlas: &TestAtlas,
+) -> RgbaImage {
+    let mut canvas = Canvas::new(size.width.0.max(0) as usize, size.height.0.max(0) as usize);
+    for batch in scene.batches() {
+        match batch {
+            PrimitiveBatch::Shadows(shadows) => {
+                for shadow in shadows {
+                    canvas.draw_shadow(shadow);
+                }
+            }
+            PrimitiveBatch::Quads(quads) => {
+                for quad in quads {
+                    canvas.draw_quad(quad);
+                }
+            }
+            PrimitiveBatch::Paths(paths) => {
+                for path in paths {
+                    canvas.draw_path(path);
+                }
+            }
+            PrimitiveBatch::Underlines(underlines) => {
+                for underline in underlines {
+                    canvas.draw_underline(underline);
+                }
+            }
+            PrimitiveBatch::MonochromeSprites { sprites, .. } => {
+                for sprite in sprites {
+                    if let Some(texture) = atlas.texture(&sprite.tile) {
+                        canvas.draw_monochrome_sprite(sprite, &texture);
+                    }
+                }
+            }
+            PrimitiveBatch::PolychromeSprites { sprites, .. } => {
+                for sprite in sprites {
+                    if let Some(texture) = atlas.texture(&sprite.tile) {
+                        canvas.draw_polychrome_sprite(sprite, &texture);
+                    }
+                }
+            }
+            // Surfaces only carry video frames on macOS.
+            PrimitiveBatch::Surfaces(_) => {}
+        }
+    }
+    canvas.into_image()
+}
+
+/// The pixels of a sprite stored in a [`TestAtlas`].
+pub(crate) struct SpriteTexture {
+    pub(crate) kind: AtlasTextureKind,
+    pub(crate) size: Size<DevicePixels>,
+    pub(crate) bytes: Vec<u8>,
+}
+
+impl SpriteTexture {
+    /// Samples the texel under the given point of the tile, in the unit square.
+    fn sample(&self, tile: &AtlasTile, u: f32, v: f32) -> Color {
+        let x = tile.bounds.origin.x.0 + (u * tile.bounds.size.width.0 as f32).floor() as i32;
+        let y = tile.bounds.origin.y.0 + (v * tile.bounds.size.height.0 as f32).floor() as i32;
+        if x < 0 || y < 0 || x >= self.size.width.0 || y >= self.size.height.0 {
+            return [0.; 4];
+        }
+        let index = y as usize * self.size.width.0 as usize + x as usize;
+        match self.kind {
+            AtlasTextureKind::Polychrome => {
+                let Some(bgra) = self.bytes.get(index * 4..index * 4 + 4) else {
+                    return [0.; 4];
+                };
+                [
+                    srgb_to_linear(bgra[2] as f32 / 255.),
+                    srgb_to_linear(bgra[1] as f32 / 255.),
+                    srgb_to_linear(bgra[0] as f32 / 255.),
+                    bgra[3] as f32 / 255.,
+                ]
+            }
+            AtlasTextureKind::Monochrome | AtlasTextureKind::Path => {
+                let alpha = self.bytes.get(index).copied().unwrap_or(0) as f32 / 255.;
+                [alpha, 0., 0., 0.]
+            }
+        }
+    }
+}
+
+struct Canvas {
+    width: usize,
+    height: usize,
+    /// Linear RGBA with premultiplied alpha.
+    pixels: Vec<Color>,
+}
+
+impl Canvas {
+    fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            pixels: vec![[0.; 4]; width * height],
+        }
+    }
+
+    fn into_image(self) -> RgbaImage {
+        let mut image = RgbaImage::new(self.width as u32, self.height as u32);
+        for (pixel, color) in image.pixels_mut().zip(self.pixels) {
+            let alpha = color[3];
+            if alpha <= 0. {
+                continue;
+            }
+            let channel = |value: f32| (linear_to_srgb(value / alpha) * 255.).round() as u8;
+            pixel.0 = [
+                channel(color[0]),
+                channel(color[1]),
+                channel(color[2]),
+                (alpha.clamp(0., 1.) * 255.).round() as u8,
+            ];
+        }
+        image
+    }
+
+    /// Composites a color over the pixel at the given coordinates, as `blend_color` and
+    /// alpha blending do on the GPU.
+    fn blend(&mut self, x: usize, y: usize, color: Color, alpha_factor: f32) {
+        let alpha = (color[3] * alpha_factor).clamp(0., 1.);
+        if alpha <= 0. {
+            return;
+        }
+        let pixel = &mut self.pixels[y * self.width + x];
+        for channel in 0..3 {
+            pixel[channel] = color[channel] * alpha + pixel[channel] * (1. - alpha);
+        }
+        pixel[3] = alpha + pixel[3] * (1. - alpha);
+    }
+
+    /// The pixels whose centers lie within both the bounds and the content mask, which are
+    /// the pixels the GPU would run a fragment shader for.
+    fn pixels_within(
+        &self,
+        bounds: &Bounds<ScaledPixels>,
+        content_mask: &ContentMask<ScaledPixels>,
+    ) -> (Range<usize>, Range<usize>) {
+        let clipped = bounds.intersect(&content_mask.bounds);
+        let range = |start: f32, end: f32, limit: usize| {
+            let start = ((start - 0.5).ceil().max(0.) as usize).min(limit);
+            let end = ((end - 0.5).ceil().max(0.) as usize).min(limit);
+            start..end.max(start)
+        };
+        (
+            range(
+                clipped.origin.x.0,
+                clipped.origin.x.0 + clipped.size.width.0,
+                self.width,
+            ),
+            range(
+                clipped.origin.y.0,
+                clipped.origin.y.0 + clipped.size.height.0,
+                self.height,
+            ),
+        )
+    }
+
+    fn draw_quad(&mut self, quad: &Quad) {
+        let gradient = GradientColors::new(&quad.background);
+        let border_color = hsla_to_linear(quad.border_color);
+        let (columns, rows) = self.pixels_within(&quad.bounds, &quad.content_mask);
+        for y in rows {
+            for x in columns.clone() {
+                let position = [x as f32 + 0.5, y as f32 + 0.5];
+                let background = gradient.color_at(&quad.background, position, &quad.bounds);
+                let (color, alpha_factor) = quad_color(quad, position, background, border_color);
+                self.blend(x, y, color, alpha_factor);
+            }
+        }
+    }
+
+    fn draw_shadow(&mut self, shadow: &Shadow) {
+        let blur_radius = shadow.blur_radius.0;
+        let margin = 3. * blur_radius;
+        let mut bounds = shadow.bounds;
+        bounds.origin.x.0 -= margin;
+        bounds.origin.y.0 -= margin;
+        bounds.size.width.0 += 2. * margin;
+        bounds.size.height.0 += 2. * margin;
+
+        let color = hsla_to_linear(shadow.color);
+        let half_size = [
+            shadow.bounds.size.width.0 / 2.,
+            shadow.bounds.size.height.0 / 2.,
+        ];
+        let center = [
+            shadow.bounds.origin.x.0 + half_size[0],
+            shadow.bounds.origin.y.0 + half_size[1],
+        ];
+
+        let (columns, rows) = self.pixels_within(&bounds, &shadow.content_mask);
+        for y in rows {
+            for x in columns.clone() {
+                let center_to_point = [x as f32 + 0.5 - center[0], y as f32 + 0.5 - center[1]];
+                let corner_radius = pick_corner_radius(center_to_point, &shadow.corner_radii);
+
+                let alpha = if blur_radius <= 0. {
+                    // Without blur the shadow is a plain rounded rectangle.
+                    let corner_to_point = [
+                        center_to_point[0].abs() - half_size[0],
+                        center_to_point[1].abs() - half_size[1],
+                    ];
+                    let distance = quad_sdf_impl(
+                        [
+                            corner_to_point[0] + corner_radius,
+                            corner_to_point[1] + corner_radius,
+                        ],
+                        corner_radius,
+                    );
+                    saturate(0.5 - distance)
+                } else {
+                    // The signal is only non-zero in a limited range, so don't waste samples.
+                    let low = center_to_point[1] - half_size[1];
+                    let high = center_to_point[1] + half_size[1];
+                    let start = (-3. * blur_radius).clamp(low, high);
+                    let end = (3. * blur_radius).clamp(low, high);
+
+                    let step = (end - start) / 4.;
+                    let mut sample_y = start + step * 0.5;
+                    let mut alpha = 0.;
+                    for _ in 0..4 {
+                        let blur = blur_along_x(
+                            center_to_point[0],
+                            center_to_point[1] - sample_y,
+                            blur_radius,
+                            corner_radius,
+                            half_size,
+                        );
+                        alpha += blur * gaussian(sample_y, blur_radius) * step;
+                        sample_y += step;
+                    }
+                    alpha
+                };
+                self.blend(x, y, color, alpha);
+            }
+        }
+    }
+
+    fn draw_underline(&mut self, underline: &Underline) {
+        let color = hsla_to_linear(underline.color);
+        let bounds = &underline.bounds;
+        let (columns, rows) = self.pixels_within(bounds, &underline.content_mask);
+        for y in rows {
+            for x in columns.clone() {
+                if !underline.wavy {
+                    self.blend(x, y, color, color[3]);
+                    continue;
+                }
+
+                let thickness = underline.thickness.0;
+                let half_thickness = thickness * 0.5;
+                let st = [
+                    (x as f32 + 0.5 - bounds.origin.x.0) / bounds.size.height.0,
+                    (y as f32 + 0.5 - bounds.origin.y.0) / bounds.size.height.0 - 0.5,
+                ];
+                let frequency = PI * 3. * thickness / 3.;
+                let amplitude = 1. / (4. * thickness);
+                let sine = (st[0] * frequency).sin() * amplitude;
+                let d_sine = (st[0] * frequency).cos() * amplitude * frequency;
+                let distance = (st[1] - sine) / (1. + d_sine * d_sine).sqrt();
+                let distance_in_pixels = distance * bounds.size.height.0;
+                let distance_from_top_border = distance_in_pixels - half_thickness;
+                let distance_from_bottom_border = distance_in_pixels + half_thickness;
+                let alpha =
+                    saturate(0.5 - (-distance_from_bottom_border).max(distance_from_top_border));
+                self.blend(x, y, color, alpha * color[3]);
+            }
+        }
+    }
+
+    /// Rasterizes the triangles of the path with the even-odd fill rule, like the GPU does
+    /// by accumulating coverage in the path atlas.
+    fn draw_path(&mut self, path: &Path<ScaledPixels>) {
+        let (columns, rows) = self.pixels_within(&path.bounds, &path.content_mask);
+        if columns.is_empty() || rows.is_empty() {
+            return;
+        }
+
+        let samples = PATH_SAMPLES_PER_AXIS;
+        let samples_wide = columns.len() * samples;
+        let samples_high = rows.len() * samples;
+        let origin = [columns.start as f32, rows.start as f32];
+        let sample_position = |column: usize, row: usize| {
+            [
+                origin[0] + (column as f32 + 0.5) / samples as f32 + PATH_SAMPLE_BIAS[0],
+                origin[1] + (row as f32 + 0.5) / samples as f32 + PATH_SAMPLE_BIAS[1],
+            ]
+        };
+
+        let mut parity = vec![false; samples_wide * samples_high];
+        for triangle in path.vertices.chunks_exact(3) {
+            let xy = [
+                [triangle[0].xy_position.x.0, triangle[0].xy_position.y.0],
+                [triangle[1].xy_position.x.0, triangle[1].xy_position.y.0],
+                [triangle[2].xy_position.x.0, triangle[2].xy_position.y.0],
+            ];
+            let st = [
+                [triangle[0].st_position.x, triangle[0].st_position.y],
+                [triangle[1].st_position.x, triangle[1].st_position.y],
+                [triangle[2].st_position.x, triangle[2].st_position.y],
+            ];
+            let area = edge_function(xy[0], xy[1], xy[2]);
+            if area == 0. {
+                continue;
+            }
+
+            let min_x = xy.iter().map(|p| p[0]).fold(f32::INFINITY, f32::min);
+            let max_x = xy.iter().map(|p| p[0]).fold(f32::NEG_INFINITY, f32::max);
+            let min_y = xy.iter().map(|p| p[1]).fold(f32::INFINITY, f32::min);
+            let max_y = xy.iter().map(|p| p[1]).fold(f32::NEG_INFINITY, f32::max);
+            let sample_range = |min: f32, max: f32, origin: f32, limit: usize| {
+                let start =
+                    (((min - origin) * samples as f32 - 0.5).floor().max(0.) as usize).min(limit);
+                let end =
+                    (((max - origin) * samples as f32 + 0.5).ceil().max(0.) as usize).min(limit);
+                start..end.max(start)
+            };
+
+            for row in sample_range(min_y, max_y, origin[1], samples_high) {
+                for column in sample_range(min_x, max_x, origin[0], samples_wide) {
+                    let position = sample_position(column, row);
+                    let weights = [
+                        edge_function(xy[1], xy[2], position) / area,
+                        edge_function(xy[2], xy[0], position) / area,
+                        edge_function(xy[0], xy[1], position) / area,
+                    ];
+                    if weights.iter().any(|weight| *weight <= 0.) {
+                        continue;
+                    }
+                    let s = weights[0] * st[0][0] + weights[1] * st[1][0] + weights[2] * st[2][0];
+                    let t = weights[0] * st[0][1] + weights[1] * st[1][1] + weights[2] * st[2][1];
+                    if s * s - t <= 0. {
+                        let index = row * samples_wide + column;
+                        parity[index] = !parity[index];
+                    }
+                }
+            }
+        }
+
+        let gradient = GradientColors::new(&path.color);
+        for (row_ix, y) in rows.enumerate() {
+            for (column_ix, x) in columns.clone().enumerate() {
+                let mut covered = 0;
+                for sample_row in 0..samples {
+                    let row = row_ix * samples + sample_row;
+                    let start = row * samples_wide + column_ix * samples;
+                    covered += parity[start..start + samples]
+                        .iter()
+                        .filter(|inside| **inside)
+                        .count();
+                }
+                if covered == 0 {
+                    continue;
+                }
+
+                let coverage = covered as f32 / (samples * samples) as f32;
+                let position = [x as f32 + 0.5, y as f32 + 0.5];
+                let color = gradient.color_at(&path.color, position, &path.bounds);
+                self.blend(x, y, color, coverage);
+            }
+        }
+    }
+
+    fn draw_monochrome_sprite(&mut self, sprite: &MonochromeSprite, texture: &SpriteTexture) {
+        let color = hsla_to_linear(sprite.color);
+        let transform = &sprite.transformation;
+        let [[a, b], [c, d]] = transform.rotation_scale;
+        let determinant = a * d - b * c;
+        if determinant == 0. {
+            return;
+        }
+
+        // Find the pixels covered by the transformed sprite, then map each of them back to
+        // the sprite's untransformed bounds to sample the tile.
+        let bounds = &sprite.bounds;
+        let corners = [
+            [bounds.origin.x.0, bounds.origin.y.0],
+            [bounds.origin.x.0 + bounds.size.width.0, bounds.origin.y.0],
+            [bounds.origin.x.0, bounds.origin.y.0 + bounds.size.height.0],
+            [
+                bounds.origin.x.0 + bounds.size.width.0,
+                bounds.origin.y.0 + bounds.size.height.0,
+            ],
+        ]
+        .map(|[x, y]| {
+            [
+                a * x + b * y + transform.translation[0],
+                c * x + d * y + transform.translation[1],
+            ]
+        });
+        let min_x = corners.iter().map(|p| p[0]).fold(f32::INFINITY, f32::min);
+        let max_x = corners
+            .iter()
+            .map(|p| p[0])
+            .fold(f32::NEG_INFINITY, f32::max);
+        let min_y = corners.iter().map(|p| p[1]).fold(f32::INFINITY, f32::min);
+        let max_y = corners
+            .iter()
+            .map(|p| p[1])
+            .fold(f32::NEG_INFINITY, f32::max);
+        let transformed_bounds = Bounds {
+            origin: crate::point(ScaledPixels(min_x), ScaledPixels(min_y)),
+            size: crate::size(ScaledPixels(max_x - min_x), ScaledPixels(max_y - min_y)),
+        };
+        let (columns, rows) = self.pixels_within(
+            &transformed_bounds,
+            &ContentMask {
+                bounds: transformed_bounds,
+            },
+        );
+
+        for y in rows {
+            for x in columns.clone() {
+                let offset = [
+                    x as f32 + 0.5 - transform.translation[0],
+                    y as f32 + 0.5 - transform.translation[1],
+                ];
+                let position = [
+                    (d * offset[0] - b * offset[1]) / determinant,
+                    (a * offset[1] - c * offset[0]) / determinant,
+                ];
+                if !contains(bounds, position) || !contains(&sprite.content_mask.bounds, position) {
+                    continue;
+                }
+
+                let u = (position[0] - bounds.origin.x.0) / bounds.size.width.0;
+                let v = (position[1] - bounds.origin.y.0) / bounds.size.height.0;
+                let sample = texture.sample(&sprite.tile, u, v);
+                self.blend(x, y, color, sample[0]);
+            }
+        }
+    }
+
+    fn draw_polychrome_sprite(&mut self, sprite: &PolychromeSprite, texture: &SpriteTexture) {
+        let bounds = &sprite.bounds;
+        let (columns, rows) = self.pixels_within(bounds, &sprite.content_mask);
+        for y in rows {
+            for x in columns.clone() {
+                let position = [x as f32 + 0.5, y as f32 + 0.5];
+                let u = (position[0] - bounds.origin.x.0) / bounds.size.width.0;
+                let v = (position[1] - bounds.origin.y.0) / bounds.size.height.0;
+                let mut color = texture.sample(&sprite.tile, u, v);
+                if sprite.grayscale {
+                    let grayscale = color[0] * GRAYSCALE_FACTORS[0]
+                        + color[1] * GRAYSCALE_FACTORS[1]
+                        + color[2] * GRAYSCALE_FACTORS[2];
+                    color = [grayscale, grayscale, grayscale, color[3]];
+                }
+                let distance = quad_sdf(position, bounds, &sprite.corner_radii);
+                self.blend(x, y, color, sprite.opacity * saturate(0.5 - distance));
+            }
+        }
+    }
+}
+
+/// Computes the color of a quad at a pixel, and the factor its alpha is multiplied by.
+/// This is a port of `fs_quad`.
+fn quad_color(
+    quad: &Quad,
+    position: [f32; 2],
+    background: Color,
+    mut border_color: Color,
+) -> (Color, f32) {
+    let radii = &quad.corner_radii;
+    let widths = &quad.border_widths;
+    let unrounded = radii.top_left.0 == 0.
+        && radii.bottom_left.0 == 0.
+        && radii.top_right.0 == 0.
+        && radii.bottom_right.0 == 0.;
+
+    // Fast path when the quad is not rounded and doesn't have any border.
+    if widths.top.0 == 0.
+        && widths.left.0 == 0.
+        && widths.right.0 == 0.
+        && widths.bottom.0 == 0.
+        && unrounded
+    {
+        return (background, 1.);
+    }
+
+    let size = [quad.bounds.size.width.0, quad.bounds.size.height.0];
+    let half_size = [size[0] / 2., size[1] / 2.];
+    let point = [
+        position[0] - quad.bounds.origin.x.0,
+        position[1] - quad.bounds.origin.y.0,
+    ];
+    let center_to_point = [point[0] - half_size[0], point[1] - half_size[1]];
+
+    // Signed distance field threshold for inclusion of pixels. 0.5 is the minimum distance
+    // between the center of the pixel and the edge.
+    let antialias_threshold = 0.5;
+
+    let corner_radius = pick_corner_radius(center_to_point, radii);
+
+    // Width of the nearest borders.
+    let border = [
+        if center_to_point[0] < 0. {
+            widths.left.0
+        } else {
+            widths.right.0
+        },
+        if center_to_point[1] < 0. {
+            widths.top.0
+        } else {
+            widths.bottom.0
+        },
+    ];
+
+    // 0-width borders are reduced so that `inner_sdf >= antialias_threshold`, so that no
+    // antialiasing pixels are drawn for them.
+    let reduced_border = border.map(|width| {
+        if width == 0. {
+            -antialias_threshold
+        } else {
+            width
+        }
+    });
+
+    let corner_to_point = [
+        center_to_point[0].abs() - half_size[0],
+        center_to_point[1].abs() - half_size[1],
+    ];
+    let corner_center_to_point = [
+        corner_to_point[0] + corner_radius,
+        corner_to_point[1] + corner_radius,
+    ];
+    let is_near_rounded_corner = corner_center_to_point[0] >= 0. && corner_center_to_point[1] >= 0.;
+
+    let straight_border_inner_corner_to_point = [
+        corner_to_point[0] + reduced_border[0],
+        corner_to_point[1] + reduced_border[1],
+    ];
+    let is_beyond_inner_straight_border = straight_border_inner_corner_to_point[0] > 0.
+        || straight_border_inner_corner_to_point[1] > 0.;
+    let is_within_inner_straight_border = straight_border_inner_corner_to_point[0]
+        < -antialias_threshold
+        && straight_border_inner_corner_to_point[1] < -antialias_threshold;
+
+    // Fast path for points that must be part of the background.
+    if is_within_inner_straight_border && !is_near_rounded_corner {
+        return (background, 1.);
+    }
+
+    let outer_sdf = quad_sdf_impl(corner_center_to_point, corner_radius);
+
+    let inner_sdf = if corner_center_to_point[0] <= 0. || corner_center_to_point[1] <= 0. {
+        -straight_border_inner_corner_to_point[0].max(straight_border_inner_corner_to_point[1])
+    } else if is_beyond_inner_straight_border {
+        -1.
+    } else if reduced_border[0] == reduced_border[1] {
+        -(outer_sdf + reduced_border[0])
+    } else {
+        let ellipse_radii = [
+            (corner_radius - reduced_border[0]).max(0.),
+            (corner_radius - reduced_border[1]).max(0.),
+        ];
+        quarter_ellipse_sdf(corner_center_to_point, ellipse_radii)
+    };
+
+    let border_sdf = inner_sdf.max(outer_sdf);
+
+    let mut color = background;
+    if border_sdf < antialias_threshold {
+        if quad.border_style == BorderStyle::Dashed {
+            border_color[3] *= dash_alpha_for_quad(
+                quad,
+                point,
+                center_to_point,
+                corner_center_to_point,
+                corner_radius,
+                border,
+                is_near_rounded_corner,
+                unrounded,
+                antialias_threshold,
+            );
+        }
+
+        // Blend the border on top of the background, then interpolate between the two as
+        // we slide inside the background.
+        let blended_border = over(background, border_color);
+        color = mix(
+            background,
+            blended_border,
+            saturate(antialias_threshold - inner_sdf),
+        );
+    }
+
+    (color, saturate(antialias_threshold - outer_sdf))
+}
+
+/// The dashed border logic of `fs_quad`.
+#[allow(clippy::too_many_arguments)]
+fn dash_alpha_for_quad(
+    quad: &Quad,
+    point: [f32; 2],
+    center_to_point: [f32; 2],
+    corner_center_to_point: [f32; 2],
+    corner_radius: f32,
+    border: [f32; 2],
+    is_near_rounded_corner: bool,
+    unrounded: bool,
+    antialias_threshold: f32,
+) -> f32 {
+    let size = [quad.bounds.size.width.0, quad.bounds.size.height.0];
+
+    // Dash pattern: (2 * border width) dash, (1 * border width) gap.
+    let dash_length_per_width = 2.;
+    let dash_gap_per_width = 1.;
+    let dash_period_per_width = dash_length_per_width + dash_gap_per_width;
+    let dv_numerator = 1. / dash_period_per_width;
+
+    let t;
+    let mut max_t;
+    let dash_velocity;
+    if unrounded {
+        // Dashes are laid out separately on each straight line, so that each line starts
+        // and ends with a dash.
+        let is_horizontal = corner_center_to_point[0] < corner_center_to_point[1];
+        let border_width = if is_horizontal { border[1] } else { border[0] };
+        dash_velocity = dv_numerator / border_width;
+        t = if is_horizontal { point[0] } else { point[1] } * dash_velocity;
+        max_t = if is_horizontal { size[0] } else { size[1] } * dash_velocity;
+    } else {
+        // Dashes are laid out clockwise around the whole perimeter.
+        let radii = &quad.corner_radii;
+        let widths = &quad.border_widths;
+        let (r_tr, r_br, r_bl, r_tl) = (
+            radii.top_right.0,
+            radii.bottom_right.0,
+            radii.bottom_left.0,
+            radii.top_left.0,
+        );
+        let velocity = |width: f32| {
+            if width <= 0. {
+                0.
+            } else {
+                dv_numerator / width
+            }
+        };
+        let dv_t = velocity(widths.top.0);
+        let dv_r = velocity(widths.right.0);
+        let dv_b = velocity(widths.bottom.0);
+        let dv_l = velocity(widths.left.0);
+
+        let s_t = (size[0] - r_tl - r_tr) * dv_t;
+        let s_r = (size[1] - r_tr - r_br) * dv_r;
+        let s_b = (size[0] - r_br - r_bl) * dv_b;
+        let s_l = (size[1] - r_bl - r_tl) * dv_l;
+
+        let corner_dash_velocity_tr = corner_dash_velocity(dv_t, dv_r);
+        let corner_dash_velocity_br = corner_dash_velocity(dv_b, dv_r);
+        let corner_dash_velocity_bl = corner_dash_velocity(dv_b, dv_l);
+        let corner_dash_velocity_tl = corner_dash_velocity(dv_t, dv_l);
+
+        let c_tr = r_tr * (PI / 2.) * corner_dash_velocity_tr;
+        let c_br = r_br * (PI / 2.) * corner_dash_velocity_br;
+        let c_bl = r_bl * (PI / 2.) * corner_dash_velocity_bl;
+        let c_tl = r_tl * (PI / 2.) * corner_dash_velocity_tl;
+
+        let upto_tr = s_t;
+        let upto_r = upto_tr + c_tr;
+        let upto_br = upto_r + s_r;
+        let upto_b = upto_br + c_br;
+        let upto_bl = upto_b + s_b;
+        let upto_l = upto_bl + c_bl;
+        let upto_tl = upto_l + s_l;
+        max_t = upto_tl + c_tl;
+
+        if is_near_rounded_corner {
+            let radians = corner_center_to_point[1].atan2(corner_center_to_point[0]);
+            let corner_t = radians * corner_radius;
+            if center_to_point[0] >= 0. {
+                if center_to_point[1] < 0. {
+                    dash_velocity = corner_dash_velocity_tr;
+                    t = upto_r - corner_t * dash_velocity;
+                } else {
+                    dash_velocity = corner_dash_velocity_br;
+                    t = upto_br + corner_t * dash_velocity;
+                }
+            } else if center_to_point[1] >= 0. {
+                dash_velocity = corner_dash_velocity_bl;
+                t = upto_l - corner_t * dash_velocity;
+            } else {
+                dash_velocity = corner_dash_velocity_tl;
+                t = upto_tl + corner_t * dash_velocity;
+            }
+        } else {
+            let is_horizontal = corner_center_to_point[0] < corner_center_to_point[1];
+            if is_horizontal {
+                if center_to_point[1] < 0. {
+                    dash_velocity = dv_t;
+                    t = (point[0] - r_tl) * dash_velocity;
+                } else {
+                    dash_velocity = dv_b;
+                    t = upto_bl - (point[0] - r_bl) * dash_velocity;
+                }
+            } else if center_to_point[0] < 0. {
+                dash_velocity = dv_l;
+                t = upto_tl - (point[1] - r_tl) * dash_velocity;
+            } else {
+                dash_velocity = dv_r;
+                t = upto_r + (point[1] - r_tr) * dash_velocity;
+            }
+        }
+    }
+
+    let dash_length = dash_length_per_width / dash_period_per_width;
+
+    // Straight borders should start and end with a dash.
+    if unrounded {
+        max_t -= dash_length;
+    }
+    if max_t >= 1. {
+        // Adjust the dash gap to evenly divide max_t.
+        let dash_count = max_t.floor();
+        let dash_period = max_t / dash_count;
+        dash_alpha(
+            t,
+            dash_period,
+            dash_length,
+            dash_velocity,
+            antialias_threshold,
+        )
+    } else if unrounded {
+        // When there isn't enough space for the full gap between the two start and end
+        // dashes of a straight border, reduce the gap to make them fit.
+        let dash_gap = max_t - dash_length;
+        if dash_gap > 0. {
+            let dash_period = dash_length + dash_gap;
+            dash_alpha(
+                t,
+                dash_period,
+                dash_length,
+                dash_velocity,
+                antialias_threshold,
+            )
+        } else {
+            1.
+        }
+    } else {
+        1.
+    }
+}
+
+fn corner_dash_velocity(dv1: f32, dv2: f32) -> f32 {
+    if dv1 == 0. {
+        dv2
+    } else if dv2 == 0. {
+        dv1
+    } else {
+        dv1.min(dv2)
+    }
+}
+
+fn dash_alpha(
+    t: f32,
+    period: f32,
+    length: f32,
+    dash_velocity: f32,
+    antialias_threshold: f32,
+) -> f32 {
+    let half_period = period / 2.;
+    let half_length = length / 2.;
+    let centered = fmod(t + half_period - half_length, period) - half_period;
+    let signed_distance = centered.abs() - half_length;
+    saturate(antialias_threshold - signed_distance / dash_velocity)
+}
+
+/// Modulus that has the same sign as `a`.
+fn fmod(a: f32, b: f32) -> f32 {
+    a - b * (a / b).trunc()
+}
+
+fn quarter_ellipse_sdf(point: [f32; 2], radii: [f32; 2]) -> f32 {
+    let circle = [point[0] / radii[0], point[1] / radii[1]];
+    let unit_circle_sdf = (circle[0] * circle[0] + circle[1] * circle[1]).sqrt() - 1.;
+    unit_circle_sdf * (radii[0] + radii[1]) * -0.5
+}
+
+fn pick_corner_radius(center_to_point: [f32; 2], radii: &Corners<ScaledPixels>) -> f32 {
+    match (center_to_point[0] < 0., center_to_point[1] < 0.) {
+        (true, true) => radii.top_left.0,
+        (true, false) => radii.bottom_left.0,
+        (false, true) => radii.top_right.0,
+        (false, false) => radii.bottom_right.0,
+    }
+}
+
+fn quad_sdf(point: [f32; 2], bounds: &Bounds<ScaledPixels>, radii: &Corners<ScaledPixels>) -> f32 {
+    let half_size = [bounds.size.width.0 / 2., bounds.size.height.0 / 2.];
+    let center_to_point = [
+        point[0] - bounds.origin.x.0 - half_size[0],
+        point[1] - bounds.origin.y.0 - half_size[1],
+    ];
+    let corner_radius = pick_corner_radius(center_to_point, radii);
+    let corner_center_to_point = [
+        center_to_point[0].abs() - half_size[0] + corner_radius,
+        center_to_point[1].abs() - half_size[1] + corner_radius,
+    ];
+    quad_sdf_impl(corner_center_to_point, corner_radius)
+}
+
+fn quad_sdf_impl(corner_center_to_point: [f32; 2], corner_radius: f32) -> f32 {
+    if corner_radius == 0. {
+        corner_center_to_point[0].max(corner_center_to_point[1])
+    } else {
+        let outside = [
+            corner_center_to_point[0].max(0.),
+            corner_center_to_point[1].max(0.),
+        ];
+        let signed_distance_to_inset_quad = (outside[0] * outside[0] + outside[1] * outside[1])
+            .sqrt()
+            + corner_center_to_point[0]
+                .max(corner_center_to_point[1])
+                .min(0.);
+        signed_distance_to_inset_quad - corner_radius
+    }
+}
+
+fn gaussian(x: f32, sigma: f32) -> f32 {
+    (-(x * x) / (2. * sigma * sigma)).exp() / ((2. * PI).sqrt() * sigma)
+}
+
+/// Approximates the error function, needed for the gaussian integral.
+fn erf(v: f32) -> f32 {
+    let s = v.signum();
+    let a = v.abs();
+    let r1 = 1. + (0.278393 + (0.230389 + (0.000972 + 0.078108 * a) * a) * a) * a;
+    let r2 = r1 * r1;
+    s - s / (r2 * r2)
+}
+
+fn blur_along_x(x: f32, y: f32, sigma: f32, corner: f32, half_size: [f32; 2]) -> f32 {
+    let delta = (half_size[1] - corner - y.abs()).min(0.);
+    let curved = half_size[0] - corner + (corner * corner - delta * delta).max(0.).sqrt();
+    let scale = 0.5_f32.sqrt() / sigma;
+    let low = 0.5 + 0.5 * erf((x - curved) * scale);
+    let high = 0.5 + 0.5 * erf((x + curved) * scale);
+    high - low
+}
+
+/// Twice the signed area of the triangle `a`, `b`, `p`.
+fn edge_function(a: [f32; 2], b: [f32; 2], p: [f32; 2]) -> f32 {
+    (b[0] - a[0]) * (p[1] - a[1]) - (b[1] - a[1]) * (p[0] - a[0])
+}
+
+fn contains(bounds: &Bounds<ScaledPixels>, point: [f32; 2]) -> bool {
+    point[0] >= bounds.origin.x.0
+        && point[1] >= bounds.origin.y.0
+        && point[0] < bounds.origin.x.0 + bounds.size.width.0
+        && point[1] < bounds.origin.y.0 + bounds.size.height.0
+}
+
+fn saturate(value: f32) -> f32 {
+    value.clamp(0., 1.)
+}
+
+fn mix(a: Color, b: Color, t: f32) -> Color {
+    [0, 1, 2, 3].map(|i| a[i] + (b[i] - a[i]) * t)
+}
+
+fn over(below: Color, above: Color) -> Color {
+    let alpha = above[3] + below[3] * (1. - above[3]);
+    if alpha <= 0. {
+        return [0.; 4];
+    }
+    let channel = |i: usize| (above[i] * above[3] + below[i] * below[3] * (1. - above[3])) / alpha;
+    [channel(0), channel(1), channel(2), alpha]
+}
+
+/// The colors of a background, converted to the space the gradient interpolates in.
+/// This is a port of `prepare_gradient_color` and `gradient_color`.
+struct GradientColors {
+    solid: Color,
+    colors: [Color; 2],
+}
+
+impl GradientColors {
+    fn new(background: &Background) -> Self {
+        match background.tag {
+            BackgroundTag::Solid | BackgroundTag::PatternSlash => Self {
+                solid: hsla_to_linear(background.solid),
+                colors: [[0.; 4]; 2],
+            },
+            BackgroundTag::LinearGradient => {
+                let colors = [background.colors[0].color, background.colors[1].color]
+                    .map(hsla_to_linear)
+                    .map(|color| match background.color_space {
+                        ColorSpace::Srgb => linear_to_srgba(color),
+                        ColorSpace::Oklab => linear_srgb_to_oklab(color),
+                    });
+                Self {
+                    solid: [0.; 4],
+                    colors,
+                }
+            }
+        }
+    }
+
+    fn color_at(
+        &self,
+        background: &Background,
+        position: [f32; 2],
+        bounds: &Bounds<ScaledPixels>,
+    ) -> Color {
+        let size = [bounds.size.width.0, bounds.size.height.0];
+        match background.tag {
+            BackgroundTag::Solid => self.solid,
+            BackgroundTag::LinearGradient => {
+                // -90 degrees to match the CSS gradient angle.
+                let angle = background.gradient_angle_or_pattern_height;
+                let radians = (angle % 360. - 90.) * PI / 180.;
+                let mut direction = [radians.cos(), radians.sin()];
+                let stop0_percentage = background.colors[0].percentage;
+                let stop1_percentage = background.colors[1].percentage;
+
+                // Expand the short side to be the same as the long side.
+                if size[0] > size[1] {
+                    direction[1] *= size[1] / size[0];
+                } else {
+                    direction[0] *= size[0] / size[1];
+                }
+
+                let half_size = [size[0] / 2., size[1] / 2.];
+                let center_to_point = [
+                    position[0] - bounds.origin.x.0 - half_size[0],
+                    position[1] - bounds.origin.y.0 - half_size[1],
+                ];
+                let length = (direction[0] * direction[0] + direction[1] * direction[1]).sqrt();
+                let mut t = (center_to_point[0] * direction[0] + center_to_point[1] * direction[1])
+                    / length;
+                if direction[0].abs() > direction[1].abs() {
+                    t = (t + half_size[0]) / size[0];
+                } else {
+                    t = (t + half_size[1]) / size[1];
+                }
+
+                t = (t - stop0_percentage) / (stop1_percentage - stop0_percentage);
+                t = t.clamp(0., 1.);
+
+                let color = mix(self.colors[0], self.colors[1], t);
+                match background.color_space {
+                    ColorSpace::Srgb => srgba_to_linear(color),
+                    ColorSpace::Oklab => oklab_to_linear_srgb(color),
+                }
+            }
+            BackgroundTag::PatternSlash => {
+                let pattern = background.gradient_angle_or_pattern_height;
+                let pattern_width = (pattern / 65535.) / 255.;
+                let pattern_interval = (pattern % 65535.) / 255.;
+                let pattern_height = pattern_width + pattern_interval;
+                let stripe_angle = PI / 4.;
+                let pattern_period = pattern_height * stripe_angle.sin();
+                let relative_position = [
+                    position[0] - bounds.origin.x.0,
+                    position[1] - bounds.origin.y.0,
+                ];
+                // WGSL matrices are column-major, so this is the transpose of how the
+                // shader's constructor reads.
+                let rotated_x = stripe_angle.cos() * relative_position[0]
+                    + stripe_angle.sin() * relative_position[1];
+                let stripe = rotated_x % pattern_period;
+                let distance = stripe.min(pattern_period - stripe)
+                    - pattern_period * (pattern_width / pattern_height) / 2.;
+                let mut color = self.solid;
+                color[3] *= saturate(0.5 - distance);
+                color
+            }
+        }
+    }
+}
+
+fn hsla_to_linear(hsla: Hsla) -> Color {
+    let rgba = Rgba::from(hsla);
+    [
+        srgb_to_linear(rgba.r),
+        srgb_to_linear(rgba.g),
+        srgb_to_linear(rgba.b),
+        rgba.a,
+    ]
+}
+
+fn srgb_to_linear(value: f32) -> f32 {
+    if value < 0.04045 {
+        value / 12.92
+    } else {
+        ((value + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+fn linear_to_srgb(value: f32) -> f32 {
+    let value = value.clamp(0., 1.);
+    if value < 0.0031308 {
+        value * 12.92
+    } else {
+        1.055 * value.powf(1. / 2.4) - 0.055
+    }
+}
+
+fn linear_to_srgba(color: Color) -> Color {
+    [
+        linear_to_srgb(color[0]),
+        linear_to_srgb(color[1]),
+        linear_to_srgb(color[2]),
+        color[3],
+    ]
+}
+
+fn srgba_to_linear(color: Color) -> Color {
+    [
+        srgb_to_linear(color[0]),
+        srgb_to_linear(color[1]),
+        srgb_to_linear(color[2]),
+        color[3],
+    ]
+}
+
+fn linear_srgb_to_oklab(color: Color) -> Color {
+    let l = 0.4122214708 * color[0] + 0.5363325363 * color[1] + 0.0514459929 * color[2];
+    let m = 0.2119034982 * color[0] + 0.6806995451 * color[1] + 0.1073969566 * color[2];
+    let s = 0.0883024619 * color[0] + 0.2817188376 * color[1] + 0.6299787005 * color[2];
+
+    let l_ = l.cbrt();
+    let m_ = m.cbrt();
+    let s_ = s.cbrt();
+
+    [
+        0.2104542553 * l_ + 0.7936177850 * m_ - 0.0040720468 * s_,
+        1.9779984951 * l_ - 2.4285922050 * m_ + 0.4505937099 * s_,
+        0.0259040371 * l_ + 0.7827717662 * m_ - 0.8086757660 * s_,
+        color[3],
+    ]
+}
+
+fn oklab_to_linear_srgb(color: Color) -> Color {
+    let l_ = color[0] + 0.3963377774 * color[1] + 0.2158037573 * color[2];
+    let m_ = color[0] - 0.1055613458 * color[1] - 0.0638541728 * color[2];
+    let s_ = color[0] - 0.0894841775 * color[1] - 1.2914855480 * color[2];
+
+    let l = l_ * l_ * l_;
+    let m = m_ * m_ * m_;
+    let s = s_ * s_ * s_;
+
+    [
+        4.0767416621 * l - 3.3077115913 * m + 0.2309699292 * s,
+        -1.2684380046 * l + 2.6097574011 * m - 0.3413193965 * s,
+        -0.0041960863 * l - 0.7034186147 * m + 1.7076147010 * s,
+        color[3],
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        AtlasKey, Context, Edges, IntoElement, ParentElement as _, PlatformAtlas as _, Render,
+        RenderSvgParams, SharedString, Styled as _, TestAppContext, TransformationMatrix, Window,
+        black, div, point, px, rgb, size,
+    };
+    use std::borrow::Cow;
+
+    fn bounds(x: f32, y: f32, width: f32, height: f32) -> Bounds<ScaledPixels> {
+        Bounds {
+            origin: point(ScaledPixels(x), ScaledPixels(y)),
+            size: size(ScaledPixels(width), ScaledPixels(height)),
+        }
+    }
+
+    fn full_mask() -> ContentMask<ScaledPixels> {
+        ContentMask {
+            bounds: bounds(0., 0., 100., 100.),
+        }
+    }
+
+    fn render(scene: &mut Scene, atlas: &TestAtlas) -> RgbaImage {
+        scene.finish();
+        render_scene(scene, size(DevicePixels(20), DevicePixels(20)), atlas)
+    }
+
+    #[test]
+    fn test_quads() {
+        let mut scene = Scene::default();
+        scene.insert_primitive(Quad {
+            bounds: bounds(2., 2., 10., 10.),
+            content_mask: full_mask(),
+            background: rgb(0xff0000).into(),
+            border_color: rgb(0x0000ff).into(),
+            border_widths: Edges::all(ScaledPixels(2.)),
+            ..Default::default()
+        });
+        // A quad clipped by its content mask only paints inside the mask.
+        scene.insert_primitive(Quad {
+            bounds: bounds(14., 0., 6., 20.),
+            content_mask: ContentMask {
+                bounds: bounds(14., 0., 6., 10.),
+            },
+            background: rgb(0x00ff00).into(),
+            ..Default::default()
+        });
+        let image = render(&mut scene, &TestAtlas::new());
+
+        assert_eq!(image.get_pixel(0, 0).0, [0, 0, 0, 0]);
+        assert_eq!(image.get_pixel(2, 2).0, [0, 0, 255, 255]);
+        assert_eq!(image.get_pixel(7, 7).0, [255, 0, 0, 255]);
+        assert_eq!(image.get_pixel(11, 7).0, [0, 0, 255, 255]);
+        assert_eq!(image.get_pixel(12, 7).0, [0, 0, 0, 0]);
+        assert_eq!(image.get_pixel(16, 5).0, [0, 255, 0, 255]);
+        assert_eq!(image.get_pixel(16, 15).0, [0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn test_rounded_corners_are_antialiased() {
+        let mut scene = Scene::default();
+        scene.insert_primitive(Quad {
+            bounds: bounds(0., 0., 20., 20.),
+            content_mask: full_mask(),
+            background: black().into(),
+            corner_radii: Corners::all(ScaledPixels(10.)),
+            ..Default::default()
+        });
+        let image = render(&mut scene, &TestAtlas::new());
+
+        assert_eq!(image.get_pixel(0, 0).0[3], 0);
+        assert_eq!(image.get_pixel(10, 10).0[3], 255);
+        let edge_alpha = image.get_pixel(3, 2).0[3];
+        assert!(edge_alpha > 0 && edge_alpha < 255, "{edge_alpha}");
+    }
+
+    #[test]
+    fn test_paths_use_even_odd_fill() {
+        let mut path = Path::new(point(px(0.), px(0.)));
+        path.line_to(point(px(20.), px(0.)));
+        path.line_to(point(px(20.), px(20.)));
+        path.line_to(point(px(0.), px(20.)));
+        // A second contour inside the first one cuts a hole into it.
+        path.move_to(point(px(5.), px(5.)));
+        path.line_to(point(px(15.), px(5.)));
+        path.line_to(point(px(15.), px(15.)));
+        path.line_to(point(px(5.), px(15.)));
+        let mut path = path.scale(1.);
+        path.content_mask = full_mask();
+        path.color = rgb(0x0000ff).into();
+
+        let mut scene = Scene::default();
+        scene.insert_primitive(path);
+        let image = render(&mut scene, &TestAtlas::new());
+
+        assert_eq!(image.get_pixel(2, 2).0, [0, 0, 255, 255]);
+        assert_eq!(image.get_pixel(17, 17).0, [0, 0, 255, 255]);
+        assert_eq!(image.get_pixel(10, 10).0, [0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn test_monochrome_sprites_sample_the_atlas() {
+        let atlas = TestAtlas::new();
+        let key = AtlasKey::Svg(RenderSvgParams {
+            path: SharedString::from("icon.svg"),
+            size: size(DevicePixels(2), DevicePixels(2)),
+        });
+        let tile = atlas
+            .get_or_insert_with(&key, &mut || {
+                Ok(Some((
+                    size(DevicePixels(2), DevicePixels(2)),
+                    Cow::Owned(vec![255, 0, 0, 255]),
+                )))
+            })
+            .unwrap()
+            .unwrap();
+
+        let mut scene = Scene::default();
+        scene.insert_primitive(MonochromeSprite {
+            order: 0,
+            pad: 0,
+            bounds: bounds(0., 0., 4., 4.),
+            content_mask: full_mask(),
+            color: rgb(0xffffff).into(),
+            tile,
+            transformation: TransformationMatrix::unit(),
+        });
+        let image = render(&mut scene, &atlas);
+
+        assert_eq!(image.get_pixel(0, 0).0, [255, 255, 255, 255]);
+        assert_eq!(image.get_pixel(3, 0).0, [0, 0, 0, 0]);
+        assert_eq!(image.get_pixel(0, 3).0, [0, 0, 0, 0]);
+        assert_eq!(image.get_pixel(3, 3).0, [255, 255, 255, 255]);
+        assert_eq!(image.get_pixel(5, 5).0, [0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn test_shadows_fade_out() {
+        let mut scene = Scene::default();
+        scene.insert_primitive(Shadow {
+            order: 0,
+            blur_radius: ScaledPixels(2.),
+            bounds: bounds(6., 6., 8., 8.),
+            corner_radii: Corners::default(),
+            content_mask: full_mask(),
+            color: black(),
+        });
+        let image = render(&mut scene, &TestAtlas::new());
+
+        let center = image.get_pixel(10, 10).0[3];
+        let edge = image.get_pixel(6, 10).0[3];
+        let outside = image.get_pixel(1, 10).0[3];
+        assert!(center > edge && edge > outside, "{center} {edge} {outside}");
+    }
+
+    #[crate::test]
+    fn test_screenshots_show_text(cx: &mut TestAppContext) {
+        struct Text;
+
+        impl Render for Text {
+            fn render(&mut self, _: &mut Window, _: &mut Context<Self>) -> impl IntoElement {
+                div()
+                    .size_full()
+                    .bg(rgb(0xffffff))
+                    .text_color(rgb(0x000000))
+                    .text_size(px(16.))
+                    .child("Hello")
+            }
+        }
+
+        let (_, cx) = cx.add_window_view(|_, _| Text);
+        let image = cx.capture_screenshot();
+        let dark_pixels = |columns: Range<u32>| {
+            columns
+                .flat_map(|x| (0..40).map(move |y| (x, y)))
+                .filter(|(x, y)| image.get_pixel(*x, *y).0[0] < 128)
+                .count()
+        };
+
+        // Five characters advance by 0.6em each, which is 96 device pixels.
+        assert!(dark_pixels(0..96) > 100);
+        assert_eq!(dark_pixels(100..200), 0);
+    }
+
+    #[crate::test]
+    fn test_capture_screenshot(cx: &mut TestAppContext) {
+        struct Swatch;
+
+        impl Render for Swatch {
+            fn render(&mut self, _: &mut Window, _: &mut Context<Self>) -> impl IntoElement {
+                div()
+                    .size_full()
+                    .bg(rgb(0xffffff))
+                    .child(div().size(px(10.)).bg(rgb(0x00ff00)))
+            }
+        }
+
+        let (_, cx) = cx.add_window_view(|_, _| Swatch);
+        let window_size = cx.update(|window, _| window.bounds().size);
+        let image = cx.capture_screenshot();
+
+        // The test window has a scale factor of 2.
+        assert_eq!(
+            image.dimensions(),
+            (
+                (window_size.width.0 * 2.).ceil() as u32,
+                (window_size.height.0 * 2.).ceil() as u32
+            )
+        );
+        assert_eq!(image.get_pixel(0, 0).0, [0, 255, 0, 255]);
+        assert_eq!(image.get_pixel(19, 19).0, [0, 255, 0, 255]);
+        assert_eq!(image.get_pixel(20, 20).0, [255, 255, 255, 255]);
+    }
+}
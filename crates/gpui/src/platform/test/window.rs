@@ -1,10 +1,11 @@
 use crate::{
     AnyWindowHandle, AtlasKey, AtlasTextureId, AtlasTile, Bounds, DispatchEventResult, GpuSpecs,
     Pixels, PlatformAtlas, PlatformDisplay, PlatformInput, PlatformInputHandler, PlatformWindow,
-    Point, RequestFrameOptions, ScaledPixels, Size, TestPlatform, TileId, WindowAppearance,
-    WindowBackgroundAppearance, WindowBounds, WindowParams,
+    Point, RequestFrameOptions, ScaledPixels, Scene, Size, SpriteTexture, TestPlatform, TileId,
+    WindowAppearance, WindowBackgroundAppearance, WindowBounds, WindowParams, render_scene,
 };
 use collections::HashMap;
+use image::RgbaImage;
 use parking_lot::Mutex;
 use raw_window_handle::{HasDisplayHandle, HasWindowHandle};
 use std::{
@@ -19,7 +20,7 @@ pub(crate) struct TestWindowState {
     pub(crate) title: Option<String>,
     pub(crate) edited: bool,
     platform: Weak<TestPlatform>,
-    sprite_atlas: Arc<dyn PlatformAtlas>,
+    sprite_atlas: Arc<TestAtlas>,
     pub(crate) should_close_handler: Option<Box<dyn FnMut() -> bool>>,
     input_callback: Option<Box<dyn FnMut(PlatformInput) -> DispatchEventResult>>,
     active_status_change_callback: Option<Box<dyn FnMut(bool)>>,
@@ -28,6 +29,7 @@ pub(crate) struct TestWindowState {
     moved_callback: Option<Box<dyn FnMut()>>,
     input_handler: Option<PlatformInputHandler>,
     is_fullscreen: bool,
+    pub(crate) accessibility_active: bool,
 }
 
 #[derive(Clone)]
@@ -72,6 +74,7 @@ impl TestWindow {
             moved_callback: None,
             input_handler: None,
             is_fullscreen: false,
+            accessibility_active: false,
         })))
     }
 
@@ -107,6 +110,14 @@ impl TestWindow {
         self.0.lock().input_callback = Some(callback);
         !result.propagate
     }
+
+    /// Rasterizes the scene on the CPU at the window's size and scale factor, reading
+    /// sprites from this window's atlas.
+    pub fn render_scene(&self, scene: &Scene) -> RgbaImage {
+        let size = self.bounds().size.to_device_pixels(self.scale_factor());
+        let atlas = self.0.lock().sprite_atlas.clone();
+        render_scene(scene, size, &atlas)
+    }
 }
 
 impl PlatformWindow for TestWindow {
@@ -259,7 +270,7 @@ impl PlatformWindow for TestWindow {
     fn draw(&self, _scene: &crate::Scene) {}
 
     fn sprite_atlas(&self) -> sync::Arc<dyn crate::PlatformAtlas> {
-        self.0.lock().sprite_atlas.clone()
+        self.0.lock().sprite_atlas.clone() as Arc<dyn PlatformAtlas>
     }
 
     fn as_test(&mut self) -> Option<&mut TestWindow> {
@@ -284,11 +295,16 @@ impl PlatformWindow for TestWindow {
     fn gpu_specs(&self) -> Option<GpuSpecs> {
         None
     }
+
+    fn is_accessibility_active(&self) -> bool {
+        self.0.lock().accessibility_active
+    }
 }
 
 pub(crate) struct TestAtlasState {
     next_id: u32,
     tiles: HashMap<AtlasKey, AtlasTile>,
+    textures: HashMap<u32, Arc<SpriteTexture>>,
 }
 
 pub(crate) struct TestAtlas(Mutex<TestAtlasState>);
@@ -298,8 +314,14 @@ impl TestAtlas {
         TestAtlas(Mutex::new(TestAtlasState {
             next_id: 0,
             tiles: HashMap::default(),
+            textures: HashMap::default(),
         }))
     }
+
+    /// Returns the pixels stored for the given tile, which fill the tile's whole texture.
+    pub(crate) fn texture(&self, tile: &AtlasTile) -> Option<Arc<SpriteTexture>> {
+        self.0.lock().textures.get(&tile.texture_id.index).cloned()
+    }
 }
 
 impl PlatformAtlas for TestAtlas {
@@ -316,7 +338,7 @@ impl PlatformAtlas for TestAtlas {
         }
         drop(state);
 
-        let Some((size, _)) = build()? else {
+        let Some((size, bytes)) = build()? else {
             return Ok(None);
         };
 
@@ -325,13 +347,22 @@ impl PlatformAtlas for TestAtlas {
         let texture_id = state.next_id;
         state.next_id += 1;
         let tile_id = state.next_id;
-
+        let kind = key.texture_kind();
+
+        state.textures.insert(
+            texture_id,
+            Arc::new(SpriteTexture {
+                kind,
+                size,
+                bytes: bytes.into_owned(),
+            }),
+        );
         state.tiles.insert(
             key.clone(),
             crate::AtlasTile {
                 texture_id: AtlasTextureId {
                     index: texture_id,
-                    kind: crate::AtlasTextureKind::Path,
+                    kind,
                 },
                 tile_id: TileId(tile_id),
                 padding: 0,
@@ -347,6 +378,8 @@ impl PlatformAtlas for TestAtlas {
 
     fn remove(&self, key: &AtlasKey) {
         let mut state = self.0.lock();
-        state.tiles.remove(key);
+        if let Some(tile) = state.tiles.remove(key) {
+            state.textures.remove(&tile.texture_id.index);
+        }
     }
 }
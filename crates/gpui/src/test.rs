@@ -26,12 +26,15 @@
 //! }
 //! ```
 use crate::{Entity, Subscription, TestAppContext, TestDispatcher};
+use anyhow::{Context as _, Result};
 use futures::StreamExt as _;
+use image::RgbaImage;
 use rand::prelude::*;
 use smol::channel;
 use std::{
     env,
     panic::{self, RefUnwindSafe},
+    path::Path,
     pin::Pin,
 };
 
@@ -156,3 +159,63 @@ pub fn observe<T: 'static>(entity: &Entity<T>, cx: &mut TestAppContext) -> Obser
 
     Observation { rx, _subscription }
 }
+
+/// Writes a screenshot captured with [`crate::VisualTestContext::capture_screenshot`] to
+/// the given path as a PNG.
+pub fn save_screenshot(image: &RgbaImage, path: impl AsRef<Path>) -> Result<()> {
+    let path = path.as_ref();
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).with_context(|| format!("failed to create {parent:?}"))?;
+    }
+    image
+        .save_with_format(path, image::ImageFormat::Png)
+        .with_context(|| format!("failed to write screenshot to {path:?}"))
+}
+
+/// Asserts that a screenshot matches the reference PNG at the given path.
+///
+/// When the `UPDATE_SNAPSHOTS` environment variable is set, the screenshot is written to
+/// the path instead, which is the only way references are created. On a mismatch, the
+/// screenshot is saved next to the reference with a `.actual.png` extension before
+/// panicking.
+#[track_caller]
+pub fn assert_screenshot_matches(image: &RgbaImage, path: impl AsRef<Path>) {
+    let path = path.as_ref();
+    if env::var("UPDATE_SNAPSHOTS").is_ok() {
+        save_screenshot(image, path).unwrap();
+        return;
+    }
+    if !path.exists() {
+        panic!(
+            "no reference screenshot at {path:?}. Run with UPDATE_SNAPSHOTS=1 to create it, \
+            then check that it's right and commit it."
+        );
+    }
+
+    let expected = image::open(path)
+        .unwrap_or_else(|error| panic!("failed to read reference screenshot {path:?}: {error}"))
+        .into_rgba8();
+    if expected.dimensions() != image.dimensions() {
+        let actual_path = path.with_extension("actual.png");
+        save_screenshot(image, &actual_path).unwrap();
+        panic!(
+            "screenshot is {:?}, but the reference {path:?} is {:?}; saved it to {actual_path:?}",
+            image.dimensions(),
+            expected.dimensions(),
+        );
+    }
+
+    let mismatched_pixels = expected
+        .pixels()
+        .zip(image.pixels())
+        .filter(|(expected, actual)| expected != actual)
+        .count();
+    if mismatched_pixels > 0 {
+        let actual_path = path.with_extension("actual.png");
+        save_screenshot(image, &actual_path).unwrap();
+        panic!(
+            "{mismatched_pixels} pixels differ from the reference screenshot {path:?}; saved the \
+            screenshot to {actual_path:?}. Run with UPDATE_SNAPSHOTS=1 to accept it."
+        );
+    }
+}
@@ -1,14 +1,16 @@
 use crate::{
-    Action, AnyDrag, AnyElement, AnyImageCache, AnyTooltip, AnyView, App, AppContext, Arena, Asset,
-    AsyncWindowContext, AvailableSpace, Background, BorderStyle, Bounds, BoxShadow, Context,
-    Corners, CursorStyle, Decorations, DevicePixels, DispatchActionListener, DispatchNodeId,
-    DispatchTree, DisplayId, Edges, Effect, Entity, EntityId, EventEmitter, FileDropEvent, FontId,
-    Global, GlobalElementId, GlyphId, GpuSpecs, Hsla, InputHandler, IsZero, KeyBinding, KeyContext,
-    KeyDownEvent, KeyEvent, Keystroke, KeystrokeEvent, LayoutId, LineLayoutIndex, Modifiers,
-    ModifiersChangedEvent, MonochromeSprite, MouseButton, MouseEvent, MouseMoveEvent, MouseUpEvent,
-    Path, Pixels, PlatformAtlas, PlatformDisplay, PlatformInput, PlatformInputHandler,
-    PlatformWindow, Point, PolychromeSprite, PromptLevel, Quad, Render, RenderGlyphParams,
-    RenderImage, RenderImageParams, RenderSvgParams, Replay, ResizeEdge, SMOOTH_SVG_SCALE_FACTOR,
+    AccessibilityAction, AccessibilityNodeId, AccessibilityProperties, AccessibilityRequest,
+    AccessibilityTree, AccessibilityTreeBuilder, Action, AnyDrag, AnyElement, AnyImageCache,
+    AnyTooltip, AnyView, App, AppContext, Arena, Asset, AsyncWindowContext, AvailableSpace,
+    Background, BorderStyle, Bounds, BoxShadow, Context, Corners, CursorStyle, Decorations,
+    DevicePixels, DispatchActionListener, DispatchNodeId, DispatchTree, DisplayId, Edges, Effect,
+    Entity, EntityId, EventEmitter, FileDropEvent, FontId, Global, GlobalElementId, GlyphId,
+    GpuSpecs, Hsla, InputHandler, IsZero, KeyBinding, KeyContext, KeyDownEvent, KeyEvent,
+    Keystroke, KeystrokeEvent, LayoutId, LineLayoutIndex, Modifiers, ModifiersChangedEvent,
+    MonochromeSprite, MouseButton, MouseDownEvent, MouseEvent, MouseMoveEvent, MouseUpEvent, Path,
+    Pixels, PlatformAtlas, PlatformDisplay, PlatformInput, PlatformInputHandler, PlatformWindow,
+    Point, PolychromeSprite, PromptLevel, Quad, Render, RenderGlyphParams, RenderImage,
+    RenderImageParams, RenderSvgParams, Replay, ResizeEdge, Role, SMOOTH_SVG_SCALE_FACTOR,
     SUBPIXEL_VARIANTS, ScaledPixels, Scene, Shadow, SharedString, Size, StrikethroughStyle, Style,
     SubscriberSet, Subscription, TaffyLayoutEngine, Task, TextStyle, TextStyleRefinement,
     TransformationMatrix, Underline, UnderlineStyle, WindowAppearance, WindowBackgroundAppearance,
@@ -500,6 +502,7 @@ pub(crate) struct Frame {
     pub(crate) input_handlers: Vec<Option<PlatformInputHandler>>,
     pub(crate) tooltip_requests: Vec<Option<TooltipRequest>>,
     pub(crate) cursor_styles: Vec<CursorStyleRequest>,
+    pub(crate) accessibility: AccessibilityTreeBuilder,
     #[cfg(any(test, feature = "test-support"))]
     pub(crate) debug_bounds: FxHashMap<String, Bounds<Pixels>>,
 }
@@ -520,6 +523,7 @@ pub(crate) struct PaintIndex {
     mouse_listeners_index: usize,
     input_handlers_index: usize,
     cursor_styles_index: usize,
+    accessibility_index: usize,
     accessed_element_states_index: usize,
     line_layout_index: LineLayoutIndex,
 }
@@ -539,6 +543,7 @@ impl Frame {
             input_handlers: Vec::new(),
             tooltip_requests: Vec::new(),
             cursor_styles: Vec::new(),
+            accessibility: AccessibilityTreeBuilder::default(),
 
             #[cfg(any(test, feature = "test-support"))]
             debug_bounds: FxHashMap::default(),
@@ -554,6 +559,7 @@ impl Frame {
         self.input_handlers.clear();
         self.tooltip_requests.clear();
         self.cursor_styles.clear();
+        self.accessibility.clear();
         self.hitboxes.clear();
         self.deferred_draws.clear();
         self.focus = None;
@@ -648,6 +654,15 @@ pub struct Window {
     pub(crate) pending_input_observers: SubscriberSet<(), AnyObserver>,
     prompt: Option<RenderablePromptHandle>,
     pub(crate) client_inset: Option<Pixels>,
+    title: Option<SharedString>,
+    accessibility_tree: RefCell<
+        Option<(
+            AccessibilityTree,
+            FxHashMap<AccessibilityNodeId, FocusHandle>,
+        )>,
+    >,
+    accessibility_tree_changed: Cell<bool>,
+    accessibility_active: bool,
 }
 
 #[derive(Clone, Debug, Default)]
@@ -868,6 +883,16 @@ impl Window {
                     .log_err();
             }
         }));
+        platform_window.on_accessibility_request(Box::new({
+            let mut cx = cx.to_async();
+            move |request| {
+                handle
+                    .update(&mut cx, |_, window, cx| {
+                        window.handle_accessibility_request(request, cx)
+                    })
+                    .log_err();
+            }
+        }));
         platform_window.on_input({
             let mut cx = cx.to_async();
             Box::new(move |event| {
@@ -934,6 +959,10 @@ impl Window {
             pending_input_observers: SubscriberSet::new(),
             prompt: None,
             client_inset: None,
+            title: None,
+            accessibility_tree: RefCell::default(),
+            accessibility_tree_changed: Cell::new(false),
+            accessibility_active: false,
             image_cache_stack: Vec::new(),
         })
     }
@@ -1174,6 +1203,31 @@ impl Window {
         });
     }
 
+    fn dispatch_keystroke_interceptors(
+        &mut self,
+        event: &dyn Any,
+        context_stack: Vec<KeyContext>,
+        cx: &mut App,
+    ) {
+        let Some(key_down_event) = event.downcast_ref::<KeyDownEvent>() else {
+            return;
+        };
+
+        cx.keystroke_interceptors
+            .clone()
+            .retain(&(), move |callback| {
+                (callback)(
+                    &KeystrokeEvent {
+                        keystroke: key_down_event.keystroke.clone(),
+                        action: None,
+                        context_stack: context_stack.clone(),
+                    },
+                    self,
+                    cx,
+                )
+            });
+    }
+
     /// Schedules the given function to be run at the end of the current effect cycle, allowing entities
     /// that are currently on the stack to be returned to the app.
     pub fn defer(&self, cx: &mut App, f: impl FnOnce(&mut Window, &mut App) + 'static) {
@@ -1416,6 +1470,7 @@ impl Window {
     /// Updates the window's title at the platform level.
     pub fn set_window_title(&mut self, title: &str) {
         self.platform_window.set_title(title);
+        self.title = Some(SharedString::from(title.to_string()));
     }
 
     /// Sets the application identifier.
@@ -1561,6 +1616,15 @@ impl Window {
         if let Some(input_handler) = self.platform_window.take_input_handler() {
             self.rendered_frame.input_handlers.push(Some(input_handler));
         }
+
+        // The accessibility tree is only built while an assistive technology observes the
+        // window. Cached views don't have nodes to replay when one starts doing so, so
+        // they're all drawn again.
+        let accessibility_active = self.platform_window.is_accessibility_active();
+        if accessibility_active != self.accessibility_active {
+            self.accessibility_active = accessibility_active;
+            self.refreshing = true;
+        }
         self.draw_roots(cx);
         self.dirty_views.clear();
         self.next_frame.window_active = self.active.get();
@@ -1587,6 +1651,8 @@ impl Window {
         let previous_window_active = self.rendered_frame.window_active;
         mem::swap(&mut self.rendered_frame, &mut self.next_frame);
         self.next_frame.clear();
+        self.accessibility_tree.take();
+        self.accessibility_tree_changed.set(true);
         let current_focus_path = self.rendered_frame.focus_path();
         let current_window_active = self.rendered_frame.window_active;
 
@@ -1650,6 +1716,11 @@ impl Window {
     #[profiling::function]
     fn present(&self) {
         self.platform_window.draw(&self.rendered_frame.scene);
+        if self.accessibility_tree_changed.get() && self.platform_window.is_accessibility_active() {
+            self.platform_window
+                .update_accessibility_tree(&self.accessibility_tree());
+            self.accessibility_tree_changed.set(false);
+        }
         self.needs_present.set(false);
         profiling::finish_frame!();
     }
@@ -1901,6 +1972,7 @@ impl Window {
             mouse_listeners_index: self.next_frame.mouse_listeners.len(),
             input_handlers_index: self.next_frame.input_handlers.len(),
             cursor_styles_index: self.next_frame.cursor_styles.len(),
+            accessibility_index: self.next_frame.accessibility.len(),
             accessed_element_states_index: self.next_frame.accessed_element_states.len(),
             line_layout_index: self.text_system.layout_index(),
         }
@@ -1938,6 +2010,130 @@ impl Window {
             range.start.scene_index..range.end.scene_index,
             &self.rendered_frame.scene,
         );
+        self.next_frame.accessibility.replay(
+            range.start.accessibility_index..range.end.accessibility_index,
+            &self.rendered_frame.accessibility,
+        );
+    }
+
+    /// Adds a node to the accessibility tree, with the nodes added by `f` as its children.
+    /// Pass the element's global ID so that the node keeps the same identifier across
+    /// frames. This method should only be called as part of the paint phase of element drawing.
+    pub fn with_accessibility_node<R>(
+        &mut self,
+        id: Option<&GlobalElementId>,
+        role: Role,
+        properties: AccessibilityProperties,
+        bounds: Bounds<Pixels>,
+        f: impl FnOnce(&mut Self) -> R,
+    ) -> R {
+        self.invalidator.debug_assert_paint();
+        if !self.accessibility_active {
+            return f(self);
+        }
+        self.next_frame
+            .accessibility
+            .open(id, role, bounds, properties);
+        let result = f(self);
+        self.next_frame.accessibility.close();
+        result
+    }
+
+    /// Adds a node without children to the accessibility tree. This method should only be
+    /// called as part of the paint phase of element drawing.
+    pub fn insert_accessibility_node(
+        &mut self,
+        id: Option<&GlobalElementId>,
+        role: Role,
+        properties: AccessibilityProperties,
+        bounds: Bounds<Pixels>,
+    ) {
+        self.with_accessibility_node(id, role, properties, bounds, |_| {});
+    }
+
+    /// Returns whether an assistive technology is observing this window. Accessibility
+    /// nodes are only added to the tree while it is, so elements can skip computing their
+    /// properties otherwise.
+    pub fn is_accessibility_active(&self) -> bool {
+        self.accessibility_active
+    }
+
+    /// Returns the accessibility tree of the last frame drawn in this window.
+    pub fn accessibility_tree(&self) -> AccessibilityTree {
+        self.accessibility_tree
+            .borrow_mut()
+            .get_or_insert_with(|| {
+                self.rendered_frame.accessibility.build(
+                    Bounds::new(Point::default(), self.viewport_size),
+                    self.title.clone(),
+                    self.rendered_frame.focus,
+                )
+            })
+            .0
+            .clone()
+    }
+
+    /// Performs an action on a node of the accessibility tree, on behalf of an assistive
+    /// technology.
+    pub fn perform_accessibility_action(
+        &mut self,
+        node_id: AccessibilityNodeId,
+        action: AccessibilityAction,
+        cx: &mut App,
+    ) {
+        let tree = self.accessibility_tree();
+        let Some(node) = tree.node(node_id).filter(|node| !node.disabled) else {
+            return;
+        };
+
+        match action {
+            AccessibilityAction::Click => {
+                // Clicks are simulated at the center of the node, so that they go through
+                // the same hit testing and listeners as real ones.
+                let position = node.bounds.center();
+                self.dispatch_event(
+                    PlatformInput::MouseDown(MouseDownEvent {
+                        button: MouseButton::Left,
+                        position,
+                        modifiers: Modifiers::default(),
+                        click_count: 1,
+                        first_mouse: false,
+                    }),
+                    cx,
+                );
+                self.dispatch_event(
+                    PlatformInput::MouseUp(MouseUpEvent {
+                        button: MouseButton::Left,
+                        position,
+                        modifiers: Modifiers::default(),
+                        click_count: 1,
+                    }),
+                    cx,
+                );
+            }
+            AccessibilityAction::Focus => {
+                let focus_handle = self
+                    .accessibility_tree
+                    .borrow()
+                    .as_ref()
+                    .and_then(|(_, focus_handles)| focus_handles.get(&node_id).cloned());
+                if let Some(focus_handle) = focus_handle {
+                    focus_handle.focus(self);
+                }
+            }
+        }
+    }
+
+    fn handle_accessibility_request(&mut self, request: AccessibilityRequest, cx: &mut App) {
+        match request {
+            AccessibilityRequest::TreeRequested => {
+                self.accessibility_tree_changed.set(true);
+                self.refresh();
+            }
+            AccessibilityRequest::Action { node, action } => {
+                self.perform_accessibility_action(node, action, cx)
+            }
+        }
     }
 
     /// Push a text style onto the stack, and call a function with that style active.
@@ -3295,6 +3491,12 @@ impl Window {
             return;
         };
 
+        cx.propagate_event = true;
+        self.dispatch_keystroke_interceptors(event, self.context_stack(), cx);
+        if !cx.propagate_event {
+            return;
+        }
+
         let mut currently_pending = self.pending_input.take().unwrap_or_default();
         if currently_pending.focus.is_some() && currently_pending.focus != self.focus {
             currently_pending = PendingInput::default();
@@ -44,6 +44,7 @@ pub enum IconName {
     Book,
     BookCopy,
     BookPlus,
+    Bookmark,
     Brain,
     BugOff,
     CaseSensitive,
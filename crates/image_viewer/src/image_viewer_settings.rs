@@ -36,6 +36,7 @@ impl Settings for ImageViewerSettings {
             [sources.default]
                 .into_iter()
                 .chain(sources.user)
+                .chain(sources.profile)
                 .chain(sources.server),
         )
     }
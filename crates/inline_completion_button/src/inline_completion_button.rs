@@ -224,7 +224,7 @@ impl Render for InlineCompletionButton {
                 );
             }
 
-            EditPredictionProvider::Zed => {
+            provider @ (EditPredictionProvider::Zed | EditPredictionProvider::LanguageModel) => {
                 let enabled = self.editor_enabled.unwrap_or(true);
 
                 let zeta_icon = if enabled {
@@ -238,7 +238,9 @@ impl Render for InlineCompletionButton {
                 let has_subscription = self.user_store.read(cx).current_plan().is_some()
                     && self.user_store.read(cx).subscription_period().is_some();
 
-                if !has_subscription || !current_user_terms_accepted.unwrap_or(false) {
+                if provider.is_zed()
+                    && (!has_subscription || !current_user_terms_accepted.unwrap_or(false))
+                {
                     let signed_in = current_user_terms_accepted.is_some();
                     let tooltip_meta = if signed_in {
                         if has_subscription {
@@ -503,7 +505,10 @@ impl InlineCompletionButton {
         let subtle_mode = matches!(current_mode, EditPredictionsMode::Subtle);
         let eager_mode = matches!(current_mode, EditPredictionsMode::Eager);
 
-        if matches!(provider, EditPredictionProvider::Zed) {
+        if matches!(
+            provider,
+            EditPredictionProvider::Zed | EditPredictionProvider::LanguageModel
+        ) {
             menu = menu
                 .separator()
                 .header("Display Modes")
@@ -0,0 +1,67 @@
+use anyhow::Result;
+use collections::HashMap;
+use gpui::{App, Keystroke};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use settings::{Settings, SettingsSources};
+
+use crate::MacroEvent;
+
+/// Named keyboard macros, which can be replayed with the `keyboard_macros::RunMacro` action.
+///
+/// Default: {}
+#[derive(Clone, Debug, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(transparent)]
+pub struct KeyboardMacroSettings(pub HashMap<String, Vec<MacroStep>>);
+
+/// A step of a keyboard macro saved in settings.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(untagged)]
+pub enum MacroStep {
+    /// Dispatches an action without arguments, e.g. `"editor::MoveDown"`.
+    Action(String),
+    /// Dispatches an action with arguments, e.g.
+    /// `["editor::SelectNext", { "replace_newest": true }]`.
+    ActionWithArguments(String, serde_json::Value),
+    /// Types text into the focused editor, e.g. `{ "text": "// " }`.
+    Text { text: String },
+    /// Dispatches keystrokes as if they were pressed, e.g. `{ "keystrokes": "ctrl-k ctrl-u" }`.
+    Keystrokes { keystrokes: String },
+}
+
+impl MacroStep {
+    pub(crate) fn to_event(&self, cx: &App) -> Result<MacroEvent> {
+        Ok(match self {
+            MacroStep::Action(name) => MacroEvent::Action {
+                action: cx.build_action(name, None)?,
+                keystrokes: None,
+            },
+            MacroStep::ActionWithArguments(name, arguments) => MacroEvent::Action {
+                action: cx.build_action(name, Some(arguments.clone()))?,
+                keystrokes: None,
+            },
+            MacroStep::Text { text } => MacroEvent::Text {
+                text: text.as_str().into(),
+                utf16_range_to_replace: None,
+            },
+            MacroStep::Keystrokes { keystrokes } => MacroEvent::Keystrokes(
+                keystrokes
+                    .split_whitespace()
+                    .map(Keystroke::parse)
+                    .collect::<Result<_, _>>()?,
+            ),
+        })
+    }
+}
+
+impl Settings for KeyboardMacroSettings {
+    const KEY: Option<&'static str> = Some("keyboard_macros");
+
+    type FileContent = Self;
+
+    fn load(sources: SettingsSources<Self::FileContent>, _: &mut App) -> Result<Self> {
+        sources.json_merge()
+    }
+
+    fn import_from_vscode(_vscode: &settings::VsCodeSettings, _current: &mut Self::FileContent) {}
+}
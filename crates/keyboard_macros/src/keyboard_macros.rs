@@ -0,0 +1,801 @@
+mod keyboard_macro_settings;
+mod recording_indicator;
+
+use std::{cell::RefCell, iter, mem, ops::Range, rc::Rc, sync::Arc};
+
+use anyhow::{Context as _, Result};
+use editor::{Anchor, Editor, EditorEvent, actions::HandleInput};
+use fs::Fs;
+use gpui::{
+    Action, App, Context, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable, Global,
+    Keystroke, KeystrokeEvent, Subscription, WeakEntity, Window, actions, impl_actions,
+};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use settings::{Settings, update_settings_file};
+use ui::prelude::*;
+use util::ResultExt;
+use workspace::{ModalView, Toast, Workspace, notifications::NotificationId};
+
+pub use keyboard_macro_settings::{KeyboardMacroSettings, MacroStep};
+pub use recording_indicator::RecordingIndicator;
+
+actions!(
+    keyboard_macros,
+    [ToggleRecording, ReplayLastMacroOnEachCursor, SaveLastMacro]
+);
+
+/// Replays the last recorded keyboard macro.
+#[derive(Clone, Default, Deserialize, JsonSchema, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct ReplayLastMacro {
+    /// How many times to replay the macro.
+    #[serde(default)]
+    pub count: Option<usize>,
+    /// Whether to replay the macro at each cursor in turn, rather than once
+    /// with all of them.
+    #[serde(default)]
+    pub each_cursor: bool,
+}
+
+/// Replays a keyboard macro saved in the `keyboard_macros` setting.
+#[derive(Clone, Default, Deserialize, Serialize, JsonSchema, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct RunMacro {
+    /// The name of the macro.
+    pub name: String,
+    /// How many times to replay the macro.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub count: Option<usize>,
+    /// Whether to replay the macro at each cursor in turn, rather than once
+    /// with all of them.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub each_cursor: bool,
+}
+
+impl_actions!(keyboard_macros, [ReplayLastMacro, RunMacro]);
+
+/// Replays are cut short after this many steps, as a saved macro that runs
+/// itself would otherwise never end.
+const MAX_REPLAY_STEPS: usize = 10000;
+
+pub fn init(cx: &mut App) {
+    KeyboardMacroSettings::register(cx);
+    cx.set_global(KeyboardMacros::default());
+    cx.on_action(toggle_recording);
+    cx.observe_keystrokes(observe_keystroke).detach();
+    cx.observe_new(register_editor).detach();
+    cx.observe_new(MacroNameModal::register).detach();
+}
+
+/// Something done while recording a macro, which is done again when it's replayed.
+pub(crate) enum MacroEvent {
+    /// An action dispatched by a key binding, and the keystrokes of that
+    /// binding, if they could be found.
+    Action {
+        action: Box<dyn Action>,
+        keystrokes: Option<String>,
+    },
+    /// Text typed into an editor.
+    Text {
+        text: Arc<str>,
+        utf16_range_to_replace: Option<Range<isize>>,
+    },
+    /// Keystrokes dispatched as if they were pressed, which only saved macros have.
+    Keystrokes(Vec<Keystroke>),
+}
+
+impl Clone for MacroEvent {
+    fn clone(&self) -> Self {
+        match self {
+            Self::Action { action, keystrokes } => Self::Action {
+                action: action.boxed_clone(),
+                keystrokes: keystrokes.clone(),
+            },
+            Self::Text {
+                text,
+                utf16_range_to_replace,
+            } => Self::Text {
+                text: text.clone(),
+                utf16_range_to_replace: utf16_range_to_replace.clone(),
+            },
+            Self::Keystrokes(keystrokes) => Self::Keystrokes(keystrokes.clone()),
+        }
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct KeyboardMacros {
+    /// The macro being recorded, if any.
+    recording: Option<Vec<MacroEvent>>,
+    last_macro: Option<Vec<MacroEvent>>,
+    /// The editor that replayed text is typed into.
+    focused_editor: Option<WeakEntity<Editor>>,
+    replayer: Option<Replayer>,
+}
+
+impl Global for KeyboardMacros {}
+
+impl KeyboardMacros {
+    pub(crate) fn is_recording(cx: &App) -> bool {
+        cx.try_global::<Self>()
+            .is_some_and(|macros| macros.recording.is_some())
+    }
+
+    fn is_replaying(cx: &App) -> bool {
+        cx.global::<Self>().replayer.is_some()
+    }
+
+    fn record(event: MacroEvent, cx: &mut App) {
+        if Self::is_recording(cx) {
+            if let Some(recording) = cx.global_mut::<Self>().recording.as_mut() {
+                recording.push(event);
+            }
+        }
+    }
+}
+
+fn toggle_recording(_: &ToggleRecording, cx: &mut App) {
+    let macros = cx.global_mut::<KeyboardMacros>();
+    match macros.recording.take() {
+        Some(events) => {
+            if !events.is_empty() {
+                macros.last_macro = Some(events);
+            }
+        }
+        None => macros.recording = Some(Vec::new()),
+    }
+}
+
+/// Records the action a key binding dispatched. Actions dispatched any other
+/// way, like from the command palette or a mouse click, don't go through a
+/// keystroke and so aren't recorded.
+fn observe_keystroke(event: &KeystrokeEvent, window: &mut Window, cx: &mut App) {
+    let Some(action) = event.action.as_deref() else {
+        return;
+    };
+    if !KeyboardMacros::is_recording(cx) || !should_record(action) {
+        return;
+    }
+    // Whatever a macro does while it's replayed is recorded as the action
+    // that replayed it.
+    if KeyboardMacros::is_replaying(cx) && !action.as_any().is::<RunMacro>() {
+        return;
+    }
+    let keystrokes = binding_keystrokes(action, &event.keystroke, window);
+    KeyboardMacros::record(
+        MacroEvent::Action {
+            action: action.boxed_clone(),
+            keystrokes,
+        },
+        cx,
+    );
+}
+
+/// Actions that control macros aren't recorded, other than running a saved
+/// macro. Neither is input, which is recorded as it's handled by the editor.
+fn should_record(action: &dyn Action) -> bool {
+    let action = action.as_any();
+    !(action.is::<ToggleRecording>()
+        || action.is::<ReplayLastMacro>()
+        || action.is::<ReplayLastMacroOnEachCursor>()
+        || action.is::<SaveLastMacro>()
+        || action.is::<HandleInput>())
+}
+
+/// Returns the keystrokes of the binding that dispatched the action, as
+/// actions with arguments can only be saved as the keystrokes that dispatch them.
+fn binding_keystrokes(
+    action: &dyn Action,
+    last_keystroke: &Keystroke,
+    window: &Window,
+) -> Option<String> {
+    let binding = window
+        .bindings_for_action(action)
+        .into_iter()
+        .rev()
+        .find(|binding| {
+            binding.keystrokes().last().is_some_and(|keystroke| {
+                keystroke.modifiers == last_keystroke.modifiers
+                    && keystroke.key == last_keystroke.key
+            })
+        })?;
+    Some(unparse_keystrokes(binding.keystrokes()))
+}
+
+fn unparse_keystrokes(keystrokes: &[Keystroke]) -> String {
+    keystrokes
+        .iter()
+        .map(|keystroke| keystroke.unparse())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn register_editor(editor: &mut Editor, _window: Option<&mut Window>, cx: &mut Context<Editor>) {
+    if !editor.mode().is_full() {
+        return;
+    }
+
+    cx.subscribe_self(|_, event: &EditorEvent, cx| match event {
+        EditorEvent::Focused => {
+            let editor = cx.entity().downgrade();
+            cx.global_mut::<KeyboardMacros>().focused_editor = Some(editor);
+        }
+        EditorEvent::InputHandled {
+            text,
+            utf16_range_to_replace,
+        } => {
+            if !KeyboardMacros::is_replaying(cx) {
+                KeyboardMacros::record(
+                    MacroEvent::Text {
+                        text: text.clone(),
+                        utf16_range_to_replace: utf16_range_to_replace.clone(),
+                    },
+                    cx,
+                );
+            }
+        }
+        _ => {}
+    })
+    .detach();
+
+    let handle = cx.entity().downgrade();
+    editor
+        .register_action({
+            let handle = handle.clone();
+            move |action: &ReplayLastMacro, window, cx| {
+                if let Some(editor) = handle.upgrade() {
+                    replay_last_macro(&editor, action.count, action.each_cursor, window, cx);
+                }
+            }
+        })
+        .detach();
+
+    editor
+        .register_action({
+            let handle = handle.clone();
+            move |_: &ReplayLastMacroOnEachCursor, window, cx| {
+                if let Some(editor) = handle.upgrade() {
+                    replay_last_macro(&editor, None, true, window, cx);
+                }
+            }
+        })
+        .detach();
+
+    editor
+        .register_action(move |action: &RunMacro, window, cx| {
+            if let Some(editor) = handle.upgrade() {
+                run_macro(&editor, action, window, cx);
+            }
+        })
+        .detach();
+}
+
+fn replay_last_macro(
+    editor: &Entity<Editor>,
+    count: Option<usize>,
+    each_cursor: bool,
+    window: &mut Window,
+    cx: &mut App,
+) {
+    let macros = cx.global::<KeyboardMacros>();
+    // The macro being recorded would depend on the one it's about to replace.
+    if macros.recording.is_some() {
+        return;
+    }
+    let Some(events) = macros.last_macro.clone() else {
+        return;
+    };
+    replay(editor, events, count, each_cursor, window, cx);
+}
+
+fn run_macro(editor: &Entity<Editor>, action: &RunMacro, window: &mut Window, cx: &mut App) {
+    let events = KeyboardMacroSettings::get_global(cx)
+        .0
+        .get(&action.name)
+        .with_context(|| format!("No keyboard macro named \"{}\"", action.name))
+        .and_then(|steps| {
+            steps
+                .iter()
+                .map(|step| step.to_event(cx))
+                .collect::<Result<Vec<_>>>()
+        });
+    match events {
+        Ok(events) => replay(editor, events, action.count, action.each_cursor, window, cx),
+        Err(error) => match editor.read(cx).workspace() {
+            Some(workspace) => {
+                workspace.update(cx, |workspace, cx| workspace.show_error(&error, cx));
+            }
+            None => log::error!("{error:?}"),
+        },
+    }
+}
+
+fn replay(
+    editor: &Entity<Editor>,
+    events: Vec<MacroEvent>,
+    count: Option<usize>,
+    each_cursor: bool,
+    window: &mut Window,
+    cx: &mut App,
+) {
+    let count = count.unwrap_or(1);
+    let repeated_events = || {
+        iter::repeat_n(&events, count)
+            .flatten()
+            .cloned()
+            .map(ReplayStep::Event)
+    };
+    let steps = if each_cursor {
+        let ranges = editor
+            .read(cx)
+            .selections
+            .disjoint_anchor_ranges()
+            .collect::<Vec<_>>();
+        let mut steps = Vec::new();
+        for range in ranges {
+            steps.push(ReplayStep::Select(range));
+            steps.extend(repeated_events());
+            steps.push(ReplayStep::CollectSelections);
+        }
+        steps.push(ReplayStep::SelectCollected);
+        steps
+    } else {
+        repeated_events().collect()
+    };
+
+    let macros = cx.global_mut::<KeyboardMacros>();
+    // A macro run by the one being replayed runs before the rest of it.
+    if let Some(replayer) = &macros.replayer {
+        replayer.insert(steps);
+        return;
+    }
+    let replayer = Replayer::new(editor.downgrade(), steps);
+    macros.replayer = Some(replayer.clone());
+    window.defer(cx, move |window, cx| replayer.next(window, cx));
+}
+
+#[derive(Clone)]
+enum ReplayStep {
+    Event(MacroEvent),
+    /// Selects one of the ranges the macro is replayed at each of.
+    Select(Range<Anchor>),
+    /// Remembers where replaying at one of the ranges left the selections.
+    CollectSelections,
+    /// Selects everything remembered by `CollectSelections`.
+    SelectCollected,
+}
+
+/// Replays the steps of a macro one at a time, as each action has to be
+/// handled before the next step can be.
+#[derive(Clone)]
+struct Replayer(Rc<RefCell<ReplayerState>>);
+
+struct ReplayerState {
+    /// The editor whose selections the macro is replayed at.
+    editor: WeakEntity<Editor>,
+    steps: Vec<ReplayStep>,
+    ix: usize,
+    collected_selections: Vec<Range<Anchor>>,
+}
+
+impl Replayer {
+    fn new(editor: WeakEntity<Editor>, steps: Vec<ReplayStep>) -> Self {
+        Self(Rc::new(RefCell::new(ReplayerState {
+            editor,
+            steps,
+            ix: 0,
+            collected_selections: Vec::new(),
+        })))
+    }
+
+    fn insert(&self, steps: Vec<ReplayStep>) {
+        let mut state = self.0.borrow_mut();
+        let ix = state.ix;
+        state.steps.splice(ix..ix, steps);
+    }
+
+    fn next(self, window: &mut Window, cx: &mut App) {
+        let mut state = self.0.borrow_mut();
+        let step = if state.ix < MAX_REPLAY_STEPS {
+            state.steps.get(state.ix).cloned()
+        } else {
+            log::error!("Aborting keyboard macro after {MAX_REPLAY_STEPS} steps");
+            None
+        };
+        state.ix += 1;
+        let editor = state.editor.upgrade();
+        drop(state);
+        let (Some(step), Some(editor)) = (step, editor) else {
+            cx.global_mut::<KeyboardMacros>().replayer.take();
+            return;
+        };
+
+        match step {
+            ReplayStep::Event(MacroEvent::Action { action, .. }) => {
+                window.dispatch_action(action, cx);
+            }
+            ReplayStep::Event(MacroEvent::Text {
+                text,
+                utf16_range_to_replace,
+            }) => {
+                // Text goes wherever the macro moved focus to, if it's an editor.
+                let editor = cx
+                    .global::<KeyboardMacros>()
+                    .focused_editor
+                    .as_ref()
+                    .and_then(|editor| editor.upgrade())
+                    .filter(|editor| editor.focus_handle(cx).contains_focused(window, cx))
+                    .unwrap_or(editor);
+                editor.update(cx, |editor, cx| {
+                    editor.replay_insert_event(&text, utf16_range_to_replace, window, cx)
+                });
+            }
+            ReplayStep::Event(MacroEvent::Keystrokes(keystrokes)) => {
+                for keystroke in keystrokes {
+                    window.dispatch_keystroke(keystroke, cx);
+                }
+            }
+            ReplayStep::Select(range) => {
+                editor.update(cx, |editor, cx| {
+                    editor.change_selections(None, window, cx, |selections| {
+                        selections.select_anchor_ranges([range])
+                    })
+                });
+            }
+            ReplayStep::CollectSelections => {
+                let ranges = editor.read(cx).selections.disjoint_anchor_ranges();
+                self.0.borrow_mut().collected_selections.extend(ranges);
+            }
+            ReplayStep::SelectCollected => {
+                let ranges = mem::take(&mut self.0.borrow_mut().collected_selections);
+                if !ranges.is_empty() {
+                    editor.update(cx, |editor, cx| {
+                        editor.change_selections(None, window, cx, |selections| {
+                            selections.select_anchor_ranges(ranges)
+                        })
+                    });
+                }
+            }
+        }
+        window.defer(cx, move |window, cx| self.next(window, cx));
+    }
+}
+
+/// Converts a recorded macro to steps that can be saved in settings, and
+/// counts the actions that can't be.
+fn macro_steps(events: &[MacroEvent], cx: &App) -> (Vec<MacroStep>, usize) {
+    let mut unsaved_actions = 0;
+    let steps = events
+        .iter()
+        .filter_map(|event| match event {
+            MacroEvent::Action { action, keystrokes } => {
+                let step = action_step(action.as_ref(), keystrokes.as_deref(), cx);
+                if step.is_none() {
+                    unsaved_actions += 1;
+                }
+                step
+            }
+            // The range to replace is only set by input methods composing
+            // text, which is replayed as the text they composed.
+            MacroEvent::Text { text, .. } => Some(MacroStep::Text {
+                text: text.to_string(),
+            }),
+            MacroEvent::Keystrokes(keystrokes) => Some(MacroStep::Keystrokes {
+                keystrokes: unparse_keystrokes(keystrokes),
+            }),
+        })
+        .collect();
+    (steps, unsaved_actions)
+}
+
+fn action_step(action: &dyn Action, keystrokes: Option<&str>, cx: &App) -> Option<MacroStep> {
+    let name = action.name();
+    if let Some(run_macro) = action.as_any().downcast_ref::<RunMacro>() {
+        let arguments = serde_json::to_value(run_macro).log_err()?;
+        return Some(MacroStep::ActionWithArguments(name.to_string(), arguments));
+    }
+    // Actions can't be serialized, but those without arguments are the same
+    // as the action built from their name.
+    if cx
+        .build_action(name, None)
+        .is_ok_and(|built| built.partial_eq(action))
+    {
+        return Some(MacroStep::Action(name.to_string()));
+    }
+    keystrokes.map(|keystrokes| MacroStep::Keystrokes {
+        keystrokes: keystrokes.to_string(),
+    })
+}
+
+/// Prompts for the name to save the last recorded macro as.
+pub struct MacroNameModal {
+    name_editor: Entity<Editor>,
+    workspace: WeakEntity<Workspace>,
+    fs: Arc<dyn Fs>,
+    _subscription: Subscription,
+}
+
+impl ModalView for MacroNameModal {}
+
+impl Focusable for MacroNameModal {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.name_editor.focus_handle(cx)
+    }
+}
+
+impl EventEmitter<DismissEvent> for MacroNameModal {}
+
+impl MacroNameModal {
+    fn register(
+        workspace: &mut Workspace,
+        _window: Option<&mut Window>,
+        _: &mut Context<Workspace>,
+    ) {
+        workspace.register_action(|workspace, _: &SaveLastMacro, window, cx| {
+            if cx.global::<KeyboardMacros>().last_macro.is_none() {
+                struct NoMacroToSave;
+
+                workspace.show_toast(
+                    Toast::new(
+                        NotificationId::unique::<NoMacroToSave>(),
+                        "No keyboard macro has been recorded",
+                    )
+                    .autohide(),
+                    cx,
+                );
+                return;
+            }
+            let fs = workspace.app_state().fs.clone();
+            let handle = cx.entity().downgrade();
+            workspace.toggle_modal(window, cx, move |window, cx| {
+                MacroNameModal::new(handle, fs, window, cx)
+            });
+        });
+    }
+
+    fn new(
+        workspace: WeakEntity<Workspace>,
+        fs: Arc<dyn Fs>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let name_editor = cx.new(|cx| {
+            let mut editor = Editor::single_line(window, cx);
+            editor.set_placeholder_text("Macro name", cx);
+            editor
+        });
+        let _subscription = cx.subscribe_in(&name_editor, window, Self::on_name_editor_event);
+        Self {
+            name_editor,
+            workspace,
+            fs,
+            _subscription,
+        }
+    }
+
+    fn on_name_editor_event(
+        &mut self,
+        _: &Entity<Editor>,
+        event: &EditorEvent,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if let EditorEvent::Blurred = event {
+            cx.emit(DismissEvent);
+        }
+    }
+
+    fn cancel(&mut self, _: &menu::Cancel, _: &mut Window, cx: &mut Context<Self>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn confirm(&mut self, _: &menu::Confirm, _: &mut Window, cx: &mut Context<Self>) {
+        let name = self.name_editor.read(cx).text(cx).trim().to_string();
+        if name.is_empty() {
+            return;
+        }
+        let Some(events) = cx.global::<KeyboardMacros>().last_macro.clone() else {
+            return;
+        };
+
+        let (steps, unsaved_actions) = macro_steps(&events, cx);
+        update_settings_file::<KeyboardMacroSettings>(self.fs.clone(), cx, move |settings, _| {
+            settings.0.insert(name, steps);
+        });
+        if unsaved_actions > 0 {
+            self.workspace
+                .update(cx, |workspace, cx| {
+                    struct UnsavedMacroActions;
+
+                    workspace.show_toast(
+                        Toast::new(
+                            NotificationId::unique::<UnsavedMacroActions>(),
+                            format!(
+                                "Couldn't save {unsaved_actions} action(s) with arguments that weren't dispatched by a key binding"
+                            ),
+                        ),
+                        cx,
+                    )
+                })
+                .log_err();
+        }
+        cx.emit(DismissEvent);
+    }
+}
+
+impl Render for MacroNameModal {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .w(rems(24.))
+            .elevation_2(cx)
+            .key_context("MacroName")
+            .on_action(cx.listener(Self::cancel))
+            .on_action(cx.listener(Self::confirm))
+            .child(
+                div()
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border_variant)
+                    .px_2()
+                    .py_1()
+                    .child(self.name_editor.clone()),
+            )
+            .child(
+                h_flex().px_2().py_1().child(
+                    Label::new(
+                        "Save the last recorded macro to run with keyboard_macros::RunMacro",
+                    )
+                    .color(Color::Muted),
+                ),
+            )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use editor::{
+        actions::{MoveDown, MoveToBeginningOfLine},
+        test::editor_test_context::EditorTestContext,
+    };
+    use gpui::{KeyBinding, TestAppContext};
+    use indoc::indoc;
+    use project::Project;
+    use settings::SettingsStore;
+
+    fn init_test(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let settings = SettingsStore::test(cx);
+            cx.set_global(settings);
+            theme::init(theme::LoadThemes::JustBase, cx);
+            language::init(cx);
+            workspace::init_settings(cx);
+            Project::init_settings(cx);
+            editor::init(cx);
+            crate::init(cx);
+            cx.bind_keys([
+                KeyBinding::new("f1", ToggleRecording, None),
+                KeyBinding::new("f2", ReplayLastMacro::default(), None),
+                KeyBinding::new("down", MoveDown, Some("Editor")),
+                KeyBinding::new(
+                    "home",
+                    MoveToBeginningOfLine {
+                        stop_at_soft_wraps: true,
+                        stop_at_indent: true,
+                    },
+                    Some("Editor"),
+                ),
+            ]);
+        });
+    }
+
+    #[gpui::test]
+    async fn test_record_and_replay(cx: &mut TestAppContext) {
+        init_test(cx);
+        let mut cx = EditorTestContext::new(cx).await;
+
+        cx.set_state(indoc! {"
+            ˇone
+            two
+            three
+            four
+            five"});
+        cx.simulate_keystrokes("f1 x home down f1");
+        cx.assert_editor_state(indoc! {"
+            xone
+            ˇtwo
+            three
+            four
+            five"});
+
+        cx.simulate_keystrokes("f2");
+        cx.assert_editor_state(indoc! {"
+            xone
+            xtwo
+            ˇthree
+            four
+            five"});
+
+        cx.dispatch_action(ReplayLastMacro {
+            count: Some(2),
+            each_cursor: false,
+        });
+        cx.run_until_parked();
+        cx.assert_editor_state(indoc! {"
+            xone
+            xtwo
+            xthree
+            xfour
+            ˇfive"});
+
+        // Actions with arguments are saved as the keystrokes that dispatched them.
+        let (steps, unsaved_actions) = cx.update(|_, cx| {
+            let events = cx.global::<KeyboardMacros>().last_macro.clone().unwrap();
+            macro_steps(&events, cx)
+        });
+        assert_eq!(unsaved_actions, 0);
+        assert_eq!(
+            steps,
+            [
+                MacroStep::Text { text: "x".into() },
+                MacroStep::Keystrokes {
+                    keystrokes: "home".into()
+                },
+                MacroStep::Action("editor::MoveDown".into()),
+            ]
+        );
+    }
+
+    #[gpui::test]
+    async fn test_replay_on_each_cursor(cx: &mut TestAppContext) {
+        init_test(cx);
+        let mut cx = EditorTestContext::new(cx).await;
+
+        cx.set_state("ˇa b c");
+        cx.simulate_keystrokes("f1 z f1");
+        cx.assert_editor_state("zˇa b c");
+
+        cx.set_state("ˇa ˇb ˇc");
+        cx.dispatch_action(ReplayLastMacroOnEachCursor);
+        cx.run_until_parked();
+        cx.assert_editor_state("zˇa zˇb zˇc");
+    }
+
+    #[gpui::test]
+    async fn test_run_saved_macro(cx: &mut TestAppContext) {
+        init_test(cx);
+        let mut cx = EditorTestContext::new(cx).await;
+        cx.update(|_, cx| {
+            cx.update_global(|store: &mut SettingsStore, cx| {
+                store.update_user_settings::<KeyboardMacroSettings>(cx, |settings| {
+                    settings.0.insert(
+                        "comment".into(),
+                        vec![
+                            MacroStep::Text { text: "// ".into() },
+                            MacroStep::Action("editor::MoveDown".into()),
+                            MacroStep::ActionWithArguments(
+                                "editor::MoveToBeginningOfLine".into(),
+                                serde_json::json!({ "stop_at_indent": true }),
+                            ),
+                        ],
+                    );
+                });
+            });
+        });
+
+        cx.set_state(indoc! {"
+            ˇone
+            two
+            three"});
+        cx.dispatch_action(RunMacro {
+            name: "comment".into(),
+            count: Some(2),
+            each_cursor: false,
+        });
+        cx.run_until_parked();
+        cx.assert_editor_state(indoc! {"
+            // one
+            // two
+            ˇthree"});
+    }
+}
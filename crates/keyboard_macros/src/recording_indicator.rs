@@ -0,0 +1,50 @@
+use gpui::{Context, Render, Subscription, Window};
+use ui::{Tooltip, prelude::*};
+use workspace::{StatusItemView, item::ItemHandle};
+
+use crate::{KeyboardMacros, ToggleRecording};
+
+/// Shows in the status bar while a keyboard macro is being recorded.
+pub struct RecordingIndicator {
+    _observe_macros: Subscription,
+}
+
+impl RecordingIndicator {
+    pub fn new(cx: &mut Context<Self>) -> Self {
+        Self {
+            _observe_macros: cx.observe_global::<KeyboardMacros>(|_, cx| cx.notify()),
+        }
+    }
+}
+
+impl Render for RecordingIndicator {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        if !KeyboardMacros::is_recording(cx) {
+            return div().into_any_element();
+        }
+
+        h_flex()
+            .id("keyboard-macro-recording")
+            .gap_1()
+            .child(
+                Icon::new(IconName::Circle)
+                    .size(IconSize::XSmall)
+                    .color(Color::Error),
+            )
+            .child(Label::new("Recording macro").size(LabelSize::Small))
+            .tooltip(|window, cx| {
+                Tooltip::for_action("Stop Recording Macro", &ToggleRecording, window, cx)
+            })
+            .into_any_element()
+    }
+}
+
+impl StatusItemView for RecordingIndicator {
+    fn set_active_pane_item(
+        &mut self,
+        _: Option<&dyn ItemHandle>,
+        _: &mut Window,
+        _: &mut Context<Self>,
+    ) {
+    }
+}
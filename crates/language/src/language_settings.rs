@@ -215,6 +215,7 @@ pub enum EditPredictionProvider {
     Copilot,
     Supermaven,
     Zed,
+    LanguageModel,
 }
 
 impl EditPredictionProvider {
@@ -223,7 +224,8 @@ impl EditPredictionProvider {
             EditPredictionProvider::Zed => true,
             EditPredictionProvider::None
             | EditPredictionProvider::Copilot
-            | EditPredictionProvider::Supermaven => false,
+            | EditPredictionProvider::Supermaven
+            | EditPredictionProvider::LanguageModel => false,
         }
     }
 }
@@ -242,6 +244,8 @@ pub struct EditPredictionSettings {
     pub mode: EditPredictionsMode,
     /// Settings specific to GitHub Copilot.
     pub copilot: CopilotSettings,
+    /// Settings specific to the `language_model` provider.
+    pub language_model: LanguageModelEditPredictionSettings,
     /// Whether edit predictions are enabled in the assistant panel.
     /// This setting has no effect if globally disabled.
     pub enabled_in_text_threads: bool,
@@ -289,6 +293,29 @@ pub struct CopilotSettings {
     pub proxy_no_verify: Option<bool>,
 }
 
+#[derive(Clone, Debug, Default)]
+pub struct LanguageModelEditPredictionSettings {
+    /// The id of the language model provider to request predictions from.
+    pub provider: Option<String>,
+    /// The id of the model to request predictions from.
+    pub model: Option<String>,
+    /// How the text around the cursor is presented to the model.
+    pub prompt_format: EditPredictionPromptFormat,
+}
+
+/// How the `language_model` edit prediction provider prompts the model.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum EditPredictionPromptFormat {
+    /// Send recent edits and an excerpt around the cursor, and ask the model to
+    /// rewrite the editable part of the excerpt, like Zed's hosted model does.
+    #[default]
+    Zeta,
+    /// Send the text before and after the cursor, and ask the model to fill in
+    /// what goes in between.
+    Fim,
+}
+
 /// The settings for all languages.
 #[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct AllLanguageSettingsContent {
@@ -588,6 +615,9 @@ pub struct EditPredictionSettingsContent {
     /// Settings specific to GitHub Copilot.
     #[serde(default)]
     pub copilot: CopilotSettingsContent,
+    /// Settings specific to the `language_model` provider.
+    #[serde(default)]
+    pub language_model: LanguageModelEditPredictionSettingsContent,
     /// Whether edit predictions are enabled in the assistant prompt editor.
     /// This has no effect if globally disabled.
     #[serde(default = "default_true")]
@@ -608,6 +638,27 @@ pub struct CopilotSettingsContent {
     pub proxy_no_verify: Option<bool>,
 }
 
+#[derive(Clone, Debug, Default, Serialize, Deserialize, JsonSchema, PartialEq)]
+pub struct LanguageModelEditPredictionSettingsContent {
+    /// The id of the language model provider to request predictions from,
+    /// such as "ollama" or "lmstudio".
+    ///
+    /// Default: the provider of the default agent model
+    #[serde(default)]
+    pub provider: Option<String>,
+    /// The id of the model to request predictions from. When unset, the
+    /// provider's default model is used.
+    ///
+    /// Default: none
+    #[serde(default)]
+    pub model: Option<String>,
+    /// How the text around the cursor is presented to the model.
+    ///
+    /// Default: zeta
+    #[serde(default)]
+    pub prompt_format: Option<EditPredictionPromptFormat>,
+}
+
 /// The settings for enabling/disabling features.
 #[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "snake_case")]
@@ -1223,6 +1274,17 @@ impl settings::Settings for AllLanguageSettings {
             })
             .unwrap_or_default();
 
+        let mut language_model_settings = default_value
+            .edit_predictions
+            .as_ref()
+            .map(|settings| settings.language_model.clone())
+            .map(|language_model| LanguageModelEditPredictionSettings {
+                provider: language_model.provider,
+                model: language_model.model,
+                prompt_format: language_model.prompt_format.unwrap_or_default(),
+            })
+            .unwrap_or_default();
+
         let mut enabled_in_text_threads = default_value
             .edit_predictions
             .as_ref()
@@ -1275,6 +1337,22 @@ impl settings::Settings for AllLanguageSettings {
                 copilot_settings.proxy_no_verify = Some(proxy_no_verify);
             }
 
+            if let Some(language_model) = user_settings
+                .edit_predictions
+                .as_ref()
+                .map(|settings| &settings.language_model)
+            {
+                if let Some(provider) = language_model.provider.clone() {
+                    language_model_settings.provider = Some(provider);
+                }
+                if let Some(model) = language_model.model.clone() {
+                    language_model_settings.model = Some(model);
+                }
+                if let Some(prompt_format) = language_model.prompt_format {
+                    language_model_settings.prompt_format = prompt_format;
+                }
+            }
+
             // A user's global settings override the default global settings and
             // all default language-specific settings.
             merge_settings(&mut defaults, &user_settings.defaults);
@@ -1330,6 +1408,7 @@ impl settings::Settings for AllLanguageSettings {
                     .collect(),
                 mode: edit_predictions_mode,
                 copilot: copilot_settings,
+                language_model: language_model_settings,
                 enabled_in_text_threads,
             },
             defaults,
@@ -91,7 +91,12 @@ pub struct ToolUseRequest {
 
 #[derive(Default)]
 pub struct FakeLanguageModel {
-    current_completion_txs: Mutex<Vec<(LanguageModelRequest, mpsc::UnboundedSender<String>)>>,
+    current_completion_txs: Mutex<
+        Vec<(
+            LanguageModelRequest,
+            mpsc::UnboundedSender<LanguageModelCompletionEvent>,
+        )>,
+    >,
 }
 
 impl FakeLanguageModel {
@@ -108,13 +113,22 @@ impl FakeLanguageModel {
     }
 
     pub fn stream_completion_response(&self, request: &LanguageModelRequest, chunk: String) {
+        self.send_completion_stream_event(request, LanguageModelCompletionEvent::Text(chunk));
+    }
+
+    /// Sends any event of a completion, such as a tool use or the reason it stopped.
+    pub fn send_completion_stream_event(
+        &self,
+        request: &LanguageModelRequest,
+        event: LanguageModelCompletionEvent,
+    ) {
         let current_completion_txs = self.current_completion_txs.lock();
         let tx = current_completion_txs
             .iter()
             .find(|(req, _)| req == request)
             .map(|(_, tx)| tx)
             .unwrap();
-        tx.unbounded_send(chunk).unwrap();
+        tx.unbounded_send(event).unwrap();
     }
 
     pub fn end_completion_stream(&self, request: &LanguageModelRequest) {
@@ -127,6 +141,10 @@ impl FakeLanguageModel {
         self.stream_completion_response(self.pending_completions().last().unwrap(), chunk);
     }
 
+    pub fn send_last_completion_stream_event(&self, event: LanguageModelCompletionEvent) {
+        self.send_completion_stream_event(self.pending_completions().last().unwrap(), event);
+    }
+
     pub fn end_last_completion_stream(&self) {
         self.end_completion_stream(self.pending_completions().last().unwrap());
     }
@@ -185,12 +203,7 @@ impl LanguageModel for FakeLanguageModel {
     > {
         let (tx, rx) = mpsc::unbounded();
         self.current_completion_txs.lock().push((request, tx));
-        async move {
-            Ok(rx
-                .map(|text| Ok(LanguageModelCompletionEvent::Text(text)))
-                .boxed())
-        }
-        .boxed()
+        async move { Ok(rx.map(Ok).boxed()) }.boxed()
     }
 
     fn as_fake(&self) -> &Self {
@@ -27,6 +27,7 @@ pub fn init(user_store: Entity<UserStore>, client: Arc<Client>, fs: Arc<dyn Fs>,
     registry.update(cx, |registry, cx| {
         register_language_model_providers(registry, user_store, client, cx);
     });
+    provider::extension::init(cx);
 }
 
 fn register_language_model_providers(
@@ -0,0 +1,936 @@
+use std::sync::Arc;
+
+use anyhow::{Result, anyhow};
+use collections::HashSet;
+use extension::{Extension, ExtensionHostProxy, ExtensionLanguageModelProviderProxy};
+use futures::{
+    FutureExt, StreamExt,
+    future::{BoxFuture, Shared},
+    stream::BoxStream,
+};
+use gpui::{AnyView, App, AsyncApp, Context, Entity, Task};
+use language_model::{
+    AuthenticateError, LanguageModel, LanguageModelCompletionError, LanguageModelCompletionEvent,
+    LanguageModelId, LanguageModelName, LanguageModelProvider, LanguageModelProviderId,
+    LanguageModelProviderName, LanguageModelProviderState, LanguageModelRegistry,
+    LanguageModelRequest, LanguageModelToolChoice, LanguageModelToolResultContent,
+    LanguageModelToolUse, MessageContent, RateLimiter, Role, StopReason, TokenUsage,
+};
+use parking_lot::Mutex;
+use ui::{ButtonLike, Indicator, prelude::*};
+use util::ResultExt;
+
+pub fn init(cx: &mut App) {
+    let proxy = ExtensionHostProxy::default_global(cx);
+    proxy.register_language_model_provider_proxy(LanguageModelRegistryProxy {
+        language_model_registry: LanguageModelRegistry::global(cx),
+        registered_provider_ids: Mutex::default(),
+    });
+}
+
+struct LanguageModelRegistryProxy {
+    language_model_registry: Entity<LanguageModelRegistry>,
+    /// The providers registered by extensions, which are the only ones extensions may unregister.
+    registered_provider_ids: Mutex<HashSet<Arc<str>>>,
+}
+
+impl ExtensionLanguageModelProviderProxy for LanguageModelRegistryProxy {
+    fn register_language_model_provider(
+        &self,
+        extension: Arc<dyn Extension>,
+        provider_id: Arc<str>,
+        provider: extension::LanguageModelProviderManifestEntry,
+        cx: &mut App,
+    ) {
+        let id = LanguageModelProviderId(provider_id.to_string().into());
+        if LanguageModelRegistry::read_global(cx)
+            .provider(&id)
+            .is_some()
+        {
+            log::error!(
+                "extension {} cannot register language model provider {provider_id}, as it is already registered",
+                extension.manifest().id
+            );
+            return;
+        }
+
+        let provider =
+            ExtensionLanguageModelProvider::new(extension, provider_id.clone(), provider, cx);
+        self.language_model_registry.update(cx, |registry, cx| {
+            registry.register_provider(provider, cx);
+        });
+        self.registered_provider_ids.lock().insert(provider_id);
+    }
+
+    fn unregister_language_model_provider(&self, provider_id: Arc<str>, cx: &mut App) {
+        if !self.registered_provider_ids.lock().remove(&provider_id) {
+            return;
+        }
+
+        self.language_model_registry.update(cx, |registry, cx| {
+            registry
+                .unregister_provider(LanguageModelProviderId(provider_id.to_string().into()), cx);
+        });
+    }
+}
+
+/// A language model provider implemented by an extension.
+pub struct ExtensionLanguageModelProvider {
+    id: LanguageModelProviderId,
+    name: LanguageModelProviderName,
+    state: Entity<State>,
+}
+
+pub struct State {
+    extension: Arc<dyn Extension>,
+    provider_id: Arc<str>,
+    models: Vec<extension::LanguageModelInfo>,
+    is_authenticated: bool,
+    authentication_error: Option<SharedString>,
+    pending_authentication: Option<Shared<Task<Result<(), SharedString>>>>,
+}
+
+impl State {
+    fn authenticate(&mut self, cx: &mut Context<Self>) -> Task<Result<(), AuthenticateError>> {
+        if self.is_authenticated {
+            return Task::ready(Ok(()));
+        }
+
+        // Reuse the authentication in flight, so that the extension isn't asked to
+        // authenticate again while it's still doing so.
+        let authentication = match self.pending_authentication.clone() {
+            Some(authentication) => authentication,
+            None => {
+                let authentication = self.start_authentication(cx).shared();
+                self.pending_authentication = Some(authentication.clone());
+                authentication
+            }
+        };
+        cx.background_spawn(async move {
+            authentication
+                .await
+                .map_err(|error| AuthenticateError::Other(anyhow!(error)))
+        })
+    }
+
+    fn start_authentication(&mut self, cx: &mut Context<Self>) -> Task<Result<(), SharedString>> {
+        let extension = self.extension.clone();
+        let provider_id = self.provider_id.clone();
+        cx.spawn(async move |this, cx| {
+            let models = async {
+                extension
+                    .language_model_provider_authenticate(provider_id.clone())
+                    .await?;
+                extension.language_model_provider_models(provider_id).await
+            }
+            .await
+            .map_err(|error| SharedString::from(error.to_string()));
+
+            this.update(cx, |this, cx| {
+                this.pending_authentication = None;
+                match &models {
+                    Ok(models) => {
+                        this.models = models.clone();
+                        this.is_authenticated = true;
+                        this.authentication_error = None;
+                    }
+                    Err(error) => {
+                        this.authentication_error = Some(error.clone());
+                    }
+                }
+                cx.notify();
+            })
+            .map_err(|error| SharedString::from(error.to_string()))?;
+
+            models.map(|_| ())
+        })
+    }
+
+    fn reset(&mut self, cx: &mut Context<Self>) {
+        self.pending_authentication = None;
+        self.models.clear();
+        self.is_authenticated = false;
+        self.authentication_error = None;
+        cx.notify();
+    }
+}
+
+impl ExtensionLanguageModelProvider {
+    pub fn new(
+        extension: Arc<dyn Extension>,
+        provider_id: Arc<str>,
+        provider: extension::LanguageModelProviderManifestEntry,
+        cx: &mut App,
+    ) -> Self {
+        let this = Self {
+            id: LanguageModelProviderId(provider_id.to_string().into()),
+            name: LanguageModelProviderName(provider.name.into()),
+            state: cx.new(|_| State {
+                extension,
+                provider_id,
+                models: Vec::new(),
+                is_authenticated: false,
+                authentication_error: None,
+                pending_authentication: None,
+            }),
+        };
+        this.state
+            .update(cx, |state, cx| state.authenticate(cx))
+            .detach();
+        this
+    }
+
+    fn create_language_model(
+        &self,
+        model: &extension::LanguageModelInfo,
+        cx: &App,
+    ) -> Arc<dyn LanguageModel> {
+        let state = self.state.read(cx);
+        Arc::new(ExtensionLanguageModel {
+            id: LanguageModelId::from(model.id.clone()),
+            model: model.clone(),
+            provider_id: self.id.clone(),
+            provider_name: self.name.clone(),
+            extension: state.extension.clone(),
+            extension_provider_id: state.provider_id.clone(),
+            request_limiter: RateLimiter::new(4),
+        })
+    }
+}
+
+impl LanguageModelProviderState for ExtensionLanguageModelProvider {
+    type ObservableEntity = State;
+
+    fn observable_entity(&self) -> Option<Entity<Self::ObservableEntity>> {
+        Some(self.state.clone())
+    }
+}
+
+impl LanguageModelProvider for ExtensionLanguageModelProvider {
+    fn id(&self) -> LanguageModelProviderId {
+        self.id.clone()
+    }
+
+    fn name(&self) -> LanguageModelProviderName {
+        self.name.clone()
+    }
+
+    fn icon(&self) -> IconName {
+        IconName::Blocks
+    }
+
+    fn default_model(&self, cx: &App) -> Option<Arc<dyn LanguageModel>> {
+        let models = &self.state.read(cx).models;
+        let model = models
+            .iter()
+            .find(|model| model.is_default)
+            .or_else(|| models.first())?;
+        Some(self.create_language_model(model, cx))
+    }
+
+    fn default_fast_model(&self, cx: &App) -> Option<Arc<dyn LanguageModel>> {
+        let model = self
+            .state
+            .read(cx)
+            .models
+            .iter()
+            .find(|model| model.is_default_fast)?;
+        Some(self.create_language_model(model, cx))
+    }
+
+    fn provided_models(&self, cx: &App) -> Vec<Arc<dyn LanguageModel>> {
+        self.state
+            .read(cx)
+            .models
+            .iter()
+            .map(|model| self.create_language_model(model, cx))
+            .collect()
+    }
+
+    fn is_authenticated(&self, cx: &App) -> bool {
+        self.state.read(cx).is_authenticated
+    }
+
+    fn authenticate(&self, cx: &mut App) -> Task<Result<(), AuthenticateError>> {
+        self.state.update(cx, |state, cx| state.authenticate(cx))
+    }
+
+    fn configuration_view(&self, _window: &mut Window, cx: &mut App) -> AnyView {
+        let state = self.state.clone();
+        cx.new(|cx| ConfigurationView::new(state, cx)).into()
+    }
+
+    fn reset_credentials(&self, cx: &mut App) -> Task<Result<()>> {
+        self.state.update(cx, |state, cx| state.reset(cx));
+        Task::ready(Ok(()))
+    }
+}
+
+pub struct ExtensionLanguageModel {
+    id: LanguageModelId,
+    model: extension::LanguageModelInfo,
+    provider_id: LanguageModelProviderId,
+    provider_name: LanguageModelProviderName,
+    extension: Arc<dyn Extension>,
+    extension_provider_id: Arc<str>,
+    request_limiter: RateLimiter,
+}
+
+impl LanguageModel for ExtensionLanguageModel {
+    fn id(&self) -> LanguageModelId {
+        self.id.clone()
+    }
+
+    fn name(&self) -> LanguageModelName {
+        LanguageModelName::from(self.model.name.clone())
+    }
+
+    fn provider_id(&self) -> LanguageModelProviderId {
+        self.provider_id.clone()
+    }
+
+    fn provider_name(&self) -> LanguageModelProviderName {
+        self.provider_name.clone()
+    }
+
+    fn supports_tools(&self) -> bool {
+        self.model.supports_tools
+    }
+
+    fn supports_images(&self) -> bool {
+        self.model.supports_images
+    }
+
+    fn supports_tool_choice(&self, _choice: LanguageModelToolChoice) -> bool {
+        self.model.supports_tools
+    }
+
+    fn telemetry_id(&self) -> String {
+        format!("{}/{}", self.provider_id.0, self.model.id)
+    }
+
+    fn max_token_count(&self) -> usize {
+        self.model.max_token_count
+    }
+
+    fn max_output_tokens(&self) -> Option<u32> {
+        self.model.max_output_tokens
+    }
+
+    fn count_tokens(
+        &self,
+        request: LanguageModelRequest,
+        cx: &App,
+    ) -> BoxFuture<'static, Result<usize>> {
+        cx.background_spawn(async move {
+            let messages = request
+                .messages
+                .into_iter()
+                .map(|message| tiktoken_rs::ChatCompletionRequestMessage {
+                    role: match message.role {
+                        Role::User => "user".into(),
+                        Role::Assistant => "assistant".into(),
+                        Role::System => "system".into(),
+                    },
+                    content: Some(message.string_contents()),
+                    name: None,
+                    function_call: None,
+                })
+                .collect::<Vec<_>>();
+
+            tiktoken_rs::num_tokens_from_messages("gpt-4", &messages)
+        })
+        .boxed()
+    }
+
+    fn stream_completion(
+        &self,
+        request: LanguageModelRequest,
+        _cx: &AsyncApp,
+    ) -> BoxFuture<
+        'static,
+        Result<
+            BoxStream<'static, Result<LanguageModelCompletionEvent, LanguageModelCompletionError>>,
+        >,
+    > {
+        let extension = self.extension.clone();
+        let provider_id = self.extension_provider_id.clone();
+        let model_id: Arc<str> = self.model.id.as_str().into();
+        let request = into_extension_request(request);
+
+        let future = self.request_limiter.stream(async move {
+            extension
+                .stream_language_model_completion(provider_id, model_id, request)
+                .await
+        });
+
+        async move {
+            Ok(future
+                .await?
+                .map(|event| {
+                    event
+                        .map_err(LanguageModelCompletionError::Other)
+                        .and_then(from_extension_event)
+                })
+                .boxed())
+        }
+        .boxed()
+    }
+}
+
+fn into_extension_request(
+    request: LanguageModelRequest,
+) -> extension::LanguageModelCompletionRequest {
+    extension::LanguageModelCompletionRequest {
+        thread_id: request.thread_id,
+        messages: request
+            .messages
+            .into_iter()
+            .map(|message| extension::LanguageModelRequestMessage {
+                role: match message.role {
+                    Role::User => extension::LanguageModelMessageRole::User,
+                    Role::Assistant => extension::LanguageModelMessageRole::Assistant,
+                    Role::System => extension::LanguageModelMessageRole::System,
+                },
+                content: message
+                    .content
+                    .into_iter()
+                    .map(into_extension_message_content)
+                    .collect(),
+                cache: message.cache,
+            })
+            .collect(),
+        tools: request
+            .tools
+            .into_iter()
+            .map(|tool| extension::LanguageModelToolDefinition {
+                name: tool.name,
+                description: tool.description,
+                input_schema: tool.input_schema,
+            })
+            .collect(),
+        tool_choice: request.tool_choice.map(|choice| match choice {
+            LanguageModelToolChoice::Auto => extension::LanguageModelToolChoice::Auto,
+            LanguageModelToolChoice::Any => extension::LanguageModelToolChoice::Any,
+            LanguageModelToolChoice::None => extension::LanguageModelToolChoice::None,
+        }),
+        stop: request.stop,
+        temperature: request.temperature,
+    }
+}
+
+fn into_extension_message_content(
+    content: MessageContent,
+) -> extension::LanguageModelMessageContent {
+    match content {
+        MessageContent::Text(text) => extension::LanguageModelMessageContent::Text(text),
+        MessageContent::Thinking { text, signature } => {
+            extension::LanguageModelMessageContent::Thinking { text, signature }
+        }
+        MessageContent::RedactedThinking(data) => {
+            extension::LanguageModelMessageContent::RedactedThinking(data)
+        }
+        MessageContent::Image(image) => {
+            extension::LanguageModelMessageContent::Image(extension::LanguageModelImage {
+                source: image.source.to_string(),
+            })
+        }
+        MessageContent::ToolUse(tool_use) => {
+            extension::LanguageModelMessageContent::ToolUse(extension::LanguageModelToolUse {
+                id: tool_use.id.to_string(),
+                name: tool_use.name.to_string(),
+                input: tool_use.input.to_string(),
+            })
+        }
+        MessageContent::ToolResult(tool_result) => {
+            extension::LanguageModelMessageContent::ToolResult(extension::LanguageModelToolResult {
+                tool_use_id: tool_result.tool_use_id.to_string(),
+                tool_name: tool_result.tool_name.to_string(),
+                is_error: tool_result.is_error,
+                content: match tool_result.content {
+                    LanguageModelToolResultContent::Text(text) => {
+                        extension::LanguageModelToolResultContent::Text(text.to_string())
+                    }
+                    LanguageModelToolResultContent::Image(image) => {
+                        extension::LanguageModelToolResultContent::Image(
+                            extension::LanguageModelImage {
+                                source: image.source.to_string(),
+                            },
+                        )
+                    }
+                },
+            })
+        }
+    }
+}
+
+fn from_extension_event(
+    event: extension::LanguageModelCompletionEvent,
+) -> Result<LanguageModelCompletionEvent, LanguageModelCompletionError> {
+    Ok(match event {
+        extension::LanguageModelCompletionEvent::StartMessage { message_id } => {
+            LanguageModelCompletionEvent::StartMessage { message_id }
+        }
+        extension::LanguageModelCompletionEvent::Text(text) => {
+            LanguageModelCompletionEvent::Text(text)
+        }
+        extension::LanguageModelCompletionEvent::Thinking { text, signature } => {
+            LanguageModelCompletionEvent::Thinking { text, signature }
+        }
+        extension::LanguageModelCompletionEvent::ToolUse(tool_use) => {
+            let input = serde_json::from_str(&tool_use.input).map_err(|error| {
+                LanguageModelCompletionError::BadInputJson {
+                    id: tool_use.id.clone().into(),
+                    tool_name: tool_use.name.clone().into(),
+                    raw_input: tool_use.input.clone().into(),
+                    json_parse_error: error.to_string(),
+                }
+            })?;
+            LanguageModelCompletionEvent::ToolUse(LanguageModelToolUse {
+                id: tool_use.id.into(),
+                name: tool_use.name.into(),
+                raw_input: tool_use.input,
+                input,
+                is_input_complete: true,
+            })
+        }
+        extension::LanguageModelCompletionEvent::Stop(reason) => {
+            LanguageModelCompletionEvent::Stop(match reason {
+                extension::LanguageModelStopReason::EndTurn => StopReason::EndTurn,
+                extension::LanguageModelStopReason::MaxTokens => StopReason::MaxTokens,
+                extension::LanguageModelStopReason::ToolUse => StopReason::ToolUse,
+            })
+        }
+        extension::LanguageModelCompletionEvent::UsageUpdate(usage) => {
+            LanguageModelCompletionEvent::UsageUpdate(TokenUsage {
+                input_tokens: usage.input_tokens,
+                output_tokens: usage.output_tokens,
+                cache_creation_input_tokens: usage.cache_creation_input_tokens,
+                cache_read_input_tokens: usage.cache_read_input_tokens,
+            })
+        }
+    })
+}
+
+struct ConfigurationView {
+    state: Entity<State>,
+    loading_models_task: Option<Task<()>>,
+}
+
+impl ConfigurationView {
+    fn new(state: Entity<State>, cx: &mut Context<Self>) -> Self {
+        let loading_models_task = Some(cx.spawn({
+            let state = state.clone();
+            async move |this, cx| {
+                if let Some(task) = state
+                    .update(cx, |state, cx| state.authenticate(cx))
+                    .log_err()
+                {
+                    // The error is shown in the view.
+                    let _ = task.await;
+                }
+                this.update(cx, |this, cx| {
+                    this.loading_models_task = None;
+                    cx.notify();
+                })
+                .log_err();
+            }
+        }));
+
+        cx.observe(&state, |_, _, cx| cx.notify()).detach();
+
+        Self {
+            state,
+            loading_models_task,
+        }
+    }
+
+    fn retry(&mut self, cx: &mut Context<Self>) {
+        let task = self.state.update(cx, |state, cx| state.authenticate(cx));
+        self.loading_models_task = Some(cx.spawn(async move |this, cx| {
+            let _ = task.await;
+            this.update(cx, |this, cx| {
+                this.loading_models_task = None;
+                cx.notify();
+            })
+            .log_err();
+        }));
+        cx.notify();
+    }
+}
+
+impl Render for ConfigurationView {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        if self.loading_models_task.is_some() {
+            return div().child(Label::new("Loading models...")).into_any();
+        }
+
+        let state = self.state.read(cx);
+        let extension_name = state.extension.manifest().name.clone();
+        let is_authenticated = state.is_authenticated;
+        let model_count = state.models.len();
+        let authentication_error = state.authentication_error.clone();
+
+        v_flex()
+            .gap_2()
+            .child(Label::new(format!(
+                "This provider is offered by the {extension_name} extension."
+            )))
+            .map(|this| {
+                if is_authenticated {
+                    this.child(
+                        ButtonLike::new("connected")
+                            .disabled(true)
+                            .cursor_style(gpui::CursorStyle::Arrow)
+                            .child(
+                                h_flex()
+                                    .gap_2()
+                                    .child(Indicator::dot().color(Color::Success))
+                                    .child(Label::new(format!("Connected ({model_count} models)")))
+                                    .into_any_element(),
+                            ),
+                    )
+                } else {
+                    this.when_some(authentication_error, |this, error| {
+                        this.child(Label::new(error).color(Color::Error))
+                    })
+                    .child(
+                        Button::new("retry_extension_provider", "Connect")
+                            .icon_position(IconPosition::Start)
+                            .icon_size(IconSize::XSmall)
+                            .icon(IconName::Play)
+                            .on_click(cx.listener(|this, _, _window, cx| this.retry(cx))),
+                    )
+                }
+            })
+            .into_any()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use extension::{ExtensionManifest, LanguageModelProviderManifestEntry, SchemaVersion};
+    use gpui::TestAppContext;
+    use language::{LanguageName, LanguageServerName};
+    use language_model::LanguageModelRequestMessage;
+    use std::{
+        path::{Path, PathBuf},
+        sync::atomic::{AtomicBool, AtomicUsize, Ordering::SeqCst},
+    };
+
+    #[gpui::test]
+    async fn test_extension_language_model_provider(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            LanguageModelRegistry::test(cx);
+            init(cx);
+        });
+
+        let extension = Arc::new(FakeExtension::default());
+        extension.fail_authentication.store(true, SeqCst);
+        cx.update(|cx| {
+            ExtensionHostProxy::global(cx).register_language_model_provider(
+                extension.clone(),
+                "fake-llm".into(),
+                LanguageModelProviderManifestEntry {
+                    name: "Fake LLM".into(),
+                },
+                cx,
+            )
+        });
+        cx.run_until_parked();
+
+        // The provider is registered and tries to authenticate right away.
+        let provider_id = LanguageModelProviderId("fake-llm".into());
+        let provider = cx
+            .update(|cx| LanguageModelRegistry::read_global(cx).provider(&provider_id))
+            .unwrap();
+        assert_eq!(provider.name().0.as_ref(), "Fake LLM");
+        assert_eq!(extension.authentication_count.load(SeqCst), 1);
+        cx.update(|cx| {
+            assert!(!provider.is_authenticated(cx));
+            assert!(provider.provided_models(cx).is_empty());
+        });
+
+        // Authenticating again while an authentication is in flight reuses it.
+        extension.fail_authentication.store(false, SeqCst);
+        let (first, second) =
+            cx.update(|cx| (provider.authenticate(cx), provider.authenticate(cx)));
+        first.await.unwrap();
+        second.await.unwrap();
+        assert_eq!(extension.authentication_count.load(SeqCst), 2);
+
+        let model = cx.update(|cx| {
+            assert!(provider.is_authenticated(cx));
+            let models = provider
+                .provided_models(cx)
+                .into_iter()
+                .map(|model| model.id().0.to_string())
+                .collect::<Vec<_>>();
+            assert_eq!(models, ["fake-small", "fake-large"]);
+            provider.default_model(cx).unwrap()
+        });
+        assert_eq!(model.id().0.as_ref(), "fake-large");
+        assert_eq!(model.max_token_count(), 200_000);
+
+        // Completions are streamed from the extension.
+        let request = LanguageModelRequest {
+            messages: vec![LanguageModelRequestMessage {
+                role: Role::User,
+                content: vec!["Say hello".into()],
+                cache: false,
+            }],
+            ..Default::default()
+        };
+        let events = model
+            .stream_completion(request, &cx.to_async())
+            .await
+            .unwrap()
+            .collect::<Vec<_>>()
+            .await
+            .into_iter()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(
+            events,
+            [
+                LanguageModelCompletionEvent::Text("Hello".into()),
+                LanguageModelCompletionEvent::UsageUpdate(TokenUsage {
+                    input_tokens: 2,
+                    output_tokens: 1,
+                    cache_creation_input_tokens: 0,
+                    cache_read_input_tokens: 0,
+                }),
+                LanguageModelCompletionEvent::Stop(StopReason::EndTurn),
+            ]
+        );
+        let requests = extension.requests.lock();
+        assert_eq!(requests.len(), 1);
+        let (model_id, request) = &requests[0];
+        assert_eq!(model_id.as_ref(), "fake-large");
+        assert_eq!(
+            request.messages[0].role,
+            extension::LanguageModelMessageRole::User
+        );
+        assert!(matches!(
+            &request.messages[0].content[..],
+            [extension::LanguageModelMessageContent::Text(text)] if text == "Say hello"
+        ));
+        drop(requests);
+
+        // Unloading the extension unregisters its provider.
+        cx.update(|cx| {
+            ExtensionHostProxy::global(cx)
+                .unregister_language_model_provider("fake-llm".into(), cx);
+            assert!(
+                LanguageModelRegistry::read_global(cx)
+                    .provider(&provider_id)
+                    .is_none()
+            );
+        });
+    }
+
+    #[derive(Default)]
+    struct FakeExtension {
+        fail_authentication: AtomicBool,
+        authentication_count: AtomicUsize,
+        requests: Mutex<Vec<(Arc<str>, extension::LanguageModelCompletionRequest)>>,
+    }
+
+    #[async_trait]
+    impl Extension for FakeExtension {
+        fn manifest(&self) -> Arc<ExtensionManifest> {
+            Arc::new(ExtensionManifest {
+                id: "fake".into(),
+                name: "Fake".into(),
+                version: "0.1.0".into(),
+                schema_version: SchemaVersion::ZERO,
+                description: None,
+                repository: None,
+                authors: Vec::new(),
+                lib: Default::default(),
+                themes: Vec::new(),
+                icon_themes: Vec::new(),
+                languages: Vec::new(),
+                grammars: Default::default(),
+                language_servers: Default::default(),
+                context_servers: Default::default(),
+                slash_commands: Default::default(),
+                indexed_docs_providers: Default::default(),
+                language_model_providers: Default::default(),
+                snippets: None,
+                capabilities: Vec::new(),
+            })
+        }
+
+        fn work_dir(&self) -> Arc<Path> {
+            Path::new("/fake").into()
+        }
+
+        async fn language_model_provider_authenticate(&self, _: Arc<str>) -> Result<()> {
+            self.authentication_count.fetch_add(1, SeqCst);
+            if self.fail_authentication.load(SeqCst) {
+                anyhow::bail!("missing API key");
+            }
+            Ok(())
+        }
+
+        async fn language_model_provider_models(
+            &self,
+            _: Arc<str>,
+        ) -> Result<Vec<extension::LanguageModelInfo>> {
+            let model = |id: &str, is_default| extension::LanguageModelInfo {
+                id: id.into(),
+                name: id.into(),
+                max_token_count: 200_000,
+                max_output_tokens: None,
+                supports_images: false,
+                supports_tools: true,
+                is_default,
+                is_default_fast: false,
+            };
+            Ok(vec![model("fake-small", false), model("fake-large", true)])
+        }
+
+        async fn stream_language_model_completion(
+            &self,
+            _: Arc<str>,
+            model_id: Arc<str>,
+            request: extension::LanguageModelCompletionRequest,
+        ) -> Result<BoxStream<'static, Result<extension::LanguageModelCompletionEvent>>> {
+            self.requests.lock().push((model_id, request));
+            Ok(futures::stream::iter([
+                Ok(extension::LanguageModelCompletionEvent::Text(
+                    "Hello".into(),
+                )),
+                Ok(extension::LanguageModelCompletionEvent::UsageUpdate(
+                    extension::LanguageModelTokenUsage {
+                        input_tokens: 2,
+                        output_tokens: 1,
+                        ..Default::default()
+                    },
+                )),
+                Ok(extension::LanguageModelCompletionEvent::Stop(
+                    extension::LanguageModelStopReason::EndTurn,
+                )),
+            ])
+            .boxed())
+        }
+
+        async fn language_server_command(
+            &self,
+            _: LanguageServerName,
+            _: LanguageName,
+            _: Arc<dyn extension::WorktreeDelegate>,
+        ) -> Result<extension::Command> {
+            unimplemented!()
+        }
+
+        async fn language_server_initialization_options(
+            &self,
+            _: LanguageServerName,
+            _: LanguageName,
+            _: Arc<dyn extension::WorktreeDelegate>,
+        ) -> Result<Option<String>> {
+            unimplemented!()
+        }
+
+        async fn language_server_workspace_configuration(
+            &self,
+            _: LanguageServerName,
+            _: Arc<dyn extension::WorktreeDelegate>,
+        ) -> Result<Option<String>> {
+            unimplemented!()
+        }
+
+        async fn language_server_additional_initialization_options(
+            &self,
+            _: LanguageServerName,
+            _: LanguageServerName,
+            _: Arc<dyn extension::WorktreeDelegate>,
+        ) -> Result<Option<String>> {
+            unimplemented!()
+        }
+
+        async fn language_server_additional_workspace_configuration(
+            &self,
+            _: LanguageServerName,
+            _: LanguageServerName,
+            _: Arc<dyn extension::WorktreeDelegate>,
+        ) -> Result<Option<String>> {
+            unimplemented!()
+        }
+
+        async fn labels_for_completions(
+            &self,
+            _: LanguageServerName,
+            _: Vec<extension::Completion>,
+        ) -> Result<Vec<Option<extension::CodeLabel>>> {
+            unimplemented!()
+        }
+
+        async fn labels_for_symbols(
+            &self,
+            _: LanguageServerName,
+            _: Vec<extension::Symbol>,
+        ) -> Result<Vec<Option<extension::CodeLabel>>> {
+            unimplemented!()
+        }
+
+        async fn complete_slash_command_argument(
+            &self,
+            _: extension::SlashCommand,
+            _: Vec<String>,
+        ) -> Result<Vec<extension::SlashCommandArgumentCompletion>> {
+            unimplemented!()
+        }
+
+        async fn run_slash_command(
+            &self,
+            _: extension::SlashCommand,
+            _: Vec<String>,
+            _: Option<Arc<dyn extension::WorktreeDelegate>>,
+        ) -> Result<extension::SlashCommandOutput> {
+            unimplemented!()
+        }
+
+        async fn context_server_command(
+            &self,
+            _: Arc<str>,
+            _: Arc<dyn extension::ProjectDelegate>,
+        ) -> Result<extension::Command> {
+            unimplemented!()
+        }
+
+        async fn context_server_configuration(
+            &self,
+            _: Arc<str>,
+            _: Arc<dyn extension::ProjectDelegate>,
+        ) -> Result<Option<extension::ContextServerConfiguration>> {
+            unimplemented!()
+        }
+
+        async fn suggest_docs_packages(&self, _: Arc<str>) -> Result<Vec<String>> {
+            unimplemented!()
+        }
+
+        async fn index_docs(
+            &self,
+            _: Arc<str>,
+            _: Arc<str>,
+            _: Arc<dyn extension::KeyValueStoreDelegate>,
+        ) -> Result<()> {
+            unimplemented!()
+        }
+
+        async fn get_dap_binary(
+            &self,
+            _: Arc<str>,
+            _: extension::DebugTaskDefinition,
+            _: Option<PathBuf>,
+        ) -> Result<extension::DebugAdapterBinary> {
+            unimplemented!()
+        }
+    }
+}
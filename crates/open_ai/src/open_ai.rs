@@ -681,8 +681,10 @@ pub async fn stream_completion(
     }
 }
 
-#[derive(Copy, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub enum OpenAiEmbeddingModel {
+    #[default]
     #[serde(rename = "text-embedding-3-small")]
     TextEmbedding3Small,
     #[serde(rename = "text-embedding-3-large")]
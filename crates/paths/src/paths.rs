@@ -215,6 +215,13 @@ pub fn keymap_backup_file() -> &'static PathBuf {
     KEYMAP_FILE.get_or_init(|| config_dir().join("keymap_backup.json"))
 }
 
+/// Returns the path to the `vimrc` file, which holds the mappings and commands
+/// of vim mode.
+pub fn vimrc_file() -> &'static PathBuf {
+    static VIMRC_FILE: OnceLock<PathBuf> = OnceLock::new();
+    VIMRC_FILE.get_or_init(|| config_dir().join("vimrc"))
+}
+
 /// Returns the path to the `tasks.json` file.
 pub fn tasks_file() -> &'static PathBuf {
     static TASKS_FILE: OnceLock<PathBuf> = OnceLock::new();
@@ -431,3 +438,18 @@ pub fn vscode_settings_file() -> &'static PathBuf {
         }
     })
 }
+
+/// Returns the path to the vscode user keybindings file
+pub fn vscode_keybindings_file() -> &'static PathBuf {
+    static KEYBINDINGS_FILE: OnceLock<PathBuf> = OnceLock::new();
+    let rel_path = "Code/User/keybindings.json";
+    KEYBINDINGS_FILE.get_or_init(|| {
+        if cfg!(target_os = "macos") {
+            home_dir()
+                .join("Library/Application Support")
+                .join(rel_path)
+        } else {
+            config_dir().join(rel_path)
+        }
+    })
+}
@@ -0,0 +1,352 @@
+//! Module for managing the bookmarks of a project.
+//!
+//! Bookmarks mark lines to come back to. They're anchored in their buffer so that they follow
+//! the line they were set on as the buffer is edited, and are keyed by the absolute path of
+//! their file so that workspaces can persist them.
+use anyhow::Result;
+use collections::BTreeMap;
+use gpui::{App, Context, Entity, EventEmitter, Subscription, Task};
+use language::{Buffer, BufferEvent, BufferSnapshot, DiskState};
+use std::{cmp::Ordering, ops::Range, path::Path, sync::Arc};
+use text::Point;
+
+use crate::{ProjectPath, buffer_store::BufferStore, worktree_store::WorktreeStore};
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Bookmark {
+    /// The name of the bookmark, if it was given one.
+    pub name: Option<Arc<str>>,
+}
+
+/// A bookmark as it's persisted, with its position as a row rather than an anchor.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SerializedBookmark {
+    pub path: Arc<Path>,
+    pub row: u32,
+    pub name: Option<Arc<str>>,
+}
+
+/// A bookmark along with the buffer it's in, as listed across all files.
+#[derive(Clone)]
+pub struct BookmarkEntry {
+    pub path: Arc<Path>,
+    pub buffer: Entity<Buffer>,
+    pub position: text::Anchor,
+    pub row: u32,
+    pub bookmark: Bookmark,
+}
+
+pub enum BookmarkStoreEvent {
+    BookmarksUpdated(Arc<Path>),
+    BookmarksCleared(Vec<Arc<Path>>),
+}
+
+struct BookmarksInFile {
+    buffer: Entity<Buffer>,
+    /// Sorted by position.
+    bookmarks: Vec<(text::Anchor, Bookmark)>,
+    _subscription: Subscription,
+}
+
+impl BookmarksInFile {
+    fn new(buffer: Entity<Buffer>, cx: &mut Context<BookmarkStore>) -> Self {
+        let subscription = cx.subscribe(&buffer, |bookmark_store, buffer, event, cx| {
+            if let BufferEvent::FileHandleChanged = event {
+                bookmark_store.on_file_handle_changed(buffer, cx);
+            }
+        });
+        Self {
+            buffer,
+            bookmarks: Vec::new(),
+            _subscription: subscription,
+        }
+    }
+
+    fn position_for_row(&self, row: u32, snapshot: &BufferSnapshot) -> Option<usize> {
+        self.bookmarks
+            .iter()
+            .position(|(position, _)| position.summary::<Point>(snapshot).row == row)
+    }
+
+    fn insert(&mut self, position: text::Anchor, bookmark: Bookmark, snapshot: &BufferSnapshot) {
+        let ix = self
+            .bookmarks
+            .partition_point(|(other, _)| other.cmp(&position, snapshot) == Ordering::Less);
+        self.bookmarks.insert(ix, (position, bookmark));
+    }
+}
+
+pub struct BookmarkStore {
+    bookmarks: BTreeMap<Arc<Path>, BookmarksInFile>,
+    worktree_store: Entity<WorktreeStore>,
+    buffer_store: Entity<BufferStore>,
+}
+
+impl EventEmitter<BookmarkStoreEvent> for BookmarkStore {}
+
+impl BookmarkStore {
+    pub fn new(worktree_store: Entity<WorktreeStore>, buffer_store: Entity<BufferStore>) -> Self {
+        Self {
+            bookmarks: BTreeMap::new(),
+            worktree_store,
+            buffer_store,
+        }
+    }
+
+    pub fn abs_path_from_buffer(buffer: &Entity<Buffer>, cx: &App) -> Option<Arc<Path>> {
+        worktree::File::from_dyn(buffer.read(cx).file())
+            .and_then(|file| file.worktree.read(cx).absolutize(&file.path).ok())
+            .map(Arc::<Path>::from)
+    }
+
+    fn on_file_handle_changed(&mut self, buffer: Entity<Buffer>, cx: &mut Context<Self>) {
+        let entity_id = buffer.entity_id();
+        let Some(old_path) = self
+            .bookmarks
+            .iter()
+            .find(|(_, in_file)| in_file.buffer.entity_id() == entity_id)
+            .map(|(path, _)| path.clone())
+        else {
+            return;
+        };
+
+        if buffer
+            .read(cx)
+            .file()
+            .is_none_or(|file| file.disk_state() == DiskState::Deleted)
+        {
+            self.bookmarks.remove(&old_path);
+            cx.emit(BookmarkStoreEvent::BookmarksCleared(vec![old_path]));
+            cx.notify();
+            return;
+        }
+
+        if let Some(new_path) = Self::abs_path_from_buffer(&buffer, cx) {
+            if new_path != old_path && !self.bookmarks.contains_key(&new_path) {
+                if let Some(bookmarks) = self.bookmarks.remove(&old_path) {
+                    self.bookmarks.insert(new_path.clone(), bookmarks);
+                    cx.emit(BookmarkStoreEvent::BookmarksUpdated(new_path));
+                    cx.notify();
+                }
+            }
+        }
+    }
+
+    /// Returns the bookmark on the given row of the buffer, if there is one.
+    pub fn bookmark_at_row(
+        &self,
+        buffer: &Entity<Buffer>,
+        row: u32,
+        cx: &App,
+    ) -> Option<(text::Anchor, Bookmark)> {
+        let path = Self::abs_path_from_buffer(buffer, cx)?;
+        let in_file = self.bookmarks.get(&path)?;
+        let snapshot = in_file.buffer.read(cx).snapshot();
+        let ix = in_file.position_for_row(row, &snapshot)?;
+        Some(in_file.bookmarks[ix].clone())
+    }
+
+    /// Removes the bookmark on the row of `position`, or adds an unnamed one if there isn't one.
+    pub fn toggle_bookmark(
+        &mut self,
+        buffer: Entity<Buffer>,
+        position: text::Anchor,
+        cx: &mut Context<Self>,
+    ) {
+        let snapshot = buffer.read(cx).snapshot();
+        let row = position.summary::<Point>(&snapshot).row;
+        let Some(abs_path) = Self::abs_path_from_buffer(&buffer, cx) else {
+            return;
+        };
+
+        if let Some(in_file) = self.bookmarks.get_mut(&abs_path) {
+            if let Some(ix) = in_file.position_for_row(row, &snapshot) {
+                in_file.bookmarks.remove(ix);
+                if in_file.bookmarks.is_empty() {
+                    self.bookmarks.remove(&abs_path);
+                }
+                self.bookmarks_updated(abs_path, cx);
+                return;
+            }
+        }
+
+        self.set_bookmark(buffer, row, Bookmark::default(), abs_path, cx);
+    }
+
+    /// Names the bookmark on the row of `position`, adding one if there isn't one.
+    pub fn set_bookmark_name(
+        &mut self,
+        buffer: Entity<Buffer>,
+        position: text::Anchor,
+        name: Option<Arc<str>>,
+        cx: &mut Context<Self>,
+    ) {
+        let snapshot = buffer.read(cx).snapshot();
+        let row = position.summary::<Point>(&snapshot).row;
+        let Some(abs_path) = Self::abs_path_from_buffer(&buffer, cx) else {
+            return;
+        };
+        self.set_bookmark(buffer, row, Bookmark { name }, abs_path, cx);
+    }
+
+    fn set_bookmark(
+        &mut self,
+        buffer: Entity<Buffer>,
+        row: u32,
+        bookmark: Bookmark,
+        abs_path: Arc<Path>,
+        cx: &mut Context<Self>,
+    ) {
+        let snapshot = buffer.read(cx).snapshot();
+        let in_file = self
+            .bookmarks
+            .entry(abs_path.clone())
+            .or_insert_with(|| BookmarksInFile::new(buffer, cx));
+        if let Some(ix) = in_file.position_for_row(row, &snapshot) {
+            in_file.bookmarks[ix].1 = bookmark;
+        } else {
+            let position = snapshot.anchor_after(Point::new(row, 0));
+            in_file.insert(position, bookmark, &snapshot);
+        }
+        self.bookmarks_updated(abs_path, cx);
+    }
+
+    fn bookmarks_updated(&mut self, abs_path: Arc<Path>, cx: &mut Context<Self>) {
+        cx.emit(BookmarkStoreEvent::BookmarksUpdated(abs_path));
+        cx.notify();
+    }
+
+    pub fn clear_bookmarks(&mut self, cx: &mut Context<Self>) {
+        let paths = self.bookmarks.keys().cloned().collect();
+        self.bookmarks.clear();
+        cx.emit(BookmarkStoreEvent::BookmarksCleared(paths));
+        cx.notify();
+    }
+
+    /// Returns the bookmarks in the buffer, in order, optionally limited to those within `range`.
+    pub fn bookmarks<'a>(
+        &'a self,
+        buffer: &'a Entity<Buffer>,
+        range: Option<Range<text::Anchor>>,
+        buffer_snapshot: &'a BufferSnapshot,
+        cx: &App,
+    ) -> impl Iterator<Item = &'a (text::Anchor, Bookmark)> + 'a {
+        let abs_path = Self::abs_path_from_buffer(buffer, cx);
+        abs_path
+            .and_then(|path| self.bookmarks.get(&path))
+            .into_iter()
+            .flat_map(move |in_file| {
+                in_file.bookmarks.iter().filter({
+                    let range = range.clone();
+                    move |(position, _)| {
+                        range.as_ref().is_none_or(|range| {
+                            position.cmp(&range.start, buffer_snapshot).is_ge()
+                                && position.cmp(&range.end, buffer_snapshot).is_le()
+                        })
+                    }
+                })
+            })
+    }
+
+    /// Returns every bookmark in the project, ordered by path and then by row.
+    pub fn all_bookmarks(&self, cx: &App) -> Vec<BookmarkEntry> {
+        self.bookmarks
+            .iter()
+            .flat_map(|(path, in_file)| {
+                let snapshot = in_file.buffer.read(cx).snapshot();
+                in_file
+                    .bookmarks
+                    .iter()
+                    .map(move |(position, bookmark)| BookmarkEntry {
+                        path: path.clone(),
+                        buffer: in_file.buffer.clone(),
+                        position: *position,
+                        row: position.summary::<Point>(&snapshot).row,
+                        bookmark: bookmark.clone(),
+                    })
+            })
+            .collect()
+    }
+
+    pub fn serialized_bookmarks(&self, cx: &App) -> BTreeMap<Arc<Path>, Vec<SerializedBookmark>> {
+        self.bookmarks
+            .iter()
+            .map(|(path, in_file)| {
+                let snapshot = in_file.buffer.read(cx).snapshot();
+                (
+                    path.clone(),
+                    in_file
+                        .bookmarks
+                        .iter()
+                        .map(|(position, bookmark)| SerializedBookmark {
+                            path: path.clone(),
+                            row: position.summary::<Point>(&snapshot).row,
+                            name: bookmark.name.clone(),
+                        })
+                        .collect(),
+                )
+            })
+            .collect()
+    }
+
+    pub fn with_serialized_bookmarks(
+        &self,
+        bookmarks: BTreeMap<Arc<Path>, Vec<SerializedBookmark>>,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        let worktree_store = self.worktree_store.clone();
+        let buffer_store = self.buffer_store.clone();
+        cx.spawn(async move |this, cx| {
+            let mut new_bookmarks = BTreeMap::default();
+            for (path, serialized) in bookmarks {
+                if serialized.is_empty() {
+                    continue;
+                }
+                let (worktree, relative_path) = worktree_store
+                    .update(cx, |worktree_store, cx| {
+                        worktree_store.find_or_create_worktree(&path, false, cx)
+                    })?
+                    .await?;
+                let buffer = buffer_store
+                    .update(cx, |buffer_store, cx| {
+                        let path = ProjectPath {
+                            worktree_id: worktree.read(cx).id(),
+                            path: relative_path.into(),
+                        };
+                        buffer_store.open_buffer(path, cx)
+                    })?
+                    .await;
+                let Ok(buffer) = buffer else {
+                    log::error!("Skipping bookmarks in {path:?}, which couldn't be opened");
+                    continue;
+                };
+                let snapshot = buffer.update(cx, |buffer, _| buffer.snapshot())?;
+
+                let mut in_file = this.update(cx, |_, cx| BookmarksInFile::new(buffer, cx))?;
+                for bookmark in serialized {
+                    if bookmark.row > snapshot.max_point().row {
+                        log::error!("Skipping a deserialized bookmark that's out of range");
+                        continue;
+                    }
+                    if in_file.position_for_row(bookmark.row, &snapshot).is_some() {
+                        continue;
+                    }
+                    let position = snapshot.anchor_after(Point::new(bookmark.row, 0));
+                    in_file.insert(
+                        position,
+                        Bookmark {
+                            name: bookmark.name,
+                        },
+                        &snapshot,
+                    );
+                }
+                new_bookmarks.insert(path, in_file);
+            }
+
+            this.update(cx, |this, cx| {
+                this.bookmarks = new_bookmarks;
+                cx.notify();
+            })
+        })
+    }
+}
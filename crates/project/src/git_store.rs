@@ -900,6 +900,37 @@ impl GitStore {
         })
     }
 
+    /// Returns the changes between two checkpoints as a patch, with one diff per repository.
+    pub fn diff_checkpoints(
+        &self,
+        base: GitStoreCheckpoint,
+        mut target: GitStoreCheckpoint,
+        cx: &mut App,
+    ) -> Task<Result<String>> {
+        let repositories_by_work_dir_abs_path = self
+            .repositories
+            .values()
+            .map(|repo| (repo.read(cx).snapshot.work_directory_abs_path.clone(), repo))
+            .collect::<HashMap<_, _>>();
+
+        let mut tasks = Vec::new();
+        for (work_dir_abs_path, base_checkpoint) in base.checkpoints_by_work_dir_abs_path {
+            let Some(target_checkpoint) = target
+                .checkpoints_by_work_dir_abs_path
+                .remove(&work_dir_abs_path)
+            else {
+                continue;
+            };
+            if let Some(repository) = repositories_by_work_dir_abs_path.get(&work_dir_abs_path) {
+                let diff = repository.update(cx, |repository, _| {
+                    repository.diff_checkpoints(base_checkpoint, target_checkpoint)
+                });
+                tasks.push(async move { diff.await? });
+            }
+        }
+        cx.background_spawn(async move { Ok(future::try_join_all(tasks).await?.concat()) })
+    }
+
     /// Blames a buffer.
     pub fn blame_buffer(
         &self,
@@ -1,9 +1,14 @@
-use crate::{Project, ProjectPath};
-use anyhow::{Context as _, Result};
-use collections::HashMap;
-use gpui::{AnyWindowHandle, App, AppContext as _, Context, Entity, Task, WeakEntity};
+use crate::{Project, ProjectClientState, ProjectPath};
+use anyhow::{Context as _, Result, anyhow};
+use client::{TypedEnvelope, proto};
+use collections::{BTreeMap, HashMap};
+use futures::{StreamExt as _, channel::mpsc};
+use gpui::{
+    AnyWindowHandle, App, AppContext as _, AsyncApp, Context, Entity, Subscription, Task,
+    WeakEntity,
+};
 use itertools::Itertools;
-use language::LanguageName;
+use language::{Capability, LanguageName};
 use settings::{Settings, SettingsLocation};
 use smol::channel::bounded;
 use std::{
@@ -11,16 +16,57 @@ use std::{
     env::{self},
     path::{Path, PathBuf},
     sync::Arc,
+    time::Duration,
 };
 use task::{DEFAULT_REMOTE_SHELL, Shell, ShellBuilder, SpawnInTerminal};
 use terminal::{
-    TaskState, TaskStatus, Terminal, TerminalBuilder,
+    EncodedScreen, ScreenSnapshot, TaskState, TaskStatus, Terminal, TerminalBuilder,
     terminal_settings::{self, TerminalSettings, VenvSettings},
 };
 use util::ResultExt;
 
+/// The screen of a shared terminal is sent to collaborators at most this often.
+const SHARED_SCREEN_UPDATE_INTERVAL: Duration = Duration::from_millis(50);
+
+#[derive(Default)]
 pub struct Terminals {
     pub(crate) local_handles: Vec<WeakEntity<terminal::Terminal>>,
+    /// The local terminals shared with collaborators, by terminal id.
+    hosted: BTreeMap<u64, HostedTerminal>,
+    /// The terminals shared by the host, when the project was joined over collab.
+    shared_with_us: Vec<SharedTerminal>,
+    /// The terminals shared by the host that were opened locally, by terminal id.
+    opened_shared: HashMap<u64, OpenedSharedTerminal>,
+}
+
+struct HostedTerminal {
+    terminal: WeakEntity<Terminal>,
+    read_only: bool,
+    /// The screen that collaborators were last sent, which later updates are diffed against.
+    sent_screen: EncodedScreen,
+    sent_version: u64,
+    pending_screen_update: Option<Task<()>>,
+    _subscriptions: [Subscription; 2],
+}
+
+/// A terminal shared by the host that is opened locally. The host sends the lines of its
+/// screen that changed, so each update only applies to the version of the screen before it.
+enum OpenedSharedTerminal {
+    /// The host's screen was requested, and these updates were received in the meantime.
+    Opening { updates: Vec<proto::TerminalScreen> },
+    Open {
+        terminal: WeakEntity<Terminal>,
+        version: u64,
+    },
+}
+
+/// A terminal shared by the host of a project joined over collab.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SharedTerminal {
+    pub id: u64,
+    pub title: String,
+    /// Whether the host only allows collaborators to watch the terminal.
+    pub read_only: bool,
 }
 
 /// Terminals are opened either for the users shell, or to run a task.
@@ -520,6 +566,398 @@ impl Project {
     pub fn local_terminal_handles(&self) -> &Vec<WeakEntity<terminal::Terminal>> {
         &self.terminals.local_handles
     }
+
+    /// Shares a local terminal with the collaborators in this project. Collaborators
+    /// can write to it if they can edit the project, unless `read_only` is set.
+    pub fn share_terminal(
+        &mut self,
+        terminal: &Entity<Terminal>,
+        read_only: bool,
+        cx: &mut Context<Self>,
+    ) -> Result<()> {
+        if !matches!(self.client_state, ProjectClientState::Shared { .. }) {
+            return Err(anyhow!("only terminals of shared projects can be shared"));
+        }
+        if terminal.read(cx).is_shared_with_us() {
+            return Err(anyhow!("cannot share a terminal shared by a collaborator"));
+        }
+
+        let id = terminal.entity_id().as_u64();
+        if let Some(hosted) = self.terminals.hosted.get_mut(&id) {
+            hosted.read_only = read_only;
+        } else {
+            let subscriptions = [
+                cx.subscribe(terminal, move |project, _, event, cx| match event {
+                    terminal::Event::Wakeup => project.schedule_shared_screen_update(id, cx),
+                    terminal::Event::TitleChanged => project.send_shared_terminals(cx),
+                    _ => {}
+                }),
+                cx.observe_release(terminal, move |project, _, cx| {
+                    if project.terminals.hosted.remove(&id).is_some() {
+                        project.send_shared_terminals(cx);
+                    }
+                }),
+            ];
+            self.terminals.hosted.insert(
+                id,
+                HostedTerminal {
+                    terminal: terminal.downgrade(),
+                    read_only,
+                    sent_screen: terminal.read(cx).encode_screen(),
+                    sent_version: 0,
+                    pending_screen_update: None,
+                    _subscriptions: subscriptions,
+                },
+            );
+        }
+        self.send_shared_terminals(cx);
+        Ok(())
+    }
+
+    pub fn stop_sharing_terminal(&mut self, terminal: &Entity<Terminal>, cx: &mut Context<Self>) {
+        let id = terminal.entity_id().as_u64();
+        if self.terminals.hosted.remove(&id).is_some() {
+            self.send_shared_terminals(cx);
+        }
+    }
+
+    pub fn is_sharing_terminal(&self, terminal: &Entity<Terminal>) -> bool {
+        self.terminals
+            .hosted
+            .contains_key(&terminal.entity_id().as_u64())
+    }
+
+    /// The terminals shared by the host, when the project was joined over collab.
+    pub fn shared_terminals(&self) -> &[SharedTerminal] {
+        &self.terminals.shared_with_us
+    }
+
+    /// Opens a terminal shared by the host. Input is only forwarded to the host when the
+    /// terminal isn't shared read-only and the local user can edit the project.
+    pub fn open_shared_terminal(
+        &mut self,
+        terminal_id: u64,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Entity<Terminal>>> {
+        match self.terminals.opened_shared.get(&terminal_id) {
+            Some(OpenedSharedTerminal::Open { terminal, .. }) => {
+                if let Some(terminal) = terminal.upgrade() {
+                    return Task::ready(Ok(terminal));
+                }
+            }
+            Some(OpenedSharedTerminal::Opening { .. }) => {
+                return Task::ready(Err(anyhow!("terminal is already being opened")));
+            }
+            None => {}
+        }
+        let ProjectClientState::Remote { remote_id, .. } = self.client_state else {
+            return Task::ready(Err(anyhow!("project was not joined over collab")));
+        };
+        let Some(shared_terminal) = self
+            .terminals
+            .shared_with_us
+            .iter()
+            .find(|terminal| terminal.id == terminal_id)
+            .cloned()
+        else {
+            return Task::ready(Err(anyhow!("terminal is not shared")));
+        };
+
+        let writable = !shared_terminal.read_only && self.capability() == Capability::ReadWrite;
+        let cursor_shape = TerminalSettings::get_global(cx)
+            .cursor_shape
+            .unwrap_or_default();
+        let client = self.client.clone();
+        let request = client.request(proto::OpenSharedTerminal {
+            project_id: remote_id,
+            terminal_id,
+        });
+        self.terminals.opened_shared.insert(
+            terminal_id,
+            OpenedSharedTerminal::Opening {
+                updates: Vec::new(),
+            },
+        );
+        cx.spawn(async move |project, cx| {
+            let screen = async { request.await?.screen.context("missing terminal screen") }.await;
+            let screen = match screen {
+                Ok(screen) => screen,
+                Err(error) => {
+                    project.update(cx, |project, _| {
+                        project.terminals.opened_shared.remove(&terminal_id);
+                    })?;
+                    return Err(error);
+                }
+            };
+
+            let (input_tx, input_rx) = if writable {
+                let (input_tx, input_rx) = mpsc::unbounded();
+                (Some(input_tx), Some(input_rx))
+            } else {
+                (None, None)
+            };
+            let title = if writable {
+                shared_terminal.title
+            } else {
+                format!("{} (read-only)", shared_terminal.title)
+            };
+            let version = screen.version;
+            let terminal = cx.new(|cx| {
+                let mut terminal =
+                    TerminalBuilder::new_shared(title.into(), input_tx, cursor_shape).subscribe(cx);
+                terminal.apply_screen_snapshot(&screen_from_proto(screen), cx);
+                terminal
+            })?;
+
+            // The input task ends once the terminal, which owns the sender, is dropped.
+            if let Some(mut input_rx) = input_rx {
+                cx.background_spawn(async move {
+                    while let Some(mut data) = input_rx.next().await {
+                        while let Ok(Some(more_data)) = input_rx.try_next() {
+                            data.extend(more_data);
+                        }
+                        client
+                            .request(proto::TerminalInput {
+                                project_id: remote_id,
+                                terminal_id,
+                                data,
+                            })
+                            .await
+                            .log_err();
+                    }
+                })
+                .detach();
+            }
+
+            project.update(cx, |project, cx| {
+                let updates = match project.terminals.opened_shared.insert(
+                    terminal_id,
+                    OpenedSharedTerminal::Open {
+                        terminal: terminal.downgrade(),
+                        version,
+                    },
+                ) {
+                    Some(OpenedSharedTerminal::Opening { updates }) => updates,
+                    _ => Vec::new(),
+                };
+                // Updates that were sent before the host's screen are already part of it.
+                for screen in updates {
+                    project.apply_shared_screen(terminal_id, screen, cx);
+                }
+            })?;
+            Ok(terminal)
+        })
+    }
+
+    pub(crate) fn send_shared_terminals(&self, cx: &App) {
+        let ProjectClientState::Shared { remote_id } = self.client_state else {
+            return;
+        };
+        let terminals = self
+            .terminals
+            .hosted
+            .iter()
+            .filter_map(|(id, hosted)| {
+                let terminal = hosted.terminal.upgrade()?;
+                Some(proto::SharedTerminal {
+                    terminal_id: *id,
+                    title: terminal.read(cx).title(false),
+                    read_only: hosted.read_only,
+                })
+            })
+            .collect();
+        self.client
+            .send(proto::UpdateSharedTerminals {
+                project_id: remote_id,
+                terminals,
+            })
+            .log_err();
+    }
+
+    pub(crate) fn stop_sharing_terminals(&mut self) {
+        self.terminals.hosted.clear();
+    }
+
+    pub(crate) fn forget_shared_terminals(&mut self) {
+        self.terminals.shared_with_us.clear();
+        self.terminals.opened_shared.clear();
+    }
+
+    fn schedule_shared_screen_update(&mut self, id: u64, cx: &mut Context<Self>) {
+        let Some(hosted) = self.terminals.hosted.get_mut(&id) else {
+            return;
+        };
+        if hosted.pending_screen_update.is_some() {
+            return;
+        }
+        hosted.pending_screen_update = Some(cx.spawn(async move |project, cx| {
+            cx.background_executor()
+                .timer(SHARED_SCREEN_UPDATE_INTERVAL)
+                .await;
+            project
+                .update(cx, |project, cx| {
+                    if let Some(hosted) = project.terminals.hosted.get_mut(&id) {
+                        hosted.pending_screen_update = None;
+                        project.send_shared_screen(id, cx);
+                    }
+                })
+                .ok();
+        }));
+    }
+
+    /// Sends collaborators the lines of a shared terminal that changed since they were
+    /// last sent.
+    fn send_shared_screen(&mut self, id: u64, cx: &App) {
+        let ProjectClientState::Shared { remote_id } = self.client_state else {
+            return;
+        };
+        let Some(hosted) = self.terminals.hosted.get_mut(&id) else {
+            return;
+        };
+        let Some(terminal) = hosted.terminal.upgrade() else {
+            return;
+        };
+        let screen = terminal.read(cx).encode_screen();
+        let Some(diff) = screen.diff(&hosted.sent_screen) else {
+            return;
+        };
+        hosted.sent_screen = screen;
+        hosted.sent_version += 1;
+        self.client
+            .send(proto::UpdateTerminalScreen {
+                project_id: remote_id,
+                terminal_id: id,
+                screen: Some(screen_to_proto(diff, hosted.sent_version)),
+            })
+            .log_err();
+    }
+
+    /// Applies an update of a terminal shared by the host, if it follows the version of the
+    /// screen shown locally.
+    fn apply_shared_screen(
+        &mut self,
+        terminal_id: u64,
+        screen: proto::TerminalScreen,
+        cx: &mut App,
+    ) {
+        match self.terminals.opened_shared.get_mut(&terminal_id) {
+            Some(OpenedSharedTerminal::Opening { updates }) => updates.push(screen),
+            Some(OpenedSharedTerminal::Open { terminal, version }) => {
+                if screen.version <= *version {
+                    return;
+                }
+                *version = screen.version;
+                if let Some(terminal) = terminal.upgrade() {
+                    terminal.update(cx, |terminal, cx| {
+                        terminal.apply_screen_snapshot(&screen_from_proto(screen), cx)
+                    });
+                }
+            }
+            None => {}
+        }
+    }
+
+    pub(crate) async fn handle_update_shared_terminals(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::UpdateSharedTerminals>,
+        mut cx: AsyncApp,
+    ) -> Result<()> {
+        this.update(&mut cx, |this, cx| {
+            let Terminals {
+                shared_with_us,
+                opened_shared,
+                ..
+            } = &mut this.terminals;
+            *shared_with_us = envelope
+                .payload
+                .terminals
+                .into_iter()
+                .map(|terminal| SharedTerminal {
+                    id: terminal.terminal_id,
+                    title: terminal.title,
+                    read_only: terminal.read_only,
+                })
+                .collect();
+            opened_shared.retain(|id, opened| {
+                let is_open = match opened {
+                    OpenedSharedTerminal::Opening { .. } => true,
+                    OpenedSharedTerminal::Open { terminal, .. } => terminal.upgrade().is_some(),
+                };
+                is_open && shared_with_us.iter().any(|shared| shared.id == *id)
+            });
+            cx.notify();
+        })
+    }
+
+    pub(crate) async fn handle_update_terminal_screen(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::UpdateTerminalScreen>,
+        mut cx: AsyncApp,
+    ) -> Result<()> {
+        let screen = envelope.payload.screen.context("missing terminal screen")?;
+        this.update(&mut cx, |this, cx| {
+            this.apply_shared_screen(envelope.payload.terminal_id, screen, cx)
+        })
+    }
+
+    pub(crate) async fn handle_open_shared_terminal(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::OpenSharedTerminal>,
+        cx: AsyncApp,
+    ) -> Result<proto::OpenSharedTerminalResponse> {
+        this.read_with(&cx, |this, _| {
+            let hosted = this
+                .terminals
+                .hosted
+                .get(&envelope.payload.terminal_id)
+                .context("terminal is not shared")?;
+            // Send the screen that the next update is diffed against, even if the terminal
+            // changed since, so the collaborator applies that update to the right screen.
+            Ok(proto::OpenSharedTerminalResponse {
+                screen: Some(screen_to_proto(
+                    hosted.sent_screen.snapshot(),
+                    hosted.sent_version,
+                )),
+            })
+        })?
+    }
+
+    pub(crate) async fn handle_terminal_input(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::TerminalInput>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        this.update(&mut cx, |this, cx| {
+            let hosted = this
+                .terminals
+                .hosted
+                .get(&envelope.payload.terminal_id)
+                .context("terminal is not shared")?;
+            if hosted.read_only {
+                return Err(anyhow!("terminal is shared read-only"));
+            }
+            let terminal = hosted.terminal.upgrade().context("terminal was closed")?;
+            terminal.update(cx, |terminal, _| terminal.input(envelope.payload.data));
+            Ok(proto::Ack {})
+        })?
+    }
+}
+
+fn screen_to_proto(screen: ScreenSnapshot, version: u64) -> proto::TerminalScreen {
+    proto::TerminalScreen {
+        columns: screen.columns as u32,
+        lines: screen.lines as u32,
+        content: screen.content,
+        version,
+    }
+}
+
+fn screen_from_proto(screen: proto::TerminalScreen) -> ScreenSnapshot {
+    ScreenSnapshot {
+        columns: screen.columns as usize,
+        lines: screen.lines as usize,
+        content: screen.content,
+    }
 }
 
 pub fn wrap_for_ssh(
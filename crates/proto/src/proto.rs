@@ -306,6 +306,11 @@ messages!(
     (RunDebugLocators, Background),
     (DebugRequest, Background),
     (LogToDebugConsole, Background),
+    (UpdateSharedTerminals, Foreground),
+    (UpdateTerminalScreen, Foreground),
+    (OpenSharedTerminal, Foreground),
+    (OpenSharedTerminalResponse, Foreground),
+    (TerminalInput, Foreground),
 );
 
 request_messages!(
@@ -467,6 +472,8 @@ request_messages!(
     (ToggleBreakpoint, Ack),
     (GetDebugAdapterBinary, DebugAdapterBinary),
     (RunDebugLocators, DebugRequest),
+    (OpenSharedTerminal, OpenSharedTerminalResponse),
+    (TerminalInput, Ack),
 );
 
 entity_messages!(
@@ -593,6 +600,10 @@ entity_messages!(
     RunDebugLocators,
     GetDebugAdapterBinary,
     LogToDebugConsole,
+    UpdateSharedTerminals,
+    UpdateTerminalScreen,
+    OpenSharedTerminal,
+    TerminalInput,
 );
 
 entity_messages!(
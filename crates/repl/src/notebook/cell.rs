@@ -1,21 +1,24 @@
 #![allow(unused, dead_code)]
 use std::sync::Arc;
 
-use editor::{Editor, EditorMode, MultiBuffer};
+use editor::{Editor, EditorEvent, EditorMode, MultiBuffer};
+use futures::FutureExt as _;
 use futures::future::Shared;
 use gpui::{
-    App, Entity, Hsla, RetainAllImageCache, Task, TextStyleRefinement, image_cache, prelude::*,
+    App, ClickEvent, Entity, Hsla, RetainAllImageCache, Subscription, Task, TextStyleRefinement,
+    image_cache, prelude::*,
 };
 use language::{Buffer, Language, LanguageRegistry};
 use markdown_preview::{markdown_parser::parse_markdown, markdown_renderer::render_markdown_block};
 use nbformat::v4::{CellId, CellMetadata, CellType};
+use serde_json::Value;
 use settings::Settings as _;
 use theme::ThemeSettings;
 use ui::{IconButtonShape, prelude::*};
 use util::ResultExt;
 
 use crate::{
-    notebook::{CODE_BLOCK_INSET, GUTTER_WIDTH},
+    notebook::{CODE_BLOCK_INSET, GUTTER_WIDTH, source_lines},
     outputs::{Output, plain::TerminalOutput, user_error::ErrorView},
 };
 
@@ -112,9 +115,77 @@ fn convert_outputs(
         .collect()
 }
 
+fn cell_editor(
+    text: String,
+    language: Option<Shared<Task<Option<Arc<Language>>>>>,
+    window: &mut Window,
+    cx: &mut App,
+) -> Entity<Editor> {
+    let buffer = cx.new(|cx| Buffer::local(text, cx));
+    let multi_buffer = cx.new(|cx| MultiBuffer::singleton(buffer.clone(), cx));
+
+    if let Some(language) = language {
+        let buffer = buffer.clone();
+        window
+            .spawn(cx, async move |cx| {
+                let language = language.await;
+                buffer
+                    .update(cx, |buffer, cx| buffer.set_language(language, cx))
+                    .log_err();
+            })
+            .detach();
+    }
+
+    cx.new(|cx| {
+        let mut editor = Editor::new(
+            EditorMode::AutoHeight { max_lines: 1024 },
+            multi_buffer,
+            None,
+            window,
+            cx,
+        );
+
+        let theme = ThemeSettings::get_global(cx);
+
+        let refinement = TextStyleRefinement {
+            font_family: Some(theme.buffer_font.family.clone()),
+            font_size: Some(theme.buffer_font_size(cx).into()),
+            color: Some(cx.theme().colors().editor_foreground),
+            background_color: Some(gpui::transparent_black()),
+            ..Default::default()
+        };
+
+        editor.set_show_gutter(false, cx);
+        editor.set_text_style_refinement(refinement);
+        editor
+    })
+}
+
+fn editor_text(editor: &Entity<Editor>, cx: &App) -> String {
+    editor.read(cx).buffer().read(cx).snapshot(cx).text()
+}
+
+fn is_editor_dirty(editor: &Entity<Editor>, cx: &App) -> bool {
+    editor.read(cx).buffer().read(cx).is_dirty(cx)
+}
+
+/// Marks the buffer behind a cell editor as saved, so it is no longer dirty.
+fn mark_editor_saved(editor: &Entity<Editor>, cx: &mut App) {
+    let Some(buffer) = editor.read(cx).buffer().read(cx).as_singleton() else {
+        return;
+    };
+    buffer.update(cx, |buffer, cx| {
+        let version = buffer.version();
+        buffer.did_save(version, None, cx);
+    });
+}
+
 impl Cell {
+    /// Loads a cell, with the outputs of a code cell as the JSON they're stored as in the
+    /// notebook file, when they were read from one.
     pub fn load(
         cell: &nbformat::v4::Cell,
+        raw_outputs: Option<&Vec<Value>>,
         languages: &Arc<LanguageRegistry>,
         notebook_language: Shared<Task<Option<Arc<Language>>>>,
         window: &mut Window,
@@ -125,40 +196,43 @@ impl Cell {
                 id,
                 metadata,
                 source,
-                ..
+                attachments,
             } => {
                 let source = source.join("");
 
                 let entity = cx.new(|cx| {
-                    let markdown_parsing_task = {
-                        let languages = languages.clone();
-                        let source = source.clone();
-
-                        cx.spawn_in(window, async move |this, cx| {
-                            let parsed_markdown = cx
-                                .background_spawn(async move {
-                                    parse_markdown(&source, None, Some(languages)).await
-                                })
-                                .await;
-
-                            this.update(cx, |cell: &mut MarkdownCell, _| {
-                                cell.parsed_markdown = Some(parsed_markdown);
-                            })
-                            .log_err();
-                        })
-                    };
+                    let markdown_language = languages.language_for_name("Markdown");
+                    let markdown_language = window
+                        .spawn(cx, async move |_| markdown_language.await.ok())
+                        .shared();
+                    let editor = cell_editor(source.clone(), Some(markdown_language), window, cx);
+                    let editor_subscription = cx.subscribe_in(
+                        &editor,
+                        window,
+                        |this: &mut MarkdownCell, _, event: &EditorEvent, window, cx| {
+                            if let EditorEvent::Blurred = event {
+                                this.stop_editing(window, cx);
+                            }
+                        },
+                    );
 
-                    MarkdownCell {
-                        markdown_parsing_task,
+                    let mut cell = MarkdownCell {
+                        markdown_parsing_task: Task::ready(()),
                         image_cache: RetainAllImageCache::new(cx),
                         languages: languages.clone(),
                         id: id.clone(),
                         metadata: metadata.clone(),
+                        attachments: attachments.clone(),
                         source: source.clone(),
+                        editor,
+                        editing: false,
                         parsed_markdown: None,
                         selected: false,
                         cell_position: None,
-                    }
+                        _editor_subscription: editor_subscription,
+                    };
+                    cell.parse_markdown(window, cx);
+                    cell
                 });
 
                 Cell::Markdown(entity)
@@ -171,55 +245,23 @@ impl Cell {
                 outputs,
             } => Cell::Code(cx.new(|cx| {
                 let text = source.join("");
-
-                let buffer = cx.new(|cx| Buffer::local(text.clone(), cx));
-                let multi_buffer = cx.new(|cx| MultiBuffer::singleton(buffer.clone(), cx));
-
-                let editor_view = cx.new(|cx| {
-                    let mut editor = Editor::new(
-                        EditorMode::AutoHeight { max_lines: 1024 },
-                        multi_buffer,
-                        None,
-                        window,
-                        cx,
-                    );
-
-                    let theme = ThemeSettings::get_global(cx);
-
-                    let refinement = TextStyleRefinement {
-                        font_family: Some(theme.buffer_font.family.clone()),
-                        font_size: Some(theme.buffer_font_size(cx).into()),
-                        color: Some(cx.theme().colors().editor_foreground),
-                        background_color: Some(gpui::transparent_black()),
-                        ..Default::default()
-                    };
-
-                    editor.set_text(text, window, cx);
-                    editor.set_show_gutter(false, cx);
-                    editor.set_text_style_refinement(refinement);
-
-                    // editor.set_read_only(true);
-                    editor
-                });
-
-                let buffer = buffer.clone();
-                let language_task = cx.spawn_in(window, async move |this, cx| {
-                    let language = notebook_language.await;
-
-                    buffer.update(cx, |buffer, cx| {
-                        buffer.set_language(language.clone(), cx);
-                    });
-                });
+                let editor = cell_editor(text.clone(), Some(notebook_language), window, cx);
 
                 CodeCell {
                     id: id.clone(),
                     metadata: metadata.clone(),
                     execution_count: *execution_count,
-                    source: source.join(""),
-                    editor: editor_view,
+                    source: text,
+                    editor,
                     outputs: convert_outputs(outputs, window, cx),
+                    raw_outputs: raw_outputs.cloned().unwrap_or_else(|| {
+                        outputs
+                            .iter()
+                            .filter_map(|output| serde_json::to_value(output).log_err())
+                            .collect()
+                    }),
+                    outputs_changed: false,
                     selected: false,
-                    language_task,
                     cell_position: None,
                 }
             })),
@@ -227,15 +269,100 @@ impl Cell {
                 id,
                 metadata,
                 source,
-            } => Cell::Raw(cx.new(|_| RawCell {
-                id: id.clone(),
-                metadata: metadata.clone(),
-                source: source.join(""),
-                selected: false,
-                cell_position: None,
+            } => Cell::Raw(cx.new(|cx| {
+                let text = source.join("");
+                let editor = cell_editor(text.clone(), None, window, cx);
+
+                RawCell {
+                    id: id.clone(),
+                    metadata: metadata.clone(),
+                    source: text,
+                    editor,
+                    selected: false,
+                    cell_position: None,
+                }
             })),
         }
     }
+
+    pub fn id(&self, cx: &App) -> CellId {
+        match self {
+            Cell::Code(cell) => cell.read(cx).id().clone(),
+            Cell::Markdown(cell) => cell.read(cx).id().clone(),
+            Cell::Raw(cell) => cell.read(cx).id().clone(),
+        }
+    }
+
+    pub fn editor(&self, cx: &App) -> Entity<Editor> {
+        match self {
+            Cell::Code(cell) => cell.read(cx).editor.clone(),
+            Cell::Markdown(cell) => cell.read(cx).editor.clone(),
+            Cell::Raw(cell) => cell.read(cx).editor.clone(),
+        }
+    }
+
+    /// Whether the cell's source or outputs have changed since it was loaded or last saved.
+    pub fn is_dirty(&self, cx: &App) -> bool {
+        match self {
+            Cell::Code(cell) => cell.read(cx).is_dirty(cx),
+            _ => is_editor_dirty(&self.editor(cx), cx),
+        }
+    }
+
+    pub fn mark_saved(&self, cx: &mut App) {
+        let editor = self.editor(cx);
+        mark_editor_saved(&editor, cx);
+        if let Cell::Code(cell) = self {
+            cell.update(cx, |cell, _| cell.outputs_changed = false);
+        }
+    }
+
+    /// The outputs of a code cell as they're written to the notebook file.
+    pub fn raw_outputs(&self, cx: &App) -> Option<Vec<Value>> {
+        match self {
+            Cell::Code(cell) => Some(cell.read(cx).raw_outputs.clone()),
+            _ => None,
+        }
+    }
+
+    /// Converts the cell back into its nbformat representation, using the
+    /// current contents of its editor as the source.
+    pub fn to_nbformat(&self, cx: &App) -> nbformat::v4::Cell {
+        let source = source_lines(&editor_text(&self.editor(cx), cx));
+        match self {
+            Cell::Code(cell) => {
+                let cell = cell.read(cx);
+                nbformat::v4::Cell::Code {
+                    id: cell.id.clone(),
+                    metadata: cell.metadata.clone(),
+                    execution_count: cell.execution_count,
+                    source,
+                    outputs: cell
+                        .raw_outputs
+                        .iter()
+                        .filter_map(|output| serde_json::from_value(output.clone()).log_err())
+                        .collect(),
+                }
+            }
+            Cell::Markdown(cell) => {
+                let cell = cell.read(cx);
+                nbformat::v4::Cell::Markdown {
+                    id: cell.id.clone(),
+                    metadata: cell.metadata.clone(),
+                    source,
+                    attachments: cell.attachments.clone(),
+                }
+            }
+            Cell::Raw(cell) => {
+                let cell = cell.read(cx);
+                nbformat::v4::Cell::Raw {
+                    id: cell.id.clone(),
+                    metadata: cell.metadata.clone(),
+                    source,
+                }
+            }
+        }
+    }
 }
 
 pub trait RenderableCell: Render {
@@ -332,13 +459,63 @@ pub trait RunnableCell: RenderableCell {
 pub struct MarkdownCell {
     id: CellId,
     metadata: CellMetadata,
+    attachments: Option<serde_json::Value>,
     image_cache: Entity<RetainAllImageCache>,
     source: String,
+    editor: Entity<Editor>,
+    editing: bool,
     parsed_markdown: Option<markdown_preview::markdown_elements::ParsedMarkdown>,
     markdown_parsing_task: Task<()>,
     selected: bool,
     cell_position: Option<CellPosition>,
     languages: Arc<LanguageRegistry>,
+    _editor_subscription: Subscription,
+}
+
+impl MarkdownCell {
+    pub fn is_editing(&self) -> bool {
+        self.editing
+    }
+
+    /// Swaps the rendered markdown for an editor containing the cell's source.
+    pub fn start_editing(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.editing = true;
+        self.editor.focus_handle(cx).focus(window);
+        cx.notify();
+    }
+
+    /// Re-renders the markdown from the editor's contents.
+    pub fn stop_editing(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if !self.editing {
+            return;
+        }
+        self.editing = false;
+        let source = editor_text(&self.editor, cx);
+        if source != self.source {
+            self.source = source;
+            self.parse_markdown(window, cx);
+        }
+        cx.notify();
+    }
+
+    fn parse_markdown(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let languages = self.languages.clone();
+        let source = self.source.clone();
+
+        self.markdown_parsing_task = cx.spawn_in(window, async move |this, cx| {
+            let parsed_markdown = cx
+                .background_spawn(
+                    async move { parse_markdown(&source, None, Some(languages)).await },
+                )
+                .await;
+
+            this.update(cx, |cell, cx| {
+                cell.parsed_markdown = Some(parsed_markdown);
+                cx.notify();
+            })
+            .log_err();
+        });
+    }
 }
 
 impl RenderableCell for MarkdownCell {
@@ -385,13 +562,53 @@ impl RenderableCell for MarkdownCell {
 
 impl Render for MarkdownCell {
     fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
-        let Some(parsed) = self.parsed_markdown.as_ref() else {
-            return div();
+        let content = if self.editing {
+            div()
+                .py_1p5()
+                .w_full()
+                .child(
+                    div()
+                        .flex()
+                        .size_full()
+                        .flex_1()
+                        .py_3()
+                        .px_5()
+                        .rounded_lg()
+                        .border_1()
+                        .border_color(cx.theme().colors().border)
+                        .bg(cx.theme().colors().editor_background)
+                        .child(div().w_full().child(self.editor.clone())),
+                )
+                .into_any_element()
+        } else {
+            let mut markdown_render_context =
+                markdown_preview::markdown_renderer::RenderContext::new(None, window, cx);
+
+            v_flex()
+                .id("markdown-content")
+                .image_cache(self.image_cache.clone())
+                .size_full()
+                .flex_1()
+                .p_3()
+                .font_ui(cx)
+                .text_size(TextSize::Default.rems(cx))
+                .when_some(self.parsed_markdown.as_ref(), |this, parsed| {
+                    this.children(parsed.children.iter().map(|child| {
+                        div().relative().child(
+                            div()
+                                .relative()
+                                .child(render_markdown_block(child, &mut markdown_render_context)),
+                        )
+                    }))
+                })
+                .on_click(cx.listener(|this, event: &ClickEvent, window, cx| {
+                    if event.up.click_count >= 2 {
+                        this.start_editing(window, cx);
+                    }
+                }))
+                .into_any_element()
         };
 
-        let mut markdown_render_context =
-            markdown_preview::markdown_renderer::RenderContext::new(None, window, cx);
-
         v_flex()
             .size_full()
             // TODO: Move base cell render into trait impl so we don't have to repeat this
@@ -405,20 +622,7 @@ impl Render for MarkdownCell {
                     .gap(DynamicSpacing::Base08.rems(cx))
                     .bg(self.selected_bg_color(window, cx))
                     .child(self.gutter(window, cx))
-                    .child(
-                        v_flex()
-                            .image_cache(self.image_cache.clone())
-                            .size_full()
-                            .flex_1()
-                            .p_3()
-                            .font_ui(cx)
-                            .text_size(TextSize::Default.rems(cx))
-                            .children(parsed.children.iter().map(|child| {
-                                div().relative().child(div().relative().child(
-                                    render_markdown_block(child, &mut markdown_render_context),
-                                ))
-                            })),
-                    ),
+                    .child(content),
             )
             // TODO: Move base cell render into trait impl so we don't have to repeat this
             .children(self.cell_position_spacer(false, window, cx))
@@ -432,21 +636,43 @@ pub struct CodeCell {
     source: String,
     editor: Entity<editor::Editor>,
     outputs: Vec<Output>,
+    /// The outputs as JSON, as they were read from the notebook file or produced by running
+    /// the cell, written back on save.
+    raw_outputs: Vec<Value>,
+    /// Set when outputs were added since the cell was loaded or last saved.
+    outputs_changed: bool,
     selected: bool,
     cell_position: Option<CellPosition>,
-    language_task: Task<()>,
 }
 
 impl CodeCell {
     pub fn is_dirty(&self, cx: &App) -> bool {
-        self.editor.read(cx).buffer().read(cx).is_dirty(cx)
+        self.outputs_changed || is_editor_dirty(&self.editor, cx)
     }
+
     pub fn has_outputs(&self) -> bool {
         !self.outputs.is_empty()
     }
 
+    /// Adds an output produced by running the cell, which is saved with the notebook.
+    pub fn append_output(
+        &mut self,
+        output: nbformat::v4::Output,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if let Some(raw_output) = serde_json::to_value(&output).log_err() {
+            self.raw_outputs.push(raw_output);
+        }
+        self.outputs
+            .extend(convert_outputs(&vec![output], window, cx));
+        self.outputs_changed = true;
+        cx.notify();
+    }
+
     pub fn clear_outputs(&mut self) {
         self.outputs.clear();
+        self.raw_outputs.clear();
     }
 
     fn output_control(&self) -> Option<CellControlType> {
@@ -669,6 +895,7 @@ pub struct RawCell {
     id: CellId,
     metadata: CellMetadata,
     source: String,
+    editor: Entity<Editor>,
     selected: bool,
     cell_position: Option<CellPosition>,
 }
@@ -732,9 +959,7 @@ impl Render for RawCell {
                             .size_full()
                             .flex_1()
                             .p_3()
-                            .font_ui(cx)
-                            .text_size(TextSize::Default.rems(cx))
-                            .child(self.source.clone()),
+                            .child(div().w_full().child(self.editor.clone())),
                     ),
             )
             // TODO: Move base cell render into trait impl so we don't have to repeat this
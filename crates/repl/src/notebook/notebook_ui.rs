@@ -1,28 +1,36 @@
 #![allow(unused, dead_code)]
 use std::future::Future;
+use std::time::Duration;
 use std::{path::PathBuf, sync::Arc};
 
 use anyhow::{Context as _, Result};
 use client::proto::ViewId;
 use collections::HashMap;
+use editor::EditorEvent;
 use feature_flags::{FeatureFlagAppExt as _, NotebookFeatureFlag};
-use futures::FutureExt;
+use fs::Fs;
 use futures::future::Shared;
+use futures::{FutureExt, StreamExt as _};
 use gpui::{
     AnyElement, App, Entity, EventEmitter, FocusHandle, Focusable, ListScrollEvent, ListState,
-    Point, Task, actions, list, prelude::*,
+    Point, Subscription, Task, actions, list, prelude::*,
 };
-use language::{Language, LanguageRegistry};
+use language::{Language, LanguageRegistry, LineEnding, Rope};
 use project::{Project, ProjectEntryId, ProjectPath};
 use ui::{Tooltip, prelude::*};
+use util::ResultExt as _;
 use workspace::item::{ItemEvent, TabContentParams};
 use workspace::searchable::SearchableItemHandle;
 use workspace::{Item, ItemHandle, Pane, ProjectItem, ToolbarItemLocation};
 use workspace::{ToolbarItemEvent, ToolbarItemView};
 
-use super::{Cell, CellPosition, RenderableCell};
+use super::{
+    Cell, CellPosition, NotebookContents, RenderableCell, new_cell, parse_notebook,
+    serialize_notebook,
+};
 
 use nbformat::v4::CellId;
+use nbformat::v4::CellType;
 use nbformat::v4::Metadata as NotebookMetadata;
 
 actions!(
@@ -35,6 +43,7 @@ actions!(
         MoveCellDown,
         AddMarkdownBlock,
         AddCodeBlock,
+        DeleteCell,
     ]
 );
 
@@ -46,6 +55,8 @@ pub(crate) const GUTTER_WIDTH: f32 = 19.0;
 pub(crate) const CODE_BLOCK_INSET: f32 = MEDIUM_SPACING_SIZE;
 pub(crate) const CONTROL_SIZE: f32 = 20.0;
 
+const FILE_WATCH_LATENCY: Duration = Duration::from_millis(100);
+
 pub fn init(cx: &mut App) {
     if cx.has_flag::<NotebookFeatureFlag>() || std::env::var("LOCAL_NOTEBOOK_DEV").is_ok() {
         workspace::register_project_item::<NotebookEditor>(cx);
@@ -70,6 +81,7 @@ pub struct NotebookEditor {
 
     focus_handle: FocusHandle,
     notebook_item: Entity<NotebookItem>,
+    notebook_language: Shared<Task<Option<Arc<Language>>>>,
 
     remote_id: Option<ViewId>,
     cell_list: ListState,
@@ -77,6 +89,15 @@ pub struct NotebookEditor {
     selected_cell_index: usize,
     cell_order: Vec<CellId>,
     cell_map: HashMap<CellId, Cell>,
+    cell_subscriptions: HashMap<CellId, Subscription>,
+
+    /// Set when cells were added, removed, reordered or had their outputs
+    /// cleared since the notebook was last loaded or saved. Edits to cell
+    /// sources are tracked by the cells' buffers.
+    structure_dirty: bool,
+    /// Set when the file changed on disk while there were unsaved edits.
+    has_conflict: bool,
+    _notebook_item_subscription: Subscription,
 }
 
 impl NotebookEditor {
@@ -89,38 +110,11 @@ impl NotebookEditor {
         let focus_handle = cx.focus_handle();
 
         let languages = project.read(cx).languages().clone();
-        let language_name = notebook_item.read(cx).language_name();
-
-        let notebook_language = notebook_item.read(cx).notebook_language();
-        let notebook_language = cx
-            .spawn_in(window, async move |_, _| notebook_language.await)
-            .shared();
-
-        let mut cell_order = vec![]; // Vec<CellId>
-        let mut cell_map = HashMap::default(); // HashMap<CellId, Cell>
-
-        for (index, cell) in notebook_item
-            .read(cx)
-            .notebook
-            .clone()
-            .cells
-            .iter()
-            .enumerate()
-        {
-            let cell_id = cell.id();
-            cell_order.push(cell_id.clone());
-            cell_map.insert(
-                cell_id.clone(),
-                Cell::load(cell, &languages, notebook_language.clone(), window, cx),
-            );
-        }
+        let notebook_language = Self::load_notebook_language(&notebook_item, window, cx);
 
         let notebook_handle = cx.entity().downgrade();
-        let cell_count = cell_order.len();
-
-        let this = cx.entity();
         let cell_list = ListState::new(
-            cell_count,
+            0,
             gpui::ListAlignment::Top,
             px(1000.),
             move |ix, window, cx| {
@@ -143,17 +137,156 @@ impl NotebookEditor {
             },
         );
 
-        Self {
+        let notebook_item_subscription =
+            cx.subscribe_in(&notebook_item, window, Self::handle_notebook_item_event);
+
+        let mut this = Self {
             project,
-            languages: languages.clone(),
+            languages,
             focus_handle,
             notebook_item,
+            notebook_language,
             remote_id: None,
             cell_list,
             selected_cell_index: 0,
-            cell_order: cell_order.clone(),
-            cell_map: cell_map.clone(),
+            cell_order: Vec::new(),
+            cell_map: HashMap::default(),
+            cell_subscriptions: HashMap::default(),
+            structure_dirty: false,
+            has_conflict: false,
+            _notebook_item_subscription: notebook_item_subscription,
+        };
+        this.load_cells(window, cx);
+        this
+    }
+
+    fn load_notebook_language(
+        notebook_item: &Entity<NotebookItem>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Shared<Task<Option<Arc<Language>>>> {
+        let notebook_language = notebook_item.read(cx).notebook_language();
+        cx.spawn_in(window, async move |_, _| notebook_language.await)
+            .shared()
+    }
+
+    /// Replaces all cells with the ones in the notebook item, discarding any
+    /// unsaved edits.
+    fn load_cells(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.notebook_language = Self::load_notebook_language(&self.notebook_item, window, cx);
+        self.cell_order.clear();
+        self.cell_map.clear();
+        self.cell_subscriptions.clear();
+
+        let contents = self.notebook_item.read(cx).contents.clone();
+        for cell in &contents.notebook.cells {
+            let raw_outputs = match cell {
+                nbformat::v4::Cell::Code { id, .. } => contents.outputs.get(id),
+                _ => None,
+            };
+            let cell = Cell::load(
+                cell,
+                raw_outputs,
+                &self.languages,
+                self.notebook_language.clone(),
+                window,
+                cx,
+            );
+            let cell_id = cell.id(cx);
+            self.insert_cell(self.cell_order.len(), cell_id, cell, window, cx);
+        }
+
+        self.cell_list.reset(self.cell_order.len());
+        self.selected_cell_index = self
+            .selected_cell_index
+            .min(self.cell_order.len().saturating_sub(1));
+        self.structure_dirty = false;
+        self.has_conflict = false;
+        cx.emit(NotebookEditorEvent::TitleChanged);
+        cx.notify();
+    }
+
+    fn insert_cell(
+        &mut self,
+        index: usize,
+        cell_id: CellId,
+        cell: Cell,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let subscription = cx.subscribe_in(
+            &cell.editor(cx),
+            window,
+            |_, _, event: &EditorEvent, _, cx| {
+                if let EditorEvent::BufferEdited = event {
+                    cx.emit(NotebookEditorEvent::Edited);
+                }
+            },
+        );
+        self.cell_subscriptions
+            .insert(cell_id.clone(), subscription);
+        self.cell_order.insert(index, cell_id.clone());
+        self.cell_map.insert(cell_id, cell);
+    }
+
+    fn handle_notebook_item_event(
+        &mut self,
+        _: &Entity<NotebookItem>,
+        event: &NotebookItemEvent,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        match event {
+            NotebookItemEvent::ChangedOnDisk => {
+                if self.is_dirty(cx) {
+                    self.has_conflict = true;
+                    cx.emit(NotebookEditorEvent::TitleChanged);
+                    cx.notify();
+                } else {
+                    self.load_cells(window, cx);
+                }
+            }
+            NotebookItemEvent::Reloaded => self.load_cells(window, cx),
+            NotebookItemEvent::FileHandleChanged => {
+                cx.emit(NotebookEditorEvent::TitleChanged);
+                cx.notify();
+            }
+        }
+    }
+
+    /// Builds the notebook as it should be written to disk, preserving the
+    /// notebook metadata read from the file and the cells' outputs.
+    pub fn to_notebook(&self, cx: &App) -> NotebookContents {
+        let mut contents = self.notebook_item.read(cx).contents.clone();
+        contents.notebook.cells.clear();
+        contents.outputs.clear();
+        for cell in self
+            .cell_order
+            .iter()
+            .filter_map(|cell_id| self.cell_map.get(cell_id))
+        {
+            if let Some(raw_outputs) = cell.raw_outputs(cx) {
+                contents.outputs.insert(cell.id(cx), raw_outputs);
+            }
+            contents.notebook.cells.push(cell.to_nbformat(cx));
+        }
+        contents
+    }
+
+    fn did_save(&mut self, cx: &mut Context<Self>) {
+        for cell in self.cell_map.values() {
+            cell.mark_saved(cx);
         }
+        self.structure_dirty = false;
+        self.has_conflict = false;
+        cx.emit(NotebookEditorEvent::Saved);
+        cx.notify();
+    }
+
+    fn mark_structure_dirty(&mut self, cx: &mut Context<Self>) {
+        self.structure_dirty = true;
+        cx.emit(NotebookEditorEvent::Edited);
+        cx.notify();
     }
 
     fn has_outputs(&self, window: &mut Window, cx: &mut Context<Self>) -> bool {
@@ -167,6 +300,9 @@ impl NotebookEditor {
     }
 
     fn clear_outputs(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if !self.has_outputs(window, cx) {
+            return;
+        }
         for cell in self.cell_map.values() {
             if let Cell::Code(code_cell) = cell {
                 code_cell.update(cx, |cell, _cx| {
@@ -174,6 +310,7 @@ impl NotebookEditor {
                 });
             }
         }
+        self.mark_structure_dirty(cx);
     }
 
     fn run_cells(&mut self, window: &mut Window, cx: &mut Context<Self>) {
@@ -185,19 +322,81 @@ impl NotebookEditor {
     }
 
     fn move_cell_up(&mut self, window: &mut Window, cx: &mut Context<Self>) {
-        println!("Move cell up triggered");
+        let index = self.selected_cell_index;
+        if index == 0 || index >= self.cell_order.len() {
+            return;
+        }
+        self.cell_order.swap(index - 1, index);
+        self.cell_list.splice(index - 1..index + 1, 2);
+        self.set_selected_index(index - 1, true, window, cx);
+        self.mark_structure_dirty(cx);
     }
 
     fn move_cell_down(&mut self, window: &mut Window, cx: &mut Context<Self>) {
-        println!("Move cell down triggered");
+        let index = self.selected_cell_index;
+        if index + 1 >= self.cell_order.len() {
+            return;
+        }
+        self.cell_order.swap(index, index + 1);
+        self.cell_list.splice(index..index + 2, 2);
+        self.set_selected_index(index + 1, true, window, cx);
+        self.mark_structure_dirty(cx);
     }
 
     fn add_markdown_block(&mut self, window: &mut Window, cx: &mut Context<Self>) {
-        println!("Add markdown block triggered");
+        if let Some(Cell::Markdown(cell)) = self.add_cell(CellType::Markdown, window, cx) {
+            cell.update(cx, |cell, cx| cell.start_editing(window, cx));
+        }
     }
 
     fn add_code_block(&mut self, window: &mut Window, cx: &mut Context<Self>) {
-        println!("Add code block triggered");
+        if let Some(cell) = self.add_cell(CellType::Code, window, cx) {
+            cell.editor(cx).focus_handle(cx).focus(window);
+        }
+    }
+
+    /// Inserts an empty cell below the selected one and selects it.
+    fn add_cell(
+        &mut self,
+        cell_type: CellType,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Option<Cell> {
+        let cell = new_cell(cell_type).log_err()?;
+        let cell = Cell::load(
+            &cell,
+            None,
+            &self.languages,
+            self.notebook_language.clone(),
+            window,
+            cx,
+        );
+
+        let index = if self.cell_order.is_empty() {
+            0
+        } else {
+            (self.selected_cell_index + 1).min(self.cell_order.len())
+        };
+        self.insert_cell(index, cell.id(cx), cell.clone(), window, cx);
+        self.cell_list.splice(index..index, 1);
+        self.set_selected_index(index, true, window, cx);
+        self.mark_structure_dirty(cx);
+        Some(cell)
+    }
+
+    fn delete_cell(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let index = self.selected_cell_index;
+        if index >= self.cell_order.len() {
+            return;
+        }
+        let cell_id = self.cell_order.remove(index);
+        self.cell_map.remove(&cell_id);
+        self.cell_subscriptions.remove(&cell_id);
+        self.cell_list.splice(index..index + 1, 0);
+
+        let index = index.min(self.cell_order.len().saturating_sub(1));
+        self.set_selected_index(index, true, window, cx);
+        self.mark_structure_dirty(cx);
     }
 
     fn cell_count(&self) -> usize {
@@ -397,6 +596,21 @@ impl NotebookEditor {
                                 .on_click(|_, window, cx| {
                                     window.dispatch_action(Box::new(MoveCellDown), cx);
                                 }),
+                            )
+                            .child(
+                                Self::render_notebook_control(
+                                    "delete-cell",
+                                    IconName::Trash,
+                                    window,
+                                    cx,
+                                )
+                                .disabled(self.cell_order.is_empty())
+                                .tooltip(move |window, cx| {
+                                    Tooltip::for_action("Delete cell", &DeleteCell, window, cx)
+                                })
+                                .on_click(|_, window, cx| {
+                                    window.dispatch_action(Box::new(DeleteCell), cx);
+                                }),
                             ),
                     )
                     .child(
@@ -520,6 +734,7 @@ impl Render for NotebookEditor {
             .on_action(
                 cx.listener(|this, &AddCodeBlock, window, cx| this.add_code_block(window, cx)),
             )
+            .on_action(cx.listener(|this, &DeleteCell, window, cx| this.delete_cell(window, cx)))
             .on_action(cx.listener(Self::select_next))
             .on_action(cx.listener(Self::select_previous))
             .on_action(cx.listener(Self::select_first))
@@ -549,17 +764,34 @@ impl Focusable for NotebookEditor {
     }
 }
 
+pub enum NotebookItemEvent {
+    /// The file was modified outside of Zed and the new contents were loaded.
+    ChangedOnDisk,
+    /// The notebook was explicitly reloaded from disk.
+    Reloaded,
+    /// The notebook was saved to a different path or its file was deleted.
+    FileHandleChanged,
+}
+
 // Intended to be a NotebookBuffer
 pub struct NotebookItem {
     path: PathBuf,
     project_path: ProjectPath,
     languages: Arc<LanguageRegistry>,
+    fs: Arc<dyn Fs>,
     // Raw notebook data
-    notebook: nbformat::v4::Notebook,
+    contents: NotebookContents,
     // Store our version of the notebook in memory (cell_order, cell_map)
-    id: ProjectEntryId,
+    id: Option<ProjectEntryId>,
+    /// The file contents as of the last load or save, used to ignore file
+    /// events caused by our own writes.
+    disk_text: String,
+    deleted: bool,
+    _file_watcher: Task<()>,
 }
 
+impl EventEmitter<NotebookItemEvent> for NotebookItem {}
+
 impl project::ProjectItem for NotebookItem {
     fn try_open(
         project: &Entity<Project>,
@@ -577,36 +809,28 @@ impl project::ProjectItem for NotebookItem {
                     .read_with(cx, |project, cx| project.absolute_path(&path, cx))?
                     .ok_or_else(|| anyhow::anyhow!("Failed to find the absolute path"))?;
 
-                // todo: watch for changes to the file
                 let file_content = fs.load(&abs_path.as_path()).await?;
-                let notebook = nbformat::parse_notebook(&file_content);
-
-                let notebook = match notebook {
-                    Ok(nbformat::Notebook::V4(notebook)) => notebook,
-                    // 4.1 - 4.4 are converted to 4.5
-                    Ok(nbformat::Notebook::Legacy(legacy_notebook)) => {
-                        // TODO: Decide if we want to mutate the notebook by including Cell IDs
-                        // and any other conversions
-                        let notebook = nbformat::upgrade_legacy_notebook(legacy_notebook)?;
-                        notebook
-                    }
-                    // Bad notebooks and notebooks v4.0 and below are not supported
-                    Err(e) => {
-                        anyhow::bail!("Failed to parse notebook: {:?}", e);
-                    }
-                };
+                let contents = parse_notebook(&file_content)?;
 
                 let id = project
                     .update(cx, |project, cx| project.entry_for_path(&path, cx))?
                     .context("Entry not found")?
                     .id;
 
-                cx.new(|_| NotebookItem {
-                    path: abs_path,
-                    project_path: path,
-                    languages,
-                    notebook,
-                    id,
+                cx.new(|cx| {
+                    let mut this = NotebookItem {
+                        path: abs_path,
+                        project_path: path,
+                        languages,
+                        fs,
+                        contents,
+                        id: Some(id),
+                        disk_text: file_content,
+                        deleted: false,
+                        _file_watcher: Task::ready(()),
+                    };
+                    this.watch_file(cx);
+                    this
                 })
             }))
         } else {
@@ -615,7 +839,7 @@ impl project::ProjectItem for NotebookItem {
     }
 
     fn entry_id(&self, _: &App) -> Option<ProjectEntryId> {
-        Some(self.id)
+        self.id
     }
 
     fn project_path(&self, _: &App) -> Option<ProjectPath> {
@@ -629,12 +853,14 @@ impl project::ProjectItem for NotebookItem {
 
 impl NotebookItem {
     pub fn language_name(&self) -> Option<String> {
-        self.notebook
+        self.contents
+            .notebook
             .metadata
             .language_info
             .as_ref()
             .map(|l| l.name.clone())
             .or(self
+                .contents
                 .notebook
                 .metadata
                 .kernelspec
@@ -654,8 +880,162 @@ impl NotebookItem {
             }
         }
     }
+
+    pub fn is_deleted(&self) -> bool {
+        self.deleted
+    }
+
+    /// Watches the notebook's file, picking up changes made outside of Zed.
+    fn watch_file(&mut self, cx: &mut Context<Self>) {
+        let fs = self.fs.clone();
+        let path = self.path.clone();
+        self._file_watcher = cx.spawn(async move |this, cx| {
+            let (events, _watcher) = fs.watch(&path, FILE_WATCH_LATENCY).await;
+            futures::pin_mut!(events);
+
+            while events.next().await.is_some() {
+                let Ok(text) = fs.load(&path).await else {
+                    if !fs.is_file(&path).await {
+                        let updated = this.update(cx, |this, cx| {
+                            if !this.deleted {
+                                this.deleted = true;
+                                cx.emit(NotebookItemEvent::FileHandleChanged);
+                            }
+                        });
+                        if updated.is_err() {
+                            break;
+                        }
+                    }
+                    continue;
+                };
+
+                let updated = this.update(cx, |this, cx| {
+                    if this.deleted {
+                        this.deleted = false;
+                        cx.emit(NotebookItemEvent::FileHandleChanged);
+                    }
+                    if text == this.disk_text {
+                        return;
+                    }
+                    match parse_notebook(&text) {
+                        Ok(contents) => {
+                            this.contents = contents;
+                            this.disk_text = text;
+                            cx.emit(NotebookItemEvent::ChangedOnDisk);
+                        }
+                        // The file may be mid-write; wait for the next event.
+                        Err(error) => log::debug!("ignoring unparsable notebook change: {error}"),
+                    }
+                });
+                if updated.is_err() {
+                    break;
+                }
+            }
+        });
+    }
+
+    /// Writes the given notebook to this item's file.
+    pub fn save(&mut self, contents: NotebookContents, cx: &mut Context<Self>) -> Task<Result<()>> {
+        let text = match serialize_notebook(&contents) {
+            Ok(text) => text,
+            Err(error) => return Task::ready(Err(error)),
+        };
+        // The file watcher can see the write before it completes, so expect the new text
+        // before writing to keep it from being taken for an external change.
+        let previous_disk_text = std::mem::replace(&mut self.disk_text, text.clone());
+        let fs = self.fs.clone();
+        let path = self.path.clone();
+        cx.spawn(async move |this, cx| {
+            if let Err(error) = fs.atomic_write(path, text).await {
+                this.update(cx, |this, _| this.disk_text = previous_disk_text)?;
+                return Err(error);
+            }
+            this.update(cx, |this, cx| {
+                this.contents = contents;
+                if this.deleted {
+                    this.deleted = false;
+                    cx.emit(NotebookItemEvent::FileHandleChanged);
+                }
+            })
+        })
+    }
+
+    /// Writes the given notebook to a new path and makes that path this item's file.
+    ///
+    /// The file is written through its worktree, so that the worktree has an
+    /// entry for it by the time this item switches over to it.
+    pub fn save_as(
+        &mut self,
+        contents: NotebookContents,
+        project: Entity<Project>,
+        project_path: ProjectPath,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        let Some(abs_path) = project.read(cx).absolute_path(&project_path, cx) else {
+            return Task::ready(Err(anyhow::anyhow!(
+                "failed to find the absolute path of {project_path:?}"
+            )));
+        };
+        let Some(worktree) = project
+            .read(cx)
+            .worktree_for_id(project_path.worktree_id, cx)
+        else {
+            return Task::ready(Err(anyhow::anyhow!(
+                "failed to find the worktree of {project_path:?}"
+            )));
+        };
+        let text = match serialize_notebook(&contents) {
+            Ok(text) => text,
+            Err(error) => return Task::ready(Err(error)),
+        };
+        let write = worktree.update(cx, |worktree, cx| {
+            worktree.write_file(
+                &project_path.path,
+                Rope::from(text.as_str()),
+                LineEnding::Unix,
+                cx,
+            )
+        });
+        cx.spawn(async move |this, cx| {
+            let file = write.await?;
+            this.update(cx, |this, cx| {
+                this.path = abs_path;
+                this.project_path = project_path;
+                this.id = file.entry_id;
+                this.contents = contents;
+                this.disk_text = text;
+                this.deleted = false;
+                this.watch_file(cx);
+                cx.emit(NotebookItemEvent::FileHandleChanged);
+            })
+        })
+    }
+
+    /// Re-reads the notebook from disk, discarding any unsaved edits in its editors.
+    pub fn reload(&mut self, cx: &mut Context<Self>) -> Task<Result<()>> {
+        let fs = self.fs.clone();
+        let path = self.path.clone();
+        cx.spawn(async move |this, cx| {
+            let text = fs.load(&path).await?;
+            let contents = parse_notebook(&text)?;
+            this.update(cx, |this, cx| {
+                this.contents = contents;
+                this.disk_text = text;
+                this.deleted = false;
+                cx.emit(NotebookItemEvent::Reloaded);
+            })
+        })
+    }
+}
+
+pub enum NotebookEditorEvent {
+    Edited,
+    Saved,
+    TitleChanged,
 }
 
+impl EventEmitter<NotebookEditorEvent> for NotebookEditor {}
+
 impl EventEmitter<()> for NotebookEditor {}
 
 // pub struct NotebookControls {
@@ -704,7 +1084,16 @@ impl EventEmitter<()> for NotebookEditor {}
 // }
 
 impl Item for NotebookEditor {
-    type Event = ();
+    type Event = NotebookEditorEvent;
+
+    fn to_item_events(event: &Self::Event, mut f: impl FnMut(ItemEvent)) {
+        match event {
+            NotebookEditorEvent::Edited => f(ItemEvent::Edit),
+            NotebookEditorEvent::Saved | NotebookEditorEvent::TitleChanged => {
+                f(ItemEvent::UpdateTab)
+            }
+        }
+    }
 
     fn clone_on_split(
         &self,
@@ -775,49 +1164,67 @@ impl Item for NotebookEditor {
         // TODO
     }
 
-    // TODO
     fn can_save(&self, _cx: &App) -> bool {
-        false
+        true
     }
-    // TODO
+
+    fn can_save_as(&self, _cx: &App) -> bool {
+        true
+    }
+
     fn save(
         &mut self,
         _format: bool,
         _project: Entity<Project>,
-        _window: &mut Window,
-        _cx: &mut Context<Self>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
     ) -> Task<Result<()>> {
-        unimplemented!("save() must be implemented if can_save() returns true")
+        let notebook = self.to_notebook(cx);
+        let save = self
+            .notebook_item
+            .update(cx, |item, cx| item.save(notebook, cx));
+        cx.spawn_in(window, async move |this, cx| {
+            save.await?;
+            this.update(cx, |this, cx| this.did_save(cx))
+        })
     }
 
-    // TODO
     fn save_as(
         &mut self,
-        _project: Entity<Project>,
-        _path: ProjectPath,
-        _window: &mut Window,
-        _cx: &mut Context<Self>,
+        project: Entity<Project>,
+        path: ProjectPath,
+        window: &mut Window,
+        cx: &mut Context<Self>,
     ) -> Task<Result<()>> {
-        unimplemented!("save_as() must be implemented if can_save() returns true")
+        let notebook = self.to_notebook(cx);
+        let save = self
+            .notebook_item
+            .update(cx, |item, cx| item.save_as(notebook, project, path, cx));
+        cx.spawn_in(window, async move |this, cx| {
+            save.await?;
+            this.update(cx, |this, cx| this.did_save(cx))
+        })
     }
-    // TODO
+
     fn reload(
         &mut self,
         _project: Entity<Project>,
         _window: &mut Window,
-        _cx: &mut Context<Self>,
+        cx: &mut Context<Self>,
     ) -> Task<Result<()>> {
-        unimplemented!("reload() must be implemented if can_save() returns true")
+        self.notebook_item.update(cx, |item, cx| item.reload(cx))
     }
 
     fn is_dirty(&self, cx: &App) -> bool {
-        self.cell_map.values().any(|cell| {
-            if let Cell::Code(code_cell) = cell {
-                code_cell.read(cx).is_dirty(cx)
-            } else {
-                false
-            }
-        })
+        self.structure_dirty || self.cell_map.values().any(|cell| cell.is_dirty(cx))
+    }
+
+    fn has_conflict(&self, _cx: &App) -> bool {
+        self.has_conflict
+    }
+
+    fn has_deleted_file(&self, cx: &App) -> bool {
+        self.notebook_item.read(cx).is_deleted()
     }
 }
 
@@ -840,3 +1247,185 @@ impl ProjectItem for NotebookEditor {
         Self::new(project, item, window, cx)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::{TestAppContext, VisualTestContext};
+    use project::FakeFs;
+    use serde_json::{Value, json};
+    use settings::SettingsStore;
+    use std::path::Path;
+    use util::path;
+
+    const NOTEBOOK: &str = include_str!("fixtures/outputs.ipynb");
+
+    fn init_test(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+            theme::init(theme::LoadThemes::JustBase, cx);
+            language::init(cx);
+            editor::init_settings(cx);
+            workspace::init_settings(cx);
+            Project::init_settings(cx);
+        });
+    }
+
+    async fn open_notebook(
+        cx: &mut TestAppContext,
+    ) -> (
+        Arc<FakeFs>,
+        Entity<Project>,
+        Entity<NotebookEditor>,
+        &mut VisualTestContext,
+    ) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(path!("/dir"), json!({ "notebook.ipynb": NOTEBOOK }))
+            .await;
+        let project = Project::test(fs.clone(), [path!("/dir").as_ref()], cx).await;
+        let project_path = project.read_with(cx, |project, cx| ProjectPath {
+            worktree_id: project.worktrees(cx).next().unwrap().read(cx).id(),
+            path: Path::new("notebook.ipynb").into(),
+        });
+        let item = cx
+            .update(|cx| {
+                <NotebookItem as project::ProjectItem>::try_open(&project, &project_path, cx)
+            })
+            .unwrap()
+            .await
+            .unwrap();
+        let (editor, cx) =
+            cx.add_window_view(|window, cx| NotebookEditor::new(project.clone(), item, window, cx));
+        cx.run_until_parked();
+        (fs, project, editor, cx)
+    }
+
+    fn cell_sources(editor: &NotebookEditor, cx: &App) -> Vec<String> {
+        editor
+            .cell_order
+            .iter()
+            .map(|cell_id| editor.cell_map[cell_id].editor(cx).read(cx).text(cx))
+            .collect()
+    }
+
+    async fn load_notebook_file(fs: &FakeFs) -> Value {
+        let text = fs
+            .load(Path::new(path!("/dir/notebook.ipynb")))
+            .await
+            .unwrap();
+        serde_json::from_str(&text).unwrap()
+    }
+
+    fn wait_for_file_events(cx: &mut VisualTestContext) {
+        cx.executor().advance_clock(FILE_WATCH_LATENCY * 2);
+        cx.run_until_parked();
+    }
+
+    #[gpui::test]
+    async fn test_save_after_running_cells(cx: &mut TestAppContext) {
+        let (fs, project, editor, cx) = open_notebook(cx).await;
+        let original = load_notebook_file(&fs).await;
+
+        editor.update_in(cx, |editor, window, cx| {
+            let Cell::Code(cell) = &editor.cell_map[&editor.cell_order[0]] else {
+                panic!("expected a code cell");
+            };
+            let output = serde_json::from_value(json!({
+                "output_type": "stream",
+                "name": "stdout",
+                "text": "done\n",
+            }))
+            .unwrap();
+            cell.update(cx, |cell, cx| cell.append_output(output, window, cx));
+            assert!(editor.is_dirty(cx));
+        });
+
+        editor
+            .update_in(cx, |editor, window, cx| {
+                editor.save(false, project.clone(), window, cx)
+            })
+            .await
+            .unwrap();
+        wait_for_file_events(cx);
+
+        // The output read from the file is written back as it was, followed by the new one.
+        let saved = load_notebook_file(&fs).await;
+        let outputs = saved["cells"][0]["outputs"].as_array().unwrap();
+        assert_eq!(outputs.len(), 2);
+        assert_eq!(outputs[0], original["cells"][0]["outputs"][0]);
+        assert_eq!(outputs[1]["output_type"], "stream");
+        assert_eq!(saved["cells"][1], original["cells"][1]);
+
+        // Our own write isn't taken for an external change.
+        editor.read_with(cx, |editor, cx| {
+            assert!(!editor.is_dirty(cx));
+            assert!(!editor.has_conflict(cx));
+            let raw_outputs = editor.cell_map[&editor.cell_order[0]].raw_outputs(cx);
+            assert_eq!(raw_outputs.map(|outputs| outputs.len()), Some(2));
+        });
+    }
+
+    #[gpui::test]
+    async fn test_reload_on_external_change(cx: &mut TestAppContext) {
+        let (fs, _project, editor, cx) = open_notebook(cx).await;
+        editor.read_with(cx, |editor, cx| {
+            assert_eq!(cell_sources(editor, cx)[1], "6 * 7");
+        });
+
+        fs.insert_file(
+            path!("/dir/notebook.ipynb"),
+            NOTEBOOK.replace("6 * 7", "6 * 9").into_bytes(),
+        )
+        .await;
+        wait_for_file_events(cx);
+
+        editor.read_with(cx, |editor, cx| {
+            assert_eq!(cell_sources(editor, cx)[1], "6 * 9");
+            assert!(!editor.is_dirty(cx));
+            assert!(!editor.has_conflict(cx));
+        });
+    }
+
+    #[gpui::test]
+    async fn test_conflict_with_unsaved_edits(cx: &mut TestAppContext) {
+        let (fs, project, editor, cx) = open_notebook(cx).await;
+
+        editor.update_in(cx, |editor, window, cx| {
+            let cell_editor = editor.cell_map[&editor.cell_order[1]].editor(cx);
+            cell_editor.update(cx, |cell_editor, cx| {
+                cell_editor.insert("# edited\n", window, cx);
+            });
+            assert!(editor.is_dirty(cx));
+        });
+
+        fs.insert_file(
+            path!("/dir/notebook.ipynb"),
+            NOTEBOOK.replace("6 * 7", "6 * 9").into_bytes(),
+        )
+        .await;
+        wait_for_file_events(cx);
+
+        // The unsaved edit is kept, and the change on disk is reported as a conflict.
+        editor.read_with(cx, |editor, cx| {
+            assert_eq!(cell_sources(editor, cx)[1], "# edited\n6 * 7");
+            assert!(editor.is_dirty(cx));
+            assert!(editor.has_conflict(cx));
+        });
+
+        editor
+            .update_in(cx, |editor, window, cx| {
+                editor.reload(project.clone(), window, cx)
+            })
+            .await
+            .unwrap();
+        cx.run_until_parked();
+
+        editor.read_with(cx, |editor, cx| {
+            assert_eq!(cell_sources(editor, cx)[1], "6 * 9");
+            assert!(!editor.is_dirty(cx));
+            assert!(!editor.has_conflict(cx));
+        });
+    }
+}
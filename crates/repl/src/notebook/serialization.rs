@@ -0,0 +1,217 @@
+use anyhow::{Context as _, Result};
+use collections::HashMap;
+use nbformat::v4::{Cell, CellId, Notebook};
+use serde::Serialize as _;
+use serde_json::{Value, json};
+
+/// A notebook, along with each code cell's outputs as the JSON they're stored as in the file.
+///
+/// nbformat's output types join multiline text and data into single strings, so outputs are
+/// written back from this JSON to leave them exactly as they were read.
+#[derive(Clone)]
+pub struct NotebookContents {
+    pub notebook: Notebook,
+    pub outputs: HashMap<CellId, Vec<Value>>,
+}
+
+/// Parses the contents of an `.ipynb` file, upgrading notebooks in the v4.1 - v4.4
+/// formats to v4.5 so that every cell has an ID.
+pub fn parse_notebook(text: &str) -> Result<NotebookContents> {
+    let notebook = match nbformat::parse_notebook(text) {
+        Ok(nbformat::Notebook::V4(notebook)) => notebook,
+        // 4.1 - 4.4 are converted to 4.5
+        Ok(nbformat::Notebook::Legacy(legacy_notebook)) => {
+            nbformat::upgrade_legacy_notebook(legacy_notebook)
+                .context("failed to upgrade legacy notebook")?
+        }
+        // Bad notebooks and notebooks v4.0 and below are not supported
+        Err(error) => anyhow::bail!("failed to parse notebook: {error:?}"),
+    };
+
+    // Upgrading a notebook keeps its cells in order, so the cells in the file line up with the
+    // parsed ones.
+    let value = serde_json::from_str::<Value>(text)?;
+    let raw_cells = value
+        .get("cells")
+        .and_then(Value::as_array)
+        .map(Vec::as_slice)
+        .unwrap_or_default();
+    let outputs = notebook
+        .cells
+        .iter()
+        .zip(raw_cells)
+        .filter_map(|(cell, raw_cell)| {
+            let Cell::Code { id, .. } = cell else {
+                return None;
+            };
+            let outputs = raw_cell.get("outputs")?.as_array()?;
+            Some((id.clone(), outputs.clone()))
+        })
+        .collect();
+
+    Ok(NotebookContents { notebook, outputs })
+}
+
+/// Serializes a notebook the same way Jupyter does: keys sorted, one space of
+/// indentation and a trailing newline. Matching Jupyter's output keeps diffs of
+/// notebooks saved from Zed minimal.
+///
+/// Code cells with outputs in `contents.outputs` are written with those outputs, and
+/// others with the outputs in the notebook.
+pub fn serialize_notebook(contents: &NotebookContents) -> Result<String> {
+    let mut value = sort_keys(serde_json::to_value(&contents.notebook)?);
+    if let Some(cells) = value.get_mut("cells").and_then(Value::as_array_mut) {
+        for (cell, cell_value) in contents.notebook.cells.iter().zip(cells) {
+            if let Cell::Code { id, .. } = cell {
+                if let Some(outputs) = contents.outputs.get(id) {
+                    cell_value["outputs"] = Value::Array(outputs.clone());
+                }
+            }
+        }
+    }
+
+    let mut bytes = Vec::new();
+    let formatter = serde_json::ser::PrettyFormatter::with_indent(b" ");
+    let mut serializer = serde_json::Serializer::with_formatter(&mut bytes, formatter);
+    value.serialize(&mut serializer)?;
+    bytes.push(b'\n');
+
+    Ok(String::from_utf8(bytes)?)
+}
+
+fn sort_keys(value: Value) -> Value {
+    match value {
+        Value::Object(map) => {
+            let mut entries = map.into_iter().collect::<Vec<_>>();
+            entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+            Value::Object(
+                entries
+                    .into_iter()
+                    .map(|(key, value)| (key, sort_keys(value)))
+                    .collect(),
+            )
+        }
+        Value::Array(values) => Value::Array(values.into_iter().map(sort_keys).collect()),
+        value => value,
+    }
+}
+
+/// Splits cell source into the list of lines nbformat stores on disk, keeping
+/// the trailing newline on every line but the last.
+pub fn source_lines(source: &str) -> Vec<String> {
+    source.split_inclusive('\n').map(str::to_string).collect()
+}
+
+pub fn new_cell_id() -> CellId {
+    CellId::from(uuid::Uuid::new_v4())
+}
+
+/// Creates an empty cell of the given type with a freshly generated ID.
+pub fn new_cell(cell_type: nbformat::v4::CellType) -> Result<Cell> {
+    let id = new_cell_id().to_string();
+    let value = match cell_type {
+        nbformat::v4::CellType::Code => json!({
+            "cell_type": "code",
+            "id": id,
+            "metadata": {},
+            "execution_count": null,
+            "source": [],
+            "outputs": [],
+        }),
+        nbformat::v4::CellType::Markdown => json!({
+            "cell_type": "markdown",
+            "id": id,
+            "metadata": {},
+            "source": [],
+        }),
+        nbformat::v4::CellType::Raw => json!({
+            "cell_type": "raw",
+            "id": id,
+            "metadata": {},
+            "source": [],
+        }),
+    };
+    Ok(serde_json::from_value(value)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SIMPLE_NOTEBOOK: &str = include_str!("fixtures/simple.ipynb");
+    const OUTPUTS_NOTEBOOK: &str = include_str!("fixtures/outputs.ipynb");
+    const LEGACY_NOTEBOOK: &str = include_str!("fixtures/legacy_v4_4.ipynb");
+
+    fn to_value(text: &str) -> Value {
+        serde_json::from_str(text).unwrap()
+    }
+
+    #[test]
+    fn test_serialization_matches_jupyter_layout() {
+        let contents = parse_notebook(SIMPLE_NOTEBOOK).unwrap();
+        let serialized = serialize_notebook(&contents).unwrap();
+
+        assert!(serialized.starts_with("{\n \"cells\": [\n"));
+        assert!(serialized.ends_with("}\n"));
+        assert_eq!(
+            to_value(&serialized)["cells"],
+            to_value(SIMPLE_NOTEBOOK)["cells"]
+        );
+    }
+
+    #[test]
+    fn test_round_trip_preserves_metadata_ids_and_outputs() {
+        let contents = parse_notebook(OUTPUTS_NOTEBOOK).unwrap();
+        let serialized = serialize_notebook(&contents).unwrap();
+
+        // Outputs keep their multiline text as lists of lines, and the file is written back
+        // byte for byte.
+        assert_eq!(to_value(&serialized), to_value(OUTPUTS_NOTEBOOK));
+        assert_eq!(serialized, OUTPUTS_NOTEBOOK);
+
+        // Serializing a second time must be stable.
+        let reparsed = parse_notebook(&serialized).unwrap();
+        assert_eq!(serialize_notebook(&reparsed).unwrap(), serialized);
+    }
+
+    #[test]
+    fn test_legacy_notebooks_get_cell_ids() {
+        let contents = parse_notebook(LEGACY_NOTEBOOK).unwrap();
+        let saved = to_value(&serialize_notebook(&contents).unwrap());
+
+        assert_eq!(saved["nbformat"], 4);
+        assert_eq!(saved["nbformat_minor"], 5);
+        for cell in saved["cells"].as_array().unwrap() {
+            assert!(cell["id"].as_str().is_some_and(|id| !id.is_empty()));
+        }
+    }
+
+    #[test]
+    fn test_source_lines() {
+        assert_eq!(source_lines(""), Vec::<String>::new());
+        assert_eq!(source_lines("a"), vec!["a"]);
+        assert_eq!(source_lines("a\nb"), vec!["a\n", "b"]);
+        assert_eq!(source_lines("a\nb\n"), vec!["a\n", "b\n"]);
+    }
+
+    #[test]
+    fn test_new_cells_are_serializable() {
+        let mut contents = parse_notebook(SIMPLE_NOTEBOOK).unwrap();
+        contents
+            .notebook
+            .cells
+            .push(new_cell(nbformat::v4::CellType::Markdown).unwrap());
+        contents
+            .notebook
+            .cells
+            .push(new_cell(nbformat::v4::CellType::Code).unwrap());
+
+        let saved = to_value(&serialize_notebook(&contents).unwrap());
+        let cells = saved["cells"].as_array().unwrap();
+        let added = &cells[cells.len() - 2..];
+        assert_eq!(added[0]["cell_type"], "markdown");
+        assert_eq!(added[1]["cell_type"], "code");
+        assert_eq!(added[1]["outputs"], json!([]));
+        assert_ne!(added[0]["id"], added[1]["id"]);
+    }
+}
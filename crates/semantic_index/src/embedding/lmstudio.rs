@@ -1,17 +1,22 @@
 use anyhow::{Context as _, Result};
 use futures::{AsyncReadExt as _, FutureExt, future::BoxFuture};
 use http_client::HttpClient;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 
 use crate::{Embedding, EmbeddingProvider, TextToEmbed};
 
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 pub enum LmStudioEmbeddingModel {
+    #[default]
+    #[serde(rename = "nomic-embed-text")]
     NomicEmbedText,
 }
 
 pub struct LmStudioEmbeddingProvider {
     client: Arc<dyn HttpClient>,
+    api_url: String,
     model: LmStudioEmbeddingModel,
 }
 
@@ -27,8 +32,16 @@ struct LmStudioEmbeddingResponse {
 }
 
 impl LmStudioEmbeddingProvider {
-    pub fn new(client: Arc<dyn HttpClient>, model: LmStudioEmbeddingModel) -> Self {
-        Self { client, model }
+    pub fn new(
+        client: Arc<dyn HttpClient>,
+        api_url: String,
+        model: LmStudioEmbeddingModel,
+    ) -> Self {
+        Self {
+            client,
+            api_url,
+            model,
+        }
     }
 }
 
@@ -47,10 +60,8 @@ impl EmbeddingProvider for LmStudioEmbeddingProvider {
             let request = serde_json::to_string(&request).unwrap();
 
             async {
-                let response = self
-                    .client
-                    .post_json("http://localhost:1234/api/v0/embeddings", request.into())
-                    .await?;
+                let uri = format!("{}/embeddings", self.api_url.trim_end_matches('/'));
+                let response = self.client.post_json(&uri, request.into()).await?;
 
                 let mut body = String::new();
                 response.into_body().read_to_string(&mut body).await?;
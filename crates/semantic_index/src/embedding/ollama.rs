@@ -1,18 +1,24 @@
 use anyhow::{Context as _, Result};
 use futures::{AsyncReadExt as _, FutureExt, future::BoxFuture};
 use http_client::HttpClient;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 
 use crate::{Embedding, EmbeddingProvider, TextToEmbed};
 
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 pub enum OllamaEmbeddingModel {
+    #[default]
+    #[serde(rename = "nomic-embed-text")]
     NomicEmbedText,
+    #[serde(rename = "mxbai-embed-large")]
     MxbaiEmbedLarge,
 }
 
 pub struct OllamaEmbeddingProvider {
     client: Arc<dyn HttpClient>,
+    api_url: String,
     model: OllamaEmbeddingModel,
 }
 
@@ -28,8 +34,12 @@ struct OllamaEmbeddingResponse {
 }
 
 impl OllamaEmbeddingProvider {
-    pub fn new(client: Arc<dyn HttpClient>, model: OllamaEmbeddingModel) -> Self {
-        Self { client, model }
+    pub fn new(client: Arc<dyn HttpClient>, api_url: String, model: OllamaEmbeddingModel) -> Self {
+        Self {
+            client,
+            api_url,
+            model,
+        }
     }
 }
 
@@ -50,10 +60,8 @@ impl EmbeddingProvider for OllamaEmbeddingProvider {
             let request = serde_json::to_string(&request).unwrap();
 
             async {
-                let response = self
-                    .client
-                    .post_json("http://localhost:11434/api/embeddings", request.into())
-                    .await?;
+                let uri = format!("{}/api/embeddings", self.api_url.trim_end_matches('/'));
+                let response = self.client.post_json(&uri, request.into()).await?;
 
                 let mut body = String::new();
                 response.into_body().read_to_string(&mut body).await?;
@@ -4,16 +4,24 @@ mod embedding_index;
 mod indexing;
 mod project_index;
 mod project_index_debug_view;
+mod semantic_index_settings;
 mod summary_backlog;
 mod summary_index;
 mod worktree_index;
 
 use anyhow::{Context as _, Result};
 use collections::HashMap;
+use credentials_provider::CredentialsProvider;
 use fs::Fs;
-use gpui::{App, AppContext as _, AsyncApp, BorrowAppContext, Context, Entity, Global, WeakEntity};
+use gpui::{
+    App, AppContext as _, AsyncApp, BorrowAppContext, Context, Entity, Global, Subscription, Task,
+    WeakEntity,
+};
+use http_client::HttpClientWithUrl;
 use language::LineEnding;
+use language_models::AllLanguageModelSettings;
 use project::{Project, Worktree};
+use settings::{Settings as _, SettingsStore};
 use std::{
     cmp::Ordering,
     path::{Path, PathBuf},
@@ -25,12 +33,129 @@ use workspace::Workspace;
 pub use embedding::*;
 pub use project_index::{LoadedSearchResult, ProjectIndex, SearchResult, Status};
 pub use project_index_debug_view::ProjectIndexDebugView;
+pub use semantic_index_settings::*;
 pub use summary_index::FileSummary;
 
+const OPENAI_API_KEY_VAR: &str = "OPENAI_API_KEY";
+
+pub fn init(http_client: Arc<HttpClientWithUrl>, cx: &mut App) {
+    SemanticIndexSettings::register(cx);
+
+    let mut active_settings = None;
+    let mut load_task = None;
+    update_semantic_db(&http_client, &mut active_settings, &mut load_task, cx);
+    cx.observe_global::<SettingsStore>(move |cx| {
+        update_semantic_db(&http_client, &mut active_settings, &mut load_task, cx);
+    })
+    .detach();
+}
+
+/// Opens the database for the configured embedding model, replacing the
+/// current [`SemanticDb`] whenever the semantic index settings, or the URL of
+/// the local server they embed with, change.
+fn update_semantic_db(
+    http_client: &Arc<HttpClientWithUrl>,
+    active_settings: &mut Option<(SemanticIndexSettings, Option<String>)>,
+    load_task: &mut Option<Task<()>>,
+    cx: &mut App,
+) {
+    let settings = SemanticIndexSettings::get_global(cx);
+    let settings = settings
+        .enabled
+        .then(|| (settings.clone(), local_api_url(settings, cx)));
+    if *active_settings == settings {
+        return;
+    }
+    *active_settings = settings.clone();
+    *load_task = None;
+    if cx.has_global::<SemanticDb>() {
+        cx.remove_global::<SemanticDb>();
+    }
+
+    let Some((settings, api_url)) = settings else {
+        return;
+    };
+    let http_client = http_client.clone();
+    let credentials_provider = <dyn CredentialsProvider>::global(cx);
+    *load_task = Some(cx.spawn(async move |cx| {
+        let semantic_db = async {
+            let embedding_provider = embedding_provider(
+                &settings,
+                api_url,
+                http_client,
+                credentials_provider,
+                cx,
+            )
+            .await?;
+            let db_path = paths::embeddings_dir()
+                .join("semantic-index")
+                .join(settings.embedding_model_id());
+            SemanticDb::new(db_path, embedding_provider, cx).await
+        };
+        match semantic_db.await {
+            Ok(semantic_db) => {
+                cx.update(|cx| cx.set_global(semantic_db)).log_err();
+            }
+            Err(error) => log::error!("failed to open semantic index: {error:#}"),
+        }
+    }));
+}
+
+/// The URL of the Ollama or LM Studio server to embed with, which is the one
+/// configured for its language model provider.
+fn local_api_url(settings: &SemanticIndexSettings, cx: &App) -> Option<String> {
+    let language_model_settings = AllLanguageModelSettings::get_global(cx);
+    match settings.provider {
+        EmbeddingProviderName::OpenAi => None,
+        EmbeddingProviderName::Ollama => Some(language_model_settings.ollama.api_url.clone()),
+        EmbeddingProviderName::LmStudio => Some(language_model_settings.lmstudio.api_url.clone()),
+    }
+}
+
+async fn embedding_provider(
+    settings: &SemanticIndexSettings,
+    local_api_url: Option<String>,
+    http_client: Arc<HttpClientWithUrl>,
+    credentials_provider: Arc<dyn CredentialsProvider>,
+    cx: &AsyncApp,
+) -> Result<Arc<dyn EmbeddingProvider>> {
+    Ok(match settings.provider {
+        EmbeddingProviderName::OpenAi => {
+            let api_url = settings.openai.api_url.clone();
+            let api_key = if let Ok(api_key) = std::env::var(OPENAI_API_KEY_VAR) {
+                api_key
+            } else {
+                let (_, api_key) = credentials_provider
+                    .read_credentials(&api_url, cx)
+                    .await?
+                    .with_context(|| format!("no OpenAI API key found for {api_url}"))?;
+                String::from_utf8(api_key).context("invalid OpenAI API key")?
+            };
+            Arc::new(OpenAiEmbeddingProvider::new(
+                http_client,
+                settings.openai.model,
+                api_url,
+                api_key,
+            ))
+        }
+        EmbeddingProviderName::Ollama => Arc::new(OllamaEmbeddingProvider::new(
+            http_client,
+            local_api_url.context("no Ollama API URL")?,
+            settings.ollama.model,
+        )),
+        EmbeddingProviderName::LmStudio => Arc::new(LmStudioEmbeddingProvider::new(
+            http_client,
+            local_api_url.context("no LM Studio API URL")?,
+            settings.lmstudio.model,
+        )),
+    })
+}
+
 pub struct SemanticDb {
     embedding_provider: Arc<dyn EmbeddingProvider>,
     db_connection: Option<heed::Env>,
     project_indices: HashMap<WeakEntity<Project>, Entity<ProjectIndex>>,
+    _workspace_observer: Subscription,
 }
 
 impl Global for SemanticDb {}
@@ -54,28 +179,27 @@ impl SemanticDb {
             .await
             .context("opening database connection")?;
 
-        cx.update(|cx| {
+        // Dropped along with the database when it's replaced after the settings change.
+        let workspace_observer = cx.update(|cx| {
             cx.observe_new(
                 |workspace: &mut Workspace, _window, cx: &mut Context<Workspace>| {
                     let project = workspace.project().clone();
-
                     if cx.has_global::<SemanticDb>() {
                         cx.update_global::<SemanticDb, _>(|this, cx| {
-                            this.create_project_index(project, cx);
+                            this.index_for_project(project, cx);
                         })
                     } else {
                         log::info!("No SemanticDb, skipping project index")
                     }
                 },
             )
-            .detach();
-        })
-        .ok();
+        })?;
 
         Ok(SemanticDb {
             db_connection: Some(db_connection),
             embedding_provider,
             project_indices: HashMap::default(),
+            _workspace_observer: workspace_observer,
         })
     }
 
@@ -212,6 +336,19 @@ impl SemanticDb {
         self.project_indices.get(&project.downgrade()).cloned()
     }
 
+    /// Returns the index for the given project, creating it if the project
+    /// was opened before the database finished loading.
+    pub fn index_for_project(
+        &mut self,
+        project: Entity<Project>,
+        cx: &mut App,
+    ) -> Entity<ProjectIndex> {
+        match self.project_index(project.clone(), cx) {
+            Some(project_index) => project_index,
+            None => self.create_project_index(project, cx),
+        }
+    }
+
     pub fn remaining_summaries(
         &self,
         project: &WeakEntity<Project>,
@@ -0,0 +1,126 @@
+use anyhow::Result;
+use gpui::App;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use settings::{Settings, SettingsSources};
+
+use crate::{LmStudioEmbeddingModel, OllamaEmbeddingModel, OpenAiEmbeddingModel};
+
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+pub struct SemanticIndexSettings {
+    pub enabled: bool,
+    pub provider: EmbeddingProviderName,
+    pub openai: OpenAiEmbeddingSettings,
+    pub ollama: OllamaEmbeddingSettings,
+    pub lmstudio: LmStudioEmbeddingSettings,
+}
+
+impl SemanticIndexSettings {
+    /// A stable identifier for the configured embedding model.
+    ///
+    /// Embeddings produced by different models can't be compared with each
+    /// other, so each model gets its own database.
+    pub fn embedding_model_id(&self) -> &'static str {
+        match self.provider {
+            EmbeddingProviderName::OpenAi => match self.openai.model {
+                OpenAiEmbeddingModel::TextEmbedding3Small => "openai-text-embedding-3-small",
+                OpenAiEmbeddingModel::TextEmbedding3Large => "openai-text-embedding-3-large",
+            },
+            EmbeddingProviderName::Ollama => match self.ollama.model {
+                OllamaEmbeddingModel::NomicEmbedText => "ollama-nomic-embed-text",
+                OllamaEmbeddingModel::MxbaiEmbedLarge => "ollama-mxbai-embed-large",
+            },
+            EmbeddingProviderName::LmStudio => match self.lmstudio.model {
+                LmStudioEmbeddingModel::NomicEmbedText => "lmstudio-nomic-embed-text",
+            },
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub enum EmbeddingProviderName {
+    #[serde(rename = "openai")]
+    OpenAi,
+    #[serde(rename = "ollama")]
+    Ollama,
+    #[serde(rename = "lmstudio")]
+    LmStudio,
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+pub struct OpenAiEmbeddingSettings {
+    pub model: OpenAiEmbeddingModel,
+    pub api_url: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+pub struct OllamaEmbeddingSettings {
+    pub model: OllamaEmbeddingModel,
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+pub struct LmStudioEmbeddingSettings {
+    pub model: LmStudioEmbeddingModel,
+}
+
+/// Configuration of the semantic index used by the agent to search code by meaning.
+#[derive(Clone, Default, Serialize, Deserialize, JsonSchema, Debug)]
+#[schemars(deny_unknown_fields)]
+pub struct SemanticIndexSettingsContent {
+    /// Whether to index open projects for semantic search.
+    ///
+    /// Default: false
+    pub enabled: Option<bool>,
+    /// Which embedding provider to use for indexing and searching.
+    ///
+    /// Default: ollama
+    pub provider: Option<EmbeddingProviderName>,
+    /// Settings for embedding with OpenAI.
+    pub openai: Option<OpenAiEmbeddingSettingsContent>,
+    /// Settings for embedding with a local Ollama server.
+    pub ollama: Option<OllamaEmbeddingSettingsContent>,
+    /// Settings for embedding with a local LM Studio server.
+    pub lmstudio: Option<LmStudioEmbeddingSettingsContent>,
+}
+
+#[derive(Clone, Default, Serialize, Deserialize, JsonSchema, Debug)]
+pub struct OpenAiEmbeddingSettingsContent {
+    /// The embedding model to use.
+    ///
+    /// Default: "text-embedding-3-small"
+    pub model: Option<OpenAiEmbeddingModel>,
+    /// The base URL of the OpenAI-compatible API. The API key is read from the
+    /// `OPENAI_API_KEY` environment variable, or from the key stored for this
+    /// URL when configuring the OpenAI language model provider.
+    ///
+    /// Default: "https://api.openai.com/v1"
+    pub api_url: Option<String>,
+}
+
+#[derive(Clone, Default, Serialize, Deserialize, JsonSchema, Debug)]
+pub struct OllamaEmbeddingSettingsContent {
+    /// The embedding model to use. It must already be pulled into Ollama.
+    ///
+    /// Default: "nomic-embed-text"
+    pub model: Option<OllamaEmbeddingModel>,
+}
+
+#[derive(Clone, Default, Serialize, Deserialize, JsonSchema, Debug)]
+pub struct LmStudioEmbeddingSettingsContent {
+    /// The embedding model to use. It must already be loaded in LM Studio.
+    ///
+    /// Default: "nomic-embed-text"
+    pub model: Option<LmStudioEmbeddingModel>,
+}
+
+impl Settings for SemanticIndexSettings {
+    const KEY: Option<&'static str> = Some("semantic_index");
+
+    type FileContent = SemanticIndexSettingsContent;
+
+    fn load(sources: SettingsSources<Self::FileContent>, _: &mut App) -> Result<Self> {
+        sources.json_merge()
+    }
+
+    fn import_from_vscode(_vscode: &settings::VsCodeSettings, _current: &mut Self::FileContent) {}
+}
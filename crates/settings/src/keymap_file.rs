@@ -89,6 +89,10 @@ pub struct KeymapSection {
 }
 
 impl KeymapSection {
+    pub fn context(&self) -> &str {
+        &self.context
+    }
+
     pub fn bindings(&self) -> impl DoubleEndedIterator<Item = (&String, &KeymapAction)> {
         self.bindings.iter().flatten()
     }
@@ -117,6 +121,21 @@ impl std::fmt::Display for KeymapAction {
     }
 }
 
+impl KeymapAction {
+    /// The name of the action, or `None` if this removes a binding.
+    pub fn name(&self) -> Option<&str> {
+        match &self.0 {
+            Value::String(name) => Some(name),
+            Value::Array(items) => items.first().and_then(Value::as_str),
+            _ => None,
+        }
+    }
+
+    pub fn value(&self) -> &Value {
+        &self.0
+    }
+}
+
 impl JsonSchema for KeymapAction {
     /// This is used when generating the JSON schema for the `KeymapAction` type, so that it can
     /// reference the keymap action schema.
@@ -146,6 +165,34 @@ pub enum KeymapFileLoadResult {
     },
 }
 
+/// A binding to write to the user's keymap file.
+#[derive(Clone, Debug, PartialEq)]
+pub struct KeybindUpdateTarget {
+    /// The context of the section the binding is in, which is empty for bindings without a
+    /// context.
+    pub context: String,
+    pub keystrokes: String,
+    /// The action as it's written in a keymap file: its name, a `[name, input]` array, or null to
+    /// remove the keystrokes' binding.
+    pub action: Value,
+}
+
+/// A change to the user's keymap file, made by [`KeymapFile::update_keybinding`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum KeybindUpdateOperation {
+    /// Adds a binding to the last section with its context, or to a new section at the end of the
+    /// keymap.
+    Add(KeybindUpdateTarget),
+    /// Replaces the binding for `keystrokes` in the last section with `context` that binds them.
+    Replace {
+        context: String,
+        keystrokes: String,
+        target: KeybindUpdateTarget,
+    },
+    /// Removes the binding for `keystrokes` from the last section with `context` that binds them.
+    Remove { context: String, keystrokes: String },
+}
+
 impl KeymapFile {
     pub fn parse(content: &str) -> anyhow::Result<Self> {
         parse_json_with_comments::<Self>(content)
@@ -606,11 +653,279 @@ impl KeymapFile {
             }
         }
     }
+
+    /// Applies a change to the bindings in the text of a keymap file, preserving the comments and
+    /// formatting of the rest of the file.
+    pub fn update_keybinding(
+        keymap_text: String,
+        operation: KeybindUpdateOperation,
+        tab_size: usize,
+    ) -> Result<String> {
+        match operation {
+            KeybindUpdateOperation::Add(target) => add_keybinding(keymap_text, target, tab_size),
+            KeybindUpdateOperation::Replace {
+                context,
+                keystrokes,
+                target,
+            } => {
+                if context.trim() == target.context.trim() {
+                    let tree = parse_keymap_syntax(&keymap_text);
+                    let range =
+                        find_binding(&tree, &keymap_text, &context, &keystrokes)?.byte_range();
+                    let mut new_text = keymap_text;
+                    new_text.replace_range(range, &binding_text(&target));
+                    Ok(new_text)
+                } else {
+                    let keymap_text = remove_keybinding(keymap_text, &context, &keystrokes)?;
+                    add_keybinding(keymap_text, target, tab_size)
+                }
+            }
+            KeybindUpdateOperation::Remove {
+                context,
+                keystrokes,
+            } => remove_keybinding(keymap_text, &context, &keystrokes),
+        }
+    }
+}
+
+fn parse_keymap_syntax(text: &str) -> tree_sitter::Tree {
+    let mut parser = tree_sitter::Parser::new();
+    parser
+        .set_language(&tree_sitter_json::LANGUAGE.into())
+        .unwrap();
+    parser.parse(text, None).unwrap()
+}
+
+/// Returns the top-level array of a keymap file's syntax tree.
+fn keymap_array(tree: &tree_sitter::Tree) -> Option<tree_sitter::Node<'_>> {
+    let root = tree.root_node();
+    let mut cursor = root.walk();
+    root.named_children(&mut cursor)
+        .find(|node| node.kind() == "array")
+}
+
+fn named_children_of_kind<'a>(
+    node: tree_sitter::Node<'a>,
+    kind: &str,
+) -> Vec<tree_sitter::Node<'a>> {
+    let mut cursor = node.walk();
+    node.named_children(&mut cursor)
+        .filter(|child| child.kind() == kind)
+        .collect()
+}
+
+/// Returns the pair in a JSON object with the given key.
+fn object_pair<'a>(
+    object: tree_sitter::Node<'a>,
+    key: &str,
+    text: &str,
+) -> Option<tree_sitter::Node<'a>> {
+    named_children_of_kind(object, "pair")
+        .into_iter()
+        .find(|pair| {
+            pair.child_by_field_name("key")
+                .and_then(|key_node| {
+                    serde_json::from_str::<String>(&text[key_node.byte_range()]).ok()
+                })
+                .is_some_and(|pair_key| pair_key == key)
+        })
+}
+
+/// Returns the syntax nodes of each section's bindings object, alongside the parsed sections.
+fn section_bindings<'a>(
+    tree: &'a tree_sitter::Tree,
+    text: &str,
+) -> Result<Vec<(KeymapSection, Option<tree_sitter::Node<'a>>)>> {
+    let keymap = KeymapFile::parse(text)?;
+    let section_nodes = keymap_array(tree)
+        .map(|array| named_children_of_kind(array, "object"))
+        .unwrap_or_default();
+    if section_nodes.len() != keymap.0.len() {
+        return Err(anyhow!("couldn't parse the sections of the keymap file"));
+    }
+    Ok(keymap
+        .0
+        .into_iter()
+        .zip(section_nodes)
+        .map(|(section, node)| {
+            let bindings = object_pair(node, "bindings", text)
+                .and_then(|pair| pair.child_by_field_name("value"))
+                .filter(|value| value.kind() == "object");
+            (section, bindings)
+        })
+        .collect())
+}
+
+/// Returns the binding for `keystrokes` in the last section with `context` that binds them.
+fn find_binding<'a>(
+    tree: &'a tree_sitter::Tree,
+    text: &str,
+    context: &str,
+    keystrokes: &str,
+) -> Result<tree_sitter::Node<'a>> {
+    section_bindings(tree, text)?
+        .into_iter()
+        .rev()
+        .filter(|(section, _)| section.context.trim() == context.trim())
+        .find_map(|(_, bindings)| object_pair(bindings?, keystrokes, text))
+        .ok_or_else(|| anyhow!("couldn't find a binding for \"{keystrokes}\" in the keymap file"))
+}
+
+fn remove_keybinding(mut text: String, context: &str, keystrokes: &str) -> Result<String> {
+    let tree = parse_keymap_syntax(&text);
+    let pair = find_binding(&tree, &text, context, keystrokes)?;
+    let trailing_comma = pair.next_sibling().filter(|node| node.kind() == ",");
+
+    let mut start = pair.start_byte();
+    let mut end = trailing_comma.map_or(pair.end_byte(), |comma| comma.end_byte());
+
+    // Remove the line the binding is on, when nothing else is on it.
+    let line_start = text[..start].rfind('\n').map_or(0, |ix| ix + 1);
+    let line_end = text[end..].find('\n').map_or(text.len(), |ix| end + ix + 1);
+    if text[line_start..start].trim().is_empty() && text[end..line_end].trim().is_empty() {
+        start = line_start;
+        end = line_end;
+    }
+    text.replace_range(start..end, "");
+
+    // Remove the comma before the last binding in a section, so the new last binding doesn't have
+    // a trailing comma where there wasn't one before.
+    if trailing_comma.is_none() {
+        let mut previous = pair.prev_sibling();
+        while let Some(node) = previous.filter(|node| node.kind() == "comment") {
+            previous = node.prev_sibling();
+        }
+        if let Some(comma) = previous.filter(|node| node.kind() == ",") {
+            text.replace_range(comma.byte_range(), "");
+        }
+    }
+    Ok(text)
+}
+
+fn add_keybinding(
+    mut text: String,
+    target: KeybindUpdateTarget,
+    tab_size: usize,
+) -> Result<String> {
+    let tab = " ".repeat(tab_size);
+    let tree = parse_keymap_syntax(&text);
+    let sections = section_bindings(&tree, &text)?;
+    let binding = binding_text(&target);
+
+    let section_bindings = sections
+        .iter()
+        .rev()
+        .filter(|(section, _)| {
+            section.context.trim() == target.context.trim() && !section.use_key_equivalents
+        })
+        .find_map(|(_, bindings)| *bindings);
+    if let Some(bindings) = section_bindings {
+        match named_children_of_kind(bindings, "pair").last() {
+            Some(last_pair) => insert_after_item(&mut text, *last_pair, &binding),
+            None => {
+                let indent = line_indent(&text, bindings.start_byte());
+                let new_text = format!("{{\n{indent}{tab}{binding}\n{indent}}}");
+                text.replace_range(bindings.byte_range(), &new_text);
+            }
+        }
+        return Ok(text);
+    }
+
+    let section = |indent: &str| {
+        let mut section = "{\n".to_string();
+        if !target.context.is_empty() {
+            section.push_str(&format!(
+                "{indent}{tab}\"context\": {},\n",
+                serde_json::to_string(&target.context).unwrap()
+            ));
+        }
+        section.push_str(&format!(
+            "{indent}{tab}\"bindings\": {{\n{indent}{tab}{tab}{binding}\n{indent}{tab}}}\n{indent}}}"
+        ));
+        section
+    };
+    let array = keymap_array(&tree);
+    match array.and_then(|array| named_children_of_kind(array, "object").pop()) {
+        Some(last_section) => {
+            let indent = line_indent(&text, last_section.start_byte()).to_string();
+            insert_after_item(&mut text, last_section, &section(&indent));
+        }
+        None => {
+            let range = array.map_or(0..text.len(), |array| array.byte_range());
+            text.replace_range(range, &format!("[\n{tab}{}\n]", section(&tab)));
+        }
+    }
+    Ok(text)
+}
+
+/// Inserts an item into a JSON object or array after another item, on its own line.
+fn insert_after_item(text: &mut String, item: tree_sitter::Node, new_item: &str) {
+    let indent = line_indent(text, item.start_byte()).to_string();
+    let comma = item.next_sibling().filter(|node| node.kind() == ",");
+    let item_end = comma.map_or(item.end_byte(), |comma| comma.end_byte());
+
+    // Keep a comment after the item on the same line next to it.
+    let line_end = text[item_end..]
+        .find('\n')
+        .map_or(text.len(), |ix| item_end + ix);
+    let rest_of_line = text[item_end..line_end].trim();
+    let insert_at = if rest_of_line.is_empty() || rest_of_line.starts_with("//") {
+        line_end
+    } else {
+        item_end
+    };
+
+    if comma.is_some() {
+        text.insert_str(insert_at, &format!("\n{indent}{new_item},"));
+    } else {
+        text.insert_str(insert_at, &format!("\n{indent}{new_item}"));
+        text.insert(item.end_byte(), ',');
+    }
+}
+
+/// Returns the whitespace at the start of the line containing `offset`.
+fn line_indent(text: &str, offset: usize) -> &str {
+    let line_start = text[..offset].rfind('\n').map_or(0, |ix| ix + 1);
+    let line = &text[line_start..];
+    &line[..line.len() - line.trim_start_matches([' ', '\t']).len()]
+}
+
+/// Formats a binding the way bindings are written in the built-in keymaps, e.g.
+/// `"ctrl-d": ["editor::SelectNext", { "replace_newest": false }]`.
+fn binding_text(target: &KeybindUpdateTarget) -> String {
+    fn value_text(value: &Value) -> String {
+        match value {
+            Value::Array(items) => format!(
+                "[{}]",
+                items.iter().map(value_text).collect::<Vec<_>>().join(", ")
+            ),
+            Value::Object(object) if !object.is_empty() => format!(
+                "{{ {} }}",
+                object
+                    .iter()
+                    .map(|(key, value)| format!(
+                        "{}: {}",
+                        serde_json::to_string(key).unwrap(),
+                        value_text(value)
+                    ))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            value => value.to_string(),
+        }
+    }
+
+    format!(
+        "{}: {}",
+        serde_json::to_string(&target.keystrokes).unwrap(),
+        value_text(&target.action)
+    )
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::KeymapFile;
+    use crate::{KeybindUpdateOperation, KeybindUpdateTarget, KeymapFile};
+    use serde_json::json;
 
     #[test]
     fn can_deserialize_keymap_with_trailing_comma() {
@@ -626,4 +941,202 @@ mod tests {
         };
         KeymapFile::parse(json).unwrap();
     }
+
+    #[test]
+    fn test_update_keybinding() {
+        let keymap = indoc::indoc! {r#"
+            [
+              // Standard bindings
+              {
+                "bindings": {
+                  "ctrl-a": "editor::SelectAll", // select everything
+                  "ctrl-b": "editor::MoveLeft"
+                }
+              },
+              {
+                "context": "Editor",
+                "bindings": {
+                  "ctrl-d": ["editor::SelectNext", { "replace_newest": false }],
+                }
+              }
+            ]
+        "#};
+        let update = |operation| KeymapFile::update_keybinding(keymap.to_string(), operation, 2);
+
+        pretty_assertions::assert_eq!(
+            update(KeybindUpdateOperation::Add(KeybindUpdateTarget {
+                context: String::new(),
+                keystrokes: "ctrl-c".into(),
+                action: json!("editor::Copy"),
+            }))
+            .unwrap(),
+            indoc::indoc! {r#"
+                [
+                  // Standard bindings
+                  {
+                    "bindings": {
+                      "ctrl-a": "editor::SelectAll", // select everything
+                      "ctrl-b": "editor::MoveLeft",
+                      "ctrl-c": "editor::Copy"
+                    }
+                  },
+                  {
+                    "context": "Editor",
+                    "bindings": {
+                      "ctrl-d": ["editor::SelectNext", { "replace_newest": false }],
+                    }
+                  }
+                ]
+            "#}
+        );
+
+        pretty_assertions::assert_eq!(
+            update(KeybindUpdateOperation::Add(KeybindUpdateTarget {
+                context: "Editor".into(),
+                keystrokes: "ctrl-k ctrl-d".into(),
+                action: json!(["editor::SelectNext", { "replace_newest": true }]),
+            }))
+            .unwrap(),
+            indoc::indoc! {r#"
+                [
+                  // Standard bindings
+                  {
+                    "bindings": {
+                      "ctrl-a": "editor::SelectAll", // select everything
+                      "ctrl-b": "editor::MoveLeft"
+                    }
+                  },
+                  {
+                    "context": "Editor",
+                    "bindings": {
+                      "ctrl-d": ["editor::SelectNext", { "replace_newest": false }],
+                      "ctrl-k ctrl-d": ["editor::SelectNext", { "replace_newest": true }],
+                    }
+                  }
+                ]
+            "#}
+        );
+
+        pretty_assertions::assert_eq!(
+            update(KeybindUpdateOperation::Add(KeybindUpdateTarget {
+                context: "Terminal".into(),
+                keystrokes: "ctrl-c".into(),
+                action: json!(null),
+            }))
+            .unwrap(),
+            indoc::indoc! {r#"
+                [
+                  // Standard bindings
+                  {
+                    "bindings": {
+                      "ctrl-a": "editor::SelectAll", // select everything
+                      "ctrl-b": "editor::MoveLeft"
+                    }
+                  },
+                  {
+                    "context": "Editor",
+                    "bindings": {
+                      "ctrl-d": ["editor::SelectNext", { "replace_newest": false }],
+                    }
+                  },
+                  {
+                    "context": "Terminal",
+                    "bindings": {
+                      "ctrl-c": null
+                    }
+                  }
+                ]
+            "#}
+        );
+
+        pretty_assertions::assert_eq!(
+            update(KeybindUpdateOperation::Replace {
+                context: String::new(),
+                keystrokes: "ctrl-a".into(),
+                target: KeybindUpdateTarget {
+                    context: String::new(),
+                    keystrokes: "ctrl-shift-a".into(),
+                    action: json!("editor::SelectAll"),
+                },
+            })
+            .unwrap(),
+            indoc::indoc! {r#"
+                [
+                  // Standard bindings
+                  {
+                    "bindings": {
+                      "ctrl-shift-a": "editor::SelectAll", // select everything
+                      "ctrl-b": "editor::MoveLeft"
+                    }
+                  },
+                  {
+                    "context": "Editor",
+                    "bindings": {
+                      "ctrl-d": ["editor::SelectNext", { "replace_newest": false }],
+                    }
+                  }
+                ]
+            "#}
+        );
+
+        pretty_assertions::assert_eq!(
+            update(KeybindUpdateOperation::Replace {
+                context: "Editor".into(),
+                keystrokes: "ctrl-d".into(),
+                target: KeybindUpdateTarget {
+                    context: String::new(),
+                    keystrokes: "ctrl-d".into(),
+                    action: json!("editor::SelectNext"),
+                },
+            })
+            .unwrap(),
+            indoc::indoc! {r#"
+                [
+                  // Standard bindings
+                  {
+                    "bindings": {
+                      "ctrl-a": "editor::SelectAll", // select everything
+                      "ctrl-b": "editor::MoveLeft",
+                      "ctrl-d": "editor::SelectNext"
+                    }
+                  },
+                  {
+                    "context": "Editor",
+                    "bindings": {
+                    }
+                  }
+                ]
+            "#}
+        );
+
+        pretty_assertions::assert_eq!(
+            update(KeybindUpdateOperation::Remove {
+                context: String::new(),
+                keystrokes: "ctrl-b".into(),
+            })
+            .unwrap(),
+            indoc::indoc! {r#"
+                [
+                  // Standard bindings
+                  {
+                    "bindings": {
+                      "ctrl-a": "editor::SelectAll" // select everything
+                    }
+                  },
+                  {
+                    "context": "Editor",
+                    "bindings": {
+                      "ctrl-d": ["editor::SelectNext", { "replace_newest": false }],
+                    }
+                  }
+                ]
+            "#}
+        );
+
+        update(KeybindUpdateOperation::Remove {
+            context: "Editor".into(),
+            keystrokes: "ctrl-b".into(),
+        })
+        .unwrap_err();
+    }
 }
@@ -15,14 +15,17 @@ pub use editable_setting_control::*;
 pub use json_schema::*;
 pub use key_equivalents::*;
 pub use keymap_file::{
-    KeyBindingValidator, KeyBindingValidatorRegistration, KeymapFile, KeymapFileLoadResult,
+    KeyBindingValidator, KeyBindingValidatorRegistration, KeybindUpdateOperation,
+    KeybindUpdateTarget, KeymapAction, KeymapFile, KeymapFileLoadResult, KeymapSection,
 };
 pub use settings_file::*;
 pub use settings_store::{
-    InvalidSettingsError, LocalSettingsKind, Settings, SettingsLocation, SettingsSources,
-    SettingsStore, parse_json_with_comments,
+    InvalidSettingsError, LocalSettingsKind, Settings, SettingsLocation, SettingsSource,
+    SettingsSources, SettingsStore, parse_json_with_comments,
+};
+pub use vscode_import::{
+    UnmappedVsCodeKeybinding, VsCodeKeybindings, VsCodeKeybindingsImport, VsCodeSettings,
 };
-pub use vscode_import::VsCodeSettings;
 
 #[derive(Copy, Clone, PartialEq, Eq, Debug, Hash, PartialOrd, Ord)]
 pub struct WorktreeId(usize);
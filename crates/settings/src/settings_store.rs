@@ -128,6 +128,8 @@ pub struct SettingsSources<'a, T> {
     pub user: Option<&'a T>,
     /// The user settings for the current release channel.
     pub release_channel: Option<&'a T>,
+    /// The user settings of the active profile.
+    pub profile: Option<&'a T>,
     /// The server's settings.
     pub server: Option<&'a T>,
     /// The project settings, ordered from least specific to most specific.
@@ -147,6 +149,7 @@ impl<'a, T: Serialize> SettingsSources<'a, T> {
             .chain(self.extensions)
             .chain(self.user)
             .chain(self.release_channel)
+            .chain(self.profile)
             .chain(self.server)
             .chain(self.project.iter().copied())
     }
@@ -179,6 +182,25 @@ pub struct SettingsLocation<'a> {
     pub path: &'a Path,
 }
 
+/// The settings that a raw setting value was read from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SettingsSource {
+    /// Zed's default settings.
+    Default,
+    /// The user's settings file.
+    User,
+    /// The settings file at the root of a project.
+    Project,
+    /// A language's overrides in the user's settings file.
+    UserLanguage,
+    /// The active profile in the user's settings file.
+    Profile,
+    /// A language's overrides in the active profile in the user's settings file.
+    ProfileLanguage,
+    /// A language's overrides in the settings file at the root of a project.
+    ProjectLanguage,
+}
+
 /// A set of strongly-typed setting values defined via multiple config files.
 pub struct SettingsStore {
     setting_values: HashMap<TypeId, Box<dyn AnySettingValue>>,
@@ -189,6 +211,7 @@ pub struct SettingsStore {
     raw_extension_settings: Value,
     raw_local_settings: BTreeMap<(WorktreeId, Arc<Path>), Value>,
     raw_editorconfig_settings: BTreeMap<(WorktreeId, Arc<Path>), (String, Option<Editorconfig>)>,
+    active_profile: Option<String>,
     tab_size_callback: Option<(
         TypeId,
         Box<dyn Fn(&dyn Any) -> Option<usize> + Send + Sync + 'static>,
@@ -282,6 +305,7 @@ impl SettingsStore {
             raw_extension_settings: serde_json::json!({}),
             raw_local_settings: Default::default(),
             raw_editorconfig_settings: BTreeMap::default(),
+            active_profile: None,
             tab_size_callback: Default::default(),
             setting_file_updates_tx,
             _setting_file_updates: cx.spawn(async move |cx| {
@@ -331,6 +355,15 @@ impl SettingsStore {
                     .log_err();
             }
 
+            let profile_value =
+                profile_settings(&self.raw_user_settings, self.active_profile.as_deref()).and_then(
+                    |profile_settings| {
+                        setting_value
+                            .deserialize_setting(profile_settings)
+                            .log_err()
+                    },
+                );
+
             let server_value = self
                 .raw_server_settings
                 .as_ref()
@@ -350,6 +383,7 @@ impl SettingsStore {
                         extensions: extension_value.as_ref(),
                         user: user_value.as_ref(),
                         release_channel: release_channel_value.as_ref(),
+                        profile: profile_value.as_ref(),
                         server: server_value.as_ref(),
                         project: &[],
                     },
@@ -463,33 +497,55 @@ impl SettingsStore {
                     let new_text = cx.read_global(|store: &SettingsStore, cx| {
                         store.new_text_for_update::<T>(old_text, |content| update(content, cx))
                     })?;
-                    let settings_path = paths::settings_file().as_path();
-                    if fs.is_file(settings_path).await {
-                        let resolved_path =
-                            fs.canonicalize(settings_path).await.with_context(|| {
-                                format!("Failed to canonicalize settings path {:?}", settings_path)
-                            })?;
-
-                        fs.atomic_write(resolved_path.clone(), new_text)
-                            .await
-                            .with_context(|| {
-                                format!("Failed to write settings to file {:?}", resolved_path)
-                            })?;
-                    } else {
-                        fs.atomic_write(settings_path.to_path_buf(), new_text)
-                            .await
-                            .with_context(|| {
-                                format!("Failed to write settings to file {:?}", settings_path)
-                            })?;
-                    }
+                    Self::write_settings_file(&fs, new_text).await
+                }
+                .boxed_local()
+            }))
+            .ok();
+    }
 
-                    anyhow::Ok(())
+    /// Sets the raw JSON value at a path of keys in the user's settings file,
+    /// preserving the comments and formatting around it. Setting a value to
+    /// null unsets it.
+    pub fn update_settings_file_at_path(
+        &self,
+        fs: Arc<dyn Fs>,
+        key_path: Vec<String>,
+        new_value: Value,
+    ) {
+        self.setting_file_updates_tx
+            .unbounded_send(Box::new(move |cx: AsyncApp| {
+                async move {
+                    let old_text = Self::load_settings(&fs).await?;
+                    let new_text = cx.read_global(|store: &SettingsStore, _cx| {
+                        store.new_text_for_path_update(old_text, &key_path, &new_value)
+                    })?;
+                    Self::write_settings_file(&fs, new_text).await
                 }
                 .boxed_local()
             }))
             .ok();
     }
 
+    async fn write_settings_file(fs: &Arc<dyn Fs>, new_text: String) -> Result<()> {
+        let settings_path = paths::settings_file().as_path();
+        if fs.is_file(settings_path).await {
+            let resolved_path = fs.canonicalize(settings_path).await.with_context(|| {
+                format!("Failed to canonicalize settings path {:?}", settings_path)
+            })?;
+
+            fs.atomic_write(resolved_path.clone(), new_text)
+                .await
+                .with_context(|| format!("Failed to write settings to file {:?}", resolved_path))?;
+        } else {
+            fs.atomic_write(settings_path.to_path_buf(), new_text)
+                .await
+                .with_context(|| format!("Failed to write settings to file {:?}", settings_path))?;
+        }
+
+        Ok(())
+    }
+
     pub fn import_vscode_settings(&self, fs: Arc<dyn Fs>, vscode_settings: VsCodeSettings) {
         self.setting_file_updates_tx
             .unbounded_send(Box::new(move |cx: AsyncApp| {
@@ -498,27 +554,7 @@ impl SettingsStore {
                     let new_text = cx.read_global(|store: &SettingsStore, _cx| {
                         store.get_vscode_edits(old_text, &vscode_settings)
                     })?;
-                    let settings_path = paths::settings_file().as_path();
-                    if fs.is_file(settings_path).await {
-                        let resolved_path =
-                            fs.canonicalize(settings_path).await.with_context(|| {
-                                format!("Failed to canonicalize settings path {:?}", settings_path)
-                            })?;
-
-                        fs.atomic_write(resolved_path.clone(), new_text)
-                            .await
-                            .with_context(|| {
-                                format!("Failed to write settings to file {:?}", resolved_path)
-                            })?;
-                    } else {
-                        fs.atomic_write(settings_path.to_path_buf(), new_text)
-                            .await
-                            .with_context(|| {
-                                format!("Failed to write settings to file {:?}", settings_path)
-                            })?;
-                    }
-
-                    anyhow::Ok(())
+                    Self::write_settings_file(&fs, new_text).await
                 }
                 .boxed_local()
             }))
@@ -540,6 +576,33 @@ impl SettingsStore {
         new_text
     }
 
+    /// Replaces the value at a path of keys in a JSON file, returning the new
+    /// text for that JSON file.
+    pub fn new_text_for_path_update(
+        &self,
+        mut text: String,
+        key_path: &[String],
+        new_value: &Value,
+    ) -> String {
+        let raw_settings = parse_json_with_comments::<Value>(&text).unwrap_or_default();
+        let old_value = key_path
+            .iter()
+            .try_fold(&raw_settings, |value, key| value.get(key))
+            .unwrap_or(&Value::Null);
+        let mut key_path = key_path.iter().map(String::as_str).collect();
+        let mut edits = Vec::new();
+        update_value_in_json_text(
+            &mut text,
+            &mut key_path,
+            self.json_tab_size(),
+            old_value,
+            new_value,
+            &[],
+            &mut edits,
+        );
+        text
+    }
+
     pub fn get_vscode_edits(&self, mut old_text: String, vscode: &VsCodeSettings) -> String {
         let mut new_text = old_text.clone();
         let mut edits: Vec<(Range<usize>, String)> = Vec::new();
@@ -843,6 +906,103 @@ impl SettingsStore {
             .map(|((_, path), content)| (path.clone(), serde_json::to_string(content).unwrap()))
     }
 
+    /// Returns the names of the profiles in the user's settings.
+    pub fn profile_names(&self) -> Vec<String> {
+        self.raw_user_settings
+            .get("profiles")
+            .and_then(Value::as_object)
+            .map(|profiles| profiles.keys().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// Returns the name of the profile whose settings are layered over the user's settings.
+    pub fn active_profile(&self) -> Option<&str> {
+        self.active_profile.as_deref()
+    }
+
+    /// Layers a profile's settings over the user's settings, or stops layering any when `None`.
+    ///
+    /// A profile that isn't in the user's settings has no settings until it's added.
+    pub fn set_active_profile(&mut self, profile: Option<String>, cx: &mut App) {
+        if self.active_profile != profile {
+            self.active_profile = profile;
+            self.recompute_values(None, cx).log_err();
+        }
+    }
+
+    /// Returns the raw JSON value at a path of keys in the settings, and the
+    /// settings it was read from.
+    ///
+    /// A language's overrides take precedence over the settings they're in,
+    /// the project's settings take precedence over the active profile's, and
+    /// the active profile's take precedence over the user's.
+    pub fn raw_value_with_source(
+        &self,
+        key_path: &[&str],
+        language: Option<&str>,
+        worktree_id: Option<WorktreeId>,
+    ) -> Option<(&Value, SettingsSource)> {
+        let project_settings = worktree_id.and_then(|worktree_id| {
+            self.raw_local_settings
+                .get(&(worktree_id, Path::new("").into()))
+        });
+        let profile_settings =
+            profile_settings(&self.raw_user_settings, self.active_profile.as_deref());
+        let language_key_path = language.map(|language| {
+            ["languages", language]
+                .into_iter()
+                .chain(key_path.iter().copied())
+                .collect::<Vec<_>>()
+        });
+
+        let mut candidates = Vec::new();
+        if let Some(language_key_path) = &language_key_path {
+            candidates.push((
+                project_settings,
+                language_key_path.as_slice(),
+                SettingsSource::ProjectLanguage,
+            ));
+            candidates.push((
+                profile_settings,
+                language_key_path.as_slice(),
+                SettingsSource::ProfileLanguage,
+            ));
+            candidates.push((
+                Some(&self.raw_user_settings),
+                language_key_path.as_slice(),
+                SettingsSource::UserLanguage,
+            ));
+        }
+        candidates.push((project_settings, key_path, SettingsSource::Project));
+        candidates.push((profile_settings, key_path, SettingsSource::Profile));
+        candidates.push((
+            Some(&self.raw_user_settings),
+            key_path,
+            SettingsSource::User,
+        ));
+        if let Some(language_key_path) = &language_key_path {
+            candidates.push((
+                Some(&self.raw_default_settings),
+                language_key_path.as_slice(),
+                SettingsSource::Default,
+            ));
+        }
+        candidates.push((
+            Some(&self.raw_default_settings),
+            key_path,
+            SettingsSource::Default,
+        ));
+
+        candidates
+            .into_iter()
+            .find_map(|(settings, key_path, source)| {
+                let value = key_path
+                    .iter()
+                    .try_fold(settings?, |value, key| value.get(key))?;
+                (!value.is_null()).then_some((value, source))
+            })
+    }
+
     pub fn local_editorconfig_settings(
         &self,
         root_id: WorktreeId,
@@ -951,6 +1111,7 @@ impl SettingsStore {
             }
         }
 
+        let profile_schema = combined_schema.schema.clone();
         for release_stage in ["dev", "nightly", "stable", "preview"] {
             let schema = combined_schema.schema.clone();
             combined_schema
@@ -960,6 +1121,27 @@ impl SettingsStore {
                 .insert(release_stage.to_string(), schema.into());
         }
 
+        combined_schema.schema.object().properties.insert(
+            "profiles".to_string(),
+            SchemaObject {
+                instance_type: Some(schemars::schema::InstanceType::Object.into()),
+                object: Some(Box::new(schemars::schema::ObjectValidation {
+                    additional_properties: Some(Box::new(profile_schema.into())),
+                    ..Default::default()
+                })),
+                metadata: Some(Box::new(schemars::schema::Metadata {
+                    description: Some(
+                        "Named sets of settings that can be layered over these settings from \
+                         the command palette."
+                            .to_string(),
+                    ),
+                    ..Default::default()
+                })),
+                ..Default::default()
+            }
+            .into(),
+        );
+
         serde_json::to_value(&combined_schema).unwrap()
     }
 
@@ -1014,6 +1196,15 @@ impl SettingsStore {
                 }
             }
 
+            let profile_settings =
+                profile_settings(&self.raw_user_settings, self.active_profile.as_deref()).and_then(
+                    |profile_settings| {
+                        setting_value
+                            .deserialize_setting(profile_settings)
+                            .log_err()
+                    },
+                );
+
             // If the global settings file changed, reload the global value for the field.
             if changed_local_path.is_none() {
                 if let Some(value) = setting_value
@@ -1024,6 +1215,7 @@ impl SettingsStore {
                             extensions: extension_settings.as_ref(),
                             user: user_settings.as_ref(),
                             release_channel: release_channel_settings.as_ref(),
+                            profile: profile_settings.as_ref(),
                             server: server_settings.as_ref(),
                             project: &[],
                         },
@@ -1076,6 +1268,7 @@ impl SettingsStore {
                                     extensions: extension_settings.as_ref(),
                                     user: user_settings.as_ref(),
                                     release_channel: release_channel_settings.as_ref(),
+                                    profile: profile_settings.as_ref(),
                                     server: server_settings.as_ref(),
                                     project: &project_settings_stack.iter().collect::<Vec<_>>(),
                                 },
@@ -1126,6 +1319,11 @@ impl SettingsStore {
     }
 }
 
+/// Returns a profile's settings in the user's settings.
+fn profile_settings<'a>(raw_user_settings: &'a Value, profile: Option<&str>) -> Option<&'a Value> {
+    raw_user_settings.get("profiles")?.get(profile?)
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum InvalidSettingsError {
     LocalSettings { path: PathBuf, message: String },
@@ -1201,6 +1399,9 @@ impl<T: Settings> AnySettingValue for SettingValue<T> {
                 release_channel: values
                     .release_channel
                     .map(|value| value.0.downcast_ref::<T::FileContent>().unwrap()),
+                profile: values
+                    .profile
+                    .map(|value| value.0.downcast_ref::<T::FileContent>().unwrap()),
                 server: values
                     .server
                     .map(|value| value.0.downcast_ref::<T::FileContent>().unwrap()),
@@ -2189,6 +2390,202 @@ mod tests {
         );
     }
 
+    #[gpui::test]
+    fn test_raw_value_with_source(cx: &mut App) {
+        let mut store = SettingsStore::new(cx);
+        store
+            .set_default_settings(
+                r#"{
+                    "tab_size": 4,
+                    "soft_wrap": "none",
+                    "languages": { "Go": { "tab_size": 8 } }
+                }"#,
+                cx,
+            )
+            .unwrap();
+        store
+            .set_user_settings(
+                r#"{
+                    "tab_size": 2,
+                    "soft_wrap": null,
+                    "languages": { "Rust": { "tab_size": 3 } }
+                }"#,
+                cx,
+            )
+            .unwrap();
+        store
+            .set_local_settings(
+                WorktreeId::from_usize(1),
+                Path::new("").into(),
+                LocalSettingsKind::Settings,
+                Some(r#"{ "languages": { "Go": { "hard_tabs": true } } }"#),
+                cx,
+            )
+            .unwrap();
+
+        let source = |key_path: &[&str], language: Option<&str>, worktree_id: Option<usize>| {
+            store
+                .raw_value_with_source(key_path, language, worktree_id.map(WorktreeId::from_usize))
+                .map(|(value, source)| (value.clone(), source))
+        };
+        assert_eq!(
+            source(&["tab_size"], None, None),
+            Some((serde_json::json!(2), SettingsSource::User))
+        );
+        assert_eq!(
+            source(&["tab_size"], Some("Rust"), None),
+            Some((serde_json::json!(3), SettingsSource::UserLanguage))
+        );
+        // The user's settings take precedence over the defaults for a language.
+        assert_eq!(
+            source(&["tab_size"], Some("Go"), None),
+            Some((serde_json::json!(2), SettingsSource::User))
+        );
+        assert_eq!(
+            source(&["hard_tabs"], Some("Go"), Some(1)),
+            Some((serde_json::json!(true), SettingsSource::ProjectLanguage))
+        );
+        assert_eq!(source(&["hard_tabs"], Some("Go"), None), None);
+        // Null values are unset.
+        assert_eq!(
+            source(&["soft_wrap"], None, None),
+            Some((serde_json::json!("none"), SettingsSource::Default))
+        );
+    }
+
+    #[gpui::test]
+    fn test_settings_profiles(cx: &mut App) {
+        let mut store = SettingsStore::new(cx);
+        store.register_setting::<UserSettings>(cx);
+        store
+            .set_default_settings(
+                r#"{
+                    "user": { "name": "John Doe", "age": 30, "staff": false }
+                }"#,
+                cx,
+            )
+            .unwrap();
+        store
+            .set_user_settings(
+                r#"{
+                    "user": { "age": 31 },
+                    "profiles": {
+                        "presenting": {
+                            "user": { "name": "Presenter" },
+                            "languages": { "Rust": { "tab_size": 8 } }
+                        },
+                        "pairing": { "user": { "staff": true } }
+                    }
+                }"#,
+                cx,
+            )
+            .unwrap();
+
+        assert_eq!(store.profile_names(), ["presenting", "pairing"]);
+        assert_eq!(store.active_profile(), None);
+        assert_eq!(store.get::<UserSettings>(None).name, "John Doe");
+
+        store.set_active_profile(Some("presenting".into()), cx);
+        assert_eq!(store.active_profile(), Some("presenting"));
+        assert_eq!(
+            store.get::<UserSettings>(None),
+            &UserSettings {
+                name: "Presenter".to_string(),
+                age: 31,
+                staff: false,
+            }
+        );
+        assert_eq!(
+            store
+                .raw_value_with_source(&["user", "name"], None, None)
+                .map(|(value, source)| (value.clone(), source)),
+            Some((serde_json::json!("Presenter"), SettingsSource::Profile))
+        );
+        assert_eq!(
+            store
+                .raw_value_with_source(&["user", "age"], None, None)
+                .map(|(value, source)| (value.clone(), source)),
+            Some((serde_json::json!(31), SettingsSource::User))
+        );
+        assert_eq!(
+            store
+                .raw_value_with_source(&["tab_size"], Some("Rust"), None)
+                .map(|(value, source)| (value.clone(), source)),
+            Some((serde_json::json!(8), SettingsSource::ProfileLanguage))
+        );
+
+        // Switching profiles replaces the previous profile's settings.
+        store.set_active_profile(Some("pairing".into()), cx);
+        assert_eq!(
+            store.get::<UserSettings>(None),
+            &UserSettings {
+                name: "John Doe".to_string(),
+                age: 31,
+                staff: true,
+            }
+        );
+
+        store.set_active_profile(None, cx);
+        assert_eq!(
+            store.get::<UserSettings>(None),
+            &UserSettings {
+                name: "John Doe".to_string(),
+                age: 31,
+                staff: false,
+            }
+        );
+    }
+
+    #[gpui::test]
+    fn test_new_text_for_path_update(cx: &mut App) {
+        let store = SettingsStore::new(cx);
+        let text = r#"{
+            // Indent Rust with two spaces.
+            "languages": {
+                "Rust": {
+                    "tab_size": 2
+                }
+            }
+        }"#
+        .unindent();
+        let key_path = |keys: &[&str]| keys.iter().map(|key| key.to_string()).collect::<Vec<_>>();
+
+        assert_eq!(
+            store.new_text_for_path_update(
+                text.clone(),
+                &key_path(&["languages", "Rust", "tab_size"]),
+                &serde_json::json!(3),
+            ),
+            text.replace("\"tab_size\": 2", "\"tab_size\": 3")
+        );
+
+        let new_text = store.new_text_for_path_update(
+            text.clone(),
+            &key_path(&["languages", "Rust", "hard_tabs"]),
+            &serde_json::json!(true),
+        );
+        assert!(new_text.contains("// Indent Rust with two spaces."));
+        assert_eq!(
+            parse_json_with_comments::<Value>(&new_text).unwrap(),
+            serde_json::json!({
+                "languages": { "Rust": { "tab_size": 2, "hard_tabs": true } }
+            })
+        );
+
+        let new_text = store.new_text_for_path_update(
+            text.clone(),
+            &key_path(&["vim_mode"]),
+            &serde_json::json!(true),
+        );
+        assert_eq!(
+            parse_json_with_comments::<Value>(&new_text).unwrap(),
+            serde_json::json!({
+                "vim_mode": true,
+                "languages": { "Rust": { "tab_size": 2 } }
+            })
+        );
+    }
+
     #[derive(Clone, Debug, Default, Serialize, Deserialize, JsonSchema)]
     struct LanguageSettings {
         #[serde(default)]
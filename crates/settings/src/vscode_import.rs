@@ -1,8 +1,12 @@
 use anyhow::Result;
 use fs::Fs;
-use serde_json::{Map, Value};
+use gpui::{KeyBindingContextPredicate, Keystroke};
+use serde::Deserialize;
+use serde_json::{Map, Value, json};
 
-use std::sync::Arc;
+use std::{fmt, iter::Peekable, sync::Arc};
+
+use crate::KeybindUpdateTarget;
 
 pub struct VsCodeSettings {
     content: Map<String, Value>,
@@ -86,3 +90,665 @@ impl VsCodeSettings {
         }
     }
 }
+
+/// A user's VS Code key bindings, as listed in their `keybindings.json`.
+pub struct VsCodeKeybindings {
+    entries: Vec<VsCodeKeybinding>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+struct VsCodeKeybinding {
+    key: String,
+    command: String,
+    #[serde(default)]
+    when: Option<String>,
+    #[serde(default)]
+    args: Option<Value>,
+}
+
+/// The result of importing VS Code key bindings: the bindings to add to the keymap file, and the
+/// ones that have no equivalent.
+#[derive(Debug, Default)]
+pub struct VsCodeKeybindingsImport {
+    pub bindings: Vec<KeybindUpdateTarget>,
+    pub unmapped: Vec<UnmappedVsCodeKeybinding>,
+}
+
+/// A VS Code key binding that couldn't be imported, and why.
+#[derive(Clone, Debug, PartialEq)]
+pub struct UnmappedVsCodeKeybinding {
+    pub key: String,
+    pub command: String,
+    pub when: Option<String>,
+    pub reason: String,
+}
+
+impl fmt::Display for UnmappedVsCodeKeybinding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} → {}", self.key, self.command)?;
+        if let Some(when) = &self.when {
+            write!(f, " (when {when})")?;
+        }
+        write!(f, ": {}", self.reason)
+    }
+}
+
+impl VsCodeKeybindings {
+    pub fn from_str(content: &str) -> Result<Self> {
+        Ok(Self {
+            entries: serde_json_lenient::from_str(content)?,
+        })
+    }
+
+    pub async fn load_user_keybindings(fs: Arc<dyn Fs>) -> Result<Self> {
+        let content = fs.load(paths::vscode_keybindings_file()).await?;
+        Self::from_str(&content)
+    }
+
+    /// Translates each key binding into a keymap binding, with its command mapped to an action and
+    /// its `when` clause translated to a context predicate.
+    pub fn import(&self) -> VsCodeKeybindingsImport {
+        let mut import = VsCodeKeybindingsImport::default();
+        for entry in &self.entries {
+            match entry.translate() {
+                Ok(binding) => import.bindings.push(binding),
+                Err(reason) => import.unmapped.push(UnmappedVsCodeKeybinding {
+                    key: entry.key.clone(),
+                    command: entry.command.clone(),
+                    when: entry.when.clone(),
+                    reason,
+                }),
+            }
+        }
+        import
+    }
+}
+
+impl VsCodeKeybinding {
+    fn translate(&self) -> Result<KeybindUpdateTarget, String> {
+        if self.command.starts_with('-') {
+            return Err("removes one of VS Code's default bindings".to_string());
+        }
+        let action = vscode_command_action(&self.command, self.args.as_ref())?;
+        let keystrokes = translate_keystrokes(&self.key)?;
+        let context = match self.when.as_deref().map(str::trim) {
+            Some(when) if !when.is_empty() => {
+                let context = WhenClause::parse(when)?.translate()?.0;
+                KeyBindingContextPredicate::parse(&context)
+                    .map_err(|error| format!("invalid context `{context}`: {error}"))?;
+                context
+            }
+            _ => String::new(),
+        };
+        Ok(KeybindUpdateTarget {
+            context,
+            keystrokes,
+            action,
+        })
+    }
+}
+
+/// Returns the action equivalent to a VS Code command, given the arguments it's bound with.
+fn vscode_command_action(command: &str, args: Option<&Value>) -> Result<Value, String> {
+    if command == "workbench.action.terminal.sendSequence" {
+        return args
+            .and_then(|args| args.get("text"))
+            .and_then(Value::as_str)
+            .map(|text| json!(["terminal::SendText", text]))
+            .ok_or_else(|| "sends no text".to_string());
+    }
+    if args.is_some() {
+        return Err("arguments aren't supported for this command".to_string());
+    }
+
+    let action = match command {
+        "workbench.action.showCommands" => json!("command_palette::Toggle"),
+        "workbench.action.quickOpen" => json!("file_finder::Toggle"),
+        "workbench.action.openRecent" => json!("projects::OpenRecent"),
+        "workbench.action.gotoLine" => json!("go_to_line::Toggle"),
+        "workbench.action.gotoSymbol" => json!("outline::Toggle"),
+        "workbench.action.showAllSymbols" => json!("project_symbols::Toggle"),
+        "workbench.action.findInFiles" => json!("pane::DeploySearch"),
+        "workbench.action.replaceInFiles" => {
+            json!(["pane::DeploySearch", { "replace_enabled": true }])
+        }
+        "workbench.action.selectTheme" => json!("theme_selector::Toggle"),
+        "workbench.action.openSettings" => json!("zed::OpenSettings"),
+        "workbench.action.openGlobalKeybindings" => json!("zed::OpenKeymap"),
+        "workbench.action.newWindow" => json!("workspace::NewWindow"),
+        "workbench.action.closeWindow" => json!("workspace::CloseWindow"),
+        "workbench.action.quit" => json!("zed::Quit"),
+        "workbench.action.files.newUntitledFile" => json!("workspace::NewFile"),
+        "workbench.action.files.openFile" | "workbench.action.files.openFolder" => {
+            json!("workspace::Open")
+        }
+        "workbench.action.files.save" => json!("workspace::Save"),
+        "workbench.action.files.saveAs" => json!("workspace::SaveAs"),
+        "workbench.action.files.saveAll" => json!("workspace::SaveAll"),
+        "workbench.action.closeActiveEditor" => {
+            json!(["pane::CloseActiveItem", { "close_pinned": false }])
+        }
+        "workbench.action.closeAllEditors" => {
+            json!(["pane::CloseAllItems", { "close_pinned": false }])
+        }
+        "workbench.action.reopenClosedEditor" => json!("pane::ReopenClosedItem"),
+        "workbench.action.nextEditor" => json!("pane::ActivateNextItem"),
+        "workbench.action.previousEditor" => json!("pane::ActivatePreviousItem"),
+        "workbench.action.pinEditor" | "workbench.action.unpinEditor" => {
+            json!("pane::TogglePinTab")
+        }
+        "workbench.action.splitEditor" | "workbench.action.splitEditorRight" => {
+            json!("pane::SplitRight")
+        }
+        "workbench.action.splitEditorDown" => json!("pane::SplitDown"),
+        "workbench.action.focusNextGroup" => json!("workspace::ActivateNextPane"),
+        "workbench.action.focusPreviousGroup" => json!("workspace::ActivatePreviousPane"),
+        "workbench.action.navigateBack" => json!("pane::GoBack"),
+        "workbench.action.navigateForward" => json!("pane::GoForward"),
+        "workbench.action.toggleSidebarVisibility" => json!("workspace::ToggleLeftDock"),
+        "workbench.action.toggleAuxiliaryBar" => json!("workspace::ToggleRightDock"),
+        "workbench.action.togglePanel" => json!("workspace::ToggleBottomDock"),
+        "workbench.action.closePanel" => json!("workspace::CloseAllDocks"),
+        "workbench.action.toggleMaximizedPanel" | "workbench.action.toggleMaximizeEditorGroup" => {
+            json!("workspace::ToggleZoom")
+        }
+        "workbench.action.zoomIn" => json!("zed::IncreaseBufferFontSize"),
+        "workbench.action.zoomOut" => json!("zed::DecreaseBufferFontSize"),
+        "workbench.action.zoomReset" => json!("zed::ResetBufferFontSize"),
+        "workbench.view.explorer" => json!("project_panel::ToggleFocus"),
+        "workbench.view.scm" => json!("git_panel::ToggleFocus"),
+        "workbench.actions.view.problems" => json!("diagnostics::Deploy"),
+        "workbench.action.terminal.toggleTerminal" => json!("terminal_panel::ToggleFocus"),
+        "workbench.action.terminal.new" => json!("workspace::NewTerminal"),
+        "workbench.action.terminal.clear" => json!("terminal::Clear"),
+        "actions.find" => json!("buffer_search::Deploy"),
+        "editor.action.startFindReplaceAction" => json!("buffer_search::DeployReplace"),
+        "editor.action.nextMatchFindAction" => json!("search::SelectNextMatch"),
+        "editor.action.previousMatchFindAction" => json!("search::SelectPreviousMatch"),
+        "undo" => json!("editor::Undo"),
+        "redo" => json!("editor::Redo"),
+        "editor.action.clipboardCutAction" => json!("editor::Cut"),
+        "editor.action.clipboardCopyAction" => json!("editor::Copy"),
+        "editor.action.clipboardPasteAction" => json!("editor::Paste"),
+        "editor.action.selectAll" => json!("editor::SelectAll"),
+        "editor.action.commentLine" => {
+            json!(["editor::ToggleComments", { "advance_downwards": false }])
+        }
+        "editor.action.formatDocument" => json!("editor::Format"),
+        "editor.action.rename" => json!("editor::Rename"),
+        "editor.action.quickFix" => json!("editor::ToggleCodeActions"),
+        "editor.action.showHover" => json!("editor::Hover"),
+        "editor.action.triggerSuggest" => json!("editor::ShowCompletions"),
+        "editor.action.triggerParameterHints" => json!("editor::ShowSignatureHelp"),
+        "acceptSelectedSuggestion" => json!("editor::ConfirmCompletion"),
+        "editor.action.revealDefinition" | "editor.action.goToDefinition" => {
+            json!("editor::GoToDefinition")
+        }
+        "editor.action.revealDefinitionAside" => json!("editor::GoToDefinitionSplit"),
+        "editor.action.goToTypeDefinition" => json!("editor::GoToTypeDefinition"),
+        "editor.action.goToImplementation" => json!("editor::GoToImplementation"),
+        "editor.action.goToReferences" | "editor.action.referenceSearch.trigger" => {
+            json!("editor::FindAllReferences")
+        }
+        "editor.action.marker.next" => json!("editor::GoToDiagnostic"),
+        "editor.action.marker.prev" => json!("editor::GoToPreviousDiagnostic"),
+        "workbench.action.editor.nextChange" => json!("editor::GoToHunk"),
+        "workbench.action.editor.previousChange" => json!("editor::GoToPreviousHunk"),
+        "editor.action.addSelectionToNextFindMatch" => {
+            json!(["editor::SelectNext", { "replace_newest": false }])
+        }
+        "editor.action.moveSelectionToNextFindMatch" => {
+            json!(["editor::SelectNext", { "replace_newest": true }])
+        }
+        "editor.action.selectHighlights" | "editor.action.changeAll" => {
+            json!("editor::SelectAllMatches")
+        }
+        "editor.action.insertCursorAbove" => json!("editor::AddSelectionAbove"),
+        "editor.action.insertCursorBelow" => json!("editor::AddSelectionBelow"),
+        "editor.action.insertCursorAtEndOfEachLineSelected" => {
+            json!("editor::SplitSelectionIntoLines")
+        }
+        "editor.action.smartSelect.expand" => json!("editor::SelectLargerSyntaxNode"),
+        "editor.action.smartSelect.shrink" => json!("editor::SelectSmallerSyntaxNode"),
+        "expandLineSelection" => json!("editor::SelectLine"),
+        "editor.action.copyLinesDownAction" => json!("editor::DuplicateLineDown"),
+        "editor.action.copyLinesUpAction" => json!("editor::DuplicateLineUp"),
+        "editor.action.moveLinesDownAction" => json!("editor::MoveLineDown"),
+        "editor.action.moveLinesUpAction" => json!("editor::MoveLineUp"),
+        "editor.action.deleteLines" => json!("editor::DeleteLine"),
+        "editor.action.joinLines" => json!("editor::JoinLines"),
+        "editor.action.insertLineAfter" => json!("editor::NewlineBelow"),
+        "editor.action.insertLineBefore" => json!("editor::NewlineAbove"),
+        "editor.action.indentLines" => json!("editor::Indent"),
+        "editor.action.outdentLines" => json!("editor::Outdent"),
+        "editor.action.jumpToBracket" => json!("editor::MoveToEnclosingBracket"),
+        "editor.action.toggleWordWrap" => json!("editor::ToggleSoftWrap"),
+        "editor.fold" => json!("editor::Fold"),
+        "editor.unfold" => json!("editor::UnfoldLines"),
+        "editor.toggleFold" => json!("editor::ToggleFold"),
+        "editor.foldAll" => json!("editor::FoldAll"),
+        "editor.unfoldAll" => json!("editor::UnfoldAll"),
+        "deleteWordLeft" => json!("editor::DeleteToPreviousWordStart"),
+        "deleteWordRight" => json!("editor::DeleteToNextWordEnd"),
+        "cursorWordLeft" => json!("editor::MoveToPreviousWordStart"),
+        "cursorWordEndRight" => json!("editor::MoveToNextWordEnd"),
+        "cursorTop" => json!("editor::MoveToBeginning"),
+        "cursorBottom" => json!("editor::MoveToEnd"),
+        _ => return Err("no equivalent action".to_string()),
+    };
+    Ok(action)
+}
+
+/// Translates VS Code keystrokes like `ctrl+k ctrl+shift+s` to keymap keystrokes.
+fn translate_keystrokes(key: &str) -> Result<String, String> {
+    let mut keystrokes = Vec::new();
+    for chord in key.split_whitespace() {
+        let chord = chord.to_lowercase();
+        let (modifiers, key) = match chord.strip_suffix("++") {
+            Some(modifiers) => (modifiers, "+"),
+            None => chord.rsplit_once('+').unwrap_or(("", chord.as_str())),
+        };
+
+        let mut keystroke = String::new();
+        for modifier in ["ctrl", "alt", "shift", "cmd", "meta", "win"] {
+            if !modifiers.split('+').any(|component| component == modifier) {
+                continue;
+            }
+            keystroke.push_str(match modifier {
+                "meta" => "super",
+                modifier => modifier,
+            });
+            keystroke.push('-');
+        }
+        if let Some(modifier) = modifiers.split('+').find(|component| {
+            !component.is_empty()
+                && !["ctrl", "alt", "shift", "cmd", "meta", "win"].contains(component)
+        }) {
+            return Err(format!("unknown modifier `{modifier}`"));
+        }
+
+        let is_named_key = matches!(
+            key,
+            "escape"
+                | "enter"
+                | "tab"
+                | "space"
+                | "backspace"
+                | "delete"
+                | "insert"
+                | "home"
+                | "end"
+                | "pageup"
+                | "pagedown"
+                | "up"
+                | "down"
+                | "left"
+                | "right"
+        );
+        let is_function_key = key
+            .strip_prefix('f')
+            .is_some_and(|number| number.parse::<u8>().is_ok_and(|n| (1..=24).contains(&n)));
+        if !is_named_key && !is_function_key && key.chars().count() != 1 {
+            return Err(format!("unsupported key `{key}`"));
+        }
+        keystroke.push_str(key);
+
+        Keystroke::parse(&keystroke)
+            .map_err(|error| format!("invalid keystroke `{keystroke}`: {error}"))?;
+        keystrokes.push(keystroke);
+    }
+
+    if keystrokes.is_empty() {
+        return Err("no keystrokes".to_string());
+    }
+    Ok(keystrokes.join(" "))
+}
+
+/// A VS Code `when` clause, limited to the operators that have key context equivalents.
+#[derive(Debug, PartialEq)]
+enum WhenClause {
+    Key(String),
+    Equal(String, String),
+    NotEqual(String, String),
+    Not(Box<WhenClause>),
+    And(Vec<WhenClause>),
+    Or(Vec<WhenClause>),
+}
+
+/// How tightly a translated context predicate binds, to know when it needs parentheses.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum Precedence {
+    Or,
+    And,
+    Unary,
+    Identifier,
+}
+
+impl WhenClause {
+    fn parse(source: &str) -> Result<Self, String> {
+        let tokens = when_clause_tokens(source)?;
+        let mut tokens = tokens.iter().map(String::as_str).peekable();
+        let clause = Self::parse_or(&mut tokens)?;
+        match tokens.next() {
+            Some(token) => Err(format!("unexpected `{token}` in when clause")),
+            None => Ok(clause),
+        }
+    }
+
+    fn parse_or<'a>(tokens: &mut Peekable<impl Iterator<Item = &'a str>>) -> Result<Self, String> {
+        let mut clauses = vec![Self::parse_and(tokens)?];
+        while tokens.next_if_eq(&"||").is_some() {
+            clauses.push(Self::parse_and(tokens)?);
+        }
+        Ok(if clauses.len() == 1 {
+            clauses.remove(0)
+        } else {
+            Self::Or(clauses)
+        })
+    }
+
+    fn parse_and<'a>(tokens: &mut Peekable<impl Iterator<Item = &'a str>>) -> Result<Self, String> {
+        let mut clauses = vec![Self::parse_unary(tokens)?];
+        while tokens.next_if_eq(&"&&").is_some() {
+            clauses.push(Self::parse_unary(tokens)?);
+        }
+        Ok(if clauses.len() == 1 {
+            clauses.remove(0)
+        } else {
+            Self::And(clauses)
+        })
+    }
+
+    fn parse_unary<'a>(
+        tokens: &mut Peekable<impl Iterator<Item = &'a str>>,
+    ) -> Result<Self, String> {
+        match tokens.next() {
+            Some("!") => Ok(Self::Not(Box::new(Self::parse_unary(tokens)?))),
+            Some("(") => {
+                let clause = Self::parse_or(tokens)?;
+                match tokens.next() {
+                    Some(")") => Ok(clause),
+                    _ => Err("unclosed parenthesis in when clause".to_string()),
+                }
+            }
+            Some(key) if is_when_clause_word(key) => {
+                let operator = tokens.next_if(|token| !matches!(*token, "&&" | "||" | ")"));
+                let Some(operator) = operator else {
+                    return Ok(Self::Key(key.to_string()));
+                };
+                let value = tokens
+                    .next()
+                    .map(|value| value.trim_matches('\'').to_string())
+                    .ok_or_else(|| format!("missing value after `{operator}`"))?;
+                match operator {
+                    "==" | "===" => Ok(Self::Equal(key.to_string(), value)),
+                    "!=" | "!==" => Ok(Self::NotEqual(key.to_string(), value)),
+                    _ => Err(format!("unsupported operator `{operator}`")),
+                }
+            }
+            Some(token) => Err(format!("unexpected `{token}` in when clause")),
+            None => Err("when clause ends unexpectedly".to_string()),
+        }
+    }
+
+    /// Translates the clause to a key context predicate.
+    fn translate(&self) -> Result<(String, Precedence), String> {
+        match self {
+            Self::Key(key) => match key.as_str() {
+                "editorTextFocus" | "editorFocus" => {
+                    Ok(("Editor && mode == full".to_string(), Precedence::And))
+                }
+                "textInputFocus" => Ok(("Editor".to_string(), Precedence::Identifier)),
+                "editorHasSelection" => Ok(("selection".to_string(), Precedence::Identifier)),
+                "suggestWidgetVisible" => {
+                    Ok(("showing_completions".to_string(), Precedence::Identifier))
+                }
+                "renameInputVisible" => Ok(("renaming".to_string(), Precedence::Identifier)),
+                "terminalFocus" => Ok(("Terminal".to_string(), Precedence::Identifier)),
+                "filesExplorerFocus" | "explorerViewletFocus" => {
+                    Ok(("ProjectPanel".to_string(), Precedence::Identifier))
+                }
+                "inQuickOpen" => Ok(("Picker".to_string(), Precedence::Identifier)),
+                "isMac" => Ok(("os == macos".to_string(), Precedence::Unary)),
+                "isLinux" => Ok(("os == linux".to_string(), Precedence::Unary)),
+                "isWindows" => Ok(("os == windows".to_string(), Precedence::Unary)),
+                _ => Err(format!("no equivalent for `{key}`")),
+            },
+            Self::Equal(key, value) => translate_comparison(key, value, false),
+            Self::NotEqual(key, value) => translate_comparison(key, value, true),
+            Self::Not(clause) => {
+                let (predicate, precedence) = clause.translate()?;
+                if precedence == Precedence::Identifier {
+                    Ok((format!("!{predicate}"), Precedence::Unary))
+                } else {
+                    Ok((format!("!({predicate})"), Precedence::Unary))
+                }
+            }
+            Self::And(clauses) => {
+                let mut predicates = Vec::new();
+                for clause in clauses {
+                    let (predicate, precedence) = clause.translate()?;
+                    let predicate = if precedence < Precedence::And {
+                        format!("({predicate})")
+                    } else {
+                        predicate
+                    };
+                    // Several VS Code keys translate to the same context, like `editorFocus` and
+                    // `editorTextFocus`.
+                    if !predicates.contains(&predicate) {
+                        predicates.push(predicate);
+                    }
+                }
+                Ok((predicates.join(" && "), Precedence::And))
+            }
+            Self::Or(clauses) => {
+                let predicates = clauses
+                    .iter()
+                    .map(|clause| Ok(clause.translate()?.0))
+                    .collect::<Result<Vec<_>, String>>()?;
+                Ok((predicates.join(" || "), Precedence::Or))
+            }
+        }
+    }
+}
+
+/// Translates a comparison of a `when` clause key with a value, which only has equivalents for
+/// the file's language and extension.
+fn translate_comparison(
+    key: &str,
+    value: &str,
+    negated: bool,
+) -> Result<(String, Precedence), String> {
+    let extensions = match key {
+        "resourceExtname" => vec![value.trim_start_matches('.')],
+        "editorLangId" | "resourceLangId" => language_extensions(value)
+            .ok_or_else(|| format!("no equivalent for language `{value}`"))?
+            .to_vec(),
+        _ => return Err(format!("no equivalent for `{key}`")),
+    };
+    let operator = if negated { "!=" } else { "==" };
+    let mut comparisons = extensions
+        .iter()
+        .map(|extension| format!("extension {operator} {extension}"))
+        .collect::<Vec<_>>();
+    if comparisons.len() == 1 {
+        Ok((comparisons.remove(0), Precedence::Unary))
+    } else if negated {
+        Ok((comparisons.join(" && "), Precedence::And))
+    } else {
+        Ok((comparisons.join(" || "), Precedence::Or))
+    }
+}
+
+/// The file extensions of the languages VS Code bindings are commonly limited to.
+fn language_extensions(language_id: &str) -> Option<&'static [&'static str]> {
+    Some(match language_id {
+        "rust" => &["rs"],
+        "go" => &["go"],
+        "python" => &["py"],
+        "javascript" => &["js", "mjs", "cjs"],
+        "javascriptreact" => &["jsx"],
+        "typescript" => &["ts", "mts", "cts"],
+        "typescriptreact" => &["tsx"],
+        "c" => &["c", "h"],
+        "cpp" => &["cpp", "cc", "cxx", "hpp"],
+        "java" => &["java"],
+        "ruby" => &["rb"],
+        "markdown" => &["md"],
+        "json" => &["json"],
+        "yaml" => &["yaml", "yml"],
+        "toml" => &["toml"],
+        "html" => &["html"],
+        "css" => &["css"],
+        "shellscript" => &["sh"],
+        _ => return None,
+    })
+}
+
+fn is_when_clause_word(token: &str) -> bool {
+    !token.is_empty() && token.chars().all(is_when_clause_char)
+}
+
+fn is_when_clause_char(c: char) -> bool {
+    c.is_alphanumeric() || matches!(c, '_' | '-' | '.' | ':' | '/')
+}
+
+/// Operators of `when` clauses, longest first so that they're matched greedily.
+const WHEN_CLAUSE_OPERATORS: &[&str] = &[
+    "===", "!==", "==", "!=", "=~", "<=", ">=", "&&", "||", "!", "<", ">", "(", ")",
+];
+
+fn when_clause_tokens(source: &str) -> Result<Vec<String>, String> {
+    let mut tokens = Vec::new();
+    let mut rest = source.trim_start();
+    while let Some(c) = rest.chars().next() {
+        let len = if c == '\'' {
+            let end = rest[1..]
+                .find('\'')
+                .ok_or_else(|| "unclosed quote in when clause".to_string())?;
+            end + 2
+        } else if is_when_clause_char(c) {
+            rest.find(|c| !is_when_clause_char(c)).unwrap_or(rest.len())
+        } else {
+            WHEN_CLAUSE_OPERATORS
+                .iter()
+                .find(|operator| rest.starts_with(*operator))
+                .map_or(c.len_utf8(), |operator| operator.len())
+        };
+        tokens.push(rest[..len].to_string());
+        rest = rest[len..].trim_start();
+    }
+    Ok(tokens)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_import_vscode_keybindings() {
+        let keybindings = VsCodeKeybindings::from_str(
+            r#"[
+                // Comments are allowed in keybindings.json.
+                { "key": "ctrl+shift+p", "command": "workbench.action.showCommands" },
+                {
+                    "key": "ctrl+d",
+                    "command": "editor.action.addSelectionToNextFindMatch",
+                    "when": "editorFocus && editorHasSelection"
+                },
+                {
+                    "key": "ctrl+k ctrl+c",
+                    "command": "editor.action.commentLine",
+                    "when": "editorTextFocus && !editorReadonly"
+                },
+                {
+                    "key": "cmd+enter",
+                    "command": "workbench.action.terminal.sendSequence",
+                    "args": { "text": "ls\n" },
+                    "when": "terminalFocus && isMac"
+                },
+                {
+                    "key": "alt+f",
+                    "command": "editor.action.formatDocument",
+                    "when": "editorTextFocus && (editorLangId == rust || resourceExtname == '.toml')"
+                },
+                { "key": "shift+alt+down", "command": "editor.action.copyLinesDownAction", "when": "!inQuickOpen" },
+                { "key": "ctrl+shift+k", "command": "-editor.action.deleteLines" },
+                { "key": "ctrl+numpad_add", "command": "workbench.action.zoomIn" },
+                { "key": "ctrl+alt+x", "command": "extension.unknownCommand" }
+            ]"#,
+        )
+        .unwrap();
+
+        let import = keybindings.import();
+        assert_eq!(
+            import.bindings,
+            [
+                KeybindUpdateTarget {
+                    context: "".into(),
+                    keystrokes: "ctrl-shift-p".into(),
+                    action: json!("command_palette::Toggle"),
+                },
+                KeybindUpdateTarget {
+                    context: "Editor && mode == full && selection".into(),
+                    keystrokes: "ctrl-d".into(),
+                    action: json!(["editor::SelectNext", { "replace_newest": false }]),
+                },
+                KeybindUpdateTarget {
+                    context: "Terminal && os == macos".into(),
+                    keystrokes: "cmd-enter".into(),
+                    action: json!(["terminal::SendText", "ls\n"]),
+                },
+                KeybindUpdateTarget {
+                    context: "Editor && mode == full && (extension == rs || extension == toml)"
+                        .into(),
+                    keystrokes: "alt-f".into(),
+                    action: json!("editor::Format"),
+                },
+                KeybindUpdateTarget {
+                    context: "!Picker".into(),
+                    keystrokes: "alt-shift-down".into(),
+                    action: json!("editor::DuplicateLineDown"),
+                },
+            ]
+        );
+
+        let unmapped = import
+            .unmapped
+            .iter()
+            .map(|entry| (entry.key.as_str(), entry.reason.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            unmapped,
+            [
+                ("ctrl+k ctrl+c", "no equivalent for `editorReadonly`"),
+                ("ctrl+shift+k", "removes one of VS Code's default bindings"),
+                ("ctrl+numpad_add", "unsupported key `numpad_add`"),
+                ("ctrl+alt+x", "no equivalent action"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_when_clause() {
+        assert_eq!(
+            WhenClause::parse("a&&!b || c == 'd e'").unwrap(),
+            WhenClause::Or(vec![
+                WhenClause::And(vec![
+                    WhenClause::Key("a".into()),
+                    WhenClause::Not(Box::new(WhenClause::Key("b".into()))),
+                ]),
+                WhenClause::Equal("c".into(), "d e".into()),
+            ])
+        );
+        assert_eq!(
+            WhenClause::parse("resourceFilename =~ /foo/"),
+            Err("unsupported operator `=~`".to_string())
+        );
+        assert_eq!(
+            WhenClause::parse("(a && b"),
+            Err("unclosed parenthesis in when clause".to_string())
+        );
+    }
+}
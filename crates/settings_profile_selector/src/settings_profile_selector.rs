@@ -0,0 +1,453 @@
+use std::sync::Arc;
+
+use collections::HashMap;
+use db::kvp::KEY_VALUE_STORE;
+use fuzzy::{StringMatch, StringMatchCandidate, match_strings};
+use gpui::{
+    App, Context, DismissEvent, Entity, EntityId, EventEmitter, Focusable, Global, Render,
+    UpdateGlobal, WeakEntity, Window,
+};
+use picker::{Picker, PickerDelegate};
+use settings::SettingsStore;
+use ui::{ListItem, ListItemSpacing, prelude::*};
+use util::{ResultExt, TryFutureExt};
+use workspace::{ModalView, Workspace, ui::HighlightedLabel};
+use zed_actions::settings_profile_selector::Toggle;
+
+const SETTINGS_PROFILE_KEY: &str = "settings_profile";
+
+/// The settings profile picked in each workspace's window, which is applied whenever that window
+/// is focused, and when it's the frontmost window left after another one closes.
+#[derive(Default)]
+struct WorkspaceSettingsProfiles(HashMap<EntityId, String>);
+
+impl Global for WorkspaceSettingsProfiles {}
+
+pub fn init(cx: &mut App) {
+    cx.set_global(WorkspaceSettingsProfiles::default());
+    cx.observe_new(|workspace: &mut Workspace, window, cx| {
+        let Some(window) = window else {
+            return;
+        };
+
+        workspace.register_action(toggle_settings_profile_selector);
+        restore_settings_profile(workspace, window, cx);
+
+        cx.observe_window_activation(window, |_, window, cx| {
+            if window.is_window_active() {
+                activate_settings_profile(Some(cx.entity_id()), cx);
+            }
+        })
+        .detach();
+
+        let workspace_id = cx.entity_id();
+        cx.on_release(move |_, cx| {
+            cx.global_mut::<WorkspaceSettingsProfiles>()
+                .0
+                .remove(&workspace_id);
+            activate_frontmost_settings_profile(workspace_id, cx);
+        })
+        .detach();
+    })
+    .detach();
+}
+
+fn toggle_settings_profile_selector(
+    workspace: &mut Workspace,
+    _: &Toggle,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let workspace_handle = workspace.weak_handle();
+    workspace.toggle_modal(window, cx, |window, cx| {
+        let delegate =
+            SettingsProfileSelectorDelegate::new(cx.entity().downgrade(), workspace_handle, cx);
+        SettingsProfileSelector::new(delegate, window, cx)
+    });
+}
+
+fn serialization_key(workspace: &Workspace) -> Option<String> {
+    workspace
+        .database_id()
+        .map(|id| format!("{SETTINGS_PROFILE_KEY}-{}", i64::from(id)))
+}
+
+/// Restores the settings profile that was picked when the workspace was last open.
+fn restore_settings_profile(
+    workspace: &Workspace,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let Some(serialization_key) = serialization_key(workspace) else {
+        return;
+    };
+    let workspace_id = cx.entity_id();
+    cx.spawn_in(window, async move |_, cx| {
+        let profile = cx
+            .background_spawn(async move { KEY_VALUE_STORE.read_kvp(&serialization_key) })
+            .await
+            .log_err()
+            .flatten();
+        let Some(profile) = profile else {
+            return;
+        };
+        cx.update(|window, cx| {
+            cx.global_mut::<WorkspaceSettingsProfiles>()
+                .0
+                .insert(workspace_id, profile);
+            if window.is_window_active() {
+                activate_settings_profile(Some(workspace_id), cx);
+            }
+        })
+        .ok();
+    })
+    .detach();
+}
+
+/// Layers the settings profile picked in a workspace's window over the user's settings, or no
+/// profile when there's no workspace.
+fn activate_settings_profile(workspace_id: Option<EntityId>, cx: &mut App) {
+    let profile = workspace_id.and_then(|workspace_id| {
+        cx.global::<WorkspaceSettingsProfiles>()
+            .0
+            .get(&workspace_id)
+            .cloned()
+    });
+    SettingsStore::update_global(cx, |store, cx| store.set_active_profile(profile, cx));
+}
+
+/// Applies the profile of the frontmost workspace window other than the one that closed, since
+/// the platform may not focus another window when one closes.
+fn activate_frontmost_settings_profile(closed_workspace_id: EntityId, cx: &mut App) {
+    let windows = cx
+        .active_window()
+        .into_iter()
+        .chain(cx.window_stack().unwrap_or_default())
+        .chain(cx.windows())
+        .collect::<Vec<_>>();
+    let workspace_id = windows
+        .into_iter()
+        .filter_map(|window| window.downcast::<Workspace>()?.root(cx).ok())
+        .map(|workspace| workspace.entity_id())
+        .find(|workspace_id| *workspace_id != closed_workspace_id);
+    activate_settings_profile(workspace_id, cx);
+}
+
+fn set_settings_profile(
+    workspace: &Workspace,
+    profile: Option<String>,
+    cx: &mut Context<Workspace>,
+) {
+    let workspace_id = cx.entity_id();
+    let profiles = &mut cx.global_mut::<WorkspaceSettingsProfiles>().0;
+    match &profile {
+        Some(profile) => profiles.insert(workspace_id, profile.clone()),
+        None => profiles.remove(&workspace_id),
+    };
+    activate_settings_profile(Some(workspace_id), cx);
+
+    if let Some(serialization_key) = serialization_key(workspace) {
+        cx.background_spawn(
+            async move {
+                match profile {
+                    Some(profile) => KEY_VALUE_STORE.write_kvp(serialization_key, profile).await,
+                    None => KEY_VALUE_STORE.delete_kvp(serialization_key).await,
+                }
+            }
+            .log_err(),
+        )
+        .detach();
+    }
+}
+
+impl ModalView for SettingsProfileSelector {}
+
+struct SettingsProfileSelector {
+    picker: Entity<Picker<SettingsProfileSelectorDelegate>>,
+}
+
+impl EventEmitter<DismissEvent> for SettingsProfileSelector {}
+
+impl Focusable for SettingsProfileSelector {
+    fn focus_handle(&self, cx: &App) -> gpui::FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl Render for SettingsProfileSelector {
+    fn render(&mut self, _window: &mut Window, _cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex().w(rems(34.)).child(self.picker.clone())
+    }
+}
+
+impl SettingsProfileSelector {
+    fn new(
+        delegate: SettingsProfileSelectorDelegate,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let picker = cx.new(|cx| Picker::uniform_list(delegate, window, cx));
+        Self { picker }
+    }
+}
+
+struct SettingsProfileSelectorDelegate {
+    /// The profiles in the user's settings, after the option of using no profile.
+    profiles: Vec<Option<String>>,
+    active_profile: Option<String>,
+    matches: Vec<StringMatch>,
+    selected_index: usize,
+    selector: WeakEntity<SettingsProfileSelector>,
+    workspace: WeakEntity<Workspace>,
+}
+
+impl SettingsProfileSelectorDelegate {
+    fn new(
+        selector: WeakEntity<SettingsProfileSelector>,
+        workspace: WeakEntity<Workspace>,
+        cx: &App,
+    ) -> Self {
+        let store = cx.global::<SettingsStore>();
+        let active_profile = store.active_profile().map(str::to_string);
+        let profiles = [None]
+            .into_iter()
+            .chain(store.profile_names().into_iter().map(Some))
+            .collect::<Vec<_>>();
+        let matches = profiles
+            .iter()
+            .enumerate()
+            .map(|(ix, profile)| StringMatch {
+                candidate_id: ix,
+                score: 0.0,
+                positions: Default::default(),
+                string: profile_label(profile.as_deref()).to_string(),
+            })
+            .collect();
+        let selected_index = profiles
+            .iter()
+            .position(|profile| *profile == active_profile)
+            .unwrap_or(0);
+
+        Self {
+            profiles,
+            active_profile,
+            matches,
+            selected_index,
+            selector,
+            workspace,
+        }
+    }
+}
+
+fn profile_label(profile: Option<&str>) -> &str {
+    profile.unwrap_or("No Profile")
+}
+
+impl PickerDelegate for SettingsProfileSelectorDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self, _window: &mut Window, _cx: &mut App) -> Arc<str> {
+        "Select Settings Profile...".into()
+    }
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(&mut self, ix: usize, _: &mut Window, _: &mut Context<Picker<Self>>) {
+        self.selected_index = ix;
+    }
+
+    fn update_matches(
+        &mut self,
+        query: String,
+        window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> gpui::Task<()> {
+        let background = cx.background_executor().clone();
+        let candidates = self
+            .profiles
+            .iter()
+            .enumerate()
+            .map(|(id, profile)| StringMatchCandidate::new(id, profile_label(profile.as_deref())))
+            .collect::<Vec<_>>();
+
+        cx.spawn_in(window, async move |this, cx| {
+            let matches = if query.is_empty() {
+                candidates
+                    .into_iter()
+                    .map(|candidate| StringMatch {
+                        candidate_id: candidate.id,
+                        string: candidate.string,
+                        positions: Vec::new(),
+                        score: 0.0,
+                    })
+                    .collect()
+            } else {
+                match_strings(
+                    &candidates,
+                    &query,
+                    false,
+                    100,
+                    &Default::default(),
+                    background,
+                )
+                .await
+            };
+
+            this.update(cx, |this, cx| {
+                this.delegate.matches = matches;
+                let active_profile = &this.delegate.active_profile;
+                this.delegate.selected_index = this
+                    .delegate
+                    .matches
+                    .iter()
+                    .position(|mat| this.delegate.profiles[mat.candidate_id] == *active_profile)
+                    .filter(|_| query.is_empty())
+                    .unwrap_or(0);
+                cx.notify();
+            })
+            .log_err();
+        })
+    }
+
+    fn confirm(&mut self, _: bool, _: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let Some(profile) = self
+            .matches
+            .get(self.selected_index)
+            .map(|mat| self.profiles[mat.candidate_id].clone())
+        else {
+            return;
+        };
+
+        self.workspace
+            .update(cx, |workspace, cx| {
+                set_settings_profile(workspace, profile, cx);
+            })
+            .log_err();
+        self.selector
+            .update(cx, |_, cx| cx.emit(DismissEvent))
+            .log_err();
+    }
+
+    fn dismissed(&mut self, _: &mut Window, cx: &mut Context<Picker<Self>>) {
+        self.selector
+            .update(cx, |_, cx| cx.emit(DismissEvent))
+            .log_err();
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _window: &mut Window,
+        _cx: &mut Context<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let profile_match = &self.matches[ix];
+        let is_active = self.profiles[profile_match.candidate_id] == self.active_profile;
+
+        Some(
+            ListItem::new(ix)
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .toggle_state(selected)
+                .child(HighlightedLabel::new(
+                    profile_match.string.clone(),
+                    profile_match.positions.clone(),
+                ))
+                .end_slot(is_active.then(|| {
+                    Icon::new(IconName::Check)
+                        .size(IconSize::Small)
+                        .color(Color::Accent)
+                })),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::{TestAppContext, WindowHandle};
+    use project::Project;
+    use workspace::AppState;
+
+    fn init_test(cx: &mut TestAppContext) -> Arc<AppState> {
+        cx.update(|cx| {
+            let state = AppState::test(cx);
+            language::init(cx);
+            Project::init_settings(cx);
+            workspace::init_settings(cx);
+            crate::init(cx);
+            state
+        })
+    }
+
+    fn active_profile(cx: &mut TestAppContext) -> Option<String> {
+        cx.read(|cx| {
+            cx.global::<SettingsStore>()
+                .active_profile()
+                .map(str::to_string)
+        })
+    }
+
+    fn activate(window: WindowHandle<Workspace>, cx: &mut TestAppContext) {
+        window
+            .update(cx, |_, window, _| window.activate_window())
+            .unwrap();
+        cx.run_until_parked();
+    }
+
+    fn pick_profile(
+        window: WindowHandle<Workspace>,
+        profile: Option<&str>,
+        cx: &mut TestAppContext,
+    ) {
+        window
+            .update(cx, |workspace, _, cx| {
+                set_settings_profile(workspace, profile.map(str::to_string), cx)
+            })
+            .unwrap();
+        cx.run_until_parked();
+    }
+
+    #[gpui::test]
+    async fn test_profile_follows_focused_window(cx: &mut TestAppContext) {
+        let app_state = init_test(cx);
+        let project = Project::test(app_state.fs.clone(), [], cx).await;
+        let first = cx.add_window(|window, cx| Workspace::test_new(project.clone(), window, cx));
+        let second = cx.add_window(|window, cx| Workspace::test_new(project.clone(), window, cx));
+
+        activate(first, cx);
+        pick_profile(first, Some("Presenting"), cx);
+        assert_eq!(active_profile(cx).as_deref(), Some("Presenting"));
+
+        // Each window applies its own profile when it's focused.
+        activate(second, cx);
+        assert_eq!(active_profile(cx), None);
+        pick_profile(second, Some("Focus"), cx);
+        assert_eq!(active_profile(cx).as_deref(), Some("Focus"));
+        activate(first, cx);
+        assert_eq!(active_profile(cx).as_deref(), Some("Presenting"));
+
+        // Closing the focused window applies the profile of the window left
+        // open, even if it isn't focused.
+        activate(second, cx);
+        assert_eq!(active_profile(cx).as_deref(), Some("Focus"));
+        second
+            .update(cx, |_, window, _| window.remove_window())
+            .unwrap();
+        cx.run_until_parked();
+        assert_eq!(active_profile(cx).as_deref(), Some("Presenting"));
+
+        // No profile applies once no windows are left.
+        first
+            .update(cx, |_, window, _| window.remove_window())
+            .unwrap();
+        cx.run_until_parked();
+        assert_eq!(active_profile(cx), None);
+    }
+}
@@ -0,0 +1,1330 @@
+use std::{ops::Range, sync::Arc};
+
+use collections::{HashMap, HashSet};
+use editor::{Editor, EditorEvent};
+use fs::Fs;
+use gpui::{
+    Action, App, Entity, EventEmitter, FocusHandle, Focusable, KeyBindingContextPredicate,
+    KeymapVersion, Keystroke, NoAction, Subscription, Task, UniformListScrollHandle, WeakEntity,
+    is_no_action, uniform_list,
+};
+use serde_json::Value;
+use settings::{KeybindUpdateOperation, KeybindUpdateTarget, KeymapAction, KeymapFile};
+use ui::{KeyBinding, Tooltip, prelude::*, text_for_keystrokes};
+use workspace::Workspace;
+use workspace::item::{Item, ItemEvent};
+
+/// The indentation used when writing bindings to the keymap file, which matches the built-in
+/// keymaps.
+const KEYMAP_TAB_SIZE: usize = 2;
+
+/// Where a key binding was loaded from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum KeybindSource {
+    /// The default keymap, the base keymap, or vim's keymap.
+    Base,
+    /// The user's keymap file.
+    User,
+}
+
+/// A binding in the user's keymap file, as it's written there.
+struct UserBinding {
+    context: String,
+    keystrokes: String,
+    action: Value,
+    action_name: Option<String>,
+    /// The context and keystrokes as they're displayed for loaded bindings, for matching the
+    /// binding to the one it was loaded as.
+    normalized: Option<(String, String)>,
+}
+
+impl UserBinding {
+    fn new(context: &str, keystrokes: &str, action: &KeymapAction) -> Self {
+        let normalized_context = if context.trim().is_empty() {
+            Some(String::new())
+        } else {
+            KeyBindingContextPredicate::parse(context)
+                .ok()
+                .map(|predicate| predicate.to_string())
+        };
+        let normalized_keystrokes = keystrokes
+            .split_whitespace()
+            .map(Keystroke::parse)
+            .collect::<Result<Vec<_>, _>>()
+            .ok()
+            .map(|keystrokes| keystrokes_text(&keystrokes));
+        Self {
+            context: context.to_string(),
+            keystrokes: keystrokes.to_string(),
+            action: action.value().clone(),
+            action_name: action.name().map(str::to_string),
+            normalized: normalized_context.zip(normalized_keystrokes),
+        }
+    }
+
+    fn matches(&self, binding: &BindingInfo) -> bool {
+        let action_matches = if is_no_action(binding.key_binding.action()) {
+            self.action.is_null()
+        } else {
+            self.action_name.as_deref() == Some(binding.key_binding.action().name())
+        };
+        action_matches
+            && self
+                .normalized
+                .as_ref()
+                .is_some_and(|(context, keystrokes)| {
+                    *context == binding.context && *keystrokes == binding.keystrokes
+                })
+    }
+}
+
+/// A key binding in the app's keymap.
+struct BindingInfo {
+    key_binding: gpui::KeyBinding,
+    keystrokes: String,
+    context: String,
+    source: KeybindSource,
+    /// How the binding is written in the user's keymap file, for user bindings.
+    user_binding: Option<UserBinding>,
+    /// How the binding interacts with the other bindings for the same keystrokes or their
+    /// prefixes, in contexts that overlap its own.
+    conflicts: Vec<Conflict>,
+}
+
+/// How a binding interacts with another binding, given by its index. Matches the way
+/// `Keymap::bindings_for_input` picks between bindings: the later binding wins when both are
+/// enabled at the same context depth, and a later binding for a prefix of the keystrokes ends the
+/// key sequence before the longer one can be typed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Conflict {
+    /// A later binding for the same keystrokes, whose context covers this binding's context, takes
+    /// precedence wherever this binding applies.
+    ShadowedBy(usize),
+    /// A later binding for the same keystrokes in a narrower context takes precedence where that
+    /// context applies.
+    NarrowedBy(usize),
+    /// This binding takes precedence over an earlier binding for the same keystrokes, at least
+    /// where this binding's context applies.
+    Overrides(usize),
+    /// A later binding for a prefix of these keystrokes dispatches its action as soon as the
+    /// prefix is typed, so these keystrokes can't be typed where that binding's context applies.
+    BlockedBy(usize),
+    /// A later binding for keystrokes that start with these ones makes the keymap wait for
+    /// another keystroke before dispatching this binding's action.
+    PrefixOf(usize),
+}
+
+impl BindingInfo {
+    fn is_no_action(&self) -> bool {
+        is_no_action(self.key_binding.action())
+    }
+}
+
+/// A row of the keymap editor: an action, and one of its bindings if it has any.
+struct KeymapEntry {
+    action_name: SharedString,
+    binding_ix: Option<usize>,
+}
+
+/// Edits the keystrokes and context of a binding, or adds a binding for an action.
+struct BindingEditor {
+    action_name: SharedString,
+    /// The binding being changed, or `None` when adding a binding.
+    binding_ix: Option<usize>,
+    keystrokes: Vec<Keystroke>,
+    context_editor: Entity<Editor>,
+    error: Option<SharedString>,
+}
+
+/// What captured keystrokes are used for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum CaptureTarget {
+    Search,
+    Binding,
+}
+
+/// Lists every registered action with its key bindings, and edits the user's keymap file.
+pub struct KeymapEditor {
+    workspace: WeakEntity<Workspace>,
+    fs: Arc<dyn Fs>,
+    focus_handle: FocusHandle,
+    capture_focus_handle: FocusHandle,
+    search_editor: Entity<Editor>,
+    /// Keystrokes that matching bindings start with, when searching by keystroke.
+    search_keystrokes: Vec<Keystroke>,
+    bindings: Vec<BindingInfo>,
+    entries: Vec<KeymapEntry>,
+    matches: Vec<usize>,
+    keymap_version: Option<KeymapVersion>,
+    user_keymap_error: Option<SharedString>,
+    binding_editor: Option<BindingEditor>,
+    capture: Option<(CaptureTarget, Subscription)>,
+    scroll_handle: UniformListScrollHandle,
+    reload_task: Task<()>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl KeymapEditor {
+    pub fn new(
+        workspace: &Workspace,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) -> Entity<Self> {
+        let workspace_handle = workspace.weak_handle();
+        let fs = workspace.app_state().fs.clone();
+        cx.new(|cx| {
+            let search_editor = cx.new(|cx| {
+                let mut editor = Editor::single_line(window, cx);
+                editor.set_placeholder_text("Search actions, keystrokes and contexts...", cx);
+                editor
+            });
+            let subscriptions =
+                vec![
+                    cx.subscribe(&search_editor, |this, _, event: &EditorEvent, cx| {
+                        if let EditorEvent::Edited { .. } = event {
+                            this.update_matches(cx);
+                        }
+                    }),
+                ];
+
+            let mut this = Self {
+                workspace: workspace_handle,
+                fs,
+                focus_handle: cx.focus_handle(),
+                capture_focus_handle: cx.focus_handle(),
+                search_editor,
+                search_keystrokes: Vec::new(),
+                bindings: Vec::new(),
+                entries: Vec::new(),
+                matches: Vec::new(),
+                keymap_version: None,
+                user_keymap_error: None,
+                binding_editor: None,
+                capture: None,
+                scroll_handle: UniformListScrollHandle::new(),
+                reload_task: Task::ready(()),
+                _subscriptions: subscriptions,
+            };
+            this.reload(cx);
+            this
+        })
+    }
+
+    /// Reloads the bindings from the app's keymap, and reads the user's keymap file to find out
+    /// which of them it contains.
+    fn reload(&mut self, cx: &mut Context<Self>) {
+        self.keymap_version = Some(cx.key_bindings().borrow().version());
+        let fs = self.fs.clone();
+        self.reload_task = cx.spawn(async move |this, cx| {
+            let user_keymap = KeymapFile::load_keymap_file(&fs)
+                .await
+                .and_then(|text| KeymapFile::parse(&text));
+            this.update(cx, |this, cx| {
+                let user_keymap = match user_keymap {
+                    Ok(user_keymap) => {
+                        this.user_keymap_error = None;
+                        user_keymap
+                    }
+                    Err(error) => {
+                        this.user_keymap_error =
+                            Some(format!("Couldn't read your keymap file: {error}").into());
+                        KeymapFile::default()
+                    }
+                };
+                this.bindings = load_bindings(&user_keymap, cx);
+                this.entries = keymap_entries(&this.bindings, cx);
+                this.binding_editor = None;
+                this.update_matches(cx);
+            })
+            .ok();
+        });
+    }
+
+    fn update_matches(&mut self, cx: &mut Context<Self>) {
+        let query = self.search_editor.read(cx).text(cx).trim().to_lowercase();
+        let search_keystrokes = self
+            .search_keystrokes
+            .iter()
+            .map(Keystroke::unparse)
+            .collect::<Vec<_>>();
+
+        self.matches = self
+            .entries
+            .iter()
+            .enumerate()
+            .filter(|(_, entry)| {
+                let binding = entry.binding_ix.map(|ix| &self.bindings[ix]);
+                if !search_keystrokes.is_empty() {
+                    let Some(binding) = binding else {
+                        return false;
+                    };
+                    let keystrokes = binding.key_binding.keystrokes();
+                    return keystrokes.len() >= search_keystrokes.len()
+                        && keystrokes
+                            .iter()
+                            .zip(&search_keystrokes)
+                            .all(|(keystroke, search)| keystroke.unparse() == *search);
+                }
+
+                query.is_empty()
+                    || entry.action_name.to_lowercase().contains(&query)
+                    || binding.is_some_and(|binding| {
+                        binding.keystrokes.contains(&query)
+                            || binding.context.to_lowercase().contains(&query)
+                    })
+            })
+            .map(|(ix, _)| ix)
+            .collect();
+        cx.notify();
+    }
+
+    fn start_capture(
+        &mut self,
+        target: CaptureTarget,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let this = cx.entity().downgrade();
+        let subscription = cx.intercept_keystrokes(move |event, window, cx| {
+            this.update(cx, |this, cx| {
+                if this.capture_keystroke(&event.keystroke, window, cx) {
+                    cx.stop_propagation();
+                }
+            })
+            .ok();
+        });
+        match target {
+            CaptureTarget::Search => {
+                self.search_keystrokes.clear();
+                self.update_matches(cx);
+            }
+            CaptureTarget::Binding => {
+                if let Some(binding_editor) = self.binding_editor.as_mut() {
+                    binding_editor.keystrokes.clear();
+                }
+            }
+        }
+        window.focus(&self.capture_focus_handle);
+        self.capture = Some((target, subscription));
+        cx.notify();
+    }
+
+    fn stop_capture(&mut self, cx: &mut Context<Self>) {
+        if self.capture.take().is_some() {
+            cx.notify();
+        }
+    }
+
+    /// Records a keystroke while capturing keystrokes, returning whether it was captured. Escape
+    /// stops capturing, as does moving focus away.
+    fn capture_keystroke(
+        &mut self,
+        keystroke: &Keystroke,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> bool {
+        let Some((target, _)) = &self.capture else {
+            return false;
+        };
+        let target = *target;
+        if !self.capture_focus_handle.is_focused(window) {
+            self.stop_capture(cx);
+            return false;
+        }
+        if keystroke.key == "escape" && !keystroke.modifiers.modified() {
+            self.stop_capture(cx);
+            return true;
+        }
+
+        let keystroke = Keystroke {
+            key_char: None,
+            ..keystroke.clone()
+        };
+        match target {
+            CaptureTarget::Search => {
+                self.search_keystrokes.push(keystroke);
+                self.update_matches(cx);
+            }
+            CaptureTarget::Binding => {
+                if let Some(binding_editor) = self.binding_editor.as_mut() {
+                    binding_editor.keystrokes.push(keystroke);
+                    binding_editor.error = None;
+                }
+                cx.notify();
+            }
+        }
+        true
+    }
+
+    fn edit_binding(
+        &mut self,
+        action_name: SharedString,
+        binding_ix: Option<usize>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let binding = binding_ix.map(|ix| &self.bindings[ix]);
+        let context = binding
+            .map(|binding| match &binding.user_binding {
+                Some(user_binding) => user_binding.context.clone(),
+                None => binding.context.clone(),
+            })
+            .unwrap_or_default();
+        let keystrokes = binding
+            .map(|binding| binding.key_binding.keystrokes().to_vec())
+            .unwrap_or_default();
+        let context_editor = cx.new(|cx| {
+            let mut editor = Editor::single_line(window, cx);
+            editor.set_placeholder_text("Context, e.g. Editor && mode == full", cx);
+            editor.set_text(context, window, cx);
+            editor
+        });
+
+        self.binding_editor = Some(BindingEditor {
+            action_name,
+            binding_ix,
+            keystrokes,
+            context_editor,
+            error: None,
+        });
+        self.start_capture(CaptureTarget::Binding, window, cx);
+    }
+
+    fn save_binding(&mut self, cx: &mut Context<Self>) {
+        match self.binding_operations(cx) {
+            Ok(operations) => {
+                self.binding_editor = None;
+                self.stop_capture(cx);
+                self.update_keymap(operations, cx);
+            }
+            Err(error) => {
+                if let Some(binding_editor) = self.binding_editor.as_mut() {
+                    binding_editor.error = Some(error);
+                }
+                cx.notify();
+            }
+        }
+    }
+
+    /// Returns the changes to the user's keymap file that save the binding being edited.
+    fn binding_operations(&self, cx: &App) -> Result<Vec<KeybindUpdateOperation>, SharedString> {
+        let Some(binding_editor) = self.binding_editor.as_ref() else {
+            return Ok(Vec::new());
+        };
+        if binding_editor.keystrokes.is_empty() {
+            return Err("Press the keystrokes to bind first.".into());
+        }
+        let context = binding_editor.context_editor.read(cx).text(cx);
+        let context = context.trim().to_string();
+        if !context.is_empty() {
+            KeyBindingContextPredicate::parse(&context)
+                .map_err(|error| SharedString::from(format!("Invalid context: {error}")))?;
+        }
+        let keystrokes = keystrokes_text(&binding_editor.keystrokes);
+
+        let binding = binding_editor.binding_ix.map(|ix| &self.bindings[ix]);
+        if let Some(user_binding) = binding.and_then(|binding| binding.user_binding.as_ref()) {
+            return Ok(vec![KeybindUpdateOperation::Replace {
+                context: user_binding.context.clone(),
+                keystrokes: user_binding.keystrokes.clone(),
+                target: KeybindUpdateTarget {
+                    context,
+                    keystrokes,
+                    action: user_binding.action.clone(),
+                },
+            }]);
+        }
+
+        // Bindings that aren't in the user's keymap file can only be written there when their
+        // action can be built from its name, as their arguments aren't known.
+        let action_name = binding_editor.action_name.as_ref();
+        let action = cx.build_action(action_name, None).ok().filter(|action| {
+            binding.is_none_or(|binding| action.partial_eq(binding.key_binding.action()))
+        });
+        if action.is_none() {
+            return Err(
+                "This action takes arguments, so it can only be bound in your keymap file.".into(),
+            );
+        }
+
+        let mut operations = vec![KeybindUpdateOperation::Add(KeybindUpdateTarget {
+            context: context.clone(),
+            keystrokes: keystrokes.clone(),
+            action: Value::String(action_name.to_string()),
+        })];
+        // Rebinding a base binding removes its old keystrokes.
+        if let Some(binding) = binding {
+            if binding.keystrokes != keystrokes || binding.context != context {
+                operations.push(KeybindUpdateOperation::Add(KeybindUpdateTarget {
+                    context: binding.context.clone(),
+                    keystrokes: binding.keystrokes.clone(),
+                    action: Value::Null,
+                }));
+            }
+        }
+        Ok(operations)
+    }
+
+    /// Removes a binding from the user's keymap file, or unbinds it there if it's a base binding.
+    fn remove_binding(&mut self, binding_ix: usize, cx: &mut Context<Self>) {
+        let binding = &self.bindings[binding_ix];
+        let operation = match &binding.user_binding {
+            Some(user_binding) => KeybindUpdateOperation::Remove {
+                context: user_binding.context.clone(),
+                keystrokes: user_binding.keystrokes.clone(),
+            },
+            None => KeybindUpdateOperation::Add(KeybindUpdateTarget {
+                context: binding.context.clone(),
+                keystrokes: binding.keystrokes.clone(),
+                action: Value::Null,
+            }),
+        };
+        self.update_keymap(vec![operation], cx);
+    }
+
+    fn update_keymap(&self, operations: Vec<KeybindUpdateOperation>, cx: &mut Context<Self>) {
+        let fs = self.fs.clone();
+        let workspace = self.workspace.clone();
+        cx.spawn(async move |_, cx| {
+            let result = update_keymap_file(&fs, operations).await;
+            if let Err(error) = result {
+                workspace
+                    .update(cx, |workspace, cx| workspace.show_error(&error, cx))
+                    .ok();
+            }
+        })
+        .detach();
+    }
+
+    fn binding_status(&self, binding: &BindingInfo) -> Option<(SharedString, Color)> {
+        // Show the conflict that affects the binding the most, and of those, the one with the
+        // latest other binding.
+        let conflict = binding
+            .conflicts
+            .iter()
+            .max_by_key(|conflict| match conflict {
+                Conflict::ShadowedBy(_) => 4,
+                Conflict::BlockedBy(_) => 3,
+                Conflict::NarrowedBy(_) => 2,
+                Conflict::PrefixOf(_) => 1,
+                Conflict::Overrides(_) => 0,
+            })?;
+        let (message, color) = match *conflict {
+            Conflict::ShadowedBy(ix) => {
+                let other = &self.bindings[ix];
+                let message = if other.is_no_action() {
+                    "Removed in your keymap".to_string()
+                } else if binding.source == KeybindSource::Base
+                    && other.source == KeybindSource::User
+                {
+                    format!(
+                        "Shadowed by {} in your keymap",
+                        other.key_binding.action().name()
+                    )
+                } else {
+                    format!(
+                        "Conflicts with {}, which takes precedence",
+                        other.key_binding.action().name()
+                    )
+                };
+                (message, Color::Warning)
+            }
+            Conflict::BlockedBy(ix) => {
+                let other = &self.bindings[ix];
+                let message = if other.is_no_action() {
+                    format!("Unreachable, since {} is removed", other.keystrokes)
+                } else {
+                    format!(
+                        "Unreachable, since {} is bound to {}",
+                        other.keystrokes,
+                        other.key_binding.action().name()
+                    )
+                };
+                (with_context(message, other), Color::Warning)
+            }
+            Conflict::NarrowedBy(ix) => {
+                let other = &self.bindings[ix];
+                let message = if other.is_no_action() {
+                    "Removed".to_string()
+                } else {
+                    format!("Overridden by {}", other.key_binding.action().name())
+                };
+                (with_context(message, other), Color::Muted)
+            }
+            Conflict::PrefixOf(ix) => {
+                let other = &self.bindings[ix];
+                let message = format!(
+                    "Waits for another keystroke, since {} is also bound",
+                    other.keystrokes
+                );
+                (with_context(message, other), Color::Warning)
+            }
+            Conflict::Overrides(ix) => {
+                let other = &self.bindings[ix];
+                let message = if binding.source == KeybindSource::User
+                    && other.source == KeybindSource::Base
+                {
+                    format!(
+                        "Overrides {} from the base keymap",
+                        other.key_binding.action().name()
+                    )
+                } else {
+                    format!("Conflicts with {}", other.key_binding.action().name())
+                };
+                (message, Color::Muted)
+            }
+        };
+        Some((message.into(), color))
+    }
+
+    fn render_entries(
+        &mut self,
+        range: Range<usize>,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Vec<AnyElement> {
+        range
+            .filter_map(|ix| {
+                let entry_ix = *self.matches.get(ix)?;
+                Some(self.render_entry(entry_ix, cx))
+            })
+            .collect()
+    }
+
+    fn render_entry(&self, entry_ix: usize, cx: &mut Context<Self>) -> AnyElement {
+        let entry = &self.entries[entry_ix];
+        let binding_ix = entry.binding_ix;
+        let binding = binding_ix.map(|ix| &self.bindings[ix]);
+        let action_name = entry.action_name.clone();
+        let status = binding.and_then(|binding| self.binding_status(binding));
+        let removed_by = binding
+            .and_then(|binding| {
+                binding
+                    .conflicts
+                    .iter()
+                    .rev()
+                    .find_map(|conflict| match conflict {
+                        Conflict::ShadowedBy(ix) => Some(*ix),
+                        _ => None,
+                    })
+            })
+            .filter(|ix| self.bindings[*ix].is_no_action());
+
+        h_flex()
+            .id(("keymap-entry", entry_ix))
+            .w_full()
+            .h_8()
+            .gap_2()
+            .px_2()
+            .border_b_1()
+            .border_color(cx.theme().colors().border_variant)
+            .child(
+                div()
+                    .w(rems(20.))
+                    .overflow_hidden()
+                    .child(Label::new(action_name.clone()).buffer_font(cx).truncate()),
+            )
+            .child(
+                div().w(rems(12.)).children(
+                    binding.map(|binding| KeyBinding::new(binding.key_binding.clone(), cx)),
+                ),
+            )
+            .child(
+                div().flex_1().min_w_0().overflow_hidden().children(
+                    binding
+                        .filter(|binding| !binding.context.is_empty())
+                        .map(|binding| {
+                            Label::new(binding.context.clone())
+                                .size(LabelSize::Small)
+                                .color(Color::Muted)
+                                .buffer_font(cx)
+                                .truncate()
+                        }),
+                ),
+            )
+            .child(div().w(rems(4.)).children(binding.map(|binding| {
+                Label::new(match binding.source {
+                    KeybindSource::Base => "Base",
+                    KeybindSource::User => "User",
+                })
+                .size(LabelSize::Small)
+                .color(Color::Muted)
+            })))
+            .child(
+                div()
+                    .w(rems(18.))
+                    .overflow_hidden()
+                    .children(status.map(|(message, color)| {
+                        Label::new(message)
+                            .size(LabelSize::Small)
+                            .color(color)
+                            .truncate()
+                    })),
+            )
+            .child(
+                h_flex()
+                    .w(rems(4.))
+                    .gap_1()
+                    .justify_end()
+                    .child(
+                        IconButton::new(
+                            ("edit-binding", entry_ix),
+                            if binding.is_some() {
+                                IconName::Pencil
+                            } else {
+                                IconName::Plus
+                            },
+                        )
+                        .icon_size(IconSize::Small)
+                        .tooltip(Tooltip::text(if binding.is_some() {
+                            "Change Binding"
+                        } else {
+                            "Add Binding"
+                        }))
+                        .on_click(cx.listener(
+                            move |this, _, window, cx| {
+                                this.edit_binding(action_name.clone(), binding_ix, window, cx);
+                            },
+                        )),
+                    )
+                    .children(binding_ix.map(|binding_ix| {
+                        match removed_by {
+                            Some(removed_by) => {
+                                IconButton::new(("restore-binding", entry_ix), IconName::RotateCcw)
+                                    .icon_size(IconSize::Small)
+                                    .tooltip(Tooltip::text("Restore Binding"))
+                                    .on_click(cx.listener(move |this, _, _, cx| {
+                                        this.remove_binding(removed_by, cx);
+                                    }))
+                            }
+                            None => IconButton::new(("remove-binding", entry_ix), IconName::Trash)
+                                .icon_size(IconSize::Small)
+                                .tooltip(Tooltip::text("Remove Binding"))
+                                .on_click(cx.listener(move |this, _, _, cx| {
+                                    this.remove_binding(binding_ix, cx);
+                                })),
+                        }
+                    })),
+            )
+            .into_any_element()
+    }
+
+    fn render_keystrokes(
+        &self,
+        id: &'static str,
+        target: CaptureTarget,
+        keystrokes: &[Keystroke],
+        placeholder: &'static str,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        let capturing = self
+            .capture
+            .as_ref()
+            .is_some_and(|(capture_target, _)| *capture_target == target);
+        let text = if keystrokes.is_empty() {
+            placeholder.to_string()
+        } else {
+            text_for_keystrokes(keystrokes, cx)
+        };
+
+        h_flex()
+            .id(id)
+            .when(capturing, |this| {
+                this.track_focus(&self.capture_focus_handle)
+            })
+            .h_8()
+            .min_w(rems(12.))
+            .px_2()
+            .gap_2()
+            .border_1()
+            .rounded_lg()
+            .border_color(if capturing {
+                cx.theme().colors().border_focused
+            } else {
+                cx.theme().colors().border
+            })
+            .child(
+                Icon::new(IconName::Keyboard)
+                    .size(IconSize::Small)
+                    .color(if capturing {
+                        Color::Accent
+                    } else {
+                        Color::Muted
+                    }),
+            )
+            .child(
+                Label::new(text)
+                    .buffer_font(cx)
+                    .color(if keystrokes.is_empty() {
+                        Color::Muted
+                    } else {
+                        Color::Default
+                    }),
+            )
+            .tooltip(Tooltip::text(if capturing {
+                "Press Escape to stop recording keystrokes"
+            } else {
+                "Click to record keystrokes"
+            }))
+            .on_click(cx.listener(move |this, _, window, cx| {
+                this.start_capture(target, window, cx);
+            }))
+    }
+
+    fn render_binding_editor(&self, cx: &mut Context<Self>) -> Option<impl IntoElement> {
+        let binding_editor = self.binding_editor.as_ref()?;
+        Some(
+            v_flex()
+                .p_2()
+                .gap_2()
+                .border_1()
+                .rounded_lg()
+                .border_color(cx.theme().colors().border)
+                .child(
+                    Label::new(format!("Binding for {}", binding_editor.action_name))
+                        .buffer_font(cx),
+                )
+                .child(
+                    h_flex()
+                        .gap_2()
+                        .child(self.render_keystrokes(
+                            "binding-keystrokes",
+                            CaptureTarget::Binding,
+                            &binding_editor.keystrokes,
+                            "Record keystrokes",
+                            cx,
+                        ))
+                        .child(
+                            div()
+                                .flex_1()
+                                .h_8()
+                                .px_2()
+                                .py_1()
+                                .border_1()
+                                .rounded_lg()
+                                .border_color(cx.theme().colors().border)
+                                .child(binding_editor.context_editor.clone()),
+                        )
+                        .child(
+                            Button::new("save-binding", "Save")
+                                .style(ButtonStyle::Filled)
+                                .on_click(cx.listener(|this, _, _, cx| this.save_binding(cx))),
+                        )
+                        .child(
+                            Button::new("cancel-binding", "Cancel").on_click(cx.listener(
+                                |this, _, _, cx| {
+                                    this.binding_editor = None;
+                                    this.stop_capture(cx);
+                                },
+                            )),
+                        ),
+                )
+                .children(
+                    binding_editor
+                        .error
+                        .clone()
+                        .map(|error| Label::new(error).size(LabelSize::Small).color(Color::Error)),
+                ),
+        )
+    }
+}
+
+/// Applies changes to the user's keymap file.
+pub(crate) async fn update_keymap_file(
+    fs: &Arc<dyn Fs>,
+    operations: Vec<KeybindUpdateOperation>,
+) -> anyhow::Result<()> {
+    let mut keymap_text = KeymapFile::load_keymap_file(fs).await?;
+    for operation in operations {
+        keymap_text = KeymapFile::update_keybinding(keymap_text, operation, KEYMAP_TAB_SIZE)?;
+    }
+    fs.atomic_write(paths::keymap_file().clone(), keymap_text)
+        .await
+}
+
+/// Loads the bindings in the app's keymap, finding the ones that came from the user's keymap file
+/// and how the bindings conflict with each other.
+fn load_bindings(user_keymap: &KeymapFile, cx: &App) -> Vec<BindingInfo> {
+    let mut user_bindings = user_keymap
+        .sections()
+        .flat_map(|section| {
+            section.bindings().map(|(keystrokes, action)| {
+                Some(UserBinding::new(section.context(), keystrokes, action))
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let keymap = cx.key_bindings();
+    let keymap = keymap.borrow();
+    let mut bindings = keymap
+        .bindings()
+        .map(|key_binding| BindingInfo {
+            key_binding: key_binding.clone(),
+            keystrokes: keystrokes_text(key_binding.keystrokes()),
+            context: key_binding
+                .predicate()
+                .map(|predicate| predicate.to_string())
+                .unwrap_or_default(),
+            source: KeybindSource::Base,
+            user_binding: None,
+            conflicts: Vec::new(),
+        })
+        .collect::<Vec<_>>();
+
+    // The user's keymap is loaded last, so match its bindings from the end of the keymap.
+    for binding in bindings.iter_mut().rev() {
+        let user_binding = user_bindings
+            .iter_mut()
+            .rev()
+            .find(|user_binding| {
+                user_binding
+                    .as_ref()
+                    .is_some_and(|user_binding| user_binding.matches(binding))
+            })
+            .and_then(Option::take);
+        if let Some(user_binding) = user_binding {
+            binding.source = KeybindSource::User;
+            binding.user_binding = Some(user_binding);
+        }
+    }
+
+    let predicates = bindings
+        .iter()
+        .map(|binding| {
+            binding
+                .key_binding
+                .predicate()
+                .map(|predicate| normalize_predicate(&predicate))
+        })
+        .collect::<Vec<_>>();
+
+    // Only bindings that start with the same keystroke can conflict.
+    let mut bindings_by_first_keystroke = HashMap::<Keystroke, Vec<usize>>::default();
+    for (ix, binding) in bindings.iter().enumerate() {
+        if let Some(keystroke) = binding.key_binding.keystrokes().first() {
+            bindings_by_first_keystroke
+                .entry(keystroke.clone())
+                .or_default()
+                .push(ix);
+        }
+    }
+    let mut conflicts = Vec::new();
+    for indices in bindings_by_first_keystroke.values() {
+        for (position, &earlier_ix) in indices.iter().enumerate() {
+            for &later_ix in &indices[position + 1..] {
+                let earlier = &bindings[earlier_ix];
+                let later = &bindings[later_ix];
+                // Binding the same action again doesn't change which action runs.
+                if earlier
+                    .key_binding
+                    .action()
+                    .partial_eq(later.key_binding.action())
+                {
+                    continue;
+                }
+                let later_covers_earlier =
+                    context_covers(&predicates[later_ix], &predicates[earlier_ix]);
+                if !later_covers_earlier
+                    && !context_covers(&predicates[earlier_ix], &predicates[later_ix])
+                {
+                    // Which binding wins depends on how deep in the focused element's contexts
+                    // each one matches, so neither is flagged.
+                    continue;
+                }
+
+                let earlier_keystrokes = earlier.key_binding.keystrokes();
+                let later_keystrokes = later.key_binding.keystrokes();
+                // Typing the later binding's keystrokes either completes the earlier binding, or
+                // leaves it pending on more keystrokes.
+                let earlier_match = earlier.key_binding.match_keystrokes(later_keystrokes);
+                if earlier_match == Some(false) {
+                    let conflict = if later_covers_earlier {
+                        Conflict::ShadowedBy(later_ix)
+                    } else {
+                        Conflict::NarrowedBy(later_ix)
+                    };
+                    conflicts.push((earlier_ix, conflict));
+                    if !earlier.is_no_action() {
+                        conflicts.push((later_ix, Conflict::Overrides(earlier_ix)));
+                    }
+                } else if earlier_match == Some(true) {
+                    conflicts.push((earlier_ix, Conflict::BlockedBy(later_ix)));
+                } else if later.key_binding.match_keystrokes(earlier_keystrokes) == Some(true) {
+                    conflicts.push((earlier_ix, Conflict::PrefixOf(later_ix)));
+                }
+            }
+        }
+    }
+    for (ix, conflict) in conflicts {
+        bindings[ix].conflicts.push(conflict);
+    }
+
+    bindings
+}
+
+/// Whether `predicate` is enabled wherever `other` is, where `None` is the predicate of a binding
+/// without a context, which is enabled everywhere.
+fn context_covers(
+    predicate: &Option<KeyBindingContextPredicate>,
+    other: &Option<KeyBindingContextPredicate>,
+) -> bool {
+    match (predicate, other) {
+        (None, _) => true,
+        (Some(_), None) => false,
+        (Some(predicate), Some(other)) => predicate_covers(predicate, other),
+    }
+}
+
+fn predicate_covers(
+    predicate: &KeyBindingContextPredicate,
+    other: &KeyBindingContextPredicate,
+) -> bool {
+    match predicate {
+        // `is_superset` only finds single operands of `other`, so `a && b` doesn't cover
+        // `a && b && c` unless each operand is checked on its own.
+        KeyBindingContextPredicate::And(left, right) => {
+            predicate_covers(left, other) && predicate_covers(right, other)
+        }
+        _ => predicate.is_superset(other),
+    }
+}
+
+/// Puts the operands of `&&` and `||` in a consistent order, so that equivalent predicates like
+/// `a && b` and `b && a` compare equal.
+fn normalize_predicate(predicate: &KeyBindingContextPredicate) -> KeyBindingContextPredicate {
+    fn collect_operands(
+        predicate: &KeyBindingContextPredicate,
+        is_and: bool,
+        operands: &mut Vec<KeyBindingContextPredicate>,
+    ) {
+        match predicate {
+            KeyBindingContextPredicate::And(left, right) if is_and => {
+                collect_operands(left, is_and, operands);
+                collect_operands(right, is_and, operands);
+            }
+            KeyBindingContextPredicate::Or(left, right) if !is_and => {
+                collect_operands(left, is_and, operands);
+                collect_operands(right, is_and, operands);
+            }
+            _ => operands.push(normalize_predicate(predicate)),
+        }
+    }
+
+    let is_and = match predicate {
+        KeyBindingContextPredicate::And(..) => true,
+        KeyBindingContextPredicate::Or(..) => false,
+        KeyBindingContextPredicate::Child(parent, child) => {
+            return KeyBindingContextPredicate::Child(
+                Box::new(normalize_predicate(parent)),
+                Box::new(normalize_predicate(child)),
+            );
+        }
+        KeyBindingContextPredicate::Not(predicate) => {
+            return KeyBindingContextPredicate::Not(Box::new(normalize_predicate(predicate)));
+        }
+        _ => return predicate.clone(),
+    };
+    let mut operands = Vec::new();
+    collect_operands(predicate, is_and, &mut operands);
+    operands.sort_by_cached_key(|operand| operand.to_string());
+    operands.dedup();
+    operands
+        .into_iter()
+        .reduce(|left, right| {
+            if is_and {
+                KeyBindingContextPredicate::And(Box::new(left), Box::new(right))
+            } else {
+                KeyBindingContextPredicate::Or(Box::new(left), Box::new(right))
+            }
+        })
+        .unwrap_or_else(|| predicate.clone())
+}
+
+/// Appends the context of `binding` to a status message about it, if it has one.
+fn with_context(message: String, binding: &BindingInfo) -> String {
+    if binding.context.is_empty() {
+        message
+    } else {
+        format!("{message} in {}", binding.context)
+    }
+}
+
+/// Lists each binding of every action, and every action without bindings, sorted by action name.
+fn keymap_entries(bindings: &[BindingInfo], cx: &App) -> Vec<KeymapEntry> {
+    let mut entries = Vec::new();
+    let mut bound_actions = HashSet::default();
+    // List later bindings first, as they take precedence.
+    for (ix, binding) in bindings.iter().enumerate().rev() {
+        if binding.is_no_action() {
+            continue;
+        }
+        let action_name = binding.key_binding.action().name();
+        bound_actions.insert(action_name);
+        entries.push(KeymapEntry {
+            action_name: action_name.to_string().into(),
+            binding_ix: Some(ix),
+        });
+    }
+    for action_name in cx.all_action_names() {
+        if !bound_actions.contains(action_name.as_ref()) && action_name.as_ref() != NoAction.name()
+        {
+            entries.push(KeymapEntry {
+                action_name: action_name.clone(),
+                binding_ix: None,
+            });
+        }
+    }
+    entries.sort_by(|a, b| a.action_name.cmp(&b.action_name));
+    entries
+}
+
+fn keystrokes_text(keystrokes: &[Keystroke]) -> String {
+    keystrokes
+        .iter()
+        .map(Keystroke::unparse)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+impl EventEmitter<ItemEvent> for KeymapEditor {}
+
+impl Focusable for KeymapEditor {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.search_editor.focus_handle(cx)
+    }
+}
+
+impl Item for KeymapEditor {
+    type Event = ItemEvent;
+
+    fn tab_icon(&self, _window: &Window, _cx: &App) -> Option<Icon> {
+        Some(Icon::new(IconName::Keyboard))
+    }
+
+    fn tab_content_text(&self, _detail: usize, _cx: &App) -> SharedString {
+        "Keymap Editor".into()
+    }
+
+    fn show_toolbar(&self) -> bool {
+        false
+    }
+
+    fn to_item_events(event: &Self::Event, mut f: impl FnMut(ItemEvent)) {
+        f(*event)
+    }
+}
+
+impl Render for KeymapEditor {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        if self.keymap_version != Some(cx.key_bindings().borrow().version()) {
+            self.reload(cx);
+        }
+
+        let searching_keystrokes = !self.search_keystrokes.is_empty()
+            || self
+                .capture
+                .as_ref()
+                .is_some_and(|(target, _)| *target == CaptureTarget::Search);
+
+        v_flex()
+            .key_context("KeymapEditor")
+            .track_focus(&self.focus_handle)
+            .p_4()
+            .size_full()
+            .gap_4()
+            .child(
+                h_flex()
+                    .gap_2()
+                    .child(
+                        h_flex()
+                            .flex_1()
+                            .h_8()
+                            .px_2()
+                            .gap_2()
+                            .border_1()
+                            .rounded_lg()
+                            .border_color(cx.theme().colors().border)
+                            .child(Icon::new(IconName::MagnifyingGlass).color(Color::Muted))
+                            .child(self.search_editor.clone()),
+                    )
+                    .child(self.render_keystrokes(
+                        "search-keystrokes",
+                        CaptureTarget::Search,
+                        &self.search_keystrokes,
+                        "Search by keystroke",
+                        cx,
+                    ))
+                    .when(searching_keystrokes, |this| {
+                        this.child(
+                            IconButton::new("clear-search-keystrokes", IconName::Close)
+                                .icon_size(IconSize::Small)
+                                .tooltip(Tooltip::text("Clear Keystrokes"))
+                                .on_click(cx.listener(|this, _, _, cx| {
+                                    this.search_keystrokes.clear();
+                                    this.stop_capture(cx);
+                                    this.update_matches(cx);
+                                })),
+                        )
+                    })
+                    .child(
+                        Button::new("open-keymap-file", "Open Keymap File").on_click(
+                            |_, window, cx| {
+                                window.dispatch_action(zed_actions::OpenKeymap.boxed_clone(), cx)
+                            },
+                        ),
+                    ),
+            )
+            .children(
+                self.user_keymap_error
+                    .clone()
+                    .map(|error| Label::new(error).size(LabelSize::Small).color(Color::Error)),
+            )
+            .children(self.render_binding_editor(cx))
+            .child(v_flex().size_full().overflow_y_hidden().map(|this| {
+                if self.matches.is_empty() {
+                    this.child(Label::new("No matching actions").color(Color::Muted))
+                } else {
+                    this.child(
+                        uniform_list(
+                            cx.entity(),
+                            "keymap-entries",
+                            self.matches.len(),
+                            Self::render_entries,
+                        )
+                        .size_full()
+                        .track_scroll(self.scroll_handle.clone()),
+                    )
+                }
+            }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    gpui::actions!(keymap_test, [First, Second, Third]);
+
+    fn load_test_bindings(base_keymap: &str, user_keymap: &str, cx: &mut App) -> Vec<BindingInfo> {
+        cx.bind_keys(KeymapFile::load_panic_on_failure(base_keymap, cx));
+        cx.bind_keys(KeymapFile::load_panic_on_failure(user_keymap, cx));
+        load_bindings(&KeymapFile::parse(user_keymap).unwrap(), cx)
+    }
+
+    #[gpui::test]
+    fn test_binding_conflicts(cx: &mut App) {
+        let bindings = load_test_bindings(
+            r#"[
+                { "bindings": { "ctrl-a": "keymap_test::First", "ctrl-k": "keymap_test::First" } },
+                { "context": "Editor && mode == full", "bindings": { "ctrl-b": "keymap_test::First" } },
+                {
+                    "context": "Editor",
+                    "bindings": {
+                        "ctrl-k ctrl-s": "keymap_test::Second",
+                        "ctrl-c": "keymap_test::First",
+                        "ctrl-d ctrl-d": "keymap_test::First"
+                    }
+                },
+                { "context": "Editor && mode == full", "bindings": { "ctrl-c": "keymap_test::Second" } },
+                { "context": "Workspace", "bindings": { "ctrl-c": "keymap_test::Third" } }
+            ]"#,
+            r#"[
+                { "context": "mode == full && Editor", "bindings": { "ctrl-b": "keymap_test::Second" } },
+                { "bindings": { "ctrl-a": null, "ctrl-d": "keymap_test::Second" } }
+            ]"#,
+            cx,
+        );
+        assert_eq!(bindings.len(), 11);
+
+        // Removed in the user's keymap.
+        assert_eq!(bindings[0].conflicts, [Conflict::ShadowedBy(9)]);
+        // A prefix of a later chord in a narrower context.
+        assert_eq!(bindings[1].conflicts, [Conflict::PrefixOf(3)]);
+        assert!(bindings[3].conflicts.is_empty());
+        // Shadowed by a user binding in an equivalent context.
+        assert_eq!(bindings[2].conflicts, [Conflict::ShadowedBy(8)]);
+        assert_eq!(bindings[8].conflicts, [Conflict::Overrides(2)]);
+        // Overridden in a narrower context, and not affected by the binding in an unrelated
+        // context.
+        assert_eq!(bindings[4].conflicts, [Conflict::NarrowedBy(6)]);
+        assert_eq!(bindings[6].conflicts, [Conflict::Overrides(4)]);
+        assert!(bindings[7].conflicts.is_empty());
+        // A later binding for the prefix makes the chord unreachable.
+        assert_eq!(bindings[5].conflicts, [Conflict::BlockedBy(10)]);
+        assert!(bindings[10].conflicts.is_empty());
+    }
+
+    #[gpui::test]
+    fn test_user_bindings_match_loaded_bindings(cx: &mut App) {
+        let bindings = load_test_bindings(
+            r#"[
+                { "context": "Editor && mode == full", "bindings": { "ctrl-a": "keymap_test::First" } },
+                { "bindings": { "ctrl-b": "keymap_test::First" } }
+            ]"#,
+            r#"[
+                { "context": "mode == full  &&  Editor", "bindings": { "ctrl-a": "keymap_test::First" } },
+                { "bindings": { "shift-ctrl-c": "keymap_test::Second", "ctrl-b": null } }
+            ]"#,
+            cx,
+        );
+        assert_eq!(
+            bindings
+                .iter()
+                .map(|binding| binding.source)
+                .collect::<Vec<_>>(),
+            [
+                KeybindSource::Base,
+                KeybindSource::Base,
+                KeybindSource::User,
+                KeybindSource::User,
+                KeybindSource::User,
+            ]
+        );
+
+        // Rebinding the same action to the same keystrokes, in an equivalent context, doesn't
+        // conflict, and is matched to the binding as written in the user's keymap.
+        assert!(bindings[0].conflicts.is_empty());
+        let user_binding = bindings[2].user_binding.as_ref().unwrap();
+        assert_eq!(user_binding.context, "mode == full  &&  Editor");
+        assert_eq!(user_binding.keystrokes, "ctrl-a");
+
+        let user_binding = bindings[3].user_binding.as_ref().unwrap();
+        assert_eq!(user_binding.keystrokes, "shift-ctrl-c");
+        assert_eq!(
+            user_binding.action_name.as_deref(),
+            Some("keymap_test::Second")
+        );
+
+        let user_binding = bindings[4].user_binding.as_ref().unwrap();
+        assert!(user_binding.action.is_null());
+        assert_eq!(bindings[1].conflicts, [Conflict::ShadowedBy(4)]);
+    }
+
+    #[test]
+    fn test_equivalent_contexts() {
+        let predicate = |source| {
+            Some(normalize_predicate(
+                &KeyBindingContextPredicate::parse(source).unwrap(),
+            ))
+        };
+
+        let a = predicate("Editor && mode == full || Terminal");
+        let b = predicate("Terminal || mode == full && Editor");
+        assert!(context_covers(&a, &b));
+        assert!(context_covers(&b, &a));
+
+        let narrower = predicate("Editor && mode == full && !vim_mode");
+        assert!(context_covers(&predicate("Editor"), &narrower));
+        assert!(context_covers(
+            &predicate("mode == full && Editor"),
+            &narrower
+        ));
+        assert!(!context_covers(&narrower, &predicate("Editor")));
+        assert!(context_covers(&None, &narrower));
+        assert!(!context_covers(&narrower, &None));
+        assert!(!context_covers(
+            &predicate("Workspace"),
+            &predicate("Editor")
+        ));
+    }
+}
@@ -0,0 +1,400 @@
+use serde_json::{Map, Value};
+
+/// Settings nested deeper than this are edited as JSON, rather than broken
+/// down into their own settings.
+const MAX_DEPTH: usize = 3;
+
+/// Keys of the settings schema that aren't listed as settings. Languages are
+/// picked separately, and the release channel and profile keys repeat every
+/// setting.
+const SKIPPED_KEYS: &[&str] = &[
+    "languages",
+    "dev",
+    "nightly",
+    "preview",
+    "stable",
+    "profiles",
+];
+
+/// A setting that can be edited on its own, found in the settings JSON schema.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SettingEntry {
+    /// The path of keys to the setting in a settings file.
+    pub key_path: Vec<String>,
+    pub description: Option<String>,
+    pub kind: SettingKind,
+    /// Whether the setting can be overridden for each language.
+    pub language_overridable: bool,
+}
+
+impl SettingEntry {
+    pub fn title(&self) -> String {
+        humanize_key(self.key_path.last().map_or("", String::as_str))
+    }
+
+    /// The name of the group of settings this setting is listed in.
+    pub fn section(&self) -> String {
+        if self.key_path.len() > 1 {
+            humanize_key(&self.key_path[0])
+        } else {
+            "General".to_string()
+        }
+    }
+
+    pub fn matches(&self, query: &str) -> bool {
+        let query = query.to_lowercase();
+        self.key_path.join(".").to_lowercase().contains(&query)
+            || self.title().to_lowercase().contains(&query)
+            || self
+                .description
+                .as_ref()
+                .is_some_and(|description| description.to_lowercase().contains(&query))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum SettingKind {
+    Bool,
+    Enum(Vec<String>),
+    Integer {
+        minimum: Option<i64>,
+        maximum: Option<i64>,
+    },
+    Number,
+    String,
+    /// Any other value, such as a list or a map, which is edited as JSON.
+    Json,
+}
+
+/// The settings listed in a settings JSON schema, and the languages they can
+/// be overridden for.
+#[derive(Debug, Default)]
+pub struct SettingsSchema {
+    pub entries: Vec<SettingEntry>,
+    pub languages: Vec<String>,
+}
+
+impl SettingsSchema {
+    pub fn new(schema: &Value) -> Self {
+        let empty = Map::new();
+        let definitions = schema
+            .get("definitions")
+            .and_then(Value::as_object)
+            .unwrap_or(&empty);
+
+        let languages_schema = schema
+            .pointer("/properties/languages")
+            .map(|languages| resolve(languages, definitions).0);
+        let languages = languages_schema
+            .and_then(|languages| languages.get("properties"))
+            .and_then(Value::as_object)
+            .map(|languages| languages.keys().cloned().collect::<Vec<_>>())
+            .unwrap_or_default();
+        let language_keys = languages_schema
+            .and_then(|languages| languages.get("properties"))
+            .and_then(Value::as_object)
+            .and_then(|languages| languages.values().next())
+            .map(|language| resolve(language, definitions).0)
+            .and_then(|language| language.get("properties"))
+            .and_then(Value::as_object)
+            .map(|properties| properties.keys().cloned().collect::<Vec<_>>())
+            .unwrap_or_default();
+
+        let mut entries = Vec::new();
+        if let Some(properties) = schema.get("properties").and_then(Value::as_object) {
+            for (key, property) in properties {
+                if SKIPPED_KEYS.contains(&key.as_str()) {
+                    continue;
+                }
+                collect_entries(
+                    vec![key.clone()],
+                    property,
+                    definitions,
+                    language_keys.contains(key),
+                    &mut entries,
+                );
+            }
+        }
+        entries.sort_by(|a, b| {
+            (a.key_path.len() > 1, &a.key_path).cmp(&(b.key_path.len() > 1, &b.key_path))
+        });
+
+        Self { entries, languages }
+    }
+}
+
+fn collect_entries(
+    key_path: Vec<String>,
+    schema: &Value,
+    definitions: &Map<String, Value>,
+    language_overridable: bool,
+    entries: &mut Vec<SettingEntry>,
+) {
+    let (schema, description) = resolve(schema, definitions);
+    if key_path.len() < MAX_DEPTH {
+        let properties = schema
+            .get("properties")
+            .and_then(Value::as_object)
+            .filter(|properties| !properties.is_empty());
+        if let Some(properties) = properties {
+            for (key, property) in properties {
+                let mut key_path = key_path.clone();
+                key_path.push(key.clone());
+                collect_entries(
+                    key_path,
+                    property,
+                    definitions,
+                    language_overridable,
+                    entries,
+                );
+            }
+            return;
+        }
+    }
+
+    entries.push(SettingEntry {
+        key_path,
+        description,
+        kind: setting_kind(schema, definitions),
+        language_overridable,
+    });
+}
+
+/// Follows references in a schema to the schema they refer to, and unwraps
+/// optional values, returning the first description found along the way.
+fn resolve<'a>(
+    mut schema: &'a Value,
+    definitions: &'a Map<String, Value>,
+) -> (&'a Value, Option<String>) {
+    let mut description = None;
+    // References can refer to each other, so give up on cycles eventually.
+    for _ in 0..16 {
+        if description.is_none() {
+            description = schema
+                .get("description")
+                .and_then(Value::as_str)
+                .map(str::to_string);
+        }
+
+        if let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+            match reference
+                .strip_prefix("#/definitions/")
+                .and_then(|name| definitions.get(name))
+            {
+                Some(definition) => schema = definition,
+                None => break,
+            }
+            continue;
+        }
+
+        let mut subschemas = ["allOf", "anyOf", "oneOf"]
+            .iter()
+            .filter_map(|key| schema.get(*key).and_then(Value::as_array))
+            .flatten()
+            .filter(|subschema| !is_null_schema(subschema));
+        match (subschemas.next(), subschemas.next()) {
+            (Some(subschema), None) => schema = subschema,
+            _ => break,
+        }
+    }
+    (schema, description)
+}
+
+fn is_null_schema(schema: &Value) -> bool {
+    schema.get("type").and_then(Value::as_str) == Some("null")
+}
+
+fn setting_kind(schema: &Value, definitions: &Map<String, Value>) -> SettingKind {
+    if let Some(values) = enum_values(schema, definitions) {
+        return SettingKind::Enum(values);
+    }
+
+    let instance_type = match schema.get("type") {
+        Some(Value::String(instance_type)) => Some(instance_type.as_str()),
+        Some(Value::Array(instance_types)) => {
+            let mut instance_types = instance_types
+                .iter()
+                .filter_map(Value::as_str)
+                .filter(|instance_type| *instance_type != "null");
+            match (instance_types.next(), instance_types.next()) {
+                (Some(instance_type), None) => Some(instance_type),
+                _ => None,
+            }
+        }
+        _ => None,
+    };
+    match instance_type {
+        Some("boolean") => SettingKind::Bool,
+        Some("integer") => SettingKind::Integer {
+            minimum: schema.get("minimum").and_then(Value::as_i64),
+            maximum: schema.get("maximum").and_then(Value::as_i64),
+        },
+        Some("number") => SettingKind::Number,
+        Some("string") => SettingKind::String,
+        _ => SettingKind::Json,
+    }
+}
+
+/// Returns the strings a setting can be, if it can only be one of a few.
+/// Enums with documented variants are a `oneOf` of single valued enums.
+fn enum_values(schema: &Value, definitions: &Map<String, Value>) -> Option<Vec<String>> {
+    if let Some(values) = schema.get("enum").and_then(Value::as_array) {
+        return values
+            .iter()
+            .filter(|value| !value.is_null())
+            .map(|value| value.as_str().map(str::to_string))
+            .collect();
+    }
+    if let Some(value) = schema.get("const").and_then(Value::as_str) {
+        return Some(vec![value.to_string()]);
+    }
+
+    let subschemas = schema
+        .get("oneOf")
+        .or_else(|| schema.get("anyOf"))
+        .and_then(Value::as_array)?;
+    let mut values = Vec::new();
+    for subschema in subschemas {
+        if is_null_schema(subschema) {
+            continue;
+        }
+        let (subschema, _) = resolve(subschema, definitions);
+        values.extend(enum_values(subschema, definitions)?);
+    }
+    (!values.is_empty()).then_some(values)
+}
+
+/// Turns a settings key like `tab_size` into a title like "Tab Size".
+pub fn humanize_key(key: &str) -> String {
+    key.split('_')
+        .filter(|word| !word.is_empty())
+        .map(|word| {
+            let mut chars = word.chars();
+            chars
+                .next()
+                .map(|first| first.to_uppercase().chain(chars).collect::<String>())
+                .unwrap_or_default()
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_settings_schema() {
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "vim_mode": {
+                    "description": "Whether to enable vim mode.",
+                    "type": ["boolean", "null"]
+                },
+                "tab_size": {
+                    "description": "How many columns a tab should occupy.",
+                    "type": ["integer", "null"],
+                    "minimum": 1
+                },
+                "soft_wrap": {
+                    "allOf": [{ "$ref": "#/definitions/SoftWrap" }]
+                },
+                "scrollbar": {
+                    "anyOf": [{ "$ref": "#/definitions/Scrollbar" }, { "type": "null" }]
+                },
+                "file_types": {
+                    "type": "object",
+                    "additionalProperties": { "type": "array", "items": { "type": "string" } }
+                },
+                "languages": { "$ref": "#/definitions/Languages" },
+                "stable": { "type": "object", "properties": { "vim_mode": { "type": "boolean" } } }
+            },
+            "definitions": {
+                "SoftWrap": {
+                    "description": "How to soft wrap lines.",
+                    "oneOf": [
+                        { "description": "Don't wrap.", "type": "string", "enum": ["none"] },
+                        { "description": "Wrap at the editor's width.", "type": "string", "enum": ["editor_width"] }
+                    ]
+                },
+                "Scrollbar": {
+                    "type": "object",
+                    "properties": {
+                        "show": { "type": "string", "enum": ["auto", "always", "never"] },
+                        "cursors": { "type": "boolean" }
+                    }
+                },
+                "Languages": {
+                    "type": "object",
+                    "properties": {
+                        "Rust": { "$ref": "#/definitions/LanguageSettingsContent" },
+                        "Go": { "$ref": "#/definitions/LanguageSettingsContent" }
+                    }
+                },
+                "LanguageSettingsContent": {
+                    "type": "object",
+                    "properties": {
+                        "tab_size": { "type": ["integer", "null"] },
+                        "soft_wrap": { "$ref": "#/definitions/SoftWrap" }
+                    }
+                }
+            }
+        });
+
+        let schema = SettingsSchema::new(&schema);
+        assert_eq!(schema.languages, ["Rust", "Go"]);
+        assert_eq!(
+            schema.entries,
+            [
+                SettingEntry {
+                    key_path: vec!["file_types".into()],
+                    description: None,
+                    kind: SettingKind::Json,
+                    language_overridable: false,
+                },
+                SettingEntry {
+                    key_path: vec!["soft_wrap".into()],
+                    description: Some("How to soft wrap lines.".into()),
+                    kind: SettingKind::Enum(vec!["none".into(), "editor_width".into()]),
+                    language_overridable: true,
+                },
+                SettingEntry {
+                    key_path: vec!["tab_size".into()],
+                    description: Some("How many columns a tab should occupy.".into()),
+                    kind: SettingKind::Integer {
+                        minimum: Some(1),
+                        maximum: None,
+                    },
+                    language_overridable: true,
+                },
+                SettingEntry {
+                    key_path: vec!["vim_mode".into()],
+                    description: Some("Whether to enable vim mode.".into()),
+                    kind: SettingKind::Bool,
+                    language_overridable: false,
+                },
+                SettingEntry {
+                    key_path: vec!["scrollbar".into(), "cursors".into()],
+                    description: None,
+                    kind: SettingKind::Bool,
+                    language_overridable: false,
+                },
+                SettingEntry {
+                    key_path: vec!["scrollbar".into(), "show".into()],
+                    description: None,
+                    kind: SettingKind::Enum(vec!["auto".into(), "always".into(), "never".into()]),
+                    language_overridable: false,
+                },
+            ]
+        );
+
+        assert_eq!(schema.entries[2].title(), "Tab Size");
+        assert_eq!(schema.entries[2].section(), "General");
+        assert_eq!(schema.entries[4].section(), "Scrollbar");
+        assert!(schema.entries[1].matches("WRAP"));
+        assert!(schema.entries[1].matches("soft wrap lines"));
+        assert!(!schema.entries[1].matches("tab"));
+    }
+}
@@ -1,23 +1,30 @@
-mod appearance_settings_controls;
+mod keybindings;
+mod settings_schema;
 
 use std::any::TypeId;
+use std::sync::Arc;
 
 use command_palette_hooks::CommandPaletteFilter;
-use editor::EditorSettingsControls;
+use editor::{Editor, EditorEvent};
 use feature_flags::{FeatureFlag, FeatureFlagViewExt};
 use fs::Fs;
 use gpui::{
-    App, AsyncWindowContext, Entity, EventEmitter, FocusHandle, Focusable, Task, actions,
-    impl_actions,
+    Action, AnyElement, App, AsyncWindowContext, ClickEvent, Entity, EventEmitter, FocusHandle,
+    Focusable, ScrollHandle, Subscription, Task, actions, impl_actions,
 };
 use schemars::JsonSchema;
 use serde::Deserialize;
-use settings::SettingsStore;
-use ui::prelude::*;
+use serde_json::Value;
+use settings::{
+    KeybindUpdateOperation, SettingsJsonSchemaParams, SettingsSource, SettingsStore,
+    UnmappedVsCodeKeybinding, WorktreeId,
+};
+use ui::{ContextMenu, NumericStepper, PopoverMenu, Switch, Tooltip, prelude::*};
 use workspace::Workspace;
 use workspace::item::{Item, ItemEvent};
 
-use crate::appearance_settings_controls::AppearanceSettingsControls;
+use crate::keybindings::{KeymapEditor, update_keymap_file};
+use crate::settings_schema::{SettingEntry, SettingKind, SettingsSchema};
 
 pub struct SettingsUiFeatureFlag;
 
@@ -32,7 +39,14 @@ pub struct ImportVsCodeSettings {
 }
 
 impl_actions!(zed, [ImportVsCodeSettings]);
-actions!(zed, [OpenSettingsEditor]);
+actions!(
+    zed,
+    [
+        OpenSettingsEditor,
+        OpenKeymapEditor,
+        ImportVsCodeKeybindings
+    ]
+);
 
 pub fn init(cx: &mut App) {
     cx.observe_new(|workspace: &mut Workspace, window, cx| {
@@ -50,11 +64,26 @@ pub fn init(cx: &mut App) {
             if let Some(existing) = existing {
                 workspace.activate_item(&existing, true, true, window, cx);
             } else {
-                let settings_page = SettingsPage::new(workspace, cx);
+                let settings_page = SettingsPage::new(workspace, window, cx);
                 workspace.add_item_to_active_pane(Box::new(settings_page), None, true, window, cx)
             }
         });
 
+        workspace.register_action(|workspace, _: &OpenKeymapEditor, window, cx| {
+            let existing = workspace
+                .active_pane()
+                .read(cx)
+                .items()
+                .find_map(|item| item.downcast::<KeymapEditor>());
+
+            if let Some(existing) = existing {
+                workspace.activate_item(&existing, true, true, window, cx);
+            } else {
+                let keymap_editor = KeymapEditor::new(workspace, window, cx);
+                workspace.add_item_to_active_pane(Box::new(keymap_editor), None, true, window, cx)
+            }
+        });
+
         workspace.register_action(|_workspace, action: &ImportVsCodeSettings, window, cx| {
             let fs = <dyn Fs>::global(cx);
             let action = *action;
@@ -108,7 +137,89 @@ pub fn init(cx: &mut App) {
                 .detach();
         });
 
-        let settings_ui_actions = [TypeId::of::<OpenSettingsEditor>()];
+        workspace.register_action(|workspace, _: &ImportVsCodeKeybindings, window, cx| {
+            let fs = workspace.app_state().fs.clone();
+            let workspace = workspace.weak_handle();
+
+            window
+                .spawn(cx, async move |cx: &mut AsyncWindowContext| {
+                    let import = match settings::VsCodeKeybindings::load_user_keybindings(
+                        fs.clone(),
+                    )
+                    .await
+                    {
+                        Ok(keybindings) => keybindings.import(),
+                        Err(err) => {
+                            log::error!(
+                                "Failed to load VsCode keybindings: {:?}",
+                                err.context(format!(
+                                    "Loading VsCode keybindings from path: {:?}",
+                                    paths::vscode_keybindings_file()
+                                ))
+                            );
+
+                            let _ = cx.prompt(
+                                gpui::PromptLevel::Info,
+                                "Could not find or load a VsCode keybindings file",
+                                None,
+                                &["Ok"],
+                            );
+                            return;
+                        }
+                    };
+
+                    for unmapped in &import.unmapped {
+                        log::info!("Skipped importing VsCode key binding {unmapped}");
+                    }
+                    let detail = unmapped_keybindings_detail(&import.unmapped);
+                    if import.bindings.is_empty() {
+                        let _ = cx.prompt(
+                            gpui::PromptLevel::Info,
+                            "None of your VsCode key bindings have equivalents",
+                            detail.as_deref(),
+                            &["Ok"],
+                        );
+                        return;
+                    }
+
+                    let message = format!(
+                        "Add {} key bindings from VsCode to your keymap?",
+                        import.bindings.len()
+                    );
+                    let prompt = cx.prompt(
+                        gpui::PromptLevel::Warning,
+                        &message,
+                        detail.as_deref(),
+                        &["Add", "Cancel"],
+                    );
+                    if prompt.await.ok() != Some(0) {
+                        return;
+                    }
+
+                    let operations = import
+                        .bindings
+                        .into_iter()
+                        .map(KeybindUpdateOperation::Add)
+                        .collect();
+                    match update_keymap_file(&fs, operations).await {
+                        // Open the keymap file to review the imported bindings.
+                        Ok(()) => cx
+                            .update(|window, cx| {
+                                window.dispatch_action(zed_actions::OpenKeymap.boxed_clone(), cx)
+                            })
+                            .ok(),
+                        Err(err) => workspace
+                            .update(cx, |workspace, cx| workspace.show_error(&err, cx))
+                            .ok(),
+                    };
+                })
+                .detach();
+        });
+
+        let settings_ui_actions = [
+            TypeId::of::<OpenSettingsEditor>(),
+            TypeId::of::<OpenKeymapEditor>(),
+        ];
 
         CommandPaletteFilter::update_global(cx, |filter, _cx| {
             filter.hide_action_types(&settings_ui_actions);
@@ -133,23 +244,502 @@ pub fn init(cx: &mut App) {
     .detach();
 }
 
+/// How many of the VsCode key bindings that couldn't be imported are listed when importing.
+const MAX_UNMAPPED_KEYBINDINGS_LISTED: usize = 10;
+
+fn unmapped_keybindings_detail(unmapped: &[UnmappedVsCodeKeybinding]) -> Option<String> {
+    if unmapped.is_empty() {
+        return None;
+    }
+    let mut detail = format!("{} key bindings have no equivalent:\n", unmapped.len());
+    for entry in unmapped.iter().take(MAX_UNMAPPED_KEYBINDINGS_LISTED) {
+        detail.push_str(&format!("\n{entry}"));
+    }
+    if unmapped.len() > MAX_UNMAPPED_KEYBINDINGS_LISTED {
+        detail.push_str(&format!(
+            "\n…and {} more, listed in the log",
+            unmapped.len() - MAX_UNMAPPED_KEYBINDINGS_LISTED
+        ));
+    }
+    Some(detail)
+}
+
+/// Values longer than this are cut short when shown in the settings editor.
+const MAX_VALUE_LABEL_LEN: usize = 60;
+
+/// A searchable editor for every setting in the settings JSON schema, which
+/// writes its edits to the user's settings file.
 pub struct SettingsPage {
-    focus_handle: FocusHandle,
+    search_editor: Entity<Editor>,
+    schema: SettingsSchema,
+    /// The language whose overrides are being edited, if any.
+    language: Option<String>,
+    worktree_id: Option<WorktreeId>,
+    value_editor: Option<ValueEditor>,
+    fs: Arc<dyn Fs>,
+    scroll_handle: ScrollHandle,
+    _subscriptions: Vec<Subscription>,
+}
+
+/// Edits a setting whose value is typed in, rather than picked.
+struct ValueEditor {
+    entry_ix: usize,
+    editor: Entity<Editor>,
+    error: Option<SharedString>,
 }
 
 impl SettingsPage {
-    pub fn new(_workspace: &Workspace, cx: &mut Context<Workspace>) -> Entity<Self> {
-        cx.new(|cx| Self {
-            focus_handle: cx.focus_handle(),
+    pub fn new(
+        workspace: &Workspace,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) -> Entity<Self> {
+        let language_names = workspace.app_state().languages.language_names();
+        let font_names = cx.text_system().all_font_names();
+        let schema = cx.global::<SettingsStore>().json_schema(
+            &SettingsJsonSchemaParams {
+                language_names: &language_names,
+                font_names: &font_names,
+            },
+            cx,
+        );
+        let worktree_id = workspace
+            .project()
+            .read(cx)
+            .visible_worktrees(cx)
+            .next()
+            .map(|worktree| worktree.read(cx).id());
+        let fs = workspace.app_state().fs.clone();
+
+        cx.new(|cx| {
+            let search_editor = cx.new(|cx| {
+                let mut editor = Editor::single_line(window, cx);
+                editor.set_placeholder_text("Search settings...", cx);
+                editor
+            });
+            let subscriptions = vec![
+                cx.subscribe(&search_editor, |_, _, event: &EditorEvent, cx| {
+                    if let EditorEvent::Edited { .. } = event {
+                        cx.notify();
+                    }
+                }),
+                cx.observe_global::<SettingsStore>(|_, cx| cx.notify()),
+            ];
+
+            Self {
+                search_editor,
+                schema: SettingsSchema::new(&schema),
+                language: None,
+                worktree_id,
+                value_editor: None,
+                fs,
+                scroll_handle: ScrollHandle::new(),
+                _subscriptions: subscriptions,
+            }
         })
     }
+
+    /// The path of keys that an entry is read from and written to, which is
+    /// within the selected language's overrides if it can be overridden.
+    fn key_path(&self, entry: &SettingEntry) -> Vec<String> {
+        match &self.language {
+            Some(language) if entry.language_overridable => ["languages", language.as_str()]
+                .into_iter()
+                .map(str::to_string)
+                .chain(entry.key_path.iter().cloned())
+                .collect(),
+            _ => entry.key_path.clone(),
+        }
+    }
+
+    fn start_editing(
+        &mut self,
+        entry_ix: usize,
+        text: String,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let editor = cx.new(|cx| {
+            let mut editor = Editor::single_line(window, cx);
+            editor.set_text(text, window, cx);
+            editor.select_all(&editor::actions::SelectAll, window, cx);
+            editor
+        });
+        window.focus(&editor.focus_handle(cx));
+        self.value_editor = Some(ValueEditor {
+            entry_ix,
+            editor,
+            error: None,
+        });
+        cx.notify();
+    }
+
+    fn confirm_edit(&mut self, _: &menu::Confirm, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(value_editor) = self.value_editor.as_mut() else {
+            return;
+        };
+        let Some(entry) = self.schema.entries.get(value_editor.entry_ix) else {
+            return;
+        };
+        let text = value_editor.editor.read(cx).text(cx);
+        match parse_value(&entry.kind, &text) {
+            Ok(value) => {
+                write_setting(self.fs.clone(), self.key_path(entry), value, cx);
+                self.cancel_edit(&editor::actions::Cancel, window, cx);
+            }
+            Err(error) => {
+                value_editor.error = Some(error.to_string().into());
+                cx.notify();
+            }
+        }
+    }
+
+    fn cancel_edit(
+        &mut self,
+        _: &editor::actions::Cancel,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.value_editor.take().is_some() {
+            window.focus(&self.search_editor.focus_handle(cx));
+            cx.notify();
+        }
+    }
+
+    fn render_language_picker(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let settings_page = cx.entity().downgrade();
+        let languages = self.schema.languages.clone();
+        let selected = self.language.clone();
+        let label = self
+            .language
+            .clone()
+            .unwrap_or_else(|| "All Languages".to_string());
+
+        PopoverMenu::new("settings-language")
+            .trigger(
+                Button::new("settings-language-trigger", label)
+                    .icon(IconName::ChevronUpDown)
+                    .icon_position(IconPosition::End)
+                    .icon_size(IconSize::XSmall)
+                    .icon_color(Color::Muted),
+            )
+            .menu(move |window, cx| {
+                let settings_page = settings_page.clone();
+                let languages = languages.clone();
+                let selected = selected.clone();
+                Some(ContextMenu::build(window, cx, move |mut menu, _, _| {
+                    for language in [None].into_iter().chain(languages.into_iter().map(Some)) {
+                        let label = language
+                            .clone()
+                            .unwrap_or_else(|| "All Languages".to_string());
+                        let settings_page = settings_page.clone();
+                        menu = menu.toggleable_entry(
+                            label,
+                            language == selected,
+                            IconPosition::Start,
+                            None,
+                            move |_, cx| {
+                                settings_page
+                                    .update(cx, |settings_page, cx| {
+                                        settings_page.language = language.clone();
+                                        settings_page.value_editor = None;
+                                        cx.notify();
+                                    })
+                                    .ok();
+                            },
+                        );
+                    }
+                    menu
+                }))
+            })
+    }
+
+    fn render_entry(
+        &self,
+        entry_ix: usize,
+        entry: &SettingEntry,
+        cx: &mut Context<Self>,
+    ) -> AnyElement {
+        let key_path = self.key_path(entry);
+        let language = self
+            .language
+            .as_deref()
+            .filter(|_| entry.language_overridable);
+        let profile = cx
+            .global::<SettingsStore>()
+            .active_profile()
+            .unwrap_or_default()
+            .to_string();
+        let (value, source) = {
+            let raw_key_path = entry
+                .key_path
+                .iter()
+                .map(String::as_str)
+                .collect::<Vec<_>>();
+            cx.global::<SettingsStore>()
+                .raw_value_with_source(&raw_key_path, language, self.worktree_id)
+                .map(|(value, source)| (value.clone(), Some(source)))
+                .unwrap_or((Value::Null, None))
+        };
+
+        // Only settings from the user's settings file can be reset from here,
+        // as the editor doesn't write to project settings.
+        let reset_key_path = match source {
+            Some(SettingsSource::User) => Some(entry.key_path.clone()),
+            Some(SettingsSource::UserLanguage) => Some(key_path.clone()),
+            _ => None,
+        };
+        let overridden_message = match source {
+            Some(SettingsSource::Project | SettingsSource::ProjectLanguage) => {
+                Some("Overridden by this project's settings".to_string())
+            }
+            Some(SettingsSource::Profile | SettingsSource::ProfileLanguage) => {
+                Some(format!("Overridden by the {profile} profile"))
+            }
+            _ => None,
+        };
+
+        h_flex()
+            .id(("setting", entry_ix))
+            .w_full()
+            .py_2()
+            .gap_4()
+            .justify_between()
+            .border_b_1()
+            .border_color(cx.theme().colors().border_variant)
+            .child(
+                v_flex()
+                    .flex_1()
+                    .min_w_0()
+                    .gap_0p5()
+                    .child(
+                        h_flex().gap_2().child(Label::new(entry.title())).child(
+                            Label::new(source_label(source, language, &profile))
+                                .size(LabelSize::XSmall)
+                                .color(Color::Muted),
+                        ),
+                    )
+                    .child(
+                        Label::new(entry.key_path.join("."))
+                            .size(LabelSize::Small)
+                            .color(Color::Muted)
+                            .buffer_font(cx),
+                    )
+                    .children(entry.description.clone().map(|description| {
+                        Label::new(description)
+                            .size(LabelSize::Small)
+                            .color(Color::Muted)
+                    }))
+                    .children(overridden_message.map(|message| {
+                        Label::new(message)
+                            .size(LabelSize::Small)
+                            .color(Color::Warning)
+                    })),
+            )
+            .child(
+                h_flex()
+                    .gap_1()
+                    .flex_none()
+                    .children(reset_key_path.map(|reset_key_path| {
+                        let fs = self.fs.clone();
+                        IconButton::new(("reset-setting", entry_ix), IconName::RotateCcw)
+                            .icon_size(IconSize::Small)
+                            .tooltip(Tooltip::text("Reset to Default"))
+                            .on_click(move |_, _, cx| {
+                                write_setting(fs.clone(), reset_key_path.clone(), Value::Null, cx);
+                            })
+                    }))
+                    .child(self.render_control(entry_ix, entry, key_path, value, cx)),
+            )
+            .into_any_element()
+    }
+
+    fn render_control(
+        &self,
+        entry_ix: usize,
+        entry: &SettingEntry,
+        key_path: Vec<String>,
+        value: Value,
+        cx: &mut Context<Self>,
+    ) -> AnyElement {
+        let fs = self.fs.clone();
+        match &entry.kind {
+            SettingKind::Bool => Switch::new(
+                ("setting-control", entry_ix),
+                value.as_bool().unwrap_or(false).into(),
+            )
+            .on_click(move |state, _, cx| {
+                let enabled = *state == ToggleState::Selected;
+                write_setting(fs.clone(), key_path.clone(), Value::Bool(enabled), cx);
+            })
+            .into_any_element(),
+            SettingKind::Enum(variants) => {
+                let variants = variants.clone();
+                let selected = value.as_str().map(str::to_string);
+                PopoverMenu::new(("setting-control", entry_ix))
+                    .trigger(
+                        Button::new(
+                            ("setting-control-trigger", entry_ix),
+                            selected.clone().unwrap_or_else(|| "Not set".to_string()),
+                        )
+                        .icon(IconName::ChevronUpDown)
+                        .icon_position(IconPosition::End)
+                        .icon_size(IconSize::XSmall)
+                        .icon_color(Color::Muted),
+                    )
+                    .menu(move |window, cx| {
+                        let fs = fs.clone();
+                        let key_path = key_path.clone();
+                        let variants = variants.clone();
+                        let selected = selected.clone();
+                        Some(ContextMenu::build(window, cx, move |mut menu, _, _| {
+                            for variant in variants {
+                                let fs = fs.clone();
+                                let key_path = key_path.clone();
+                                menu = menu.toggleable_entry(
+                                    variant.clone(),
+                                    selected.as_ref() == Some(&variant),
+                                    IconPosition::Start,
+                                    None,
+                                    move |_, cx| {
+                                        write_setting(
+                                            fs.clone(),
+                                            key_path.clone(),
+                                            Value::String(variant.clone()),
+                                            cx,
+                                        );
+                                    },
+                                );
+                            }
+                            menu
+                        }))
+                    })
+                    .into_any_element()
+            }
+            SettingKind::Integer { minimum, maximum } if value.is_i64() || value.is_null() => {
+                let current = value.as_i64().or(*minimum).unwrap_or(0);
+                let (minimum, maximum) = (minimum.unwrap_or(i64::MIN), maximum.unwrap_or(i64::MAX));
+                let decrement = {
+                    let fs = fs.clone();
+                    let key_path = key_path.clone();
+                    move |_: &ClickEvent, _: &mut Window, cx: &mut App| {
+                        let value = current.saturating_sub(1).clamp(minimum, maximum);
+                        write_setting(fs.clone(), key_path.clone(), value.into(), cx);
+                    }
+                };
+                let increment = move |_: &ClickEvent, _: &mut Window, cx: &mut App| {
+                    let value = current.saturating_add(1).clamp(minimum, maximum);
+                    write_setting(fs.clone(), key_path.clone(), value.into(), cx);
+                };
+                NumericStepper::new(
+                    ("setting-control", entry_ix),
+                    current.to_string(),
+                    decrement,
+                    increment,
+                )
+                .into_any_element()
+            }
+            _ => match &self.value_editor {
+                Some(value_editor) if value_editor.entry_ix == entry_ix => {
+                    v_flex()
+                        .w_64()
+                        .gap_1()
+                        .on_action(cx.listener(Self::confirm_edit))
+                        .on_action(cx.listener(Self::cancel_edit))
+                        .child(
+                            div()
+                                .px_2()
+                                .py_1()
+                                .border_1()
+                                .rounded_md()
+                                .border_color(cx.theme().colors().border_focused)
+                                .child(value_editor.editor.clone()),
+                        )
+                        .children(value_editor.error.clone().map(|error| {
+                            Label::new(error).size(LabelSize::Small).color(Color::Error)
+                        }))
+                        .into_any_element()
+                }
+                _ => {
+                    let text = value_text(&value);
+                    let label = if value.is_null() {
+                        "Not set".to_string()
+                    } else if text.chars().count() > MAX_VALUE_LABEL_LEN {
+                        let mut label = text.chars().take(MAX_VALUE_LABEL_LEN).collect::<String>();
+                        label.push('…');
+                        label
+                    } else {
+                        text.clone()
+                    };
+                    Button::new(("setting-control", entry_ix), label)
+                        .style(ButtonStyle::Filled)
+                        .tooltip(Tooltip::text("Edit"))
+                        .on_click(cx.listener(move |this, _, window, cx| {
+                            this.start_editing(entry_ix, text.clone(), window, cx);
+                        }))
+                        .into_any_element()
+                }
+            },
+        }
+    }
+}
+
+/// Writes a setting's value to the user's settings file, or unsets the setting
+/// if the value is null.
+fn write_setting(fs: Arc<dyn Fs>, key_path: Vec<String>, value: Value, cx: &App) {
+    cx.global::<SettingsStore>()
+        .update_settings_file_at_path(fs, key_path, value);
+}
+
+/// Parses the text a setting's value was edited to. Clearing the text unsets
+/// the setting.
+fn parse_value(kind: &SettingKind, text: &str) -> anyhow::Result<Value> {
+    if text.trim().is_empty() {
+        return Ok(Value::Null);
+    }
+    Ok(match kind {
+        SettingKind::String => Value::String(text.to_string()),
+        SettingKind::Number => {
+            let number = text.trim().parse::<f64>()?;
+            serde_json::Number::from_f64(number)
+                .map(Value::Number)
+                .ok_or_else(|| anyhow::anyhow!("{number} is not a valid number"))?
+        }
+        _ => settings::parse_json_with_comments(text)?,
+    })
+}
+
+fn value_text(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(text) => text.clone(),
+        value => value.to_string(),
+    }
+}
+
+fn source_label(
+    source: Option<SettingsSource>,
+    language: Option<&str>,
+    profile: &str,
+) -> SharedString {
+    let language = language.unwrap_or_default();
+    match source {
+        None => "Not set".into(),
+        Some(SettingsSource::Default) => "Default".into(),
+        Some(SettingsSource::User) => "User".into(),
+        Some(SettingsSource::Project) => "Project".into(),
+        Some(SettingsSource::UserLanguage) => format!("User ({language})").into(),
+        Some(SettingsSource::ProjectLanguage) => format!("Project ({language})").into(),
+        Some(SettingsSource::Profile) => format!("Profile: {profile}").into(),
+        Some(SettingsSource::ProfileLanguage) => format!("Profile: {profile} ({language})").into(),
+    }
 }
 
 impl EventEmitter<ItemEvent> for SettingsPage {}
 
 impl Focusable for SettingsPage {
-    fn focus_handle(&self, _cx: &App) -> FocusHandle {
-        self.focus_handle.clone()
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.search_editor.focus_handle(cx)
     }
 }
 
@@ -175,24 +765,65 @@ impl Item for SettingsPage {
 
 impl Render for SettingsPage {
     fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let query = self.search_editor.read(cx).text(cx);
+        let query = query.trim();
+        let language_selected = self.language.is_some();
+
+        let mut rows = Vec::new();
+        let mut current_section = None;
+        for (entry_ix, entry) in self.schema.entries.iter().enumerate() {
+            if (language_selected && !entry.language_overridable)
+                || (!query.is_empty() && !entry.matches(query))
+            {
+                continue;
+            }
+            let section = entry.section();
+            if current_section.as_ref() != Some(&section) {
+                rows.push(
+                    Label::new(section.clone())
+                        .size(LabelSize::Large)
+                        .into_any_element(),
+                );
+                current_section = Some(section);
+            }
+            rows.push(self.render_entry(entry_ix, entry, cx));
+        }
+
         v_flex()
             .p_4()
             .size_full()
             .gap_4()
-            .child(Label::new("Settings").size(LabelSize::Large))
             .child(
-                v_flex().gap_1().child(Label::new("Appearance")).child(
-                    v_flex()
-                        .elevation_2(cx)
-                        .child(AppearanceSettingsControls::new()),
-                ),
+                h_flex()
+                    .gap_2()
+                    .child(
+                        h_flex()
+                            .flex_1()
+                            .h_8()
+                            .px_2()
+                            .gap_2()
+                            .border_1()
+                            .rounded_lg()
+                            .border_color(cx.theme().colors().border)
+                            .child(Icon::new(IconName::MagnifyingGlass).color(Color::Muted))
+                            .child(self.search_editor.clone()),
+                    )
+                    .child(self.render_language_picker(cx)),
             )
             .child(
-                v_flex().gap_1().child(Label::new("Editor")).child(
-                    v_flex()
-                        .elevation_2(cx)
-                        .child(EditorSettingsControls::new()),
-                ),
+                v_flex()
+                    .id("settings")
+                    .size_full()
+                    .gap_2()
+                    .overflow_y_scroll()
+                    .track_scroll(&self.scroll_handle)
+                    .map(|this| {
+                        if rows.is_empty() {
+                            this.child(Label::new("No matching settings").color(Color::Muted))
+                        } else {
+                            this.children(rows)
+                        }
+                    }),
             )
     }
 }
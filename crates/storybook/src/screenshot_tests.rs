@@ -0,0 +1,38 @@
+//! Pixel snapshot tests of stories, rendered on the CPU and compared against the reference
+//! images in `screenshots/`. Run with `UPDATE_SNAPSHOTS=1` to update them after an intended
+//! change in appearance.
+
+use crate::story_selector::ComponentStory;
+use gpui::{TestAppContext, assert_screenshot_matches, px, size};
+use settings::SettingsStore;
+use std::path::PathBuf;
+
+/// Stories that only need a theme to render. Others load images over the network, or need
+/// editors and projects.
+const STORIES: &[ComponentStory] = &[
+    ComponentStory::ListHeader,
+    ComponentStory::Tab,
+    ComponentStory::TabBar,
+    ComponentStory::ToggleButton,
+];
+
+#[gpui::test]
+fn test_story_screenshots(cx: &mut TestAppContext) {
+    cx.update(|cx| {
+        let settings_store = SettingsStore::test(cx);
+        cx.set_global(settings_store);
+        theme::init(theme::LoadThemes::JustBase, cx);
+    });
+
+    for story in STORIES {
+        let (_, cx) =
+            cx.add_window_view(|window, cx| crate::StoryWrapper::new(story.story(window, cx)));
+        cx.simulate_resize(size(px(800.), px(600.)));
+        let image = cx.capture_screenshot();
+
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("screenshots")
+            .join(format!("{story}.png"));
+        assert_screenshot_matches(&image, path);
+    }
+}
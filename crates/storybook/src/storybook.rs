@@ -1,6 +1,8 @@
 mod actions;
 mod app_menus;
 mod assets;
+#[cfg(test)]
+mod screenshot_tests;
 mod stories;
 mod story_selector;
 
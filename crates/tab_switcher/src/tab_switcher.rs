@@ -7,12 +7,10 @@ use fuzzy::StringMatchCandidate;
 use gpui::{
     Action, AnyElement, App, Context, DismissEvent, Entity, EntityId, EventEmitter, FocusHandle,
     Focusable, Modifiers, ModifiersChangedEvent, MouseButton, MouseUpEvent, ParentElement, Render,
-    Styled, Task, WeakEntity, Window, actions, impl_actions, rems,
+    Styled, Task, WeakEntity, Window, actions, rems,
 };
 use picker::{Picker, PickerDelegate};
 use project::Project;
-use schemars::JsonSchema;
-use serde::Deserialize;
 use settings::Settings;
 use std::{cmp::Reverse, sync::Arc};
 use ui::{ListItem, ListItemSpacing, Tooltip, prelude::*};
@@ -25,14 +23,8 @@ use workspace::{
 
 const PANEL_WIDTH_REMS: f32 = 28.;
 
-#[derive(PartialEq, Clone, Deserialize, JsonSchema, Default)]
-#[serde(deny_unknown_fields)]
-pub struct Toggle {
-    #[serde(default)]
-    pub select_last: bool,
-}
+pub use workspace::ToggleTabSwitcher as Toggle;
 
-impl_actions!(tab_switcher, [Toggle]);
 actions!(tab_switcher, [CloseSelectedItem, ToggleAll]);
 
 pub struct TabSwitcher {
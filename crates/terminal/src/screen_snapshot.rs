@@ -0,0 +1,337 @@
+//! Snapshots of the visible screen of a terminal, used to share terminals with collaborators.
+//!
+//! A snapshot encodes the screen as escape sequences: the characters of each line along
+//! with their colors and attributes, and the final cursor position. The first snapshot a
+//! collaborator receives draws the whole screen, and later ones only redraw the lines that
+//! changed since the previous snapshot. The scrollback isn't shared.
+
+use std::fmt::Write as _;
+
+use alacritty_terminal::{
+    Term,
+    event::EventListener,
+    grid::Dimensions,
+    index::{Column, Line},
+    term::{
+        TermMode,
+        cell::{Cell, Flags},
+    },
+    vte::ansi::Color,
+};
+
+/// The visible screen of a terminal, or the lines of it that changed, encoded as ANSI
+/// escape sequences.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ScreenSnapshot {
+    pub columns: usize,
+    pub lines: usize,
+    pub content: String,
+}
+
+/// The size of the grid a snapshot is replayed into.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct ScreenSize {
+    pub columns: usize,
+    pub lines: usize,
+}
+
+impl Dimensions for ScreenSize {
+    fn total_lines(&self) -> usize {
+        self.lines
+    }
+
+    fn screen_lines(&self) -> usize {
+        self.lines
+    }
+
+    fn columns(&self) -> usize {
+        self.columns
+    }
+}
+
+impl ScreenSnapshot {
+    pub(crate) fn size(&self) -> ScreenSize {
+        ScreenSize {
+            columns: self.columns.max(1),
+            lines: self.lines.max(1),
+        }
+    }
+}
+
+/// The visible screen of a terminal, encoded line by line so that it can be compared with
+/// the screen that was last sent to collaborators.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct EncodedScreen {
+    columns: usize,
+    lines: usize,
+    /// The characters and attributes of each line, which are empty for blank lines.
+    rows: Vec<String>,
+    /// Moves the cursor to its position, and shows or hides it.
+    cursor: String,
+}
+
+impl EncodedScreen {
+    /// A snapshot that draws the whole screen from scratch.
+    pub fn snapshot(&self) -> ScreenSnapshot {
+        // Reset the attributes, then clear the screen and scrollback.
+        let mut content = String::from("\x1b[0m\x1b[H\x1b[2J\x1b[3J");
+        for (line, row) in self.rows.iter().enumerate() {
+            if !row.is_empty() {
+                write!(content, "\x1b[{};1H{row}", line + 1).ok();
+            }
+        }
+        content.push_str(&self.cursor);
+
+        ScreenSnapshot {
+            columns: self.columns,
+            lines: self.lines,
+            content,
+        }
+    }
+
+    /// A snapshot that turns `previous` into this screen by redrawing the lines that
+    /// differ, or `None` if the screens are the same. The whole screen is drawn if its
+    /// size changed.
+    pub fn diff(&self, previous: &EncodedScreen) -> Option<ScreenSnapshot> {
+        if self == previous {
+            return None;
+        }
+        if (self.columns, self.lines) != (previous.columns, previous.lines) {
+            return Some(self.snapshot());
+        }
+
+        let mut content = String::new();
+        for (line, (row, previous_row)) in self.rows.iter().zip(&previous.rows).enumerate() {
+            if row != previous_row {
+                write!(content, "\x1b[{};1H\x1b[0m\x1b[2K{row}", line + 1).ok();
+            }
+        }
+        content.push_str(&self.cursor);
+
+        Some(ScreenSnapshot {
+            columns: self.columns,
+            lines: self.lines,
+            content,
+        })
+    }
+}
+
+pub(crate) fn encode<T: EventListener>(term: &Term<T>) -> EncodedScreen {
+    let grid = term.grid();
+    let columns = grid.columns();
+    let lines = grid.screen_lines();
+    let default_cell = Cell::default();
+
+    let rows = (0..lines)
+        .map(|line| {
+            let row = &grid[Line(line as i32)];
+            let mut content = String::new();
+            let Some(last_column) = (0..columns)
+                .rev()
+                .find(|&column| row[Column(column)] != default_cell)
+            else {
+                return content;
+            };
+
+            let mut attributes = None;
+            for column in 0..=last_column {
+                let cell = &row[Column(column)];
+                if cell
+                    .flags
+                    .intersects(Flags::WIDE_CHAR_SPACER | Flags::LEADING_WIDE_CHAR_SPACER)
+                {
+                    continue;
+                }
+
+                let cell_attributes = (cell.fg, cell.bg, cell.flags & STYLE_FLAGS);
+                if attributes != Some(cell_attributes) {
+                    write_sgr(&mut content, &cell.fg, &cell.bg, cell.flags);
+                    attributes = Some(cell_attributes);
+                }
+                content.push(cell.c);
+                if let Some(zerowidth) = cell.zerowidth() {
+                    content.extend(zerowidth);
+                }
+            }
+            content.push_str("\x1b[0m");
+            content
+        })
+        .collect();
+
+    let point = grid.cursor.point;
+    let mut cursor = format!("\x1b[{};{}H", point.line.0.max(0) + 1, point.column.0 + 1);
+    if term.mode().contains(TermMode::SHOW_CURSOR) {
+        cursor.push_str("\x1b[?25h");
+    } else {
+        cursor.push_str("\x1b[?25l");
+    }
+
+    EncodedScreen {
+        columns,
+        lines,
+        rows,
+        cursor,
+    }
+}
+
+/// The cell flags that are drawn as SGR attributes.
+const STYLE_FLAGS: Flags = Flags::BOLD
+    .union(Flags::DIM)
+    .union(Flags::ITALIC)
+    .union(Flags::ALL_UNDERLINES)
+    .union(Flags::INVERSE)
+    .union(Flags::HIDDEN)
+    .union(Flags::STRIKEOUT);
+
+fn write_sgr(content: &mut String, fg: &Color, bg: &Color, flags: Flags) {
+    content.push_str("\x1b[0");
+    for (flag, parameter) in [
+        (Flags::BOLD, "1"),
+        (Flags::DIM, "2"),
+        (Flags::ITALIC, "3"),
+        (Flags::UNDERLINE, "4"),
+        (Flags::DOUBLE_UNDERLINE, "21"),
+        (Flags::UNDERCURL, "4:3"),
+        (Flags::DOTTED_UNDERLINE, "4:4"),
+        (Flags::DASHED_UNDERLINE, "4:5"),
+        (Flags::INVERSE, "7"),
+        (Flags::HIDDEN, "8"),
+        (Flags::STRIKEOUT, "9"),
+    ] {
+        if flags.contains(flag) {
+            content.push(';');
+            content.push_str(parameter);
+        }
+    }
+    write_color(content, fg, 30, 90, 38);
+    write_color(content, bg, 40, 100, 48);
+    content.push('m');
+}
+
+/// Appends the SGR parameters selecting `color`. Named colors other than the 16 ANSI
+/// colors are left at the default, which [`write_sgr`] already reset to.
+fn write_color(content: &mut String, color: &Color, normal: u8, bright: u8, extended: u8) {
+    match color {
+        Color::Named(name) => {
+            let index = *name as usize;
+            if index < 8 {
+                write!(content, ";{}", normal as usize + index).ok();
+            } else if index < 16 {
+                write!(content, ";{}", bright as usize + index - 8).ok();
+            }
+        }
+        Color::Indexed(index) => {
+            write!(content, ";{extended};5;{index}").ok();
+        }
+        Color::Spec(rgb) => {
+            write!(content, ";{extended};2;{};{};{}", rgb.r, rgb.g, rgb.b).ok();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alacritty_terminal::{event::VoidListener, term::Config, vte::ansi::Processor};
+
+    fn term_with_output(size: ScreenSize, output: &str) -> Term<VoidListener> {
+        let mut term = Term::new(Config::default(), &size, VoidListener);
+        let mut parser: Processor = Processor::new();
+        parser.advance(&mut term, output.as_bytes());
+        term
+    }
+
+    fn assert_same_screen(left: &Term<VoidListener>, right: &Term<VoidListener>) {
+        let lines = left.grid().screen_lines();
+        let columns = Column(left.grid().columns());
+        assert_eq!(lines, right.grid().screen_lines());
+        for line in 0..lines as i32 {
+            assert_eq!(
+                left.grid()[Line(line)][..columns],
+                right.grid()[Line(line)][..columns],
+                "line {line} differs"
+            );
+        }
+        assert_eq!(left.grid().cursor.point, right.grid().cursor.point);
+    }
+
+    #[test]
+    fn test_snapshot_round_trip() {
+        let size = ScreenSize {
+            columns: 20,
+            lines: 5,
+        };
+        let term = term_with_output(
+            size,
+            "plain\r\n\x1b[1;31mbold red\x1b[0m \x1b[4;48;5;120mindexed\x1b[0m\r\n\
+             \x1b[38;2;10;20;30;3mrgb italic\x1b[7m inverse\x1b[0m\r\n\
+             wide: 漢字 e\u{301}\r\n$ ls",
+        );
+
+        let snapshot = encode(&term).snapshot();
+        assert_eq!((snapshot.columns, snapshot.lines), (20, 5));
+
+        let replayed = term_with_output(snapshot.size(), &snapshot.content);
+        assert_same_screen(&term, &replayed);
+    }
+
+    #[test]
+    fn test_snapshot_replaces_previous_screen() {
+        let size = ScreenSize {
+            columns: 10,
+            lines: 3,
+        };
+        let term = term_with_output(size, "\x1b[2;4Hx");
+        let snapshot = encode(&term).snapshot();
+
+        let mut replayed = term_with_output(size, "some\r\nold\r\noutput");
+        let mut parser: Processor = Processor::new();
+        parser.advance(&mut replayed, snapshot.content.as_bytes());
+        assert_same_screen(&term, &replayed);
+    }
+
+    #[test]
+    fn test_diff_redraws_changed_lines() {
+        let size = ScreenSize {
+            columns: 10,
+            lines: 4,
+        };
+        let mut term = term_with_output(size, "one\r\n\x1b[32mtwo\x1b[0m\r\nthree");
+        let previous = encode(&term);
+        let mut replayed = term_with_output(size, &previous.snapshot().content);
+        assert_eq!(previous.diff(&previous), None);
+
+        let mut parser: Processor = Processor::new();
+        parser.advance(&mut term, b"\x1b[2;1H\x1b[2Kdeux\x1b[4;1H$ ");
+        let current = encode(&term);
+        let diff = current.diff(&previous).unwrap();
+        assert_eq!(
+            diff.content,
+            "\x1b[2;1H\x1b[0m\x1b[2K\x1b[0mdeux\x1b[0m\
+             \x1b[4;1H\x1b[0m\x1b[2K\x1b[0m$\x1b[0m\
+             \x1b[4;3H\x1b[?25h"
+        );
+
+        parser.advance(&mut replayed, diff.content.as_bytes());
+        assert_same_screen(&term, &replayed);
+    }
+
+    #[test]
+    fn test_diff_redraws_resized_screen() {
+        let mut term = term_with_output(
+            ScreenSize {
+                columns: 10,
+                lines: 3,
+            },
+            "one\r\ntwo",
+        );
+        let previous = encode(&term);
+        let size = ScreenSize {
+            columns: 8,
+            lines: 2,
+        };
+        term.resize(size);
+        let current = encode(&term);
+        assert_eq!(current.diff(&previous), Some(current.snapshot()));
+    }
+}
@@ -0,0 +1,1167 @@
+//! Shell integration through the OSC 133 (FinalTerm) and OSC 633 (VS Code) escape sequences.
+//!
+//! Shells configured for shell integration mark where prompts, typed commands and command
+//! output start, and report exit codes once commands finish. Alacritty ignores these
+//! sequences, so [`ShellIntegrationPty`] rewrites them before they reach its parser: every
+//! mark is turned into an OSC 8 hyperlink that tags the cells printed afterwards with the
+//! command block and region they belong to. Alacritty then keeps the tags attached to the
+//! right cells through scrolling, reflow and scrollback truncation, and the terminal can
+//! find block boundaries by looking at the grid.
+
+use std::{
+    cmp,
+    io::{self, Read},
+    ops::RangeInclusive,
+    sync::{Arc, Mutex},
+};
+
+use alacritty_terminal::{
+    Term,
+    event::{OnResize, WindowSize},
+    grid::Dimensions,
+    index::{Column, Line, Point as AlacPoint},
+    term::cell::Hyperlink,
+    tty::{ChildEvent, EventedPty, EventedReadWrite},
+};
+use collections::VecDeque;
+use polling::{Event as PollEvent, PollMode, Poller};
+
+/// The URI scheme of the hyperlinks used to tag command blocks in the grid.
+const BLOCK_LINK_SCHEME: &str = "zed-block";
+const ESC: u8 = 0x1b;
+const BEL: u8 = 0x07;
+/// Sequences longer than this are not shell integration marks and are passed through as-is.
+const MAX_SEQUENCE_LEN: usize = 4096;
+/// How many command blocks to remember. Older blocks have usually scrolled out of the
+/// scrollback by the time this many commands ran.
+const MAX_COMMAND_BLOCKS: usize = 1024;
+
+/// A part of a command block.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BlockRegion {
+    /// The prompt printed by the shell.
+    Prompt,
+    /// The command line typed by the user.
+    Input,
+    /// The output of the command.
+    Output,
+}
+
+impl BlockRegion {
+    fn tag(self) -> char {
+        match self {
+            BlockRegion::Prompt => 'p',
+            BlockRegion::Input => 'i',
+            BlockRegion::Output => 'o',
+        }
+    }
+
+    fn from_tag(tag: char) -> Option<Self> {
+        match tag {
+            'p' => Some(BlockRegion::Prompt),
+            'i' => Some(BlockRegion::Input),
+            'o' => Some(BlockRegion::Output),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ShellIntegrationEvent {
+    PromptStarted {
+        block_id: usize,
+    },
+    CommandStarted {
+        block_id: usize,
+    },
+    CommandExecuted {
+        block_id: usize,
+    },
+    CommandFinished {
+        block_id: usize,
+        exit_code: Option<i32>,
+    },
+    /// The command line as reported by the shell with OSC 633 `E`.
+    CommandLine {
+        block_id: usize,
+        command_line: String,
+    },
+}
+
+/// A prompt, the command typed after it, and the command's output.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CommandBlock {
+    pub id: usize,
+    /// The command line reported by the shell, if it reports one.
+    pub command_line: Option<String>,
+    pub executed: bool,
+    pub finished: bool,
+    pub exit_code: Option<i32>,
+}
+
+impl CommandBlock {
+    fn new(id: usize) -> Self {
+        Self {
+            id,
+            command_line: None,
+            executed: false,
+            finished: false,
+            exit_code: None,
+        }
+    }
+
+    pub fn failed(&self) -> bool {
+        self.exit_code.is_some_and(|exit_code| exit_code != 0)
+    }
+}
+
+/// The output of a finished command, read back from the terminal grid.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CommandOutput {
+    pub command: String,
+    pub output: String,
+    pub exit_code: Option<i32>,
+}
+
+/// Tracks the command blocks reported by the shell running in a terminal.
+#[derive(Default)]
+pub struct ShellIntegrationState {
+    events: Arc<Mutex<Vec<ShellIntegrationEvent>>>,
+    blocks: VecDeque<CommandBlock>,
+}
+
+impl ShellIntegrationState {
+    /// Applies the events received from the PTY since the last call, returning whether
+    /// anything changed.
+    pub fn apply_pending_events(&mut self) -> bool {
+        let events = std::mem::take(&mut *self.events.lock().unwrap());
+        let changed = !events.is_empty();
+        for event in events {
+            self.apply_event(event);
+        }
+        changed
+    }
+
+    fn apply_event(&mut self, event: ShellIntegrationEvent) {
+        match event {
+            ShellIntegrationEvent::PromptStarted { block_id }
+            | ShellIntegrationEvent::CommandStarted { block_id } => {
+                self.block_mut(block_id);
+            }
+            ShellIntegrationEvent::CommandExecuted { block_id } => {
+                self.block_mut(block_id).executed = true;
+            }
+            ShellIntegrationEvent::CommandFinished {
+                block_id,
+                exit_code,
+            } => {
+                let block = self.block_mut(block_id);
+                block.finished = true;
+                block.exit_code = exit_code;
+            }
+            ShellIntegrationEvent::CommandLine {
+                block_id,
+                command_line,
+            } => {
+                self.block_mut(block_id).command_line = Some(command_line);
+            }
+        }
+    }
+
+    fn block_mut(&mut self, block_id: usize) -> &mut CommandBlock {
+        let ix = match self
+            .blocks
+            .binary_search_by_key(&block_id, |block| block.id)
+        {
+            Ok(ix) => ix,
+            Err(mut ix) => {
+                if self.blocks.len() >= MAX_COMMAND_BLOCKS && ix > 0 {
+                    self.blocks.pop_front();
+                    ix -= 1;
+                }
+                self.blocks.insert(ix, CommandBlock::new(block_id));
+                ix
+            }
+        };
+        &mut self.blocks[ix]
+    }
+
+    pub fn is_active(&self) -> bool {
+        !self.blocks.is_empty()
+    }
+
+    pub fn blocks(&self) -> impl DoubleEndedIterator<Item = &CommandBlock> {
+        self.blocks.iter()
+    }
+
+    pub fn block(&self, block_id: usize) -> Option<&CommandBlock> {
+        self.blocks
+            .binary_search_by_key(&block_id, |block| block.id)
+            .ok()
+            .map(|ix| &self.blocks[ix])
+    }
+
+    pub fn last_finished_block(&self) -> Option<&CommandBlock> {
+        self.blocks.iter().rev().find(|block| block.finished)
+    }
+
+    /// Wraps a PTY so that the shell integration marks it outputs are reported to this state.
+    pub fn wrap_pty<T: EventedPty>(&self, pty: T) -> ShellIntegrationPty<T> {
+        ShellIntegrationPty::new(pty, self.events.clone())
+    }
+}
+
+/// The line each command block starts on, from the top of the scrollback down.
+///
+/// Blocks are found by looking at the first cell of every line, which is where shells print
+/// their prompts. Rather than scanning the whole scrollback whenever the terminal content
+/// changes, the starts found so far are moved along as the terminal scrolls, and only the
+/// lines printed since the last update are scanned for new blocks.
+#[derive(Default)]
+pub struct BlockStarts {
+    starts: Vec<(usize, Line)>,
+    /// Set when the grid was reflowed or cleared, so blocks must be found again.
+    invalidated: bool,
+}
+
+impl BlockStarts {
+    pub fn as_slice(&self) -> &[(usize, Line)] {
+        &self.starts
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.starts.is_empty()
+    }
+
+    /// Makes the next update scan the whole scrollback, for when lines were reflowed.
+    pub fn invalidate(&mut self) {
+        self.invalidated = true;
+    }
+
+    /// Updates the block starts after the terminal content changed, given the id of the
+    /// newest block the shell reported.
+    pub fn update<T>(&mut self, term: &Term<T>, newest_block_id: Option<usize>) {
+        if self.invalidated || !self.follow_scrolling(term) {
+            self.starts = scan_block_starts(term);
+            self.invalidated = false;
+            return;
+        }
+
+        let last_block_id = self.starts.last().map(|(block_id, _)| *block_id);
+        if newest_block_id.is_some_and(|newest_block_id| Some(newest_block_id) > last_block_id) {
+            self.find_new_blocks(term);
+        }
+    }
+
+    /// Moves the block starts up by the number of lines that scrolled into the scrollback,
+    /// returning false when the newest block can no longer be found.
+    fn follow_scrolling<T>(&mut self, term: &Term<T>) -> bool {
+        let Some(&(block_id, old_line)) = self.starts.last() else {
+            return true;
+        };
+
+        // Lines only move up as the terminal scrolls, so the newest block now starts at
+        // or above where it did, and finding it takes as many steps as lines scrolled.
+        let grid = term.grid();
+        let mut start = None;
+        for line in (grid.topmost_line().0..=cmp::min(old_line, grid.bottommost_line()).0).rev() {
+            match line_block(term, Line(line)) {
+                Some(id) if id == block_id => start = Some(Line(line)),
+                Some(id) if id < block_id => break,
+                _ => {}
+            }
+        }
+        let Some(start) = start else {
+            return false;
+        };
+
+        let scrolled = old_line.0 - start.0;
+        if scrolled > 0 {
+            let topmost_line = grid.topmost_line();
+            let mut truncated_block_id = None;
+            self.starts.retain_mut(|(block_id, line)| {
+                line.0 -= scrolled;
+                if *line < topmost_line {
+                    truncated_block_id = Some(*block_id);
+                }
+                *line >= topmost_line
+            });
+
+            // A block whose prompt was truncated from the scrollback now starts at the
+            // first of its lines that's left.
+            if let Some(block_id) = truncated_block_id {
+                let first_start = self.starts.first().map_or(start, |(_, line)| *line);
+                let first_tagged_line = (topmost_line.0..first_start.0).find_map(|line| {
+                    line_block(term, Line(line)).map(|line_block_id| (line_block_id, Line(line)))
+                });
+                if let Some((first_block_id, line)) = first_tagged_line {
+                    if first_block_id == block_id {
+                        self.starts.insert(0, (block_id, line));
+                    }
+                }
+            }
+        }
+        true
+    }
+
+    /// Scans up from the bottom of the grid for blocks newer than the newest known one.
+    fn find_new_blocks<T>(&mut self, term: &Term<T>) {
+        let grid = term.grid();
+        let last_block_id = self.starts.last().map(|(block_id, _)| *block_id);
+        let mut new_starts: Vec<(usize, Line)> = Vec::new();
+        for line in (grid.topmost_line().0..=grid.bottommost_line().0).rev() {
+            let Some(block_id) = line_block(term, Line(line)) else {
+                continue;
+            };
+            if last_block_id.is_some_and(|last_block_id| block_id <= last_block_id) {
+                break;
+            }
+            match new_starts.last_mut() {
+                Some((last_new_block_id, start)) if *last_new_block_id == block_id => {
+                    *start = Line(line);
+                }
+                _ => new_starts.push((block_id, Line(line))),
+            }
+        }
+        self.starts.extend(new_starts.into_iter().rev());
+    }
+}
+
+fn scan_block_starts<T>(term: &Term<T>) -> Vec<(usize, Line)> {
+    let grid = term.grid();
+    let mut starts = Vec::new();
+    let mut last_block_id = None;
+    for line in grid.topmost_line().0..=grid.bottommost_line().0 {
+        let line = Line(line);
+        let Some(block_id) = line_block(term, line) else {
+            continue;
+        };
+        if last_block_id != Some(block_id) {
+            starts.push((block_id, line));
+            last_block_id = Some(block_id);
+        }
+    }
+    starts
+}
+
+/// The block the first cell of a line belongs to.
+fn line_block<T>(term: &Term<T>, line: Line) -> Option<usize> {
+    cell_block(&term.grid()[line][Column(0)].hyperlink()).map(|(block_id, _)| block_id)
+}
+
+/// The line of the closest prompt above the given line.
+pub fn previous_prompt_line(block_starts: &[(usize, Line)], line: Line) -> Option<Line> {
+    block_starts
+        .iter()
+        .rev()
+        .map(|(_, start)| *start)
+        .find(|start| *start < line)
+}
+
+/// The line of the closest prompt below the given line.
+pub fn next_prompt_line(block_starts: &[(usize, Line)], line: Line) -> Option<Line> {
+    block_starts
+        .iter()
+        .map(|(_, start)| *start)
+        .find(|start| *start > line)
+}
+
+/// Reads the command line and output of a block back from the grid.
+pub fn command_output<T>(
+    term: &Term<T>,
+    block: &CommandBlock,
+    block_starts: &[(usize, Line)],
+) -> Option<CommandOutput> {
+    let start_ix = block_starts
+        .iter()
+        .position(|(block_id, _)| *block_id == block.id)?;
+    let start_line = block_starts[start_ix].1;
+    let end_line = block_starts
+        .get(start_ix + 1)
+        .map_or(term.bottommost_line(), |(_, line)| *line);
+    let lines = start_line..=end_line;
+
+    let region_text = |region| {
+        region_bounds(term, block.id, region, lines.clone())
+            .map(|bounds| {
+                term.bounds_to_string(*bounds.start(), *bounds.end())
+                    .trim_end()
+                    .to_string()
+            })
+            .unwrap_or_default()
+    };
+    let command = block
+        .command_line
+        .clone()
+        .unwrap_or_else(|| region_text(BlockRegion::Input).trim().to_string());
+    let output = region_text(BlockRegion::Output);
+
+    Some(CommandOutput {
+        command,
+        output,
+        exit_code: block.exit_code,
+    })
+}
+
+/// Reads back the output of the last finished block whose command line is `command`, skipping
+/// the blocks of other commands, such as those run by the command itself.
+pub fn last_output_of_command<T>(
+    term: &Term<T>,
+    state: &ShellIntegrationState,
+    block_starts: &[(usize, Line)],
+    command: &str,
+) -> Option<CommandOutput> {
+    let command = command.trim();
+    state
+        .blocks()
+        .rev()
+        .filter(|block| block.finished)
+        .filter(|block| {
+            block
+                .command_line
+                .as_ref()
+                .is_none_or(|command_line| command_line.trim() == command)
+        })
+        .filter_map(|block| command_output(term, block, block_starts))
+        .find(|output| output.command == command)
+}
+
+/// The first and last cell of the given region of a block, searching only the given lines.
+pub fn region_bounds<T>(
+    term: &Term<T>,
+    block_id: usize,
+    region: BlockRegion,
+    lines: RangeInclusive<Line>,
+) -> Option<RangeInclusive<AlacPoint>> {
+    let grid = term.grid();
+    let start_line = cmp::max(*lines.start(), grid.topmost_line());
+    let end_line = cmp::min(*lines.end(), grid.bottommost_line());
+
+    let mut bounds: Option<(AlacPoint, AlacPoint)> = None;
+    for line in start_line.0..=end_line.0 {
+        let line = Line(line);
+        for column in 0..grid.columns() {
+            let column = Column(column);
+            if cell_block(&grid[line][column].hyperlink()) == Some((block_id, region)) {
+                let point = AlacPoint::new(line, column);
+                bounds = Some(match bounds {
+                    Some((start, _)) => (start, point),
+                    None => (point, point),
+                });
+            }
+        }
+    }
+    bounds.map(|(start, end)| start..=end)
+}
+
+fn cell_block(link: &Option<Hyperlink>) -> Option<(usize, BlockRegion)> {
+    link.as_ref().and_then(|link| parse_block_link(link.uri()))
+}
+
+/// Whether a hyperlink is one of the tags used to mark command blocks, rather than a link
+/// printed by a program.
+pub fn is_block_link(link: &Hyperlink) -> bool {
+    parse_block_link(link.uri()).is_some()
+}
+
+fn block_link_uri(block_id: usize, region: BlockRegion) -> String {
+    format!("{BLOCK_LINK_SCHEME}:{block_id}{}", region.tag())
+}
+
+fn parse_block_link(uri: &str) -> Option<(usize, BlockRegion)> {
+    let rest = uri.strip_prefix(BLOCK_LINK_SCHEME)?.strip_prefix(':')?;
+    let tag = rest.chars().last()?;
+    let block_id = rest[..rest.len() - tag.len_utf8()].parse().ok()?;
+    Some((block_id, BlockRegion::from_tag(tag)?))
+}
+
+/// Rewrites shell integration marks in the PTY output into OSC 8 block tags.
+#[derive(Default)]
+pub struct ShellIntegrationFilter {
+    /// The start of an escape sequence that may be a shell integration mark, held back
+    /// until it is complete.
+    pending: Vec<u8>,
+    next_block_id: usize,
+    current_block: Option<usize>,
+    current_region: Option<BlockRegion>,
+}
+
+enum SequenceState {
+    /// The sequence may still turn into one we handle.
+    Incomplete,
+    /// The sequence is not one we handle and is passed through.
+    Unhandled,
+    /// A complete OSC sequence with the given command and payload.
+    Complete(OscKind),
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum OscKind {
+    ShellIntegration,
+    Hyperlink,
+}
+
+impl ShellIntegrationFilter {
+    pub fn process(
+        &mut self,
+        input: &[u8],
+        output: &mut Vec<u8>,
+        events: &mut Vec<ShellIntegrationEvent>,
+    ) {
+        let mut remaining = input;
+        while !remaining.is_empty() {
+            if self.pending.is_empty() {
+                let Some(escape_ix) = remaining.iter().position(|byte| *byte == ESC) else {
+                    output.extend_from_slice(remaining);
+                    return;
+                };
+                output.extend_from_slice(&remaining[..escape_ix]);
+                remaining = &remaining[escape_ix..];
+            }
+
+            let byte = remaining[0];
+            remaining = &remaining[1..];
+            self.pending.push(byte);
+
+            match self.classify_pending() {
+                SequenceState::Incomplete => {}
+                SequenceState::Unhandled => {
+                    // An escape starts a new sequence that may be one we handle.
+                    let restart = self.pending.len() > 1 && byte == ESC;
+                    if restart {
+                        self.pending.pop();
+                    }
+                    output.append(&mut self.pending);
+                    if restart {
+                        self.pending.push(ESC);
+                    }
+                }
+                SequenceState::Complete(kind) => {
+                    let sequence = std::mem::take(&mut self.pending);
+                    self.handle_sequence(kind, &sequence, output, events);
+                }
+            }
+        }
+    }
+
+    /// Passes through any sequence that was held back, for when the PTY is closed.
+    pub fn flush(&mut self, output: &mut Vec<u8>) {
+        output.append(&mut self.pending);
+    }
+
+    fn classify_pending(&self) -> SequenceState {
+        const PREFIXES: [(&[u8], OscKind); 3] = [
+            (b"\x1b]133;", OscKind::ShellIntegration),
+            (b"\x1b]633;", OscKind::ShellIntegration),
+            (b"\x1b]8;", OscKind::Hyperlink),
+        ];
+
+        let pending = self.pending.as_slice();
+        let Some((_, kind)) = PREFIXES.iter().find(|(prefix, _)| {
+            let len = cmp::min(prefix.len(), pending.len());
+            pending[..len] == prefix[..len]
+        }) else {
+            return SequenceState::Unhandled;
+        };
+        if PREFIXES
+            .iter()
+            .all(|(prefix, _)| pending.len() <= prefix.len() || !pending.starts_with(prefix))
+        {
+            return SequenceState::Incomplete;
+        }
+        if pending.len() > MAX_SEQUENCE_LEN {
+            return SequenceState::Unhandled;
+        }
+
+        match pending.last() {
+            Some(&BEL) => SequenceState::Complete(*kind),
+            Some(b'\\') if pending[pending.len() - 2] == ESC => SequenceState::Complete(*kind),
+            Some(&ESC) => SequenceState::Incomplete,
+            // An escape not followed by `\` cancels the sequence.
+            Some(_) if pending[pending.len() - 2] == ESC => SequenceState::Unhandled,
+            _ => SequenceState::Incomplete,
+        }
+    }
+
+    fn handle_sequence(
+        &mut self,
+        kind: OscKind,
+        sequence: &[u8],
+        output: &mut Vec<u8>,
+        events: &mut Vec<ShellIntegrationEvent>,
+    ) {
+        let body = sequence
+            .strip_suffix(&[BEL])
+            .or_else(|| sequence.strip_suffix(b"\x1b\\"))
+            .unwrap_or(sequence);
+        // Skip `ESC ]`.
+        let body = &body[2..];
+
+        match kind {
+            OscKind::Hyperlink => {
+                output.extend_from_slice(sequence);
+                // `OSC 8 ; params ; ST` closes a link, which also removes our tag from the
+                // cells printed afterwards, so re-apply it.
+                let closes_link = body
+                    .rsplit(|byte| *byte == b';')
+                    .next()
+                    .is_some_and(|uri| uri.is_empty());
+                if let Some((block_id, region)) = self
+                    .current_block
+                    .zip(self.current_region)
+                    .filter(|_| closes_link)
+                {
+                    write_block_link(output, block_id, region);
+                }
+            }
+            OscKind::ShellIntegration => {
+                // Skip the `133;` or `633;` prefix.
+                let body = String::from_utf8_lossy(&body[4..]);
+                let mut params = body.splitn(2, ';');
+                let command = params.next().unwrap_or_default();
+                let argument = params.next();
+                self.handle_mark(command, argument, output, events);
+            }
+        }
+    }
+
+    fn handle_mark(
+        &mut self,
+        command: &str,
+        argument: Option<&str>,
+        output: &mut Vec<u8>,
+        events: &mut Vec<ShellIntegrationEvent>,
+    ) {
+        match command {
+            "A" => {
+                let block_id = self.start_block();
+                self.enter_region(block_id, BlockRegion::Prompt, output);
+                events.push(ShellIntegrationEvent::PromptStarted { block_id });
+            }
+            "B" => {
+                let block_id = self.current_or_new_block();
+                self.enter_region(block_id, BlockRegion::Input, output);
+                events.push(ShellIntegrationEvent::CommandStarted { block_id });
+            }
+            "C" => {
+                let block_id = self.current_or_new_block();
+                self.enter_region(block_id, BlockRegion::Output, output);
+                events.push(ShellIntegrationEvent::CommandExecuted { block_id });
+            }
+            "D" => {
+                // Shells send `D` before their first prompt too, when no command ran yet.
+                let Some(block_id) = self.current_block.take() else {
+                    return;
+                };
+                if self.current_region.take().is_some() {
+                    output.extend_from_slice(b"\x1b]8;;\x1b\\");
+                }
+                let exit_code = argument
+                    .and_then(|argument| argument.split(';').next())
+                    .and_then(|exit_code| exit_code.trim().parse().ok());
+                events.push(ShellIntegrationEvent::CommandFinished {
+                    block_id,
+                    exit_code,
+                });
+            }
+            "E" => {
+                if let Some((block_id, argument)) = self.current_block.zip(argument) {
+                    let command_line = argument.split(';').next().unwrap_or_default();
+                    events.push(ShellIntegrationEvent::CommandLine {
+                        block_id,
+                        command_line: unescape_command_line(command_line),
+                    });
+                }
+            }
+            // Properties such as the working directory (`P`) are not used yet.
+            _ => {}
+        }
+    }
+
+    fn start_block(&mut self) -> usize {
+        let block_id = self.next_block_id;
+        self.next_block_id += 1;
+        self.current_block = Some(block_id);
+        block_id
+    }
+
+    fn current_or_new_block(&mut self) -> usize {
+        match self.current_block {
+            Some(block_id) => block_id,
+            None => self.start_block(),
+        }
+    }
+
+    fn enter_region(&mut self, block_id: usize, region: BlockRegion, output: &mut Vec<u8>) {
+        self.current_region = Some(region);
+        write_block_link(output, block_id, region);
+    }
+}
+
+fn write_block_link(output: &mut Vec<u8>, block_id: usize, region: BlockRegion) {
+    let uri = block_link_uri(block_id, region);
+    // Alacritty considers cells to be part of the same link when both the id and the URI
+    // match, so use the URI as the id too.
+    output.extend_from_slice(format!("\x1b]8;id={uri};{uri}\x1b\\").as_bytes());
+}
+
+/// Decodes the `\\` and `\xAB` escapes VS Code's shell integration scripts use in command lines.
+///
+/// Escaped bytes may be parts of multi-byte characters, so they are decoded before the
+/// command line is read as UTF-8.
+fn unescape_command_line(escaped: &str) -> String {
+    let bytes = escaped.as_bytes();
+    let mut result = Vec::with_capacity(bytes.len());
+    let mut ix = 0;
+    while ix < bytes.len() {
+        if bytes[ix] == b'\\' {
+            if bytes.get(ix + 1) == Some(&b'\\') {
+                result.push(b'\\');
+                ix += 2;
+                continue;
+            }
+            if let Some(byte) = bytes
+                .get(ix + 1..ix + 4)
+                .and_then(|escape| escape.strip_prefix(b"x"))
+                .and_then(|hex| std::str::from_utf8(hex).ok())
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+            {
+                result.push(byte);
+                ix += 4;
+                continue;
+            }
+        }
+        result.push(bytes[ix]);
+        ix += 1;
+    }
+    String::from_utf8_lossy(&result).into_owned()
+}
+
+/// A PTY whose output is passed through a [`ShellIntegrationFilter`] before Alacritty
+/// parses it.
+pub struct ShellIntegrationPty<T> {
+    pty: T,
+    filter: ShellIntegrationFilter,
+    read_buffer: Vec<u8>,
+    output: Vec<u8>,
+    output_ix: usize,
+    events: Arc<Mutex<Vec<ShellIntegrationEvent>>>,
+}
+
+impl<T> ShellIntegrationPty<T> {
+    /// How much less than the caller's buffer to read from the PTY, to leave room for the
+    /// block tags being longer than the marks they replace.
+    const TAG_HEADROOM: usize = 1024;
+
+    fn new(pty: T, events: Arc<Mutex<Vec<ShellIntegrationEvent>>>) -> Self {
+        Self {
+            pty,
+            filter: ShellIntegrationFilter::default(),
+            read_buffer: Vec::new(),
+            output: Vec::new(),
+            output_ix: 0,
+            events,
+        }
+    }
+}
+
+impl<T: EventedReadWrite> Read for ShellIntegrationPty<T> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        while self.output_ix == self.output.len() {
+            self.output.clear();
+            self.output_ix = 0;
+
+            // Tags are longer than the marks they replace. Alacritty waits for the PTY to
+            // become readable again once it has parsed enough, so output left in our
+            // buffer would only be shown once the shell prints something else. Leaving
+            // room for the tags means the filtered output almost always fits.
+            let read_len = buf.len().saturating_sub(Self::TAG_HEADROOM).max(1);
+            if self.read_buffer.len() < read_len {
+                self.read_buffer.resize(read_len, 0);
+            }
+            let count = self.pty.reader().read(&mut self.read_buffer[..read_len])?;
+            if count == 0 {
+                self.filter.flush(&mut self.output);
+                if self.output.is_empty() {
+                    return Ok(0);
+                }
+                break;
+            }
+
+            let mut events = Vec::new();
+            self.filter
+                .process(&self.read_buffer[..count], &mut self.output, &mut events);
+            if !events.is_empty() {
+                self.events.lock().unwrap().extend(events);
+            }
+        }
+
+        let len = cmp::min(buf.len(), self.output.len() - self.output_ix);
+        buf[..len].copy_from_slice(&self.output[self.output_ix..self.output_ix + len]);
+        self.output_ix += len;
+        Ok(len)
+    }
+}
+
+impl<T: EventedReadWrite> EventedReadWrite for ShellIntegrationPty<T> {
+    type Reader = Self;
+    type Writer = T::Writer;
+
+    unsafe fn register(
+        &mut self,
+        poller: &Arc<Poller>,
+        interest: PollEvent,
+        mode: PollMode,
+    ) -> io::Result<()> {
+        unsafe { self.pty.register(poller, interest, mode) }
+    }
+
+    fn reregister(
+        &mut self,
+        poller: &Arc<Poller>,
+        interest: PollEvent,
+        mode: PollMode,
+    ) -> io::Result<()> {
+        self.pty.reregister(poller, interest, mode)
+    }
+
+    fn deregister(&mut self, poller: &Arc<Poller>) -> io::Result<()> {
+        self.pty.deregister(poller)
+    }
+
+    fn reader(&mut self) -> &mut Self::Reader {
+        self
+    }
+
+    fn writer(&mut self) -> &mut Self::Writer {
+        self.pty.writer()
+    }
+}
+
+impl<T: EventedPty> EventedPty for ShellIntegrationPty<T> {
+    fn next_child_event(&mut self) -> Option<ChildEvent> {
+        self.pty.next_child_event()
+    }
+}
+
+impl<T: OnResize> OnResize for ShellIntegrationPty<T> {
+    fn on_resize(&mut self, window_size: WindowSize) {
+        self.pty.on_resize(window_size)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::screen_snapshot::ScreenSize;
+    use alacritty_terminal::{event::VoidListener, term::Config, vte::ansi::Processor};
+
+    /// A terminal fed with shell integration marks the way [`ShellIntegrationPty`] and the
+    /// terminal's event handling feed it.
+    struct TestShell {
+        term: Term<VoidListener>,
+        parser: Processor,
+        filter: ShellIntegrationFilter,
+        state: ShellIntegrationState,
+        block_starts: BlockStarts,
+    }
+
+    impl TestShell {
+        fn new(lines: usize, scrolling_history: usize) -> Self {
+            let config = Config {
+                scrolling_history,
+                ..Config::default()
+            };
+            let size = ScreenSize { columns: 20, lines };
+            Self {
+                term: Term::new(config, &size, VoidListener),
+                parser: Processor::new(),
+                filter: ShellIntegrationFilter::default(),
+                state: ShellIntegrationState::default(),
+                block_starts: BlockStarts::default(),
+            }
+        }
+
+        fn print(&mut self, text: &str) {
+            let mut output = Vec::new();
+            let mut events = Vec::new();
+            self.filter
+                .process(text.as_bytes(), &mut output, &mut events);
+            self.parser.advance(&mut self.term, &output);
+            self.state.events.lock().unwrap().extend(events);
+            self.state.apply_pending_events();
+            let newest_block_id = self.state.blocks().next_back().map(|block| block.id);
+            self.block_starts.update(&self.term, newest_block_id);
+        }
+
+        fn run(&mut self, command: &str, output: &str, exit_code: i32) {
+            self.print(&format!(
+                "\x1b]133;A\x07$ \x1b]133;B\x07{command}\r\n\x1b]133;C\x07{output}\x1b]133;D;{exit_code}\x07"
+            ));
+        }
+
+        fn assert_block_starts_match_full_scan(&self) {
+            assert_eq!(
+                self.block_starts.as_slice(),
+                scan_block_starts(&self.term).as_slice()
+            );
+        }
+    }
+
+    #[test]
+    fn test_block_starts_follow_scrolling() {
+        let mut shell = TestShell::new(5, 100);
+        shell.print("Welcome!\r\n");
+        shell.run("echo a", "a\r\n", 0);
+        shell.run("false", "", 1);
+        shell.assert_block_starts_match_full_scan();
+        assert_eq!(shell.block_starts.as_slice(), [(0, Line(1)), (1, Line(3))]);
+
+        // Output that scrolls earlier blocks into the scrollback.
+        let output = (0..30)
+            .map(|ix| format!("line {ix}\r\n"))
+            .collect::<String>();
+        shell.run("seq 30", &output, 0);
+        shell.assert_block_starts_match_full_scan();
+        assert_eq!(shell.block_starts.as_slice().len(), 3);
+
+        // Output printed in several reads, with no new blocks.
+        shell.print("\x1b]133;A\x07$ \x1b]133;B\x07cat\r\n\x1b]133;C\x07");
+        for ix in 0..10 {
+            shell.print(&format!("more {ix}\r\n"));
+            shell.assert_block_starts_match_full_scan();
+        }
+        assert_eq!(shell.block_starts.as_slice().len(), 4);
+    }
+
+    #[test]
+    fn test_block_starts_drop_truncated_blocks() {
+        let mut shell = TestShell::new(4, 6);
+        for ix in 0..6 {
+            shell.run(&format!("echo {ix}"), &format!("{ix}\r\n"), 0);
+            shell.assert_block_starts_match_full_scan();
+        }
+        let starts = shell.block_starts.as_slice();
+        assert!(starts.len() < 6);
+        assert_eq!(starts.last().map(|(block_id, _)| *block_id), Some(5));
+        assert!(
+            starts
+                .iter()
+                .all(|(_, line)| *line >= shell.term.grid().topmost_line())
+        );
+    }
+
+    #[test]
+    fn test_command_output() {
+        let mut shell = TestShell::new(10, 100);
+        shell.run("ls", "file1\r\nfile2\r\n", 0);
+        shell.run("cat missing", "no such file\r\n", 1);
+        shell.print("\x1b]133;A\x07$ ");
+
+        let block = shell.state.last_finished_block().unwrap();
+        assert_eq!(
+            command_output(&shell.term, block, shell.block_starts.as_slice()),
+            Some(CommandOutput {
+                command: "cat missing".into(),
+                output: "no such file".into(),
+                exit_code: Some(1),
+            })
+        );
+
+        let first_block = shell.state.block(0).unwrap();
+        assert_eq!(
+            command_output(&shell.term, first_block, shell.block_starts.as_slice()),
+            Some(CommandOutput {
+                command: "ls".into(),
+                output: "file1\nfile2".into(),
+                exit_code: Some(0),
+            })
+        );
+    }
+
+    #[test]
+    fn test_last_output_of_command() {
+        let mut shell = TestShell::new(10, 100);
+        shell.run("make", "building\r\n", 0);
+        shell.run("ls", "file1\r\n", 0);
+        shell.print("\x1b]133;A\x07$ ");
+
+        assert_eq!(
+            last_output_of_command(
+                &shell.term,
+                &shell.state,
+                shell.block_starts.as_slice(),
+                "make "
+            ),
+            Some(CommandOutput {
+                command: "make".into(),
+                output: "building".into(),
+                exit_code: Some(0),
+            })
+        );
+        assert_eq!(
+            last_output_of_command(
+                &shell.term,
+                &shell.state,
+                shell.block_starts.as_slice(),
+                "cargo test"
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn test_prompt_navigation() {
+        let block_starts = [(0, Line(-12)), (1, Line(-4)), (2, Line(3))];
+
+        assert_eq!(previous_prompt_line(&block_starts, Line(0)), Some(Line(-4)));
+        assert_eq!(
+            previous_prompt_line(&block_starts, Line(-4)),
+            Some(Line(-12))
+        );
+        assert_eq!(previous_prompt_line(&block_starts, Line(-12)), None);
+
+        assert_eq!(next_prompt_line(&block_starts, Line(-12)), Some(Line(-4)));
+        assert_eq!(next_prompt_line(&block_starts, Line(0)), Some(Line(3)));
+        assert_eq!(next_prompt_line(&block_starts, Line(3)), None);
+    }
+
+    #[test]
+    fn test_unescape_command_line() {
+        assert_eq!(unescape_command_line("echo a\\x3bb"), "echo a;b");
+        assert_eq!(unescape_command_line("a\\\\b \\q"), "a\\b \\q");
+        assert_eq!(
+            unescape_command_line("echo caf\\xc3\\xa9 ünï"),
+            "echo café ünï"
+        );
+        assert_eq!(unescape_command_line("bad \\xzz \\x4"), "bad \\xzz \\x4");
+    }
+
+    fn filter(chunks: &[&[u8]]) -> (String, Vec<ShellIntegrationEvent>) {
+        let mut filter = ShellIntegrationFilter::default();
+        let mut output = Vec::new();
+        let mut events = Vec::new();
+        for chunk in chunks {
+            filter.process(chunk, &mut output, &mut events);
+        }
+        filter.flush(&mut output);
+        (String::from_utf8(output).unwrap(), events)
+    }
+
+    fn tag(block_id: usize, region: BlockRegion) -> String {
+        let uri = block_link_uri(block_id, region);
+        format!("\x1b]8;id={uri};{uri}\x1b\\")
+    }
+
+    #[test]
+    fn test_plain_output_is_untouched() {
+        let input = "hello \x1b[31mred\x1b[0m \x1b]0;title\x07 \x1b]8;;https://zed.dev\x1b\\link";
+        let (output, events) = filter(&[input.as_bytes()]);
+        assert_eq!(output, input);
+        assert!(events.is_empty());
+    }
+
+    #[test]
+    fn test_marks_are_replaced_by_tags() {
+        let (output, events) = filter(&[
+            b"\x1b]133;D\x07\x1b]133;A\x07$ \x1b]133;B\x07ls\r\n\x1b]133;C\x07",
+            b"file\r\n\x1b]133;D;1\x1b\\\x1b]133;A\x07$ ",
+        ]);
+
+        let expected = format!(
+            "{}$ {}ls\r\n{}file\r\n\x1b]8;;\x1b\\{}$ ",
+            tag(0, BlockRegion::Prompt),
+            tag(0, BlockRegion::Input),
+            tag(0, BlockRegion::Output),
+            tag(1, BlockRegion::Prompt),
+        );
+        assert_eq!(output, expected);
+        assert_eq!(
+            events,
+            vec![
+                ShellIntegrationEvent::PromptStarted { block_id: 0 },
+                ShellIntegrationEvent::CommandStarted { block_id: 0 },
+                ShellIntegrationEvent::CommandExecuted { block_id: 0 },
+                ShellIntegrationEvent::CommandFinished {
+                    block_id: 0,
+                    exit_code: Some(1)
+                },
+                ShellIntegrationEvent::PromptStarted { block_id: 1 },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_marks_split_across_reads() {
+        let input = b"\x1b]633;A\x07$ \x1b]633;E;echo a\\x3bb\x07\x1b]633;C\x07";
+        let chunks = input.chunks(1).collect::<Vec<_>>();
+        let (output, events) = filter(&chunks);
+
+        assert_eq!(
+            output,
+            format!(
+                "{}$ {}",
+                tag(0, BlockRegion::Prompt),
+                tag(0, BlockRegion::Output)
+            )
+        );
+        assert_eq!(
+            events[1],
+            ShellIntegrationEvent::CommandLine {
+                block_id: 0,
+                command_line: "echo a;b".into()
+            }
+        );
+    }
+
+    #[test]
+    fn test_closing_a_program_link_restores_the_tag() {
+        let (output, _) =
+            filter(&[b"\x1b]133;C\x07\x1b]8;;https://zed.dev\x07zed\x1b]8;;\x07 done"]);
+        let output_tag = tag(0, BlockRegion::Output);
+        assert_eq!(
+            output,
+            format!("{output_tag}\x1b]8;;https://zed.dev\x07zed\x1b]8;;\x07{output_tag} done")
+        );
+    }
+
+    #[test]
+    fn test_cancelled_and_unrelated_sequences_pass_through() {
+        let (output, events) = filter(&[b"\x1b]13x\x1b]133\x1b[1m", b"\x1b]13"]);
+        assert_eq!(output, "\x1b]13x\x1b]133\x1b[1m\x1b]13");
+        assert!(events.is_empty());
+    }
+
+    #[test]
+    fn test_block_links_round_trip() {
+        for region in [BlockRegion::Prompt, BlockRegion::Input, BlockRegion::Output] {
+            let uri = block_link_uri(42, region);
+            assert_eq!(parse_block_link(&uri), Some((42, region)));
+        }
+        assert_eq!(parse_block_link("https://zed.dev"), None);
+        assert_eq!(parse_block_link("zed-block:x"), None);
+    }
+
+    #[test]
+    fn test_state_tracks_blocks() {
+        let mut state = ShellIntegrationState::default();
+        state.events.lock().unwrap().extend([
+            ShellIntegrationEvent::PromptStarted { block_id: 0 },
+            ShellIntegrationEvent::CommandExecuted { block_id: 0 },
+            ShellIntegrationEvent::CommandFinished {
+                block_id: 0,
+                exit_code: Some(2),
+            },
+            ShellIntegrationEvent::PromptStarted { block_id: 1 },
+        ]);
+
+        assert!(state.apply_pending_events());
+        assert!(!state.apply_pending_events());
+        assert_eq!(state.blocks().count(), 2);
+
+        let finished = state.last_finished_block().unwrap();
+        assert_eq!(finished.id, 0);
+        assert!(finished.failed());
+        assert!(!state.block(1).unwrap().finished);
+    }
+}
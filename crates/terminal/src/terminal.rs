@@ -3,6 +3,8 @@ pub mod mappings;
 pub use alacritty_terminal;
 
 mod pty_info;
+mod screen_snapshot;
+pub mod shell_integration;
 pub mod terminal_settings;
 
 use alacritty_terminal::{
@@ -22,6 +24,7 @@ use alacritty_terminal::{
     vi_mode::{ViModeCursor, ViMotion},
     vte::ansi::{
         ClearMode, CursorStyle as AlacCursorStyle, Handler, NamedPrivateMode, PrivateMode,
+        Processor,
     },
 };
 use anyhow::{Result, bail};
@@ -40,8 +43,10 @@ use collections::{HashMap, VecDeque};
 use futures::StreamExt;
 use pty_info::PtyProcessInfo;
 use regex::Regex;
+pub use screen_snapshot::{EncodedScreen, ScreenSnapshot};
 use serde::{Deserialize, Serialize};
 use settings::Settings;
+use shell_integration::{BlockStarts, CommandOutput, ShellIntegrationState};
 use smol::channel::{Receiver, Sender};
 use task::{HideStrategy, Shell, TaskId};
 use terminal_settings::{AlternateScroll, CursorShape, TerminalSettings};
@@ -81,6 +86,9 @@ actions!(
         ScrollPageDown,
         ScrollToTop,
         ScrollToBottom,
+        ScrollToPreviousPrompt,
+        ScrollToNextPrompt,
+        CopyLastCommandOutput,
         ToggleViMode,
     ]
 );
@@ -464,6 +472,8 @@ impl TerminalBuilder {
         };
 
         let pty_info = PtyProcessInfo::new(&pty);
+        let shell_integration = ShellIntegrationState::default();
+        let pty = shell_integration.wrap_pty(pty);
 
         //And connect them together
         let event_loop = EventLoop::new(
@@ -480,7 +490,10 @@ impl TerminalBuilder {
 
         let terminal = Terminal {
             task,
-            pty_tx: Notifier(pty_tx),
+            terminal_type: TerminalType::Pty {
+                pty_tx: Notifier(pty_tx),
+                info: pty_info,
+            },
             completion_tx,
             term,
             term_config: config,
@@ -490,7 +503,6 @@ impl TerminalBuilder {
             last_mouse: None,
             matches: Vec::new(),
             selection_head: None,
-            pty_info,
             breadcrumb_text: String::new(),
             scroll_px: px(0.),
             next_link_id: 0,
@@ -502,6 +514,8 @@ impl TerminalBuilder {
             vi_mode_enabled: false,
             is_ssh_terminal,
             python_venv_directory,
+            shell_integration,
+            block_starts: BlockStarts::default(),
         };
 
         Ok(TerminalBuilder {
@@ -510,6 +524,81 @@ impl TerminalBuilder {
         })
     }
 
+    /// Builds a terminal showing a terminal that a collaborator shares, instead of
+    /// running a process. Its screen is set with [`Terminal::apply_screen_snapshot`],
+    /// and typed input is sent through `input_tx`, or dropped if it is `None`.
+    pub fn new_shared(
+        title: SharedString,
+        input_tx: Option<UnboundedSender<Vec<u8>>>,
+        cursor_shape: CursorShape,
+    ) -> TerminalBuilder {
+        Self::new_without_pty(title, TerminalType::Shared { input_tx }, cursor_shape)
+    }
+
+    /// Builds a terminal that doesn't run a process, for tests to share with collaborators.
+    /// Its screen is set with [`Terminal::apply_screen_snapshot`], and typed input is sent
+    /// through `input_tx`.
+    #[cfg(any(test, feature = "test-support"))]
+    pub fn new_fake(title: SharedString, input_tx: UnboundedSender<Vec<u8>>) -> TerminalBuilder {
+        Self::new_without_pty(
+            title,
+            TerminalType::Fake { input_tx },
+            CursorShape::default(),
+        )
+    }
+
+    fn new_without_pty(
+        title: SharedString,
+        terminal_type: TerminalType,
+        cursor_shape: CursorShape,
+    ) -> TerminalBuilder {
+        let config = Config {
+            // Snapshots only contain the visible screen.
+            scrolling_history: 0,
+            default_cursor_style: AlacCursorStyle::from(cursor_shape),
+            ..Config::default()
+        };
+
+        let (events_tx, events_rx) = unbounded();
+        let term = Term::new(
+            config.clone(),
+            &TerminalBounds::default(),
+            ZedListener(events_tx),
+        );
+        let (completion_tx, _) = smol::channel::bounded(1);
+
+        let terminal = Terminal {
+            task: None,
+            terminal_type,
+            completion_tx,
+            term: Arc::new(FairMutex::new(term)),
+            term_config: config,
+            title_override: Some(title),
+            events: VecDeque::with_capacity(10),
+            last_content: Default::default(),
+            last_mouse: None,
+            matches: Vec::new(),
+            selection_head: None,
+            breadcrumb_text: String::new(),
+            scroll_px: px(0.),
+            next_link_id: 0,
+            selection_phase: SelectionPhase::Ended,
+            url_regex: RegexSearch::new(URL_REGEX).unwrap(),
+            word_regex: RegexSearch::new(WORD_REGEX).unwrap(),
+            python_file_line_regex: RegexSearch::new(PYTHON_FILE_LINE_REGEX).unwrap(),
+            vi_mode_enabled: false,
+            is_ssh_terminal: false,
+            python_venv_directory: None,
+            shell_integration: ShellIntegrationState::default(),
+            block_starts: BlockStarts::default(),
+        };
+
+        TerminalBuilder {
+            terminal,
+            events_rx,
+        }
+    }
+
     pub fn subscribe(mut self, cx: &Context<Terminal>) -> Terminal {
         //Event loop
         cx.spawn(async move |terminal, cx| {
@@ -638,8 +727,25 @@ pub enum SelectionPhase {
     Ended,
 }
 
+/// Where the output of a terminal comes from, and where its input goes.
+enum TerminalType {
+    /// A process running in a local pty.
+    Pty {
+        pty_tx: Notifier,
+        info: PtyProcessInfo,
+    },
+    /// A terminal shared by a collaborator, whose screen is replayed from snapshots.
+    /// Input is forwarded through `input_tx`, and dropped if the terminal is read-only.
+    Shared {
+        input_tx: Option<UnboundedSender<Vec<u8>>>,
+    },
+    /// A terminal that tests set the screen of, and whose input is sent through `input_tx`.
+    #[cfg(any(test, feature = "test-support"))]
+    Fake { input_tx: UnboundedSender<Vec<u8>> },
+}
+
 pub struct Terminal {
-    pty_tx: Notifier,
+    terminal_type: TerminalType,
     completion_tx: Sender<Option<ExitStatus>>,
     term: Arc<FairMutex<Term<ZedListener>>>,
     term_config: Config,
@@ -650,7 +756,6 @@ pub struct Terminal {
     pub last_content: TerminalContent,
     pub selection_head: Option<AlacPoint>,
     pub breadcrumb_text: String,
-    pub pty_info: PtyProcessInfo,
     title_override: Option<SharedString>,
     pub python_venv_directory: Option<PathBuf>,
     scroll_px: Pixels,
@@ -662,6 +767,9 @@ pub struct Terminal {
     task: Option<TaskState>,
     vi_mode_enabled: bool,
     is_ssh_terminal: bool,
+    shell_integration: ShellIntegrationState,
+    /// The line each command block reported through shell integration starts on.
+    block_starts: BlockStarts,
 }
 
 pub struct TaskState {
@@ -743,9 +851,19 @@ impl Terminal {
                 //NOOP, Handled in render
             }
             AlacTermEvent::Wakeup => {
+                self.shell_integration.apply_pending_events();
+                if self.shell_integration.is_active() {
+                    let newest_block_id = self
+                        .shell_integration
+                        .blocks()
+                        .next_back()
+                        .map(|block| block.id);
+                    self.block_starts.update(&self.term.lock(), newest_block_id);
+                }
+
                 cx.emit(Event::Wakeup);
 
-                if self.pty_info.has_changed() {
+                if self.pty_info().is_some_and(|info| info.has_changed()) {
                     cx.emit(Event::TitleChanged);
                 }
             }
@@ -788,9 +906,16 @@ impl Terminal {
 
                 self.last_content.terminal_bounds = new_bounds;
 
-                self.pty_tx.0.send(Msg::Resize(new_bounds.into())).ok();
-
-                term.resize(new_bounds);
+                match &self.terminal_type {
+                    TerminalType::Pty { pty_tx, .. } => {
+                        pty_tx.0.send(Msg::Resize(new_bounds.into())).ok();
+                        term.resize(new_bounds);
+                        self.block_starts.invalidate();
+                    }
+                    // The grid of a terminal without a process has the size of the snapshots
+                    // applied to it, such as those of the host's terminal.
+                    _ => {}
+                }
             }
             InternalEvent::Clear => {
                 // Clear back buffer
@@ -821,6 +946,7 @@ impl Terminal {
                 if (new_cursor.line.0 as usize) < term.screen_lines() - 1 {
                     term.grid_mut().reset_region((new_cursor.line + 1)..);
                 }
+                self.block_starts.invalidate();
 
                 cx.emit(Event::Wakeup);
             }
@@ -925,7 +1051,11 @@ impl Terminal {
                 )
                 .grid_clamp(term, Boundary::Grid);
 
-                let link = term.grid().index(point).hyperlink();
+                let link = term
+                    .grid()
+                    .index(point)
+                    .hyperlink()
+                    .filter(|link| !shell_integration::is_block_link(link));
                 let found_word = if link.is_some() {
                     let mut min_index = point;
                     loop {
@@ -1211,6 +1341,79 @@ impl Terminal {
             .push_back(InternalEvent::Scroll(AlacScroll::Bottom));
     }
 
+    /// Scrolls to the closest prompt above the top of the viewport, when the shell reports
+    /// its prompts through shell integration.
+    pub fn scroll_to_previous_prompt(&mut self) {
+        let viewport_top = Line(-(self.last_content.display_offset as i32));
+        if let Some(line) =
+            shell_integration::previous_prompt_line(self.block_starts.as_slice(), viewport_top)
+        {
+            self.scroll_up_by((viewport_top.0 - line.0) as usize);
+        }
+    }
+
+    /// Scrolls to the closest prompt below the top of the viewport, or to the bottom when
+    /// there is none.
+    pub fn scroll_to_next_prompt(&mut self) {
+        let viewport_top = Line(-(self.last_content.display_offset as i32));
+        match shell_integration::next_prompt_line(self.block_starts.as_slice(), viewport_top) {
+            Some(line) => self.scroll_down_by((line.0 - viewport_top.0) as usize),
+            None => self.scroll_to_bottom(),
+        }
+    }
+
+    pub fn shell_integration(&self) -> &ShellIntegrationState {
+        &self.shell_integration
+    }
+
+    /// The lines of the prompts of commands that failed, counted from the top of the
+    /// scrollback.
+    pub fn failed_command_lines(&self) -> Vec<usize> {
+        if self.block_starts.is_empty() {
+            return Vec::new();
+        }
+
+        let history_size = self.term.lock_unfair().history_size() as i32;
+        self.block_starts
+            .as_slice()
+            .iter()
+            .filter(|(block_id, _)| {
+                self.shell_integration
+                    .block(*block_id)
+                    .is_some_and(|block| block.failed())
+            })
+            .map(|(_, line)| (line.0 + history_size) as usize)
+            .collect()
+    }
+
+    /// The command line and output of the last command that finished, when the shell
+    /// reports commands through shell integration.
+    pub fn last_command_output(&self) -> Option<CommandOutput> {
+        let block = self.shell_integration.last_finished_block()?;
+        shell_integration::command_output(
+            &self.term.lock_unfair(),
+            block,
+            self.block_starts.as_slice(),
+        )
+    }
+
+    /// The output of the last finished command whose command line is `command`, when the
+    /// shell reports commands through shell integration.
+    pub fn last_output_of_command(&self, command: &str) -> Option<CommandOutput> {
+        shell_integration::last_output_of_command(
+            &self.term.lock_unfair(),
+            &self.shell_integration,
+            self.block_starts.as_slice(),
+            command,
+        )
+    }
+
+    pub fn copy_last_command_output(&mut self, cx: &mut Context<Self>) {
+        if let Some(output) = self.last_command_output() {
+            cx.write_to_clipboard(ClipboardItem::new_string(output.output));
+        }
+    }
+
     pub fn scrolled_to_top(&self) -> bool {
         self.last_content.scrolled_to_top
     }
@@ -1228,7 +1431,18 @@ impl Terminal {
 
     ///Write the Input payload to the tty.
     fn write_to_pty(&self, input: impl Into<Vec<u8>>) {
-        self.pty_tx.notify(input.into());
+        match &self.terminal_type {
+            TerminalType::Pty { pty_tx, .. } => pty_tx.notify(input.into()),
+            TerminalType::Shared { input_tx } => {
+                if let Some(input_tx) = input_tx {
+                    input_tx.unbounded_send(input.into()).ok();
+                }
+            }
+            #[cfg(any(test, feature = "test-support"))]
+            TerminalType::Fake { input_tx } => {
+                input_tx.unbounded_send(input.into()).ok();
+            }
+        }
     }
 
     pub fn input(&mut self, input: impl Into<Vec<u8>>) {
@@ -1530,7 +1744,7 @@ impl Terminal {
                 if let Some(bytes) =
                     mouse_moved_report(point, e.pressed_button, e.modifiers, self.last_content.mode)
                 {
-                    self.pty_tx.notify(bytes);
+                    self.write_to_pty(bytes);
                 }
             }
         } else if e.modifiers.secondary() {
@@ -1622,7 +1836,7 @@ impl Terminal {
             if let Some(bytes) =
                 mouse_button_report(point, e.button, e.modifiers, true, self.last_content.mode)
             {
-                self.pty_tx.notify(bytes);
+                self.write_to_pty(bytes);
             }
         } else {
             match e.button {
@@ -1681,7 +1895,7 @@ impl Terminal {
             if let Some(bytes) =
                 mouse_button_report(point, e.button, e.modifiers, false, self.last_content.mode)
             {
-                self.pty_tx.notify(bytes);
+                self.write_to_pty(bytes);
             }
         } else {
             if e.button == MouseButton::Left && setting.copy_on_select {
@@ -1692,7 +1906,10 @@ impl Terminal {
             if self.selection_phase == SelectionPhase::Ended {
                 let mouse_cell_index =
                     content_index_for_mouse(position, &self.last_content.terminal_bounds);
-                if let Some(link) = self.last_content.cells[mouse_cell_index].hyperlink() {
+                if let Some(link) = self.last_content.cells[mouse_cell_index]
+                    .hyperlink()
+                    .filter(|link| !shell_integration::is_block_link(link))
+                {
                     cx.open_url(link.uri());
                 } else if e.modifiers.secondary() {
                     self.events
@@ -1720,7 +1937,7 @@ impl Terminal {
                 if let Some(scrolls) = scroll_report(point, scroll_lines, e, self.last_content.mode)
                 {
                     for scroll in scrolls {
-                        self.pty_tx.notify(scroll);
+                        self.write_to_pty(scroll);
                     }
                 };
             } else if self
@@ -1729,7 +1946,7 @@ impl Terminal {
                 .contains(TermMode::ALT_SCREEN | TermMode::ALTERNATE_SCROLL)
                 && !e.shift
             {
-                self.pty_tx.notify(alt_scroll(scroll_lines))
+                self.write_to_pty(alt_scroll(scroll_lines))
             } else if scroll_lines != 0 {
                 let scroll = AlacScroll::Delta(scroll_lines);
 
@@ -1782,6 +1999,50 @@ impl Terminal {
         })
     }
 
+    /// The pty the terminal's process runs in, or `None` for terminals shared by a collaborator.
+    pub fn pty_info(&self) -> Option<&PtyProcessInfo> {
+        match &self.terminal_type {
+            TerminalType::Pty { info, .. } => Some(info),
+            _ => None,
+        }
+    }
+
+    /// Whether this terminal shows a terminal shared by a collaborator.
+    pub fn is_shared_with_us(&self) -> bool {
+        matches!(self.terminal_type, TerminalType::Shared { .. })
+    }
+
+    /// Whether input typed into this terminal is dropped, because it shows a terminal
+    /// that a collaborator shares without write access.
+    pub fn is_read_only(&self) -> bool {
+        matches!(self.terminal_type, TerminalType::Shared { input_tx: None })
+    }
+
+    /// Encodes the visible screen so it can be shown to collaborators.
+    pub fn encode_screen(&self) -> EncodedScreen {
+        screen_snapshot::encode(&self.term.lock())
+    }
+
+    /// Updates the screen of a terminal shared by a collaborator with a snapshot of theirs.
+    /// Terminals running a process ignore snapshots.
+    pub fn apply_screen_snapshot(&mut self, snapshot: &ScreenSnapshot, cx: &mut Context<Self>) {
+        if self.pty_info().is_some() {
+            return;
+        }
+
+        {
+            let mut term = self.term.lock();
+            let size = snapshot.size();
+            if term.columns() != size.columns || term.screen_lines() != size.lines {
+                term.resize(size);
+            }
+            let mut parser: Processor = Processor::new();
+            parser.advance(&mut *term, snapshot.content.as_bytes());
+        }
+        cx.emit(Event::Wakeup);
+        cx.notify();
+    }
+
     pub fn working_directory(&self) -> Option<PathBuf> {
         if self.is_ssh_terminal {
             // We can't yet reliably detect the working directory of a shell on the
@@ -1800,7 +2061,7 @@ impl Terminal {
     /// This does *not* return the working directory of the shell that runs on the
     /// remote host, in case Zed is connected to a remote host.
     fn client_side_working_directory(&self) -> Option<PathBuf> {
-        self.pty_info
+        self.pty_info()?
             .current
             .as_ref()
             .map(|process| process.cwd.clone())
@@ -1821,9 +2082,8 @@ impl Terminal {
                 .as_ref()
                 .map(|title_override| title_override.to_string())
                 .unwrap_or_else(|| {
-                    self.pty_info
-                        .current
-                        .as_ref()
+                    self.pty_info()
+                        .and_then(|info| info.current.as_ref())
                         .map(|fpi| {
                             let process_file = fpi
                                 .cwd
@@ -2020,7 +2280,9 @@ unsafe fn append_text_to_term(term: &mut Term<ZedListener>, text_lines: &[&str])
 
 impl Drop for Terminal {
     fn drop(&mut self) {
-        self.pty_tx.0.send(Msg::Shutdown).ok();
+        if let TerminalType::Pty { pty_tx, .. } = &self.terminal_type {
+            pty_tx.0.send(Msg::Shutdown).ok();
+        }
     }
 }
 
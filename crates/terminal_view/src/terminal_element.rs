@@ -22,6 +22,7 @@ use terminal::{
             CursorShape as AlacCursorShape, NamedColor,
         },
     },
+    shell_integration,
     terminal_settings::TerminalSettings,
 };
 use theme::{ActiveTheme, Theme, ThemeSettings};
@@ -356,7 +357,10 @@ impl TerminalElement {
         }
 
         let underline = (flags.intersects(Flags::ALL_UNDERLINES)
-            || indexed.cell.hyperlink().is_some())
+            || indexed
+                .cell
+                .hyperlink()
+                .is_some_and(|link| !shell_integration::is_block_link(&link)))
         .then(|| UnderlineStyle {
             color: Some(fg),
             thickness: Pixels::from(1.0),
@@ -1178,7 +1182,10 @@ pub fn is_blank(cell: &IndexedCell) -> bool {
         return false;
     }
 
-    if cell.hyperlink().is_some() {
+    if cell
+        .hyperlink()
+        .is_some_and(|link| !shell_integration::is_block_link(&link))
+    {
         return false;
     }
 
@@ -17,9 +17,10 @@ use project::{Entry, Metadata, Project, search::SearchQuery, terminals::Terminal
 use schemars::JsonSchema;
 use task::TaskId;
 use terminal::{
-    Clear, Copy, Event, HoveredWord, MaybeNavigationTarget, Paste, ScrollLineDown, ScrollLineUp,
-    ScrollPageDown, ScrollPageUp, ScrollToBottom, ScrollToTop, ShowCharacterPalette, TaskState,
-    TaskStatus, Terminal, TerminalBounds, ToggleViMode,
+    Clear, Copy, CopyLastCommandOutput, Event, HoveredWord, MaybeNavigationTarget, Paste,
+    ScrollLineDown, ScrollLineUp, ScrollPageDown, ScrollPageUp, ScrollToBottom, ScrollToNextPrompt,
+    ScrollToPreviousPrompt, ScrollToTop, ShowCharacterPalette, TaskState, TaskStatus, Terminal,
+    TerminalBounds, ToggleViMode,
     alacritty_terminal::{
         index::Point,
         term::{TermMode, search::RegexSearch},
@@ -35,11 +36,12 @@ use ui::{
 };
 use util::{ResultExt, debug_panic, paths::PathWithPosition};
 use workspace::{
-    CloseActiveItem, NewCenterTerminal, NewTerminal, OpenOptions, OpenVisible, ToolbarItemLocation,
-    Workspace, WorkspaceId, delete_unloaded_items,
+    CloseActiveItem, NewCenterTerminal, NewTerminal, OpenOptions, OpenVisible, Toast,
+    ToolbarItemLocation, Workspace, WorkspaceId, delete_unloaded_items,
     item::{
         BreadcrumbText, Item, ItemEvent, SerializableItem, TabContentParams, TabTooltipContent,
     },
+    notifications::NotificationId,
     register_serializable_item,
     searchable::{Direction, SearchEvent, SearchOptions, SearchableItem, SearchableItemHandle},
 };
@@ -73,7 +75,16 @@ pub struct SendText(String);
 #[derive(Clone, Debug, Default, Deserialize, JsonSchema, PartialEq)]
 pub struct SendKeystroke(String);
 
-actions!(terminal, [RerunTask]);
+actions!(
+    terminal,
+    [
+        RerunTask,
+        ShareTerminal,
+        ShareTerminalReadOnly,
+        StopSharingTerminal,
+        OpenSharedTerminals
+    ]
+);
 
 impl_actions!(terminal, [SendText, SendKeystroke]);
 
@@ -85,6 +96,7 @@ pub fn init(cx: &mut App) {
 
     cx.observe_new(|workspace: &mut Workspace, _window, _cx| {
         workspace.register_action(TerminalView::deploy);
+        workspace.register_action(TerminalView::open_shared_terminals);
     })
     .detach();
 }
@@ -166,6 +178,77 @@ impl TerminalView {
         .detach_and_log_err(cx);
     }
 
+    /// Opens the terminals shared by the host of a project joined over collab.
+    fn open_shared_terminals(
+        workspace: &mut Workspace,
+        _: &OpenSharedTerminals,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) {
+        let project = workspace.project().clone();
+        let shared_terminal_ids = project
+            .read(cx)
+            .shared_terminals()
+            .iter()
+            .map(|terminal| terminal.id)
+            .collect::<Vec<_>>();
+        if shared_terminal_ids.is_empty() {
+            struct NoSharedTerminalsToast;
+
+            workspace.show_toast(
+                Toast::new(
+                    NotificationId::unique::<NoSharedTerminalsToast>(),
+                    "The host isn't sharing any terminals",
+                ),
+                cx,
+            );
+            return;
+        }
+
+        let open_tasks = project.update(cx, |project, cx| {
+            shared_terminal_ids
+                .into_iter()
+                .map(|terminal_id| project.open_shared_terminal(terminal_id, cx))
+                .collect::<Vec<_>>()
+        });
+        cx.spawn_in(window, async move |workspace, cx| {
+            for open_task in open_tasks {
+                let Some(terminal) = open_task.await.log_err() else {
+                    continue;
+                };
+                workspace.update_in(cx, |workspace, window, cx| {
+                    let existing_view = workspace
+                        .items_of_type::<TerminalView>(cx)
+                        .find(|view| view.read(cx).terminal() == &terminal);
+                    if let Some(existing_view) = existing_view {
+                        workspace.activate_item(&existing_view, true, true, window, cx);
+                    } else {
+                        let terminal_view = cx.new(|cx| {
+                            TerminalView::new(
+                                terminal,
+                                workspace.weak_handle(),
+                                workspace.database_id(),
+                                workspace.project().downgrade(),
+                                false,
+                                window,
+                                cx,
+                            )
+                        });
+                        workspace.add_item_to_active_pane(
+                            Box::new(terminal_view),
+                            None,
+                            true,
+                            window,
+                            cx,
+                        );
+                    }
+                })?;
+            }
+            anyhow::Ok(())
+        })
+        .detach_and_log_err(cx);
+    }
+
     pub fn new(
         terminal: Entity<Terminal>,
         workspace: WeakEntity<Workspace>,
@@ -296,6 +379,7 @@ impl TerminalView {
             .map_or(false, |terminal_panel| {
                 terminal_panel.read(cx).assistant_enabled()
             });
+        let sharing_state = self.sharing_state(cx);
         let context_menu = ContextMenu::build(window, cx, |menu, _, _| {
             menu.context(self.focus_handle.clone())
                 .action("New Terminal", Box::new(NewTerminal))
@@ -308,6 +392,19 @@ impl TerminalView {
                     menu.separator()
                         .action("Inline Assist", Box::new(InlineAssist::default()))
                 })
+                .when_some(sharing_state, |menu, is_shared| {
+                    menu.separator().map(|menu| {
+                        if is_shared {
+                            menu.action("Stop Sharing Terminal", Box::new(StopSharingTerminal))
+                        } else {
+                            menu.action("Share Terminal", Box::new(ShareTerminal))
+                                .action(
+                                    "Share Terminal (Read-Only)",
+                                    Box::new(ShareTerminalReadOnly),
+                                )
+                        }
+                    })
+                })
                 .separator()
                 .action(
                     "Close Terminal Tab",
@@ -393,6 +490,55 @@ impl TerminalView {
         window.dispatch_action(Box::new(task), cx);
     }
 
+    fn share_terminal(&mut self, _: &ShareTerminal, _: &mut Window, cx: &mut Context<Self>) {
+        self.set_shared(false, cx);
+    }
+
+    fn share_terminal_read_only(
+        &mut self,
+        _: &ShareTerminalReadOnly,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.set_shared(true, cx);
+    }
+
+    fn set_shared(&mut self, read_only: bool, cx: &mut Context<Self>) {
+        let terminal = self.terminal.clone();
+        self.project
+            .update(cx, |project, cx| {
+                project.share_terminal(&terminal, read_only, cx).log_err();
+            })
+            .ok();
+    }
+
+    fn stop_sharing_terminal(
+        &mut self,
+        _: &StopSharingTerminal,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let terminal = self.terminal.clone();
+        self.project
+            .update(cx, |project, cx| {
+                project.stop_sharing_terminal(&terminal, cx);
+            })
+            .ok();
+    }
+
+    /// Whether the terminal is being shared, or `None` if it can't be shared.
+    fn sharing_state(&self, cx: &App) -> Option<bool> {
+        let project = self.project.upgrade()?;
+        let project = project.read(cx);
+        if !project.is_shared()
+            || project.is_via_collab()
+            || self.terminal.read(cx).is_shared_with_us()
+        {
+            return None;
+        }
+        Some(project.is_sharing_terminal(&self.terminal))
+    }
+
     fn clear(&mut self, _: &Clear, _: &mut Window, cx: &mut Context<Self>) {
         self.scroll_top = px(0.);
         self.terminal.update(cx, |term, _| term.clear());
@@ -525,6 +671,38 @@ impl TerminalView {
         cx.notify();
     }
 
+    fn scroll_to_previous_prompt(
+        &mut self,
+        _: &ScrollToPreviousPrompt,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.terminal
+            .update(cx, |term, _| term.scroll_to_previous_prompt());
+        cx.notify();
+    }
+
+    fn scroll_to_next_prompt(
+        &mut self,
+        _: &ScrollToNextPrompt,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.terminal
+            .update(cx, |term, _| term.scroll_to_next_prompt());
+        cx.notify();
+    }
+
+    fn copy_last_command_output(
+        &mut self,
+        _: &CopyLastCommandOutput,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.terminal
+            .update(cx, |term, cx| term.copy_last_command_output(cx));
+    }
+
     fn toggle_vi_mode(&mut self, _: &ToggleViMode, _: &mut Window, cx: &mut Context<Self>) {
         self.terminal.update(cx, |term, _| term.toggle_vi_mode());
         cx.notify();
@@ -869,10 +1047,30 @@ impl TerminalView {
                 .bottom_0()
                 .w(px(12.))
                 .cursor_default()
+                .children(self.render_failed_command_markers(cx))
                 .children(Scrollbar::vertical(self.scrollbar_state.clone())),
         )
     }
 
+    fn render_failed_command_markers(&self, cx: &App) -> Vec<Div> {
+        let terminal = self.terminal.read(cx);
+        let total_lines = terminal.total_lines().max(1) as f32;
+        let color = cx.theme().status().error;
+        terminal
+            .failed_command_lines()
+            .into_iter()
+            .map(|line| {
+                div()
+                    .absolute()
+                    .left_0()
+                    .right_0()
+                    .top(relative(line as f32 / total_lines))
+                    .h(px(2.))
+                    .bg(color)
+            })
+            .collect()
+    }
+
     fn rerun_button(task: &TaskState) -> Option<IconButton> {
         if !task.show_rerun {
             return None;
@@ -1419,10 +1617,16 @@ impl Render for TerminalView {
             .on_action(cx.listener(TerminalView::scroll_page_down))
             .on_action(cx.listener(TerminalView::scroll_to_top))
             .on_action(cx.listener(TerminalView::scroll_to_bottom))
+            .on_action(cx.listener(TerminalView::scroll_to_previous_prompt))
+            .on_action(cx.listener(TerminalView::scroll_to_next_prompt))
+            .on_action(cx.listener(TerminalView::copy_last_command_output))
             .on_action(cx.listener(TerminalView::toggle_vi_mode))
             .on_action(cx.listener(TerminalView::show_character_palette))
             .on_action(cx.listener(TerminalView::select_all))
             .on_action(cx.listener(TerminalView::rerun_task))
+            .on_action(cx.listener(TerminalView::share_terminal))
+            .on_action(cx.listener(TerminalView::share_terminal_read_only))
+            .on_action(cx.listener(TerminalView::stop_sharing_terminal))
             .on_key_down(cx.listener(Self::key_down))
             .on_mouse_down(
                 MouseButton::Right,
@@ -1483,7 +1687,12 @@ impl Item for TerminalView {
     fn tab_tooltip_content(&self, cx: &App) -> Option<TabTooltipContent> {
         let terminal = self.terminal().read(cx);
         let title = terminal.title(false);
-        let pid = terminal.pty_info.pid_getter().fallback_pid();
+        let Some(pid) = terminal
+            .pty_info()
+            .map(|info| info.pid_getter().fallback_pid())
+        else {
+            return Some(TabTooltipContent::Text(title.into()));
+        };
 
         Some(TabTooltipContent::Custom(Box::new(move |_window, cx| {
             cx.new(|_| TerminalTooltip::new(title.clone(), pid)).into()
@@ -1560,6 +1769,20 @@ impl Item for TerminalView {
         window: &mut Window,
         cx: &mut Context<Self>,
     ) -> Option<Entity<Self>> {
+        if self.terminal.read(cx).is_shared_with_us() {
+            return Some(cx.new(|cx| {
+                TerminalView::new(
+                    self.terminal.clone(),
+                    self.workspace.clone(),
+                    workspace_id,
+                    self.project.clone(),
+                    false,
+                    window,
+                    cx,
+                )
+            }));
+        }
+
         let window_handle = window.window_handle();
         let terminal = self
             .project
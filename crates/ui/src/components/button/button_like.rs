@@ -1,7 +1,7 @@
 use documented::Documented;
 use gpui::{
     AnyElement, AnyView, ClickEvent, CursorStyle, DefiniteLength, Hsla, MouseButton,
-    MouseDownEvent, MouseUpEvent, Rems, relative, transparent_black,
+    MouseDownEvent, MouseUpEvent, Rems, Role, relative, transparent_black,
 };
 use smallvec::SmallVec;
 
@@ -514,6 +514,15 @@ impl RenderOnce for ButtonLike {
         self.base
             .h_flex()
             .id(self.id.clone())
+            .role(Role::Button)
+            .accessibility(|properties| {
+                let properties = properties.disabled(self.disabled);
+                if self.selected {
+                    properties.selected(true)
+                } else {
+                    properties
+                }
+            })
             .font_ui(cx)
             .group("")
             .flex_none()
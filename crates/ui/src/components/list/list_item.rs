@@ -1,6 +1,6 @@
 use std::sync::Arc;
 
-use gpui::{AnyElement, AnyView, ClickEvent, MouseButton, MouseDownEvent, Pixels, px};
+use gpui::{AnyElement, AnyView, ClickEvent, MouseButton, MouseDownEvent, Pixels, Role, px};
 use smallvec::SmallVec;
 
 use crate::{Disclosure, prelude::*};
@@ -210,6 +210,14 @@ impl RenderOnce for ListItem {
     fn render(self, _window: &mut Window, cx: &mut App) -> impl IntoElement {
         h_flex()
             .id(self.id)
+            .role(Role::ListItem)
+            .accessibility(|properties| {
+                let properties = properties.selected(self.selected).disabled(self.disabled);
+                match self.toggle {
+                    Some(is_open) => properties.expanded(is_open),
+                    None => properties,
+                }
+            })
             .when_some(self.group_name, |this, group| this.group(group))
             .w_full()
             .relative()
@@ -1,5 +1,5 @@
 use gpui::{
-    AnyElement, AnyView, ElementId, Hsla, IntoElement, Styled, Window, div, hsla, prelude::*,
+    AnyElement, AnyView, ElementId, Hsla, IntoElement, Role, Styled, Window, div, hsla, prelude::*,
 };
 use std::sync::Arc;
 
@@ -221,6 +221,12 @@ impl RenderOnce for Checkbox {
 
         h_flex()
             .id(self.id)
+            .role(Role::CheckBox)
+            .accessibility(|properties| {
+                properties
+                    .toggled(self.toggle_state.into())
+                    .disabled(self.disabled)
+            })
             .gap(DynamicSpacing::Base06.rems(cx))
             .child(checkbox)
             .when_some(
@@ -514,6 +520,12 @@ impl RenderOnce for Switch {
 
         h_flex()
             .id(self.id)
+            .role(Role::Switch)
+            .accessibility(|properties| {
+                properties
+                    .toggled(self.toggle_state.into())
+                    .disabled(self.disabled)
+            })
             .gap(DynamicSpacing::Base06.rems(cx))
             .cursor_pointer()
             .child(switch)
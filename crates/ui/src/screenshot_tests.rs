@@ -0,0 +1,98 @@
+//! Pixel snapshot tests of components, rendered on the CPU and compared against the
+//! reference images in `screenshots/`. Run with `UPDATE_SNAPSHOTS=1` to update them after
+//! an intended change in appearance.
+
+use crate::{Checkbox, Switch, TintColor, prelude::*};
+use gpui::{AnyElement, Size, TestAppContext, assert_screenshot_matches, size};
+use settings::SettingsStore;
+use std::path::PathBuf;
+
+struct Preview(fn() -> AnyElement);
+
+impl Render for Preview {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        div()
+            .size_full()
+            .p_2()
+            .bg(cx.theme().colors().background)
+            .child((self.0)())
+    }
+}
+
+#[track_caller]
+fn assert_preview_matches(
+    name: &str,
+    window_size: Size<Pixels>,
+    preview: fn() -> AnyElement,
+    cx: &mut TestAppContext,
+) {
+    cx.update(|cx| {
+        let settings_store = SettingsStore::test(cx);
+        cx.set_global(settings_store);
+        theme::init(theme::LoadThemes::JustBase, cx);
+    });
+
+    let (_, cx) = cx.add_window_view(|_, _| Preview(preview));
+    cx.simulate_resize(window_size);
+    let image = cx.capture_screenshot();
+
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("screenshots")
+        .join(format!("{name}.png"));
+    assert_screenshot_matches(&image, path);
+}
+
+#[gpui::test]
+fn test_button_screenshot(cx: &mut TestAppContext) {
+    assert_preview_matches(
+        "buttons",
+        size(px(320.), px(40.)),
+        || {
+            h_flex()
+                .gap_2()
+                .child(Button::new("filled", "Filled").style(ButtonStyle::Filled))
+                .child(Button::new("subtle", "Subtle"))
+                .child(
+                    Button::new("tinted", "Tinted").style(ButtonStyle::Tinted(TintColor::Accent)),
+                )
+                .child(Button::new("disabled", "Disabled").disabled(true))
+                .into_any_element()
+        },
+        cx,
+    );
+}
+
+#[gpui::test]
+fn test_label_screenshot(cx: &mut TestAppContext) {
+    assert_preview_matches(
+        "labels",
+        size(px(240.), px(120.)),
+        || {
+            v_flex()
+                .gap_1()
+                .child(Label::new("Default"))
+                .child(Label::new("Muted").color(Color::Muted))
+                .child(Label::new("Large").size(LabelSize::Large))
+                .child(Label::new("Struck through").strikethrough())
+                .into_any_element()
+        },
+        cx,
+    );
+}
+
+#[gpui::test]
+fn test_toggle_screenshot(cx: &mut TestAppContext) {
+    assert_preview_matches(
+        "toggles",
+        size(px(160.), px(40.)),
+        || {
+            h_flex()
+                .gap_2()
+                .child(Switch::new("switch-on", ToggleState::Selected))
+                .child(Switch::new("switch-off", ToggleState::Unselected))
+                .child(Checkbox::new("checkbox", ToggleState::Unselected))
+                .into_any_element()
+        },
+        cx,
+    );
+}
@@ -48,6 +48,16 @@ impl ToggleState {
     }
 }
 
+impl From<ToggleState> for gpui::Toggled {
+    fn from(state: ToggleState) -> Self {
+        match state {
+            ToggleState::Unselected => Self::False,
+            ToggleState::Indeterminate => Self::Mixed,
+            ToggleState::Selected => Self::True,
+        }
+    }
+}
+
 impl From<bool> for ToggleState {
     fn from(selected: bool) -> Self {
         if selected {
@@ -10,6 +10,8 @@
 pub mod component_prelude;
 mod components;
 pub mod prelude;
+#[cfg(test)]
+mod screenshot_tests;
 mod styles;
 mod traits;
 pub mod utils;
@@ -7,7 +7,10 @@ use editor::{
     display_map::ToDisplayPoint,
     scroll::Autoscroll,
 };
-use gpui::{Action, App, AppContext as _, Context, Global, Window, actions, impl_internal_actions};
+use gpui::{
+    Action, App, AppContext as _, Context, Global, SharedString, Window, actions,
+    impl_internal_actions,
+};
 use itertools::Itertools;
 use language::Point;
 use multi_buffer::MultiBufferRow;
@@ -42,6 +45,7 @@ use crate::{
     },
     object::Object,
     state::{Mark, Mode},
+    vimrc::{self, RunUserCommand},
     visual::VisualDeleteLine,
 };
 
@@ -410,8 +414,8 @@ pub fn register(editor: &mut Editor, cx: &mut Context<Vim>) {
 
 #[derive(Default)]
 struct VimCommand {
-    prefix: &'static str,
-    suffix: &'static str,
+    prefix: SharedString,
+    suffix: SharedString,
     action: Option<Box<dyn Action>>,
     action_name: Option<&'static str>,
     bang_action: Option<Box<dyn Action>>,
@@ -432,8 +436,8 @@ struct VimCommand {
 impl VimCommand {
     fn new(pattern: (&'static str, &'static str), action: impl Action) -> Self {
         Self {
-            prefix: pattern.0,
-            suffix: pattern.1,
+            prefix: pattern.0.into(),
+            suffix: pattern.1.into(),
             action: Some(action.boxed_clone()),
             ..Default::default()
         }
@@ -442,8 +446,8 @@ impl VimCommand {
     // from_str is used for actions in other crates.
     fn str(pattern: (&'static str, &'static str), action_name: &'static str) -> Self {
         Self {
-            prefix: pattern.0,
-            suffix: pattern.1,
+            prefix: pattern.0.into(),
+            suffix: pattern.1.into(),
             action_name: Some(action_name),
             ..Default::default()
         }
@@ -483,7 +487,7 @@ impl VimCommand {
     ) -> Option<Box<dyn Action>> {
         let rest = query
             .to_string()
-            .strip_prefix(self.prefix)?
+            .strip_prefix(self.prefix.as_ref())?
             .to_string()
             .chars()
             .zip_longest(self.suffix.to_string().chars())
@@ -733,6 +737,18 @@ impl CommandRange {
         }
     }
 
+    /// Whether this is the implicit range of a command given without one,
+    /// which applies to the line of each cursor.
+    pub(crate) fn is_current_line(&self) -> bool {
+        matches!(
+            self,
+            CommandRange {
+                start: Position::CurrentLine { offset: 0 },
+                end: None,
+            }
+        )
+    }
+
     pub fn as_count(&self) -> Option<u32> {
         if let CommandRange {
             start: Position::Line { row, offset: 0 },
@@ -1025,6 +1041,35 @@ fn wrap_count(action: Box<dyn Action>, range: &CommandRange) -> Option<Box<dyn A
     })
 }
 
+/// Returns the ex commands defined with `:command`.
+fn user_commands(cx: &App) -> Vec<VimCommand> {
+    vimrc::user_commands(cx)
+        .iter()
+        .map(|user_command| {
+            let action = |bang| RunUserCommand {
+                name: user_command.name.clone(),
+                bang,
+                args: String::new(),
+            };
+            let command = VimCommand {
+                prefix: user_command.name.clone(),
+                action: Some(action(false).boxed_clone()),
+                ..Default::default()
+            }
+            .args(|action, args| {
+                let mut action = action.as_any().downcast_ref::<RunUserCommand>()?.clone();
+                action.args = args;
+                Some(action.boxed_clone())
+            });
+            if user_command.bang {
+                command.bang(action(true))
+            } else {
+                command
+            }
+        })
+        .collect()
+}
+
 pub fn command_interceptor(mut input: &str, cx: &App) -> Vec<CommandInterceptResult> {
     // NOTE: We also need to support passing arguments to commands like :w
     // (ideally with filename autocompletion).
@@ -1043,6 +1088,13 @@ pub fn command_interceptor(mut input: &str, cx: &App) -> Vec<CommandInterceptRes
             }
             .boxed_clone(),
         )
+    } else if let Some(action) = vimrc::definition_action(query) {
+        Some(action)
+    } else if let Some(action) = user_commands(cx)
+        .iter()
+        .find_map(|command| command.parse(query, &range, cx))
+    {
+        Some(action)
     } else if query.starts_with('/') || query.starts_with('?') {
         Some(
             FindCommand {
@@ -1122,7 +1174,8 @@ pub fn command_interceptor(mut input: &str, cx: &App) -> Vec<CommandInterceptRes
 
     for command in commands(cx).iter() {
         if let Some(action) = command.parse(query, &range, cx) {
-            let mut string = ":".to_owned() + &range_prefix + command.prefix + command.suffix;
+            let mut string =
+                ":".to_owned() + &range_prefix + command.prefix.as_ref() + command.suffix.as_ref();
             if query.contains('!') {
                 string.push('!');
             }
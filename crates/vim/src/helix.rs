@@ -1,16 +1,215 @@
-use editor::{DisplayPoint, Editor, movement, scroll::Autoscroll};
+mod regex_prompt;
+
+use std::ops::Range;
+use std::sync::Arc;
+
+use editor::{
+    Bias, DisplayPoint, Editor, MultiBufferSnapshot,
+    display_map::{DisplaySnapshot, ToDisplayPoint},
+    movement,
+    scroll::Autoscroll,
+};
 use gpui::{Action, actions};
 use gpui::{Context, Window};
-use language::{CharClassifier, CharKind};
+use itertools::Itertools;
+use language::{CharClassifier, CharKind, Point, Selection, SelectionGoal};
+use multi_buffer::MultiBufferRow;
+use regex_prompt::{RegexPrompt, RegexPromptKind};
 
 use crate::motion::MotionKind;
+use crate::object::Object;
+use crate::state::Operator;
+use crate::surrounds::surround_pair;
 use crate::{Vim, motion::Motion, state::Mode};
 
-actions!(vim, [HelixNormalAfter, HelixDelete]);
+actions!(
+    vim,
+    [
+        HelixNormalAfter,
+        HelixDelete,
+        HelixChange,
+        HelixYank,
+        ToggleHelixSelectMode,
+        HelixExtendToLineBounds,
+        HelixCollapseSelection,
+        HelixFlipSelections,
+        HelixKeepPrimarySelection,
+        HelixRemovePrimarySelection,
+        HelixRotateSelectionsForward,
+        HelixRotateSelectionsBackward,
+        HelixRotateSelectionContentsForward,
+        HelixRotateSelectionContentsBackward,
+        HelixSelectRegex,
+        HelixSplitSelection,
+        HelixKeepSelections,
+        HelixRemoveSelections,
+    ]
+);
 
 pub fn register(editor: &mut Editor, cx: &mut Context<Vim>) {
     Vim::action(editor, cx, Vim::helix_normal_after);
     Vim::action(editor, cx, Vim::helix_delete);
+    Vim::action(editor, cx, Vim::helix_change);
+    Vim::action(editor, cx, Vim::helix_yank);
+    Vim::action(editor, cx, Vim::helix_extend_to_line_bounds);
+    Vim::action(editor, cx, |vim, _: &ToggleHelixSelectMode, window, cx| {
+        let mode = if vim.mode == Mode::HelixSelect {
+            Mode::HelixNormal
+        } else {
+            Mode::HelixSelect
+        };
+        vim.switch_mode(mode, true, window, cx);
+    });
+    Vim::action(editor, cx, |vim, _: &HelixCollapseSelection, window, cx| {
+        vim.update_editor(window, cx, |_, editor, window, cx| {
+            editor.change_selections(Some(Autoscroll::fit()), window, cx, |s| {
+                s.move_with(|map, selection| {
+                    let cursor = helix_cursor(map, selection);
+                    selection.collapse_to(cursor, selection.goal);
+                })
+            });
+        });
+    });
+    Vim::action(editor, cx, |vim, _: &HelixFlipSelections, window, cx| {
+        vim.update_editor(window, cx, |_, editor, window, cx| {
+            editor.change_selections(Some(Autoscroll::fit()), window, cx, |s| {
+                s.move_with(|_, selection| {
+                    if !selection.is_empty() {
+                        selection.reversed = !selection.reversed;
+                    }
+                })
+            });
+        });
+    });
+    Vim::action(
+        editor,
+        cx,
+        |vim, _: &HelixKeepPrimarySelection, window, cx| {
+            vim.update_editor(window, cx, |_, editor, window, cx| {
+                editor.change_selections(Some(Autoscroll::fit()), window, cx, |s| {
+                    let primary = s.newest_anchor().clone();
+                    s.select_anchors(vec![primary]);
+                });
+            });
+        },
+    );
+    Vim::action(
+        editor,
+        cx,
+        |vim, _: &HelixRemovePrimarySelection, window, cx| {
+            vim.update_editor(window, cx, |_, editor, window, cx| {
+                editor.change_selections(Some(Autoscroll::fit()), window, cx, |s| {
+                    if s.count() > 1 {
+                        let primary = s.newest_anchor().id;
+                        s.delete(primary);
+                    }
+                });
+            });
+        },
+    );
+    Vim::action(
+        editor,
+        cx,
+        |vim, _: &HelixRotateSelectionsForward, window, cx| {
+            vim.helix_rotate_selections(true, window, cx)
+        },
+    );
+    Vim::action(
+        editor,
+        cx,
+        |vim, _: &HelixRotateSelectionsBackward, window, cx| {
+            vim.helix_rotate_selections(false, window, cx)
+        },
+    );
+    Vim::action(
+        editor,
+        cx,
+        |vim, _: &HelixRotateSelectionContentsForward, window, cx| {
+            vim.helix_rotate_selection_contents(true, window, cx)
+        },
+    );
+    Vim::action(
+        editor,
+        cx,
+        |vim, _: &HelixRotateSelectionContentsBackward, window, cx| {
+            vim.helix_rotate_selection_contents(false, window, cx)
+        },
+    );
+    Vim::action(editor, cx, |vim, _: &HelixSelectRegex, window, cx| {
+        vim.helix_regex_prompt(RegexPromptKind::Select, window, cx)
+    });
+    Vim::action(editor, cx, |vim, _: &HelixSplitSelection, window, cx| {
+        vim.helix_regex_prompt(RegexPromptKind::Split, window, cx)
+    });
+    Vim::action(editor, cx, |vim, _: &HelixKeepSelections, window, cx| {
+        vim.helix_regex_prompt(RegexPromptKind::Keep, window, cx)
+    });
+    Vim::action(editor, cx, |vim, _: &HelixRemoveSelections, window, cx| {
+        vim.helix_regex_prompt(RegexPromptKind::Remove, window, cx)
+    });
+}
+
+/// The character a Helix selection's cursor sits on. An empty selection is a
+/// block cursor over the character at its head.
+fn helix_cursor(map: &DisplaySnapshot, selection: &Selection<DisplayPoint>) -> DisplayPoint {
+    if selection.is_empty() || selection.reversed {
+        selection.head()
+    } else {
+        movement::left(map, selection.head())
+    }
+}
+
+/// The character at the opposite end of a Helix selection from its cursor.
+fn helix_anchor(map: &DisplaySnapshot, selection: &Selection<DisplayPoint>) -> DisplayPoint {
+    if selection.is_empty() {
+        selection.head()
+    } else if selection.reversed {
+        movement::left(map, selection.end)
+    } else {
+        selection.start
+    }
+}
+
+/// Selects the characters from `anchor` to `cursor`, both inclusive, with the
+/// cursor at the head.
+fn set_helix_selection(
+    map: &DisplaySnapshot,
+    selection: &mut Selection<DisplayPoint>,
+    anchor: DisplayPoint,
+    cursor: DisplayPoint,
+    goal: SelectionGoal,
+) {
+    if cursor >= anchor {
+        selection.start = anchor;
+        selection.end = movement::right(map, cursor);
+        selection.reversed = false;
+    } else {
+        selection.start = cursor;
+        selection.end = movement::right(map, anchor);
+        selection.reversed = true;
+    }
+    selection.goal = goal;
+}
+
+/// Widens a block cursor into the one character selection it stands for.
+fn select_cursor_char(map: &DisplaySnapshot, selection: &mut Selection<DisplayPoint>) {
+    if selection.is_empty() {
+        selection.end = movement::right(map, selection.end);
+        selection.reversed = false;
+    }
+}
+
+/// The buffer range covered by a Helix selection, counting a block cursor as
+/// the character under it.
+pub(crate) fn helix_range(
+    buffer: &MultiBufferSnapshot,
+    selection: &Selection<usize>,
+) -> Range<usize> {
+    if selection.is_empty() && selection.start < buffer.len() {
+        selection.start..buffer.clip_offset(selection.start + 1, Bias::Right)
+    } else {
+        selection.range()
+    }
 }
 
 impl Vim {
@@ -37,12 +236,20 @@ impl Vim {
         window: &mut Window,
         cx: &mut Context<Self>,
     ) {
-        self.helix_move_cursor(motion, times, window, cx);
+        let extend = self.mode == Mode::HelixSelect;
+        match motion {
+            // Finds select from the cursor to the target, even in normal mode.
+            Motion::FindForward { .. } | Motion::FindBackward { .. } => {
+                self.helix_select_motion(motion, times, extend, window, cx)
+            }
+            _ => self.helix_move_cursor(motion, times, extend, window, cx),
+        }
     }
 
     fn helix_find_range_forward(
         &mut self,
         times: Option<usize>,
+        extend: bool,
         window: &mut Window,
         cx: &mut Context<Self>,
         mut is_boundary: impl FnMut(char, char, &CharClassifier) -> bool,
@@ -51,6 +258,7 @@ impl Vim {
             editor.change_selections(Some(Autoscroll::fit()), window, cx, |s| {
                 s.move_with(|map, selection| {
                     let times = times.unwrap_or(1);
+                    let anchor = helix_anchor(map, selection);
 
                     if selection.head() == map.max_point() {
                         return;
@@ -88,6 +296,12 @@ impl Vim {
                         }
                         last_selection = selection.clone();
                     }
+
+                    if extend {
+                        let cursor = helix_cursor(map, selection);
+                        let goal = selection.goal;
+                        set_helix_selection(map, selection, anchor, cursor, goal);
+                    }
                 });
             });
         });
@@ -96,6 +310,7 @@ impl Vim {
     fn helix_find_range_backward(
         &mut self,
         times: Option<usize>,
+        extend: bool,
         window: &mut Window,
         cx: &mut Context<Self>,
         mut is_boundary: impl FnMut(char, char, &CharClassifier) -> bool,
@@ -104,6 +319,7 @@ impl Vim {
             editor.change_selections(Some(Autoscroll::fit()), window, cx, |s| {
                 s.move_with(|map, selection| {
                     let times = times.unwrap_or(1);
+                    let anchor = helix_anchor(map, selection);
 
                     if selection.head() == DisplayPoint::zero() {
                         return;
@@ -145,6 +361,12 @@ impl Vim {
                         }
                         last_selection = selection.clone();
                     }
+
+                    if extend {
+                        let cursor = helix_cursor(map, selection);
+                        let goal = selection.goal;
+                        set_helix_selection(map, selection, anchor, cursor, goal);
+                    }
                 });
             })
         });
@@ -162,11 +384,7 @@ impl Vim {
             editor.change_selections(Some(Autoscroll::fit()), window, cx, |s| {
                 s.move_with(|map, selection| {
                     let goal = selection.goal;
-                    let cursor = if selection.is_empty() || selection.reversed {
-                        selection.head()
-                    } else {
-                        movement::left(map, selection.head())
-                    };
+                    let cursor = helix_cursor(map, selection);
 
                     let (point, goal) = motion
                         .move_point(map, cursor, selection.goal, times, &text_layout_details)
@@ -178,16 +396,52 @@ impl Vim {
         });
     }
 
+    /// Moves each cursor with `motion` and selects from the selection's anchor
+    /// (when `keep_anchor` is set) or from the old cursor to the new one.
+    fn helix_select_motion(
+        &mut self,
+        motion: Motion,
+        times: Option<usize>,
+        keep_anchor: bool,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.update_editor(window, cx, |_, editor, window, cx| {
+            let text_layout_details = editor.text_layout_details(window);
+            editor.change_selections(Some(Autoscroll::fit()), window, cx, |s| {
+                s.move_with(|map, selection| {
+                    let cursor = helix_cursor(map, selection);
+                    let anchor = if keep_anchor {
+                        helix_anchor(map, selection)
+                    } else {
+                        cursor
+                    };
+
+                    if let Some((point, goal)) =
+                        motion.move_point(map, cursor, selection.goal, times, &text_layout_details)
+                    {
+                        set_helix_selection(map, selection, anchor, point, goal);
+                    }
+                })
+            });
+        });
+    }
+
     pub fn helix_move_cursor(
         &mut self,
         motion: Motion,
         times: Option<usize>,
+        extend: bool,
         window: &mut Window,
         cx: &mut Context<Self>,
     ) {
         match motion {
-            Motion::NextWordStart { ignore_punctuation } => {
-                self.helix_find_range_forward(times, window, cx, |left, right, classifier| {
+            Motion::NextWordStart { ignore_punctuation } => self.helix_find_range_forward(
+                times,
+                extend,
+                window,
+                cx,
+                |left, right, classifier| {
                     let left_kind = classifier.kind_with(left, ignore_punctuation);
                     let right_kind = classifier.kind_with(right, ignore_punctuation);
                     let at_newline = right == '\n';
@@ -196,10 +450,14 @@ impl Vim {
                         left_kind != right_kind && right_kind != CharKind::Whitespace || at_newline;
 
                     found
-                })
-            }
-            Motion::NextWordEnd { ignore_punctuation } => {
-                self.helix_find_range_forward(times, window, cx, |left, right, classifier| {
+                },
+            ),
+            Motion::NextWordEnd { ignore_punctuation } => self.helix_find_range_forward(
+                times,
+                extend,
+                window,
+                cx,
+                |left, right, classifier| {
                     let left_kind = classifier.kind_with(left, ignore_punctuation);
                     let right_kind = classifier.kind_with(right, ignore_punctuation);
                     let at_newline = right == '\n';
@@ -208,10 +466,14 @@ impl Vim {
                         && (left_kind != CharKind::Whitespace || at_newline);
 
                     found
-                })
-            }
-            Motion::PreviousWordStart { ignore_punctuation } => {
-                self.helix_find_range_backward(times, window, cx, |left, right, classifier| {
+                },
+            ),
+            Motion::PreviousWordStart { ignore_punctuation } => self.helix_find_range_backward(
+                times,
+                extend,
+                window,
+                cx,
+                |left, right, classifier| {
                     let left_kind = classifier.kind_with(left, ignore_punctuation);
                     let right_kind = classifier.kind_with(right, ignore_punctuation);
                     let at_newline = right == '\n';
@@ -220,10 +482,14 @@ impl Vim {
                         && (left_kind != CharKind::Whitespace || at_newline);
 
                     found
-                })
-            }
-            Motion::PreviousWordEnd { ignore_punctuation } => {
-                self.helix_find_range_backward(times, window, cx, |left, right, classifier| {
+                },
+            ),
+            Motion::PreviousWordEnd { ignore_punctuation } => self.helix_find_range_backward(
+                times,
+                extend,
+                window,
+                cx,
+                |left, right, classifier| {
                     let left_kind = classifier.kind_with(left, ignore_punctuation);
                     let right_kind = classifier.kind_with(right, ignore_punctuation);
                     let at_newline = right == '\n';
@@ -233,25 +499,280 @@ impl Vim {
                         && !at_newline;
 
                     found
+                },
+            ),
+            _ if extend => self.helix_select_motion(motion, times, true, window, cx),
+            _ => self.helix_move_and_collapse(motion, times, window, cx),
+        }
+    }
+
+    fn helix_extend_to_line_bounds(
+        &mut self,
+        _: &HelixExtendToLineBounds,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.update_editor(window, cx, |_, editor, window, cx| {
+            editor.change_selections(Some(Autoscroll::fit()), window, cx, |s| {
+                s.move_with(|map, selection| {
+                    let last = if selection.is_empty() {
+                        selection.start
+                    } else {
+                        movement::left(map, selection.end)
+                    };
+                    let start_row = selection.start.to_point(map).row;
+                    let end_row = last.to_point(map).row;
+                    let end_column = map.buffer_snapshot.line_len(MultiBufferRow(end_row));
+
+                    selection.start = Point::new(start_row, 0).to_display_point(map);
+                    selection.end =
+                        movement::right(map, Point::new(end_row, end_column).to_display_point(map));
+                    selection.goal = SelectionGoal::None;
                 })
+            });
+        });
+    }
+
+    /// Makes the next (or previous) selection in the buffer the primary one.
+    fn helix_rotate_selections(
+        &mut self,
+        forward: bool,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.update_editor(window, cx, |_, editor, window, cx| {
+            let mut selections = editor.selections.all::<usize>(cx);
+            let Some(primary_ix) = selections.iter().position_max_by_key(|s| s.id) else {
+                return;
+            };
+            let len = selections.len();
+            let next_ix = if forward {
+                (primary_ix + 1) % len
+            } else {
+                (primary_ix + len - 1) % len
+            };
+            editor.change_selections(Some(Autoscroll::fit()), window, cx, |s| {
+                // The newest selection is the primary one.
+                selections[next_ix].id = s.new_selection_id();
+                s.select(selections);
+            });
+        });
+    }
+
+    /// Moves the text of each selection into the next (or previous) selection.
+    fn helix_rotate_selection_contents(
+        &mut self,
+        forward: bool,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.update_editor(window, cx, |_, editor, window, cx| {
+            editor.transact(window, cx, |editor, window, cx| {
+                let buffer = editor.buffer().read(cx).snapshot(cx);
+                let selections = editor.selections.all::<usize>(cx);
+                let ranges = selections
+                    .iter()
+                    .map(|selection| helix_range(&buffer, selection))
+                    .collect::<Vec<_>>();
+                let mut contents = ranges
+                    .iter()
+                    .map(|range| buffer.text_for_range(range.clone()).collect::<String>())
+                    .collect::<Vec<_>>();
+                if forward {
+                    contents.rotate_right(1);
+                } else {
+                    contents.rotate_left(1);
+                }
+
+                let mut new_selections = Vec::with_capacity(selections.len());
+                let mut delta = 0isize;
+                for ((selection, range), text) in selections.iter().zip(&ranges).zip(&contents) {
+                    let start = (range.start as isize + delta) as usize;
+                    delta += text.len() as isize - range.len() as isize;
+                    new_selections.push(Selection {
+                        id: selection.id,
+                        start,
+                        end: start + text.len(),
+                        reversed: selection.reversed,
+                        goal: SelectionGoal::None,
+                    });
+                }
+
+                editor.edit(ranges.into_iter().zip(contents), cx);
+                editor.change_selections(Some(Autoscroll::fit()), window, cx, |s| {
+                    s.select(new_selections)
+                });
+            });
+        });
+    }
+
+    fn helix_regex_prompt(
+        &mut self,
+        kind: RegexPromptKind,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(editor) = self.editor() else {
+            return;
+        };
+        let Some(workspace) = self.workspace(window) else {
+            return;
+        };
+        workspace.update(cx, |workspace, cx| {
+            workspace.toggle_modal(window, cx, |window, cx| {
+                RegexPrompt::new(kind, editor, window, cx)
+            });
+        });
+    }
+
+    pub(crate) fn helix_object(
+        &mut self,
+        object: Object,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        match self.maybe_pop_operator() {
+            Some(Operator::Object { around }) => {
+                self.update_editor(window, cx, |_, editor, window, cx| {
+                    editor.change_selections(Some(Autoscroll::fit()), window, cx, |s| {
+                        s.move_with(|map, selection| {
+                            let mut cursor = selection.clone();
+                            cursor.collapse_to(helix_cursor(map, selection), selection.goal);
+                            if let Some(range) = object.range(map, cursor, around) {
+                                selection.start = range.start;
+                                selection.end = range.end;
+                                selection.reversed = false;
+                                selection.goal = SelectionGoal::None;
+                            }
+                        })
+                    });
+                });
+                self.clear_operator(window, cx);
             }
-            _ => self.helix_move_and_collapse(motion, times, window, cx),
+            Some(Operator::ChangeSurrounds { target: None }) => {
+                self.clear_operator(window, cx);
+                self.push_operator(
+                    Operator::ChangeSurrounds {
+                        target: Some(object),
+                    },
+                    window,
+                    cx,
+                );
+            }
+            _ => self.clear_operator(window, cx),
         }
     }
 
+    /// Surrounds every selection with the pair for `text` and selects the
+    /// result, pair included.
+    pub(crate) fn helix_add_surrounds(
+        &mut self,
+        text: Arc<str>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.stop_recording(cx);
+        let pair = surround_pair(&text);
+        self.update_editor(window, cx, |_, editor, window, cx| {
+            editor.transact(window, cx, |editor, window, cx| {
+                let buffer = editor.buffer().read(cx).snapshot(cx);
+                let mut edits = Vec::new();
+                let mut ranges = Vec::new();
+                for selection in editor.selections.all::<usize>(cx) {
+                    let range = helix_range(&buffer, &selection);
+                    edits.push((range.start..range.start, pair.start.clone()));
+                    edits.push((range.end..range.end, pair.end.clone()));
+                    ranges.push(buffer.anchor_before(range.start)..buffer.anchor_after(range.end));
+                }
+                editor.edit(edits, cx);
+                editor.change_selections(Some(Autoscroll::fit()), window, cx, |s| {
+                    s.select_anchor_ranges(ranges)
+                });
+            });
+        });
+    }
+
+    pub(crate) fn helix_delete_surrounds(
+        &mut self,
+        text: Arc<str>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        // Helix never pads surrounds, so always pass the closing character on.
+        let text: Arc<str> = surround_pair(&text).end.into();
+        self.helix_surround_at_cursors(window, cx, |vim, window, cx| {
+            vim.delete_surrounds(text, window, cx)
+        });
+    }
+
+    pub(crate) fn helix_change_surrounds(
+        &mut self,
+        text: Arc<str>,
+        target: Object,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let text: Arc<str> = surround_pair(&text).end.into();
+        self.helix_surround_at_cursors(window, cx, |vim, window, cx| {
+            vim.change_surrounds(text, target, window, cx)
+        });
+    }
+
+    /// Runs a vim surround edit from each selection's cursor, then restores the
+    /// selections it started with.
+    fn helix_surround_at_cursors(
+        &mut self,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+        edit: impl FnOnce(&mut Self, &mut Window, &mut Context<Self>),
+    ) {
+        let Some(selections) = self.update_editor(window, cx, |_, editor, window, cx| {
+            let selections = editor.selections.disjoint_anchors();
+            editor.change_selections(None, window, cx, |s| {
+                s.move_with(|map, selection| {
+                    let cursor = helix_cursor(map, selection);
+                    selection.collapse_to(cursor, selection.goal);
+                })
+            });
+            selections
+        }) else {
+            return;
+        };
+        edit(self, window, cx);
+        self.update_editor(window, cx, |_, editor, window, cx| {
+            editor.change_selections(Some(Autoscroll::fit()), window, cx, |s| {
+                s.select_anchors(selections.to_vec())
+            });
+        });
+    }
+
+    pub fn helix_yank(&mut self, _: &HelixYank, window: &mut Window, cx: &mut Context<Self>) {
+        self.update_editor(window, cx, |vim, editor, window, cx| {
+            editor.change_selections(None, window, cx, |s| s.move_with(select_cursor_char));
+            vim.copy_selections_content(editor, MotionKind::Exclusive, window, cx);
+        });
+    }
+
     pub fn helix_delete(&mut self, _: &HelixDelete, window: &mut Window, cx: &mut Context<Self>) {
+        self.helix_delete_selections(window, cx);
+        if self.mode == Mode::HelixSelect {
+            self.switch_mode(Mode::HelixNormal, true, window, cx);
+        }
+    }
+
+    pub fn helix_change(&mut self, _: &HelixChange, window: &mut Window, cx: &mut Context<Self>) {
+        self.helix_delete_selections(window, cx);
+        self.switch_mode(Mode::Insert, true, window, cx);
+    }
+
+    fn helix_delete_selections(&mut self, window: &mut Window, cx: &mut Context<Self>) {
         self.store_visual_marks(window, cx);
         self.update_editor(window, cx, |vim, editor, window, cx| {
-            // Fixup selections so they have helix's semantics.
-            // Specifically:
-            //  - Make sure that each cursor acts as a 1 character wide selection
+            // Make sure that each cursor acts as a 1 character wide selection,
+            // as it does in helix.
             editor.transact(window, cx, |editor, window, cx| {
                 editor.change_selections(Some(Autoscroll::fit()), window, cx, |s| {
-                    s.move_with(|map, selection| {
-                        if selection.is_empty() && !selection.reversed {
-                            selection.end = movement::right(map, selection.end);
-                        }
-                    });
+                    s.move_with(select_cursor_char);
                 });
             });
 
@@ -263,9 +784,15 @@ impl Vim {
 
 #[cfg(test)]
 mod test {
+    use std::{cell::RefCell, rc::Rc};
+
+    use command_palette::CommandPalette;
     use indoc::indoc;
 
-    use crate::{state::Mode, test::VimTestContext};
+    use crate::{
+        state::Mode,
+        test::{HelixTestContext, VimTestContext},
+    };
 
     #[gpui::test]
     async fn test_next_word_start(cx: &mut gpui::TestAppContext) {
@@ -302,83 +829,241 @@ mod test {
         );
     }
 
-    // #[gpui::test]
-    // async fn test_delete(cx: &mut gpui::TestAppContext) {
-    //     let mut cx = VimTestContext::new(cx, true).await;
-
-    //     // test delete a selection
-    //     cx.set_state(
-    //         indoc! {"
-    //         The qu«ick ˇ»brown
-    //         fox jumps over
-    //         the lazy dog."},
-    //         Mode::HelixNormal,
-    //     );
-
-    //     cx.simulate_keystrokes("d");
-
-    //     cx.assert_state(
-    //         indoc! {"
-    //         The quˇbrown
-    //         fox jumps over
-    //         the lazy dog."},
-    //         Mode::HelixNormal,
-    //     );
-
-    //     // test deleting a single character
-    //     cx.simulate_keystrokes("d");
-
-    //     cx.assert_state(
-    //         indoc! {"
-    //         The quˇrown
-    //         fox jumps over
-    //         the lazy dog."},
-    //         Mode::HelixNormal,
-    //     );
-    // }
-
-    // #[gpui::test]
-    // async fn test_delete_character_end_of_line(cx: &mut gpui::TestAppContext) {
-    //     let mut cx = VimTestContext::new(cx, true).await;
-
-    //     cx.set_state(
-    //         indoc! {"
-    //         The quick brownˇ
-    //         fox jumps over
-    //         the lazy dog."},
-    //         Mode::HelixNormal,
-    //     );
-
-    //     cx.simulate_keystrokes("d");
-
-    //     cx.assert_state(
-    //         indoc! {"
-    //         The quick brownˇfox jumps over
-    //         the lazy dog."},
-    //         Mode::HelixNormal,
-    //     );
-    // }
-
-    // #[gpui::test]
-    // async fn test_delete_character_end_of_buffer(cx: &mut gpui::TestAppContext) {
-    //     let mut cx = VimTestContext::new(cx, true).await;
-
-    //     cx.set_state(
-    //         indoc! {"
-    //         The quick brown
-    //         fox jumps over
-    //         the lazy dog.ˇ"},
-    //         Mode::HelixNormal,
-    //     );
-
-    //     cx.simulate_keystrokes("d");
-
-    //     cx.assert_state(
-    //         indoc! {"
-    //         The quick brown
-    //         fox jumps over
-    //         the lazy dog.ˇ"},
-    //         Mode::HelixNormal,
-    //     );
-    // }
+    #[gpui::test]
+    async fn test_delete(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        // test delete a selection
+        cx.set_state(
+            indoc! {"
+            The qu«ick ˇ»brown
+            fox jumps over
+            the lazy dog."},
+            Mode::HelixNormal,
+        );
+
+        cx.simulate_keystrokes("d");
+
+        cx.assert_state(
+            indoc! {"
+            The quˇbrown
+            fox jumps over
+            the lazy dog."},
+            Mode::HelixNormal,
+        );
+
+        // test deleting a single character
+        cx.simulate_keystrokes("d");
+
+        cx.assert_state(
+            indoc! {"
+            The quˇrown
+            fox jumps over
+            the lazy dog."},
+            Mode::HelixNormal,
+        );
+    }
+
+    #[gpui::test]
+    async fn test_delete_character_end_of_line(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state(
+            indoc! {"
+            The quick brownˇ
+            fox jumps over
+            the lazy dog."},
+            Mode::HelixNormal,
+        );
+
+        cx.simulate_keystrokes("d");
+
+        cx.assert_state(
+            indoc! {"
+            The quick brownˇfox jumps over
+            the lazy dog."},
+            Mode::HelixNormal,
+        );
+    }
+
+    #[gpui::test]
+    async fn test_delete_character_end_of_buffer(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state(
+            indoc! {"
+            The quick brown
+            fox jumps over
+            the lazy dog.ˇ"},
+            Mode::HelixNormal,
+        );
+
+        cx.simulate_keystrokes("d");
+
+        cx.assert_state(
+            indoc! {"
+            The quick brown
+            fox jumps over
+            the lazy dog.ˇ"},
+            Mode::HelixNormal,
+        );
+    }
+
+    #[gpui::test]
+    async fn test_select_mode(cx: &mut gpui::TestAppContext) {
+        let mut cx = HelixTestContext::new(cx).await;
+
+        cx.assert_helix("#[T|]#he quick brown", "vww", "#[The quick |]#brown");
+        assert_eq!(cx.mode(), Mode::HelixSelect);
+        cx.simulate_helix_keys("<esc>");
+        cx.assert_helix_state("#[The quick |]#brown");
+        assert_eq!(cx.mode(), Mode::HelixNormal);
+
+        cx.assert_helix("The quick #[b|]#rown", "vbb", "#[|The quick b]#rown");
+        cx.simulate_helix_keys("v");
+        assert_eq!(cx.mode(), Mode::HelixNormal);
+    }
+
+    #[gpui::test]
+    async fn test_find_selects(cx: &mut gpui::TestAppContext) {
+        let mut cx = HelixTestContext::new(cx).await;
+
+        cx.assert_helix("#[T|]#he quick brown", "fq", "#[The q|]#uick brown");
+        cx.assert_helix("#[T|]#he quick brown", "tq", "#[The |]#quick brown");
+        cx.assert_helix("The quick #[b|]#rown", "Fq", "The #[|quick b]#rown");
+    }
+
+    #[gpui::test]
+    async fn test_line_selection(cx: &mut gpui::TestAppContext) {
+        let mut cx = HelixTestContext::new(cx).await;
+
+        cx.assert_helix("#[h|]#ello\nworld", "x", "#[hello\n|]#world");
+        cx.assert_helix("#[h|]#ello\nworld", "xx", "#[hello\nworld|]#");
+        cx.assert_helix("he#[llo\nwo|]#rld\n!", "X", "#[hello\nworld\n|]#!");
+        cx.assert_helix("hel#[l|]#o\nworld", "XX", "#[hello\n|]#world");
+    }
+
+    #[gpui::test]
+    async fn test_selection_manipulation(cx: &mut gpui::TestAppContext) {
+        let mut cx = HelixTestContext::new(cx).await;
+
+        cx.assert_helix("#[The |]#quick", ";", "The#[ |]#quick");
+        cx.assert_helix("#[The |]#quick", "<A-;>", "#[|The ]#quick");
+        cx.assert_helix("#(a|)# #[b|]# #(c|)#", ",", "a #[b|]# c");
+        cx.assert_helix("#(a|)# #[b|]# #(c|)#", "<A-,>", "#(a|)# b #[c|]#");
+        cx.assert_helix("#[a|]# #(b|)# #(c|)#", ")", "#(a|)# #[b|]# #(c|)#");
+        cx.assert_helix("#[a|]# #(b|)# #(c|)#", "(", "#(a|)# #(b|)# #[c|]#");
+        cx.assert_helix("#[a|]# #(bb|)# #(c|)#", "<A-)>", "#[c|]# #(a|)# #(bb|)#");
+        cx.assert_helix("#[a|]# #(bb|)# #(c|)#", "<A-(>", "#[bb|]# #(c|)# #(a|)#");
+    }
+
+    #[gpui::test]
+    async fn test_regex_selections(cx: &mut gpui::TestAppContext) {
+        let mut cx = HelixTestContext::new(cx).await;
+
+        cx.assert_helix("#[one two three|]#", "st<ret>", "one #[t|]#wo #(t|)#hree");
+        cx.assert_helix(
+            "#[one two three|]#",
+            "S<space><ret>",
+            "#[one|]# #(two|)# #(three|)#",
+        );
+        cx.assert_helix(
+            "#[one|]# #(two|)# #(three|)#",
+            "Ko<ret>",
+            "#[one|]# #(two|)# three",
+        );
+        cx.assert_helix(
+            "#[one|]# #(two|)# #(three|)#",
+            "<A-K>o<ret>",
+            "one two #[three|]#",
+        );
+
+        // Dismissing the prompt restores the selections.
+        cx.assert_helix("#[one two three|]#", "st<esc>", "#[one two three|]#");
+        // As does a regex without matches.
+        cx.assert_helix("#[one two three|]#", "sx<ret>", "#[one two three|]#");
+    }
+
+    #[gpui::test]
+    async fn test_match_mode(cx: &mut gpui::TestAppContext) {
+        let mut cx = HelixTestContext::new(cx).await;
+
+        cx.assert_helix("(h#[e|]#llo)", "mi(", "(#[hello|]#)");
+        cx.assert_helix("(h#[e|]#llo)", "ma(", "#[(hello)|]#");
+        cx.assert_helix("#[hello|]# world", "ms(", "#[(hello)|]# world");
+        cx.assert_helix("#[hello|]# world", "ms*", "#[*hello*|]# world");
+        cx.assert_helix("(#[hello|]#)", "md(", "#[hello|]#");
+        cx.assert_helix("(#[hello|]#)", "mr([", "[#[hello|]#]");
+        assert_eq!(cx.mode(), Mode::HelixNormal);
+    }
+
+    #[gpui::test]
+    async fn test_delete_and_change(cx: &mut gpui::TestAppContext) {
+        let mut cx = HelixTestContext::new(cx).await;
+
+        cx.assert_helix("#[hello |]#world", "d", "#[w|]#orld");
+        cx.assert_helix("v#[i|]#ew", "vld", "v#[w|]#");
+        assert_eq!(cx.mode(), Mode::HelixNormal);
+
+        cx.assert_helix("#[hello|]# world", "cbye", "bye#[ |]#world");
+        assert_eq!(cx.mode(), Mode::Insert);
+
+        // `c` and `xc` substitute a character and a line, like `s` and `S` in Vim.
+        cx.assert_helix("#[h|]#ello", "cj<esc>", "#[j|]#ello");
+        cx.assert_helix(
+            "one\n#[t|]#wo\nthree",
+            "xcnew<ret><esc>",
+            "one\nnew\n#[t|]#hree",
+        );
+    }
+
+    #[gpui::test]
+    async fn test_space_mode(cx: &mut gpui::TestAppContext) {
+        let mut cx = HelixTestContext::new(cx).await;
+        // Handle the pickers whose crates aren't part of these tests.
+        cx.update_workspace(|workspace, _, cx| {
+            workspace
+                .register_action(|_, _: &workspace::ToggleFileFinder, _, _| {})
+                .register_action(|_, _: &workspace::ToggleTabSwitcher, _, _| {})
+                .register_action(|_, _: &workspace::ToggleProjectSymbols, _, _| {})
+                .register_action(|_, _: &workspace::DeployDiagnostics, _, _| {});
+            cx.notify();
+        });
+        let dispatched = Rc::new(RefCell::new(Vec::new()));
+        cx.update(|_, cx| {
+            let dispatched = dispatched.clone();
+            cx.observe_keystrokes(move |event, _, _| {
+                if let Some(action) = &event.action {
+                    dispatched.borrow_mut().push(action.name().to_string());
+                }
+            })
+            .detach();
+        });
+
+        for (keys, action) in [
+            ("space f", "file_finder::Toggle"),
+            ("space b", "tab_switcher::Toggle"),
+            ("space shift-s", "project_symbols::Toggle"),
+            ("space shift-d", "diagnostics::Deploy"),
+        ] {
+            cx.simulate_keystrokes(keys);
+            assert_eq!(dispatched.borrow_mut().pop().as_deref(), Some(action));
+        }
+
+        cx.simulate_keystrokes("space ?");
+        assert_eq!(
+            dispatched.borrow_mut().pop().as_deref(),
+            Some("command_palette::Toggle")
+        );
+        cx.workspace(|workspace, _, cx| {
+            assert!(workspace.active_modal::<CommandPalette>(cx).is_some());
+        });
+        cx.simulate_keystrokes("escape");
+
+        cx.simulate_keystrokes("space /");
+        assert_eq!(
+            dispatched.borrow_mut().pop().as_deref(),
+            Some("pane::DeploySearch")
+        );
+    }
 }
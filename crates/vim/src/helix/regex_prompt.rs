@@ -0,0 +1,252 @@
+use std::sync::Arc;
+
+use editor::{Anchor, Editor, EditorEvent, MultiBufferSnapshot, ToOffset, scroll::Autoscroll};
+use gpui::{
+    App, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable, Render, SharedString,
+    Subscription, Window,
+};
+use language::{Selection, SelectionGoal};
+use regex::{Regex, RegexBuilder};
+use ui::prelude::*;
+use workspace::ModalView;
+
+use super::helix_range;
+
+/// What the regex typed into a [`RegexPrompt`] does to the selections.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum RegexPromptKind {
+    /// `s`: select every match inside the selections.
+    Select,
+    /// `S`: split the selections on every match.
+    Split,
+    /// `K`: keep the selections that contain a match.
+    Keep,
+    /// `alt-K`: remove the selections that contain a match.
+    Remove,
+}
+
+impl RegexPromptKind {
+    fn label(&self) -> &'static str {
+        match self {
+            RegexPromptKind::Select => "select:",
+            RegexPromptKind::Split => "split:",
+            RegexPromptKind::Keep => "keep:",
+            RegexPromptKind::Remove => "remove:",
+        }
+    }
+
+    /// Applies `regex` to `selections`. New selections are returned in buffer
+    /// order with an `id` of zero; kept selections retain their own.
+    fn apply(
+        &self,
+        regex: &Regex,
+        selections: &[Selection<usize>],
+        buffer: &MultiBufferSnapshot,
+    ) -> Vec<Selection<usize>> {
+        let mut result = Vec::new();
+        for selection in selections {
+            let range = helix_range(buffer, selection);
+            let text = buffer.text_for_range(range.clone()).collect::<String>();
+            let new_selection = |start: usize, end: usize| Selection {
+                id: 0,
+                start: range.start + start,
+                end: range.start + end,
+                reversed: false,
+                goal: SelectionGoal::None,
+            };
+            match self {
+                RegexPromptKind::Select => result.extend(
+                    regex
+                        .find_iter(&text)
+                        .filter(|found| found.start() < found.end())
+                        .map(|found| new_selection(found.start(), found.end())),
+                ),
+                RegexPromptKind::Split => {
+                    let mut start = 0;
+                    for found in regex
+                        .find_iter(&text)
+                        .filter(|found| found.start() < found.end())
+                    {
+                        result.push(new_selection(start, found.start()));
+                        start = found.end();
+                    }
+                    result.push(new_selection(start, text.len()));
+                }
+                RegexPromptKind::Keep | RegexPromptKind::Remove => {
+                    if regex.is_match(&text) == (*self == RegexPromptKind::Keep) {
+                        result.push(selection.clone());
+                    }
+                }
+            }
+        }
+        result
+    }
+}
+
+/// The prompt behind Helix's `s`, `S`, `K` and `alt-K`. Selections update
+/// as the regex is typed and are restored if the prompt is dismissed.
+pub(crate) struct RegexPrompt {
+    kind: RegexPromptKind,
+    query_editor: Entity<Editor>,
+    target: Entity<Editor>,
+    original_selections: Arc<[Selection<Anchor>]>,
+    error: Option<SharedString>,
+    confirmed: bool,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl ModalView for RegexPrompt {}
+
+impl Focusable for RegexPrompt {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.query_editor.focus_handle(cx)
+    }
+}
+
+impl EventEmitter<DismissEvent> for RegexPrompt {}
+
+impl RegexPrompt {
+    pub(crate) fn new(
+        kind: RegexPromptKind,
+        target: Entity<Editor>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let original_selections = target.read(cx).selections.disjoint_anchors();
+        let query_editor = cx.new(|cx| Editor::single_line(window, cx));
+        let query_editor_change = cx.subscribe_in(&query_editor, window, Self::on_query_event);
+
+        Self {
+            kind,
+            query_editor,
+            target,
+            original_selections,
+            error: None,
+            confirmed: false,
+            _subscriptions: vec![query_editor_change, cx.on_release_in(window, Self::release)],
+        }
+    }
+
+    fn release(&mut self, window: &mut Window, cx: &mut App) {
+        if self.confirmed {
+            return;
+        }
+        let selections = self.original_selections.to_vec();
+        self.target.update(cx, |editor, cx| {
+            editor.change_selections(Some(Autoscroll::fit()), window, cx, |s| {
+                s.select_anchors(selections)
+            });
+        });
+    }
+
+    fn on_query_event(
+        &mut self,
+        _: &Entity<Editor>,
+        event: &EditorEvent,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        match event {
+            EditorEvent::Blurred => cx.emit(DismissEvent),
+            EditorEvent::BufferEdited { .. } => self.update_selections(window, cx),
+            _ => {}
+        }
+    }
+
+    fn regex(&mut self, cx: &App) -> Option<Regex> {
+        let query = self.query_editor.read(cx).text(cx);
+        self.error = None;
+        if query.is_empty() {
+            return None;
+        }
+        // Like Helix, searches are case-insensitive unless they contain an
+        // uppercase character.
+        let case_insensitive = !query.chars().any(char::is_uppercase);
+        match RegexBuilder::new(&query)
+            .case_insensitive(case_insensitive)
+            .build()
+        {
+            Ok(regex) => Some(regex),
+            Err(error) => {
+                self.error = Some(error.to_string().into());
+                None
+            }
+        }
+    }
+
+    fn update_selections(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let regex = self.regex(cx);
+        let kind = self.kind;
+        let original_selections = self.original_selections.clone();
+        self.target.update(cx, |editor, cx| {
+            let buffer = editor.buffer().read(cx).snapshot(cx);
+            let mut selections = regex
+                .map(|regex| {
+                    let original = original_selections
+                        .iter()
+                        .map(|selection| selection.map(|anchor| anchor.to_offset(&buffer)))
+                        .collect::<Vec<_>>();
+                    kind.apply(&regex, &original, &buffer)
+                })
+                .unwrap_or_default();
+
+            editor.change_selections(Some(Autoscroll::fit()), window, cx, |s| {
+                if selections.is_empty() {
+                    s.select_anchors(original_selections.to_vec());
+                    return;
+                }
+                // The newest selection is the primary one, and Helix makes the
+                // first new selection primary.
+                if matches!(kind, RegexPromptKind::Select | RegexPromptKind::Split) {
+                    for selection in selections.iter_mut().rev() {
+                        selection.id = s.new_selection_id();
+                    }
+                }
+                s.select(selections);
+            });
+        });
+        cx.notify();
+    }
+
+    fn cancel(&mut self, _: &menu::Cancel, _: &mut Window, cx: &mut Context<Self>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn confirm(&mut self, _: &menu::Confirm, window: &mut Window, cx: &mut Context<Self>) {
+        self.update_selections(window, cx);
+        self.confirmed = true;
+        self.target.update(cx, |editor, cx| {
+            editor.focus_handle(cx).focus(window);
+        });
+        cx.emit(DismissEvent);
+    }
+}
+
+impl Render for RegexPrompt {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .w(rems(34.))
+            .elevation_2(cx)
+            .key_context("HelixRegexPrompt")
+            .on_action(cx.listener(Self::cancel))
+            .on_action(cx.listener(Self::confirm))
+            .child(
+                h_flex()
+                    .px_2()
+                    .py_1()
+                    .gap_2()
+                    .child(Label::new(self.kind.label()).color(Color::Muted))
+                    .child(self.query_editor.clone()),
+            )
+            .when_some(self.error.clone(), |this, error| {
+                this.child(
+                    div()
+                        .border_t_1()
+                        .border_color(cx.theme().colors().border_variant)
+                        .px_2()
+                        .py_1()
+                        .child(Label::new(error).color(Color::Error)),
+                )
+            })
+    }
+}
@@ -653,7 +653,7 @@ impl Vim {
                     }
                 }
 
-                Mode::HelixNormal => {}
+                Mode::HelixNormal | Mode::HelixSelect => {}
             }
         }
 
@@ -694,7 +694,9 @@ impl Vim {
                 self.visual_motion(motion.clone(), count, window, cx)
             }
 
-            Mode::HelixNormal => self.helix_normal_motion(motion.clone(), count, window, cx),
+            Mode::HelixNormal | Mode::HelixSelect => {
+                self.helix_normal_motion(motion.clone(), count, window, cx)
+            }
         }
         self.clear_operator(window, cx);
         if let Some(operator) = waiting_operator {
@@ -1,10 +1,14 @@
-use editor::Editor;
+use anyhow::anyhow;
+use editor::{Anchor, Editor, EditorSettings, ToOffset, ToPoint, scroll::Autoscroll};
 use gpui::{Context, Window, actions, impl_actions, impl_internal_actions};
-use language::Point;
+use language::{Point, TransactionId};
+use multi_buffer::MultiBufferRow;
+use regex::RegexBuilder;
 use schemars::JsonSchema;
 use search::{BufferSearchBar, SearchOptions, buffer_search};
 use serde_derive::Deserialize;
-use std::{iter::Peekable, str::Chars};
+use settings::Settings;
+use std::{iter::Peekable, ops::Range, str::Chars};
 use util::serde::default_true;
 use workspace::{notifications::NotifyResultExt, searchable::Direction};
 
@@ -12,7 +16,7 @@ use crate::{
     Vim,
     command::CommandRange,
     motion::Motion,
-    state::{Mode, SearchState},
+    state::{Mode, Operator, SearchState},
 };
 
 #[derive(Clone, Debug, Deserialize, JsonSchema, PartialEq)]
@@ -64,9 +68,21 @@ pub(crate) struct Replacement {
     search: String,
     replacement: String,
     should_replace_all: bool,
+    should_confirm: bool,
+    should_count: bool,
     is_case_sensitive: bool,
 }
 
+/// The matches of a `:s` command given the `c` flag, which are replaced one
+/// at a time as the user confirms them.
+pub(crate) struct SubstituteConfirmation {
+    matches: Vec<(Range<Anchor>, String)>,
+    current: usize,
+    cursor: Option<Point>,
+    replaced_all: bool,
+    transaction: Option<TransactionId>,
+}
+
 actions!(vim, [SearchSubmit, MoveToNextMatch, MoveToPreviousMatch]);
 impl_actions!(vim, [FindCommand, Search, MoveToPrevious, MoveToNext]);
 impl_internal_actions!(vim, [ReplaceCommand]);
@@ -425,6 +441,10 @@ impl Vim {
         window: &mut Window,
         cx: &mut Context<Self>,
     ) {
+        if action.replacement.should_count || action.replacement.should_confirm {
+            self.substitute_with_flags(action, window, cx);
+            return;
+        }
         let replacement = action.replacement.clone();
         let Some(((pane, workspace), editor)) = self
             .pane(window, cx)
@@ -512,6 +532,285 @@ impl Vim {
             .detach_and_log_err(cx);
         })
     }
+
+    /// Runs a `:s` command given the `n` flag, which only reports the number of
+    /// matches, or the `c` flag, which asks before replacing each match.
+    fn substitute_with_flags(
+        &mut self,
+        action: &ReplaceCommand,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let matches = match self.substitution_matches(action, window, cx) {
+            None => return,
+            Some(Ok(matches)) => matches,
+            Some(Err(err)) => {
+                if let Some(workspace) = self.workspace(window) {
+                    workspace.update(cx, |workspace, cx| {
+                        Err::<(), _>(err).notify_err(workspace, cx);
+                    });
+                }
+                return;
+            }
+        };
+
+        if action.replacement.should_count {
+            let Some(editor) = self.editor() else {
+                return;
+            };
+            let snapshot = editor.read(cx).buffer().read(cx).snapshot(cx);
+            let mut lines = matches
+                .iter()
+                .map(|(range, _)| range.start.to_point(&snapshot).row)
+                .collect::<Vec<_>>();
+            lines.dedup();
+            self.status_label = Some(
+                format!(
+                    "{} {} on {} {}",
+                    matches.len(),
+                    if matches.len() == 1 {
+                        "match"
+                    } else {
+                        "matches"
+                    },
+                    lines.len(),
+                    if lines.len() == 1 { "line" } else { "lines" },
+                )
+                .into(),
+            );
+            cx.notify();
+            return;
+        }
+
+        self.substitute_confirmation = Some(SubstituteConfirmation {
+            matches,
+            current: 0,
+            cursor: None,
+            replaced_all: false,
+            transaction: None,
+        });
+        self.push_operator(Operator::ConfirmSubstitute, window, cx);
+        self.prompt_substitution(window, cx);
+    }
+
+    /// Returns each match of the command's pattern in its range, along with
+    /// the text that replaces it.
+    fn substitution_matches(
+        &mut self,
+        action: &ReplaceCommand,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Option<anyhow::Result<Vec<(Range<Anchor>, String)>>> {
+        let replacement = &action.replacement;
+        let search = if replacement.search.is_empty() {
+            let pane = self.pane(window, cx)?;
+            let search_bar = pane
+                .read(cx)
+                .toolbar()
+                .read(cx)
+                .item_of_type::<BufferSearchBar>()?;
+            search_bar.read(cx).query(cx)
+        } else {
+            replacement.search.clone()
+        };
+        let is_case_sensitive = if EditorSettings::get_global(cx).use_smartcase_search {
+            search.chars().any(|c| c.is_uppercase())
+        } else {
+            replacement.is_case_sensitive
+        };
+
+        self.update_editor(window, cx, |vim, editor, window, cx| {
+            let regex = RegexBuilder::new(&search)
+                .case_insensitive(!is_case_sensitive)
+                .build()?;
+            let mut rows = if action.range.is_current_line() {
+                // Run from `:g`, there is a cursor on each matching line.
+                let snapshot = editor.buffer().read(cx).snapshot(cx);
+                editor
+                    .selections
+                    .disjoint_anchors()
+                    .iter()
+                    .map(|selection| selection.head().to_point(&snapshot).row)
+                    .collect::<Vec<_>>()
+            } else {
+                let range = action.range.buffer_range(vim, editor, window, cx)?;
+                (range.start.0..=range.end.0).collect()
+            };
+            rows.sort_unstable();
+            rows.dedup();
+
+            let snapshot = editor.buffer().read(cx).snapshot(cx);
+            let mut matches = Vec::new();
+            for row in rows {
+                let line_start = Point::new(row, 0);
+                let line_end = Point::new(row, snapshot.line_len(MultiBufferRow(row)));
+                let line = snapshot
+                    .text_for_range(line_start..line_end)
+                    .collect::<String>();
+                let line_offset = line_start.to_offset(&snapshot);
+                let max_matches = if replacement.should_replace_all {
+                    usize::MAX
+                } else {
+                    1
+                };
+                for captures in regex.captures_iter(&line).take(max_matches) {
+                    let Some(found) = captures.get(0) else {
+                        continue;
+                    };
+                    let mut text = String::new();
+                    captures.expand(&replacement.replacement, &mut text);
+                    let start = snapshot.anchor_after(line_offset + found.start());
+                    let end = if found.is_empty() {
+                        start
+                    } else {
+                        snapshot.anchor_before(line_offset + found.end())
+                    };
+                    matches.push((start..end, text));
+                }
+            }
+
+            if matches.is_empty() {
+                Err(anyhow!("E486: Pattern not found: {search}"))
+            } else {
+                Ok(matches)
+            }
+        })
+    }
+
+    /// Highlights the next match of a `:s///c` command and asks whether to
+    /// replace it, or finishes the command if there are no more matches.
+    fn prompt_substitution(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(confirmation) = self.substitute_confirmation.as_ref() else {
+            return;
+        };
+        let Some((range, text)) = confirmation.matches.get(confirmation.current).cloned() else {
+            self.finish_substitution(window, cx);
+            return;
+        };
+
+        let cursor = self.update_editor(window, cx, |_, editor, window, cx| {
+            editor.highlight_background::<SubstituteConfirmation>(
+                &[range.clone()],
+                |colors| colors.search_match_background,
+                cx,
+            );
+            let cursor = range.start.to_point(&editor.buffer().read(cx).snapshot(cx));
+            editor.change_selections(Some(Autoscroll::fit()), window, cx, |s| {
+                s.select_ranges([cursor..cursor]);
+            });
+            cursor
+        });
+        if let Some(confirmation) = self.substitute_confirmation.as_mut() {
+            confirmation.cursor = cursor;
+        }
+        self.status_label = Some(format!("replace with {text} (y/n/a/q/l/^E/^Y)?").into());
+        cx.notify();
+    }
+
+    /// Handles the answer to the prompt of a `:s///c` command.
+    pub(crate) fn confirm_substitution(
+        &mut self,
+        answer: &str,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        match answer {
+            "y" => {
+                self.replace_substitution_matches(1, window, cx);
+                self.prompt_substitution(window, cx);
+            }
+            "l" => {
+                self.replace_substitution_matches(1, window, cx);
+                self.finish_substitution(window, cx);
+            }
+            "n" => {
+                if let Some(confirmation) = self.substitute_confirmation.as_mut() {
+                    confirmation.current += 1;
+                }
+                self.prompt_substitution(window, cx);
+            }
+            "a" => {
+                if let Some(confirmation) = self.substitute_confirmation.as_mut() {
+                    confirmation.replaced_all = true;
+                }
+                self.replace_substitution_matches(usize::MAX, window, cx);
+                self.finish_substitution(window, cx);
+            }
+            "q" => self.finish_substitution(window, cx),
+            _ => {}
+        }
+    }
+
+    fn replace_substitution_matches(
+        &mut self,
+        count: usize,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(confirmation) = self.substitute_confirmation.as_mut() else {
+            return;
+        };
+        let start = confirmation.current;
+        let end = start.saturating_add(count).min(confirmation.matches.len());
+        let edits = confirmation.matches[start..end].to_vec();
+        confirmation.current = end;
+
+        let transaction = self
+            .update_editor(window, cx, |_, editor, window, cx| {
+                editor.transact(window, cx, |editor, _, cx| {
+                    editor.edit(edits, cx);
+                })
+            })
+            .flatten();
+        if let Some(confirmation) = self.substitute_confirmation.as_mut() {
+            confirmation.transaction = confirmation.transaction.or(transaction);
+        }
+    }
+
+    /// Ends a `:s///c` command, leaving the cursor on the last match that was
+    /// prompted for, or on the last replaced line once all were accepted.
+    pub(crate) fn finish_substitution(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(confirmation) = self.substitute_confirmation.take() else {
+            return;
+        };
+        self.status_label.take();
+        self.update_editor(window, cx, |_, editor, window, cx| {
+            editor.clear_background_highlights::<SubstituteConfirmation>(cx);
+            if let Some(transaction) = confirmation.transaction {
+                editor.group_until_transaction(transaction, cx);
+            }
+            let cursor = if confirmation.replaced_all {
+                confirmation.matches.last().map(|(range, _)| {
+                    let row = range
+                        .start
+                        .to_point(&editor.buffer().read(cx).snapshot(cx))
+                        .row;
+                    Point::new(row, 0)
+                })
+            } else {
+                confirmation.cursor
+            };
+            if let Some(cursor) = cursor {
+                editor.change_selections(Some(Autoscroll::fit()), window, cx, |s| {
+                    s.select_ranges([cursor..cursor]);
+                });
+            }
+        });
+        if confirmation.replaced_all {
+            self.move_cursor(
+                Motion::FirstNonWhitespace {
+                    display_lines: false,
+                },
+                None,
+                window,
+                cx,
+            );
+        }
+        if self.active_operator() == Some(Operator::ConfirmSubstitute) {
+            self.pop_operator(window, cx);
+        }
+        cx.notify();
+    }
 }
 
 impl Replacement {
@@ -572,13 +871,16 @@ impl Replacement {
             search,
             replacement,
             should_replace_all: false,
+            should_confirm: false,
+            should_count: false,
             is_case_sensitive: true,
         };
 
         for c in flags.chars() {
             match c {
                 'g' => replacement.should_replace_all = true,
-                'c' | 'n' => replacement.should_replace_all = false,
+                'c' => replacement.should_confirm = true,
+                'n' => replacement.should_count = true,
                 'i' => replacement.is_case_sensitive = false,
                 'I' => replacement.is_case_sensitive = true,
                 _ => {}
@@ -594,7 +896,8 @@ mod test {
     use std::time::Duration;
 
     use crate::{
-        state::Mode,
+        VimAddon,
+        state::{Mode, Operator},
         test::{NeovimBackedTestContext, VimTestContext},
     };
     use editor::EditorSettings;
@@ -999,4 +1302,187 @@ mod test {
                  "
         });
     }
+
+    #[gpui::test]
+    async fn test_replace_with_confirm(cx: &mut gpui::TestAppContext) {
+        let mut cx = NeovimBackedTestContext::new(cx).await;
+
+        cx.set_shared_state(indoc! {
+            "ˇaa
+            ba
+            aa
+            "
+        })
+        .await;
+        cx.simulate_shared_keystrokes(": % s / a / x / g c enter")
+            .await;
+        cx.simulate_shared_keystrokes("y n y n y").await;
+        cx.shared_state().await.assert_eq(indoc! {
+            "xa
+            bx
+            aˇx
+            "
+        });
+
+        cx.simulate_shared_keystrokes("u").await;
+        cx.shared_state().await.assert_eq(indoc! {
+            "ˇaa
+            ba
+            aa
+            "
+        });
+    }
+
+    #[gpui::test]
+    async fn test_replace_with_confirm_all(cx: &mut gpui::TestAppContext) {
+        let mut cx = NeovimBackedTestContext::new(cx).await;
+
+        cx.set_shared_state(indoc! {
+            "ˇa
+              a
+            b
+             a
+            "
+        })
+        .await;
+        cx.simulate_shared_keystrokes(": % s / a / x / c enter")
+            .await;
+        cx.simulate_shared_keystrokes("n a").await;
+        cx.shared_state().await.assert_eq(indoc! {
+            "a
+              x
+            b
+             ˇx
+            "
+        });
+    }
+
+    #[gpui::test]
+    async fn test_replace_with_confirm_quit(cx: &mut gpui::TestAppContext) {
+        let mut cx = NeovimBackedTestContext::new(cx).await;
+
+        cx.set_shared_state(indoc! {
+            "ˇa a a
+            a
+            "
+        })
+        .await;
+        cx.simulate_shared_keystrokes(": % s / a / x / g c enter")
+            .await;
+        cx.simulate_shared_keystrokes("y q").await;
+        cx.shared_state().await.assert_eq(indoc! {
+            "x ˇa a
+            a
+            "
+        });
+
+        cx.simulate_shared_keystrokes(": % s / a / x / g c enter")
+            .await;
+        cx.simulate_shared_keystrokes("n l").await;
+        cx.shared_state().await.assert_eq(indoc! {
+            "x a ˇx
+            a
+            "
+        });
+    }
+
+    #[gpui::test]
+    async fn test_replace_with_confirm_in_range(cx: &mut gpui::TestAppContext) {
+        let mut cx = NeovimBackedTestContext::new(cx).await;
+
+        cx.set_shared_state(indoc! {
+            "ˇa
+            a
+            a
+            a
+            "
+        })
+        .await;
+        cx.simulate_shared_keystrokes(": 2 , 3 s / a / b / c enter")
+            .await;
+        cx.simulate_shared_keystrokes("y y").await;
+        cx.shared_state().await.assert_eq(indoc! {
+            "a
+            b
+            ˇb
+            a
+            "
+        });
+
+        cx.simulate_shared_keystrokes(": g / b / s / / c / c enter")
+            .await;
+        cx.simulate_shared_keystrokes("n y").await;
+        cx.shared_state().await.assert_eq(indoc! {
+            "a
+            b
+            ˇc
+            a
+            "
+        });
+    }
+
+    #[gpui::test]
+    async fn test_replace_count_only(cx: &mut gpui::TestAppContext) {
+        let mut cx = NeovimBackedTestContext::new(cx).await;
+
+        cx.set_shared_state(indoc! {
+            "a a
+            ˇb
+            a
+            "
+        })
+        .await;
+        cx.simulate_shared_keystrokes(": % s / a / x / g n enter")
+            .await;
+        cx.shared_state().await.assert_eq(indoc! {
+            "a a
+            ˇb
+            a
+            "
+        });
+
+        let status_label = cx.update_editor(|editor, _, cx| {
+            editor
+                .addon::<VimAddon>()
+                .unwrap()
+                .entity
+                .read(cx)
+                .status_label
+                .clone()
+        });
+        assert_eq!(status_label, Some("3 matches on 2 lines".into()));
+    }
+
+    #[gpui::test]
+    async fn test_replace_confirm_prompt(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state("ˇone two one", Mode::Normal);
+        cx.simulate_keystrokes(": s / o n e / 1 / g c enter");
+        assert_eq!(cx.active_operator(), Some(Operator::ConfirmSubstitute));
+        let status_label = |cx: &mut VimTestContext| {
+            cx.update_editor(|editor, _, cx| {
+                editor
+                    .addon::<VimAddon>()
+                    .unwrap()
+                    .entity
+                    .read(cx)
+                    .status_label
+                    .clone()
+            })
+        };
+        assert_eq!(
+            status_label(&mut cx),
+            Some("replace with 1 (y/n/a/q/l/^E/^Y)?".into())
+        );
+
+        cx.simulate_keystrokes("n");
+        cx.assert_state("one two ˇone", Mode::Normal);
+
+        cx.simulate_keystrokes("escape");
+        cx.run_until_parked();
+        assert_eq!(cx.active_operator(), None);
+        assert_eq!(status_label(&mut cx), None);
+        cx.assert_state("one two ˇone", Mode::Normal);
+    }
 }
@@ -2,6 +2,7 @@ use crate::command::command_interceptor;
 use crate::motion::MotionKind;
 use crate::normal::repeat::Replayer;
 use crate::surrounds::SurroundsType;
+use crate::vimrc::Mapping;
 use crate::{ToggleMarksView, ToggleRegistersView, UseSystemClipboard, Vim, VimAddon, VimSettings};
 use crate::{motion::Motion, object::Object};
 use anyhow::Result;
@@ -13,7 +14,8 @@ use editor::display_map::{is_invisible, replacement};
 use editor::{Anchor, ClipboardSelection, Editor, MultiBuffer, ToPoint as EditorToPoint};
 use gpui::{
     Action, App, AppContext, BorrowAppContext, ClipboardEntry, ClipboardItem, DismissEvent, Entity,
-    EntityId, Global, HighlightStyle, StyledText, Subscription, Task, TextStyle, WeakEntity,
+    EntityId, Global, HighlightStyle, Keystroke, StyledText, Subscription, Task, TextStyle,
+    WeakEntity,
 };
 use language::{Buffer, BufferEvent, BufferId, Chunk, Point};
 use multi_buffer::MultiBufferRow;
@@ -44,6 +46,7 @@ pub enum Mode {
     VisualLine,
     VisualBlock,
     HelixNormal,
+    HelixSelect,
 }
 
 impl Display for Mode {
@@ -56,6 +59,7 @@ impl Display for Mode {
             Mode::VisualLine => write!(f, "VISUAL LINE"),
             Mode::VisualBlock => write!(f, "VISUAL BLOCK"),
             Mode::HelixNormal => write!(f, "HELIX NORMAL"),
+            Mode::HelixSelect => write!(f, "HELIX SELECT"),
         }
     }
 }
@@ -64,9 +68,15 @@ impl Mode {
     pub fn is_visual(&self) -> bool {
         match self {
             Self::Visual | Self::VisualLine | Self::VisualBlock => true,
-            Self::Normal | Self::Insert | Self::Replace | Self::HelixNormal => false,
+            Self::Normal | Self::Insert | Self::Replace | Self::HelixNormal | Self::HelixSelect => {
+                false
+            }
         }
     }
+
+    pub fn is_helix(&self) -> bool {
+        matches!(self, Self::HelixNormal | Self::HelixSelect)
+    }
 }
 
 impl Default for Mode {
@@ -130,6 +140,7 @@ pub enum Operator {
     ToggleComments,
     ReplaceWithRegister,
     Exchange,
+    ConfirmSubstitute,
 }
 
 #[derive(Default, Clone, Debug)]
@@ -222,6 +233,12 @@ pub struct VimGlobals {
     pub focused_vim: Option<WeakEntity<Vim>>,
 
     pub marks: HashMap<EntityId, Entity<MarksState>>,
+
+    /// Keys still to be typed by mappings, last first, and whether each may
+    /// be remapped.
+    pub mapped_keys: Vec<(Keystroke, bool)>,
+    pub typing_mapped_keys: bool,
+    pub typing_noremap_keys: bool,
 }
 
 pub struct MarksState {
@@ -829,6 +846,10 @@ impl VimGlobals {
     }
 
     pub fn observe_action(&mut self, action: Box<dyn Action>) {
+        // The actions of the keys a mapping types are recorded instead.
+        if action.as_any().is::<Mapping>() {
+            return;
+        }
         if self.dot_recording {
             self.recording_actions
                 .push(ReplayableAction::Action(action.boxed_clone()));
@@ -966,6 +987,7 @@ impl Operator {
             Operator::RecordRegister => "q",
             Operator::ReplayRegister => "@",
             Operator::ToggleComments => "gc",
+            Operator::ConfirmSubstitute => ":s",
         }
     }
 
@@ -985,7 +1007,9 @@ impl Operator {
 
     pub fn is_waiting(&self, mode: Mode) -> bool {
         match self {
-            Operator::AddSurrounds { target } => target.is_some() || mode.is_visual(),
+            Operator::AddSurrounds { target } => {
+                target.is_some() || mode.is_visual() || mode.is_helix()
+            }
             Operator::FindForward { .. }
             | Operator::Mark
             | Operator::Jump { .. }
@@ -999,7 +1023,8 @@ impl Operator {
             | Operator::Digraph { .. }
             | Operator::Literal { .. }
             | Operator::ChangeSurrounds { target: Some(_) }
-            | Operator::DeleteSurrounds => true,
+            | Operator::DeleteSurrounds
+            | Operator::ConfirmSubstitute => true,
             Operator::Change
             | Operator::Delete
             | Operator::Yank
@@ -1056,7 +1081,8 @@ impl Operator {
             | Operator::Jump { .. }
             | Operator::Register
             | Operator::RecordRegister
-            | Operator::ReplayRegister => false,
+            | Operator::ReplayRegister
+            | Operator::ConfirmSubstitute => false,
         }
     }
 }
@@ -34,16 +34,7 @@ impl Vim {
             editor.transact(window, cx, |editor, window, cx| {
                 editor.set_clip_at_line_ends(false, cx);
 
-                let pair = match find_surround_pair(&all_support_surround_pair(), &text) {
-                    Some(pair) => pair.clone(),
-                    None => BracketPair {
-                        start: text.to_string(),
-                        end: text.to_string(),
-                        close: true,
-                        surround: true,
-                        newline: false,
-                    },
-                };
+                let pair = surround_pair(&text);
                 let surround = pair.end != surround_alias((*text).as_ref());
                 let (display_map, display_selections) = editor.selections.all_adjusted_display(cx);
                 let mut edits = Vec::new();
@@ -230,16 +221,7 @@ impl Vim {
                 editor.transact(window, cx, |editor, window, cx| {
                     editor.set_clip_at_line_ends(false, cx);
 
-                    let pair = match find_surround_pair(&all_support_surround_pair(), &text) {
-                        Some(pair) => pair.clone(),
-                        None => BracketPair {
-                            start: text.to_string(),
-                            end: text.to_string(),
-                            close: true,
-                            surround: true,
-                            newline: false,
-                        },
-                    };
+                    let pair = surround_pair(&text);
                     let surround = pair.end != surround_alias((*text).as_ref());
                     let (display_map, selections) = editor.selections.all_adjusted_display(cx);
                     let mut edits = Vec::new();
@@ -392,6 +374,21 @@ fn find_surround_pair<'a>(pairs: &'a [BracketPair], ch: &str) -> Option<&'a Brac
         .find(|pair| pair.start == surround_alias(ch) || pair.end == surround_alias(ch))
 }
 
+/// Returns the pair that surrounding with `ch` inserts, falling back to
+/// using `ch` on both sides when it isn't a known bracket or quote.
+pub(crate) fn surround_pair(ch: &str) -> BracketPair {
+    match find_surround_pair(&all_support_surround_pair(), ch) {
+        Some(pair) => pair.clone(),
+        None => BracketPair {
+            start: ch.to_string(),
+            end: ch.to_string(),
+            close: true,
+            surround: true,
+            newline: false,
+        },
+    }
+}
+
 fn surround_alias(ch: &str) -> &str {
     match ch {
         "b" => ")",
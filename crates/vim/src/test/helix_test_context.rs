@@ -0,0 +1,299 @@
+use std::ops::{Deref, DerefMut, Range};
+
+use gpui::UpdateGlobal;
+use indoc::indoc;
+use language::Selection;
+use settings::SettingsStore;
+
+use super::VimTestContext;
+use crate::{ModeContent, VimSettings, state::Mode};
+
+/// A test context that speaks the notation of Helix's own integration tests,
+/// so that cases can be copied from them. Unlike the Neovim-backed tests, the
+/// expected states aren't recorded from Helix, so they're written by hand.
+///
+/// The primary selection is written `#[...]#` and the others `#(...)#`, with
+/// `|` on the cursor's side: `#[w|]#ord` is a cursor on the `w`, and
+/// `#[|word]#` selects `word` with the cursor at its start. Keys are written
+/// the way Helix writes them, e.g. `wv<A-;><esc>`.
+pub struct HelixTestContext {
+    cx: VimTestContext,
+    last_set_state: Option<String>,
+    recent_keys: Option<String>,
+}
+
+struct HelixSelection {
+    range: Range<usize>,
+    reversed: bool,
+    primary: bool,
+}
+
+impl HelixTestContext {
+    pub async fn new(cx: &mut gpui::TestAppContext) -> HelixTestContext {
+        let mut cx = VimTestContext::new(cx, true).await;
+        cx.update(|_, cx| {
+            SettingsStore::update_global(cx, |store, cx| {
+                store.update_user_settings::<VimSettings>(cx, |s| {
+                    s.default_mode = Some(ModeContent::HelixNormal)
+                });
+            });
+        });
+        Self {
+            cx,
+            last_set_state: None,
+            recent_keys: None,
+        }
+    }
+
+    pub fn set_helix_state(&mut self, marked_text: &str) {
+        let (text, selections) = parse_helix_text(marked_text);
+        self.last_set_state = Some(marked_text.to_string());
+        self.recent_keys = None;
+        self.cx.set_state("ˇ", Mode::HelixNormal);
+        self.cx.update_editor(|editor, window, cx| {
+            editor.set_text(text.clone(), window, cx);
+            editor.change_selections(None, window, cx, |s| {
+                // The newest selection is the primary one.
+                let (primary, others): (Vec<_>, Vec<_>) = selections
+                    .into_iter()
+                    .partition(|selection| selection.primary);
+                let selections = others
+                    .into_iter()
+                    .chain(primary)
+                    .map(|selection| {
+                        // A one character selection is how Helix draws a
+                        // block cursor.
+                        let is_cursor = text[selection.range.clone()].chars().count() == 1;
+                        let end = if is_cursor {
+                            selection.range.start
+                        } else {
+                            selection.range.end
+                        };
+                        Selection {
+                            id: s.new_selection_id(),
+                            start: selection.range.start,
+                            end,
+                            reversed: selection.reversed && !is_cursor,
+                            goal: language::SelectionGoal::None,
+                        }
+                    })
+                    .collect::<Vec<_>>();
+                s.select(selections);
+            });
+        });
+    }
+
+    pub fn simulate_helix_keys(&mut self, keys: &str) {
+        let keystrokes = helix_keystrokes(keys);
+        if keystrokes.is_empty() {
+            return;
+        }
+        self.recent_keys = Some(match self.recent_keys.take() {
+            Some(recent_keys) => recent_keys + keys,
+            None => keys.to_string(),
+        });
+        self.cx.simulate_keystrokes(&keystrokes.join(" "));
+    }
+
+    pub fn helix_state(&mut self) -> String {
+        self.cx.update_editor(|editor, _, cx| {
+            let text = editor.text(cx);
+            let selections = editor.selections.all::<usize>(cx);
+            generate_helix_text(&text, &selections)
+        })
+    }
+
+    #[track_caller]
+    pub fn assert_helix_state(&mut self, marked_text: &str) {
+        let state = self.helix_state();
+        let mode = self.cx.mode();
+        if state != marked_text {
+            panic!(
+                indoc! {"Editor does not match Helix behavior
+                    # initial state:
+                    {}
+                    # keys:
+                    {}
+                    # expected (helix):
+                    {}
+                    # zed ({}):
+                    {}"},
+                self.last_set_state.as_deref().unwrap_or(""),
+                self.recent_keys.as_deref().unwrap_or(""),
+                marked_text,
+                mode,
+                state,
+            )
+        }
+    }
+
+    #[track_caller]
+    pub fn assert_helix(&mut self, initial_state: &str, keys: &str, state_after: &str) {
+        self.set_helix_state(initial_state);
+        self.simulate_helix_keys(keys);
+        self.assert_helix_state(state_after);
+    }
+}
+
+impl Deref for HelixTestContext {
+    type Target = VimTestContext;
+
+    fn deref(&self) -> &Self::Target {
+        &self.cx
+    }
+}
+
+impl DerefMut for HelixTestContext {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.cx
+    }
+}
+
+fn parse_helix_text(marked_text: &str) -> (String, Vec<HelixSelection>) {
+    let mut text = String::new();
+    let mut selections = Vec::new();
+    // The start, whether it is primary, and the cursor of the open selection.
+    let mut open: Option<(usize, bool, Option<usize>)> = None;
+    let mut rest = marked_text;
+    while let Some(ch) = rest.chars().next() {
+        match open {
+            None if rest.starts_with("#[") || rest.starts_with("#(") => {
+                open = Some((text.len(), rest.starts_with("#["), None));
+                rest = &rest[2..];
+                continue;
+            }
+            Some((start, primary, cursor)) => {
+                let close = if primary { "]#" } else { ")#" };
+                if rest.starts_with(close) {
+                    let cursor = cursor.unwrap_or_else(|| {
+                        panic!("selection in {marked_text:?} is missing its | cursor")
+                    });
+                    selections.push(HelixSelection {
+                        range: start..text.len(),
+                        reversed: cursor == start && start != text.len(),
+                        primary,
+                    });
+                    open = None;
+                    rest = &rest[2..];
+                    continue;
+                }
+                if ch == '|' && cursor.is_none() {
+                    open = Some((start, primary, Some(text.len())));
+                    rest = &rest[1..];
+                    continue;
+                }
+            }
+            None => {}
+        }
+        text.push(ch);
+        rest = &rest[ch.len_utf8()..];
+    }
+    assert!(open.is_none(), "unclosed selection in {marked_text:?}");
+    assert_eq!(
+        selections
+            .iter()
+            .filter(|selection| selection.primary)
+            .count(),
+        1,
+        "{marked_text:?} must have exactly one primary selection"
+    );
+    (text, selections)
+}
+
+fn generate_helix_text(text: &str, selections: &[Selection<usize>]) -> String {
+    let primary_id = selections.iter().map(|selection| selection.id).max();
+    let mut marked_text = String::new();
+    let mut offset = 0;
+    for selection in selections {
+        let mut range = selection.range();
+        // Empty selections are block cursors over the next character.
+        if range.is_empty() {
+            if let Some(ch) = text[range.start..].chars().next() {
+                range.end += ch.len_utf8();
+            }
+        }
+        let (open, close) = if Some(selection.id) == primary_id {
+            ("#[", "]#")
+        } else {
+            ("#(", ")#")
+        };
+        marked_text.push_str(&text[offset..range.start]);
+        marked_text.push_str(open);
+        if selection.reversed && !selection.is_empty() {
+            marked_text.push('|');
+            marked_text.push_str(&text[range.clone()]);
+        } else {
+            marked_text.push_str(&text[range.clone()]);
+            marked_text.push('|');
+        }
+        marked_text.push_str(close);
+        offset = range.end;
+    }
+    marked_text.push_str(&text[offset..]);
+    marked_text
+}
+
+/// Converts keys written as in Helix's tests into zed keystrokes.
+fn helix_keystrokes(keys: &str) -> Vec<String> {
+    let mut keystrokes = Vec::new();
+    let mut rest = keys;
+    while let Some(ch) = rest.chars().next() {
+        if ch == '<' {
+            if let Some(end) = rest.find('>').filter(|end| *end > 1) {
+                keystrokes.push(helix_named_key(&rest[1..end]));
+                rest = &rest[end + 1..];
+                continue;
+            }
+        }
+        keystrokes.push(helix_char_key(ch));
+        rest = &rest[ch.len_utf8()..];
+    }
+    keystrokes
+}
+
+fn helix_named_key(name: &str) -> String {
+    let mut modifiers = String::new();
+    let mut key = name;
+    loop {
+        if let Some(rest) = key.strip_prefix("C-") {
+            modifiers.push_str("ctrl-");
+            key = rest;
+        } else if let Some(rest) = key.strip_prefix("A-") {
+            modifiers.push_str("alt-");
+            key = rest;
+        } else if let Some(rest) = key.strip_prefix("S-") {
+            modifiers.push_str("shift-");
+            key = rest;
+        } else {
+            break;
+        }
+    }
+    let key = match key {
+        "esc" => "escape".to_string(),
+        "ret" => "enter".to_string(),
+        "del" => "delete".to_string(),
+        "lt" => "<".to_string(),
+        "gt" => ">".to_string(),
+        "minus" => "-".to_string(),
+        "space" | "tab" | "backspace" | "home" | "end" | "up" | "down" | "left" | "right" => {
+            key.to_string()
+        }
+        _ => {
+            let mut chars = key.chars();
+            match (chars.next(), chars.next()) {
+                (Some(ch), None) => helix_char_key(ch),
+                _ => panic!("unknown helix key <{name}>"),
+            }
+        }
+    };
+    modifiers + &key
+}
+
+fn helix_char_key(ch: char) -> String {
+    match ch {
+        ' ' => "space".to_string(),
+        '\n' => "enter".to_string(),
+        ch if ch.is_ascii_uppercase() => format!("shift-{}", ch.to_ascii_lowercase()),
+        ch => ch.to_string(),
+    }
+}
@@ -17,6 +17,7 @@ mod replace;
 mod rewrap;
 mod state;
 mod surrounds;
+mod vimrc;
 mod visual;
 
 use anyhow::Result;
@@ -33,7 +34,7 @@ use insert::{NormalBefore, TemporaryNormal};
 use language::{CharKind, CursorShape, Point, Selection, SelectionGoal, TransactionId};
 pub use mode_indicator::ModeIndicator;
 use motion::Motion;
-use normal::search::SearchSubmit;
+use normal::search::{SearchSubmit, SubstituteConfirmation};
 use object::Object;
 use schemars::JsonSchema;
 use serde::Deserialize;
@@ -45,6 +46,7 @@ use surrounds::SurroundsType;
 use theme::ThemeSettings;
 use ui::{IntoElement, SharedString, px};
 use vim_mode_setting::VimModeSetting;
+pub use vimrc::{load_vimrc, vimrc_key_bindings};
 use workspace::{self, Pane, Workspace};
 
 use crate::state::ReplayableAction;
@@ -321,6 +323,7 @@ pub(crate) struct Vim {
 
     operator_stack: Vec<Operator>,
     pub(crate) replacements: Vec<(Range<editor::Anchor>, String)>,
+    pub(crate) substitute_confirmation: Option<SubstituteConfirmation>,
 
     pub(crate) stored_visual_mode: Option<(Mode, Vec<bool>)>,
 
@@ -366,6 +369,7 @@ impl Vim {
             exit_temporary_mode: false,
             operator_stack: Vec::new(),
             replacements: Vec::new(),
+            substitute_confirmation: None,
 
             stored_visual_mode: None,
             current_tx: None,
@@ -709,6 +713,7 @@ impl Vim {
             visual::register(editor, cx);
             change_list::register(editor, cx);
             digraph::register(editor, cx);
+            vimrc::register(editor, cx);
 
             cx.defer_in(window, |vim, window, cx| {
                 vim.focused(false, window, cx);
@@ -1035,7 +1040,8 @@ impl Vim {
                         | Operator::Jump { .. }
                         | Operator::Register
                         | Operator::RecordRegister
-                        | Operator::ReplayRegister => CursorShape::Block,
+                        | Operator::ReplayRegister
+                        | Operator::ConfirmSubstitute => CursorShape::Block,
 
                         // All other operators -> Underline cursor
                         _ => CursorShape::Underline,
@@ -1044,7 +1050,9 @@ impl Vim {
                     cursor_shape.normal.unwrap_or(CursorShape::Block)
                 }
             }
-            Mode::HelixNormal => cursor_shape.normal.unwrap_or(CursorShape::Block),
+            Mode::HelixNormal | Mode::HelixSelect => {
+                cursor_shape.normal.unwrap_or(CursorShape::Block)
+            }
             Mode::Replace => cursor_shape.replace.unwrap_or(CursorShape::Underline),
             Mode::Visual | Mode::VisualLine | Mode::VisualBlock => {
                 cursor_shape.visual.unwrap_or(CursorShape::Block)
@@ -1067,6 +1075,7 @@ impl Vim {
             }
             Mode::Normal
             | Mode::HelixNormal
+            | Mode::HelixSelect
             | Mode::Replace
             | Mode::Visual
             | Mode::VisualLine
@@ -1085,7 +1094,8 @@ impl Vim {
             | Mode::VisualLine
             | Mode::VisualBlock
             | Mode::Replace
-            | Mode::HelixNormal => false,
+            | Mode::HelixNormal
+            | Mode::HelixSelect => false,
             Mode::Normal => true,
         }
     }
@@ -1097,6 +1107,7 @@ impl Vim {
             Mode::Insert => "insert",
             Mode::Replace => "replace",
             Mode::HelixNormal => "helix_normal",
+            Mode::HelixSelect => "helix_select",
         }
         .to_string();
 
@@ -1113,6 +1124,8 @@ impl Vim {
             if active_operator.is_waiting(self.mode) {
                 if matches!(active_operator, Operator::Literal { .. }) {
                     mode = "literal".to_string();
+                } else if matches!(active_operator, Operator::ConfirmSubstitute) {
+                    mode = "substitute_confirm".to_string();
                 } else {
                     mode = "waiting".to_string();
                 }
@@ -1122,7 +1135,12 @@ impl Vim {
             }
         }
 
-        if mode == "normal" || mode == "visual" || mode == "operator" || mode == "helix_normal" {
+        if mode == "normal"
+            || mode == "visual"
+            || mode == "operator"
+            || mode == "helix_normal"
+            || mode == "helix_select"
+        {
             context.add("VimControl");
         }
         context.set("vim_mode", mode);
@@ -1388,6 +1406,11 @@ impl Vim {
         self.selected_register.take();
         self.operator_stack.clear();
         self.sync_vim_settings(window, cx);
+        if self.substitute_confirmation.is_some() {
+            cx.defer_in(window, |vim, window, cx| {
+                vim.finish_substitution(window, cx)
+            });
+        }
     }
 
     fn active_operator(&self) -> Option<Operator> {
@@ -1461,7 +1484,7 @@ impl Vim {
                     })
                 });
             }
-            Mode::Insert | Mode::Replace | Mode::HelixNormal => {}
+            Mode::Insert | Mode::Replace | Mode::HelixNormal | Mode::HelixSelect => {}
         }
     }
 
@@ -1598,6 +1621,10 @@ impl Vim {
                     self.add_surrounds(text, SurroundsType::Selection, window, cx);
                     self.clear_operator(window, cx);
                 }
+                Mode::HelixNormal | Mode::HelixSelect => {
+                    self.helix_add_surrounds(text, window, cx);
+                    self.clear_operator(window, cx);
+                }
                 _ => self.clear_operator(window, cx),
             },
             Some(Operator::ChangeSurrounds { target }) => match self.mode {
@@ -1607,6 +1634,12 @@ impl Vim {
                         self.clear_operator(window, cx);
                     }
                 }
+                Mode::HelixNormal | Mode::HelixSelect => {
+                    if let Some(target) = target {
+                        self.helix_change_surrounds(text, target, window, cx);
+                        self.clear_operator(window, cx);
+                    }
+                }
                 _ => self.clear_operator(window, cx),
             },
             Some(Operator::DeleteSurrounds) => match self.mode {
@@ -1614,6 +1647,10 @@ impl Vim {
                     self.delete_surrounds(text, window, cx);
                     self.clear_operator(window, cx);
                 }
+                Mode::HelixNormal | Mode::HelixSelect => {
+                    self.helix_delete_surrounds(text, window, cx);
+                    self.clear_operator(window, cx);
+                }
                 _ => self.clear_operator(window, cx),
             },
             Some(Operator::Mark) => self.create_mark(text, window, cx),
@@ -1645,6 +1682,7 @@ impl Vim {
                 }
             },
             Some(Operator::Jump { line }) => self.jump(text, line, true, window, cx),
+            Some(Operator::ConfirmSubstitute) => self.confirm_substitution(&text, window, cx),
             _ => {
                 if self.mode == Mode::Replace {
                     self.multi_replace(text, window, cx)
@@ -0,0 +1,1032 @@
+//! Mappings and user commands written the way they are in a vimrc: loaded from
+//! the `vimrc` file in Zed's config directory, or run as ex commands.
+//!
+//! Mappings become key bindings whose context matches the modes they apply to,
+//! and user commands become ex commands that run their replacement.
+
+use std::rc::Rc;
+
+use anyhow::{Result, anyhow};
+use editor::Editor;
+use gpui::{
+    Action, App, Context, Global, KeyBinding, KeyBindingContextPredicate, Keystroke, NoAction,
+    SharedString, Window, impl_internal_actions,
+};
+use util::ResultExt;
+use workspace::notifications::NotifyResultExt;
+
+use crate::{Vim, command::command_interceptor};
+
+/// The most keys that mappings can type in response to one keystroke, which
+/// stops recursive mappings that never end.
+const MAX_MAPPED_KEYS: usize = 1000;
+
+/// Types the right-hand side of a mapping.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct Mapping {
+    rhs: MappingRhs,
+    recursive: bool,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum MappingRhs {
+    /// Keys that are typed as if the user typed them.
+    Keys(String),
+    /// An ex command that is run directly, for mappings like `:w<CR>`.
+    Command(String),
+}
+
+/// Runs a command defined with `:command`.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct RunUserCommand {
+    pub(crate) name: SharedString,
+    pub(crate) bang: bool,
+    pub(crate) args: String,
+}
+
+/// Runs a `:map`, `:command` or `:let mapleader` command typed in the command
+/// palette.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct VimrcCommand {
+    command: String,
+}
+
+impl_internal_actions!(vim, [Mapping, RunUserCommand, VimrcCommand]);
+
+pub(crate) fn register(editor: &mut Editor, cx: &mut Context<Vim>) {
+    Vim::action(editor, cx, Vim::run_mapping);
+    Vim::action(editor, cx, Vim::run_user_command);
+    Vim::action(editor, cx, |vim, action: &VimrcCommand, window, cx| {
+        let vimrc = cx.default_global::<VimrcState>();
+        let mappings = vimrc.vimrc.mappings.len();
+        match vimrc.vimrc.execute(&action.command) {
+            Ok(()) => {
+                vimrc.runtime_commands.push(action.command.clone());
+                let key_bindings = vimrc.vimrc.key_bindings(mappings);
+                cx.bind_keys(key_bindings);
+            }
+            Err(error) => vim.notify_err(error, window, cx),
+        }
+    });
+}
+
+/// The mappings and commands defined by the vimrc file, followed by those
+/// defined at runtime.
+#[derive(Default)]
+struct VimrcState {
+    vimrc: Vimrc,
+    runtime_commands: Vec<String>,
+}
+
+impl Global for VimrcState {}
+
+/// Replaces the mappings and commands of the vimrc file with the ones in
+/// `content`, keeping those defined at runtime. Returns an error for each line
+/// that couldn't be loaded.
+///
+/// The new mappings take effect when the keymap is next loaded, which includes
+/// their key bindings from [`vimrc_key_bindings`].
+pub fn load_vimrc(content: &str, cx: &mut App) -> Vec<String> {
+    let vimrc = cx.default_global::<VimrcState>();
+    let mut errors = Vec::new();
+    let mut new_vimrc = Vimrc::default();
+    for (row, line) in logical_lines(content) {
+        if let Err(error) = new_vimrc.execute(&line) {
+            errors.push(format!("line {}: {error}", row + 1));
+        }
+    }
+    for command in &vimrc.runtime_commands {
+        new_vimrc.execute(command).log_err();
+    }
+    vimrc.vimrc = new_vimrc;
+    errors
+}
+
+/// Returns the key bindings for the mappings of the vimrc file and of the
+/// mappings defined at runtime.
+pub fn vimrc_key_bindings(cx: &App) -> Vec<KeyBinding> {
+    cx.try_global::<VimrcState>()
+        .map(|vimrc| vimrc.vimrc.key_bindings(0))
+        .unwrap_or_default()
+}
+
+pub(crate) fn user_commands(cx: &App) -> &[UserCommand] {
+    cx.try_global::<VimrcState>()
+        .map(|vimrc| vimrc.vimrc.commands.as_slice())
+        .unwrap_or_default()
+}
+
+/// Returns the action that runs `query` if it is a command that defines a
+/// mapping, a user command or the leader key.
+pub(crate) fn definition_action(query: &str) -> Option<Box<dyn Action>> {
+    let (name, _) = split_command_name(query);
+    if name == "let" || MapCommand::find(name).is_some() || is_command_command(name) {
+        Some(
+            VimrcCommand {
+                command: query.to_string(),
+            }
+            .boxed_clone(),
+        )
+    } else {
+        None
+    }
+}
+
+/// Yields the lines of a vimrc that hold a command, joining lines continued by
+/// a leading backslash and skipping comments.
+fn logical_lines(content: &str) -> Vec<(usize, String)> {
+    let mut lines: Vec<(usize, String)> = Vec::new();
+    for (row, line) in content.lines().enumerate() {
+        let trimmed = line.trim_start();
+        if let Some(continuation) = trimmed.strip_prefix('\\') {
+            if let Some((_, previous)) = lines.last_mut() {
+                previous.push_str(continuation);
+                continue;
+            }
+        }
+        if trimmed.is_empty() || trimmed.starts_with('"') {
+            continue;
+        }
+        lines.push((row, trimmed.to_string()));
+    }
+    lines
+}
+
+fn split_command_name(line: &str) -> (&str, &str) {
+    let line = line.trim_start_matches([':', ' ', '\t']);
+    let end = line
+        .find(|c: char| !c.is_ascii_alphabetic())
+        .unwrap_or(line.len());
+    line.split_at(end)
+}
+
+fn is_command_command(name: &str) -> bool {
+    name.len() >= 3 && "command".starts_with(name)
+}
+
+/// The modes in which a mapping applies.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum MapMode {
+    Normal,
+    Visual,
+    OperatorPending,
+    Insert,
+}
+
+impl MapMode {
+    fn context(self) -> &'static str {
+        match self {
+            MapMode::Normal => "vim_mode == normal",
+            MapMode::Visual => "vim_mode == visual",
+            MapMode::OperatorPending => "vim_mode == operator",
+            MapMode::Insert => "vim_mode == insert",
+        }
+    }
+}
+
+const NORMAL_VISUAL_OPERATOR: &[MapMode] =
+    &[MapMode::Normal, MapMode::Visual, MapMode::OperatorPending];
+
+/// A command of the `:map` family.
+struct MapCommand {
+    prefix: &'static str,
+    suffix: &'static str,
+    modes: &'static [MapMode],
+    recursive: bool,
+}
+
+impl MapCommand {
+    const ALL: &[MapCommand] = &[
+        MapCommand::new("map", "", NORMAL_VISUAL_OPERATOR, true),
+        MapCommand::new("nm", "ap", &[MapMode::Normal], true),
+        MapCommand::new("vm", "ap", &[MapMode::Visual], true),
+        MapCommand::new("xm", "ap", &[MapMode::Visual], true),
+        MapCommand::new("om", "ap", &[MapMode::OperatorPending], true),
+        MapCommand::new("im", "ap", &[MapMode::Insert], true),
+        MapCommand::new("no", "remap", NORMAL_VISUAL_OPERATOR, false),
+        MapCommand::new("nn", "oremap", &[MapMode::Normal], false),
+        MapCommand::new("vn", "oremap", &[MapMode::Visual], false),
+        MapCommand::new("xn", "oremap", &[MapMode::Visual], false),
+        MapCommand::new("ono", "remap", &[MapMode::OperatorPending], false),
+        MapCommand::new("ino", "remap", &[MapMode::Insert], false),
+    ];
+
+    const fn new(
+        prefix: &'static str,
+        suffix: &'static str,
+        modes: &'static [MapMode],
+        recursive: bool,
+    ) -> Self {
+        Self {
+            prefix,
+            suffix,
+            modes,
+            recursive,
+        }
+    }
+
+    fn find(name: &str) -> Option<&'static MapCommand> {
+        Self::ALL.iter().find(|command| {
+            name.starts_with(command.prefix)
+                && format!("{}{}", command.prefix, command.suffix).starts_with(name)
+        })
+    }
+}
+
+/// The number of arguments a user command accepts, set with `-nargs`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum Nargs {
+    Zero,
+    One,
+    Any,
+    ZeroOrOne,
+    OneOrMore,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct UserCommand {
+    pub(crate) name: SharedString,
+    pub(crate) nargs: Nargs,
+    pub(crate) bang: bool,
+    replacement: String,
+}
+
+impl UserCommand {
+    /// Returns the ex command run for the given arguments.
+    fn expand(&self, bang: bool, args: &str) -> Result<String> {
+        let args = args.trim();
+        match self.nargs {
+            Nargs::Zero if !args.is_empty() => return Err(anyhow!("E488: Trailing characters")),
+            Nargs::One | Nargs::OneOrMore if args.is_empty() => {
+                return Err(anyhow!("E471: Argument required"));
+            }
+            Nargs::ZeroOrOne if args.contains(char::is_whitespace) => {
+                return Err(anyhow!("E488: Trailing characters"));
+            }
+            _ => {}
+        }
+
+        // With `-nargs=1` or `-nargs=?`, spaces are part of the one argument.
+        let function_args = match self.nargs {
+            Nargs::One | Nargs::ZeroOrOne if !args.is_empty() => quote_arg(args),
+            _ => split_args(args)
+                .iter()
+                .map(|arg| quote_arg(arg))
+                .collect::<Vec<_>>()
+                .join(", "),
+        };
+        Ok(self
+            .replacement
+            .replace("<args>", args)
+            .replace("<f-args>", &function_args)
+            .replace("<q-args>", &quote_arg(args))
+            .replace("<bang>", if bang { "!" } else { "" })
+            .replace("<lt>", "<"))
+    }
+}
+
+/// Quotes `arg` as a Vim string.
+fn quote_arg(arg: &str) -> String {
+    format!("\"{}\"", arg.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Splits the arguments of a user command for `<f-args>`, at whitespace that
+/// isn't escaped with a backslash. A double backslash stands for one.
+fn split_args(args: &str) -> Vec<String> {
+    let mut split = Vec::new();
+    let mut arg = String::new();
+    let mut chars = args.chars().peekable();
+    while let Some(char) = chars.next() {
+        match char {
+            '\\' => match chars.peek() {
+                Some(&next) if next == '\\' || next.is_whitespace() => {
+                    arg.push(next);
+                    chars.next();
+                }
+                _ => arg.push(char),
+            },
+            char if char.is_whitespace() => {
+                if !arg.is_empty() {
+                    split.push(std::mem::take(&mut arg));
+                }
+            }
+            char => arg.push(char),
+        }
+    }
+    if !arg.is_empty() {
+        split.push(arg);
+    }
+    split
+}
+
+/// A mapping from keys typed in some modes to the keys, or ex command, it
+/// stands for.
+#[derive(Clone, Debug, PartialEq)]
+struct KeyMapping {
+    modes: &'static [MapMode],
+    keystrokes: String,
+    /// `None` for mappings to `<Nop>`, which disable the keys.
+    rhs: Option<MappedKeys>,
+    recursive: bool,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct MappedKeys {
+    keystrokes: String,
+    /// The ex command typed by keys like `:w<CR>` or `<Cmd>w<CR>`.
+    command: Option<String>,
+    /// Whether the command was given with `<Cmd>`, which runs it in any mode.
+    is_cmd: bool,
+}
+
+impl KeyMapping {
+    fn key_bindings(&self) -> impl Iterator<Item = KeyBinding> + '_ {
+        self.modes.iter().filter_map(|mode| {
+            let action = match &self.rhs {
+                None => NoAction.boxed_clone(),
+                Some(rhs) => {
+                    let rhs = match &rhs.command {
+                        Some(command) if rhs.is_cmd || *mode == MapMode::Normal => {
+                            MappingRhs::Command(command.clone())
+                        }
+                        _ => MappingRhs::Keys(rhs.keystrokes.clone()),
+                    };
+                    Mapping {
+                        rhs,
+                        recursive: self.recursive,
+                    }
+                    .boxed_clone()
+                }
+            };
+            let context = KeyBindingContextPredicate::parse(mode.context()).log_err()?;
+            KeyBinding::load(&self.keystrokes, action, Some(Rc::new(context)), None).log_err()
+        })
+    }
+}
+
+/// The mappings and commands defined by a vimrc.
+#[derive(Debug)]
+struct Vimrc {
+    leader: Vec<String>,
+    local_leader: Vec<String>,
+    mappings: Vec<KeyMapping>,
+    commands: Vec<UserCommand>,
+}
+
+impl Default for Vimrc {
+    fn default() -> Self {
+        Self {
+            leader: vec!["\\".to_string()],
+            local_leader: vec!["\\".to_string()],
+            mappings: Vec::new(),
+            commands: Vec::new(),
+        }
+    }
+}
+
+impl Vimrc {
+    /// Returns the key bindings of the mappings defined after the first `skip`.
+    fn key_bindings(&self, skip: usize) -> Vec<KeyBinding> {
+        self.mappings
+            .iter()
+            .skip(skip)
+            .flat_map(|mapping| mapping.key_bindings())
+            .collect()
+    }
+
+    /// Runs a line of a vimrc, which may hold several commands separated by `|`.
+    fn execute(&mut self, line: &str) -> Result<()> {
+        let mut line = line.trim_start_matches([':', ' ', '\t']);
+        while !line.is_empty() {
+            let (name, rest) = split_command_name(line);
+            line = if name == "let" {
+                self.execute_let(rest)?
+            } else if is_command_command(name) {
+                self.execute_command(rest)?;
+                ""
+            } else if let Some(command) = MapCommand::find(name) {
+                self.execute_map(command, rest)?
+            } else {
+                return Err(anyhow!("E492: Not an editor command: {}", line.trim()));
+            };
+            line = line.trim_start_matches(['|', ' ', '\t']);
+        }
+        Ok(())
+    }
+
+    fn execute_let<'a>(&mut self, args: &'a str) -> Result<&'a str> {
+        let (args, rest) = split_bar(args);
+        let Some((name, value)) = args.split_once('=') else {
+            return Err(anyhow!("E15: Invalid expression: \"{}\"", args.trim()));
+        };
+        let name = name.trim();
+        let value = value.trim();
+        let value = if let Some(value) = value
+            .strip_prefix('"')
+            .and_then(|value| value.strip_suffix('"'))
+        {
+            value.replace("\\<", "<").replace("\\\\", "\\")
+        } else if let Some(value) = value
+            .strip_prefix('\'')
+            .and_then(|value| value.strip_suffix('\''))
+        {
+            value.replace('<', "<lt>")
+        } else {
+            return Err(anyhow!("E15: Invalid expression: \"{value}\""));
+        };
+
+        let keystrokes = self.parse_keys(&value, false)?;
+        match name.trim_start_matches("g:") {
+            "mapleader" => self.leader = keystrokes,
+            "maplocalleader" => self.local_leader = keystrokes,
+            _ => return Err(anyhow!("only `mapleader` and `maplocalleader` can be set")),
+        }
+        Ok(rest)
+    }
+
+    fn execute_command(&mut self, args: &str) -> Result<()> {
+        let (replace, mut args) = match args.strip_prefix('!') {
+            Some(args) => (true, args),
+            None => (false, args),
+        };
+
+        let mut nargs = Nargs::Zero;
+        let mut bang = false;
+        loop {
+            args = args.trim_start();
+            let Some(attribute) = args.strip_prefix('-') else {
+                break;
+            };
+            let end = attribute
+                .find(char::is_whitespace)
+                .unwrap_or(attribute.len());
+            let (attribute, rest) = attribute.split_at(end);
+            args = rest;
+            match attribute.split_once('=') {
+                Some(("nargs", value)) => {
+                    nargs = match value {
+                        "0" => Nargs::Zero,
+                        "1" => Nargs::One,
+                        "*" => Nargs::Any,
+                        "?" => Nargs::ZeroOrOne,
+                        "+" => Nargs::OneOrMore,
+                        _ => return Err(anyhow!("E176: Invalid number of arguments")),
+                    }
+                }
+                Some(("complete", _)) => {}
+                None if attribute == "bang" => bang = true,
+                None if attribute == "bar" => {}
+                _ => return Err(anyhow!("unsupported command attribute: -{attribute}")),
+            }
+        }
+
+        let (name, replacement) = args
+            .split_once(char::is_whitespace)
+            .map(|(name, replacement)| (name, replacement.trim()))
+            .unwrap_or((args, ""));
+        if name.is_empty() {
+            return Err(anyhow!("listing user commands isn't supported"));
+        }
+        if !name.starts_with(|c: char| c.is_ascii_uppercase())
+            || !name.chars().all(|c| c.is_ascii_alphanumeric())
+        {
+            return Err(anyhow!(
+                "E183: User defined commands must start with an uppercase letter"
+            ));
+        }
+        if replacement.is_empty() {
+            return Err(anyhow!("E471: Argument required"));
+        }
+
+        let command = UserCommand {
+            name: name.to_string().into(),
+            nargs,
+            bang,
+            replacement: replacement.to_string(),
+        };
+        if let Some(existing) = self.commands.iter_mut().find(|c| c.name == command.name) {
+            if !replace {
+                return Err(anyhow!(
+                    "E174: Command already exists: add ! to replace it: {name}"
+                ));
+            }
+            *existing = command;
+        } else {
+            self.commands.push(command);
+        }
+        Ok(())
+    }
+
+    fn execute_map<'a>(&mut self, command: &MapCommand, args: &'a str) -> Result<&'a str> {
+        let (modes, mut args) = match args.strip_prefix('!') {
+            // `:map!` applies to insert and command-line modes, and Zed's
+            // command line isn't an editor in vim mode.
+            Some(args) => (&[MapMode::Insert][..], args),
+            None => (command.modes, args),
+        };
+
+        loop {
+            args = args.trim_start();
+            let Some(option_end) = args.find('>') else {
+                break;
+            };
+            let option = args[..=option_end].to_ascii_lowercase();
+            match option.as_str() {
+                "<silent>" | "<nowait>" | "<unique>" | "<script>" | "<special>" => {}
+                "<buffer>" | "<expr>" => {
+                    return Err(anyhow!("{option} mappings aren't supported"));
+                }
+                _ => break,
+            }
+            args = &args[option_end + 1..];
+        }
+
+        let lhs_end = args.find(char::is_whitespace).unwrap_or(args.len());
+        let (lhs, rhs) = args.split_at(lhs_end);
+        let (rhs, rest) = split_bar(rhs.trim_start());
+        if lhs.is_empty() || rhs.is_empty() {
+            return Err(anyhow!("listing mappings isn't supported"));
+        }
+
+        let keystrokes = self.parse_keys(lhs, true)?.join(" ");
+        let rhs = if rhs.eq_ignore_ascii_case("<nop>") {
+            None
+        } else {
+            Some(self.parse_rhs(rhs)?)
+        };
+        self.mappings.push(KeyMapping {
+            modes,
+            keystrokes,
+            rhs,
+            recursive: command.recursive,
+        });
+        Ok(rest)
+    }
+
+    fn parse_rhs(&self, rhs: &str) -> Result<MappedKeys> {
+        let lowercase = rhs.to_ascii_lowercase();
+        if lowercase.starts_with("<cmd>") && lowercase.ends_with("<cr>") {
+            let Some(command) = self.parse_command(&rhs[5..rhs.len() - 4])? else {
+                return Err(anyhow!("unsupported keys in <Cmd> mapping: {rhs}"));
+            };
+            return Ok(MappedKeys {
+                keystrokes: String::new(),
+                command: Some(command),
+                is_cmd: true,
+            });
+        }
+
+        let command = if rhs.starts_with(':') && lowercase.ends_with("<cr>") {
+            self.parse_command(&rhs[1..rhs.len() - 4])?
+        } else {
+            None
+        };
+        Ok(MappedKeys {
+            keystrokes: self.parse_keys(rhs, true)?.join(" "),
+            command,
+            is_cmd: false,
+        })
+    }
+
+    /// Returns the ex command typed by `keys`, if they are all printable.
+    fn parse_command(&self, keys: &str) -> Result<Option<String>> {
+        let mut command = String::new();
+        if keys.is_empty() {
+            return Ok(None);
+        }
+        for keystroke in self.parse_keys(keys, true)? {
+            match keystroke.as_str() {
+                "space" => command.push(' '),
+                _ => match keystroke.strip_prefix("shift-") {
+                    Some(key) if key.len() == 1 => command.push_str(&key.to_ascii_uppercase()),
+                    None if keystroke.chars().count() == 1 => command.push_str(&keystroke),
+                    _ => return Ok(None),
+                },
+            }
+        }
+        Ok(Some(command))
+    }
+
+    /// Converts keys in Vim's notation, like `<C-w>j` or `<leader>f`, to the
+    /// keystrokes of a Zed key binding.
+    fn parse_keys(&self, keys: &str, expand_leader: bool) -> Result<Vec<String>> {
+        let mut keystrokes = Vec::new();
+        let mut rest = keys;
+        while let Some(c) = rest.chars().next() {
+            if c == '<' {
+                if let Some(end) = rest.find('>') {
+                    let name = &rest[1..end];
+                    if let Some(special) = self.special_key(name, expand_leader) {
+                        keystrokes.extend(special);
+                        rest = &rest[end + 1..];
+                        continue;
+                    }
+                }
+            }
+            keystrokes.push(char_keystroke(c));
+            rest = &rest[c.len_utf8()..];
+        }
+        if keystrokes.is_empty() {
+            return Err(anyhow!("E474: Invalid argument"));
+        }
+        for keystroke in &keystrokes {
+            Keystroke::parse(keystroke)?;
+        }
+        Ok(keystrokes)
+    }
+
+    fn special_key(&self, name: &str, expand_leader: bool) -> Option<Vec<String>> {
+        let lowercase = name.to_ascii_lowercase();
+        match lowercase.as_str() {
+            "leader" if expand_leader => return Some(self.leader.clone()),
+            "localleader" if expand_leader => return Some(self.local_leader.clone()),
+            _ => {}
+        }
+
+        let mut modifiers = String::new();
+        let mut key = name;
+        while key.len() > 2 && key.as_bytes()[1] == b'-' {
+            modifiers.push_str(match key.as_bytes()[0].to_ascii_lowercase() {
+                b'c' => "ctrl-",
+                b's' => "shift-",
+                b'a' | b'm' => "alt-",
+                b'd' => "cmd-",
+                _ => return None,
+            });
+            key = &key[2..];
+        }
+
+        let key = match key.to_ascii_lowercase().as_str() {
+            "cr" | "enter" | "return" => "enter".to_string(),
+            "esc" => "escape".to_string(),
+            "space" => "space".to_string(),
+            "tab" => "tab".to_string(),
+            "bs" => "backspace".to_string(),
+            "del" => "delete".to_string(),
+            "lt" => "<".to_string(),
+            "bar" => "|".to_string(),
+            "bslash" => "\\".to_string(),
+            key @ ("up" | "down" | "left" | "right" | "home" | "end" | "pageup" | "pagedown"
+            | "insert") => key.to_string(),
+            key if key.len() >= 2
+                && key.starts_with('f')
+                && key[1..].parse::<u8>().is_ok_and(|n| (1..=12).contains(&n)) =>
+            {
+                key.to_string()
+            }
+            _ => {
+                let mut chars = key.chars();
+                let c = chars.next()?;
+                if chars.next().is_some() || modifiers.is_empty() {
+                    return None;
+                }
+                // Vim doesn't tell `<C-a>` and `<C-A>` apart.
+                c.to_ascii_lowercase().to_string()
+            }
+        };
+        Some(vec![format!("{modifiers}{key}")])
+    }
+}
+
+/// Returns the keystroke that types `c`.
+fn char_keystroke(c: char) -> String {
+    if c == ' ' {
+        "space".to_string()
+    } else if c.is_ascii_uppercase() {
+        format!("shift-{}", c.to_ascii_lowercase())
+    } else {
+        c.to_string()
+    }
+}
+
+/// Splits the arguments of a mapping at the first `|` that isn't escaped
+/// with a backslash, which starts another command.
+fn split_bar(args: &str) -> (String, &str) {
+    let mut result = String::new();
+    let mut chars = args.char_indices();
+    while let Some((ix, c)) = chars.next() {
+        match c {
+            '\\' if args[ix + 1..].starts_with('|') => {
+                result.push('|');
+                chars.next();
+            }
+            '|' => return (result, &args[ix + 1..]),
+            _ => result.push(c),
+        }
+    }
+    (result, "")
+}
+
+impl Vim {
+    fn run_mapping(&mut self, mapping: &Mapping, window: &mut Window, cx: &mut Context<Self>) {
+        // Keys typed by a non-recursive mapping run the bindings they had
+        // before any mappings were defined.
+        if Vim::globals(cx).typing_noremap_keys {
+            cx.propagate();
+            return;
+        }
+
+        match &mapping.rhs {
+            MappingRhs::Command(command) => self.run_ex_command(command, window, cx),
+            MappingRhs::Keys(keys) => self.type_mapped_keys(keys, mapping.recursive, window, cx),
+        }
+    }
+
+    fn run_user_command(
+        &mut self,
+        action: &RunUserCommand,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(command) = user_commands(cx)
+            .iter()
+            .find(|command| command.name == action.name)
+            .cloned()
+        else {
+            return;
+        };
+        match command.expand(action.bang, &action.args) {
+            Ok(command) => self.run_ex_command(&command, window, cx),
+            Err(error) => self.notify_err(error, window, cx),
+        }
+    }
+
+    fn run_ex_command(&mut self, command: &str, window: &mut Window, cx: &mut Context<Self>) {
+        match command_interceptor(command, cx).into_iter().next() {
+            Some(result) => window.dispatch_action(result.action, cx),
+            None => self.notify_err(
+                anyhow!("E492: Not an editor command: {}", command.trim()),
+                window,
+                cx,
+            ),
+        }
+    }
+
+    /// Types `keys` after any keys already being typed by mappings, which are
+    /// remapped if `recursive` is set.
+    fn type_mapped_keys(
+        &mut self,
+        keys: &str,
+        recursive: bool,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let globals = Vim::globals(cx);
+        let mut keystrokes = keys
+            .split(' ')
+            .filter_map(|key| Keystroke::parse(key).log_err())
+            .map(|keystroke| (keystroke, recursive))
+            .collect::<Vec<_>>();
+        keystrokes.reverse();
+        globals.mapped_keys.append(&mut keystrokes);
+        if globals.typing_mapped_keys {
+            return;
+        }
+        globals.typing_mapped_keys = true;
+
+        let this = cx.weak_entity();
+        window
+            .spawn(cx, async move |cx| {
+                for _ in 0..MAX_MAPPED_KEYS {
+                    let finished = cx.update(|window, cx| {
+                        let Some((keystroke, recursive)) = Vim::globals(cx).mapped_keys.pop()
+                        else {
+                            Vim::globals(cx).typing_mapped_keys = false;
+                            return true;
+                        };
+                        // Each key is typed in its own update, so the effects of the
+                        // previous one have been flushed, and the window redraws with
+                        // the mode and operator they set before matching this one.
+                        Vim::globals(cx).typing_noremap_keys = !recursive;
+                        window.dispatch_keystroke(keystroke, cx);
+                        Vim::globals(cx).typing_noremap_keys = false;
+                        false
+                    })?;
+                    if finished {
+                        return anyhow::Ok(());
+                    }
+                }
+
+                cx.update(|_, cx| {
+                    let globals = Vim::globals(cx);
+                    globals.mapped_keys.clear();
+                    globals.typing_mapped_keys = false;
+                })?;
+                this.update_in(cx, |vim, window, cx| {
+                    vim.notify_err(anyhow!("E223: recursive mapping"), window, cx)
+                })
+            })
+            .detach_and_log_err(cx);
+    }
+
+    fn notify_err(&self, error: anyhow::Error, window: &mut Window, cx: &mut Context<Self>) {
+        if let Some(workspace) = self.workspace(window) {
+            workspace.update(cx, |workspace, cx| {
+                Err::<(), _>(error).notify_err(workspace, cx);
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use gpui::TestAppContext;
+    use indoc::indoc;
+
+    use super::*;
+    use crate::{state::Mode, test::VimTestContext};
+
+    fn load(cx: &mut VimTestContext, content: &str) {
+        cx.update(|_, cx| {
+            assert_eq!(load_vimrc(content, cx), Vec::<String>::new());
+            cx.bind_keys(vimrc_key_bindings(cx));
+        });
+    }
+
+    #[test]
+    fn test_parse_keys() {
+        let mut vimrc = Vimrc::default();
+        assert_eq!(
+            vimrc.parse_keys("<C-w>J<leader>x <Space>", true).unwrap(),
+            ["ctrl-w", "shift-j", "\\", "x", "space", "space"]
+        );
+        assert_eq!(
+            vimrc
+                .parse_keys("<lt>a<CR><S-Tab><F12><Bar>", true)
+                .unwrap(),
+            ["<", "a", "enter", "shift-tab", "f12", "|"]
+        );
+        assert_eq!(
+            vimrc.parse_keys("<notakey>", true).unwrap(),
+            ["<", "n", "o", "t", "a", "k", "e", "y", ">"]
+        );
+
+        vimrc.execute("let mapleader = \"\\<Space>\"").unwrap();
+        assert_eq!(vimrc.parse_keys("<Leader>w", true).unwrap(), ["space", "w"]);
+        vimrc.execute("let g:mapleader=','").unwrap();
+        assert_eq!(vimrc.parse_keys("<leader>w", true).unwrap(), [",", "w"]);
+    }
+
+    #[test]
+    fn test_parse_vimrc() {
+        let mut vimrc = Vimrc::default();
+        for (_, line) in logical_lines(indoc! {r#"
+            " A comment
+            let mapleader = " "
+            nnoremap <silent> <leader>w :w<CR>
+            inoremap jk <Esc>| vmap <C-j>
+                \ 5j
+            noremap! <C-a> <Home>
+            nnoremap Q <Nop>
+            command! -nargs=1 -bang Grep :s/<args>/x/g<bang>
+        "#})
+        {
+            vimrc.execute(&line).unwrap();
+        }
+
+        assert_eq!(
+            vimrc.mappings,
+            [
+                KeyMapping {
+                    modes: &[MapMode::Normal],
+                    keystrokes: "space w".into(),
+                    rhs: Some(MappedKeys {
+                        keystrokes: ": w enter".into(),
+                        command: Some("w".into()),
+                        is_cmd: false,
+                    }),
+                    recursive: false,
+                },
+                KeyMapping {
+                    modes: &[MapMode::Insert],
+                    keystrokes: "j k".into(),
+                    rhs: Some(MappedKeys {
+                        keystrokes: "escape".into(),
+                        command: None,
+                        is_cmd: false,
+                    }),
+                    recursive: false,
+                },
+                KeyMapping {
+                    modes: &[MapMode::Visual],
+                    keystrokes: "ctrl-j".into(),
+                    rhs: Some(MappedKeys {
+                        keystrokes: "5 j".into(),
+                        command: None,
+                        is_cmd: false,
+                    }),
+                    recursive: true,
+                },
+                KeyMapping {
+                    modes: &[MapMode::Insert],
+                    keystrokes: "ctrl-a".into(),
+                    rhs: Some(MappedKeys {
+                        keystrokes: "home".into(),
+                        command: None,
+                        is_cmd: false,
+                    }),
+                    recursive: false,
+                },
+                KeyMapping {
+                    modes: &[MapMode::Normal],
+                    keystrokes: "shift-q".into(),
+                    rhs: None,
+                    recursive: false,
+                },
+            ]
+        );
+
+        let command = &vimrc.commands[0];
+        assert_eq!(command.name, "Grep");
+        assert_eq!(command.nargs, Nargs::One);
+        assert_eq!(
+            command.expand(true, "foo").unwrap(),
+            ":s/foo/x/g!".to_string()
+        );
+        assert!(command.expand(false, "").is_err());
+
+        vimrc
+            .execute("command! -nargs=* Args :echo <f-args> <q-args>")
+            .unwrap();
+        assert_eq!(
+            vimrc.commands[1].expand(false, r#"a  b\ c "d"\\"#).unwrap(),
+            r#":echo "a", "b c", "\"d\"\\" "a  b\\ c \"d\"\\\\""#
+        );
+        assert_eq!(vimrc.commands[1].expand(false, "").unwrap(), r#":echo  """#);
+
+        assert!(vimrc.execute("command Grep :w").is_err());
+        assert!(vimrc.execute("command lower :w").is_err());
+        assert!(vimrc.execute("nnoremap <buffer> x y").is_err());
+        assert!(vimrc.execute("set number").is_err());
+    }
+
+    #[gpui::test]
+    async fn test_mappings(cx: &mut TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+        load(
+            &mut cx,
+            indoc! {r#"
+                let mapleader = " "
+                nnoremap j k
+                nnoremap k j
+                nmap <leader>j jj
+                nnoremap <leader>d dd
+                inoremap jk <Esc>
+                nnoremap x <Nop>
+            "#},
+        );
+
+        cx.set_state("one\ntwo\nˇthree\nfour", Mode::Normal);
+        cx.simulate_keystrokes("j");
+        cx.assert_state("one\nˇtwo\nthree\nfour", Mode::Normal);
+        cx.simulate_keystrokes("k");
+        cx.assert_state("one\ntwo\nˇthree\nfour", Mode::Normal);
+
+        // `j` is remapped to `k` when typed by a recursive mapping.
+        cx.simulate_keystrokes("space j");
+        cx.assert_state("ˇone\ntwo\nthree\nfour", Mode::Normal);
+
+        cx.simulate_keystrokes("space d");
+        cx.assert_state("ˇtwo\nthree\nfour", Mode::Normal);
+
+        cx.simulate_keystrokes("x");
+        cx.assert_state("ˇtwo\nthree\nfour", Mode::Normal);
+
+        cx.simulate_keystrokes("i a j k");
+        cx.assert_state("ˇatwo\nthree\nfour", Mode::Normal);
+    }
+
+    #[gpui::test]
+    async fn test_noremap_types_default_bindings(cx: &mut TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+        load(
+            &mut cx,
+            indoc! {r#"
+                nnoremap w b
+                nnoremap Y y$
+            "#},
+        );
+
+        cx.set_state("one two ˇthree", Mode::Normal);
+        cx.simulate_keystrokes("w");
+        cx.assert_state("one ˇtwo three", Mode::Normal);
+        cx.simulate_keystrokes("shift-y $ p");
+        cx.assert_state("one two threetwo threˇe", Mode::Normal);
+    }
+
+    #[gpui::test]
+    async fn test_runtime_definitions(cx: &mut TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state("ˇone\ntwo", Mode::Normal);
+        cx.simulate_keystrokes(": n n o r e m a p space g o space : 2 < C R > enter");
+        cx.simulate_keystrokes("g o");
+        cx.assert_state("one\nˇtwo", Mode::Normal);
+
+        cx.simulate_keystrokes(
+            ": c o m m a n d space - n a r g s = 1 space D space : < a r g s > d enter",
+        );
+        cx.simulate_keystrokes(": D space 1 enter");
+        cx.assert_state("ˇtwo", Mode::Normal);
+    }
+}
@@ -25,11 +25,9 @@ impl Settings for VimModeSetting {
 
     fn load(sources: SettingsSources<Self::FileContent>, _: &mut App) -> Result<Self> {
         Ok(Self(
-            sources
-                .user
-                .or(sources.server)
-                .copied()
-                .flatten()
+            [sources.profile, sources.user, sources.server]
+                .into_iter()
+                .find_map(|value| value.copied().flatten())
                 .unwrap_or(sources.default.ok_or_else(Self::missing_default)?),
         ))
     }
@@ -38,3 +36,41 @@ impl Settings for VimModeSetting {
         // TODO: could possibly check if any of the `vim.<foo>` keys are set?
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use settings::SettingsStore;
+
+    #[gpui::test]
+    fn test_vim_mode_in_profile(cx: &mut App) {
+        let mut store = SettingsStore::new(cx);
+        store.register_setting::<VimModeSetting>(cx);
+        store
+            .set_default_settings(r#"{ "vim_mode": false }"#, cx)
+            .unwrap();
+        store
+            .set_user_settings(
+                r#"{
+                    "vim_mode": true,
+                    "profiles": {
+                        "presenting": { "vim_mode": false },
+                        "pairing": { "buffer_font_size": 16 }
+                    }
+                }"#,
+                cx,
+            )
+            .unwrap();
+        assert!(store.get::<VimModeSetting>(None).0);
+
+        store.set_active_profile(Some("presenting".into()), cx);
+        assert!(!store.get::<VimModeSetting>(None).0);
+
+        // A profile that doesn't set vim_mode keeps the user's setting.
+        store.set_active_profile(Some("pairing".into()), cx);
+        assert!(store.get::<VimModeSetting>(None).0);
+
+        store.set_active_profile(None, cx);
+        assert!(store.get::<VimModeSetting>(None).0);
+    }
+}
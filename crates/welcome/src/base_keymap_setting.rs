@@ -99,6 +99,9 @@ impl Settings for BaseKeymap {
         sources: SettingsSources<Self::FileContent>,
         _: &mut gpui::App,
     ) -> anyhow::Result<Self> {
+        if let Some(Some(profile_value)) = sources.profile.copied() {
+            return Ok(profile_value);
+        }
         if let Some(Some(user_value)) = sources.user.copied() {
             return Ok(user_value);
         }
@@ -13,7 +13,10 @@ use client::DevServerProjectId;
 use db::{define_connection, query, sqlez::connection::Connection, sqlez_macros::sql};
 use gpui::{Axis, Bounds, Task, WindowBounds, WindowId, point, size};
 use itertools::Itertools;
-use project::debugger::breakpoint_store::{BreakpointState, SourceBreakpoint};
+use project::{
+    bookmark_store::SerializedBookmark,
+    debugger::breakpoint_store::{BreakpointState, SourceBreakpoint},
+};
 
 use language::{LanguageName, Toolchain};
 use project::WorktreeId;
@@ -336,6 +339,13 @@ define_connection! {
     //      kind: int, // The kind of breakpoint (standard, log)
     //      log_message: String, // log message for log breakpoints, otherwise it's Null
     // )
+    //
+    // CREATE TABLE bookmarks(
+    //      workspace_id: usize Foreign Key, // References workspace table
+    //      path: PathBuf, // The absolute path of the file that this bookmark belongs to
+    //      bookmark_location: u32, // The row of the bookmark
+    //      name: String, // The name of the bookmark, or Null if it's anonymous
+    // )
     pub static ref DB: WorkspaceDb<()> =
     &[
         sql!(
@@ -542,6 +552,17 @@ define_connection! {
         ALTER TABLE breakpoints ADD COLUMN condition TEXT;
         ALTER TABLE breakpoints ADD COLUMN hit_condition TEXT;
     ),
+    sql!(
+        CREATE TABLE bookmarks (
+            workspace_id INTEGER NOT NULL,
+            path TEXT NOT NULL,
+            bookmark_location INTEGER NOT NULL,
+            name TEXT,
+            FOREIGN KEY(workspace_id) REFERENCES workspaces(workspace_id)
+            ON DELETE CASCADE
+            ON UPDATE CASCADE
+        );
+    ),
     ];
 }
 
@@ -630,6 +651,7 @@ impl WorkspaceDb {
             docks,
             session_id: None,
             breakpoints: self.breakpoints(workspace_id),
+            bookmarks: self.bookmarks(workspace_id),
             window_id,
         })
     }
@@ -684,6 +706,7 @@ impl WorkspaceDb {
             window_bounds,
             centered_layout: centered_layout.unwrap_or(false),
             breakpoints: self.breakpoints(workspace_id),
+            bookmarks: self.bookmarks(workspace_id),
             display,
             docks,
             session_id: None,
@@ -737,6 +760,38 @@ impl WorkspaceDb {
         }
     }
 
+    fn bookmarks(&self, workspace_id: WorkspaceId) -> BTreeMap<Arc<Path>, Vec<SerializedBookmark>> {
+        let bookmarks: Result<Vec<(PathBuf, u32, Option<String>)>> = self
+            .select_bound(sql! {
+                SELECT path, bookmark_location, name
+                FROM bookmarks
+                WHERE workspace_id = ?
+                ORDER BY path, bookmark_location
+            })
+            .and_then(|mut prepared_statement| (prepared_statement)(workspace_id));
+
+        match bookmarks {
+            Ok(bookmarks) => {
+                let mut map: BTreeMap<Arc<Path>, Vec<SerializedBookmark>> = Default::default();
+                for (path, row, name) in bookmarks {
+                    let path: Arc<Path> = path.into();
+                    map.entry(path.clone())
+                        .or_default()
+                        .push(SerializedBookmark {
+                            path,
+                            row,
+                            name: name.map(Arc::from),
+                        });
+                }
+                map
+            }
+            Err(msg) => {
+                log::error!("Bookmarks query failed with msg: {msg}");
+                Default::default()
+            }
+        }
+    }
+
     /// Saves a workspace using the worktree roots. Will garbage collect any workspaces
     /// that used this workspace previously
     pub(crate) async fn save_workspace(&self, workspace: SerializedWorkspace) {
@@ -779,6 +834,25 @@ impl WorkspaceDb {
 
                 }
 
+                conn.exec_bound(sql!(DELETE FROM bookmarks WHERE workspace_id = ?1))?(workspace.id).context("Clearing old bookmarks")?;
+
+                for (path, bookmarks) in workspace.bookmarks {
+                    for bookmark in bookmarks {
+                        if let Err(err) = conn.exec_bound(sql!(
+                            INSERT INTO bookmarks (workspace_id, path, bookmark_location, name)
+                            VALUES (?1, ?2, ?3, ?4);))?
+
+                        ((
+                            workspace.id,
+                            path.as_ref(),
+                            bookmark.row,
+                            bookmark.name,
+                        )) {
+                            log::error!("{err}");
+                        }
+                    }
+                }
+
 
                 match workspace.location {
                     SerializedWorkspaceLocation::Local(local_paths, local_paths_order) => {
@@ -1570,6 +1644,7 @@ mod tests {
                 );
                 map
             },
+            bookmarks: Default::default(),
             session_id: None,
             window_id: None,
         };
@@ -1649,6 +1724,69 @@ mod tests {
         assert_eq!(loaded_breakpoints[4].path, Arc::from(path));
     }
 
+    #[gpui::test]
+    async fn test_bookmarks() {
+        env_logger::try_init().ok();
+
+        let db = WorkspaceDb::open_test_db("test_bookmarks").await;
+        let id = db.next_id().await.unwrap();
+
+        let path: Arc<Path> = Arc::from(Path::new("/tmp/test_bookmarks.rs"));
+        let other_path: Arc<Path> = Arc::from(Path::new("/tmp/other.rs"));
+
+        let mut workspace = SerializedWorkspace {
+            id,
+            location: SerializedWorkspaceLocation::from_local_paths(["/tmp"]),
+            center_group: Default::default(),
+            window_bounds: Default::default(),
+            display: Default::default(),
+            docks: Default::default(),
+            centered_layout: false,
+            breakpoints: Default::default(),
+            bookmarks: {
+                let mut map = collections::BTreeMap::default();
+                map.insert(
+                    path.clone(),
+                    vec![
+                        SerializedBookmark {
+                            path: path.clone(),
+                            row: 3,
+                            name: None,
+                        },
+                        SerializedBookmark {
+                            path: path.clone(),
+                            row: 42,
+                            name: Some("entry point".into()),
+                        },
+                    ],
+                );
+                map.insert(
+                    other_path.clone(),
+                    vec![SerializedBookmark {
+                        path: other_path.clone(),
+                        row: 0,
+                        name: None,
+                    }],
+                );
+                map
+            },
+            session_id: None,
+            window_id: None,
+        };
+
+        db.save_workspace(workspace.clone()).await;
+
+        let loaded = db.workspace_for_roots(&["/tmp"]).unwrap();
+        assert_eq!(loaded.bookmarks, workspace.bookmarks);
+
+        workspace.bookmarks.remove(&other_path);
+        db.save_workspace(workspace.clone()).await;
+
+        let loaded = db.workspace_for_roots(&["/tmp"]).unwrap();
+        assert_eq!(loaded.bookmarks.len(), 1);
+        assert_eq!(loaded.bookmarks, workspace.bookmarks);
+    }
+
     #[gpui::test]
     async fn test_remove_last_breakpoint() {
         env_logger::try_init().ok();
@@ -1689,6 +1827,7 @@ mod tests {
                 );
                 map
             },
+            bookmarks: Default::default(),
             session_id: None,
             window_id: None,
         };
@@ -1721,6 +1860,7 @@ mod tests {
             docks: Default::default(),
             centered_layout: false,
             breakpoints: collections::BTreeMap::default(),
+            bookmarks: Default::default(),
             session_id: None,
             window_id: None,
         };
@@ -1815,6 +1955,7 @@ mod tests {
             docks: Default::default(),
             centered_layout: false,
             breakpoints: Default::default(),
+            bookmarks: Default::default(),
             session_id: None,
             window_id: None,
         };
@@ -1828,6 +1969,7 @@ mod tests {
             docks: Default::default(),
             centered_layout: false,
             breakpoints: Default::default(),
+            bookmarks: Default::default(),
             session_id: None,
             window_id: None,
         };
@@ -1933,6 +2075,7 @@ mod tests {
             center_group,
             window_bounds: Default::default(),
             breakpoints: Default::default(),
+            bookmarks: Default::default(),
             display: Default::default(),
             docks: Default::default(),
             centered_layout: false,
@@ -1968,6 +2111,7 @@ mod tests {
             center_group: Default::default(),
             window_bounds: Default::default(),
             breakpoints: Default::default(),
+            bookmarks: Default::default(),
             display: Default::default(),
             docks: Default::default(),
             centered_layout: false,
@@ -1984,6 +2128,7 @@ mod tests {
             docks: Default::default(),
             centered_layout: false,
             breakpoints: Default::default(),
+            bookmarks: Default::default(),
             session_id: None,
             window_id: Some(2),
         };
@@ -2024,6 +2169,7 @@ mod tests {
             center_group: Default::default(),
             window_bounds: Default::default(),
             breakpoints: Default::default(),
+            bookmarks: Default::default(),
             display: Default::default(),
             docks: Default::default(),
             centered_layout: false,
@@ -2064,6 +2210,7 @@ mod tests {
             docks: Default::default(),
             centered_layout: false,
             breakpoints: Default::default(),
+            bookmarks: Default::default(),
             session_id: Some("session-id-1".to_owned()),
             window_id: Some(10),
         };
@@ -2077,6 +2224,7 @@ mod tests {
             docks: Default::default(),
             centered_layout: false,
             breakpoints: Default::default(),
+            bookmarks: Default::default(),
             session_id: Some("session-id-1".to_owned()),
             window_id: Some(20),
         };
@@ -2090,6 +2238,7 @@ mod tests {
             docks: Default::default(),
             centered_layout: false,
             breakpoints: Default::default(),
+            bookmarks: Default::default(),
             session_id: Some("session-id-2".to_owned()),
             window_id: Some(30),
         };
@@ -2103,6 +2252,7 @@ mod tests {
             docks: Default::default(),
             centered_layout: false,
             breakpoints: Default::default(),
+            bookmarks: Default::default(),
             session_id: None,
             window_id: None,
         };
@@ -2121,6 +2271,7 @@ mod tests {
             docks: Default::default(),
             centered_layout: false,
             breakpoints: Default::default(),
+            bookmarks: Default::default(),
             session_id: Some("session-id-2".to_owned()),
             window_id: Some(50),
         };
@@ -2134,6 +2285,7 @@ mod tests {
             center_group: Default::default(),
             window_bounds: Default::default(),
             breakpoints: Default::default(),
+            bookmarks: Default::default(),
             display: Default::default(),
             docks: Default::default(),
             centered_layout: false,
@@ -2192,6 +2344,7 @@ mod tests {
             display: Default::default(),
             docks: Default::default(),
             breakpoints: Default::default(),
+            bookmarks: Default::default(),
             centered_layout: false,
             session_id: None,
             window_id: None,
@@ -2240,6 +2393,7 @@ mod tests {
             centered_layout: false,
             session_id: Some("one-session".to_owned()),
             breakpoints: Default::default(),
+            bookmarks: Default::default(),
             window_id: Some(window_id),
         })
         .collect::<Vec<_>>();
@@ -2333,6 +2487,7 @@ mod tests {
             centered_layout: false,
             session_id: Some("one-session".to_owned()),
             breakpoints: Default::default(),
+            bookmarks: Default::default(),
             window_id: Some(window_id),
         })
         .collect::<Vec<_>>();
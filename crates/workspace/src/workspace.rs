@@ -68,7 +68,7 @@ pub use persistence::{
 use postage::stream::Stream;
 use project::{
     DirectoryLister, Project, ProjectEntryId, ProjectPath, ResolvedPath, Worktree, WorktreeId,
-    debugger::breakpoint_store::BreakpointStoreEvent,
+    bookmark_store::BookmarkStoreEvent, debugger::breakpoint_store::BreakpointStoreEvent,
 };
 use remote::{SshClientDelegate, SshConnectionOptions, ssh_session::ConnectionIdentifier};
 use schemars::JsonSchema;
@@ -260,6 +260,17 @@ pub struct ToggleFileFinder {
 
 impl_action_as!(file_finder, ToggleFileFinder as Toggle);
 
+#[derive(Default, PartialEq, Eq, Clone, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct ToggleTabSwitcher {
+    #[serde(default)]
+    pub select_last: bool,
+}
+
+impl_action_as!(tab_switcher, ToggleTabSwitcher as Toggle);
+
+action_as!(diagnostics, DeployDiagnostics as Deploy);
+
 impl_actions!(
     workspace,
     [
@@ -1082,6 +1093,18 @@ impl Workspace {
         )
         .detach();
 
+        cx.subscribe_in(
+            &project.read(cx).bookmark_store(),
+            window,
+            |workspace, _, event, window, cx| match event {
+                BookmarkStoreEvent::BookmarksUpdated(_)
+                | BookmarkStoreEvent::BookmarksCleared(_) => {
+                    workspace.serialize_workspace(window, cx);
+                }
+            },
+        )
+        .detach();
+
         cx.on_focus_lost(window, |this, window, cx| {
             let focus_handle = this.focus_handle(cx);
             window.focus(&focus_handle);
@@ -4989,6 +5012,9 @@ impl Workspace {
             let breakpoints = self.project.update(cx, |project, cx| {
                 project.breakpoint_store().read(cx).all_breakpoints(cx)
             });
+            let bookmarks = self.project.update(cx, |project, cx| {
+                project.bookmark_store().read(cx).serialized_bookmarks(cx)
+            });
 
             let center_group = build_serialized_pane_group(&self.center.root, window, cx);
             let docks = build_serialized_docks(self, window, cx);
@@ -5003,6 +5029,7 @@ impl Workspace {
                 centered_layout: self.centered_layout,
                 session_id: self.session_id.clone(),
                 breakpoints,
+                bookmarks,
                 window_id: Some(window.window_handle().window_id().as_u64()),
             };
 
@@ -5180,6 +5207,14 @@ impl Workspace {
                 })?
                 .await;
 
+            let _ = project
+                .update(cx, |project, cx| {
+                    project.bookmark_store().update(cx, |bookmark_store, cx| {
+                        bookmark_store.with_serialized_bookmarks(serialized_workspace.bookmarks, cx)
+                    })
+                })?
+                .await;
+
             // Clean up all the items that have _not_ been loaded. Our ItemIds aren't stable. That means
             // after loading the items, we might have different items and in order to avoid
             // the database filling up, we delete items that haven't been loaded now.
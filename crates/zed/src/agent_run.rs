@@ -0,0 +1,370 @@
+use std::io::{self, Read as _, Write as _};
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use agent::{HeadlessEvent, HeadlessOutcome, HeadlessRun, ThreadStore};
+use anyhow::{Context as _, Result, anyhow};
+use assistant_settings::{AgentProfileId, AssistantSettings};
+use assistant_tool::ToolWorkingSet;
+use cli::AgentRunArgs;
+use client::{Client, ProxySettings, UserStore};
+use extension::ExtensionHostProxy;
+use fs::{Fs, RealFs};
+use gpui::{
+    App, AppContext as _, Application, AsyncApp, Entity, SemanticVersion, UpdateGlobal as _,
+};
+use gpui_tokio::Tokio;
+use http_client::{Url, read_proxy_from_env};
+use language::LanguageRegistry;
+use language_model::{
+    ConfiguredModel, LanguageModelProviderId, LanguageModelRegistry, ZED_CLOUD_PROVIDER_ID,
+};
+use node_runtime::{NodeBinaryOptions, NodeRuntime};
+use project::Project;
+use project::project_settings::ProjectSettings;
+use prompt_store::PromptBuilder;
+use release_channel::AppVersion;
+use reqwest_client::ReqwestClient;
+use settings::{Settings as _, SettingsStore};
+use util::ResultExt as _;
+
+/// Runs the agent as requested by the `--agent-run` options and exits the
+/// process.
+pub fn main(args: AgentRunArgs, app_version: SemanticVersion) {
+    // Stdout is for the run's events, and Zed's log belongs to the running editor.
+    zlog::init();
+    zlog::init_output_stderr();
+
+    let prompt = match read_prompt(&args) {
+        Ok(prompt) => prompt,
+        Err(error) => exit_with_error(error),
+    };
+
+    Application::headless().run(move |cx| {
+        release_channel::init(app_version, cx);
+        gpui_tokio::init(cx);
+        let app_state = init(cx);
+
+        cx.spawn(async move |cx| {
+            let exit_code = match run(args, prompt, app_state, cx).await {
+                Ok(outcome) => outcome.exit_code(),
+                Err(error) => {
+                    print_event(&HeadlessEvent::Error {
+                        message: format!("{error:#}"),
+                    });
+                    2
+                }
+            };
+            cx.update(|cx| cx.quit()).ok();
+            std::process::exit(exit_code);
+        })
+        .detach();
+    });
+}
+
+struct AgentAppState {
+    client: Arc<Client>,
+    user_store: Entity<UserStore>,
+    languages: Arc<LanguageRegistry>,
+    fs: Arc<dyn Fs>,
+    node_runtime: NodeRuntime,
+    prompt_builder: Arc<PromptBuilder>,
+}
+
+fn init(cx: &mut App) -> AgentAppState {
+    let mut settings_store = SettingsStore::new(cx);
+    settings_store
+        .set_default_settings(settings::default_settings().as_ref(), cx)
+        .unwrap();
+    cx.set_global(settings_store);
+    client::init_settings(cx);
+
+    let user_agent = format!(
+        "Zed/{} ({}; {})",
+        AppVersion::global(cx),
+        std::env::consts::OS,
+        std::env::consts::ARCH
+    );
+    let proxy_str = ProxySettings::get_global(cx).proxy.to_owned();
+    let proxy_url = proxy_str
+        .as_ref()
+        .and_then(|input| input.parse::<Url>().ok())
+        .or_else(read_proxy_from_env);
+    let http = {
+        let _guard = Tokio::handle(cx).enter();
+
+        ReqwestClient::proxy_and_user_agent(proxy_url, &user_agent)
+            .expect("could not start HTTP client")
+    };
+    cx.set_http_client(Arc::new(http));
+
+    Project::init_settings(cx);
+
+    let client = Client::production(cx);
+    cx.set_http_client(client.http_client());
+
+    let fs: Arc<dyn Fs> = Arc::new(RealFs::new(None, cx.background_executor().clone()));
+
+    let mut languages = LanguageRegistry::new(cx.background_executor().clone());
+    languages.set_language_server_download_dir(paths::languages_dir().clone());
+    let languages = Arc::new(languages);
+
+    let user_store = cx.new(|cx| UserStore::new(client.clone(), cx));
+
+    extension::init(cx);
+
+    let (tx, rx) = async_watch::channel(None);
+    cx.observe_global::<SettingsStore>(move |cx| {
+        let settings = &ProjectSettings::get_global(cx).node;
+        let options = NodeBinaryOptions {
+            allow_path_lookup: !settings.ignore_system_version,
+            allow_binary_download: true,
+            use_paths: settings.path.as_ref().map(|node_path| {
+                let node_path = PathBuf::from(shellexpand::tilde(node_path).as_ref());
+                let npm_path = settings
+                    .npm_path
+                    .as_ref()
+                    .map(|path| PathBuf::from(shellexpand::tilde(&path).as_ref()));
+                (
+                    node_path.clone(),
+                    npm_path.unwrap_or_else(|| {
+                        let base_path = PathBuf::new();
+                        node_path.parent().unwrap_or(&base_path).join("npm")
+                    }),
+                )
+            }),
+        };
+        tx.send(Some(options)).log_err();
+    })
+    .detach();
+    let node_runtime = NodeRuntime::new(client.http_client(), None, rx);
+
+    let extension_host_proxy = ExtensionHostProxy::global(cx);
+
+    language::init(cx);
+    language_extension::init(extension_host_proxy, languages.clone());
+    language_model::init(client.clone(), cx);
+    language_models::init(user_store.clone(), client.clone(), fs.clone(), cx);
+    languages::init(languages.clone(), node_runtime.clone(), cx);
+    prompt_store::init(cx);
+    let prompt_builder = PromptBuilder::load(fs.clone(), false, cx);
+    agent::init(
+        fs.clone(),
+        client.clone(),
+        prompt_builder.clone(),
+        languages.clone(),
+        cx,
+    );
+    assistant_tools::init(client.http_client(), cx);
+
+    if let Ok(user_settings) = std::fs::read_to_string(paths::settings_file()) {
+        SettingsStore::update_global(cx, |store, cx| store.set_user_settings(&user_settings, cx))
+            .context("failed to load user settings")
+            .log_err();
+    }
+
+    AgentAppState {
+        client,
+        user_store,
+        languages,
+        fs,
+        node_runtime,
+        prompt_builder,
+    }
+}
+
+async fn run(
+    args: AgentRunArgs,
+    prompt: String,
+    app_state: AgentAppState,
+    cx: &mut AsyncApp,
+) -> Result<HeadlessOutcome> {
+    let project_path = match args.project {
+        Some(path) => path,
+        None => std::env::current_dir()?,
+    };
+    let project_path = project_path
+        .canonicalize()
+        .with_context(|| format!("project directory {project_path:?} doesn't exist"))?;
+
+    // Permissions and the terminal sandbox follow the selected profile, so
+    // the requested profile has to become the selected one.
+    let profile_id = cx.update(|cx| -> Result<AgentProfileId> {
+        let mut settings = AssistantSettings::get_global(cx).clone();
+        if let Some(profile) = args.profile {
+            settings.default_profile = AgentProfileId(profile.into());
+        }
+        if !settings.profiles.contains_key(&settings.default_profile) {
+            return Err(anyhow!(
+                "no agent profile named {:?}. Available profiles: {}",
+                settings.default_profile.0,
+                settings
+                    .profiles
+                    .keys()
+                    .map(|id| id.0.as_ref())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ));
+        }
+        let profile_id = settings.default_profile.clone();
+        AssistantSettings::override_global(settings, cx);
+        Ok(profile_id)
+    })??;
+
+    let model = select_model(args.model.as_deref(), &app_state.client, cx).await?;
+
+    let project = cx.update(|cx| {
+        Project::local(
+            app_state.client.clone(),
+            app_state.node_runtime.clone(),
+            app_state.user_store.clone(),
+            app_state.languages.clone(),
+            app_state.fs.clone(),
+            None,
+            cx,
+        )
+    })?;
+    let worktree = project
+        .update(cx, |project, cx| {
+            project.create_worktree(&project_path, true, cx)
+        })?
+        .await?;
+    worktree
+        .update(cx, |worktree, _| {
+            worktree.as_local().map(|worktree| worktree.scan_complete())
+        })?
+        .context("project isn't local")?
+        .await;
+
+    let tools = cx.new(|_| ToolWorkingSet::default())?;
+    let thread_store = cx
+        .update(|cx| {
+            ThreadStore::load(
+                project.clone(),
+                tools,
+                None,
+                app_state.prompt_builder.clone(),
+                cx,
+            )
+        })?
+        .await?;
+    let thread = thread_store.update(cx, |thread_store, cx| {
+        thread_store.load_profile_by_id(profile_id, cx);
+        thread_store.create_thread(cx)
+    })?;
+
+    let result = agent::run_headless(
+        thread,
+        HeadlessRun {
+            prompt,
+            model,
+            max_turns: args.max_turns.unwrap_or(50),
+            approve_confirmations: args.approve_confirmations,
+        },
+        |event| print_event(&event),
+        cx,
+    )
+    .await?;
+
+    if let Some(patch_file) = args.patch_file {
+        std::fs::write(&patch_file, result.patch.unwrap_or_default())
+            .with_context(|| format!("failed to write patch to {patch_file:?}"))?;
+    }
+
+    Ok(result.outcome)
+}
+
+async fn select_model(
+    model: Option<&str>,
+    client: &Arc<Client>,
+    cx: &mut AsyncApp,
+) -> Result<ConfiguredModel> {
+    let (provider_id, model_id) = match model {
+        Some(model) => {
+            let (provider, model) = model
+                .split_once('/')
+                .context("models must be given as `provider/model`")?;
+            (provider.to_string(), model.to_string())
+        }
+        None => cx.update(|cx| {
+            let selection = &AssistantSettings::get_global(cx).default_model;
+            (selection.provider.0.clone(), selection.model.clone())
+        })?,
+    };
+
+    let provider = cx
+        .update(|cx| {
+            LanguageModelRegistry::read_global(cx)
+                .provider(&LanguageModelProviderId(provider_id.clone().into()))
+        })?
+        .with_context(|| format!("no language model provider named {provider_id:?}"))?;
+
+    if provider.id().0 == ZED_CLOUD_PROVIDER_ID {
+        // Signing in would otherwise open a browser.
+        if !client.has_credentials(cx).await {
+            return Err(anyhow!("sign in to Zed before using its hosted models"));
+        }
+        client
+            .authenticate_and_connect(true, cx)
+            .await
+            .into_response()?;
+    }
+    cx.update(|cx| provider.authenticate(cx))?
+        .await
+        .map_err(|error| anyhow!("failed to authenticate with {provider_id:?}: {error}"))?;
+
+    let model = cx
+        .update(|cx| {
+            provider
+                .provided_models(cx)
+                .into_iter()
+                .find(|model| model.id().0 == model_id)
+        })?
+        .with_context(|| format!("no model named {model_id:?} in {provider_id:?}"))?;
+
+    cx.update(|cx| {
+        LanguageModelRegistry::global(cx).update(cx, |registry, cx| {
+            registry.set_default_model(
+                Some(ConfiguredModel {
+                    provider: provider.clone(),
+                    model: model.clone(),
+                }),
+                cx,
+            );
+        })
+    })?;
+
+    Ok(ConfiguredModel { provider, model })
+}
+
+fn read_prompt(args: &AgentRunArgs) -> Result<String> {
+    match (&args.prompt, &args.prompt_file) {
+        (Some(prompt), _) => Ok(prompt.clone()),
+        (None, Some(path)) if path.as_os_str() == "-" => {
+            let mut prompt = String::new();
+            io::stdin().read_to_string(&mut prompt)?;
+            Ok(prompt)
+        }
+        (None, Some(path)) => std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read prompt from {path:?}")),
+        (None, None) => Err(anyhow!(
+            "either --agent-run or --agent-run-file is required"
+        )),
+    }
+}
+
+fn print_event(event: &HeadlessEvent) {
+    let Some(line) = serde_json::to_string(event).log_err() else {
+        return;
+    };
+    let mut stdout = io::stdout().lock();
+    writeln!(stdout, "{line}").ok();
+    stdout.flush().ok();
+}
+
+fn exit_with_error(error: anyhow::Error) -> ! {
+    print_event(&HeadlessEvent::Error {
+        message: format!("{error:#}"),
+    });
+    std::process::exit(2);
+}
@@ -1,6 +1,7 @@
 // Disable command line from opening on release mode
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
+mod agent_run;
 mod reliability;
 mod zed;
 
@@ -174,7 +175,7 @@ fn main() {
         return;
     }
 
-    let args = Args::parse();
+    let mut args = Args::parse();
 
     if let Some(socket) = &args.askpass {
         askpass::main(socket);
@@ -204,6 +205,11 @@ fn main() {
         return;
     }
 
+    if args.agent.is_requested() {
+        agent_run::main(args.agent, AppVersion::load(env!("CARGO_PKG_VERSION")));
+        return;
+    }
+
     zlog::init();
     if stdout_is_a_pty() {
         zlog::init_output_stdout();
@@ -304,6 +310,11 @@ fn main() {
         fs.clone(),
         paths::keymap_file().clone(),
     );
+    let user_vimrc_file_rx = watch_config_file(
+        &app.background_executor(),
+        fs.clone(),
+        paths::vimrc_file().clone(),
+    );
 
     let (shell_env_loaded_tx, shell_env_loaded_rx) = oneshot::channel();
     if !stdout_is_a_pty() {
@@ -351,7 +362,7 @@ fn main() {
             cx,
             handle_settings_changed,
         );
-        handle_keymap_file_changes(user_keymap_file_rx, cx);
+        handle_keymap_file_changes(user_keymap_file_rx, user_vimrc_file_rx, cx);
         client::init_settings(cx);
         let user_agent = format!(
             "Zed/{} ({}; {})",
@@ -506,6 +517,7 @@ fn main() {
         );
         web_search::init(cx);
         web_search_providers::init(app_state.client.clone(), cx);
+        semantic_index::init(app_state.client.http_client(), cx);
         snippet_provider::init(cx);
         inline_completion_registry::init(
             app_state.client.clone(),
@@ -548,6 +560,7 @@ fn main() {
         tab_switcher::init(cx);
         outline::init(cx);
         project_symbols::init(cx);
+        bookmarks::init(cx);
         project_panel::init(cx);
         outline_panel::init(cx);
         tasks_ui::init(cx);
@@ -555,6 +568,7 @@ fn main() {
         channel::init(&app_state.client.clone(), app_state.user_store.clone(), cx);
         search::init(cx);
         vim::init(cx);
+        keyboard_macros::init(cx);
         terminal_view::init(cx);
         journal::init(app_state.clone(), cx);
         language_selector::init(cx);
@@ -569,6 +583,7 @@ fn main() {
         feedback::init(cx);
         markdown_preview::init(cx);
         welcome::init(cx);
+        settings_profile_selector::init(cx);
         settings_ui::init(cx);
         extensions_ui::init(cx);
         zeta::init(cx);
@@ -1045,6 +1060,9 @@ struct Args {
     #[cfg(target_os = "windows")]
     #[arg(hide = true)]
     dock_action: Option<usize>,
+
+    #[command(flatten)]
+    agent: cli::AgentRunArgs,
 }
 
 #[derive(Clone, Debug)]
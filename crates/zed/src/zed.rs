@@ -97,6 +97,15 @@ actions!(
     ]
 );
 
+const INITIAL_VIMRC_CONTENT: &str = r#"" Mappings and commands for vim mode, written as in a vimrc.
+" For example:
+"
+"   let mapleader = " "
+"   nnoremap <leader>w :w<CR>
+"   inoremap jk <Esc>
+"   command! -nargs=1 Replace %s/<args>/g
+"#;
+
 pub fn init(cx: &mut App) {
     #[cfg(target_os = "macos")]
     cx.on_action(|_: &Hide, cx| cx.hide());
@@ -235,6 +244,7 @@ pub fn initialize_workspace(
         let active_toolchain_language =
             cx.new(|cx| toolchain_selector::ActiveToolchain::new(workspace, window, cx));
         let vim_mode_indicator = cx.new(|cx| vim::ModeIndicator::new(window, cx));
+        let macro_recording_indicator = cx.new(keyboard_macros::RecordingIndicator::new);
         let image_info = cx.new(|_cx| ImageInfo::new(workspace));
         let cursor_position =
             cx.new(|_| go_to_line::cursor_position::CursorPosition::new(workspace));
@@ -246,6 +256,7 @@ pub fn initialize_workspace(
             status_bar.add_right_item(active_buffer_language, window, cx);
             status_bar.add_right_item(active_toolchain_language, window, cx);
             status_bar.add_right_item(vim_mode_indicator, window, cx);
+            status_bar.add_right_item(macro_recording_indicator, window, cx);
             status_bar.add_right_item(cursor_position, window, cx);
             status_bar.add_right_item(image_info, window, cx);
         });
@@ -680,6 +691,16 @@ fn register_actions(
                 );
             },
         )
+        .register_action(
+            move |_: &mut Workspace, _: &zed_actions::vim::OpenVimrc, window, cx| {
+                open_settings_file(
+                    paths::vimrc_file(),
+                    || INITIAL_VIMRC_CONTENT.into(),
+                    window,
+                    cx,
+                );
+            },
+        )
         .register_action(move |_: &mut Workspace, _: &OpenSettings, window, cx| {
             open_settings_file(
                 paths::settings_file(),
@@ -1178,6 +1199,7 @@ pub fn handle_settings_file_changes(
 
 pub fn handle_keymap_file_changes(
     mut user_keymap_file_rx: mpsc::UnboundedReceiver<String>,
+    mut user_vimrc_file_rx: mpsc::UnboundedReceiver<String>,
     cx: &mut App,
 ) {
     BaseKeymap::register(cx);
@@ -1213,6 +1235,8 @@ pub fn handle_keymap_file_changes(
 
     struct KeymapParseErrorNotification;
     let notification_id = NotificationId::unique::<KeymapParseErrorNotification>();
+    struct VimrcLoadErrorNotification;
+    let vimrc_notification_id = NotificationId::unique::<VimrcLoadErrorNotification>();
 
     cx.spawn(async move |cx| {
         let mut user_keymap_content = String::new();
@@ -1221,6 +1245,19 @@ pub fn handle_keymap_file_changes(
             select_biased! {
                 _ = base_keymap_rx.next() => {},
                 _ = keyboard_layout_rx.next() => {},
+                content = user_vimrc_file_rx.next() => {
+                    if let Some(content) = content {
+                        cx.update(|cx| {
+                            let errors = vim::load_vimrc(&content, cx);
+                            if errors.is_empty() {
+                                dismiss_app_notification(&vimrc_notification_id, cx);
+                            } else {
+                                show_vimrc_load_error(vimrc_notification_id.clone(), errors, cx);
+                            }
+                        })
+                        .ok();
+                    }
+                },
                 content = user_keymap_file_rx.next() => {
                     if let Some(content) = content {
                         if let Ok(Some(migrated_content)) = migrate_keymap(&content) {
@@ -1304,6 +1341,21 @@ fn show_keymap_file_load_error(
     )
 }
 
+fn show_vimrc_load_error(notification_id: NotificationId, errors: Vec<String>, cx: &mut App) {
+    let message: SharedString =
+        format!("Some vimrc lines failed to load.\n\n{}", errors.join("\n")).into();
+    show_app_notification(notification_id, cx, move |cx| {
+        cx.new(|cx| {
+            MessageNotification::new(message.clone(), cx)
+                .primary_message("Open Vimrc")
+                .primary_on_click(|window, cx| {
+                    window.dispatch_action(zed_actions::vim::OpenVimrc.boxed_clone(), cx);
+                    cx.emit(DismissEvent);
+                })
+        })
+    });
+}
+
 fn show_markdown_app_notification<F>(
     notification_id: NotificationId,
     message: MarkdownString,
@@ -1383,6 +1435,7 @@ pub fn load_default_keymap(cx: &mut App) {
 
     if VimModeSetting::get_global(cx).0 {
         cx.bind_keys(KeymapFile::load_asset(VIM_KEYMAP_PATH, cx).unwrap());
+        cx.bind_keys(vim::vimrc_key_bindings(cx));
     }
 }
 
@@ -3915,13 +3968,15 @@ mod tests {
                 app_state.fs.clone(),
                 PathBuf::from("/keymap.json"),
             );
+            let vimrc_rx =
+                watch_config_file(&executor, app_state.fs.clone(), PathBuf::from("/vimrc"));
             let global_settings_rx = watch_config_file(
                 &executor,
                 app_state.fs.clone(),
                 PathBuf::from("/global_settings.json"),
             );
             handle_settings_file_changes(settings_rx, global_settings_rx, cx, |_, _| {});
-            handle_keymap_file_changes(keymap_rx, cx);
+            handle_keymap_file_changes(keymap_rx, vimrc_rx, cx);
         });
         workspace
             .update(cx, |workspace, _, cx| {
@@ -4033,6 +4088,8 @@ mod tests {
                 app_state.fs.clone(),
                 PathBuf::from("/keymap.json"),
             );
+            let vimrc_rx =
+                watch_config_file(&executor, app_state.fs.clone(), PathBuf::from("/vimrc"));
 
             let global_settings_rx = watch_config_file(
                 &executor,
@@ -4040,7 +4097,7 @@ mod tests {
                 PathBuf::from("/global_settings.json"),
             );
             handle_settings_file_changes(settings_rx, global_settings_rx, cx, |_, _| {});
-            handle_keymap_file_changes(keymap_rx, cx);
+            handle_keymap_file_changes(keymap_rx, vimrc_rx, cx);
         });
 
         cx.background_executor.run_until_parked();
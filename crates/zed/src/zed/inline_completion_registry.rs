@@ -11,7 +11,9 @@ use supermaven::{Supermaven, SupermavenCompletionProvider};
 use ui::Window;
 use util::ResultExt;
 use workspace::Workspace;
-use zeta::{ProviderDataCollection, ZetaInlineCompletionProvider};
+use zeta::{
+    LanguageModelInlineCompletionProvider, ProviderDataCollection, ZetaInlineCompletionProvider,
+};
 
 pub fn init(client: Arc<Client>, user_store: Entity<UserStore>, cx: &mut App) {
     let editors: Rc<RefCell<HashMap<WeakEntity<Editor>, AnyWindowHandle>>> = Rc::default();
@@ -129,7 +131,8 @@ pub fn init(client: Arc<Client>, user_store: Entity<UserStore>, cx: &mut App) {
                         }
                         EditPredictionProvider::None
                         | EditPredictionProvider::Copilot
-                        | EditPredictionProvider::Supermaven => {}
+                        | EditPredictionProvider::Supermaven
+                        | EditPredictionProvider::LanguageModel => {}
                     }
                 }
             }
@@ -283,5 +286,25 @@ fn assign_edit_prediction_provider(
                 editor.set_edit_prediction_provider(Some(provider), window, cx);
             }
         }
+        EditPredictionProvider::LanguageModel => {
+            let workspace = window
+                .root::<Workspace>()
+                .flatten()
+                .map(|workspace| workspace.downgrade());
+
+            // Zeta keeps track of recent edits, which are included in the prompt.
+            let zeta = zeta::Zeta::register(workspace, None, client.clone(), user_store, cx);
+
+            if let Some(buffer) = &singleton_buffer {
+                if buffer.read(cx).file().is_some() {
+                    zeta.update(cx, |zeta, cx| {
+                        zeta.register_buffer(&buffer, cx);
+                    });
+                }
+            }
+
+            let provider = cx.new(|_| LanguageModelInlineCompletionProvider::new(zeta));
+            editor.set_edit_prediction_provider(Some(provider), window, cx);
+        }
     }
 }
@@ -160,6 +160,18 @@ pub mod feedback {
     actions!(feedback, [FileBugReport, GiveFeedback]);
 }
 
+pub mod vim {
+    use gpui::actions;
+
+    actions!(vim, [OpenVimrc]);
+}
+
+pub mod settings_profile_selector {
+    use gpui::actions;
+
+    actions!(settings_profile_selector, [Toggle]);
+}
+
 pub mod theme_selector {
     use gpui::impl_actions;
     use schemars::JsonSchema;
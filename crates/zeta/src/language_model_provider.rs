@@ -0,0 +1,428 @@
+use std::sync::Arc;
+
+use anyhow::{Context as _, Result, anyhow};
+use futures::StreamExt as _;
+use gpui::{App, Context, Entity, Task};
+use language::{
+    Buffer,
+    language_settings::{EditPredictionPromptFormat, all_language_settings},
+};
+use language_model::{
+    LanguageModel, LanguageModelId, LanguageModelProviderId, LanguageModelRegistry,
+    LanguageModelRequest, LanguageModelRequestMessage, Role,
+};
+use project::Project;
+use uuid::Uuid;
+use zed_llm_client::{PredictEditsBody, PredictEditsResponse};
+
+use crate::{
+    CURSOR_MARKER, CompletionRequests, EDITABLE_REGION_END_MARKER, EDITABLE_REGION_START_MARKER,
+    InlineCompletion, START_OF_FILE_MARKER, Zeta,
+};
+
+const ZETA_INSTRUCTIONS: &str = "You are a code completion assistant and your task is to analyze user edits and then rewrite an excerpt that the user provides, suggesting the appropriate edits within the excerpt, taking into account the cursor location.
+
+Respond with the rewritten excerpt only. Keep the <|editable_region_start|> and <|editable_region_end|> markers, and only change the code between them.";
+
+const FIM_INSTRUCTIONS: &str = "Fill in the code at <|user_cursor_is_here|> in the following excerpt. Respond with only the code that belongs at that position, without repeating the surrounding code or explaining it.";
+
+impl Zeta {
+    /// Requests a completion from the given language model instead of Zed's
+    /// hosted model. The prompt is built from the same excerpt and edit
+    /// history that would be sent to Zed.
+    pub fn request_completion_with_model(
+        &mut self,
+        project: Option<&Entity<Project>>,
+        buffer: &Entity<Buffer>,
+        position: language::Anchor,
+        model: Arc<dyn LanguageModel>,
+        prompt_format: EditPredictionPromptFormat,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Option<InlineCompletion>>> {
+        let async_cx = cx.to_async();
+        self.request_completion_impl(None, project, buffer, position, false, cx, move |params| {
+            let body = params.body;
+            let prompt = match prompt_format {
+                EditPredictionPromptFormat::Zeta => zeta_prompt(&body),
+                EditPredictionPromptFormat::Fim => fim_prompt(&body.input_excerpt),
+            };
+            let request = LanguageModelRequest {
+                thread_id: None,
+                prompt_id: None,
+                mode: None,
+                messages: vec![LanguageModelRequestMessage {
+                    role: Role::User,
+                    content: vec![prompt.into()],
+                    cache: false,
+                }],
+                tools: Vec::new(),
+                tool_choice: None,
+                stop: Vec::new(),
+                temperature: None,
+            };
+            let response = model.stream_completion_text(request, &async_cx);
+
+            async move {
+                let mut stream = response.await?.stream;
+                let mut output = String::new();
+                while let Some(chunk) = stream.next().await {
+                    output.push_str(&chunk?);
+                }
+
+                let output_excerpt = match prompt_format {
+                    EditPredictionPromptFormat::Zeta => output,
+                    EditPredictionPromptFormat::Fim => fim_output_excerpt(
+                        body.speculated_output.as_deref().unwrap_or_default(),
+                        &output,
+                    ),
+                };
+                let response = PredictEditsResponse {
+                    request_id: Uuid::new_v4(),
+                    output_excerpt,
+                };
+                Ok((response, None))
+            }
+        })
+    }
+}
+
+fn zeta_prompt(body: &PredictEditsBody) -> String {
+    format!(
+        "### Instruction:\n{ZETA_INSTRUCTIONS}\n\n### User Edits:\n\n{}\n\n### User Excerpt:\n\n{}\n\n### Response:\n",
+        body.input_events, body.input_excerpt
+    )
+}
+
+/// Builds a fill-in-the-middle prompt from a Zeta excerpt by removing every
+/// marker except the cursor.
+fn fim_prompt(input_excerpt: &str) -> String {
+    let excerpt = input_excerpt
+        .replace(&format!("{START_OF_FILE_MARKER}\n"), "")
+        .replace(&format!("{EDITABLE_REGION_START_MARKER}\n"), "")
+        .replace(&format!("\n{EDITABLE_REGION_END_MARKER}"), "");
+    format!("{FIM_INSTRUCTIONS}\n\n{excerpt}")
+}
+
+/// Inserts the text produced by a fill-in-the-middle prompt at the cursor of
+/// the editable region, so it can be diffed like a Zeta response.
+fn fim_output_excerpt(speculated_output: &str, output: &str) -> String {
+    speculated_output.replace(CURSOR_MARKER, strip_code_fence(output))
+}
+
+/// Chat models often wrap code in a fence even when asked not to.
+fn strip_code_fence(text: &str) -> &str {
+    let trimmed = text.trim();
+    let Some(rest) = trimmed.strip_prefix("```") else {
+        return text;
+    };
+    let Some((_, body)) = rest.split_once('\n') else {
+        return text;
+    };
+    body.strip_suffix("```")
+        .map(|body| body.strip_suffix('\n').unwrap_or(body))
+        .unwrap_or(body)
+}
+
+/// Resolves the model configured for language model edit predictions,
+/// authenticating its provider first if needed.
+fn configured_model(cx: &mut App) -> Task<Result<Arc<dyn LanguageModel>>> {
+    let settings = &all_language_settings(None, cx)
+        .edit_predictions
+        .language_model;
+    let registry = LanguageModelRegistry::read_global(cx);
+    let provider = match &settings.provider {
+        Some(provider_id) => {
+            registry.provider(&LanguageModelProviderId(provider_id.clone().into()))
+        }
+        None => registry.default_model().map(|model| model.provider),
+    };
+    let Some(provider) = provider else {
+        return Task::ready(Err(anyhow!(
+            "no language model provider is configured for edit predictions"
+        )));
+    };
+    let model_id = settings.model.clone().map(|id| LanguageModelId(id.into()));
+    let use_default_model = settings.provider.is_none() && model_id.is_none();
+
+    let authenticate = (!provider.is_authenticated(cx)).then(|| provider.authenticate(cx));
+    cx.spawn(async move |cx| {
+        if let Some(authenticate) = authenticate {
+            authenticate.await?;
+        }
+
+        cx.update(|cx| {
+            let model = match model_id {
+                Some(model_id) => provider
+                    .provided_models(cx)
+                    .into_iter()
+                    .find(|model| model.id() == model_id),
+                None if use_default_model => LanguageModelRegistry::read_global(cx)
+                    .default_model()
+                    .map(|model| model.model),
+                None => provider.default_model(cx),
+            };
+            model.with_context(|| {
+                format!(
+                    "{} does not provide the model configured for edit predictions",
+                    provider.name().0
+                )
+            })
+        })?
+    })
+}
+
+/// Requests edit predictions from one of the configured language model
+/// providers, which may be running locally.
+pub struct LanguageModelInlineCompletionProvider {
+    zeta: Entity<Zeta>,
+    requests: CompletionRequests,
+}
+
+impl LanguageModelInlineCompletionProvider {
+    pub fn new(zeta: Entity<Zeta>) -> Self {
+        Self {
+            zeta,
+            requests: CompletionRequests::new(),
+        }
+    }
+}
+
+impl inline_completion::EditPredictionProvider for LanguageModelInlineCompletionProvider {
+    fn name() -> &'static str {
+        "language-model"
+    }
+
+    fn display_name() -> &'static str {
+        "Language Model Edit Predictions"
+    }
+
+    fn show_completions_in_menu() -> bool {
+        true
+    }
+
+    fn show_tab_accept_marker() -> bool {
+        true
+    }
+
+    fn is_enabled(
+        &self,
+        _buffer: &Entity<Buffer>,
+        _cursor_position: language::Anchor,
+        _cx: &App,
+    ) -> bool {
+        true
+    }
+
+    fn is_refreshing(&self) -> bool {
+        self.requests.is_refreshing()
+    }
+
+    fn refresh(
+        &mut self,
+        project: Option<Entity<Project>>,
+        buffer: Entity<Buffer>,
+        position: language::Anchor,
+        debounce: bool,
+        cx: &mut Context<Self>,
+    ) {
+        let prompt_format = all_language_settings(None, cx)
+            .edit_predictions
+            .language_model
+            .prompt_format;
+        self.requests.refresh(
+            buffer.clone(),
+            debounce,
+            cx,
+            |this| &mut this.requests,
+            move |this, cx| {
+                let zeta = this.zeta.clone();
+                let model = configured_model(cx);
+                cx.spawn(async move |_, cx| {
+                    let model = model.await?;
+                    zeta.update(cx, |zeta, cx| {
+                        zeta.request_completion_with_model(
+                            project.as_ref(),
+                            &buffer,
+                            position,
+                            model,
+                            prompt_format,
+                            cx,
+                        )
+                    })?
+                    .await
+                })
+            },
+            |_, _, _| {},
+        );
+    }
+
+    fn cycle(
+        &mut self,
+        _buffer: Entity<Buffer>,
+        _cursor_position: language::Anchor,
+        _direction: inline_completion::Direction,
+        _cx: &mut Context<Self>,
+    ) {
+    }
+
+    fn accept(&mut self, _cx: &mut Context<Self>) {
+        self.requests.accept();
+    }
+
+    fn discard(&mut self, _cx: &mut Context<Self>) {
+        self.requests.discard();
+    }
+
+    fn suggest(
+        &mut self,
+        buffer: &Entity<Buffer>,
+        cursor_position: language::Anchor,
+        cx: &mut Context<Self>,
+    ) -> Option<inline_completion::InlineCompletion> {
+        self.requests.suggest(buffer, cursor_position, cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use client::{Client, UserStore, test::FakeServer};
+    use clock::FakeSystemClock;
+    use gpui::TestAppContext;
+    use http_client::FakeHttpClient;
+    use indoc::indoc;
+    use inline_completion::EditPredictionProvider as _;
+    use language::Point;
+    use language_model::RefreshLlmTokenListener;
+    use settings::SettingsStore;
+
+    #[gpui::test]
+    async fn test_language_model_edit_prediction(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+            client::init_settings(cx);
+            language::init(cx);
+            LanguageModelRegistry::test(cx);
+        });
+
+        let http_client = FakeHttpClient::with_404_response();
+        let client = cx.update(|cx| Client::new(Arc::new(FakeSystemClock::new()), http_client, cx));
+        cx.update(|cx| {
+            RefreshLlmTokenListener::register(client.clone(), cx);
+        });
+        let _server = FakeServer::for_client(42, &client, cx).await;
+        let user_store = cx.new(|cx| UserStore::new(client.clone(), cx));
+        let zeta = cx.new(|cx| Zeta::new(None, client, user_store, cx));
+        let provider = cx.new(|_| LanguageModelInlineCompletionProvider::new(zeta));
+        let model = cx.update(|cx| {
+            LanguageModelRegistry::read_global(cx)
+                .default_model()
+                .unwrap()
+                .model
+        });
+
+        let buffer = cx.new(|cx| Buffer::local("lorem\n", cx));
+        let cursor = buffer.read_with(cx, |buffer, _| buffer.anchor_before(Point::new(1, 0)));
+        provider.update(cx, |provider, cx| {
+            provider.refresh(None, buffer.clone(), cursor, true, cx);
+            assert!(provider.is_refreshing());
+        });
+
+        // The request waits for the user to stop typing.
+        cx.run_until_parked();
+        assert_eq!(model.as_fake().completion_count(), 0);
+        cx.executor().advance_clock(
+            CompletionRequests::DEBOUNCE_TIMEOUT
+                + crate::ZetaInlineCompletionProvider::THROTTLE_TIMEOUT,
+        );
+        cx.run_until_parked();
+
+        let requests = model.as_fake().pending_completions();
+        assert_eq!(requests.len(), 1);
+        let prompt = requests[0].messages[0].string_contents();
+        assert!(prompt.starts_with(&format!("### Instruction:\n{ZETA_INSTRUCTIONS}")));
+        assert!(prompt.contains(CURSOR_MARKER));
+
+        model.as_fake().stream_last_completion_response(
+            indoc! {"
+                ```untitled
+                <|start_of_file|>
+                <|editable_region_start|>
+                lorem
+                ipsum
+                <|editable_region_end|>
+                ```"}
+            .to_string(),
+        );
+        model.as_fake().end_last_completion_stream();
+        cx.run_until_parked();
+        assert!(provider.read_with(cx, |provider, _| !provider.is_refreshing()));
+
+        // Typing the start of the prediction keeps it, so no new request is made.
+        let prediction = provider
+            .update(cx, |provider, cx| provider.suggest(&buffer, cursor, cx))
+            .expect("a prediction should have been made");
+        let (range, text) = prediction.edits[0].clone();
+        buffer.update(cx, |buffer, cx| {
+            buffer.edit([(range, &text[..1])], None, cx)
+        });
+        provider.update(cx, |provider, cx| {
+            provider.refresh(None, buffer.clone(), cursor, false, cx);
+            assert!(!provider.is_refreshing());
+        });
+
+        let prediction = provider
+            .update(cx, |provider, cx| provider.suggest(&buffer, cursor, cx))
+            .expect("the prediction should still apply");
+        buffer.update(cx, |buffer, cx| buffer.edit(prediction.edits, None, cx));
+        assert_eq!(
+            buffer.read_with(cx, |buffer, _| buffer.text()),
+            "lorem\nipsum"
+        );
+    }
+
+    #[test]
+    fn test_fim_prompt_removes_region_markers() {
+        let input_excerpt = indoc! {"
+            ```src/main.rs
+            <|start_of_file|>
+            fn main() {
+            <|editable_region_start|>
+                let x = <|user_cursor_is_here|>;
+            <|editable_region_end|>
+            }
+            ```"};
+
+        assert_eq!(
+            fim_prompt(input_excerpt),
+            format!(
+                "{FIM_INSTRUCTIONS}\n\n{}",
+                indoc! {"
+                    ```src/main.rs
+                    fn main() {
+                        let x = <|user_cursor_is_here|>;
+                    }
+                    ```"}
+            )
+        );
+    }
+
+    #[test]
+    fn test_fim_output_excerpt() {
+        let speculated_output = indoc! {"
+            <|editable_region_start|>
+                let x = <|user_cursor_is_here|>;
+            <|editable_region_end|>"};
+
+        let expected = indoc! {"
+            <|editable_region_start|>
+                let x = 42;
+            <|editable_region_end|>"};
+        assert_eq!(fim_output_excerpt(speculated_output, "42"), expected);
+        assert_eq!(
+            fim_output_excerpt(speculated_output, "```rust\n42\n```\n"),
+            expected
+        );
+    }
+}
@@ -1,6 +1,7 @@
 mod completion_diff_element;
 mod init;
 mod input_excerpt;
+mod language_model_provider;
 mod license_detection;
 mod onboarding_modal;
 mod onboarding_telemetry;
@@ -10,6 +11,7 @@ pub(crate) use completion_diff_element::*;
 use db::kvp::KEY_VALUE_STORE;
 pub use init::*;
 use inline_completion::{DataCollectionState, EditPredictionUsage};
+pub use language_model_provider::*;
 use license_detection::LICENSE_FILES_TO_CHECK;
 pub use license_detection::is_license_eligible_for_data_collection;
 pub use rate_completion_modal::*;
@@ -1267,6 +1269,155 @@ struct PendingCompletion {
     _task: Task<()>,
 }
 
+/// The requests of an edit prediction provider and the completion they
+/// produced. Requests are throttled, and at most two of them are pending.
+struct CompletionRequests {
+    pending_completions: ArrayVec<PendingCompletion, 2>,
+    next_pending_completion_id: usize,
+    current_completion: Option<CurrentInlineCompletion>,
+    last_request_timestamp: Instant,
+}
+
+impl CompletionRequests {
+    /// How long to wait for the user to stop typing before a debounced request.
+    const DEBOUNCE_TIMEOUT: Duration = Duration::from_millis(75);
+
+    fn new() -> Self {
+        Self {
+            pending_completions: ArrayVec::new(),
+            next_pending_completion_id: 0,
+            current_completion: None,
+            last_request_timestamp: Instant::now(),
+        }
+    }
+
+    fn is_refreshing(&self) -> bool {
+        !self.pending_completions.is_empty()
+    }
+
+    /// Requests a new completion with `request_completion`, unless the current
+    /// one still applies to the buffer. `requests` returns these requests from
+    /// the provider, and `completion_shown` is called when the new completion
+    /// replaces the current one.
+    fn refresh<P: 'static>(
+        &mut self,
+        buffer: Entity<Buffer>,
+        debounce: bool,
+        cx: &mut Context<P>,
+        requests: fn(&mut P) -> &mut Self,
+        request_completion: impl 'static
+        + FnOnce(
+            &mut P,
+            &mut Context<P>,
+        ) -> Task<Result<Option<InlineCompletion>>>,
+        completion_shown: impl 'static + FnOnce(&mut P, &InlineCompletion, &mut Context<P>),
+    ) {
+        if let Some(current_completion) = self.current_completion.as_ref() {
+            let snapshot = buffer.read(cx).snapshot();
+            if current_completion
+                .completion
+                .interpolate(&snapshot)
+                .is_some()
+            {
+                return;
+            }
+        }
+
+        let pending_completion_id = self.next_pending_completion_id;
+        self.next_pending_completion_id += 1;
+        let last_request_timestamp = self.last_request_timestamp;
+
+        let task =
+            cx.spawn(async move |this, cx| {
+                if debounce {
+                    cx.background_executor().timer(Self::DEBOUNCE_TIMEOUT).await;
+                }
+                if let Some(timeout) = (last_request_timestamp
+                    + ZetaInlineCompletionProvider::THROTTLE_TIMEOUT)
+                    .checked_duration_since(Instant::now())
+                {
+                    cx.background_executor().timer(timeout).await;
+                }
+
+                let completion_request = this.update(cx, |this, cx| {
+                    requests(this).last_request_timestamp = Instant::now();
+                    request_completion(this, cx)
+                });
+                let completion = match completion_request {
+                    Ok(completion_request) => completion_request.await,
+                    Err(error) => Err(error),
+                };
+                let new_completion = completion
+                    .context("edit prediction failed")
+                    .log_err()
+                    .flatten()
+                    .map(|completion| CurrentInlineCompletion {
+                        buffer_id: buffer.entity_id(),
+                        completion,
+                    });
+
+                this.update(cx, |this, cx| {
+                    let this_requests = requests(this);
+                    if this_requests.pending_completions[0].id == pending_completion_id {
+                        this_requests.pending_completions.remove(0);
+                    } else {
+                        this_requests.pending_completions.clear();
+                    }
+
+                    if let Some(new_completion) = new_completion {
+                        let snapshot = buffer.read(cx).snapshot();
+                        let should_replace = this_requests.current_completion.as_ref().is_none_or(
+                            |old_completion| {
+                                new_completion.should_replace_completion(old_completion, &snapshot)
+                            },
+                        );
+                        if should_replace {
+                            let completion = new_completion.completion.clone();
+                            this_requests.current_completion = Some(new_completion);
+                            completion_shown(this, &completion, cx);
+                        }
+                    }
+
+                    cx.notify();
+                })
+                .ok();
+            });
+
+        // We always maintain at most two pending completions. When we already
+        // have two, we replace the newest one.
+        if self.pending_completions.len() <= 1 {
+            self.pending_completions.push(PendingCompletion {
+                id: pending_completion_id,
+                _task: task,
+            });
+        } else if self.pending_completions.len() == 2 {
+            self.pending_completions.pop();
+            self.pending_completions.push(PendingCompletion {
+                id: pending_completion_id,
+                _task: task,
+            });
+        }
+    }
+
+    fn accept(&mut self) {
+        self.pending_completions.clear();
+    }
+
+    fn discard(&mut self) {
+        self.pending_completions.clear();
+        self.current_completion.take();
+    }
+
+    fn suggest(
+        &mut self,
+        buffer: &Entity<Buffer>,
+        cursor_position: language::Anchor,
+        cx: &App,
+    ) -> Option<inline_completion::InlineCompletion> {
+        suggest_current_completion(&mut self.current_completion, buffer, cursor_position, cx)
+    }
+}
+
 #[derive(Debug, Clone, Copy)]
 pub enum DataCollectionChoice {
     NotAnswered,
@@ -1382,12 +1533,9 @@ impl ProviderDataCollection {
 
 pub struct ZetaInlineCompletionProvider {
     zeta: Entity<Zeta>,
-    pending_completions: ArrayVec<PendingCompletion, 2>,
-    next_pending_completion_id: usize,
-    current_completion: Option<CurrentInlineCompletion>,
+    requests: CompletionRequests,
     /// None if this is entirely disabled for this provider
     provider_data_collection: ProviderDataCollection,
-    last_request_timestamp: Instant,
 }
 
 impl ZetaInlineCompletionProvider {
@@ -1396,11 +1544,8 @@ impl ZetaInlineCompletionProvider {
     pub fn new(zeta: Entity<Zeta>, provider_data_collection: ProviderDataCollection) -> Self {
         Self {
             zeta,
-            pending_completions: ArrayVec::new(),
-            next_pending_completion_id: 0,
-            current_completion: None,
+            requests: CompletionRequests::new(),
             provider_data_collection,
-            last_request_timestamp: Instant::now(),
         }
     }
 }
@@ -1458,7 +1603,7 @@ impl inline_completion::EditPredictionProvider for ZetaInlineCompletionProvider
     }
 
     fn is_refreshing(&self) -> bool {
-        !self.pending_completions.is_empty()
+        self.requests.is_refreshing()
     }
 
     fn refresh(
@@ -1477,31 +1622,14 @@ impl inline_completion::EditPredictionProvider for ZetaInlineCompletionProvider
             return;
         }
 
-        if let Some(current_completion) = self.current_completion.as_ref() {
-            let snapshot = buffer.read(cx).snapshot();
-            if current_completion
-                .completion
-                .interpolate(&snapshot)
-                .is_some()
-            {
-                return;
-            }
-        }
-
-        let pending_completion_id = self.next_pending_completion_id;
-        self.next_pending_completion_id += 1;
         let can_collect_data = self.provider_data_collection.can_collect_data(cx);
-        let last_request_timestamp = self.last_request_timestamp;
-
-        let task = cx.spawn(async move |this, cx| {
-            if let Some(timeout) = (last_request_timestamp + Self::THROTTLE_TIMEOUT)
-                .checked_duration_since(Instant::now())
-            {
-                cx.background_executor().timer(timeout).await;
-            }
-
-            let completion_request = this.update(cx, |this, cx| {
-                this.last_request_timestamp = Instant::now();
+        // Requests to Zed's model are throttled, but not debounced.
+        self.requests.refresh(
+            buffer.clone(),
+            false,
+            cx,
+            |this| &mut this.requests,
+            move |this, cx| {
                 this.zeta.update(cx, |zeta, cx| {
                     zeta.request_completion(
                         project.as_ref(),
@@ -1511,79 +1639,13 @@ impl inline_completion::EditPredictionProvider for ZetaInlineCompletionProvider
                         cx,
                     )
                 })
-            });
-
-            let completion = match completion_request {
-                Ok(completion_request) => {
-                    let completion_request = completion_request.await;
-                    completion_request.map(|c| {
-                        c.map(|completion| CurrentInlineCompletion {
-                            buffer_id: buffer.entity_id(),
-                            completion,
-                        })
-                    })
-                }
-                Err(error) => Err(error),
-            };
-            let Some(new_completion) = completion
-                .context("edit prediction failed")
-                .log_err()
-                .flatten()
-            else {
-                this.update(cx, |this, cx| {
-                    if this.pending_completions[0].id == pending_completion_id {
-                        this.pending_completions.remove(0);
-                    } else {
-                        this.pending_completions.clear();
-                    }
-
-                    cx.notify();
-                })
-                .ok();
-                return;
-            };
-
-            this.update(cx, |this, cx| {
-                if this.pending_completions[0].id == pending_completion_id {
-                    this.pending_completions.remove(0);
-                } else {
-                    this.pending_completions.clear();
-                }
-
-                if let Some(old_completion) = this.current_completion.as_ref() {
-                    let snapshot = buffer.read(cx).snapshot();
-                    if new_completion.should_replace_completion(&old_completion, &snapshot) {
-                        this.zeta.update(cx, |zeta, cx| {
-                            zeta.completion_shown(&new_completion.completion, cx);
-                        });
-                        this.current_completion = Some(new_completion);
-                    }
-                } else {
-                    this.zeta.update(cx, |zeta, cx| {
-                        zeta.completion_shown(&new_completion.completion, cx);
-                    });
-                    this.current_completion = Some(new_completion);
-                }
-
-                cx.notify();
-            })
-            .ok();
-        });
-
-        // We always maintain at most two pending completions. When we already
-        // have two, we replace the newest one.
-        if self.pending_completions.len() <= 1 {
-            self.pending_completions.push(PendingCompletion {
-                id: pending_completion_id,
-                _task: task,
-            });
-        } else if self.pending_completions.len() == 2 {
-            self.pending_completions.pop();
-            self.pending_completions.push(PendingCompletion {
-                id: pending_completion_id,
-                _task: task,
-            });
-        }
+            },
+            |this, completion, cx| {
+                this.zeta.update(cx, |zeta, cx| {
+                    zeta.completion_shown(completion, cx);
+                });
+            },
+        );
     }
 
     fn cycle(
@@ -1597,12 +1659,11 @@ impl inline_completion::EditPredictionProvider for ZetaInlineCompletionProvider
     }
 
     fn accept(&mut self, _cx: &mut Context<Self>) {
-        self.pending_completions.clear();
+        self.requests.accept();
     }
 
     fn discard(&mut self, _cx: &mut Context<Self>) {
-        self.pending_completions.clear();
-        self.current_completion.take();
+        self.requests.discard();
     }
 
     fn suggest(
@@ -1611,60 +1672,71 @@ impl inline_completion::EditPredictionProvider for ZetaInlineCompletionProvider
         cursor_position: language::Anchor,
         cx: &mut Context<Self>,
     ) -> Option<inline_completion::InlineCompletion> {
-        let CurrentInlineCompletion {
-            buffer_id,
-            completion,
-            ..
-        } = self.current_completion.as_mut()?;
-
-        // Invalidate previous completion if it was generated for a different buffer.
-        if *buffer_id != buffer.entity_id() {
-            self.current_completion.take();
-            return None;
-        }
+        self.requests.suggest(buffer, cursor_position, cx)
+    }
+}
 
-        let buffer = buffer.read(cx);
-        let Some(edits) = completion.interpolate(&buffer.snapshot()) else {
-            self.current_completion.take();
-            return None;
-        };
+/// Returns the edits of the current completion that are closest to the cursor,
+/// discarding the completion if it no longer applies to the buffer.
+fn suggest_current_completion(
+    current_completion: &mut Option<CurrentInlineCompletion>,
+    buffer: &Entity<Buffer>,
+    cursor_position: language::Anchor,
+    cx: &App,
+) -> Option<inline_completion::InlineCompletion> {
+    let CurrentInlineCompletion {
+        buffer_id,
+        completion,
+        ..
+    } = current_completion.as_mut()?;
+
+    // Invalidate previous completion if it was generated for a different buffer.
+    if *buffer_id != buffer.entity_id() {
+        current_completion.take();
+        return None;
+    }
 
-        let cursor_row = cursor_position.to_point(buffer).row;
-        let (closest_edit_ix, (closest_edit_range, _)) =
-            edits.iter().enumerate().min_by_key(|(_, (range, _))| {
-                let distance_from_start = cursor_row.abs_diff(range.start.to_point(buffer).row);
-                let distance_from_end = cursor_row.abs_diff(range.end.to_point(buffer).row);
-                cmp::min(distance_from_start, distance_from_end)
-            })?;
-
-        let mut edit_start_ix = closest_edit_ix;
-        for (range, _) in edits[..edit_start_ix].iter().rev() {
-            let distance_from_closest_edit =
-                closest_edit_range.start.to_point(buffer).row - range.end.to_point(buffer).row;
-            if distance_from_closest_edit <= 1 {
-                edit_start_ix -= 1;
-            } else {
-                break;
-            }
+    let buffer = buffer.read(cx);
+    let Some(edits) = completion.interpolate(&buffer.snapshot()) else {
+        current_completion.take();
+        return None;
+    };
+
+    let cursor_row = cursor_position.to_point(buffer).row;
+    let (closest_edit_ix, (closest_edit_range, _)) =
+        edits.iter().enumerate().min_by_key(|(_, (range, _))| {
+            let distance_from_start = cursor_row.abs_diff(range.start.to_point(buffer).row);
+            let distance_from_end = cursor_row.abs_diff(range.end.to_point(buffer).row);
+            cmp::min(distance_from_start, distance_from_end)
+        })?;
+
+    let mut edit_start_ix = closest_edit_ix;
+    for (range, _) in edits[..edit_start_ix].iter().rev() {
+        let distance_from_closest_edit =
+            closest_edit_range.start.to_point(buffer).row - range.end.to_point(buffer).row;
+        if distance_from_closest_edit <= 1 {
+            edit_start_ix -= 1;
+        } else {
+            break;
         }
+    }
 
-        let mut edit_end_ix = closest_edit_ix + 1;
-        for (range, _) in &edits[edit_end_ix..] {
-            let distance_from_closest_edit =
-                range.start.to_point(buffer).row - closest_edit_range.end.to_point(buffer).row;
-            if distance_from_closest_edit <= 1 {
-                edit_end_ix += 1;
-            } else {
-                break;
-            }
+    let mut edit_end_ix = closest_edit_ix + 1;
+    for (range, _) in &edits[edit_end_ix..] {
+        let distance_from_closest_edit =
+            range.start.to_point(buffer).row - closest_edit_range.end.to_point(buffer).row;
+        if distance_from_closest_edit <= 1 {
+            edit_end_ix += 1;
+        } else {
+            break;
         }
-
-        Some(inline_completion::InlineCompletion {
-            id: Some(completion.id.to_string().into()),
-            edits: edits[edit_start_ix..edit_end_ix].to_vec(),
-            edit_preview: Some(completion.edit_preview.clone()),
-        })
     }
+
+    Some(inline_completion::InlineCompletion {
+        id: Some(completion.id.to_string().into()),
+        edits: edits[edit_start_ix..edit_end_ix].to_vec(),
+        edit_preview: Some(completion.edit_preview.clone()),
+    })
 }
 
 fn tokens_for_bytes(bytes: usize) -> usize {
@@ -21,6 +21,8 @@ const ANSI_MAGENTA: &str = "\x1b[35m";
 
 /// Whether stdout output is enabled.
 static mut ENABLED_SINKS_STDOUT: bool = false;
+/// Whether stderr output is enabled.
+static mut ENABLED_SINKS_STDERR: bool = false;
 
 /// Is Some(file) if file output is enabled.
 static ENABLED_SINKS_FILE: Mutex<Option<std::fs::File>> = Mutex::new(None);
@@ -45,6 +47,13 @@ pub fn init_output_stdout() {
     }
 }
 
+/// Writes logs to stderr, for processes whose stdout is their output.
+pub fn init_output_stderr() {
+    unsafe {
+        ENABLED_SINKS_STDERR = true;
+    }
+}
+
 pub fn init_output_file(
     path: &'static PathBuf,
     path_rotate: Option<&'static PathBuf>,
@@ -98,23 +107,29 @@ static LEVEL_ANSI_COLORS: [&str; 6] = [
     ANSI_MAGENTA, // Trace: Magenta
 ];
 
+fn write_ansi(writer: &mut impl Write, record: &Record) {
+    _ = writeln!(
+        writer,
+        "{} {ANSI_BOLD}{}{}{ANSI_RESET} {} {}",
+        chrono::Local::now().format("%Y-%m-%dT%H:%M:%S%:z"),
+        LEVEL_ANSI_COLORS[record.level as usize],
+        LEVEL_OUTPUT_STRINGS[record.level as usize],
+        SourceFmt {
+            scope: record.scope,
+            module_path: record.module_path,
+            ansi: true,
+        },
+        record.message
+    );
+}
+
 // PERF: batching
 pub fn submit(record: Record) {
     if unsafe { ENABLED_SINKS_STDOUT } {
-        let mut stdout = std::io::stdout().lock();
-        _ = writeln!(
-            &mut stdout,
-            "{} {ANSI_BOLD}{}{}{ANSI_RESET} {} {}",
-            chrono::Local::now().format("%Y-%m-%dT%H:%M:%S%:z"),
-            LEVEL_ANSI_COLORS[record.level as usize],
-            LEVEL_OUTPUT_STRINGS[record.level as usize],
-            SourceFmt {
-                scope: record.scope,
-                module_path: record.module_path,
-                ansi: true,
-            },
-            record.message
-        );
+        write_ansi(&mut std::io::stdout().lock(), &record);
+    }
+    if unsafe { ENABLED_SINKS_STDERR } {
+        write_ansi(&mut std::io::stderr().lock(), &record);
     }
     let mut file = ENABLED_SINKS_FILE.lock().unwrap_or_else(|handle| {
         ENABLED_SINKS_FILE.clear_poison();
@@ -167,6 +182,9 @@ pub fn flush() {
     if unsafe { ENABLED_SINKS_STDOUT } {
         _ = std::io::stdout().lock().flush();
     }
+    if unsafe { ENABLED_SINKS_STDERR } {
+        _ = std::io::stderr().lock().flush();
+    }
     let mut file = ENABLED_SINKS_FILE.lock().unwrap_or_else(|handle| {
         ENABLED_SINKS_FILE.clear_poison();
         handle.into_inner()